@@ -6,13 +6,24 @@ import os
 from tempfile import TemporaryDirectory
 
 from pathlib import Path
-from typing import Any, Dict, List, Optional, Union, Callable, TYPE_CHECKING
+from typing import (
+    Any,
+    Dict,
+    List,
+    Optional,
+    Sequence,
+    Tuple,
+    Union,
+    Callable,
+    TYPE_CHECKING,
+)
 from nrel.routee.compass.routee_compass_py import (
     CompassAppWrapper,
 )
 from nrel.routee.compass.io.generate_dataset import generate_compass_dataset
 
 if TYPE_CHECKING:
+    import numpy as np
     from shapely.geometry import Polygon, MultiPolygon
 
 import toml
@@ -76,11 +87,15 @@ class CompassApp:
     @classmethod
     def from_dict(cls, config: Dict, working_dir: Optional[Path] = None) -> CompassApp:
         """
-        Build a CompassApp from a configuration object
+        Build a CompassApp from a configuration object, without writing it to a
+        file. Relative file paths in the configuration are resolved against the
+        working directory, so a notebook can sweep over configurations in memory.
+        Values of None are treated as unset.
 
         Args:
             config (Dict): Configuration dictionary
-            working_dir (Path): optional path to working directory
+            working_dir (Path): optional path to working directory, or to a
+                configuration file in that directory
 
         Returns:
             CompassApp: a CompassApp object
@@ -88,11 +103,10 @@ class CompassApp:
         Example:
             >>> from nrel.routee.compass import CompassApp
             >>> conf = { parallelism: 2 }
-            >>> app = CompassApp.from_config(conf)
+            >>> app = CompassApp.from_dict(conf, Path("path/to/data"))
         """
-        path_str = str(working_dir.absolute()) if working_dir is not None else ""
-        toml_string = toml.dumps(config)
-        app = cls.get_constructor()._from_config_toml_string(toml_string, path_str)
+        path_str = str(Path(working_dir).absolute()) if working_dir is not None else ""
+        app = cls.get_constructor()._from_config_dict(config, path_str)
         return cls(app, config)
 
     @classmethod
@@ -279,13 +293,26 @@ class CompassApp:
         return cache_dir, None
 
     def run(
-        self, query: Union[Query, List[Query]], config: Optional[Dict] = None
+        self,
+        query: Union[Query, List[Query]],
+        config: Optional[Dict] = None,
+        progress_callback: Optional[Callable[[Dict[str, Any]], None]] = None,
+        progress_interval: int = 100,
     ) -> Union[Result, Results]:
         """
-        Run a query (or multiple queries) against the CompassApp
+        Run a query (or multiple queries) against the CompassApp.
+        The GIL is released while the queries run, so other python threads,
+        such as a notebook kernel, are not blocked by a long batch.
+        A keyboard interrupt cancels the running queries and raises
+        KeyboardInterrupt once they have stopped.
 
         Args:
             query (Union[Dict[str, Any], List[Dict[str, Any]]]): A query or list of queries to run
+            config (Optional[Dict]): an optional run configuration
+            progress_callback (Optional[Callable]): called with a dict of the "stage",
+                "completed", "total" and "errors" of the run every `progress_interval`
+                completed queries of each stage, where the stage is "input plugins" or "search"
+            progress_interval (int): the number of completed queries between callbacks
 
         Returns:
             List[Dict[str, Any]]: A list of results (or a single result if a single query was passed)
@@ -319,13 +346,79 @@ class CompassApp:
         queries_str = list(map(json.dumps, queries))
         config_str = json.dumps(config) if config is not None else None
 
-        results_json: List[str] = self._app._run_queries(queries_str, config_str)
+        results: Results = self._app._run_queries_dicts(
+            queries_str, config_str, progress_callback, progress_interval
+        )
 
-        results = list(map(json.loads, results_json))
         if single_query and len(results) == 1:
             return results[0]
         return results
 
+    def run_od_matrix(
+        self,
+        origins: "np.ndarray",
+        destinations: "np.ndarray",
+        query: Optional[Query] = None,
+        features: Sequence[str] = (
+            "distance",
+            "time",
+            "energy_liquid",
+            "energy_electric",
+        ),
+        config: Optional[Dict] = None,
+    ) -> Dict[str, "np.ndarray"]:
+        """
+        Run a query between every origin and every destination, building the
+        queries in the engine rather than as python dictionaries
+
+        Args:
+            origins (np.ndarray): an (n, 2) array of origin x and y coordinates
+            destinations (np.ndarray): an (m, 2) array of destination x and y coordinates
+            query (Optional[Dict[str, Any]]): fields shared by every query, such as a model_name
+            features (Sequence[str]): the traversal summary features to return
+            config (Optional[Dict]): an optional run configuration
+
+        Returns:
+            Dict[str, np.ndarray]: an (n, m) array for each feature, which is NaN
+                where a query failed or the feature is not in the state model
+
+        Example:
+            >>> import numpy as np
+            >>> origins = np.array([[-105.1710052, 39.7402804]])
+            >>> destinations = np.array([[-104.9009913, 39.6757025], [-104.99, 39.74]])
+            >>> matrix = app.run_od_matrix(origins, destinations)
+            >>> matrix["time"].shape
+            (1, 2)
+        """
+        try:
+            import numpy as np
+        except ImportError:
+            raise ImportError("requires numpy to be installed. Try 'pip install numpy'")
+        o = np.asarray(origins, dtype=np.float64)
+        d = np.asarray(destinations, dtype=np.float64)
+        for name, arr in [("origins", o), ("destinations", d)]:
+            if arr.ndim != 2 or arr.shape[1] != 2:
+                raise ValueError(
+                    f"{name} must be an array of shape (n, 2), not {arr.shape}"
+                )
+
+        query_str = json.dumps(query) if query is not None else None
+        config_str = json.dumps(config) if config is not None else None
+        matrices = self._app._run_od_matrix(
+            o[:, 0].tolist(),
+            o[:, 1].tolist(),
+            d[:, 0].tolist(),
+            d[:, 1].tolist(),
+            list(features),
+            query_str,
+            config_str,
+        )
+        shape = (o.shape[0], d.shape[0])
+        return {
+            feature: np.array(values, dtype=np.float64).reshape(shape)
+            for feature, values in zip(features, matrices)
+        }
+
     def graph_edge_origin(self, edge_id: int) -> int:
         """
         get the origin vertex id for some edge
@@ -388,3 +481,132 @@ class CompassApp:
             List[int]: the edge ids of edges arriving at this vertex
         """
         return self._app.graph_get_in_edge_ids(vertex_id)
+
+    def graph_vertex_coordinate(self, vertex_id: int) -> Tuple[float, float]:
+        """
+        get the x and y coordinate of some vertex
+
+        Args:
+            vertex_id (int): the id of the vertex
+
+        Returns:
+            Tuple[float, float]: the x and y coordinate of the vertex
+        """
+        return self._app.graph_vertex_coordinate(vertex_id)
+
+    def graph_edge_geometry(
+        self, edge_id: int, format: str = "wkt"
+    ) -> Union[str, bytes]:
+        """
+        get the geometry of some edge, which requires an output plugin that
+        loads edge geometries, such as the traversal plugin
+
+        Args:
+            edge_id (int): the id of the edge
+            format (str): "wkt" for well-known text or "wkb" for well-known
+                binary, which can be read with shapely.wkb.loads
+
+        Returns:
+            Union[str, bytes]: the LINESTRING of the edge
+        """
+        if format == "wkt":
+            return self._app.graph_edge_geometry_wkt(edge_id)
+        elif format == "wkb":
+            return self._app.graph_edge_geometry_wkb(edge_id)
+        raise ValueError(f"format must be 'wkt' or 'wkb', not {format}")
+
+    def graph_edge_speed(
+        self, edge_id: int, speed_unit: Optional[str] = None
+    ) -> float:
+        """
+        get the speed of some edge from the traversal model
+
+        Args:
+            edge_id (int): the id of the edge
+            speed_unit (Optional[str]): speed unit, by default the unit of the speed table
+
+        Returns:
+            float: the speed of the edge
+        """
+        return self._app.graph_edge_speed(edge_id, speed_unit)
+
+    def graph_edge_grade(
+        self, edge_id: int, grade_unit: Optional[str] = None
+    ) -> float:
+        """
+        get the grade of some edge from the traversal model
+
+        Args:
+            edge_id (int): the id of the edge
+            grade_unit (Optional[str]): grade unit, by default the unit of the grade table
+
+        Returns:
+            float: the grade of the edge
+        """
+        return self._app.graph_edge_grade(edge_id, grade_unit)
+
+    def graph_edge_road_class(self, edge_id: int) -> Optional[int]:
+        """
+        get the road class of some edge from the road class frontier model
+
+        Args:
+            edge_id (int): the id of the edge
+
+        Returns:
+            Optional[int]: the road class of the edge, or None if no frontier
+                model restricts travel by road class
+        """
+        return self._app.graph_edge_road_class(edge_id)
+
+    def graph_edges(self) -> Dict[str, List[Any]]:
+        """
+        get every edge of the graph as columns, which can be passed to
+        pandas.DataFrame
+
+        Returns:
+            Dict[str, List[Any]]: the edge_id, src_vertex_id, dst_vertex_id and
+                distance in meters of each edge
+        """
+        edge_ids, src, dst, distance = self._app.graph_edges()
+        return {
+            "edge_id": edge_ids,
+            "src_vertex_id": src,
+            "dst_vertex_id": dst,
+            "distance": distance,
+        }
+
+    def graph_vertices(self) -> Dict[str, List[Any]]:
+        """
+        get every vertex of the graph as columns, which can be passed to
+        pandas.DataFrame
+
+        Returns:
+            Dict[str, List[Any]]: the vertex_id, x and y of each vertex
+        """
+        vertex_ids, x, y = self._app.graph_vertices()
+        return {"vertex_id": vertex_ids, "x": x, "y": y}
+
+    def update_edge_speeds(
+        self,
+        edge_ids: List[int],
+        speeds: List[float],
+        speed_unit: str = "kilometers_per_hour",
+    ) -> None:
+        """
+        replace the speeds of some edges in the running app, such as from a
+        live traffic feed, without reloading the graph. queries run after this
+        call use the new speeds.
+
+        Args:
+            edge_ids (List[int]): the ids of the edges to update
+            speeds (List[float]): the new speed of each edge
+            speed_unit (str): unit of the new speeds, by default kilometers_per_hour
+        """
+        self._app.update_edge_speeds(edge_ids, speeds, speed_unit)
+
+    def finish(self) -> None:
+        """
+        finish the output plugins, such as writing the batch summary file, and
+        write the reproducibility manifest. call this once when the app is no longer needed, after the last run.
+        """
+        self._app._finish()
@@ -1,4 +1,4 @@
-from typing import Optional, Tuple, Union, TYPE_CHECKING
+from typing import Any, Dict, Iterable, List, Optional, Tuple, Union, TYPE_CHECKING
 
 from nrel.routee.compass.utils.geometry import geometry_from_route
 
@@ -6,6 +6,16 @@ from nrel.routee.compass.compass_app import Result, Results
 
 if TYPE_CHECKING:
     from geopandas import GeoDataFrame
+    from pandas import DataFrame
+
+
+def _error_message(error: Union[str, dict]) -> str:
+    """
+    formats the error of a result, which is an object with a code, message and component
+    """
+    if isinstance(error, dict) and "message" in error:
+        return f"{error.get('code')}: {error['message']}"
+    return str(error)
 
 
 def tree_result_to_geopandas(
@@ -19,7 +29,7 @@ def tree_result_to_geopandas(
             "requires geopandas to be installed. Try 'pip install nrel.routee.compass[osm]'"
         )
     if "error" in result:
-        raise ValueError(f"Error in result: {result['error']}")
+        raise ValueError(f"Error in result: {_error_message(result['error'])}")
 
     tree = result.get("tree")
     if tree is None:
@@ -44,7 +54,7 @@ def route_result_to_geopandas(
             "requires geopandas to be installed. Try 'pip install nrel.routee.compass[osm]'"
         )
     if "error" in result:
-        raise ValueError(f"Error in result: {result['error']}")
+        raise ValueError(f"Error in result: {_error_message(result['error'])}")
 
     route = result.get("route")
     if route is None:
@@ -116,3 +126,69 @@ def results_to_geopandas(
         return full_tree_gdf
     else:
         raise ValueError("No route or tree results found in results")
+
+
+def _flatten(
+    value: Dict[str, Any], prefix: str, sep: str, record: Dict[str, Any]
+) -> None:
+    for key, child in value.items():
+        column = f"{prefix}{sep}{key}" if prefix else key
+        if isinstance(child, dict):
+            _flatten(child, column, sep, record)
+        else:
+            record[column] = child
+
+
+def results_to_records(
+    results: Union[Result, Results],
+    exclude: Iterable[str] = ("route", "tree"),
+    sep: str = ".",
+) -> List[Dict[str, Any]]:
+    """
+    flattens results into one record per result, where nested objects become
+    columns joined by `sep`, such as `traversal_summary.distance`. the route and
+    tree of each result are excluded by default since they hold a value per edge.
+
+    Args:
+        results: a result or list of results from CompassApp.run
+        exclude: top-level keys of each result to leave out of the records
+        sep: separator between the keys of nested objects in column names
+
+    Returns:
+        List[Dict[str, Any]]: a flat record for each result
+
+    Example:
+        >>> import pandas as pd
+        >>> results = app.run(queries)
+        >>> df = pd.DataFrame(results_to_records(results))
+    """
+    if isinstance(results, dict):
+        results = [results]
+    excluded = set(exclude)
+    records = []
+    for result in results:
+        record: Dict[str, Any] = {}
+        top = {k: v for k, v in result.items() if k not in excluded}
+        if "error" in top:
+            top["error"] = _error_message(top["error"])
+        _flatten(top, "", sep, record)
+        records.append(record)
+    return records
+
+
+def results_to_dataframe(
+    results: Union[Result, Results],
+    exclude: Iterable[str] = ("route", "tree"),
+    sep: str = ".",
+) -> "DataFrame":
+    """
+    builds a pandas DataFrame with a row for each result, using the records
+    from `results_to_records`
+    """
+    try:
+        import pandas as pd
+    except ImportError:
+        raise ImportError(
+            "requires pandas to be installed. Try 'pip install nrel.routee.compass[osm]'"
+        )
+    return pd.DataFrame(results_to_records(results, exclude=exclude, sep=sep))
@@ -0,0 +1,28 @@
+from unittest import TestCase
+
+from nrel.routee.compass.io.convert_results import results_to_records
+
+
+class TestConvertResults(TestCase):
+    def test_results_to_records(self):
+        results = [
+            {
+                "query_id": "0",
+                "traversal_summary": {"distance": 1.5, "time": 2.0},
+                "route": {"path": []},
+            },
+            {
+                "query_id": "1",
+                "error": {"code": "search", "message": "no path", "component": "x"},
+            },
+        ]
+        records = results_to_records(results)
+        self.assertEqual(
+            records[0],
+            {
+                "query_id": "0",
+                "traversal_summary.distance": 1.5,
+                "traversal_summary.time": 2.0,
+            },
+        )
+        self.assertEqual(records[1], {"query_id": "1", "error": "search: no path"})
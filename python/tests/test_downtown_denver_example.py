@@ -25,3 +25,21 @@ class TestDowntownDenverExample(TestCase):
         result = app.run(query)
 
         self.assertTrue("error" not in result)
+
+    def test_graph_introspection(self):
+        app = CompassApp.from_config_file(
+            package_root()
+            / "resources"
+            / "downtown_denver_example"
+            / "osm_default_energy.toml"
+        )
+
+        vertices = app.graph_vertices()
+        edges = app.graph_edges()
+        self.assertEqual(
+            app.graph_vertex_coordinate(0), (vertices["x"][0], vertices["y"][0])
+        )
+        self.assertEqual(len(edges["edge_id"]), len(edges["distance"]))
+        self.assertTrue(app.graph_edge_geometry(0).startswith("LINESTRING"))
+        self.assertEqual(app.graph_edge_geometry(0, format="wkb")[0], 1)
+        self.assertGreater(app.graph_edge_speed(0, "kilometers_per_hour"), 0)
@@ -190,10 +190,12 @@ mod tests {
         // Construct the Graph instance.
 
         Graph {
-            adj: adj.into_boxed_slice(),
-            rev: rev.into_boxed_slice(),
-            edges: edges.into_boxed_slice(),
-            vertices: vertices.into_boxed_slice(),
+            adj: adj.into(),
+            rev: rev.into(),
+            edges: edges.into(),
+            vertices: vertices.into(),
+            geometries: None,
+            edge_attributes: None,
         }
     }
 
@@ -1,18 +1,22 @@
 use crate::algorithm::search::direction::Direction;
 use crate::algorithm::search::edge_traversal::EdgeTraversal;
+use crate::algorithm::search::search_buffers::SearchBuffers;
 use crate::algorithm::search::search_error::SearchError;
 use crate::algorithm::search::search_instance::SearchInstance;
+use crate::algorithm::search::search_metrics::SearchMetrics;
 use crate::algorithm::search::search_result::SearchResult;
 use crate::algorithm::search::search_tree_branch::SearchTreeBranch;
+use crate::algorithm::search::terminated_search::TerminatedSearch;
 use crate::model::road_network::edge_id::EdgeId;
 use crate::model::road_network::vertex_id::VertexId;
 use crate::model::unit::as_f64::AsF64;
 use crate::model::unit::cost::ReverseCost;
 use crate::model::unit::Cost;
+use crate::util::bucket_queue::BucketQueue;
 use crate::util::priority_queue::InternalPriorityQueue;
 
-use std::collections::HashMap;
-use std::time::Instant;
+use crate::util::instant::Instant;
+use std::collections::{HashMap, HashSet};
 
 /// run an A* Search over the given directed graph model. traverses links
 /// from the source, via the provided direction, to the target. uses the
@@ -26,47 +30,326 @@ pub fn run_a_star(
     weight_factor: Option<Cost>,
     si: &SearchInstance,
 ) -> Result<SearchResult, SearchError> {
-    if target.map_or(false, |t| t == source) {
+    if target == Some(source) {
         return Ok(SearchResult::default());
     }
+    let mut goal = match target {
+        Some(target) => SearchGoal::Target(target),
+        None => SearchGoal::All,
+    };
+    run_search(
+        &[(source, Cost::ZERO)],
+        &mut goal,
+        direction,
+        weight_factor,
+        FrontierQueue::BinaryHeap,
+        si,
+    )
+}
+
+/// run an A* search which begins from several candidate sources at once, such as
+/// the nearest vertices to an origin coordinate. each source is seeded into the
+/// frontier with its cost offset, such as the cost of reaching it from the origin,
+/// so the search finds the route of least total cost from any of them. the source
+/// each route begins at is found by [`crate::algorithm::search::backtrack::multi_origin_route`].
+pub fn run_a_star_multi_origin(
+    sources: &[(VertexId, Cost)],
+    target: Option<VertexId>,
+    direction: &Direction,
+    weight_factor: Option<Cost>,
+    si: &SearchInstance,
+) -> Result<SearchResult, SearchError> {
+    if let Some((_, offset)) = sources.iter().find(|(_, offset)| *offset < Cost::ZERO) {
+        return Err(SearchError::BuildError(format!(
+            "source cost offsets must not be negative, found {}",
+            offset
+        )));
+    }
+    let mut goal = match target {
+        Some(target) => SearchGoal::Target(target),
+        None => SearchGoal::All,
+    };
+    run_search(
+        sources,
+        &mut goal,
+        direction,
+        weight_factor,
+        FrontierQueue::BinaryHeap,
+        si,
+    )
+}
+
+/// run a dijkstra search which orders its frontier with a bucket queue instead of a
+/// binary heap. costs are rounded to multiples of the cost resolution, so routes are
+/// optimal up to that resolution, and must not be negative.
+pub fn run_bucket_dijkstra(
+    source: VertexId,
+    target: Option<VertexId>,
+    direction: &Direction,
+    cost_resolution: Cost,
+    si: &SearchInstance,
+) -> Result<SearchResult, SearchError> {
+    if cost_resolution <= Cost::ZERO {
+        return Err(SearchError::BuildError(format!(
+            "bucket dijkstra cost_resolution must be positive, found {}",
+            cost_resolution
+        )));
+    }
+    if target == Some(source) {
+        return Ok(SearchResult::default());
+    }
+    let mut goal = match target {
+        Some(target) => SearchGoal::Target(target),
+        None => SearchGoal::All,
+    };
+    let queue = FrontierQueue::Buckets {
+        cost_resolution: cost_resolution.as_f64(),
+    };
+    run_search(
+        &[(source, Cost::ZERO)],
+        &mut goal,
+        direction,
+        Some(Cost::ZERO),
+        queue,
+        si,
+    )
+}
+
+/// run a one-to-many search from a source that stops once every one of the
+/// targets has been reached, so that a route to each target can be backtracked
+/// from the single tree. no cost estimate is used, as there is no one target
+/// to estimate toward. targets which cannot be reached are left out of the
+/// tree rather than failing the search.
+pub fn run_a_star_to_targets(
+    source: VertexId,
+    targets: &[VertexId],
+    direction: &Direction,
+    si: &SearchInstance,
+) -> Result<SearchResult, SearchError> {
+    let remaining: HashSet<VertexId> = targets.iter().filter(|t| **t != source).cloned().collect();
+    if remaining.is_empty() {
+        return Ok(SearchResult::default());
+    }
+    run_search(
+        &[(source, Cost::ZERO)],
+        &mut SearchGoal::Targets(remaining),
+        direction,
+        Some(Cost::ZERO),
+        FrontierQueue::BinaryHeap,
+        si,
+    )
+}
+
+/// run a one-to-any search from a source that stops as soon as any one of the
+/// targets is reached, such as the nearest of a set of chargers or depots. returns
+/// the search tree along with the target that was reached, which is the target of
+/// least cost from the source. no cost estimate is used, as there is no one target
+/// to estimate toward.
+pub fn run_a_star_to_any(
+    source: VertexId,
+    targets: &[VertexId],
+    direction: &Direction,
+    si: &SearchInstance,
+) -> Result<(SearchResult, VertexId), SearchError> {
+    if targets.contains(&source) {
+        return Ok((SearchResult::default(), source));
+    }
+    if targets.is_empty() {
+        return Err(SearchError::BuildError(String::from(
+            "search to any of a set of targets requires at least one target",
+        )));
+    }
+    let mut goal = SearchGoal::AnyOf {
+        targets: targets.iter().cloned().collect(),
+        reached: None,
+    };
+    let result = run_search(
+        &[(source, Cost::ZERO)],
+        &mut goal,
+        direction,
+        Some(Cost::ZERO),
+        FrontierQueue::BinaryHeap,
+        si,
+    )?;
+    match goal {
+        SearchGoal::AnyOf {
+            reached: Some(reached),
+            ..
+        } => Ok((result, reached)),
+        _ => Err(SearchError::InternalSearchError(String::from(
+            "search to any of a set of targets finished without reaching one",
+        ))),
+    }
+}
+
+/// the condition under which a search stops expanding its tree
+enum SearchGoal {
+    /// stop on reaching a single target, which the cost estimate guides the search toward
+    Target(VertexId),
+    /// stop once every remaining target has been reached
+    Targets(HashSet<VertexId>),
+    /// stop on reaching any one of the targets, recording which one was reached
+    AnyOf {
+        targets: HashSet<VertexId>,
+        reached: Option<VertexId>,
+    },
+    /// expand every vertex reachable from the source
+    All,
+}
+
+impl SearchGoal {
+    fn target(&self) -> Option<VertexId> {
+        match self {
+            SearchGoal::Target(target) => Some(*target),
+            _ => None,
+        }
+    }
+}
 
-    // context for the search (graph, search functions, frontier priority queue)
-    let mut costs: InternalPriorityQueue<VertexId, ReverseCost> = InternalPriorityQueue::default();
-    let mut traversal_costs: HashMap<VertexId, Cost> = HashMap::new();
-    let mut solution: HashMap<VertexId, SearchTreeBranch> = HashMap::new();
+/// the queue ordering the frontier of a search
+enum FrontierQueue {
+    BinaryHeap,
+    /// a bucket queue over costs rounded to multiples of the cost resolution, which
+    /// requires that no vertex is pushed with a lower cost than the last one popped
+    Buckets {
+        cost_resolution: f64,
+    },
+}
+
+/// the frontier of a search, held in the search buffers of the thread
+enum Frontier<'a> {
+    BinaryHeap(&'a mut InternalPriorityQueue<VertexId, ReverseCost>),
+    Buckets {
+        queue: &'a mut BucketQueue<VertexId>,
+        cost_resolution: f64,
+    },
+}
+
+impl Frontier<'_> {
+    /// adds a vertex, or updates it if it is queued with a higher cost
+    fn push(&mut self, vertex_id: VertexId, cost: Cost) {
+        match self {
+            Frontier::BinaryHeap(heap) => {
+                heap.push_increase(vertex_id, cost.into());
+            }
+            Frontier::Buckets {
+                queue,
+                cost_resolution,
+            } => {
+                // a negative or infinite cost saturates to the first or last bucket
+                let key = (cost.as_f64() / *cost_resolution).round() as u64;
+                queue.push_decrease(vertex_id, key);
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<VertexId> {
+        match self {
+            Frontier::BinaryHeap(heap) => heap.pop().map(|(vertex_id, _)| vertex_id),
+            Frontier::Buckets { queue, .. } => queue.pop().map(|(vertex_id, _)| vertex_id),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Frontier::BinaryHeap(heap) => heap.len(),
+            Frontier::Buckets { queue, .. } => queue.len(),
+        }
+    }
+}
+
+/// runs a search grown from each of the sources, which begin with their cost offsets
+fn run_search(
+    sources: &[(VertexId, Cost)],
+    goal: &mut SearchGoal,
+    direction: &Direction,
+    weight_factor: Option<Cost>,
+    queue: FrontierQueue,
+    si: &SearchInstance,
+) -> Result<SearchResult, SearchError> {
+    SearchBuffers::with(|buffers| {
+        run_search_with_buffers(sources, goal, direction, weight_factor, queue, si, buffers)
+    })
+}
+
+fn run_search_with_buffers(
+    sources: &[(VertexId, Cost)],
+    goal: &mut SearchGoal,
+    direction: &Direction,
+    weight_factor: Option<Cost>,
+    queue: FrontierQueue,
+    si: &SearchInstance,
+    buffers: &mut SearchBuffers,
+) -> Result<SearchResult, SearchError> {
+    let target = goal.target();
+    let source = sources
+        .first()
+        .map(|(vertex_id, _)| *vertex_id)
+        .ok_or_else(|| SearchError::BuildError(String::from("search has no source vertex")))?;
+
+    // context for the search (graph, search functions, frontier priority queue),
+    // reusing the cleared buffers of earlier searches on this thread
+    let mut solution: HashMap<VertexId, SearchTreeBranch> = buffers.new_tree();
+    let SearchBuffers {
+        frontier,
+        bucket_frontier,
+        g_scores: traversal_costs,
+        ..
+    } = buffers;
+    let mut costs = match queue {
+        FrontierQueue::BinaryHeap => Frontier::BinaryHeap(frontier),
+        FrontierQueue::Buckets { cost_resolution } => Frontier::Buckets {
+            queue: bucket_frontier,
+            cost_resolution,
+        },
+    };
 
-    // setup initial search state
-    traversal_costs.insert(source, Cost::ZERO);
+    // setup initial search state, with each source beginning at its cost offset
     let initial_state = si.state_model.initial_state()?;
-    let origin_cost = match target {
-        None => Cost::ZERO,
-        Some(target) => {
-            let cost_est = si.estimate_traversal_cost(source, target, &initial_state)?;
-            Cost::new(cost_est.as_f64() * weight_factor.unwrap_or(Cost::ONE).as_f64())
+    // a weight factor of zero is a dijkstra search, so estimates are never computed
+    let use_estimate = weight_factor != Some(Cost::ZERO);
+    for (source, offset) in sources.iter() {
+        let origin_cost = match target {
+            Some(target) if use_estimate => {
+                let cost_est = si.estimate_traversal_cost(*source, target, &initial_state)?;
+                Cost::new(cost_est.as_f64() * weight_factor.unwrap_or(Cost::ONE).as_f64())
+            }
+            _ => Cost::ZERO,
+        };
+        let existing = traversal_costs.get(source).unwrap_or(&Cost::INFINITY);
+        if *offset < *existing {
+            traversal_costs.insert(*source, *offset);
+            costs.push(*source, *offset + origin_cost);
         }
+    }
+    let mut metrics = SearchMetrics {
+        heap_pushes: costs.len() as u64,
+        ..Default::default()
     };
-    costs.push(source, origin_cost.into());
 
     let start_time = Instant::now();
     let mut iterations = 0;
 
     loop {
-        si.termination_model
-            .test(&start_time, solution.len(), iterations)?;
-
-        let current_vertex_id = match advance_search(&mut costs, source, target)? {
+        si.cancellation.check()?;
+        metrics.observe_frontier(costs.len());
+        let current_vertex_id = match advance_search(&mut costs, source, goal, &mut metrics)? {
             None => break,
             Some(id) => id,
         };
 
-        let last_edge_id = get_last_traversed_edge_id(&current_vertex_id, &source, &solution)?;
+        // a source is the root of its branch of the tree, unless the search reached it
+        // for less than its offset from another source
+        let is_root = !solution.contains_key(&current_vertex_id)
+            && sources.iter().any(|(v, _)| *v == current_vertex_id);
+        let last_edge_id = get_last_traversed_edge_id(&current_vertex_id, is_root, &solution)?;
         let last_edge = match last_edge_id {
             Some(id) => Some(si.directed_graph.get_edge(id)?),
             None => None,
         };
 
         // grab the current state from the solution
-        let current_state = if current_vertex_id == source {
+        let current_state = if is_root {
             initial_state.clone()
         } else {
             solution
@@ -81,6 +364,22 @@ pub fn run_a_star(
                 .result_state
                 .clone()
         };
+        si.termination_model
+            .test_with_state(
+                &start_time,
+                solution.len(),
+                iterations,
+                Some((&current_state, &si.state_model)),
+            )
+            .map_err(|e| {
+                TerminatedSearch::search_error(
+                    e,
+                    Some(current_vertex_id),
+                    solution.len(),
+                    iterations,
+                    &start_time,
+                )
+            })?;
 
         // visit all neighbors of this source vertex
         let incident_edge_iterator = direction.get_incident_edges(&current_vertex_id, si)?;
@@ -98,6 +397,12 @@ pub fn run_a_star(
             }
             let et =
                 direction.perform_edge_traversal(*edge_id, last_edge_id, &current_state, si)?;
+            let valid_traversal =
+                si.frontier_model
+                    .valid_traversal(e, &et.result_state, &si.state_model)?;
+            if !valid_traversal {
+                continue;
+            }
             let current_gscore = traversal_costs
                 .get(&terminal_vertex_id)
                 .unwrap_or(&Cost::INFINITY)
@@ -118,15 +423,16 @@ pub fn run_a_star(
                 solution.insert(key_vertex_id, traversal);
 
                 let dst_h_cost = match target {
-                    None => Cost::ZERO,
-                    Some(target_v) => {
+                    Some(target_v) if use_estimate => {
                         let cost_est =
                             si.estimate_traversal_cost(key_vertex_id, target_v, &current_state)?;
                         Cost::new(cost_est.as_f64() * weight_factor.unwrap_or(Cost::ONE).as_f64())
                     }
+                    _ => Cost::ZERO,
                 };
                 let f_score_value = tentative_gscore + dst_h_cost;
-                costs.push_increase(key_vertex_id, f_score_value.into());
+                costs.push(key_vertex_id, f_score_value);
+                metrics.heap_pushes += 1;
             }
         }
         iterations += 1;
@@ -144,14 +450,19 @@ pub fn run_a_star(
 
         log::debug!("Building flamegraph for search memory usage..");
         let mut flamegraph = allocative::FlameGraphBuilder::default();
-        flamegraph.visit_root(&costs);
-        flamegraph.visit_root(&traversal_costs);
+        match &costs {
+            Frontier::BinaryHeap(heap) => flamegraph.visit_root(&**heap),
+            Frontier::Buckets { queue, .. } => flamegraph.visit_root(&**queue),
+        }
+        flamegraph.visit_root(&*traversal_costs);
         flamegraph.visit_root(&solution);
         let output = flamegraph.finish_and_write_flame_graph();
 
-        let search_name = match target {
-            None => format!("{}_to_all", source),
-            Some(tid) => format!("{}_to_{}", source, tid),
+        let search_name = match &goal {
+            SearchGoal::All => format!("{}_to_all", source),
+            SearchGoal::Targets(_) => format!("{}_to_many", source),
+            SearchGoal::AnyOf { .. } => format!("{}_to_any", source),
+            SearchGoal::Target(tid) => format!("{}_to_{}", source, tid),
         };
 
         let outdir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -170,7 +481,8 @@ pub fn run_a_star(
         flamegraph_file.write_all(output.as_bytes()).unwrap();
     }
 
-    let result = SearchResult::new(solution, iterations);
+    buffers.observe_tree(&solution);
+    let result = SearchResult::new(solution, iterations, metrics);
     Ok(result)
 }
 
@@ -206,6 +518,7 @@ pub fn run_a_star_edge_oriented(
             let SearchResult {
                 mut tree,
                 iterations,
+                metrics,
             } = run_a_star(e1_dst, None, direction, weight_factor, si)?;
             if !tree.contains_key(&e1_dst) {
                 tree.extend([(e1_dst, src_branch)]);
@@ -213,6 +526,7 @@ pub fn run_a_star_edge_oriented(
             let updated = SearchResult {
                 tree,
                 iterations: iterations + 1,
+                metrics,
             };
             Ok(updated)
         }
@@ -244,13 +558,15 @@ pub fn run_a_star_edge_oriented(
                 let result = SearchResult {
                     tree,
                     iterations: 1,
+                    metrics: SearchMetrics::default(),
                 };
-                return Ok(result);
+                Ok(result)
             } else {
                 // run a search and append source/target edges to result
                 let SearchResult {
                     mut tree,
                     iterations,
+                    metrics,
                 } = run_a_star(e1_dst, Some(e2_src), direction, weight_factor, si)?;
 
                 if tree.is_empty() {
@@ -259,7 +575,7 @@ pub fn run_a_star_edge_oriented(
 
                 let final_state = &tree
                     .get(&e2_src)
-                    .ok_or_else(|| SearchError::VertexMissingFromSearchTree(e2_src))?
+                    .ok_or(SearchError::VertexMissingFromSearchTree(e2_src))?
                     .edge_traversal
                     .result_state;
                 let dst_et = EdgeTraversal {
@@ -285,6 +601,7 @@ pub fn run_a_star_edge_oriented(
                 let result = SearchResult {
                     tree,
                     iterations: iterations + 2,
+                    metrics,
                 };
                 Ok(result)
             }
@@ -293,30 +610,56 @@ pub fn run_a_star_edge_oriented(
 }
 
 /// grab the current vertex id, but handle some other termination conditions
-/// based on the state of the priority queue and optional search destination
+/// based on the state of the priority queue and the goal of the search
 /// - we reach the destination                                       (Ok)
-/// - if the set is ever empty and there's no destination            (Ok)
+/// - we reach the last of many destinations                         (Ok)
+/// - if the set is ever empty and there's no single destination     (Ok)
 /// - if the set is ever empty and there's a destination             (Err)
 ///
 /// # Arguments
-/// * `cost`   - queue of priority-ranked vertices for exploration
+/// * `cost`   - frontier of priority-ranked vertices for exploration
 /// * `source` - search source vertex
-/// * `target` - optional search destination
+/// * `goal`   - search destination(s), which are removed as they are reached
+/// * `metrics` - counters of the search, which records each pop from the queue
 ///
 /// # Results
 /// The next vertex to search. None if the queue has been exhausted in a search with no
-/// destination, or we have reached our destination.
+/// single destination, or we have reached our destination(s).
 /// An error if no path exists for a search that includes a destination.
 fn advance_search(
-    cost: &mut InternalPriorityQueue<VertexId, ReverseCost>,
+    cost: &mut Frontier,
     source: VertexId,
-    target: Option<VertexId>,
+    goal: &mut SearchGoal,
+    metrics: &mut SearchMetrics,
 ) -> Result<Option<VertexId>, SearchError> {
-    match (cost.pop(), target) {
-        (None, Some(target_vertex_id)) => Err(SearchError::NoPathExists(source, target_vertex_id)),
-        (None, None) => Ok(None),
-        (Some((current_v, _)), Some(target_v)) if current_v == target_v => Ok(None),
-        (Some((current_vertex_id, _)), _) => Ok(Some(current_vertex_id)),
+    let next = cost.pop();
+    if next.is_some() {
+        metrics.heap_pops += 1;
+    }
+    match (next, goal) {
+        (None, SearchGoal::Target(target_vertex_id)) => {
+            Err(SearchError::NoPathExists(source, *target_vertex_id))
+        }
+        (None, SearchGoal::AnyOf { targets, .. }) => {
+            Err(SearchError::NoPathExistsToAny(source, targets.len()))
+        }
+        (None, _) => Ok(None),
+        (Some(current_v), SearchGoal::Target(target_v)) if current_v == *target_v => Ok(None),
+        (Some(current_v), SearchGoal::AnyOf { targets, reached })
+            if targets.contains(&current_v) =>
+        {
+            *reached = Some(current_v);
+            Ok(None)
+        }
+        (Some(current_v), SearchGoal::Targets(remaining)) => {
+            remaining.remove(&current_v);
+            if remaining.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(current_v))
+            }
+        }
+        (Some(current_vertex_id), _) => Ok(Some(current_vertex_id)),
     }
 }
 
@@ -327,21 +670,22 @@ fn advance_search(
 /// Reverse case: find `next` from v2 in `(v1)-[prev]->(v2)-[next]->(v3)`
 ///
 /// # Arguments
-/// * `this_vertex_id`  - current vertex, v2 in diagram
-/// * `first_vertex_id` - source of this search, the origin vertex in a forward
-///                       search or the destination vertex in a reverse search
-/// * `tree`            - current search solution tree
+/// * `this_vertex_id` - current vertex, v2 in diagram
+/// * `is_root`        - true when this vertex is a source of the search which was
+///   not reached from another source, such as the origin of a forward search or
+///   the destination of a reverse search
+/// * `tree`           - current search solution tree
 ///
 /// # Returns
 ///
 /// The EdgeId for the edge that was traversed to reach this vertex, or None
-/// if no edges have yet been traversed.
+/// if this vertex is a root and so no edges have yet been traversed.
 fn get_last_traversed_edge_id(
     this_vertex_id: &VertexId,
-    first_vertex_id: &VertexId,
+    is_root: bool,
     tree: &HashMap<VertexId, SearchTreeBranch>,
 ) -> Result<Option<EdgeId>, SearchError> {
-    if this_vertex_id == first_vertex_id {
+    if is_root {
         Ok(None)
     } else {
         let edge_id = tree
@@ -361,7 +705,8 @@ fn get_last_traversed_edge_id(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::algorithm::search::backtrack::vertex_oriented_route;
+    use crate::algorithm::search::backtrack::{multi_origin_route, vertex_oriented_route};
+    use crate::algorithm::search::cancellation_token::CancellationToken;
     use crate::algorithm::search::MinSearchTree;
     use crate::model::access::default::no_access_model::NoAccessModel;
     use crate::model::cost::cost_aggregation::CostAggregation;
@@ -409,10 +754,49 @@ mod tests {
         }
 
         Graph {
-            adj: adj.into_boxed_slice(),
-            rev: rev.into_boxed_slice(),
-            edges: edges.into_boxed_slice(),
-            vertices: vertices.into_boxed_slice(),
+            adj: adj.into(),
+            rev: rev.into(),
+            edges: edges.into(),
+            vertices: vertices.into(),
+            geometries: None,
+            edge_attributes: None,
+        }
+    }
+
+    fn build_mock_search_instance() -> SearchInstance {
+        let state_model = Arc::new(
+            StateModel::empty()
+                .extend(vec![(
+                    String::from("distance"),
+                    StateFeature::Distance {
+                        distance_unit: DistanceUnit::Kilometers,
+                        initial: Distance::new(0.0),
+                    },
+                )])
+                .unwrap(),
+        );
+        let cost_model = CostModel::new(
+            // vec![(String::from("distance"), 0usize)],
+            Arc::new(HashMap::from([(String::from("distance"), 1.0)])),
+            Arc::new(HashMap::from([(
+                String::from("distance"),
+                VehicleCostRate::Raw,
+            )])),
+            Arc::new(HashMap::new()),
+            CostAggregation::Sum,
+            state_model.clone(),
+        )
+        .unwrap();
+        SearchInstance {
+            directed_graph: Arc::new(build_mock_graph()),
+            state_model: state_model.clone(),
+            traversal_model: Arc::new(DistanceTraversalModel::new(DistanceUnit::Meters)),
+            access_model: Arc::new(NoAccessModel {}),
+            cost_model,
+            frontier_model: Arc::new(NoRestriction {}),
+            termination_model: Arc::new(TerminationModel::IterationsLimit { limit: 20 }),
+            heuristic: None,
+            cancellation: CancellationToken::default(),
         }
     }
 
@@ -465,38 +849,7 @@ mod tests {
 
         // setup the graph, traversal model, and a* heuristic to be shared across the queries in parallel
         // these live in the "driver" process and are passed as read-only memory to each executor process
-        let state_model = Arc::new(
-            StateModel::empty()
-                .extend(vec![(
-                    String::from("distance"),
-                    StateFeature::Distance {
-                        distance_unit: DistanceUnit::Kilometers,
-                        initial: Distance::new(0.0),
-                    },
-                )])
-                .unwrap(),
-        );
-        let cost_model = CostModel::new(
-            // vec![(String::from("distance"), 0usize)],
-            Arc::new(HashMap::from([(String::from("distance"), 1.0)])),
-            Arc::new(HashMap::from([(
-                String::from("distance"),
-                VehicleCostRate::Raw,
-            )])),
-            Arc::new(HashMap::new()),
-            CostAggregation::Sum,
-            state_model.clone(),
-        )
-        .unwrap();
-        let si = SearchInstance {
-            directed_graph: Arc::new(build_mock_graph()),
-            state_model: state_model.clone(),
-            traversal_model: Arc::new(DistanceTraversalModel::new(DistanceUnit::Meters)),
-            access_model: Arc::new(NoAccessModel {}),
-            cost_model,
-            frontier_model: Arc::new(NoRestriction {}),
-            termination_model: Arc::new(TerminationModel::IterationsLimit { limit: 20 }),
-        };
+        let si = build_mock_search_instance();
 
         // execute the route search
         let result: Vec<Result<MinSearchTree, SearchError>> = queries
@@ -520,4 +873,128 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_shared_origin_targets() {
+        // the routes backtracked from one tree to many targets match the routes of
+        // searches to each target on their own
+        let si = build_mock_search_instance();
+        let targets = vec![VertexId(1), VertexId(2), VertexId(3), VertexId(0)];
+        let tree = run_a_star_to_targets(VertexId(0), &targets, &Direction::Forward, &si)
+            .unwrap()
+            .tree;
+        for target in targets.into_iter().filter(|t| *t != VertexId(0)) {
+            let single = run_a_star(VertexId(0), Some(target), &Direction::Forward, None, &si)
+                .unwrap()
+                .tree;
+            let shared_route = vertex_oriented_route(VertexId(0), target, &tree).unwrap();
+            let single_route = vertex_oriented_route(VertexId(0), target, &single).unwrap();
+            let edges = |route: &Vec<EdgeTraversal>| -> Vec<EdgeId> {
+                route.iter().map(|e| e.edge_id).collect()
+            };
+            assert_eq!(edges(&shared_route), edges(&single_route));
+        }
+    }
+
+    #[test]
+    fn test_any_of_targets() {
+        // the search stops at the nearest of the targets: from vertex 0, vertex 2 is
+        // reached over edges 7 and 5 at a cost of 3, before vertex 1 at a cost of 5
+        let si = build_mock_search_instance();
+        let targets = vec![VertexId(1), VertexId(2)];
+        let (result, chosen) =
+            run_a_star_to_any(VertexId(0), &targets, &Direction::Forward, &si).unwrap();
+        assert_eq!(chosen, VertexId(2));
+        let route = vertex_oriented_route(VertexId(0), chosen, &result.tree).unwrap();
+        let edges: Vec<EdgeId> = route.iter().map(|e| e.edge_id).collect();
+        assert_eq!(edges, vec![EdgeId(7), EdgeId(5)]);
+
+        let (_, chosen) = run_a_star_to_any(
+            VertexId(0),
+            &[VertexId(1), VertexId(0)],
+            &Direction::Forward,
+            &si,
+        )
+        .unwrap();
+        assert_eq!(chosen, VertexId(0));
+        assert!(run_a_star_to_any(VertexId(0), &[], &Direction::Forward, &si).is_err());
+    }
+
+    #[test]
+    fn test_multi_origin() {
+        // vertex 2 is reached from vertex 1 over edge 2, or from vertex 0 over edges 7
+        // and 5 at 1.5 times the cost, so the offset of vertex 1 decides the origin
+        let si = build_mock_search_instance();
+        let tree = run_a_star(
+            VertexId(1),
+            Some(VertexId(2)),
+            &Direction::Forward,
+            None,
+            &si,
+        )
+        .unwrap()
+        .tree;
+        let route = vertex_oriented_route(VertexId(1), VertexId(2), &tree).unwrap();
+        let cost_1_2 = route[0].total_cost();
+        for (offset, expected_origin, expected_edges) in [
+            (0.25, VertexId(1), vec![EdgeId(2)]),
+            (1.0, VertexId(0), vec![EdgeId(7), EdgeId(5)]),
+        ] {
+            let offset = Cost::new(cost_1_2.as_f64() * offset);
+            let sources = [(VertexId(0), Cost::ZERO), (VertexId(1), offset)];
+            let result = run_a_star_multi_origin(
+                &sources,
+                Some(VertexId(2)),
+                &Direction::Forward,
+                None,
+                &si,
+            )
+            .unwrap();
+            let (origin, route) =
+                multi_origin_route(&[VertexId(0), VertexId(1)], VertexId(2), &result.tree).unwrap();
+            assert_eq!(origin, expected_origin);
+            let edges: Vec<EdgeId> = route.iter().map(|e| e.edge_id).collect();
+            assert_eq!(edges, expected_edges);
+        }
+    }
+
+    #[test]
+    fn test_bucket_dijkstra() {
+        // the bucket queue finds the same routes as the binary heap, and a full tree
+        // reaches every vertex other than the source
+        let si = build_mock_search_instance();
+        let resolution = Cost::new(0.001);
+        for (o, d) in [(0, 1), (1, 0), (2, 3), (3, 1)] {
+            let (o, d) = (VertexId(o), VertexId(d));
+            let heap = run_a_star(o, Some(d), &Direction::Forward, None, &si).unwrap();
+            let buckets =
+                run_bucket_dijkstra(o, Some(d), &Direction::Forward, resolution, &si).unwrap();
+            let edges = |tree: &MinSearchTree| -> Vec<EdgeId> {
+                let route = vertex_oriented_route(o, d, tree).unwrap();
+                route.iter().map(|e| e.edge_id).collect()
+            };
+            assert_eq!(edges(&buckets.tree), edges(&heap.tree));
+        }
+        let tree = run_bucket_dijkstra(VertexId(0), None, &Direction::Forward, resolution, &si)
+            .unwrap()
+            .tree;
+        assert_eq!(tree.len(), 3);
+        assert!(
+            run_bucket_dijkstra(VertexId(0), None, &Direction::Forward, Cost::ZERO, &si).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cancelled_search() {
+        let si = build_mock_search_instance();
+        si.cancellation.cancel();
+        let result = run_a_star(
+            VertexId(0),
+            Some(VertexId(1)),
+            &Direction::Forward,
+            None,
+            &si,
+        );
+        assert!(matches!(result, Err(SearchError::Cancelled)));
+    }
 }
@@ -0,0 +1,358 @@
+use crate::algorithm::search::edge_traversal::EdgeTraversal;
+use crate::algorithm::search::search_algorithm_result::SearchAlgorithmResult;
+use crate::algorithm::search::search_error::SearchError;
+use crate::algorithm::search::search_instance::SearchInstance;
+use crate::algorithm::search::search_metrics::SearchMetrics;
+use crate::algorithm::search::search_tree_branch::SearchTreeBranch;
+use crate::algorithm::search::terminated_search::TerminatedSearch;
+use crate::algorithm::search::MinSearchTree;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::unit::as_f64::AsF64;
+use crate::model::unit::cost::ReverseCost;
+use crate::model::unit::Cost;
+use crate::util::instant::Instant;
+use crate::util::priority_queue::InternalPriorityQueue;
+use std::collections::HashMap;
+
+/// a search label for an edge in an edge-based search. stores the edge that was
+/// traversed before this one along with the traversal of this edge.
+struct EdgeLabel {
+    prev_edge_id: Option<EdgeId>,
+    cost: Cost,
+    edge_traversal: EdgeTraversal,
+}
+
+/// run an edge-based a* search between a source edge and a target edge.
+///
+/// unlike [super::a_star_algorithm::run_a_star], which keeps a single label per
+/// vertex, this search keeps a label per edge. a vertex may therefore be visited
+/// more than once, once for each edge used to approach it. this makes turn
+/// restrictions exact: the frontier model is asked whether each (previous edge,
+/// next edge) pair is valid, and a restricted turn at a vertex does not prevent
+/// reaching that vertex by another approach and turning there legally. the
+/// restriction check also applies to the turn from the source edge and the turn
+/// onto the target edge.
+///
+/// as with vertex-oriented searches, the source and target edges are included in
+/// the route with zero cost.
+///
+/// # Arguments
+///
+/// * `source` - edge where the search begins
+/// * `target` - edge where the search ends
+/// * `weight_factor` - optional a* heuristic weight
+/// * `si` - the search instance
+///
+/// # Returns
+///
+/// the route between the edges along with a vertex-oriented tree built from the
+/// least-cost label found for each vertex
+pub fn run_edge_based_a_star(
+    source: EdgeId,
+    target: EdgeId,
+    weight_factor: Option<Cost>,
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    if source == target {
+        return Ok(SearchAlgorithmResult::default());
+    }
+    let e1_dst = si.directed_graph.dst_vertex_id(source)?;
+    let e2_src = si.directed_graph.src_vertex_id(target)?;
+    let weight = weight_factor.unwrap_or(Cost::ONE).as_f64();
+
+    let mut frontier: InternalPriorityQueue<EdgeId, ReverseCost> = InternalPriorityQueue::default();
+    let mut labels: HashMap<EdgeId, EdgeLabel> = HashMap::new();
+
+    let initial_state = si.state_model.initial_state()?;
+    let origin_estimate = if weight == 0.0 {
+        Cost::ZERO
+    } else {
+        si.estimate_traversal_cost(e1_dst, e2_src, &initial_state)?
+    };
+    labels.insert(
+        source,
+        EdgeLabel {
+            prev_edge_id: None,
+            cost: Cost::ZERO,
+            edge_traversal: EdgeTraversal {
+                edge_id: source,
+                access_cost: Cost::ZERO,
+                traversal_cost: Cost::ZERO,
+                result_state: initial_state,
+            },
+        },
+    );
+    frontier.push(source, Cost::new(origin_estimate.as_f64() * weight).into());
+
+    let start_time = Instant::now();
+    let mut iterations = 0;
+    let mut metrics = SearchMetrics {
+        heap_pushes: 1,
+        ..Default::default()
+    };
+
+    loop {
+        si.cancellation.check()?;
+        metrics.observe_frontier(frontier.len());
+        let next = frontier.pop();
+        if next.is_some() {
+            metrics.heap_pops += 1;
+        }
+        let current_edge_id = match next {
+            None => return Err(SearchError::NoPathExists(e1_dst, e2_src)),
+            Some((edge_id, _)) if edge_id == target => break,
+            Some((edge_id, _)) => edge_id,
+        };
+        let (current_cost, current_state) = match labels.get(&current_edge_id) {
+            Some(label) => (label.cost, label.edge_traversal.result_state.clone()),
+            None => {
+                return Err(SearchError::InternalSearchError(format!(
+                    "expected edge id {} missing from edge labels",
+                    current_edge_id
+                )))
+            }
+        };
+        let current_edge = si.directed_graph.get_edge(current_edge_id)?;
+        si.termination_model
+            .test_with_state(
+                &start_time,
+                labels.len(),
+                iterations,
+                Some((&current_state, &si.state_model)),
+            )
+            .map_err(|e| {
+                TerminatedSearch::search_error(
+                    e,
+                    Some(current_edge.dst_vertex_id),
+                    labels.len(),
+                    iterations,
+                    &start_time,
+                )
+            })?;
+
+        for next_edge_id in si
+            .directed_graph
+            .out_edges_iter(current_edge.dst_vertex_id)?
+        {
+            let next_edge = si.directed_graph.get_edge(*next_edge_id)?;
+            let valid_frontier = si.frontier_model.valid_frontier(
+                next_edge,
+                &current_state,
+                Some(current_edge),
+                &si.state_model,
+            )?;
+            if !valid_frontier {
+                continue;
+            }
+
+            let et = if *next_edge_id == target {
+                EdgeTraversal {
+                    edge_id: target,
+                    access_cost: Cost::ZERO,
+                    traversal_cost: Cost::ZERO,
+                    result_state: current_state.clone(),
+                }
+            } else {
+                EdgeTraversal::forward_traversal(
+                    *next_edge_id,
+                    Some(current_edge_id),
+                    &current_state,
+                    si,
+                )?
+            };
+            let valid_traversal =
+                si.frontier_model
+                    .valid_traversal(next_edge, &et.result_state, &si.state_model)?;
+            if !valid_traversal {
+                continue;
+            }
+            let tentative_cost = current_cost + et.total_cost();
+            let existing_cost = labels
+                .get(next_edge_id)
+                .map(|l| l.cost)
+                .unwrap_or(Cost::INFINITY);
+            if tentative_cost < existing_cost {
+                let estimate = if *next_edge_id == target || weight == 0.0 {
+                    Cost::ZERO
+                } else {
+                    si.estimate_traversal_cost(next_edge.dst_vertex_id, e2_src, &et.result_state)?
+                };
+                let f_score = tentative_cost + Cost::new(estimate.as_f64() * weight);
+                labels.insert(
+                    *next_edge_id,
+                    EdgeLabel {
+                        prev_edge_id: Some(current_edge_id),
+                        cost: tentative_cost,
+                        edge_traversal: et,
+                    },
+                );
+                frontier.push_increase(*next_edge_id, f_score.into());
+                metrics.heap_pushes += 1;
+            }
+        }
+        iterations += 1;
+    }
+
+    let route_start = Instant::now();
+    let route = edge_label_route(source, target, &labels)?;
+    metrics.route_runtime = route_start.elapsed();
+    let tree = edge_label_tree(&labels, si)?;
+    Ok(SearchAlgorithmResult {
+        trees: vec![tree],
+        routes: vec![route],
+        iterations,
+        metrics,
+    })
+}
+
+/// follows the previous edge of each label from the target back to the source
+fn edge_label_route(
+    source: EdgeId,
+    target: EdgeId,
+    labels: &HashMap<EdgeId, EdgeLabel>,
+) -> Result<Vec<EdgeTraversal>, SearchError> {
+    let mut route = vec![];
+    let mut current = Some(target);
+    while let Some(edge_id) = current {
+        let label = labels.get(&edge_id).ok_or_else(|| {
+            SearchError::InternalSearchError(format!(
+                "expected edge id {} missing from edge labels",
+                edge_id
+            ))
+        })?;
+        route.push(label.edge_traversal.clone());
+        current = label.prev_edge_id;
+        if route.len() > labels.len() {
+            return Err(SearchError::InternalSearchError(String::from(
+                "cycle found when reconstructing edge-based route",
+            )));
+        }
+    }
+    match route.last() {
+        Some(et) if et.edge_id == source => {
+            route.reverse();
+            Ok(route)
+        }
+        _ => Err(SearchError::InternalSearchError(format!(
+            "edge-based route does not begin at source edge {}",
+            source
+        ))),
+    }
+}
+
+/// collapses the edge labels into a tree keyed by vertex, keeping the
+/// least-cost label that arrives at each vertex
+fn edge_label_tree(
+    labels: &HashMap<EdgeId, EdgeLabel>,
+    si: &SearchInstance,
+) -> Result<MinSearchTree, SearchError> {
+    let mut best: HashMap<_, (Cost, SearchTreeBranch)> = HashMap::new();
+    for (edge_id, label) in labels.iter() {
+        let edge = si.directed_graph.get_edge(*edge_id)?;
+        let replace = best
+            .get(&edge.dst_vertex_id)
+            .map(|(cost, _)| label.cost < *cost)
+            .unwrap_or(true);
+        if replace {
+            let branch = SearchTreeBranch {
+                terminal_vertex: edge.src_vertex_id,
+                edge_traversal: label.edge_traversal.clone(),
+            };
+            best.insert(edge.dst_vertex_id, (label.cost, branch));
+        }
+    }
+    Ok(best.into_iter().map(|(k, (_, b))| (k, b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_at_origin};
+    use crate::model::frontier::default::no_restriction::NoRestriction;
+    use crate::model::frontier::frontier_model::FrontierModel;
+    use crate::model::frontier::frontier_model_error::FrontierModelError;
+    use crate::model::property::edge::Edge;
+    use crate::model::road_network::graph::Graph;
+    use crate::model::state::state_model::StateModel;
+    use crate::model::traversal::state::state_variable::StateVar;
+    use crate::model::unit::DistanceUnit;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    /// forbids a set of (previous edge, next edge) turns
+    struct MockTurnRestriction {
+        restricted: HashSet<(EdgeId, EdgeId)>,
+    }
+
+    impl FrontierModel for MockTurnRestriction {
+        fn valid_frontier(
+            &self,
+            edge: &Edge,
+            _state: &[StateVar],
+            previous_edge: Option<&Edge>,
+            _state_model: &StateModel,
+        ) -> Result<bool, FrontierModelError> {
+            match previous_edge {
+                None => Ok(true),
+                Some(prev) => Ok(!self.restricted.contains(&(prev.edge_id, edge.edge_id))),
+            }
+        }
+    }
+
+    /// a straight road (0) -> (1) -> (2) with a loop from (1) back to (1)
+    /// through vertex (3). the loop is the only way to reach (2) when turning
+    /// from edge 0 onto edge 1 is forbidden, and it revisits vertex (1).
+    ///
+    /// (0) -[0]-> (1) -[1]-> (2)
+    ///            | ^
+    ///           [2][3]
+    ///            v |
+    ///            (3)
+    fn build_mock_graph() -> Graph {
+        let edges = vec![
+            Edge::new(0, 0, 1, 1.0),
+            Edge::new(1, 1, 2, 1.0),
+            Edge::new(2, 1, 3, 1.0),
+            Edge::new(3, 3, 1, 1.0),
+            Edge::new(4, 2, 1, 1.0),
+        ];
+        graph_at_origin(4, edges)
+    }
+
+    fn build_search_instance(frontier_model: Arc<dyn FrontierModel>) -> SearchInstance {
+        SearchInstance {
+            frontier_model,
+            ..distance_search_instance(build_mock_graph(), DistanceUnit::Meters)
+        }
+    }
+
+    fn route_edges(result: &SearchAlgorithmResult) -> Vec<EdgeId> {
+        result.routes[0].iter().map(|et| et.edge_id).collect()
+    }
+
+    #[test]
+    fn test_unrestricted_route() {
+        let si = build_search_instance(Arc::new(NoRestriction {}));
+        let result = run_edge_based_a_star(EdgeId(0), EdgeId(1), None, &si).unwrap();
+        assert_eq!(route_edges(&result), vec![EdgeId(0), EdgeId(1)]);
+    }
+
+    #[test]
+    fn test_restricted_turn_revisits_vertex() {
+        let restricted = HashSet::from([(EdgeId(0), EdgeId(1))]);
+        let si = build_search_instance(Arc::new(MockTurnRestriction { restricted }));
+        let result = run_edge_based_a_star(EdgeId(0), EdgeId(1), None, &si).unwrap();
+        assert_eq!(
+            route_edges(&result),
+            vec![EdgeId(0), EdgeId(2), EdgeId(3), EdgeId(1)]
+        );
+    }
+
+    #[test]
+    fn test_no_legal_route() {
+        let restricted = HashSet::from([(EdgeId(0), EdgeId(1)), (EdgeId(3), EdgeId(1))]);
+        let si = build_search_instance(Arc::new(MockTurnRestriction { restricted }));
+        let result = run_edge_based_a_star(EdgeId(0), EdgeId(1), None, &si);
+        assert!(matches!(result, Err(SearchError::NoPathExists(_, _))));
+    }
+}
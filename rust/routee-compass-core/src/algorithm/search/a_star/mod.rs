@@ -1,2 +1,3 @@
 pub mod a_star_algorithm;
 pub mod bidirectional_a_star_algorithm;
+pub mod edge_based_a_star;
@@ -24,7 +24,7 @@ pub fn vertex_oriented_route(
         }
         let traversal = solution
             .get(&this_vertex)
-            .ok_or_else(|| SearchError::VertexMissingFromSearchTree(this_vertex))?;
+            .ok_or(SearchError::VertexMissingFromSearchTree(this_vertex))?;
         let first_visit = visited.insert(traversal.edge_traversal.edge_id);
         if !first_visit {
             return Err(SearchError::LoopInSearchResult(
@@ -38,6 +38,36 @@ pub fn vertex_oriented_route(
     Ok(reversed)
 }
 
+/// reconstructs a path from a search tree grown from several sources to some target.
+/// travels up from the target until reaching a source which is the root of its branch
+/// of the tree, returning that source along with the path.
+pub fn multi_origin_route(
+    source_ids: &[VertexId],
+    target_id: VertexId,
+    solution: &HashMap<VertexId, SearchTreeBranch>,
+) -> Result<(VertexId, Vec<EdgeTraversal>), SearchError> {
+    let mut result: Vec<EdgeTraversal> = vec![];
+    let mut visited: HashSet<EdgeId> = HashSet::new();
+    let mut this_vertex = target_id;
+    loop {
+        let traversal = match solution.get(&this_vertex) {
+            None if source_ids.contains(&this_vertex) => break,
+            None => return Err(SearchError::VertexMissingFromSearchTree(this_vertex)),
+            Some(traversal) => traversal,
+        };
+        let first_visit = visited.insert(traversal.edge_traversal.edge_id);
+        if !first_visit {
+            return Err(SearchError::LoopInSearchResult(
+                traversal.edge_traversal.edge_id,
+            ));
+        }
+        result.push(traversal.edge_traversal.clone());
+        this_vertex = traversal.terminal_vertex;
+    }
+    let reversed = result.into_iter().rev().collect();
+    Ok((this_vertex, reversed))
+}
+
 /// edge-oriented backtrack method
 pub fn edge_oriented_route(
     source_id: EdgeId,
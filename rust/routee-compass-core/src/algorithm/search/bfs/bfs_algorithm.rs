@@ -0,0 +1,172 @@
+use crate::algorithm::search::direction::Direction;
+use crate::algorithm::search::search_error::SearchError;
+use crate::algorithm::search::search_instance::SearchInstance;
+use crate::algorithm::search::search_metrics::SearchMetrics;
+use crate::algorithm::search::search_result::SearchResult;
+use crate::algorithm::search::search_tree_branch::SearchTreeBranch;
+use crate::algorithm::search::terminated_search::TerminatedSearch;
+use crate::model::road_network::vertex_id::VertexId;
+use crate::util::instant::Instant;
+use std::collections::{HashMap, VecDeque};
+
+/// run a breadth-first search over the given directed graph model. vertices are
+/// visited in order of the number of edges (hops) from the source, so a route to
+/// the target uses the fewest edges, regardless of cost. the traversal model is
+/// still run on each edge so that the search tree carries the traversal state.
+/// the search queue is first-in first-out rather than a heap, so only the largest
+/// frontier size is recorded in the search metrics and the heap counters stay zero.
+///
+/// # Arguments
+///
+/// * `source` - vertex where the search begins
+/// * `target` - optional vertex where the search ends
+/// * `direction` - direction of the search
+/// * `max_hops` - optional limit on the number of edges from the source
+/// * `si` - the search instance
+///
+/// # Returns
+///
+/// a search tree of all vertices reached within `max_hops`, or until the target
+/// was reached. an error if a target was provided and it could not be reached.
+pub fn run_bfs(
+    source: VertexId,
+    target: Option<VertexId>,
+    direction: &Direction,
+    max_hops: Option<usize>,
+    si: &SearchInstance,
+) -> Result<SearchResult, SearchError> {
+    if target == Some(source) {
+        return Ok(SearchResult::default());
+    }
+
+    let mut queue: VecDeque<(VertexId, usize)> = VecDeque::from([(source, 0)]);
+    let mut solution: HashMap<VertexId, SearchTreeBranch> = HashMap::new();
+    let initial_state = si.state_model.initial_state()?;
+
+    let start_time = Instant::now();
+    let mut iterations = 0;
+    let mut metrics = SearchMetrics::default();
+
+    while let Some((current_vertex_id, hops)) = queue.pop_front() {
+        metrics.observe_frontier(queue.len() + 1);
+        si.cancellation.check()?;
+        si.termination_model
+            .test(&start_time, solution.len(), iterations)
+            .map_err(|e| {
+                TerminatedSearch::search_error(
+                    e,
+                    Some(current_vertex_id),
+                    solution.len(),
+                    iterations,
+                    &start_time,
+                )
+            })?;
+        iterations += 1;
+        if max_hops.map(|max| hops >= max).unwrap_or_default() {
+            continue;
+        }
+
+        let (last_edge_id, current_state) = match solution.get(&current_vertex_id) {
+            None => (None, initial_state.clone()),
+            Some(branch) => (
+                Some(branch.edge_traversal.edge_id),
+                branch.edge_traversal.result_state.clone(),
+            ),
+        };
+        let last_edge = match last_edge_id {
+            Some(id) => Some(si.directed_graph.get_edge(id)?),
+            None => None,
+        };
+
+        for edge_id in direction.get_incident_edges(&current_vertex_id, si)? {
+            let e = si.directed_graph.get_edge(*edge_id)?;
+            let key_vertex_id = direction.tree_key_vertex_id(e);
+            if key_vertex_id == source || solution.contains_key(&key_vertex_id) {
+                continue;
+            }
+            let valid_frontier =
+                si.frontier_model
+                    .valid_frontier(e, &current_state, last_edge, &si.state_model)?;
+            if !valid_frontier {
+                continue;
+            }
+            let et =
+                direction.perform_edge_traversal(*edge_id, last_edge_id, &current_state, si)?;
+            let valid_traversal =
+                si.frontier_model
+                    .valid_traversal(e, &et.result_state, &si.state_model)?;
+            if !valid_traversal {
+                continue;
+            }
+            let branch = SearchTreeBranch {
+                terminal_vertex: direction.terminal_vertex_id(e),
+                edge_traversal: et,
+            };
+            solution.insert(key_vertex_id, branch);
+            if target == Some(key_vertex_id) {
+                return Ok(SearchResult::new(solution, iterations, metrics));
+            }
+            queue.push_back((key_vertex_id, hops + 1));
+        }
+    }
+
+    match target {
+        Some(target_vertex_id) => Err(SearchError::NoPathExists(source, target_vertex_id)),
+        None => Ok(SearchResult::new(solution, iterations, metrics)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::backtrack::vertex_oriented_route;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_at_origin};
+    use crate::model::property::edge::Edge;
+    use crate::model::road_network::edge_id::EdgeId;
+    use crate::model::unit::DistanceUnit;
+
+    /// a path (0) -> (1) -> (2) -> (3) of short edges and a single long edge (0) -> (3)
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![
+            Edge::new(0, 0, 1, 1.0),
+            Edge::new(1, 1, 2, 1.0),
+            Edge::new(2, 2, 3, 1.0),
+            Edge::new(3, 0, 3, 100.0),
+        ];
+        distance_search_instance(graph_at_origin(4, edges), DistanceUnit::Meters)
+    }
+
+    #[test]
+    fn test_bfs_finds_fewest_hops() {
+        let si = build_search_instance();
+        let result = run_bfs(
+            VertexId(0),
+            Some(VertexId(3)),
+            &Direction::Forward,
+            None,
+            &si,
+        )
+        .unwrap();
+        let route = vertex_oriented_route(VertexId(0), VertexId(3), &result.tree).unwrap();
+        let route_edges = route.iter().map(|et| et.edge_id).collect::<Vec<_>>();
+        assert_eq!(route_edges, vec![EdgeId(3)]);
+    }
+
+    #[test]
+    fn test_bfs_hop_limit() {
+        let si = build_search_instance();
+        let result = run_bfs(VertexId(0), None, &Direction::Forward, Some(1), &si).unwrap();
+        let mut reached = result.tree.keys().cloned().collect::<Vec<_>>();
+        reached.sort();
+        assert_eq!(reached, vec![VertexId(1), VertexId(3)]);
+
+        let unreachable = run_bfs(
+            VertexId(1),
+            Some(VertexId(3)),
+            &Direction::Forward,
+            Some(1),
+            &si,
+        );
+        assert!(unreachable.is_err());
+    }
+}
@@ -0,0 +1 @@
+pub mod bfs_algorithm;
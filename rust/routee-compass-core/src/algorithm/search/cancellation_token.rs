@@ -0,0 +1,38 @@
+use super::search_error::SearchError;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// a flag shared between a running search and the caller that started it, so that
+/// the caller can abort the search from another thread, such as when a client
+/// disconnects or a notebook is interrupted. searches check the flag at the top of
+/// each iteration and return [`SearchError::Cancelled`], dropping their search tree.
+///
+/// clones share the same flag, and once cancelled a token stays cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// cancels every search holding a clone of this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// returns an error if this token has been cancelled
+    pub fn check(&self) -> Result<(), SearchError> {
+        if self.is_cancelled() {
+            Err(SearchError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::model::{
+    road_network::{edge_id::EdgeId, vertex_id::VertexId},
+    unit::Cost,
+};
+use serde::{Deserialize, Serialize};
+
+/// an edge in a contraction hierarchy. either a copy of an edge in the
+/// source graph or a shortcut which replaces a pair of hierarchy edges
+/// that passed through a contracted vertex.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChEdge {
+    pub src_vertex_id: VertexId,
+    pub dst_vertex_id: VertexId,
+    pub weight: Cost,
+    pub edge_type: ChEdgeType,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ChEdgeType {
+    /// edge taken directly from the source graph
+    Original { edge_id: EdgeId },
+    /// shortcut composed of two hierarchy edges, by index, traversed in order
+    Shortcut { first: usize, second: usize },
+}
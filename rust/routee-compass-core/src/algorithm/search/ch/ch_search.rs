@@ -0,0 +1,350 @@
+use super::contraction_hierarchy::ContractionHierarchy;
+use crate::algorithm::search::edge_traversal::EdgeTraversal;
+use crate::algorithm::search::search_algorithm_result::SearchAlgorithmResult;
+use crate::algorithm::search::search_error::SearchError;
+use crate::algorithm::search::search_instance::SearchInstance;
+use crate::algorithm::search::search_metrics::SearchMetrics;
+use crate::algorithm::search::search_tree_branch::SearchTreeBranch;
+use crate::algorithm::search::terminated_search::TerminatedSearch;
+use crate::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
+use crate::model::unit::cost::ReverseCost;
+use crate::model::unit::Cost;
+use crate::util::instant::Instant;
+use crate::util::priority_queue::InternalPriorityQueue;
+use std::collections::HashMap;
+
+/// label for a vertex reached during a hierarchy search: the cost to reach the
+/// vertex and the hierarchy edge used to reach it.
+type ChLabel = (Cost, Option<usize>);
+
+/// runs a bidirectional upward search over a contraction hierarchy between a source
+/// and target vertex. the forward search follows upward edges from the source and
+/// the backward search follows downward edges in reverse from the target. the
+/// resulting path is unpacked into source graph edges and traversed with the
+/// search instance models to produce the route state and costs.
+///
+/// the hierarchy was built from static weights, so the frontier model cannot steer
+/// the search. the app rejects the `ch` algorithm alongside restricting frontier or
+/// access models, and a query whose route still crosses an edge that the query
+/// restricts is an invalid query.
+pub fn run_ch(
+    source: VertexId,
+    target: VertexId,
+    hierarchy: &ContractionHierarchy,
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    hierarchy.validate(&si.directed_graph)?;
+    if source == target {
+        return Ok(SearchAlgorithmResult::default());
+    }
+
+    let mut fwd_queue: InternalPriorityQueue<VertexId, ReverseCost> =
+        InternalPriorityQueue::default();
+    let mut bwd_queue: InternalPriorityQueue<VertexId, ReverseCost> =
+        InternalPriorityQueue::default();
+    let mut fwd_labels: HashMap<VertexId, ChLabel> = HashMap::from([(source, (Cost::ZERO, None))]);
+    let mut bwd_labels: HashMap<VertexId, ChLabel> = HashMap::from([(target, (Cost::ZERO, None))]);
+    fwd_queue.push(source, Cost::ZERO.into());
+    bwd_queue.push(target, Cost::ZERO.into());
+
+    let mut best: Option<(Cost, VertexId)> = None;
+    let start_time = Instant::now();
+    let mut iterations = 0;
+    let mut metrics = SearchMetrics {
+        heap_pushes: 2,
+        ..Default::default()
+    };
+
+    loop {
+        metrics.observe_frontier(fwd_queue.len() + bwd_queue.len());
+        let tree_size = fwd_labels.len() + bwd_labels.len();
+        si.cancellation.check()?;
+        si.termination_model
+            .test(&start_time, tree_size, iterations)
+            .map_err(|e| {
+                TerminatedSearch::search_error(e, None, tree_size, iterations, &start_time)
+            })?;
+        let fwd_min = peek_cost(&fwd_queue);
+        let bwd_min = peek_cost(&bwd_queue);
+        let best_cost = best.map(|(c, _)| c).unwrap_or(Cost::INFINITY);
+        let forward = match (fwd_min, bwd_min) {
+            (None, None) => break,
+            (Some(f), Some(b)) if f >= best_cost && b >= best_cost => break,
+            (Some(f), None) if f >= best_cost => break,
+            (None, Some(b)) if b >= best_cost => break,
+            (Some(f), Some(b)) => f <= b,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+        };
+
+        let (queue, labels, other_labels) = if forward {
+            (&mut fwd_queue, &mut fwd_labels, &bwd_labels)
+        } else {
+            (&mut bwd_queue, &mut bwd_labels, &fwd_labels)
+        };
+        let (vertex_id, _) = queue.pop().ok_or_else(|| {
+            SearchError::InternalSearchError(String::from("expected non-empty search frontier"))
+        })?;
+        metrics.heap_pops += 1;
+        let (vertex_cost, _) = labels[&vertex_id];
+
+        if let Some((other_cost, _)) = other_labels.get(&vertex_id) {
+            let meeting_cost = vertex_cost + *other_cost;
+            if meeting_cost < best_cost {
+                best = Some((meeting_cost, vertex_id));
+            }
+        }
+
+        let edge_indices = if forward {
+            &hierarchy.upward[vertex_id.0]
+        } else {
+            &hierarchy.downward[vertex_id.0]
+        };
+        for edge_idx in edge_indices.iter() {
+            let edge = &hierarchy.edges[*edge_idx];
+            let next_vertex_id = if forward {
+                edge.dst_vertex_id
+            } else {
+                edge.src_vertex_id
+            };
+            let next_cost = vertex_cost + edge.weight;
+            let improves = labels
+                .get(&next_vertex_id)
+                .map(|(c, _)| next_cost < *c)
+                .unwrap_or(true);
+            if improves {
+                labels.insert(next_vertex_id, (next_cost, Some(*edge_idx)));
+                queue.push_increase(next_vertex_id, next_cost.into());
+                metrics.heap_pushes += 1;
+            }
+        }
+        iterations += 1;
+    }
+
+    let (_, meeting_vertex) = best.ok_or(SearchError::NoPathExists(source, target))?;
+    let route_start = Instant::now();
+
+    // collect hierarchy edges from source to meeting vertex, then meeting vertex to target
+    let mut ch_edges = backtrack_labels(meeting_vertex, &fwd_labels, hierarchy, true)?;
+    ch_edges.reverse();
+    ch_edges.extend(backtrack_labels(
+        meeting_vertex,
+        &bwd_labels,
+        hierarchy,
+        false,
+    )?);
+    let mut edge_ids: Vec<EdgeId> = vec![];
+    for edge_idx in ch_edges {
+        edge_ids.extend(hierarchy.unpack_edge(edge_idx)?);
+    }
+
+    // replay the route with the search instance models to compute state and costs
+    let mut route: Vec<EdgeTraversal> = Vec::with_capacity(edge_ids.len());
+    let mut tree: HashMap<VertexId, SearchTreeBranch> = HashMap::new();
+    let mut state = si.state_model.initial_state()?;
+    let mut prev_edge_id: Option<EdgeId> = None;
+    for edge_id in edge_ids {
+        let edge = si.directed_graph.get_edge(edge_id)?;
+        let prev_edge = match prev_edge_id {
+            Some(prev) => Some(si.directed_graph.get_edge(prev)?),
+            None => None,
+        };
+        if !si
+            .frontier_model
+            .valid_frontier(edge, &state, prev_edge, &si.state_model)?
+        {
+            return Err(SearchError::InvalidQuery(format!(
+                "contraction hierarchy route traverses edge {} which the frontier model restricts for this query",
+                edge_id
+            )));
+        }
+        let et = EdgeTraversal::forward_traversal(edge_id, prev_edge_id, &state, si)?;
+        tree.insert(
+            edge.dst_vertex_id,
+            SearchTreeBranch {
+                terminal_vertex: edge.src_vertex_id,
+                edge_traversal: et.clone(),
+            },
+        );
+        state = et.result_state.clone();
+        prev_edge_id = Some(edge_id);
+        route.push(et);
+    }
+
+    metrics.route_runtime = route_start.elapsed();
+
+    Ok(SearchAlgorithmResult {
+        trees: vec![tree],
+        routes: vec![route],
+        iterations,
+        metrics,
+    })
+}
+
+fn peek_cost(queue: &InternalPriorityQueue<VertexId, ReverseCost>) -> Option<Cost> {
+    queue.peek().map(|(_, cost)| cost.0)
+}
+
+/// walks the labels of one side of the search from the meeting vertex back to
+/// its root, returning the hierarchy edges in the order they were visited.
+fn backtrack_labels(
+    meeting_vertex: VertexId,
+    labels: &HashMap<VertexId, ChLabel>,
+    hierarchy: &ContractionHierarchy,
+    forward: bool,
+) -> Result<Vec<usize>, SearchError> {
+    let mut result = vec![];
+    let mut current = meeting_vertex;
+    loop {
+        let (_, edge_idx_opt) = labels
+            .get(&current)
+            .ok_or(SearchError::VertexMissingFromSearchTree(current))?;
+        match edge_idx_opt {
+            None => return Ok(result),
+            Some(edge_idx) => {
+                if result.len() > hierarchy.edges.len() {
+                    return Err(SearchError::InternalSearchError(String::from(
+                        "loop found while backtracking contraction hierarchy search",
+                    )));
+                }
+                let edge = &hierarchy.edges[*edge_idx];
+                current = if forward {
+                    edge.src_vertex_id
+                } else {
+                    edge.dst_vertex_id
+                };
+                result.push(*edge_idx);
+            }
+        }
+    }
+}
+
+/// computes a static cost for every edge in the graph by traversing each edge
+/// from the initial search state. this can be used to build a [`ContractionHierarchy`]
+/// which agrees with the configured traversal and cost models when those models
+/// do not depend on the search state.
+pub fn static_edge_costs(si: &SearchInstance) -> Result<Vec<Cost>, SearchError> {
+    let initial_state = si.state_model.initial_state()?;
+    si.directed_graph
+        .edge_ids()
+        .map(|edge_id| {
+            EdgeTraversal::forward_traversal(edge_id, None, &initial_state, si)
+                .map(|et| et.total_cost())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::a_star::a_star_algorithm::run_a_star;
+    use crate::algorithm::search::backtrack::vertex_oriented_route;
+    use crate::algorithm::search::ch::contraction_hierarchy::DEFAULT_WITNESS_SEARCH_LIMIT;
+    use crate::algorithm::search::direction::Direction;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_from_edges};
+    use crate::model::property::edge::Edge;
+    use crate::model::property::vertex::Vertex;
+    use crate::model::road_network::graph::Graph;
+    use crate::model::termination::termination_model::TerminationModel;
+    use crate::model::unit::DistanceUnit;
+    use std::sync::Arc;
+
+    /// a 3x3 grid of vertices with bidirectional edges of varying distance
+    fn build_mock_graph() -> Graph {
+        let vertices = (0..9)
+            .map(|i| Vertex::new(i, (i % 3) as f32, (i / 3) as f32))
+            .collect::<Vec<_>>();
+        let pairs = [
+            (0, 1, 4.0),
+            (1, 2, 1.0),
+            (3, 4, 2.0),
+            (4, 5, 7.0),
+            (6, 7, 1.0),
+            (7, 8, 3.0),
+            (0, 3, 1.0),
+            (3, 6, 5.0),
+            (1, 4, 1.0),
+            (4, 7, 2.0),
+            (2, 5, 6.0),
+            (5, 8, 1.0),
+        ];
+        let mut edges = vec![];
+        for (src, dst, distance) in pairs {
+            edges.push(Edge::new(edges.len(), src, dst, distance));
+            edges.push(Edge::new(edges.len(), dst, src, distance));
+        }
+        graph_from_edges(vertices, edges)
+    }
+
+    fn build_search_instance() -> SearchInstance {
+        SearchInstance {
+            termination_model: Arc::new(TerminationModel::IterationsLimit { limit: 1000 }),
+            ..distance_search_instance(build_mock_graph(), DistanceUnit::Meters)
+        }
+    }
+
+    fn route_cost(route: &[EdgeTraversal]) -> Cost {
+        route.iter().map(|et| et.total_cost()).sum()
+    }
+
+    #[test]
+    fn test_ch_matches_dijkstra() {
+        let si = build_search_instance();
+        let weights = static_edge_costs(&si).unwrap();
+        let hierarchy =
+            ContractionHierarchy::new(&si.directed_graph, &weights, DEFAULT_WITNESS_SEARCH_LIMIT)
+                .unwrap();
+        for src in 0..9 {
+            for dst in 0..9 {
+                if src == dst {
+                    continue;
+                }
+                let (o, d) = (VertexId(src), VertexId(dst));
+                let ch_result = run_ch(o, d, &hierarchy, &si).unwrap();
+                let tree = run_a_star(o, Some(d), &Direction::Forward, Some(Cost::ZERO), &si)
+                    .unwrap()
+                    .tree;
+                let expected = vertex_oriented_route(o, d, &tree).unwrap();
+                let ch_route = &ch_result.routes[0];
+                assert_eq!(
+                    route_cost(ch_route),
+                    route_cost(&expected),
+                    "cost mismatch for {} -> {}",
+                    src,
+                    dst
+                );
+                let first = si.directed_graph.get_edge(ch_route[0].edge_id).unwrap();
+                let last = si
+                    .directed_graph
+                    .get_edge(ch_route[ch_route.len() - 1].edge_id)
+                    .unwrap();
+                assert_eq!(first.src_vertex_id, o);
+                assert_eq!(last.dst_vertex_id, d);
+                assert!(ch_result.metrics.heap_pops > 0);
+                assert!(ch_result.metrics.heap_pushes >= ch_result.metrics.heap_pops);
+                assert!(ch_result.metrics.max_frontier_size >= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ch_file_round_trip() {
+        let si = build_search_instance();
+        let weights = static_edge_costs(&si).unwrap();
+        let hierarchy =
+            ContractionHierarchy::new(&si.directed_graph, &weights, DEFAULT_WITNESS_SEARCH_LIMIT)
+                .unwrap();
+        let filepath = std::env::temp_dir().join("routee_compass_ch_round_trip.json.gz");
+        hierarchy.to_file(&filepath).unwrap();
+        let loaded = ContractionHierarchy::from_file(&filepath).unwrap();
+        let _ = std::fs::remove_file(&filepath);
+        assert_eq!(loaded.rank, hierarchy.rank);
+        assert_eq!(loaded.edges.len(), hierarchy.edges.len());
+        let expected = run_ch(VertexId(0), VertexId(8), &hierarchy, &si).unwrap();
+        let result = run_ch(VertexId(0), VertexId(8), &loaded, &si).unwrap();
+        assert_eq!(
+            route_cost(&result.routes[0]),
+            route_cost(&expected.routes[0])
+        );
+    }
+}
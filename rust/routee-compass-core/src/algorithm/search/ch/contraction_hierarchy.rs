@@ -0,0 +1,397 @@
+use super::ch_edge::{ChEdge, ChEdgeType};
+use crate::algorithm::search::search_error::SearchError;
+use crate::model::road_network::{edge_id::EdgeId, graph::Graph, vertex_id::VertexId};
+use crate::model::unit::Cost;
+use crate::util::fs::fs_utils;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use priority_queue::PriorityQueue;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// default number of vertices a witness search may settle before it gives up
+/// and the contraction step falls back to inserting a shortcut.
+pub const DEFAULT_WITNESS_SEARCH_LIMIT: usize = 500;
+
+/// a road network graph contracted into shortcut-augmented form. every vertex
+/// receives a rank (the order in which it was contracted). queries run a
+/// bidirectional search which only relaxes edges that lead toward higher-ranked
+/// vertices, which settles a small fraction of the graph compared to A*.
+///
+/// the hierarchy is only valid for the static edge weights it was built with.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractionHierarchy {
+    /// contraction order of each vertex, indexed by VertexId
+    pub rank: Vec<usize>,
+    /// all hierarchy edges, including original edges replaced by shortcuts
+    pub edges: Vec<ChEdge>,
+    /// for each vertex, indices of outgoing edges leading to higher-ranked vertices
+    pub upward: Vec<Vec<usize>>,
+    /// for each vertex, indices of incoming edges arriving from higher-ranked vertices
+    pub downward: Vec<Vec<usize>>,
+    /// number of edges in the source graph, used to validate against the loaded graph
+    pub n_graph_edges: usize,
+    /// the configuration of the models which produced the edge weights, used to
+    /// validate against the configuration of the app which loads the hierarchy
+    #[serde(default)]
+    pub cost_configuration: Option<serde_json::Value>,
+    /// location this hierarchy was read from, if any
+    #[serde(skip)]
+    pub filepath: Option<PathBuf>,
+}
+
+/// working adjacency used while contracting the graph. maps neighbor vertex
+/// index to the index of the best hierarchy edge connecting the pair.
+struct ContractionState {
+    edges: Vec<ChEdge>,
+    out_adj: Vec<HashMap<usize, usize>>,
+    in_adj: Vec<HashMap<usize, usize>>,
+    contracted: Vec<bool>,
+}
+
+/// a shortcut discovered while contracting a vertex: (src, dst, first, second, weight)
+type Shortcut = (usize, usize, usize, usize, Cost);
+
+impl ContractionHierarchy {
+    /// contracts a graph into a hierarchy using the provided static edge weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - the road network to contract
+    /// * `edge_weights` - cost of traversing each edge, indexed by EdgeId
+    /// * `witness_search_limit` - max vertices settled during each witness search
+    ///
+    /// # Returns
+    ///
+    /// The contracted hierarchy, or an error if the weights do not match the graph
+    pub fn new(
+        graph: &Graph,
+        edge_weights: &[Cost],
+        witness_search_limit: usize,
+    ) -> Result<ContractionHierarchy, SearchError> {
+        if edge_weights.len() != graph.n_edges() {
+            return Err(SearchError::BuildError(format!(
+                "contraction hierarchy expected {} edge weights, found {}",
+                graph.n_edges(),
+                edge_weights.len()
+            )));
+        }
+        let n = graph.n_vertices();
+        let mut state = ContractionState {
+            edges: vec![],
+            out_adj: vec![HashMap::new(); n],
+            in_adj: vec![HashMap::new(); n],
+            contracted: vec![false; n],
+        };
+        for edge in graph.edges.iter() {
+            let weight = edge_weights[edge.edge_id.0];
+            if weight < Cost::ZERO {
+                return Err(SearchError::BuildError(format!(
+                    "contraction hierarchy requires non-negative edge weights, edge {} has weight {}",
+                    edge.edge_id, weight
+                )));
+            }
+            if edge.src_vertex_id == edge.dst_vertex_id {
+                continue;
+            }
+            state.add_edge(ChEdge {
+                src_vertex_id: edge.src_vertex_id,
+                dst_vertex_id: edge.dst_vertex_id,
+                weight,
+                edge_type: ChEdgeType::Original {
+                    edge_id: edge.edge_id,
+                },
+            });
+        }
+
+        // order vertices by edge difference, lazily updating priorities as we contract
+        let mut contracted_neighbors = vec![0i64; n];
+        let mut queue: PriorityQueue<usize, Reverse<i64>> = PriorityQueue::new();
+        for v in 0..n {
+            let shortcuts = state.find_shortcuts(v, witness_search_limit);
+            queue.push(v, Reverse(state.priority(v, shortcuts.len(), 0)));
+        }
+
+        let mut rank = vec![0; n];
+        let mut order = 0;
+        while let Some((v, _)) = queue.pop() {
+            let shortcuts = state.find_shortcuts(v, witness_search_limit);
+            let priority = state.priority(v, shortcuts.len(), contracted_neighbors[v]);
+            if let Some((_, Reverse(next_priority))) = queue.peek() {
+                if priority > *next_priority {
+                    queue.push(v, Reverse(priority));
+                    continue;
+                }
+            }
+
+            for (src, dst, first, second, weight) in shortcuts {
+                state.add_edge(ChEdge {
+                    src_vertex_id: VertexId(src),
+                    dst_vertex_id: VertexId(dst),
+                    weight,
+                    edge_type: ChEdgeType::Shortcut { first, second },
+                });
+            }
+            state.contracted[v] = true;
+            rank[v] = order;
+            order += 1;
+            let neighbors = state.out_adj[v].keys().chain(state.in_adj[v].keys());
+            for u in neighbors {
+                if !state.contracted[*u] {
+                    contracted_neighbors[*u] += 1;
+                }
+            }
+        }
+
+        let mut upward: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut downward: Vec<Vec<usize>> = vec![vec![]; n];
+        for (src, adj) in state.out_adj.iter().enumerate() {
+            for (dst, edge_idx) in adj.iter() {
+                if rank[*dst] > rank[src] {
+                    upward[src].push(*edge_idx);
+                } else {
+                    downward[*dst].push(*edge_idx);
+                }
+            }
+        }
+        upward.iter_mut().for_each(|e| e.sort_unstable());
+        downward.iter_mut().for_each(|e| e.sort_unstable());
+
+        Ok(ContractionHierarchy {
+            rank,
+            edges: state.edges,
+            upward,
+            downward,
+            n_graph_edges: graph.n_edges(),
+            cost_configuration: None,
+            filepath: None,
+        })
+    }
+
+    /// records the configuration of the models which produced the edge weights
+    pub fn with_cost_configuration(mut self, configuration: serde_json::Value) -> Self {
+        self.cost_configuration = Some(configuration);
+        self
+    }
+
+    /// reads a hierarchy previously written by [`ContractionHierarchy::to_file`].
+    /// gzip-compressed files are detected and decompressed automatically.
+    pub fn from_file<P: AsRef<Path>>(filepath: P) -> Result<ContractionHierarchy, SearchError> {
+        let path = filepath.as_ref();
+        let file = File::open(path).map_err(|e| {
+            SearchError::BuildError(format!(
+                "failure reading contraction hierarchy file {:?}: {}",
+                path, e
+            ))
+        })?;
+        let reader: Box<dyn Read> = if fs_utils::is_gzip(path) {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(BufReader::new(file))
+        };
+        let mut hierarchy: ContractionHierarchy = serde_json::from_reader(reader).map_err(|e| {
+            SearchError::BuildError(format!(
+                "failure decoding contraction hierarchy file {:?}: {}",
+                path, e
+            ))
+        })?;
+        hierarchy.filepath = Some(path.to_path_buf());
+        Ok(hierarchy)
+    }
+
+    /// writes this hierarchy to disk as JSON. if the file name ends with ".gz"
+    /// the output is gzip-compressed.
+    pub fn to_file<P: AsRef<Path>>(&self, filepath: P) -> Result<(), SearchError> {
+        let path = filepath.as_ref();
+        let to_error = |e: String| {
+            SearchError::BuildError(format!(
+                "failure writing contraction hierarchy file {:?}: {}",
+                path, e
+            ))
+        };
+        let file = File::create(path).map_err(|e| to_error(e.to_string()))?;
+        let gzip = path.extension().map(|ext| ext == "gz").unwrap_or(false);
+        let mut writer: Box<dyn Write> = if gzip {
+            Box::new(GzEncoder::new(BufWriter::new(file), Compression::default()))
+        } else {
+            Box::new(BufWriter::new(file))
+        };
+        serde_json::to_writer(&mut writer, self).map_err(|e| to_error(e.to_string()))?;
+        writer.flush().map_err(|e| to_error(e.to_string()))?;
+        Ok(())
+    }
+
+    /// number of vertices in the hierarchy
+    pub fn n_vertices(&self) -> usize {
+        self.rank.len()
+    }
+
+    /// confirms this hierarchy was built from a graph with the same dimensions
+    pub fn validate(&self, graph: &Graph) -> Result<(), SearchError> {
+        if self.n_vertices() != graph.n_vertices() || self.n_graph_edges != graph.n_edges() {
+            Err(SearchError::BuildError(format!(
+                "contraction hierarchy built for graph with {} vertices and {} edges but loaded graph has {} vertices and {} edges",
+                self.n_vertices(),
+                self.n_graph_edges,
+                graph.n_vertices(),
+                graph.n_edges()
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// expands a hierarchy edge into the sequence of source graph edges it represents.
+    pub fn unpack_edge(&self, edge_idx: usize) -> Result<Vec<EdgeId>, SearchError> {
+        let mut result = vec![];
+        let mut stack = vec![edge_idx];
+        while let Some(idx) = stack.pop() {
+            let edge = self.edges.get(idx).ok_or_else(|| {
+                SearchError::InternalSearchError(format!(
+                    "contraction hierarchy edge {} not found",
+                    idx
+                ))
+            })?;
+            match edge.edge_type {
+                ChEdgeType::Original { edge_id } => result.push(edge_id),
+                ChEdgeType::Shortcut { first, second } => {
+                    // pushed in reverse so that the first edge is expanded first
+                    stack.push(second);
+                    stack.push(first);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl ContractionState {
+    /// adds an edge unless an edge of lower or equal weight already connects the pair
+    fn add_edge(&mut self, edge: ChEdge) {
+        let (src, dst) = (edge.src_vertex_id.0, edge.dst_vertex_id.0);
+        if let Some(existing) = self.out_adj[src].get(&dst) {
+            if self.edges[*existing].weight <= edge.weight {
+                return;
+            }
+        }
+        let idx = self.edges.len();
+        self.edges.push(edge);
+        self.out_adj[src].insert(dst, idx);
+        self.in_adj[dst].insert(src, idx);
+    }
+
+    /// edge difference heuristic used to order contraction
+    fn priority(&self, v: usize, n_shortcuts: usize, contracted_neighbors: i64) -> i64 {
+        let removed = self.active_neighbors(&self.in_adj[v]).count()
+            + self.active_neighbors(&self.out_adj[v]).count();
+        n_shortcuts as i64 - removed as i64 + contracted_neighbors
+    }
+
+    fn active_neighbors<'a>(
+        &'a self,
+        adj: &'a HashMap<usize, usize>,
+    ) -> impl Iterator<Item = (&'a usize, &'a usize)> + 'a {
+        adj.iter().filter(|(u, _)| !self.contracted[**u])
+    }
+
+    /// finds the shortcuts required to preserve shortest path costs if
+    /// vertex `v` were removed from the remaining graph.
+    fn find_shortcuts(&self, v: usize, witness_search_limit: usize) -> Vec<Shortcut> {
+        let mut shortcuts = vec![];
+        let outgoing: Vec<(usize, usize)> = self
+            .active_neighbors(&self.out_adj[v])
+            .map(|(x, e)| (*x, *e))
+            .collect();
+        for (u, e_in) in self.active_neighbors(&self.in_adj[v]) {
+            let w_in = self.edges[*e_in].weight;
+            let max_weight = outgoing
+                .iter()
+                .filter(|(x, _)| x != u)
+                .map(|(_, e_out)| w_in + self.edges[*e_out].weight)
+                .max();
+            let max_weight = match max_weight {
+                Some(w) => w,
+                None => continue,
+            };
+            let witness = self.witness_search(*u, v, max_weight, witness_search_limit);
+            for (x, e_out) in outgoing.iter() {
+                if x == u {
+                    continue;
+                }
+                let via_weight = w_in + self.edges[*e_out].weight;
+                let has_witness = witness.get(x).map(|w| *w <= via_weight).unwrap_or(false);
+                if !has_witness {
+                    shortcuts.push((*u, *x, *e_in, *e_out, via_weight));
+                }
+            }
+        }
+        shortcuts
+    }
+
+    /// bounded dijkstra search from `source` over the remaining graph which avoids `ignore`.
+    fn witness_search(
+        &self,
+        source: usize,
+        ignore: usize,
+        max_weight: Cost,
+        limit: usize,
+    ) -> HashMap<usize, Cost> {
+        let mut dist: HashMap<usize, Cost> = HashMap::from([(source, Cost::ZERO)]);
+        let mut heap = BinaryHeap::from([Reverse((Cost::ZERO, source))]);
+        let mut settled = 0;
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if d > max_weight || settled >= limit {
+                break;
+            }
+            if dist.get(&u).map(|best| d > *best).unwrap_or(false) {
+                continue;
+            }
+            settled += 1;
+            for (x, e) in self.active_neighbors(&self.out_adj[u]) {
+                if *x == ignore {
+                    continue;
+                }
+                let next = d + self.edges[*e].weight;
+                if dist.get(x).map(|best| next < *best).unwrap_or(true) {
+                    dist.insert(*x, next);
+                    heap.push(Reverse((next, *x)));
+                }
+            }
+        }
+        dist
+    }
+}
+
+/// serde helpers for reading a hierarchy from the file path found in a configuration.
+/// serializing writes back the path the hierarchy was loaded from.
+pub mod hierarchy_file {
+    use super::ContractionHierarchy;
+    use serde::{de::Error as DeError, ser::Error as SerError};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{path::PathBuf, sync::Arc};
+
+    pub fn serialize<S>(hierarchy: &Arc<ContractionHierarchy>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &hierarchy.filepath {
+            Some(path) => path.serialize(s),
+            None => Err(S::Error::custom(
+                "contraction hierarchy was not loaded from a file",
+            )),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Arc<ContractionHierarchy>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = PathBuf::deserialize(d)?;
+        ContractionHierarchy::from_file(path)
+            .map(Arc::new)
+            .map_err(D::Error::custom)
+    }
+}
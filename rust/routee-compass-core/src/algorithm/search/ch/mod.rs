@@ -0,0 +1,3 @@
+pub mod ch_edge;
+pub mod ch_search;
+pub mod contraction_hierarchy;
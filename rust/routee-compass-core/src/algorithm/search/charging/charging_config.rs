@@ -0,0 +1,78 @@
+use super::charging_network::{ChargerRecord, ChargingNetwork};
+use crate::algorithm::search::search_error::SearchError;
+use crate::model::unit::{Energy, EnergyUnit};
+use crate::util::fs::read_utils;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// configuration for inserting charging stops into routes for battery electric
+/// vehicles. found in the `[algorithm.charging]` section of a configuration file.
+/// the charger file is a CSV with a header and columns `vertex_id,power_kw`.
+///
+/// # Example
+///
+/// ```toml
+/// [algorithm.charging]
+/// charger_input_file = "chargers.csv"
+/// battery_capacity = 60
+/// battery_capacity_unit = "kilowatt_hours"
+/// target_soc_percent = 80
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChargingConfig {
+    pub charger_input_file: PathBuf,
+    pub battery_capacity: f64,
+    #[serde(default = "default_battery_capacity_unit")]
+    pub battery_capacity_unit: EnergyUnit,
+    #[serde(default = "default_target_soc_percent")]
+    pub target_soc_percent: f64,
+    #[serde(default = "default_soc_feature")]
+    pub soc_feature: String,
+    #[serde(default = "default_time_feature")]
+    pub time_feature: String,
+}
+
+fn default_battery_capacity_unit() -> EnergyUnit {
+    EnergyUnit::KilowattHours
+}
+
+fn default_target_soc_percent() -> f64 {
+    80.0
+}
+
+fn default_soc_feature() -> String {
+    String::from("battery_state")
+}
+
+fn default_time_feature() -> String {
+    String::from("time")
+}
+
+impl ChargingConfig {
+    /// loads the charger dataset and builds the charging network
+    pub fn build(&self) -> Result<ChargingNetwork, SearchError> {
+        let chargers: Box<[ChargerRecord]> =
+            read_utils::from_csv(&self.charger_input_file, true, None).map_err(|e| {
+                SearchError::BuildError(format!(
+                    "failure reading charger file {:?}: {}",
+                    self.charger_input_file, e
+                ))
+            })?;
+        let battery_capacity_kwh = self.battery_capacity_unit.convert(
+            &Energy::new(self.battery_capacity),
+            &EnergyUnit::KilowattHours,
+        );
+        let network = ChargingNetwork::new(
+            &chargers,
+            battery_capacity_kwh,
+            self.target_soc_percent,
+            self.soc_feature.clone(),
+            self.time_feature.clone(),
+        )?;
+        log::info!(
+            "loaded charging network with {} chargers",
+            network.chargers.len()
+        );
+        Ok(network)
+    }
+}
@@ -0,0 +1,138 @@
+use super::charging_stop::ChargingStop;
+use crate::algorithm::search::search_error::SearchError;
+use crate::model::road_network::vertex_id::VertexId;
+use crate::model::state::state_model::StateModel;
+use crate::model::traversal::state::state_variable::StateVar;
+use crate::model::unit::{as_f64::AsF64, Energy, Time, TimeUnit};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// a row of the charger dataset, describing a charger located at a vertex
+#[derive(Deserialize, Clone, Debug)]
+pub struct ChargerRecord {
+    pub vertex_id: VertexId,
+    pub power_kw: f64,
+}
+
+/// the set of chargers available for inserting charging stops into a route,
+/// along with the battery parameters used to compute charging times.
+pub struct ChargingNetwork {
+    pub chargers: HashMap<VertexId, f64>,
+    pub battery_capacity_kwh: Energy,
+    pub target_soc_percent: f64,
+    pub soc_feature: String,
+    pub time_feature: String,
+}
+
+impl ChargingNetwork {
+    pub fn new(
+        chargers: &[ChargerRecord],
+        battery_capacity_kwh: Energy,
+        target_soc_percent: f64,
+        soc_feature: String,
+        time_feature: String,
+    ) -> Result<ChargingNetwork, SearchError> {
+        if !(0.0..=100.0).contains(&target_soc_percent) {
+            return Err(SearchError::BuildError(format!(
+                "charging target soc percent must be in range [0, 100], found {}",
+                target_soc_percent
+            )));
+        }
+        if battery_capacity_kwh <= Energy::ZERO {
+            return Err(SearchError::BuildError(format!(
+                "charging battery capacity must be positive, found {}",
+                battery_capacity_kwh
+            )));
+        }
+        let mut charger_lookup = HashMap::new();
+        for charger in chargers.iter() {
+            if charger.power_kw <= 0.0 {
+                return Err(SearchError::BuildError(format!(
+                    "charger at vertex {} has non-positive power {} kw",
+                    charger.vertex_id, charger.power_kw
+                )));
+            }
+            // when a vertex has more than one charger, the fastest is used
+            let power = charger_lookup.entry(charger.vertex_id).or_insert(0.0);
+            *power = charger.power_kw.max(*power);
+        }
+        Ok(ChargingNetwork {
+            chargers: charger_lookup,
+            battery_capacity_kwh,
+            target_soc_percent,
+            soc_feature,
+            time_feature,
+        })
+    }
+
+    /// true if the vehicle for this search has a battery state of charge to manage
+    pub fn applies_to(&self, state_model: &StateModel) -> bool {
+        state_model.contains_key(&self.soc_feature)
+    }
+
+    /// reads the battery state of charge from a state vector
+    pub fn get_soc_percent(
+        &self,
+        state: &[StateVar],
+        state_model: &StateModel,
+    ) -> Result<f64, SearchError> {
+        let soc = state_model.get_custom_f64(state, &self.soc_feature)?;
+        Ok(soc)
+    }
+
+    /// charges the battery to the target state of charge at a vertex, adding
+    /// the charging duration to the time feature of the state.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_id` - the vertex where the vehicle would charge
+    /// * `state` - the state on arrival at the vertex
+    /// * `state_model` - provides API for interacting with state
+    ///
+    /// # Returns
+    ///
+    /// the state after charging along with a description of the stop, or None if
+    /// there is no charger at this vertex or the battery is already charged.
+    pub fn charge(
+        &self,
+        vertex_id: VertexId,
+        state: &[StateVar],
+        state_model: &StateModel,
+    ) -> Result<Option<(Vec<StateVar>, ChargingStop)>, SearchError> {
+        let power_kw = match self.chargers.get(&vertex_id) {
+            None => return Ok(None),
+            Some(power_kw) => *power_kw,
+        };
+        let arrival_soc = self.get_soc_percent(state, state_model)?;
+        if arrival_soc >= self.target_soc_percent {
+            return Ok(None);
+        }
+
+        let energy_kwh =
+            self.battery_capacity_kwh.as_f64() * (self.target_soc_percent - arrival_soc) / 100.0;
+        let charge_time = Time::new(energy_kwh / power_kw);
+        let mut charged_state = state.to_vec();
+        state_model.set_custom_f64(
+            &mut charged_state,
+            &self.soc_feature,
+            &self.target_soc_percent,
+        )?;
+        state_model.add_time(
+            &mut charged_state,
+            &self.time_feature,
+            &charge_time,
+            &TimeUnit::Hours,
+        )?;
+
+        let stop = ChargingStop {
+            vertex_id,
+            power_kw,
+            arrival_soc_percent: arrival_soc,
+            departure_soc_percent: self.target_soc_percent,
+            charge_time_minutes: TimeUnit::Hours
+                .convert(&charge_time, &TimeUnit::Minutes)
+                .as_f64(),
+        };
+        Ok(Some((charged_state, stop)))
+    }
+}
@@ -0,0 +1,498 @@
+use super::charging_network::ChargingNetwork;
+use super::charging_stop::ChargingStop;
+use crate::algorithm::search::edge_traversal::EdgeTraversal;
+use crate::algorithm::search::search_algorithm_result::SearchAlgorithmResult;
+use crate::algorithm::search::search_error::SearchError;
+use crate::algorithm::search::search_instance::SearchInstance;
+use crate::algorithm::search::search_metrics::SearchMetrics;
+use crate::algorithm::search::terminated_search::TerminatedSearch;
+use crate::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
+use crate::model::traversal::state::state_variable::StateVar;
+use crate::model::unit::Cost;
+use crate::util::instant::Instant;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// a partial path in a charging search, stored in an arena and linked to the
+/// label it was extended from. a label either traverses an edge or charges the
+/// battery at the vertex of the previous label.
+struct Label {
+    vertex_id: VertexId,
+    cost: Cost,
+    soc: f64,
+    stops: usize,
+    state: Vec<StateVar>,
+    last_edge_id: Option<EdgeId>,
+    prev_label: Option<usize>,
+    edge_traversal: Option<EdgeTraversal>,
+    charge: Option<(ChargingStop, Cost)>,
+    dominated: bool,
+}
+
+/// true if `a` is no worse than `b` in cost, state of charge and number of stops
+fn weakly_dominates(a: &Label, b: &Label) -> bool {
+    a.cost <= b.cost && a.soc >= b.soc && a.stops <= b.stops
+}
+
+/// the result of a charging search: the route along with the charging stops
+/// inserted into it, in the order they are visited.
+pub type ChargingSearchResult = (SearchAlgorithmResult, Vec<ChargingStop>);
+
+/// run an energy-constrained search between two vertices for a vehicle with a
+/// battery. edges which deplete the battery are never traversed, and the frontier
+/// model may reject edges which drop the state of charge below some floor. when
+/// the destination cannot be reached on the current charge, the vehicle charges
+/// to a target state of charge at vertices of the charging network, and the
+/// charging time is added to the time feature of the state.
+///
+/// labels track cost, state of charge and number of stops, and each vertex keeps
+/// the set of nondominated labels that reached it. labels are settled in order of
+/// cost, so the first label to reach the target is the least-cost route, using the
+/// fewest charging stops among routes of equal cost.
+///
+/// # Arguments
+///
+/// * `source` - vertex where the search begins
+/// * `target` - vertex where the search ends
+/// * `network` - the chargers and battery parameters
+/// * `si` - the search instance
+///
+/// # Returns
+///
+/// the route to the target along with the charging stops made along the way.
+/// no search tree is returned.
+pub fn run_charging_search(
+    source: VertexId,
+    target: VertexId,
+    network: &ChargingNetwork,
+    si: &SearchInstance,
+) -> Result<ChargingSearchResult, SearchError> {
+    if source == target {
+        return Ok((SearchAlgorithmResult::default(), vec![]));
+    }
+    let initial_state = si.state_model.initial_state()?;
+    search(source, target, initial_state, None, network, si)
+}
+
+/// run an energy-constrained search between two edges. as with other edge-oriented
+/// searches, the source and target edges do not contribute to the route cost.
+pub fn run_charging_search_edge_oriented(
+    source: EdgeId,
+    target: EdgeId,
+    network: &ChargingNetwork,
+    si: &SearchInstance,
+) -> Result<ChargingSearchResult, SearchError> {
+    if source == target {
+        return Ok((SearchAlgorithmResult::default(), vec![]));
+    }
+    let e1_dst = si.directed_graph.dst_vertex_id(source)?;
+    let e2_src = si.directed_graph.src_vertex_id(target)?;
+    let src_et = EdgeTraversal {
+        edge_id: source,
+        access_cost: Cost::ZERO,
+        traversal_cost: Cost::ZERO,
+        result_state: si.state_model.initial_state()?,
+    };
+
+    let (mut result, stops) = if e1_dst == e2_src {
+        let route = vec![src_et.clone()];
+        let result = SearchAlgorithmResult {
+            trees: vec![],
+            routes: vec![route],
+            iterations: 1,
+            metrics: SearchMetrics::default(),
+        };
+        (result, vec![])
+    } else {
+        search(
+            e1_dst,
+            e2_src,
+            src_et.result_state.clone(),
+            Some(src_et),
+            network,
+            si,
+        )?
+    };
+
+    for route in result.routes.iter_mut() {
+        let final_state = route
+            .last()
+            .map(|et| et.result_state.clone())
+            .ok_or_else(|| {
+                SearchError::InternalSearchError(String::from("found empty result route"))
+            })?;
+        route.push(EdgeTraversal {
+            edge_id: target,
+            access_cost: Cost::ZERO,
+            traversal_cost: Cost::ZERO,
+            result_state: final_state,
+        });
+    }
+    Ok((result, stops))
+}
+
+fn search(
+    source: VertexId,
+    target: VertexId,
+    initial_state: Vec<StateVar>,
+    initial_edge: Option<EdgeTraversal>,
+    network: &ChargingNetwork,
+    si: &SearchInstance,
+) -> Result<ChargingSearchResult, SearchError> {
+    let mut labels: Vec<Label> = vec![Label {
+        vertex_id: source,
+        cost: Cost::ZERO,
+        soc: network.get_soc_percent(&initial_state, &si.state_model)?,
+        stops: 0,
+        state: initial_state,
+        last_edge_id: initial_edge.as_ref().map(|et| et.edge_id),
+        prev_label: None,
+        edge_traversal: initial_edge,
+        charge: None,
+        dominated: false,
+    }];
+    let mut vertex_labels: HashMap<VertexId, Vec<usize>> = HashMap::from([(source, vec![0])]);
+    let mut queue: BinaryHeap<Reverse<(Cost, usize, usize)>> =
+        BinaryHeap::from([Reverse((Cost::ZERO, 0, 0))]);
+
+    let start_time = Instant::now();
+    let mut iterations = 0;
+    let mut metrics = SearchMetrics {
+        heap_pushes: 1,
+        ..Default::default()
+    };
+
+    loop {
+        metrics.observe_frontier(queue.len());
+        let Some(Reverse((_, _, label_idx))) = queue.pop() else {
+            break;
+        };
+        metrics.heap_pops += 1;
+        si.cancellation.check()?;
+        si.termination_model
+            .test(&start_time, labels.len(), iterations)
+            .map_err(|e| {
+                TerminatedSearch::search_error(
+                    e,
+                    Some(labels[label_idx].vertex_id),
+                    labels.len(),
+                    iterations,
+                    &start_time,
+                )
+            })?;
+        if labels[label_idx].dominated {
+            continue;
+        }
+        iterations += 1;
+        let vertex_id = labels[label_idx].vertex_id;
+        if vertex_id == target {
+            let route_start = Instant::now();
+            let (route, stops) = label_route(label_idx, &labels);
+            metrics.route_runtime = route_start.elapsed();
+            log::debug!(
+                "charging search iterations: {}, labels: {}, stops: {}",
+                iterations,
+                labels.len(),
+                stops.len()
+            );
+            let result = SearchAlgorithmResult {
+                trees: vec![],
+                routes: vec![route],
+                iterations,
+                metrics,
+            };
+            return Ok((result, stops));
+        }
+
+        let state = labels[label_idx].state.clone();
+        let last_edge_id = labels[label_idx].last_edge_id;
+        let last_edge = match last_edge_id {
+            Some(id) => Some(si.directed_graph.get_edge(id)?),
+            None => None,
+        };
+
+        // branch on charging at this vertex
+        if let Some((charged_state, stop)) = network.charge(vertex_id, &state, &si.state_model)? {
+            let charge_cost = si.cost_model.cost_estimate(&state, &charged_state)?;
+            let label = Label {
+                vertex_id,
+                cost: labels[label_idx].cost + charge_cost,
+                soc: stop.departure_soc_percent,
+                stops: labels[label_idx].stops + 1,
+                state: charged_state,
+                last_edge_id,
+                prev_label: Some(label_idx),
+                edge_traversal: None,
+                charge: Some((stop, charge_cost)),
+                dominated: false,
+            };
+            insert_label(
+                label,
+                &mut labels,
+                &mut vertex_labels,
+                &mut queue,
+                &mut metrics,
+            );
+        }
+
+        // branch on each edge leaving this vertex
+        for edge_id in si.directed_graph.out_edges_iter(vertex_id)? {
+            let edge = si.directed_graph.get_edge(*edge_id)?;
+            let valid_frontier =
+                si.frontier_model
+                    .valid_frontier(edge, &state, last_edge, &si.state_model)?;
+            if !valid_frontier {
+                continue;
+            }
+            let et = EdgeTraversal::forward_traversal(*edge_id, last_edge_id, &state, si)?;
+            let valid_traversal =
+                si.frontier_model
+                    .valid_traversal(edge, &et.result_state, &si.state_model)?;
+            let soc = network.get_soc_percent(&et.result_state, &si.state_model)?;
+            if !valid_traversal || soc <= 0.0 {
+                continue;
+            }
+            let label = Label {
+                vertex_id: edge.dst_vertex_id,
+                cost: labels[label_idx].cost + et.total_cost(),
+                soc,
+                stops: labels[label_idx].stops,
+                state: et.result_state.clone(),
+                last_edge_id: Some(*edge_id),
+                prev_label: Some(label_idx),
+                edge_traversal: Some(et),
+                charge: None,
+                dominated: false,
+            };
+            insert_label(
+                label,
+                &mut labels,
+                &mut vertex_labels,
+                &mut queue,
+                &mut metrics,
+            );
+        }
+    }
+
+    Err(SearchError::NoPathExists(source, target))
+}
+
+/// adds a label to the search if no label at the same vertex dominates it,
+/// marking any labels that it dominates.
+fn insert_label(
+    label: Label,
+    labels: &mut Vec<Label>,
+    vertex_labels: &mut HashMap<VertexId, Vec<usize>>,
+    queue: &mut BinaryHeap<Reverse<(Cost, usize, usize)>>,
+    metrics: &mut SearchMetrics,
+) {
+    let existing = vertex_labels.entry(label.vertex_id).or_default();
+    if existing
+        .iter()
+        .any(|l| weakly_dominates(&labels[*l], &label))
+    {
+        return;
+    }
+    let mut kept = vec![];
+    for l in existing.drain(..) {
+        if weakly_dominates(&label, &labels[l]) {
+            labels[l].dominated = true;
+        } else {
+            kept.push(l);
+        }
+    }
+    let label_idx = labels.len();
+    kept.push(label_idx);
+    *existing = kept;
+    queue.push(Reverse((label.cost, label.stops, label_idx)));
+    metrics.heap_pushes += 1;
+    labels.push(label);
+}
+
+/// follows the chain of labels back to the source to build a route. the cost
+/// of each charging stop is added to the access cost of the edge leaving it.
+fn label_route(label_idx: usize, labels: &[Label]) -> (Vec<EdgeTraversal>, Vec<ChargingStop>) {
+    let mut route: Vec<EdgeTraversal> = vec![];
+    let mut stops = vec![];
+    let mut current = Some(label_idx);
+    while let Some(idx) = current {
+        let label = &labels[idx];
+        if let Some(et) = &label.edge_traversal {
+            route.push(et.clone());
+        }
+        if let Some((stop, charge_cost)) = &label.charge {
+            if let Some(next_et) = route.last_mut() {
+                next_et.access_cost = next_et.access_cost + *charge_cost;
+            }
+            stops.push(stop.clone());
+        }
+        current = label.prev_label;
+    }
+    route.reverse();
+    stops.reverse();
+    (route, stops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::cancellation_token::CancellationToken;
+    use crate::algorithm::search::charging::charging_network::ChargerRecord;
+    use crate::algorithm::search::test_fixtures::graph_at_origin;
+    use crate::model::access::default::no_access_model::NoAccessModel;
+    use crate::model::cost::cost_aggregation::CostAggregation;
+    use crate::model::cost::cost_model::CostModel;
+    use crate::model::cost::vehicle::vehicle_cost_rate::VehicleCostRate;
+    use crate::model::frontier::default::no_restriction::NoRestriction;
+    use crate::model::property::{edge::Edge, vertex::Vertex};
+    use crate::model::state::custom_feature_format::CustomFeatureFormat;
+    use crate::model::state::state_feature::StateFeature;
+    use crate::model::state::state_model::StateModel;
+    use crate::model::termination::termination_model::TerminationModel;
+    use crate::model::traversal::traversal_model::TraversalModel;
+    use crate::model::traversal::traversal_model_error::TraversalModelError;
+    use crate::model::unit::{as_f64::AsF64, Energy, Time, TimeUnit};
+    use std::sync::Arc;
+
+    /// drives at 60 kph and uses 1% of the battery per kilometer
+    struct MockBatteryModel {}
+
+    impl TraversalModel for MockBatteryModel {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            vec![]
+        }
+
+        fn traverse_edge(
+            &self,
+            trajectory: (&Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVar>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            let (_, edge, _) = trajectory;
+            let km = edge.distance.as_f64() / 1000.0;
+            let time = Time::new(km);
+            state_model.add_time(state, &"time".into(), &time, &TimeUnit::Minutes)?;
+            let soc = state_model.get_custom_f64(state, &"battery_state".into())?;
+            state_model.set_custom_f64(state, &"battery_state".into(), &(soc - km))?;
+            Ok(())
+        }
+
+        fn estimate_traversal(
+            &self,
+            _od: (&Vertex, &Vertex),
+            _state: &mut Vec<StateVar>,
+            _state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            Ok(())
+        }
+    }
+
+    /// a chain (0) -> (1) -> (2) -> (3) of 30km edges, starting at 50% charge
+    fn build_search_instance() -> SearchInstance {
+        let edges = (0..3)
+            .map(|i| Edge::new(i, i, i + 1, 30000.0))
+            .collect::<Vec<_>>();
+        let graph = graph_at_origin(4, edges);
+        let state_model = Arc::new(
+            StateModel::empty()
+                .extend(vec![
+                    (
+                        String::from("time"),
+                        StateFeature::Time {
+                            time_unit: TimeUnit::Minutes,
+                            initial: Time::new(0.0),
+                        },
+                    ),
+                    (
+                        String::from("battery_state"),
+                        StateFeature::Custom {
+                            r#type: String::from("soc"),
+                            unit: String::from("percent"),
+                            format: CustomFeatureFormat::FloatingPoint {
+                                initial: 50.0.into(),
+                            },
+                        },
+                    ),
+                ])
+                .unwrap(),
+        );
+        let cost_model = CostModel::new(
+            Arc::new(HashMap::from([(String::from("time"), 1.0)])),
+            Arc::new(HashMap::from([(
+                String::from("time"),
+                VehicleCostRate::Raw,
+            )])),
+            Arc::new(HashMap::new()),
+            CostAggregation::Sum,
+            state_model.clone(),
+        )
+        .unwrap();
+        SearchInstance {
+            directed_graph: Arc::new(graph),
+            state_model,
+            traversal_model: Arc::new(MockBatteryModel {}),
+            access_model: Arc::new(NoAccessModel {}),
+            cost_model,
+            frontier_model: Arc::new(NoRestriction {}),
+            termination_model: Arc::new(TerminationModel::IterationsLimit { limit: 100 }),
+            heuristic: None,
+            cancellation: CancellationToken::default(),
+        }
+    }
+
+    /// a 100 kWh battery so that 1% of charge is 1 kWh
+    fn build_network(chargers: Vec<ChargerRecord>) -> ChargingNetwork {
+        ChargingNetwork::new(
+            &chargers,
+            Energy::new(100.0),
+            80.0,
+            String::from("battery_state"),
+            String::from("time"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_inserts_charging_stop() {
+        let si = build_search_instance();
+        let network = build_network(vec![ChargerRecord {
+            vertex_id: VertexId(1),
+            power_kw: 50.0,
+        }]);
+        let (result, stops) = run_charging_search(VertexId(0), VertexId(3), &network, &si).unwrap();
+        let route = &result.routes[0];
+        let edge_ids = route.iter().map(|et| et.edge_id).collect::<Vec<_>>();
+        assert_eq!(edge_ids, vec![EdgeId(0), EdgeId(1), EdgeId(2)]);
+        assert!(result.metrics.heap_pops > 0);
+        assert!(result.metrics.heap_pushes >= result.metrics.heap_pops);
+        assert!(result.metrics.max_frontier_size > 0);
+
+        // arrives at (1) with 20%, charges 60 kWh at 50 kW in 72 minutes
+        assert_eq!(stops.len(), 1);
+        assert_eq!(stops[0].vertex_id, VertexId(1));
+        assert!((stops[0].arrival_soc_percent - 20.0).abs() < 1e-6);
+        assert!((stops[0].charge_time_minutes - 72.0).abs() < 1e-6);
+
+        let final_state = &route[2].result_state;
+        let time = si
+            .state_model
+            .get_time(final_state, &"time".into(), &TimeUnit::Minutes)
+            .unwrap();
+        assert!((time.as_f64() - 162.0).abs() < 1e-6);
+        let soc = si
+            .state_model
+            .get_custom_f64(final_state, &"battery_state".into())
+            .unwrap();
+        assert!((soc - 20.0).abs() < 1e-6);
+        let total_cost = route.iter().map(|et| et.total_cost()).sum::<Cost>();
+        assert!((total_cost.as_f64() - 162.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_no_path_without_chargers() {
+        let si = build_search_instance();
+        let network = build_network(vec![]);
+        let result = run_charging_search(VertexId(0), VertexId(3), &network, &si);
+        assert!(matches!(result, Err(SearchError::NoPathExists(_, _))));
+    }
+}
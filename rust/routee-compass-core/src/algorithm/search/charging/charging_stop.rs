@@ -0,0 +1,13 @@
+use crate::model::road_network::vertex_id::VertexId;
+use allocative::Allocative;
+use serde::{Deserialize, Serialize};
+
+/// a stop inserted into a route in order to recharge the vehicle battery.
+#[derive(Clone, Debug, Serialize, Deserialize, Allocative)]
+pub struct ChargingStop {
+    pub vertex_id: VertexId,
+    pub power_kw: f64,
+    pub arrival_soc_percent: f64,
+    pub departure_soc_percent: f64,
+    pub charge_time_minutes: f64,
+}
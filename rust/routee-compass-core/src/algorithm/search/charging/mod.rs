@@ -0,0 +1,4 @@
+pub mod charging_config;
+pub mod charging_network;
+pub mod charging_search;
+pub mod charging_stop;
@@ -0,0 +1,218 @@
+use super::direction::Direction;
+use super::edge_traversal::EdgeTraversal;
+use super::search_algorithm::SearchAlgorithm;
+use super::search_algorithm_result::SearchAlgorithmResult;
+use super::search_error::SearchError;
+use super::search_instance::SearchInstance;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::unit::Cost;
+use serde::{Deserialize, Serialize};
+
+/// how an edge-oriented search treats the origin and destination edges. by default, a
+/// route leaves the origin edge at its end and reaches the destination edge at its start,
+/// so an origin snapped to a dead end or to a one-way edge leading away from the network
+/// has no route. the other policies also consider the opposite endpoints of those edges.
+///
+/// ```json
+/// { "type": "u_turn", "penalty": 30.0 }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum DeadEndPolicy {
+    /// routes leave the origin edge at its end and reach the destination edge at its start
+    #[default]
+    Strict,
+    /// when no strict route exists, routes may also leave from the start of the origin
+    /// edge or reach the destination edge at its end
+    BothEndpoints,
+    /// routes may always leave from the start of the origin edge or reach the destination
+    /// edge at its end, as a u-turn which adds the penalty to the cost of the route
+    UTurn { penalty: Cost },
+}
+
+impl DeadEndPolicy {
+    /// runs a forward search between two edges under this policy. each route which uses
+    /// the opposite endpoint of the origin or destination edge is searched between the
+    /// vertices of those endpoints, and the route of least cost is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - the search algorithm
+    /// * `source` - the origin edge
+    /// * `target` - the destination edge
+    /// * `si` - the search instance
+    ///
+    /// # Results
+    ///
+    /// The result of the search with the least-cost route, or an error if no endpoints
+    /// allowed by the policy are connected.
+    pub fn run_edge_oriented(
+        &self,
+        algorithm: &SearchAlgorithm,
+        source: EdgeId,
+        target: EdgeId,
+        si: &SearchInstance,
+    ) -> Result<SearchAlgorithmResult, SearchError> {
+        let strict = algorithm.run_edge_oriented(source, Some(target), &Direction::Forward, si);
+        if source == target {
+            return strict;
+        }
+        let penalty = match (self, &strict) {
+            (DeadEndPolicy::Strict, _) => return strict,
+            (DeadEndPolicy::BothEndpoints, Err(SearchError::NoPathExists(_, _))) => Cost::ZERO,
+            (DeadEndPolicy::BothEndpoints, _) => return strict,
+            (DeadEndPolicy::UTurn { penalty }, _) => *penalty,
+        };
+        if penalty < Cost::ZERO {
+            return Err(SearchError::BuildError(format!(
+                "u-turn penalty must not be negative, found {}",
+                penalty
+            )));
+        }
+
+        let e1_src = si.directed_graph.src_vertex_id(source)?;
+        let e1_dst = si.directed_graph.dst_vertex_id(source)?;
+        let e2_src = si.directed_graph.src_vertex_id(target)?;
+        let e2_dst = si.directed_graph.dst_vertex_id(target)?;
+        let mut best = match strict {
+            Ok(result) => route_cost(&result).map(|cost| (cost, result)),
+            Err(SearchError::NoPathExists(_, _)) => None,
+            Err(e) => return Err(e),
+        };
+        let endpoints = [
+            (e1_src, e2_src, penalty, Cost::ZERO),
+            (e1_dst, e2_dst, Cost::ZERO, penalty),
+            (e1_src, e2_dst, penalty, penalty),
+        ];
+        for (o, d, origin_penalty, destination_penalty) in endpoints {
+            let result = match algorithm.run_vertex_oriented(o, Some(d), &Direction::Forward, si) {
+                Ok(result) => result,
+                Err(SearchError::NoPathExists(_, _)) => continue,
+                Err(e) => return Err(e),
+            };
+            // a route between the endpoints which already traverses the origin or
+            // destination edge is found by another pair of endpoints
+            let revisits = result
+                .routes
+                .iter()
+                .flatten()
+                .any(|e| e.edge_id == source || e.edge_id == target);
+            if revisits {
+                continue;
+            }
+            let result = with_endpoint_edges(
+                result,
+                source,
+                target,
+                origin_penalty,
+                destination_penalty,
+                si,
+            )?;
+            if let Some(cost) = route_cost(&result) {
+                if best.as_ref().map(|(c, _)| cost < *c).unwrap_or(true) {
+                    best = Some((cost, result));
+                }
+            }
+        }
+        best.map(|(_, result)| result)
+            .ok_or(SearchError::NoPathExists(e1_dst, e2_src))
+    }
+}
+
+/// the total cost of the first route of a search result, if it has one
+fn route_cost(result: &SearchAlgorithmResult) -> Option<Cost> {
+    result
+        .routes
+        .first()
+        .map(|route| route.iter().fold(Cost::ZERO, |acc, e| acc + e.total_cost()))
+}
+
+/// adds the origin and destination edges to each route of a search between their
+/// vertices, with the u-turn penalty of each endpoint as its access cost
+fn with_endpoint_edges(
+    mut result: SearchAlgorithmResult,
+    source: EdgeId,
+    target: EdgeId,
+    origin_penalty: Cost,
+    destination_penalty: Cost,
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    let initial_state = si.state_model.initial_state()?;
+    if result.routes.is_empty() {
+        // the search began at its destination, such as a u-turn onto the destination edge
+        result.routes.push(vec![]);
+    }
+    for route in result.routes.iter_mut() {
+        let final_state = route
+            .last()
+            .map(|e| e.result_state.clone())
+            .unwrap_or_else(|| initial_state.clone());
+        route.insert(
+            0,
+            EdgeTraversal {
+                edge_id: source,
+                access_cost: origin_penalty,
+                traversal_cost: Cost::ZERO,
+                result_state: initial_state.clone(),
+            },
+        );
+        route.push(EdgeTraversal {
+            edge_id: target,
+            access_cost: destination_penalty,
+            traversal_cost: Cost::ZERO,
+            result_state: final_state,
+        });
+    }
+    result.iterations += 2;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_at_origin};
+    use crate::model::property::edge::Edge;
+    use crate::model::unit::DistanceUnit;
+
+    /// a one-way edge (0) -> (1) into the dead end (1), and the 1km edges
+    /// (0) -> (2) and (2) -> (3)
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![
+            Edge::new(0, 0, 1, 1000.0),
+            Edge::new(1, 0, 2, 1000.0),
+            Edge::new(2, 2, 3, 1000.0),
+        ];
+        distance_search_instance(graph_at_origin(4, edges), DistanceUnit::Kilometers)
+    }
+
+    #[test]
+    fn test_origin_on_dead_end() {
+        let si = build_search_instance();
+        let alg = SearchAlgorithm::Dijkstra;
+        let (source, target) = (EdgeId(0), EdgeId(2));
+        let strict = DeadEndPolicy::Strict.run_edge_oriented(&alg, source, target, &si);
+        assert!(matches!(strict, Err(SearchError::NoPathExists(_, _))));
+
+        // the route leaves from the start of the origin edge instead
+        let result = DeadEndPolicy::BothEndpoints
+            .run_edge_oriented(&alg, source, target, &si)
+            .unwrap();
+        let edge_ids = |result: &SearchAlgorithmResult| {
+            result.routes[0]
+                .iter()
+                .map(|e| e.edge_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(edge_ids(&result), vec![EdgeId(0), EdgeId(1), EdgeId(2)]);
+        assert_eq!(result.routes[0][0].access_cost, Cost::ZERO);
+
+        // the u-turn off the origin edge is charged the penalty
+        let penalty = Cost::new(5.0);
+        let result = DeadEndPolicy::UTurn { penalty }
+            .run_edge_oriented(&alg, source, target, &si)
+            .unwrap();
+        assert_eq!(edge_ids(&result), vec![EdgeId(0), EdgeId(1), EdgeId(2)]);
+        assert_eq!(result.routes[0][0].access_cost, penalty);
+        assert_eq!(route_cost(&result), Some(Cost::new(6.0)));
+    }
+}
@@ -21,7 +21,7 @@ impl Direction {
         &'a self,
         vertex_id: &VertexId,
         si: &'a SearchInstance,
-    ) -> Result<Box<dyn Iterator<Item = &EdgeId> + 'a>, GraphError> {
+    ) -> Result<Box<dyn Iterator<Item = &'a EdgeId> + 'a>, GraphError> {
         match self {
             Direction::Forward => si.directed_graph.out_edges_iter(*vertex_id),
             Direction::Reverse => si.directed_graph.in_edges_iter(*vertex_id),
@@ -0,0 +1,285 @@
+use super::direction::Direction;
+use super::edge_traversal::EdgeTraversal;
+use super::search_algorithm::SearchAlgorithm;
+use super::search_algorithm_result::SearchAlgorithmResult;
+use super::search_error::SearchError;
+use super::search_instance::SearchInstance;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::state::custom_feature_format::CustomFeatureFormat;
+use crate::model::state::state_feature::StateFeature;
+use crate::model::traversal::state::state_variable::StateVar;
+use crate::model::unit::{as_f64::AsF64, Cost};
+
+/// the positions along the origin and destination edges of an edge-oriented route at
+/// which it starts and ends, as fractions of the length of each edge from its start.
+/// without offsets, a route leaves the origin edge at its end and reaches the
+/// destination edge at its start, so neither edge adds to its distance, time or energy.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EdgeOffsets {
+    pub origin: Option<f64>,
+    pub destination: Option<f64>,
+}
+
+impl EdgeOffsets {
+    pub fn new(origin: Option<f64>, destination: Option<f64>) -> Result<EdgeOffsets, SearchError> {
+        for offset in [origin, destination].into_iter().flatten() {
+            if !(0.0..=1.0).contains(&offset) {
+                return Err(SearchError::BuildError(format!(
+                    "edge offset {} is not in the range [0, 1]",
+                    offset
+                )));
+            }
+        }
+        Ok(EdgeOffsets {
+            origin,
+            destination,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.origin.is_none() && self.destination.is_none()
+    }
+
+    /// true if, on a route within a single edge, the destination offset comes before
+    /// the origin offset
+    pub fn is_backwards(&self) -> bool {
+        self.destination.unwrap_or(1.0) < self.origin.unwrap_or(0.0)
+    }
+
+    /// re-evaluates each route of an edge-oriented search so that it traverses the
+    /// remainder of the origin edge after the origin offset and the part of the
+    /// destination edge before the destination offset. the traversal cost and the
+    /// change of each accumulated state feature over those edges is pro-rated by the
+    /// fraction of the edge traversed, while the edges in between are traversed in full.
+    /// when the origin and destination edges are the same, the route traverses the part
+    /// of the edge between the offsets. if the destination offset is behind the origin
+    /// offset, the route instead leaves the end of the edge and searches for a way back
+    /// onto its start.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - the result of an edge-oriented search
+    /// * `source` - the origin edge
+    /// * `target` - the destination edge
+    /// * `alg` - the algorithm used to search for a way back onto a single edge
+    /// * `si` - the search instance
+    ///
+    /// # Returns
+    ///
+    /// the search result with its routes re-evaluated, or an error if no route leads
+    /// back onto a single edge whose destination offset is behind its origin offset.
+    pub fn apply(
+        &self,
+        mut result: SearchAlgorithmResult,
+        source: EdgeId,
+        target: EdgeId,
+        alg: &SearchAlgorithm,
+        si: &SearchInstance,
+    ) -> Result<SearchAlgorithmResult, SearchError> {
+        if self.is_empty() {
+            return Ok(result);
+        }
+        if source == target && self.is_backwards() {
+            return self.turn_around(source, result.iterations, alg, si);
+        }
+        if source == target && result.routes.is_empty() {
+            result.routes.push(vec![]);
+        }
+        for route in result.routes.iter_mut() {
+            let mut edge_ids = route.iter().map(|e| e.edge_id).collect::<Vec<_>>();
+            if source == target && edge_ids.is_empty() {
+                edge_ids.push(source);
+            }
+            if edge_ids.first() != Some(&source) || edge_ids.last() != Some(&target) {
+                return Err(SearchError::InternalSearchError(format!(
+                    "route does not run from edge {} to edge {}",
+                    source, target
+                )));
+            }
+            *route = self.evaluate(&edge_ids, si)?;
+        }
+        Ok(result)
+    }
+
+    /// the fraction of the edge at position `idx` of a route of `n_edges` edges which
+    /// the route traverses: the first and last edges are cut at their offsets, and
+    /// the edges in between are traversed in full.
+    pub fn fraction(&self, idx: usize, n_edges: usize) -> f64 {
+        match (idx == 0, idx + 1 == n_edges) {
+            (true, true) => self.destination.unwrap_or(1.0) - self.origin.unwrap_or(0.0),
+            (true, false) => 1.0 - self.origin.unwrap_or(1.0),
+            (false, true) => self.destination.unwrap_or(0.0),
+            (false, false) => 1.0,
+        }
+    }
+
+    /// routes from the end of an edge back onto its start, so that a route within the
+    /// edge can reach a destination offset behind its origin offset
+    fn turn_around(
+        &self,
+        edge_id: EdgeId,
+        iterations: u64,
+        alg: &SearchAlgorithm,
+        si: &SearchInstance,
+    ) -> Result<SearchAlgorithmResult, SearchError> {
+        let src = si.directed_graph.dst_vertex_id(edge_id)?;
+        let dst = si.directed_graph.src_vertex_id(edge_id)?;
+        let (detour, edge_ids) = if src == dst {
+            (SearchAlgorithmResult::default(), vec![edge_id, edge_id])
+        } else {
+            let detour = alg.run_vertex_oriented(src, Some(dst), &Direction::Forward, si)?;
+            let route = detour
+                .routes
+                .first()
+                .ok_or(SearchError::NoPathExists(src, dst))?;
+            let edge_ids = std::iter::once(edge_id)
+                .chain(route.iter().map(|e| e.edge_id))
+                .chain(std::iter::once(edge_id))
+                .collect::<Vec<_>>();
+            (detour, edge_ids)
+        };
+        Ok(SearchAlgorithmResult {
+            trees: detour.trees,
+            routes: vec![self.evaluate(&edge_ids, si)?],
+            iterations: iterations + detour.iterations,
+            metrics: detour.metrics,
+        })
+    }
+
+    /// traverses a route from the initial state, pro-rating its first and last edges
+    fn evaluate(
+        &self,
+        edge_ids: &[EdgeId],
+        si: &SearchInstance,
+    ) -> Result<Vec<EdgeTraversal>, SearchError> {
+        let mut route: Vec<EdgeTraversal> = Vec::with_capacity(edge_ids.len());
+        let mut state = si.state_model.initial_state()?;
+        let mut prev_edge_id: Option<EdgeId> = None;
+        for (idx, edge_id) in edge_ids.iter().enumerate() {
+            let fraction = self.fraction(idx, edge_ids.len());
+            let et = EdgeTraversal::forward_traversal(*edge_id, prev_edge_id, &state, si)?;
+            let et = if fraction < 1.0 {
+                pro_rate(et, &state, fraction, si)
+            } else {
+                et
+            };
+            state.clone_from(&et.result_state);
+            prev_edge_id = Some(*edge_id);
+            route.push(et);
+        }
+        Ok(route)
+    }
+}
+
+/// scales the traversal cost of an edge traversal and the change of each accumulated
+/// state feature by the fraction of the edge traversed. the access cost is kept in full,
+/// and features which are not accumulated, such as integer or boolean custom features,
+/// keep their value at the end of the edge.
+fn pro_rate(
+    et: EdgeTraversal,
+    prev_state: &[StateVar],
+    fraction: f64,
+    si: &SearchInstance,
+) -> EdgeTraversal {
+    let mut result_state = et.result_state;
+    for (idx, (_, feature)) in si.state_model.indexed_iter() {
+        let accumulated = match feature {
+            StateFeature::Custom { format, .. } => {
+                matches!(format, CustomFeatureFormat::FloatingPoint { .. })
+            }
+            _ => true,
+        };
+        if let (true, Some(prev), Some(next)) =
+            (accumulated, prev_state.get(idx), result_state.get_mut(idx))
+        {
+            *next = StateVar::new(prev.as_f64() + (next.as_f64() - prev.as_f64()) * fraction);
+        }
+    }
+    EdgeTraversal {
+        edge_id: et.edge_id,
+        access_cost: et.access_cost,
+        traversal_cost: Cost::new(et.traversal_cost.as_f64() * fraction),
+        result_state,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_at_origin};
+    use crate::model::property::edge::Edge;
+    use crate::model::traversal::state::state_variable::StateVar;
+    use crate::model::unit::DistanceUnit;
+
+    /// compares within the rounding error of a few state variable operations, which
+    /// are single precision with the `compact` feature
+    fn approx_eq(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() <= 1e-9 + 8.0 * StateVar::EPSILON * expected.abs()
+    }
+
+    /// the 1km edges (0) -> (1) -> (2) -> (3), with the 1km edge (1) -> (0) back
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![
+            Edge::new(0, 0, 1, 1000.0),
+            Edge::new(1, 1, 2, 1000.0),
+            Edge::new(2, 2, 3, 1000.0),
+            Edge::new(3, 1, 0, 1000.0),
+        ];
+        distance_search_instance(graph_at_origin(4, edges), DistanceUnit::Kilometers)
+    }
+
+    #[test]
+    fn test_partial_edges() {
+        let si = build_search_instance();
+        let distance = |route: &[EdgeTraversal]| {
+            let state = &route.last().unwrap().result_state;
+            si.state_model
+                .get_distance(state, &String::from("distance"), &DistanceUnit::Kilometers)
+                .unwrap()
+                .as_f64()
+        };
+        let (source, target) = (EdgeId(0), EdgeId(2));
+        let alg = SearchAlgorithm::Dijkstra;
+        let search = || {
+            alg.run_edge_oriented(source, Some(target), &Direction::Forward, &si)
+                .unwrap()
+        };
+
+        // starting 40% along the origin edge and ending 25% along the destination edge
+        let offsets = EdgeOffsets::new(Some(0.4), Some(0.25)).unwrap();
+        let result = offsets.apply(search(), source, target, &alg, &si).unwrap();
+        let route = &result.routes[0];
+        assert_eq!(route.len(), 3);
+        assert!(approx_eq(distance(route), 1.85), "{}", distance(route));
+        let cost = route.iter().map(|e| e.total_cost()).sum::<Cost>();
+        assert!(approx_eq(cost.as_f64(), 1.85));
+
+        // without a destination offset, the destination edge is not traversed
+        let offsets = EdgeOffsets::new(Some(0.4), None).unwrap();
+        let result = offsets.apply(search(), source, target, &alg, &si).unwrap();
+        assert!(approx_eq(distance(&result.routes[0]), 1.6));
+
+        // within a single edge, the route runs between the offsets
+        let offsets = EdgeOffsets::new(Some(0.2), Some(0.7)).unwrap();
+        let single = alg
+            .run_edge_oriented(source, Some(source), &Direction::Forward, &si)
+            .unwrap();
+        let result = offsets.apply(single, source, source, &alg, &si).unwrap();
+        assert!(approx_eq(distance(&result.routes[0]), 0.5));
+
+        // behind the origin on a single edge, the route goes around and back onto the edge
+        let backwards = EdgeOffsets::new(Some(0.7), Some(0.2)).unwrap();
+        let single = SearchAlgorithmResult::default();
+        let result = backwards.apply(single, source, source, &alg, &si).unwrap();
+        let route = &result.routes[0];
+        let edge_ids = route.iter().map(|e| e.edge_id).collect::<Vec<_>>();
+        assert_eq!(edge_ids, vec![EdgeId(0), EdgeId(3), EdgeId(0)]);
+        assert!(approx_eq(distance(route), 1.5), "{}", distance(route));
+
+        // unless no route leads back onto the edge
+        let single = SearchAlgorithmResult::default();
+        let result = backwards.apply(single, target, target, &alg, &si);
+        assert!(matches!(result, Err(SearchError::NoPathExists(..))));
+        assert!(EdgeOffsets::new(Some(1.5), None).is_err());
+    }
+}
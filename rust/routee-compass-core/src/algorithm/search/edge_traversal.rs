@@ -39,7 +39,6 @@ impl EdgeTraversal {
     ///
     /// # Arguments
     ///
-
     /// * `next_edge_id`     - the edge to traverse
     /// * `prev_edge_id_opt` - the previously traversed edge, if exists, for access costs
     /// * `prev_state`       - the state before traversal, positioned closer to the destination
@@ -80,6 +79,9 @@ impl EdgeTraversal {
 
             si.access_model
                 .access_edge(access_trajectory, &mut result_state, &si.state_model)?;
+            si.traversal_model
+                .access_edge(access_trajectory, &mut result_state, &si.state_model)
+                .map_err(SearchError::TraversalModelFailure)?;
 
             let ac = si
                 .cost_model
@@ -120,7 +122,7 @@ impl EdgeTraversal {
     ///
     /// * `prev_edge_id`     - the edge to traverse
     /// * `next_edge_id_opt` - the edge previously traversed that appears closer to the origin
-    ///                        of this reverse search
+    ///   of this reverse search
     /// * `prev_state`       - the state before traversal, positioned closer to the destination
     /// * `si`               - the search assets for this query
     ///
@@ -159,6 +161,9 @@ impl EdgeTraversal {
 
             si.access_model
                 .access_edge(access_trajectory, &mut result_state, &si.state_model)?;
+            si.traversal_model
+                .access_edge(access_trajectory, &mut result_state, &si.state_model)
+                .map_err(SearchError::TraversalModelFailure)?;
 
             let ac = si
                 .cost_model
@@ -0,0 +1,191 @@
+use crate::algorithm::search::ch::ch_search::static_edge_costs;
+use crate::algorithm::search::direction::Direction;
+use crate::algorithm::search::search_error::SearchError;
+use crate::algorithm::search::search_instance::SearchInstance;
+use crate::model::road_network::{graph::Graph, vertex_id::VertexId};
+use crate::model::unit::Cost;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// landmark-based lower bound on the cost between two vertices (the "ALT" heuristic).
+/// for a set of landmark vertices, the cost from each landmark to every vertex and
+/// from every vertex to each landmark is precomputed. by the triangle inequality,
+/// for any landmark `L`:
+///
+/// ```text
+/// cost(u, v) >= cost(L, v) - cost(L, u)
+/// cost(u, v) >= cost(u, L) - cost(v, L)
+/// ```
+///
+/// and the largest of these bounds over all landmarks is used as the estimate.
+pub struct AltHeuristic {
+    pub landmarks: Vec<VertexId>,
+    from_landmark: Vec<Vec<Cost>>,
+    to_landmark: Vec<Vec<Cost>>,
+}
+
+impl AltHeuristic {
+    /// builds the heuristic using static edge costs computed from the search instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `si` - search instance used to compute the cost of each edge
+    /// * `n_landmarks` - number of landmark vertices to select
+    pub fn new(si: &SearchInstance, n_landmarks: usize) -> Result<AltHeuristic, SearchError> {
+        let edge_costs = static_edge_costs(si)?;
+        AltHeuristic::from_edge_costs(&si.directed_graph, &edge_costs, n_landmarks)
+    }
+
+    /// builds the heuristic from a cost for each edge, indexed by EdgeId. landmarks are
+    /// chosen greedily, each new landmark being the vertex farthest from all landmarks
+    /// chosen so far.
+    pub fn from_edge_costs(
+        graph: &Graph,
+        edge_costs: &[Cost],
+        n_landmarks: usize,
+    ) -> Result<AltHeuristic, SearchError> {
+        if edge_costs.len() != graph.n_edges() {
+            return Err(SearchError::BuildError(format!(
+                "alt heuristic expected {} edge costs, found {}",
+                graph.n_edges(),
+                edge_costs.len()
+            )));
+        }
+        if n_landmarks == 0 || graph.n_vertices() == 0 {
+            return Err(SearchError::BuildError(String::from(
+                "alt heuristic requires at least one landmark and a non-empty graph",
+            )));
+        }
+
+        // seed the selection with the vertex farthest from vertex 0
+        let seed_costs = shortest_path_costs(graph, edge_costs, VertexId(0), Direction::Forward)?;
+        let mut landmarks: Vec<VertexId> = vec![];
+        let mut from_landmark: Vec<Vec<Cost>> = vec![];
+        let mut nearest_landmark_cost = seed_costs;
+        while landmarks.len() < n_landmarks {
+            let next = nearest_landmark_cost
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| **c < Cost::INFINITY)
+                .max_by_key(|(_, c)| **c)
+                .map(|(idx, _)| VertexId(idx));
+            let landmark = match next {
+                Some(v) if !landmarks.contains(&v) => v,
+                _ => break,
+            };
+            let costs = shortest_path_costs(graph, edge_costs, landmark, Direction::Forward)?;
+            for (nearest, cost) in nearest_landmark_cost.iter_mut().zip(costs.iter()) {
+                if landmarks.is_empty() || cost < nearest {
+                    *nearest = *cost;
+                }
+            }
+            landmarks.push(landmark);
+            from_landmark.push(costs);
+        }
+
+        let to_landmark = landmarks
+            .par_iter()
+            .map(|l| shortest_path_costs(graph, edge_costs, *l, Direction::Reverse))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        log::info!("built alt heuristic with {} landmarks", landmarks.len());
+        Ok(AltHeuristic {
+            landmarks,
+            from_landmark,
+            to_landmark,
+        })
+    }
+
+    /// lower bound on the cost of any path from `src` to `dst`.
+    pub fn lower_bound(&self, src: VertexId, dst: VertexId) -> Cost {
+        let mut bound = Cost::ZERO;
+        for (from, to) in self.from_landmark.iter().zip(self.to_landmark.iter()) {
+            let (from_src, from_dst) = (from[src.0], from[dst.0]);
+            if from_src < Cost::INFINITY && from_dst < Cost::INFINITY {
+                bound = bound.max(from_dst - from_src);
+            }
+            let (to_src, to_dst) = (to[src.0], to[dst.0]);
+            if to_src < Cost::INFINITY && to_dst < Cost::INFINITY {
+                bound = bound.max(to_src - to_dst);
+            }
+        }
+        bound
+    }
+}
+
+/// one-to-all dijkstra search over the graph with static edge costs. in the
+/// reverse direction, the result is the cost of reaching the source from each vertex.
+fn shortest_path_costs(
+    graph: &Graph,
+    edge_costs: &[Cost],
+    source: VertexId,
+    direction: Direction,
+) -> Result<Vec<Cost>, SearchError> {
+    let mut costs = vec![Cost::INFINITY; graph.n_vertices()];
+    costs[source.0] = Cost::ZERO;
+    let mut heap = BinaryHeap::from([Reverse((Cost::ZERO, source))]);
+    while let Some(Reverse((cost, vertex_id))) = heap.pop() {
+        if cost > costs[vertex_id.0] {
+            continue;
+        }
+        for edge_id in graph.incident_edges(vertex_id, direction)? {
+            let next_vertex_id = graph.incident_vertex(edge_id, direction)?;
+            let next_cost = cost + edge_costs[edge_id.0];
+            if next_cost < costs[next_vertex_id.0] {
+                costs[next_vertex_id.0] = next_cost;
+                heap.push(Reverse((next_cost, next_vertex_id)));
+            }
+        }
+    }
+    Ok(costs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::graph_at_origin;
+    use crate::model::property::edge::Edge;
+
+    /// a directed ring of 6 vertices with unit costs plus a chord 0 -> 3 with cost 2
+    fn build_mock_graph() -> Graph {
+        let mut edges = (0..6)
+            .map(|i| Edge::new(i, i, (i + 1) % 6, 1.0))
+            .collect::<Vec<_>>();
+        edges.push(Edge::new(6, 0, 3, 2.0));
+        graph_at_origin(6, edges)
+    }
+
+    #[test]
+    fn test_alt_lower_bound_is_admissible() {
+        let graph = build_mock_graph();
+        let edge_costs = graph
+            .edges
+            .iter()
+            .map(|e| Cost::from(e.distance))
+            .collect::<Vec<_>>();
+        let alt = AltHeuristic::from_edge_costs(&graph, &edge_costs, 2).unwrap();
+        assert_eq!(alt.landmarks.len(), 2);
+        for src in 0..6 {
+            let exact = shortest_path_costs(&graph, &edge_costs, VertexId(src), Direction::Forward)
+                .unwrap();
+            for (dst, exact_cost) in exact.iter().enumerate() {
+                let bound = alt.lower_bound(VertexId(src), VertexId(dst));
+                assert!(
+                    bound <= *exact_cost,
+                    "bound {} exceeds cost {} for {} -> {}",
+                    bound,
+                    exact_cost,
+                    src,
+                    dst
+                );
+            }
+        }
+        // the ring forces a tight bound for at least one pair
+        let tight = (0..6).any(|src| {
+            (0..6)
+                .any(|dst| src != dst && alt.lower_bound(VertexId(src), VertexId(dst)) > Cost::ZERO)
+        });
+        assert!(tight);
+    }
+}
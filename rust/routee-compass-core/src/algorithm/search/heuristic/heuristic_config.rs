@@ -0,0 +1,118 @@
+use super::alt_heuristic::AltHeuristic;
+use crate::algorithm::search::{search_error::SearchError, search_instance::SearchInstance};
+use serde::{Deserialize, Serialize};
+
+/// configuration for an A* heuristic which is precomputed when the app is built.
+/// found in the `[algorithm.heuristic]` section of a configuration file.
+///
+/// # Example
+///
+/// ```toml
+/// [algorithm.heuristic]
+/// type = "alt"
+/// landmarks = 16
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum HeuristicConfig {
+    /// A*, Landmarks, Triangle inequality heuristic. `landmarks` sets the number
+    /// of landmark vertices. edge costs are computed once from the provided
+    /// `query`, which should describe the least expensive configuration expected
+    /// at query time so that the resulting lower bound remains admissible. not
+    /// available with time-dependent traversal models.
+    Alt {
+        #[serde(default = "default_landmarks")]
+        landmarks: usize,
+        #[serde(default)]
+        query: Option<serde_json::Value>,
+    },
+}
+
+fn default_landmarks() -> usize {
+    8
+}
+
+impl HeuristicConfig {
+    /// the query used to build the search instance for precomputing this heuristic
+    pub fn query(&self) -> serde_json::Value {
+        match self {
+            HeuristicConfig::Alt { query, .. } => {
+                query.clone().unwrap_or_else(|| serde_json::json!({}))
+            }
+        }
+    }
+
+    /// precomputes the heuristic using the static edge costs of a search instance.
+    /// time-dependent traversal models are rejected, since a query reaching an edge
+    /// at another time of day may traverse it for less than its static cost, and the
+    /// heuristic would overestimate the cost of the route.
+    pub fn build(&self, si: &SearchInstance) -> Result<AltHeuristic, SearchError> {
+        if si.traversal_model.is_time_dependent() {
+            return Err(SearchError::BuildError(String::from(
+                "the alt heuristic is precomputed from static edge costs and cannot be used with a time-dependent traversal model",
+            )));
+        }
+        match self {
+            HeuristicConfig::Alt { landmarks, .. } => AltHeuristic::new(si, *landmarks),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_at_origin};
+    use crate::model::property::{edge::Edge, vertex::Vertex};
+    use crate::model::state::{state_feature::StateFeature, state_model::StateModel};
+    use crate::model::traversal::state::state_variable::StateVar;
+    use crate::model::traversal::traversal_model::TraversalModel;
+    use crate::model::traversal::traversal_model_error::TraversalModelError;
+    use crate::model::unit::DistanceUnit;
+    use std::sync::Arc;
+
+    /// a model which traverses edges as another model, but reports that its costs
+    /// depend on the time of day
+    struct TimeDependentModel(Arc<dyn TraversalModel>);
+
+    impl TraversalModel for TimeDependentModel {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            self.0.state_features()
+        }
+
+        fn traverse_edge(
+            &self,
+            trajectory: (&Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVar>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.0.traverse_edge(trajectory, state, state_model)
+        }
+
+        fn estimate_traversal(
+            &self,
+            od: (&Vertex, &Vertex),
+            state: &mut Vec<StateVar>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.0.estimate_traversal(od, state, state_model)
+        }
+
+        fn is_time_dependent(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_alt_rejects_time_dependent_models() {
+        let edges = vec![Edge::new(0, 0, 1, 1.0), Edge::new(1, 1, 0, 1.0)];
+        let mut si = distance_search_instance(graph_at_origin(2, edges), DistanceUnit::Meters);
+        let config = HeuristicConfig::Alt {
+            landmarks: 1,
+            query: None,
+        };
+        assert!(config.build(&si).is_ok());
+
+        si.traversal_model = Arc::new(TimeDependentModel(si.traversal_model.clone()));
+        assert!(config.build(&si).is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod alt_heuristic;
+pub mod heuristic_config;
@@ -25,11 +25,13 @@ pub fn run(
         trees: fwd_trees,
         routes: _,
         iterations: fwd_iterations,
+        metrics: fwd_metrics,
     } = underlying.run_vertex_oriented(source, Some(target), &Direction::Forward, si)?;
     let SearchAlgorithmResult {
         trees: rev_trees,
         routes: _,
         iterations: rev_iterations,
+        metrics: rev_metrics,
     } = underlying.run_vertex_oriented(target, Some(source), &Direction::Reverse, si)?;
     if fwd_trees.len() != 1 {
         Err(SearchError::InternalSearchError(format!(
@@ -129,6 +131,7 @@ pub fn run(
         trees: vec![fwd_tree.clone(), rev_tree.clone()], // todo: figure out how to avoid this clone
         routes: solution,
         iterations: fwd_iterations + rev_iterations + ksp_it, // todo: figure out how to report individually
+        metrics: fwd_metrics.merge(&rev_metrics),
     };
     Ok(result)
 }
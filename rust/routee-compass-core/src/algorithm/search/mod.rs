@@ -4,14 +4,29 @@ use std::collections::HashMap;
 
 pub mod a_star;
 pub mod backtrack;
+pub mod bfs;
+pub mod cancellation_token;
+pub mod ch;
+pub mod charging;
+pub mod dead_end_policy;
 pub mod direction;
+pub mod edge_offsets;
 pub mod edge_traversal;
+pub mod heuristic;
 pub mod ksp;
+pub mod pareto;
+pub mod route_evaluation;
 pub mod search_algorithm;
 pub mod search_algorithm_result;
+pub mod search_buffers;
 pub mod search_error;
 pub mod search_instance;
+pub mod search_metrics;
 pub mod search_result;
 pub mod search_tree_branch;
+pub mod terminated_search;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+pub mod waypoint_search;
 
 pub type MinSearchTree = HashMap<VertexId, SearchTreeBranch>;
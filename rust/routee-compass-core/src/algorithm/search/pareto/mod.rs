@@ -0,0 +1 @@
+pub mod pareto_search;
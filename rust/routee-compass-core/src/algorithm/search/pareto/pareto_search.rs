@@ -0,0 +1,370 @@
+use crate::algorithm::search::edge_traversal::EdgeTraversal;
+use crate::algorithm::search::search_algorithm_result::SearchAlgorithmResult;
+use crate::algorithm::search::search_error::SearchError;
+use crate::algorithm::search::search_instance::SearchInstance;
+use crate::algorithm::search::search_metrics::SearchMetrics;
+use crate::algorithm::search::terminated_search::TerminatedSearch;
+use crate::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
+use crate::model::traversal::state::state_variable::StateVar;
+use crate::model::unit::{as_f64::AsF64, Cost};
+use crate::util::instant::Instant;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// a partial path in a multi-objective search, stored in an arena and
+/// linked to the label it was extended from.
+struct Label {
+    vertex_id: VertexId,
+    objectives: Vec<f64>,
+    prev_label: Option<usize>,
+    edge_traversal: Option<EdgeTraversal>,
+    dominated: bool,
+}
+
+impl Label {
+    fn state<'a>(&'a self, initial_state: &'a [StateVar]) -> &'a [StateVar] {
+        match &self.edge_traversal {
+            Some(et) => &et.result_state,
+            None => initial_state,
+        }
+    }
+
+    fn edge_id(&self) -> Option<EdgeId> {
+        self.edge_traversal.as_ref().map(|et| et.edge_id)
+    }
+}
+
+/// true if every objective in `a` is no worse than in `b`. equal labels
+/// dominate each other so that only one copy is kept.
+fn weakly_dominates(a: &[f64], b: &[f64]) -> bool {
+    a.iter().zip(b.iter()).all(|(a, b)| a <= b)
+}
+
+/// run a multi-objective search which finds the set of nondominated (Pareto-optimal)
+/// routes between two vertices over a set of state features, instead of collapsing
+/// them into a single scalar cost. each objective is the change in a state feature
+/// from the initial state, such as "time" or "energy_liquid".
+///
+/// labels are settled in order of the sum of their objectives, so a settled label
+/// can never be dominated by a label found later. this requires each objective to
+/// never decrease along an edge, and the search fails on an edge which decreases one,
+/// such as an energy objective on a downhill edge with regenerative braking. each vertex keeps the set of
+/// nondominated labels that reached it, and labels dominated by a route already
+/// found to the target are pruned.
+///
+/// # Arguments
+///
+/// * `source` - vertex where the search begins
+/// * `target` - vertex where the search ends
+/// * `objectives` - names of the state features to minimize, at least two
+/// * `si` - the search instance
+///
+/// # Returns
+///
+/// one route for each point on the Pareto frontier, ordered by the first objective.
+/// no search tree is returned.
+pub fn run_pareto(
+    source: VertexId,
+    target: VertexId,
+    objectives: &[String],
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    if objectives.len() < 2 {
+        return Err(SearchError::BuildError(format!(
+            "pareto search requires at least two objectives, found [{}]",
+            objectives.join(", ")
+        )));
+    }
+    if source == target {
+        return Ok(SearchAlgorithmResult::default());
+    }
+
+    let initial_state = si.state_model.initial_state()?;
+    let measure = |state: &[StateVar]| -> Result<Vec<f64>, SearchError> {
+        objectives
+            .iter()
+            .map(|name| {
+                si.state_model
+                    .get_delta(&initial_state, state, name)
+                    .map(|delta| delta.as_f64())
+                    .map_err(SearchError::from)
+            })
+            .collect()
+    };
+
+    let mut labels: Vec<Label> = vec![Label {
+        vertex_id: source,
+        objectives: measure(&initial_state)?,
+        prev_label: None,
+        edge_traversal: None,
+        dominated: false,
+    }];
+    let mut vertex_labels: HashMap<VertexId, Vec<usize>> = HashMap::from([(source, vec![0])]);
+    let mut queue: BinaryHeap<Reverse<(Cost, usize)>> =
+        BinaryHeap::from([Reverse((Cost::ZERO, 0))]);
+    let mut solutions: Vec<usize> = vec![];
+
+    let start_time = Instant::now();
+    let mut iterations = 0;
+    let mut metrics = SearchMetrics {
+        heap_pushes: 1,
+        ..Default::default()
+    };
+
+    loop {
+        metrics.observe_frontier(queue.len());
+        let Some(Reverse((_, label_idx))) = queue.pop() else {
+            break;
+        };
+        metrics.heap_pops += 1;
+        si.cancellation.check()?;
+        si.termination_model
+            .test(&start_time, labels.len(), iterations)
+            .map_err(|e| {
+                TerminatedSearch::search_error(
+                    e,
+                    Some(labels[label_idx].vertex_id),
+                    labels.len(),
+                    iterations,
+                    &start_time,
+                )
+            })?;
+        if labels[label_idx].dominated {
+            continue;
+        }
+        iterations += 1;
+        let vertex_id = labels[label_idx].vertex_id;
+        if vertex_id == target {
+            solutions.push(label_idx);
+            continue;
+        }
+
+        let state = labels[label_idx].state(&initial_state).to_vec();
+        let prev_edge_id = labels[label_idx].edge_id();
+        let prev_edge = match prev_edge_id {
+            Some(id) => Some(si.directed_graph.get_edge(id)?),
+            None => None,
+        };
+
+        for edge_id in si.directed_graph.out_edges_iter(vertex_id)? {
+            let edge = si.directed_graph.get_edge(*edge_id)?;
+            let valid_frontier =
+                si.frontier_model
+                    .valid_frontier(edge, &state, prev_edge, &si.state_model)?;
+            if !valid_frontier {
+                continue;
+            }
+            let et = EdgeTraversal::forward_traversal(*edge_id, prev_edge_id, &state, si)?;
+            let valid_traversal =
+                si.frontier_model
+                    .valid_traversal(edge, &et.result_state, &si.state_model)?;
+            if !valid_traversal {
+                continue;
+            }
+            let next_objectives = measure(&et.result_state)?;
+            // settling in order of summed objectives requires that no objective
+            // decreases along an edge, which energy regeneration or negative costs break
+            let prev_objectives = &labels[label_idx].objectives;
+            if let Some((name, _)) = objectives
+                .iter()
+                .zip(prev_objectives.iter().zip(next_objectives.iter()))
+                .find(|(_, (prev, next))| next < prev)
+            {
+                return Err(SearchError::BuildError(format!(
+                    "pareto search requires non-negative objective deltas, but objective \"{}\" decreases when traversing edge {}",
+                    name, edge_id
+                )));
+            }
+
+            // prune against routes already found and labels already at the next vertex
+            let next_vertex_id = edge.dst_vertex_id;
+            let dominated_by_solution = solutions
+                .iter()
+                .any(|s| weakly_dominates(&labels[*s].objectives, &next_objectives));
+            let existing = vertex_labels.entry(next_vertex_id).or_default();
+            let dominated_at_vertex = existing
+                .iter()
+                .any(|l| weakly_dominates(&labels[*l].objectives, &next_objectives));
+            if dominated_by_solution || dominated_at_vertex {
+                continue;
+            }
+            let mut kept = vec![];
+            for l in existing.drain(..) {
+                if weakly_dominates(&next_objectives, &labels[l].objectives) {
+                    labels[l].dominated = true;
+                } else {
+                    kept.push(l);
+                }
+            }
+
+            let next_idx = labels.len();
+            kept.push(next_idx);
+            *existing = kept;
+            let priority = Cost::new(next_objectives.iter().sum());
+            labels.push(Label {
+                vertex_id: next_vertex_id,
+                objectives: next_objectives,
+                prev_label: Some(label_idx),
+                edge_traversal: Some(et),
+                dominated: false,
+            });
+            queue.push(Reverse((priority, next_idx)));
+            metrics.heap_pushes += 1;
+        }
+    }
+
+    if solutions.is_empty() {
+        return Err(SearchError::NoPathExists(source, target));
+    }
+    solutions.sort_by(|a, b| labels[*a].objectives[0].total_cmp(&labels[*b].objectives[0]));
+    let route_start = Instant::now();
+    let routes = solutions
+        .iter()
+        .map(|s| label_route(*s, &labels))
+        .collect::<Vec<_>>();
+    metrics.route_runtime = route_start.elapsed();
+    log::debug!(
+        "pareto search iterations: {}, labels: {}, routes: {}",
+        iterations,
+        labels.len(),
+        routes.len()
+    );
+    Ok(SearchAlgorithmResult {
+        trees: vec![],
+        routes,
+        iterations,
+        metrics,
+    })
+}
+
+/// follows the chain of labels back to the source to build a route
+fn label_route(label_idx: usize, labels: &[Label]) -> Vec<EdgeTraversal> {
+    let mut route = vec![];
+    let mut current = Some(label_idx);
+    while let Some(idx) = current {
+        if let Some(et) = &labels[idx].edge_traversal {
+            route.push(et.clone());
+        }
+        current = labels[idx].prev_label;
+    }
+    route.reverse();
+    route
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::cancellation_token::CancellationToken;
+    use crate::algorithm::search::test_fixtures::graph_at_origin;
+    use crate::model::access::default::no_access_model::NoAccessModel;
+    use crate::model::cost::cost_aggregation::CostAggregation;
+    use crate::model::cost::cost_model::CostModel;
+    use crate::model::cost::vehicle::vehicle_cost_rate::VehicleCostRate;
+    use crate::model::frontier::default::no_restriction::NoRestriction;
+    use crate::model::property::edge::Edge;
+    use crate::model::state::state_feature::StateFeature;
+    use crate::model::state::state_model::StateModel;
+    use crate::model::termination::termination_model::TerminationModel;
+    use crate::model::traversal::default::speed_traversal_engine::SpeedTraversalEngine;
+    use crate::model::traversal::default::speed_traversal_model::SpeedTraversalModel;
+    use crate::model::unit::{Distance, DistanceUnit, SpeedUnit, Time, TimeUnit};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    /// routes from (0) to (2) with speeds from the test velocities file, where
+    /// edges 0, 1, 2 and 3 have speeds of 10, 20, 30 and 40 kph:
+    ///   [0]      3km @ 10kph                 -> 3km, 18 minutes
+    ///   [1], [2] 2km @ 20kph then 3km @ 30kph -> 5km, 12 minutes
+    ///   [1], [3] 2km @ 20kph then 4km @ 40kph -> 6km, 12 minutes (dominated)
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![
+            Edge::new(0, 0, 2, 3000.0),
+            Edge::new(1, 0, 1, 2000.0),
+            Edge::new(2, 1, 2, 3000.0),
+            Edge::new(3, 1, 2, 4000.0),
+        ];
+        let graph = graph_at_origin(3, edges);
+        let speed_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join("velocities.txt");
+        let engine = SpeedTraversalEngine::new(
+            &speed_file,
+            SpeedUnit::KilometersPerHour,
+            Some(DistanceUnit::Kilometers),
+            Some(TimeUnit::Minutes),
+        )
+        .unwrap();
+        let state_model = Arc::new(
+            StateModel::empty()
+                .extend(vec![
+                    (
+                        String::from("distance"),
+                        StateFeature::Distance {
+                            distance_unit: DistanceUnit::Kilometers,
+                            initial: Distance::new(0.0),
+                        },
+                    ),
+                    (
+                        String::from("time"),
+                        StateFeature::Time {
+                            time_unit: TimeUnit::Minutes,
+                            initial: Time::new(0.0),
+                        },
+                    ),
+                ])
+                .unwrap(),
+        );
+        let cost_model = CostModel::new(
+            Arc::new(HashMap::from([(String::from("time"), 1.0)])),
+            Arc::new(HashMap::from([(
+                String::from("time"),
+                VehicleCostRate::Raw,
+            )])),
+            Arc::new(HashMap::new()),
+            CostAggregation::Sum,
+            state_model.clone(),
+        )
+        .unwrap();
+        SearchInstance {
+            directed_graph: Arc::new(graph),
+            state_model,
+            traversal_model: Arc::new(SpeedTraversalModel::new(Arc::new(engine))),
+            access_model: Arc::new(NoAccessModel {}),
+            cost_model,
+            frontier_model: Arc::new(NoRestriction {}),
+            termination_model: Arc::new(TerminationModel::IterationsLimit { limit: 100 }),
+            heuristic: None,
+            cancellation: CancellationToken::default(),
+        }
+    }
+
+    #[test]
+    fn test_pareto_frontier() {
+        let si = build_search_instance();
+        let objectives = vec![String::from("distance"), String::from("time")];
+        let result = run_pareto(VertexId(0), VertexId(2), &objectives, &si).unwrap();
+        let routes = result
+            .routes
+            .iter()
+            .map(|r| r.iter().map(|et| et.edge_id).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        // the short route is best for distance, the long fast route is best for time
+        assert_eq!(routes, vec![vec![EdgeId(0)], vec![EdgeId(1), EdgeId(2)]]);
+        // every label pushed is popped, as the search runs until the queue is empty
+        assert!(result.metrics.heap_pushes > 1);
+        assert_eq!(result.metrics.heap_pops, result.metrics.heap_pushes);
+        assert!(result.metrics.max_frontier_size > 0);
+    }
+
+    #[test]
+    fn test_pareto_requires_two_objectives() {
+        let si = build_search_instance();
+        let objectives = vec![String::from("time")];
+        let result = run_pareto(VertexId(0), VertexId(2), &objectives, &si);
+        assert!(result.is_err());
+    }
+}
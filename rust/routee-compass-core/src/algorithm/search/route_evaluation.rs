@@ -0,0 +1,165 @@
+use super::edge_traversal::EdgeTraversal;
+use super::search_algorithm_result::SearchAlgorithmResult;
+use super::search_error::SearchError;
+use super::search_instance::SearchInstance;
+use super::search_metrics::SearchMetrics;
+use crate::model::road_network::edge_id::EdgeId;
+
+/// evaluates a route which was found elsewhere, such as by another router or by map
+/// matching, without running a search. each edge is traversed in full, in order, from
+/// the initial state, so that the result carries the accumulated state (time, energy,
+/// etc) and cost of the route just as if a search had produced it.
+///
+/// # Arguments
+///
+/// * `edge_ids` - the edges of the route, in order
+/// * `si` - the search instance
+///
+/// # Returns
+///
+/// a single route with no search tree, or an error if the edges are not connected
+pub fn evaluate_route(
+    edge_ids: &[EdgeId],
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    if edge_ids.is_empty() {
+        return Err(SearchError::BuildError(String::from(
+            "route evaluation requires at least one edge",
+        )));
+    }
+    for pair in edge_ids.windows(2) {
+        let prev_dst = si.directed_graph.dst_vertex_id(pair[0])?;
+        let next_src = si.directed_graph.src_vertex_id(pair[1])?;
+        if prev_dst != next_src {
+            return Err(SearchError::BuildError(format!(
+                "route edges {} and {} are not connected",
+                pair[0], pair[1]
+            )));
+        }
+    }
+
+    let mut route: Vec<EdgeTraversal> = Vec::with_capacity(edge_ids.len());
+    let mut state = si.state_model.initial_state()?;
+    let mut prev_edge_id: Option<EdgeId> = None;
+    for edge_id in edge_ids.iter() {
+        let et = EdgeTraversal::forward_traversal(*edge_id, prev_edge_id, &state, si)?;
+        state.clone_from(&et.result_state);
+        prev_edge_id = Some(*edge_id);
+        route.push(et);
+    }
+
+    Ok(SearchAlgorithmResult {
+        trees: vec![],
+        routes: vec![route],
+        iterations: 0,
+        metrics: SearchMetrics::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_at_origin};
+    use crate::model::property::edge::Edge;
+    use crate::model::property::vertex::Vertex;
+    use crate::model::state::state_feature::StateFeature;
+    use crate::model::state::state_model::StateModel;
+    use crate::model::traversal::default::distance_traversal_model::DistanceTraversalModel;
+    use crate::model::traversal::state::state_variable::StateVar;
+    use crate::model::traversal::traversal_model::TraversalModel;
+    use crate::model::traversal::traversal_model_error::TraversalModelError;
+    use crate::model::unit::{as_f64::AsF64, Cost, Distance, DistanceUnit};
+    use std::sync::Arc;
+
+    /// a path (0) -> (1) -> (2) with edges of 1km and 2km
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![Edge::new(0, 0, 1, 1000.0), Edge::new(1, 1, 2, 2000.0)];
+        distance_search_instance(graph_at_origin(3, edges), DistanceUnit::Kilometers)
+    }
+
+    #[test]
+    fn test_evaluate_route() {
+        let si = build_search_instance();
+        let result = evaluate_route(&[EdgeId(0), EdgeId(1)], &si).unwrap();
+        let route = &result.routes[0];
+        assert_eq!(route.len(), 2);
+        let final_state = &route[1].result_state;
+        let distance = si
+            .state_model
+            .get_distance(
+                final_state,
+                &String::from("distance"),
+                &DistanceUnit::Kilometers,
+            )
+            .unwrap();
+        assert_eq!(distance, Distance::new(3.0));
+
+        // edges out of order are not a valid route
+        let result = evaluate_route(&[EdgeId(1), EdgeId(0)], &si);
+        assert!(result.is_err());
+    }
+
+    /// a distance model which charges half a kilometer to move from one edge to the next
+    struct IntersectionPenaltyModel(DistanceTraversalModel);
+
+    impl TraversalModel for IntersectionPenaltyModel {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            self.0.state_features()
+        }
+
+        fn traverse_edge(
+            &self,
+            trajectory: (&Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVar>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.0.traverse_edge(trajectory, state, state_model)
+        }
+
+        fn access_edge(
+            &self,
+            _traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVar>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            state_model.add_distance(
+                state,
+                &String::from("distance"),
+                &Distance::new(0.5),
+                &DistanceUnit::Kilometers,
+            )?;
+            Ok(())
+        }
+
+        fn estimate_traversal(
+            &self,
+            od: (&Vertex, &Vertex),
+            state: &mut Vec<StateVar>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.0.estimate_traversal(od, state, state_model)
+        }
+    }
+
+    #[test]
+    fn test_traversal_model_access_cost() {
+        let mut si = build_search_instance();
+        si.traversal_model = Arc::new(IntersectionPenaltyModel(DistanceTraversalModel::new(
+            DistanceUnit::Kilometers,
+        )));
+        let result = evaluate_route(&[EdgeId(0), EdgeId(1)], &si).unwrap();
+        let route = &result.routes[0];
+        // the first edge is not accessed from another edge
+        assert_eq!(route[0].access_cost, Cost::ZERO);
+        assert!((route[1].access_cost.as_f64() - 0.5).abs() < 1e-9);
+        let distance = si
+            .state_model
+            .get_distance(
+                &route[1].result_state,
+                &String::from("distance"),
+                &DistanceUnit::Kilometers,
+            )
+            .unwrap();
+        assert_eq!(distance, Distance::new(3.5));
+    }
+}
@@ -1,31 +1,75 @@
 use super::backtrack;
+use super::bfs::bfs_algorithm;
+use super::ch::{ch_search, contraction_hierarchy, contraction_hierarchy::ContractionHierarchy};
 use super::edge_traversal::EdgeTraversal;
 use super::ksp::ksp_single_via_paths;
 use super::ksp::route_similarity_function::RouteSimilarityFunction;
+use super::pareto::pareto_search;
 use super::search_algorithm_result::SearchAlgorithmResult;
 use super::search_error::SearchError;
 use super::search_instance::SearchInstance;
+use super::search_metrics::SearchMetrics;
+use super::search_result::SearchResult;
 use super::search_tree_branch::SearchTreeBranch;
-use super::{a_star::a_star_algorithm, direction::Direction};
+use super::{
+    a_star::{a_star_algorithm, edge_based_a_star},
+    direction::Direction,
+};
 use crate::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
 
 use crate::model::unit::Cost;
+use crate::util::instant::Instant;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum SearchAlgorithm {
+    /// a* search with no cost estimate. use when the cost function has no
+    /// admissible estimate, such as energy-based costs.
     Dijkstra,
+    /// breadth-first search which finds the route with the fewest edges,
+    /// optionally limited to some number of edges from the source.
+    Bfs {
+        #[serde(default)]
+        max_hops: Option<usize>,
+    },
+    /// a* search. with `edge_based` set, forward searches between two edges keep a
+    /// label per edge rather than per vertex, which enforces turn restrictions from
+    /// the frontier model exactly at the cost of a larger search.
     #[serde(rename = "a*")]
     AStarAlgorithm {
         weight_factor: Option<Cost>,
+        #[serde(default)]
+        edge_based: bool,
+    },
+    /// dijkstra search which orders its frontier with a bucket queue over costs
+    /// rounded to multiples of `cost_resolution`, in place of a binary heap. use
+    /// when costs are non-negative and a fixed resolution, such as a millisecond
+    /// for costs in seconds, is precise enough.
+    BucketDijkstra { cost_resolution: Cost },
+    /// multi-objective search which returns each route on the Pareto frontier
+    /// of the listed state features. queries may override the objectives.
+    Pareto {
+        #[serde(default)]
+        objectives: Vec<String>,
     },
     KspSingleVia {
         k: usize,
         underlying: Box<SearchAlgorithm>,
         similarity: RouteSimilarityFunction,
     },
+    /// bidirectional search over a contraction hierarchy written by the
+    /// `graph contract` command. requires the no-op access and frontier models.
+    #[serde(rename = "ch")]
+    ContractionHierarchy {
+        #[serde(
+            rename = "hierarchy_input_file",
+            with = "contraction_hierarchy::hierarchy_file"
+        )]
+        hierarchy: Arc<ContractionHierarchy>,
+    },
 }
 
 impl SearchAlgorithm {
@@ -39,9 +83,10 @@ impl SearchAlgorithm {
         match self {
             SearchAlgorithm::Dijkstra => SearchAlgorithm::AStarAlgorithm {
                 weight_factor: Some(Cost::ZERO),
+                edge_based: false,
             }
             .run_vertex_oriented(src_id, dst_id_opt, direction, si),
-            SearchAlgorithm::AStarAlgorithm { weight_factor } => {
+            SearchAlgorithm::AStarAlgorithm { weight_factor, .. } => {
                 let search_result = a_star_algorithm::run_a_star(
                     src_id,
                     dst_id_opt,
@@ -49,6 +94,22 @@ impl SearchAlgorithm {
                     *weight_factor,
                     si,
                 )?;
+                backtrack_search_result(src_id, dst_id_opt, search_result)
+            }
+            SearchAlgorithm::BucketDijkstra { cost_resolution } => {
+                let search_result = a_star_algorithm::run_bucket_dijkstra(
+                    src_id,
+                    dst_id_opt,
+                    direction,
+                    *cost_resolution,
+                    si,
+                )?;
+                backtrack_search_result(src_id, dst_id_opt, search_result)
+            }
+            SearchAlgorithm::Bfs { max_hops } => {
+                let search_result =
+                    bfs_algorithm::run_bfs(src_id, dst_id_opt, direction, *max_hops, si)?;
+                let route_start = Instant::now();
                 let routes = match dst_id_opt {
                     None => vec![],
                     Some(dst_id) => {
@@ -57,12 +118,28 @@ impl SearchAlgorithm {
                         vec![route]
                     }
                 };
+                let metrics = SearchMetrics {
+                    route_runtime: route_start.elapsed(),
+                    ..search_result.metrics
+                };
                 Ok(SearchAlgorithmResult {
                     trees: vec![search_result.tree],
                     routes,
                     iterations: search_result.iterations,
+                    metrics,
                 })
             }
+            SearchAlgorithm::Pareto { objectives } => match (dst_id_opt, direction) {
+                (None, _) => Err(SearchError::BuildError(String::from(
+                    "request has source but no destination which is invalid for pareto search",
+                ))),
+                (_, Direction::Reverse) => Err(SearchError::BuildError(String::from(
+                    "pareto search only supports forward-oriented queries",
+                ))),
+                (Some(dst_id), Direction::Forward) => {
+                    pareto_search::run_pareto(src_id, dst_id, objectives, si)
+                }
+            },
             SearchAlgorithm::KspSingleVia {
                 k,
                 underlying,
@@ -75,8 +152,92 @@ impl SearchAlgorithm {
                     "request has source but no destination which is invalid for k-shortest paths",
                 ))),
             },
+            SearchAlgorithm::ContractionHierarchy { hierarchy } => match (dst_id_opt, direction) {
+                (None, _) => Err(SearchError::BuildError(String::from(
+                    "request has source but no destination which is invalid for contraction hierarchy search",
+                ))),
+                (_, Direction::Reverse) => Err(SearchError::BuildError(String::from(
+                    "contraction hierarchy search only supports forward-oriented queries",
+                ))),
+                (Some(dst_id), Direction::Forward) => ch_search::run_ch(src_id, dst_id, hierarchy, si),
+            },
         }
     }
+    /// runs a vertex-oriented search which stops at whichever of the destinations is
+    /// reached first, which is the destination of least cost from the source. only
+    /// the searches which settle vertices in order of cost support a set of destinations.
+    ///
+    /// # Results
+    ///
+    /// The search result with the route to the chosen destination, and that destination.
+    pub fn run_vertex_oriented_to_any(
+        &self,
+        src_id: VertexId,
+        dst_ids: &[VertexId],
+        direction: &Direction,
+        si: &SearchInstance,
+    ) -> Result<(SearchAlgorithmResult, VertexId), SearchError> {
+        match self {
+            SearchAlgorithm::Dijkstra
+            | SearchAlgorithm::AStarAlgorithm { .. }
+            | SearchAlgorithm::BucketDijkstra { .. } => {
+                let (search_result, chosen) =
+                    a_star_algorithm::run_a_star_to_any(src_id, dst_ids, direction, si)?;
+                let result = backtrack_search_result(src_id, Some(chosen), search_result)?;
+                Ok((result, chosen))
+            }
+            _ => Err(SearchError::BuildError(String::from(
+                "a set of destinations is only supported by the dijkstra, a* and bucket dijkstra searches",
+            ))),
+        }
+    }
+
+    /// runs a vertex-oriented search which begins from several candidate origins, each
+    /// with a cost offset, and finds the route of least total cost to the destination
+    /// from any of them. only the dijkstra, a* and bucket dijkstra searches support
+    /// several origins, and a bucket dijkstra search runs as a dijkstra search.
+    ///
+    /// # Results
+    ///
+    /// The search result with the route to the destination, and the origin it begins at.
+    pub fn run_vertex_oriented_multi_origin(
+        &self,
+        src_ids: &[(VertexId, Cost)],
+        dst_id: VertexId,
+        si: &SearchInstance,
+    ) -> Result<(SearchAlgorithmResult, VertexId), SearchError> {
+        let weight_factor = match self {
+            SearchAlgorithm::Dijkstra | SearchAlgorithm::BucketDijkstra { .. } => Some(Cost::ZERO),
+            SearchAlgorithm::AStarAlgorithm { weight_factor, .. } => *weight_factor,
+            _ => {
+                return Err(SearchError::BuildError(String::from(
+                    "several origins are only supported by the dijkstra, a* and bucket dijkstra searches",
+                )))
+            }
+        };
+        let search_result = a_star_algorithm::run_a_star_multi_origin(
+            src_ids,
+            Some(dst_id),
+            &Direction::Forward,
+            weight_factor,
+            si,
+        )?;
+        let route_start = Instant::now();
+        let sources = src_ids.iter().map(|(v, _)| *v).collect::<Vec<_>>();
+        let (origin, route) = backtrack::multi_origin_route(&sources, dst_id, &search_result.tree)?;
+        let metrics = SearchMetrics {
+            route_runtime: route_start.elapsed(),
+            ..search_result.metrics
+        };
+        let result = SearchAlgorithmResult {
+            trees: vec![search_result.tree],
+            routes: vec![route],
+            iterations: search_result.iterations,
+            metrics,
+        };
+        Ok((result, origin))
+    }
+
     pub fn run_edge_oriented(
         &self,
         src_id: EdgeId,
@@ -87,9 +248,25 @@ impl SearchAlgorithm {
         match self {
             SearchAlgorithm::Dijkstra => SearchAlgorithm::AStarAlgorithm {
                 weight_factor: Some(Cost::ZERO),
+                edge_based: false,
             }
             .run_edge_oriented(src_id, dst_id_opt, direction, search_instance),
-            SearchAlgorithm::AStarAlgorithm { weight_factor } => {
+            SearchAlgorithm::AStarAlgorithm {
+                weight_factor,
+                edge_based,
+            } => {
+                // when enabled, forward searches between two edges keep a label per edge
+                // so that turn restrictions (via the frontier model) are enforced exactly
+                if let (true, Some(dst_id), Direction::Forward) =
+                    (*edge_based, dst_id_opt, direction)
+                {
+                    return edge_based_a_star::run_edge_based_a_star(
+                        src_id,
+                        dst_id,
+                        *weight_factor,
+                        search_instance,
+                    );
+                }
                 let search_result = a_star_algorithm::run_a_star_edge_oriented(
                     src_id,
                     dst_id_opt,
@@ -97,8 +274,11 @@ impl SearchAlgorithm {
                     *weight_factor,
                     search_instance,
                 )?;
+                let route_start = Instant::now();
                 let routes = match dst_id_opt {
                     None => vec![],
+                    // a search from an edge to itself has no tree to backtrack
+                    Some(dst_id) if dst_id == src_id => vec![],
                     Some(dst_id) => {
                         let route = backtrack::edge_oriented_route(
                             src_id,
@@ -109,21 +289,65 @@ impl SearchAlgorithm {
                         vec![route]
                     }
                 };
+                let metrics = SearchMetrics {
+                    route_runtime: route_start.elapsed(),
+                    ..search_result.metrics
+                };
                 Ok(SearchAlgorithmResult {
                     trees: vec![search_result.tree],
                     routes,
                     iterations: search_result.iterations,
+                    metrics,
                 })
             }
+            SearchAlgorithm::Bfs { max_hops: _ } => {
+                run_edge_oriented(src_id, dst_id_opt, direction, self, search_instance)
+            }
+            SearchAlgorithm::BucketDijkstra { cost_resolution: _ } => {
+                run_edge_oriented(src_id, dst_id_opt, direction, self, search_instance)
+            }
+            SearchAlgorithm::Pareto { objectives: _ } => {
+                run_edge_oriented(src_id, dst_id_opt, direction, self, search_instance)
+            }
             SearchAlgorithm::KspSingleVia {
                 k: _,
                 underlying: _,
                 similarity: _,
             } => run_edge_oriented(src_id, dst_id_opt, direction, self, search_instance),
+            SearchAlgorithm::ContractionHierarchy { hierarchy: _ } => {
+                run_edge_oriented(src_id, dst_id_opt, direction, self, search_instance)
+            }
         }
     }
 }
 
+/// builds the result of a vertex-oriented search which returns a shortest path tree,
+/// backtracking the route to the destination, if any
+fn backtrack_search_result(
+    src_id: VertexId,
+    dst_id_opt: Option<VertexId>,
+    search_result: SearchResult,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    let route_start = Instant::now();
+    let routes = match dst_id_opt {
+        None => vec![],
+        Some(dst_id) => {
+            let route = backtrack::vertex_oriented_route(src_id, dst_id, &search_result.tree)?;
+            vec![route]
+        }
+    };
+    let metrics = SearchMetrics {
+        route_runtime: route_start.elapsed(),
+        ..search_result.metrics
+    };
+    Ok(SearchAlgorithmResult {
+        trees: vec![search_result.tree],
+        routes,
+        iterations: search_result.iterations,
+        metrics,
+    })
+}
+
 // convenience method when origin and destination are specified using
 /// edge ids instead of vertex ids. invokes a vertex-oriented search
 /// from the out-vertex of the source edge to the in-vertex of the
@@ -157,6 +381,7 @@ pub fn run_edge_oriented(
                 mut trees,
                 mut routes,
                 iterations,
+                metrics,
             } = alg.run_vertex_oriented(e1_dst, None, direction, si)?;
             for tree in trees.iter_mut() {
                 if !tree.contains_key(&e1_dst) {
@@ -170,6 +395,7 @@ pub fn run_edge_oriented(
                 trees,
                 routes,
                 iterations: iterations + 1,
+                metrics,
             };
             Ok(updated)
         }
@@ -203,17 +429,19 @@ pub fn run_edge_oriented(
                     trees: vec![tree],
                     routes: vec![route],
                     iterations: 1,
+                    metrics: SearchMetrics::default(),
                 };
-                return Ok(result);
+                Ok(result)
             } else {
                 // run a search and append source/target edges to result
                 let SearchAlgorithmResult {
                     trees,
                     mut routes,
                     iterations,
+                    metrics,
                 } = alg.run_vertex_oriented(e1_dst, Some(e2_src), direction, si)?;
 
-                if trees.is_empty() {
+                if trees.is_empty() && routes.is_empty() {
                     return Err(SearchError::NoPathExists(e1_dst, e2_src));
                 }
 
@@ -238,6 +466,7 @@ pub fn run_edge_oriented(
                     trees,
                     routes,
                     iterations: iterations + 2,
+                    metrics,
                 };
                 Ok(result)
             }
@@ -0,0 +1,154 @@
+use super::search_tree_branch::SearchTreeBranch;
+use crate::model::road_network::vertex_id::VertexId;
+use crate::model::unit::{cost::ReverseCost, Cost};
+use crate::util::{bucket_queue::BucketQueue, priority_queue::InternalPriorityQueue};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// working memory of a vertex-oriented search. each thread keeps one set of buffers
+/// which is cleared between searches instead of being reallocated, so that running
+/// millions of short queries does not spend its time growing new hash maps.
+///
+/// the search tree is returned with the result of a search, so it cannot be reused,
+/// but it is allocated at the running average size of the trees built on the thread,
+/// up to a small limit, so that a typical tree is not rehashed as it grows while one
+/// large search, such as an isochrone, does not inflate every tree after it.
+pub struct SearchBuffers {
+    /// vertices to visit, ordered by their estimated cost
+    pub frontier: InternalPriorityQueue<VertexId, ReverseCost>,
+    /// vertices to visit in a search which orders them with a bucket queue
+    pub bucket_frontier: BucketQueue<VertexId>,
+    /// best known cost to reach each vertex
+    pub g_scores: HashMap<VertexId, Cost>,
+    /// running average of the number of branches of the trees built on this thread
+    average_tree_len: f64,
+}
+
+thread_local! {
+    static BUFFERS: RefCell<Option<SearchBuffers>> = const { RefCell::new(None) };
+}
+
+impl SearchBuffers {
+    /// buffers that grew past this many entries in a very large search are dropped
+    /// rather than kept for the next search, so that one large search does not hold
+    /// on to its memory for the life of the thread.
+    pub const MAX_RETAINED_ENTRIES: usize = 1 << 20;
+
+    /// the most branches a new search tree is allocated for. larger trees grow as needed.
+    pub const MAX_PRESIZED_TREE_ENTRIES: usize = 1 << 14;
+
+    /// the weight of the latest tree in the running average of tree sizes
+    const TREE_AVERAGE_WEIGHT: f64 = 0.125;
+
+    fn new() -> SearchBuffers {
+        SearchBuffers {
+            frontier: InternalPriorityQueue::default(),
+            bucket_frontier: BucketQueue::default(),
+            g_scores: HashMap::new(),
+            average_tree_len: 0.0,
+        }
+    }
+
+    /// runs a function with the cleared buffers of this thread. a search nested within
+    /// another search on the same thread runs with new buffers.
+    pub fn with<T>(f: impl FnOnce(&mut SearchBuffers) -> T) -> T {
+        let mut buffers = BUFFERS
+            .with(|b| b.borrow_mut().take())
+            .unwrap_or_else(SearchBuffers::new);
+        let result = f(&mut buffers);
+        if buffers.retain() {
+            BUFFERS.with(|b| *b.borrow_mut() = Some(buffers));
+        }
+        result
+    }
+
+    /// creates an empty search tree with room for the average number of branches of
+    /// recent trees, up to [`SearchBuffers::MAX_PRESIZED_TREE_ENTRIES`]
+    pub fn new_tree(&self) -> HashMap<VertexId, SearchTreeBranch> {
+        HashMap::with_capacity(self.tree_capacity())
+    }
+
+    /// records the size of a finished search tree, which sizes the next ones
+    pub fn observe_tree(&mut self, tree: &HashMap<VertexId, SearchTreeBranch>) {
+        let len = tree.len() as f64;
+        self.average_tree_len += (len - self.average_tree_len) * Self::TREE_AVERAGE_WEIGHT;
+    }
+
+    fn tree_capacity(&self) -> usize {
+        (self.average_tree_len as usize).min(Self::MAX_PRESIZED_TREE_ENTRIES)
+    }
+
+    /// clears the buffers for the next search, returning false if they are too large to keep
+    fn retain(&mut self) -> bool {
+        if self.g_scores.capacity() > Self::MAX_RETAINED_ENTRIES
+            || self.frontier.capacity() > Self::MAX_RETAINED_ENTRIES
+            || self.bucket_frontier.capacity() > Self::MAX_RETAINED_ENTRIES
+        {
+            return false;
+        }
+        self.frontier.clear();
+        self.bucket_frontier.clear();
+        self.g_scores.clear();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::edge_traversal::EdgeTraversal;
+    use crate::model::road_network::edge_id::EdgeId;
+
+    #[test]
+    fn test_buffers_are_cleared_and_reused() {
+        let capacity = SearchBuffers::with(|b| {
+            for i in 0..100 {
+                b.g_scores.insert(VertexId(i), Cost::ONE);
+                b.frontier.push(VertexId(i), Cost::ONE.into());
+            }
+            b.g_scores.capacity()
+        });
+        SearchBuffers::with(|b| {
+            assert!(b.g_scores.is_empty());
+            assert!(b.frontier.is_empty());
+            assert_eq!(b.g_scores.capacity(), capacity);
+
+            // a nested search gets its own buffers
+            SearchBuffers::with(|nested| assert_eq!(nested.g_scores.capacity(), 0));
+        });
+    }
+
+    #[test]
+    fn test_tree_capacity_follows_recent_trees() {
+        let branch = SearchTreeBranch {
+            terminal_vertex: VertexId(0),
+            edge_traversal: EdgeTraversal {
+                edge_id: EdgeId(0),
+                access_cost: Cost::ZERO,
+                traversal_cost: Cost::ZERO,
+                result_state: vec![],
+            },
+        };
+        let tree = |n: usize| {
+            (0..n)
+                .map(|i| (VertexId(i), branch.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+        let mut buffers = SearchBuffers::new();
+        for _ in 0..50 {
+            buffers.observe_tree(&tree(100));
+        }
+        assert!((99..=100).contains(&buffers.tree_capacity()));
+
+        // one large search does not size every tree after it
+        buffers.observe_tree(&tree(SearchBuffers::MAX_RETAINED_ENTRIES));
+        assert_eq!(
+            buffers.tree_capacity(),
+            SearchBuffers::MAX_PRESIZED_TREE_ENTRIES
+        );
+        for _ in 0..100 {
+            buffers.observe_tree(&tree(100));
+        }
+        assert!(buffers.tree_capacity() < 200);
+    }
+}
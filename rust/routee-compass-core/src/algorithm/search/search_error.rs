@@ -1,3 +1,4 @@
+use super::terminated_search::TerminatedSearch;
 use crate::model::{
     access::access_model_error::AccessModelError,
     cost::cost_error::CostError,
@@ -30,14 +31,22 @@ pub enum SearchError {
     LoopInSearchResult(EdgeId),
     #[error("query terminated due to {0}")]
     QueryTerminated(String),
+    #[error("query terminated due to {}", .0.reason)]
+    Terminated(Box<TerminatedSearch>),
+    #[error("query was cancelled")]
+    Cancelled,
     #[error("no path exists between vertices {0} and {1}")]
     NoPathExists(VertexId, VertexId),
+    #[error("no path exists from vertex {0} to any of {1} destination vertices")]
+    NoPathExistsToAny(VertexId, usize),
     #[error("search tree is missing linked vertex {0}")]
     VertexMissingFromSearchTree(VertexId),
     #[error("error accessing shared read-only dataset: {0}")]
     ReadOnlyPoisonError(String),
     #[error("failure building search algorithm: {0}")]
     BuildError(String),
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
     #[error("internal error due to search logic: {0}")]
     InternalSearchError(String),
 }
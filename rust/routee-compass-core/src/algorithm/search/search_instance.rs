@@ -1,3 +1,5 @@
+use super::cancellation_token::CancellationToken;
+use super::heuristic::alt_heuristic::AltHeuristic;
 use super::search_error::SearchError;
 use crate::model::{
     access::access_model::AccessModel,
@@ -13,6 +15,7 @@ use std::sync::Arc;
 
 /// instances of read-only objects used for a search that have
 /// been prepared for a specific query.
+#[derive(Clone)]
 pub struct SearchInstance {
     pub directed_graph: Arc<Graph>,
     pub state_model: Arc<StateModel>,
@@ -21,11 +24,15 @@ pub struct SearchInstance {
     pub cost_model: CostModel,
     pub frontier_model: Arc<dyn FrontierModel>,
     pub termination_model: Arc<TerminationModel>,
+    pub heuristic: Option<Arc<AltHeuristic>>,
+    /// aborts the search when cancelled by the caller
+    pub cancellation: CancellationToken,
 }
 
 impl SearchInstance {
     /// approximates the traversal state delta between two vertices and uses
-    /// the result to compute a cost estimate.
+    /// the result to compute a cost estimate. if a precomputed heuristic is
+    /// available, the larger of the two lower bounds is returned.
     pub fn estimate_traversal_cost(
         &self,
         src: VertexId,
@@ -39,6 +46,12 @@ impl SearchInstance {
         self.traversal_model
             .estimate_traversal((src, dst), &mut dst_state, &self.state_model)?;
         let cost_estimate = self.cost_model.cost_estimate(state, &dst_state)?;
-        Ok(cost_estimate)
+        match &self.heuristic {
+            Some(heuristic) => {
+                let bound = heuristic.lower_bound(src.vertex_id, dst.vertex_id);
+                Ok(cost_estimate.max(bound))
+            }
+            None => Ok(cost_estimate),
+        }
     }
 }
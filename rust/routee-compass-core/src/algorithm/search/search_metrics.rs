@@ -0,0 +1,35 @@
+use allocative::Allocative;
+use std::time::Duration;
+
+/// counters describing the work done by a search, used to tune heuristics and
+/// search parameters. algorithms that do not use a priority queue leave the
+/// heap counters at zero.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Allocative)]
+pub struct SearchMetrics {
+    /// number of entries added to, or re-prioritized in, the frontier queue
+    pub heap_pushes: u64,
+    /// number of entries removed from the frontier queue
+    pub heap_pops: u64,
+    /// the largest number of vertices held in the frontier at once
+    pub max_frontier_size: usize,
+    /// time spent backtracking routes from the search tree
+    pub route_runtime: Duration,
+}
+
+impl SearchMetrics {
+    /// records the current size of the frontier
+    pub fn observe_frontier(&mut self, frontier_size: usize) {
+        self.max_frontier_size = self.max_frontier_size.max(frontier_size);
+    }
+
+    /// combines the metrics of two searches that make up a single query, such as
+    /// the legs of a route with waypoints
+    pub fn merge(&self, other: &SearchMetrics) -> SearchMetrics {
+        SearchMetrics {
+            heap_pushes: self.heap_pushes + other.heap_pushes,
+            heap_pops: self.heap_pops + other.heap_pops,
+            max_frontier_size: self.max_frontier_size.max(other.max_frontier_size),
+            route_runtime: self.route_runtime + other.route_runtime,
+        }
+    }
+}
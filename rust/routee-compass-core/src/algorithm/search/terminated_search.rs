@@ -0,0 +1,47 @@
+use super::search_error::SearchError;
+use crate::model::road_network::vertex_id::VertexId;
+use crate::model::termination::termination_model_error::TerminationModelError;
+use crate::util::instant::Instant;
+use std::time::Duration;
+
+/// what a search had reached when its termination model stopped it, so that a query
+/// which ran out of resources can be told apart from one with no path.
+#[derive(Debug, Clone)]
+pub struct TerminatedSearch {
+    /// explanation from the termination model
+    pub reason: String,
+    /// the vertex the search was about to expand when it stopped, if known. this is
+    /// the front of the search queue, so for a* it has the lowest cost plus heuristic
+    /// estimate rather than the lowest cost, and for bfs it has the fewest hops. edge
+    /// based searches report the destination of the edge being expanded.
+    pub best_vertex: Option<VertexId>,
+    /// number of entries in the search tree (or labels) when the search stopped
+    pub tree_size: usize,
+    pub iterations: u64,
+    pub runtime: Duration,
+}
+
+impl TerminatedSearch {
+    /// converts an error from testing the termination model into a search error. when
+    /// the model terminated the query, the error carries the partial progress of the search.
+    pub fn search_error(
+        error: TerminationModelError,
+        best_vertex: Option<VertexId>,
+        tree_size: usize,
+        iterations: u64,
+        start_time: &Instant,
+    ) -> SearchError {
+        match error {
+            TerminationModelError::QueryTerminated(reason) => {
+                SearchError::Terminated(Box::new(TerminatedSearch {
+                    reason,
+                    best_vertex,
+                    tree_size,
+                    iterations,
+                    runtime: start_time.elapsed(),
+                }))
+            }
+            other => SearchError::TerminationModelError(other),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::cancellation_token::CancellationToken;
+use super::search_instance::SearchInstance;
+use crate::model::access::default::no_access_model::NoAccessModel;
+use crate::model::cost::cost_aggregation::CostAggregation;
+use crate::model::cost::cost_model::CostModel;
+use crate::model::cost::vehicle::vehicle_cost_rate::VehicleCostRate;
+use crate::model::frontier::default::no_restriction::NoRestriction;
+use crate::model::property::edge::Edge;
+use crate::model::property::vertex::Vertex;
+use crate::model::road_network::graph::Graph;
+use crate::model::state::state_feature::StateFeature;
+use crate::model::state::state_model::StateModel;
+use crate::model::termination::termination_model::TerminationModel;
+use crate::model::traversal::default::distance_traversal_model::DistanceTraversalModel;
+use crate::model::traversal::traversal_model::TraversalModel;
+use crate::model::unit::{Distance, DistanceUnit};
+use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// builds a graph without geometries from vertices and edges whose ids match
+/// their index
+pub fn graph_from_edges(vertices: Vec<Vertex>, edges: Vec<Edge>) -> Graph {
+    let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
+    let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+    for edge in &edges {
+        adj[edge.src_vertex_id.0].insert(edge.edge_id, edge.dst_vertex_id);
+        rev[edge.dst_vertex_id.0].insert(edge.edge_id, edge.src_vertex_id);
+    }
+    Graph {
+        adj: adj.into(),
+        rev: rev.into(),
+        edges: edges.into(),
+        vertices: vertices.into(),
+        geometries: None,
+        edge_attributes: None,
+    }
+}
+
+/// builds a graph of `n_vertices` vertices at the origin from its edges
+pub fn graph_at_origin(n_vertices: usize, edges: Vec<Edge>) -> Graph {
+    let vertices = (0..n_vertices)
+        .map(|i| Vertex::new(i, 0.0, 0.0))
+        .collect::<Vec<_>>();
+    graph_from_edges(vertices, edges)
+}
+
+/// builds an unrestricted search instance whose cost is the raw value of one of
+/// its state features
+///
+/// # Arguments
+///
+/// * `graph` - the graph to search
+/// * `features` - the state features of the search
+/// * `cost_feature` - name of the feature used as the cost
+/// * `traversal_model` - model which updates the state features
+/// * `iteration_limit` - number of iterations before the search terminates
+pub fn search_instance(
+    graph: Graph,
+    features: Vec<(String, StateFeature)>,
+    cost_feature: &str,
+    traversal_model: Arc<dyn TraversalModel>,
+    iteration_limit: u64,
+) -> SearchInstance {
+    let state_model = Arc::new(StateModel::empty().extend(features).unwrap());
+    let cost_model = CostModel::new(
+        Arc::new(HashMap::from([(String::from(cost_feature), 1.0)])),
+        Arc::new(HashMap::from([(
+            String::from(cost_feature),
+            VehicleCostRate::Raw,
+        )])),
+        Arc::new(HashMap::new()),
+        CostAggregation::Sum,
+        state_model.clone(),
+    )
+    .unwrap();
+    SearchInstance {
+        directed_graph: Arc::new(graph),
+        state_model,
+        traversal_model,
+        access_model: Arc::new(NoAccessModel {}),
+        cost_model,
+        frontier_model: Arc::new(NoRestriction {}),
+        termination_model: Arc::new(TerminationModel::IterationsLimit {
+            limit: iteration_limit,
+        }),
+        heuristic: None,
+        cancellation: CancellationToken::default(),
+    }
+}
+
+/// builds a search instance over a graph whose cost is the distance traveled in
+/// the given unit, which terminates after 20 iterations
+pub fn distance_search_instance(graph: Graph, distance_unit: DistanceUnit) -> SearchInstance {
+    let features = vec![(
+        String::from("distance"),
+        StateFeature::Distance {
+            distance_unit,
+            initial: Distance::new(0.0),
+        },
+    )];
+    let traversal_model = Arc::new(DistanceTraversalModel::new(distance_unit));
+    search_instance(graph, features, "distance", traversal_model, 20)
+}
@@ -0,0 +1,276 @@
+use super::direction::Direction;
+use super::edge_traversal::EdgeTraversal;
+use super::search_algorithm::SearchAlgorithm;
+use super::search_algorithm_result::SearchAlgorithmResult;
+use super::search_error::SearchError;
+use super::search_instance::SearchInstance;
+use super::search_metrics::SearchMetrics;
+use crate::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
+use crate::model::unit::Cost;
+use std::sync::Arc;
+
+/// runs a search through an ordered list of stops, from the first vertex to the
+/// last, visiting each intermediate waypoint vertex in order. each leg is searched
+/// from the state at the end of the previous leg, so time of day, state of charge
+/// and totals carry across waypoints, and the legs are joined into a single route.
+///
+/// # Arguments
+///
+/// * `alg` - the search algorithm used for each leg
+/// * `stops` - origin, waypoints and destination, in order
+/// * `si` - the search instance
+///
+/// # Returns
+///
+/// a route through all stops, followed by any alternative routes, along with the
+/// search tree of each leg
+pub fn run_vertex_oriented(
+    alg: &SearchAlgorithm,
+    stops: &[VertexId],
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    run_legs(stops, false, si, |src, dst, leg_si| {
+        alg.run_vertex_oriented(src, Some(dst), &Direction::Forward, leg_si)
+    })
+}
+
+/// runs a search through an ordered list of stops, from the first edge to the
+/// last, visiting each intermediate waypoint edge in order. as with edge-oriented
+/// search, the origin and destination edges contribute zero cost, while each
+/// waypoint edge is traversed in full.
+///
+/// # Arguments
+///
+/// * `alg` - the search algorithm used for each leg
+/// * `stops` - origin, waypoints and destination, in order
+/// * `si` - the search instance
+///
+/// # Returns
+///
+/// a route through all stops, followed by any alternative routes, along with the
+/// search tree of each leg
+pub fn run_edge_oriented(
+    alg: &SearchAlgorithm,
+    stops: &[EdgeId],
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    run_legs(stops, true, si, |src, dst, leg_si| {
+        alg.run_edge_oriented(src, Some(dst), &Direction::Forward, leg_si)
+    })
+}
+
+fn validate_stops(n_stops: usize) -> Result<(), SearchError> {
+    if n_stops < 2 {
+        Err(SearchError::BuildError(format!(
+            "waypoint search requires an origin and destination, found {} stops",
+            n_stops
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// searches each leg between consecutive stops. every leg after the first is searched
+/// with a state model whose initial state is the state at the end of the route so far.
+/// the first route of each leg makes up the returned route. each alternative route of a
+/// leg produces an alternative route which takes it in place of that leg's first route.
+fn run_legs<T, F>(
+    stops: &[T],
+    edge_oriented: bool,
+    si: &SearchInstance,
+    run_leg: F,
+) -> Result<SearchAlgorithmResult, SearchError>
+where
+    T: Copy,
+    F: Fn(T, T, &SearchInstance) -> Result<SearchAlgorithmResult, SearchError>,
+{
+    validate_stops(stops.len())?;
+    let mut leg_routes: Vec<Vec<Vec<EdgeId>>> = vec![];
+    let mut trees = vec![];
+    let mut iterations = 0;
+    let mut metrics = SearchMetrics::default();
+    for pair in stops.windows(2) {
+        let leg = if leg_routes.is_empty() {
+            run_leg(pair[0], pair[1], si)?
+        } else {
+            // continue from the state at the end of the route so far, where the
+            // waypoint edge of an edge-oriented search is traversed in full
+            let primary = leg_routes
+                .iter()
+                .map(|r| first_route(r.as_slice()))
+                .collect::<Vec<_>>();
+            let edge_ids = join_legs(&primary, edge_oriented);
+            let route = replay(&edge_ids, edge_oriented, false, si)?;
+            let leg_si = match route.last() {
+                Some(et) => SearchInstance {
+                    state_model: Arc::new(si.state_model.with_initial_state(&et.result_state)?),
+                    ..si.clone()
+                },
+                None => si.clone(),
+            };
+            run_leg(pair[0], pair[1], &leg_si)?
+        };
+        let routes = leg
+            .routes
+            .iter()
+            .map(|r| r.iter().map(|et| et.edge_id).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        leg_routes.push(routes);
+        trees.extend(leg.trees);
+        iterations += leg.iterations;
+        metrics = metrics.merge(&leg.metrics);
+    }
+
+    let primary = leg_routes
+        .iter()
+        .map(|r| first_route(r.as_slice()))
+        .collect::<Vec<_>>();
+    let mut routes = vec![replay(
+        &join_legs(&primary, edge_oriented),
+        edge_oriented,
+        edge_oriented,
+        si,
+    )?];
+    for (leg_idx, alternatives) in leg_routes.iter().enumerate() {
+        for alternative in alternatives.iter().skip(1) {
+            let mut legs = primary.clone();
+            legs[leg_idx] = alternative.as_slice();
+            let edge_ids = join_legs(&legs, edge_oriented);
+            routes.push(replay(&edge_ids, edge_oriented, edge_oriented, si)?);
+        }
+    }
+
+    Ok(SearchAlgorithmResult {
+        trees,
+        routes,
+        iterations,
+        metrics,
+    })
+}
+
+/// the first route found for a leg, or an empty route if the leg has no routes
+fn first_route(routes: &[Vec<EdgeId>]) -> &[EdgeId] {
+    routes.first().map(|r| r.as_slice()).unwrap_or_default()
+}
+
+/// joins the routes of consecutive legs into one sequence of edges. in edge-oriented
+/// legs, the edge shared between the end of one leg and the start of the next is
+/// only included once.
+fn join_legs(legs: &[&[EdgeId]], edge_oriented: bool) -> Vec<EdgeId> {
+    let mut edge_ids: Vec<EdgeId> = vec![];
+    for leg in legs.iter() {
+        for (idx, edge_id) in leg.iter().enumerate() {
+            let repeated = edge_oriented && idx == 0 && edge_ids.last() == Some(edge_id);
+            if !repeated {
+                edge_ids.push(*edge_id);
+            }
+        }
+    }
+    edge_ids
+}
+
+/// traverses a sequence of edges from the initial state. the first edge and, when
+/// `free_last` is set, the last edge of an edge-oriented route contribute zero cost.
+fn replay(
+    edge_ids: &[EdgeId],
+    edge_oriented: bool,
+    free_last: bool,
+    si: &SearchInstance,
+) -> Result<Vec<EdgeTraversal>, SearchError> {
+    let mut route: Vec<EdgeTraversal> = Vec::with_capacity(edge_ids.len());
+    let mut state = si.state_model.initial_state()?;
+    let mut prev_edge_id: Option<EdgeId> = None;
+    let last_idx = edge_ids.len().saturating_sub(1);
+    for (idx, edge_id) in edge_ids.iter().enumerate() {
+        let free = edge_oriented && (idx == 0 || (free_last && idx == last_idx));
+        let et = if free {
+            EdgeTraversal {
+                edge_id: *edge_id,
+                access_cost: Cost::ZERO,
+                traversal_cost: Cost::ZERO,
+                result_state: state.clone(),
+            }
+        } else {
+            EdgeTraversal::forward_traversal(*edge_id, prev_edge_id, &state, si)?
+        };
+        state.clone_from(&et.result_state);
+        prev_edge_id = Some(*edge_id);
+        route.push(et);
+    }
+    Ok(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::{distance_search_instance, graph_at_origin};
+    use crate::model::property::edge::Edge;
+    use crate::model::unit::{Distance, DistanceUnit};
+
+    /// a directed square (0) -> (1) -> (2) -> (3) -> (0) where each edge is 1km,
+    /// plus a 1km shortcut (0) -> (2)
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![
+            Edge::new(0, 0, 1, 1000.0),
+            Edge::new(1, 1, 2, 1000.0),
+            Edge::new(2, 2, 3, 1000.0),
+            Edge::new(3, 3, 0, 1000.0),
+            Edge::new(4, 0, 2, 1000.0),
+        ];
+        distance_search_instance(graph_at_origin(4, edges), DistanceUnit::Kilometers)
+    }
+
+    #[test]
+    fn test_vertex_oriented_waypoint_carries_state() {
+        let si = build_search_instance();
+        let alg = SearchAlgorithm::Dijkstra;
+        // without the waypoint the shortcut would be taken
+        let stops = vec![VertexId(0), VertexId(1), VertexId(3)];
+        let result = run_vertex_oriented(&alg, &stops, &si).unwrap();
+        let route = &result.routes[0];
+        let edge_ids = route.iter().map(|et| et.edge_id).collect::<Vec<_>>();
+        assert_eq!(edge_ids, vec![EdgeId(0), EdgeId(1), EdgeId(2)]);
+        assert_eq!(result.trees.len(), 2);
+        let distance = si
+            .state_model
+            .get_distance(
+                &route[2].result_state,
+                &String::from("distance"),
+                &DistanceUnit::Kilometers,
+            )
+            .unwrap();
+        assert_eq!(distance, Distance::new(3.0));
+
+        // the second leg is searched from the state at the waypoint
+        let leg_distance = si
+            .state_model
+            .get_distance(
+                &result.trees[1][&VertexId(3)].edge_traversal.result_state,
+                &String::from("distance"),
+                &DistanceUnit::Kilometers,
+            )
+            .unwrap();
+        assert_eq!(leg_distance, Distance::new(3.0));
+    }
+
+    #[test]
+    fn test_edge_oriented_waypoint_shares_edge() {
+        let si = build_search_instance();
+        let alg = SearchAlgorithm::Dijkstra;
+        let stops = vec![EdgeId(3), EdgeId(0), EdgeId(2)];
+        let result = run_edge_oriented(&alg, &stops, &si).unwrap();
+        let route = &result.routes[0];
+        let edge_ids = route.iter().map(|et| et.edge_id).collect::<Vec<_>>();
+        assert_eq!(edge_ids, vec![EdgeId(3), EdgeId(0), EdgeId(1), EdgeId(2)]);
+        // the origin and destination edges are free, the waypoint edge is not
+        let distance = si
+            .state_model
+            .get_distance(
+                &route[3].result_state,
+                &String::from("distance"),
+                &DistanceUnit::Kilometers,
+            )
+            .unwrap();
+        assert_eq!(distance, Distance::new(2.0));
+    }
+}
@@ -0,0 +1,36 @@
+use crate::model::road_network::vertex_id::VertexId;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// the traffic control at an intersection
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum IntersectionControl {
+    Signal,
+    Stop,
+    Yield,
+}
+
+impl Display for IntersectionControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            IntersectionControl::Signal => "signal",
+            IntersectionControl::Stop => "stop",
+            IntersectionControl::Yield => "yield",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// a row of an intersection control file, which lists only the controlled vertices
+///
+/// ```csv
+/// vertex_id,control
+/// 12,signal
+/// 40,stop
+/// ```
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct IntersectionControlRow {
+    pub vertex_id: VertexId,
+    pub control: IntersectionControl,
+}
@@ -0,0 +1,158 @@
+use super::intersection_control_access_model_engine::IntersectionControlAccessModelEngine;
+use crate::model::{
+    access::{access_model::AccessModel, access_model_error::AccessModelError},
+    property::{edge::Edge, vertex::Vertex},
+    state::{state_feature::StateFeature, state_model::StateModel},
+    traversal::state::state_variable::StateVar,
+    unit::Time,
+};
+use std::sync::Arc;
+
+/// adds the expected delay, and the energy used idling through it, when a route
+/// passes through a signalized, stop or yield controlled intersection. the delay is
+/// also recorded in its own state feature.
+pub struct IntersectionControlAccessModel {
+    pub engine: Arc<IntersectionControlAccessModelEngine>,
+}
+
+impl AccessModel for IntersectionControlAccessModel {
+    fn access_edge(
+        &self,
+        traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), AccessModelError> {
+        let (_, _, intersection, _, _) = traversal;
+        let delay = match self.engine.get_delay(&intersection.vertex_id) {
+            Some(delay) => delay,
+            None => return Ok(()),
+        };
+        let time_unit = &self.engine.time_unit;
+        state_model.add_time(state, &self.engine.time_feature_name, &delay, time_unit)?;
+        state_model.add_time(state, &self.engine.delay_feature_name, &delay, time_unit)?;
+        for rate in self.engine.idle_energy.iter() {
+            if state_model.contains_key(&rate.feature) {
+                let energy = self.engine.idle_energy(rate, &delay);
+                state_model.add_energy(state, &rate.feature, &energy, &rate.energy_unit)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn state_features(&self) -> Vec<(String, StateFeature)> {
+        vec![(
+            self.engine.delay_feature_name.clone(),
+            StateFeature::Time {
+                time_unit: self.engine.time_unit,
+                initial: Time::ZERO,
+            },
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::access::default::intersection_control::{
+        control::{IntersectionControl, IntersectionControlRow},
+        intersection_control_access_model_engine::IdleEnergyRate,
+    };
+    use crate::model::road_network::vertex_id::VertexId;
+    use crate::model::unit::{as_f64::AsF64, Energy, EnergyUnit, TimeUnit};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_delay_at_controlled_intersection() {
+        let rows = [IntersectionControlRow {
+            vertex_id: VertexId(1),
+            control: IntersectionControl::Signal,
+        }];
+        let delays = HashMap::from([(IntersectionControl::Signal, Time::new(36.0))]);
+        let idle_energy = vec![
+            IdleEnergyRate {
+                feature: String::from("energy_liquid"),
+                rate: 0.5,
+                energy_unit: EnergyUnit::GallonsGasoline,
+            },
+            IdleEnergyRate {
+                feature: String::from("energy_electric"),
+                rate: 1.0,
+                energy_unit: EnergyUnit::KilowattHours,
+            },
+        ];
+        let engine = IntersectionControlAccessModelEngine::new(
+            &rows,
+            delays,
+            TimeUnit::Seconds,
+            idle_energy,
+            String::from("time"),
+            String::from(IntersectionControlAccessModelEngine::DEFAULT_DELAY_FEATURE_NAME),
+        )
+        .unwrap();
+        let model = IntersectionControlAccessModel {
+            engine: Arc::new(engine),
+        };
+        let mut features = vec![
+            (
+                String::from("time"),
+                StateFeature::Time {
+                    time_unit: TimeUnit::Seconds,
+                    initial: Time::ZERO,
+                },
+            ),
+            (
+                String::from("energy_liquid"),
+                StateFeature::Energy {
+                    energy_unit: EnergyUnit::GallonsGasoline,
+                    initial: Energy::ZERO,
+                },
+            ),
+        ];
+        features.extend(model.state_features());
+        let state_model = StateModel::new(features);
+
+        let vertices = [0, 1, 2].map(|i| Vertex::new(i, 0.0, 0.0));
+        let edges = [Edge::new(0, 0, 1, 1.0), Edge::new(1, 1, 2, 1.0)];
+        let mut state = state_model.initial_state().unwrap();
+        // turning from the second edge onto another at vertex 2, which has no control
+        let uncontrolled = (
+            &vertices[1],
+            &edges[1],
+            &vertices[2],
+            &edges[0],
+            &vertices[0],
+        );
+        model
+            .access_edge(uncontrolled, &mut state, &state_model)
+            .unwrap();
+        assert_eq!(state, state_model.initial_state().unwrap());
+
+        let controlled = (
+            &vertices[0],
+            &edges[0],
+            &vertices[1],
+            &edges[1],
+            &vertices[2],
+        );
+        model
+            .access_edge(controlled, &mut state, &state_model)
+            .unwrap();
+        let get_time = |name: &str| {
+            state_model
+                .get_time(&state, &String::from(name), &TimeUnit::Seconds)
+                .unwrap()
+                .as_f64()
+        };
+        assert_eq!(get_time("time"), 36.0);
+        assert_eq!(get_time("intersection_delay"), 36.0);
+        let energy = state_model
+            .get_energy(
+                &state,
+                &String::from("energy_liquid"),
+                &EnergyUnit::GallonsGasoline,
+            )
+            .unwrap();
+        // 36 seconds is 0.01 hours
+        assert!((energy.as_f64() - 0.005).abs() < 1e-6);
+    }
+}
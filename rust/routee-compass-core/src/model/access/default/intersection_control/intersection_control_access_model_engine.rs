@@ -0,0 +1,88 @@
+use super::control::{IntersectionControl, IntersectionControlRow};
+use crate::model::access::access_model_error::AccessModelError;
+use crate::model::road_network::vertex_id::VertexId;
+use crate::model::unit::{as_f64::AsF64, Energy, EnergyUnit, Time, TimeUnit};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// energy used while idling in the delay at an intersection, applied to one energy
+/// feature of the state model. a rate is only applied when the state model of a
+/// search has its feature, so rates can be listed for the energy features of every
+/// vehicle type that the application serves.
+///
+/// ```toml
+/// idle_energy = [
+///   { feature = "energy_liquid", rate = 0.16, energy_unit = "gallons_gasoline" },
+///   { feature = "energy_electric", rate = 0.5, energy_unit = "kilowatt_hours" },
+/// ]
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IdleEnergyRate {
+    /// name of the energy feature
+    pub feature: String,
+    /// energy used per hour of delay
+    pub rate: f64,
+    pub energy_unit: EnergyUnit,
+}
+
+/// the expected delay at controlled intersections, shared across queries
+pub struct IntersectionControlAccessModelEngine {
+    pub controls: HashMap<VertexId, IntersectionControl>,
+    /// expected delay for each type of control. controls without a delay add none.
+    pub delays: HashMap<IntersectionControl, Time>,
+    pub time_unit: TimeUnit,
+    pub idle_energy: Vec<IdleEnergyRate>,
+    /// the feature which the delay is added to
+    pub time_feature_name: String,
+    /// the feature which records the total delay at intersections
+    pub delay_feature_name: String,
+}
+
+impl IntersectionControlAccessModelEngine {
+    pub const DEFAULT_DELAY_FEATURE_NAME: &'static str = "intersection_delay";
+
+    pub fn new(
+        rows: &[IntersectionControlRow],
+        delays: HashMap<IntersectionControl, Time>,
+        time_unit: TimeUnit,
+        idle_energy: Vec<IdleEnergyRate>,
+        time_feature_name: String,
+        delay_feature_name: String,
+    ) -> Result<IntersectionControlAccessModelEngine, AccessModelError> {
+        if let Some((control, delay)) = delays.iter().find(|(_, d)| d.as_f64() < 0.0) {
+            return Err(AccessModelError::BuildError(format!(
+                "delay at {} intersections must not be negative, found {}",
+                control, delay
+            )));
+        }
+        if let Some(rate) = idle_energy.iter().find(|r| r.rate < 0.0) {
+            return Err(AccessModelError::BuildError(format!(
+                "idle energy rate for {} must not be negative, found {}",
+                rate.feature, rate.rate
+            )));
+        }
+        let controls = rows.iter().map(|r| (r.vertex_id, r.control)).collect();
+        Ok(IntersectionControlAccessModelEngine {
+            controls,
+            delays,
+            time_unit,
+            idle_energy,
+            time_feature_name,
+            delay_feature_name,
+        })
+    }
+
+    /// the expected delay at a vertex, if it is a controlled intersection
+    pub fn get_delay(&self, vertex_id: &VertexId) -> Option<Time> {
+        self.controls
+            .get(vertex_id)
+            .and_then(|control| self.delays.get(control))
+            .copied()
+    }
+
+    /// the energy used while idling for a delay
+    pub fn idle_energy(&self, rate: &IdleEnergyRate, delay: &Time) -> Energy {
+        let hours = self.time_unit.convert(delay, &TimeUnit::Hours);
+        Energy::new(rate.rate * hours.as_f64())
+    }
+}
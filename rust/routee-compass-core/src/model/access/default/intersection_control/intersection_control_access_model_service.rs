@@ -0,0 +1,19 @@
+use super::intersection_control_access_model::IntersectionControlAccessModel;
+use super::intersection_control_access_model_engine::IntersectionControlAccessModelEngine;
+use crate::model::access::access_model::AccessModel;
+use crate::model::access::access_model_error::AccessModelError;
+use crate::model::access::access_model_service::AccessModelService;
+use std::sync::Arc;
+
+pub struct IntersectionControlAccessModelService {
+    pub engine: Arc<IntersectionControlAccessModelEngine>,
+}
+
+impl AccessModelService for IntersectionControlAccessModelService {
+    fn build(&self, _query: &serde_json::Value) -> Result<Arc<dyn AccessModel>, AccessModelError> {
+        let model = IntersectionControlAccessModel {
+            engine: self.engine.clone(),
+        };
+        Ok(Arc::new(model))
+    }
+}
@@ -0,0 +1,4 @@
+pub mod control;
+pub mod intersection_control_access_model;
+pub mod intersection_control_access_model_engine;
+pub mod intersection_control_access_model_service;
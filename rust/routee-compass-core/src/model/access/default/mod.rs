@@ -1,3 +1,4 @@
 pub mod combined_model;
+pub mod intersection_control;
 pub mod no_access_model;
 pub mod turn_delays;
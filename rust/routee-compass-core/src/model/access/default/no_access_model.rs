@@ -43,6 +43,10 @@ impl AccessModelService for NoAccessModel {
 }
 
 impl AccessModelBuilder for NoAccessModel {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![])
+    }
+
     fn build(
         &self,
         _parameters: &serde_json::Value,
@@ -1,9 +1,10 @@
+use geo::{HaversineBearing, LineString, Point};
 use serde::Deserialize;
 
 /// simplifies the representation of directionality for a linestring
 /// to just the headings of the start and end points, using cardinal angles [0, 360).
 /// if the start and end have the same heading, the edge heading is None.
-#[derive(Copy, Clone, Deserialize)]
+#[derive(Copy, Clone, Debug, Deserialize)]
 pub struct EdgeHeading {
     arrival_heading: i16,
     departure_heading: Option<i16>,
@@ -18,6 +19,18 @@ impl EdgeHeading {
         }
     }
 
+    /// creates an EdgeHeading from the first and last segments of a linestring.
+    /// returns None if the linestring has fewer than two points.
+    pub fn from_linestring(linestring: &LineString<f32>) -> Option<Self> {
+        let points = linestring.points().collect::<Vec<_>>();
+        let first_segment = (points.first()?, points.get(1)?);
+        let last_segment = (points.get(points.len() - 2)?, points.last()?);
+        Some(Self::new(
+            cardinal_bearing(first_segment),
+            cardinal_bearing(last_segment),
+        ))
+    }
+
     /// retrieve the start
     pub fn start_heading(&self) -> i16 {
         self.arrival_heading
@@ -43,6 +56,13 @@ impl EdgeHeading {
     }
 }
 
+/// bearing from the first to the second point, as a cardinal angle in [0, 360)
+fn cardinal_bearing(segment: (&Point<f32>, &Point<f32>)) -> i16 {
+    let (src, dst) = segment;
+    let bearing = src.haversine_bearing(*dst).round() as i16;
+    bearing.rem_euclid(360)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -54,6 +74,17 @@ mod test {
         assert_eq!(edge_heading.bearing_to_destination(&next_edge_heading), 0);
     }
 
+    #[test]
+    fn test_from_linestring() {
+        // heads north, then turns east
+        let linestring = LineString::from(vec![(0.0, 0.0), (0.0, 0.001), (0.001, 0.001)]);
+        let heading = EdgeHeading::from_linestring(&linestring).unwrap();
+        assert_eq!(heading.start_heading(), 0);
+        assert_eq!(heading.end_heading(), 90);
+        let single_point = LineString::from(vec![(0.0, 0.0)]);
+        assert!(EdgeHeading::from_linestring(&single_point).is_none());
+    }
+
     #[test]
     fn test_wrap_360() {
         let edge_heading = EdgeHeading::new(10, 10);
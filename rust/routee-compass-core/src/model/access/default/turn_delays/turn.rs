@@ -1,7 +1,8 @@
 use crate::model::access::access_model_error::AccessModelError;
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum Turn {
     NoTurn,
@@ -14,9 +15,10 @@ pub enum Turn {
     UTurn,
 }
 
-impl ToString for Turn {
-    fn to_string(&self) -> String {
-        serde_json::to_string(self).unwrap_or_else(|_| String::from("<internal error>"))
+impl Display for Turn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(self).unwrap_or_else(|_| String::from("<internal error>"));
+        write!(f, "{}", s)
     }
 }
 
@@ -6,7 +6,7 @@ use crate::model::cost::cost_error::CostError;
 use crate::model::property::edge::Edge;
 use crate::model::state::state_model::StateModel;
 use crate::model::traversal::state::state_variable::StateVar;
-use crate::model::unit::Cost;
+use crate::model::unit::{Cost, Currency};
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -14,6 +14,7 @@ use std::sync::Arc;
 /// implementation of a model for calculating Cost from a state transition.
 /// vectorized, where each index in these vectors matches the corresponding index
 /// in the state model.
+#[derive(Clone)]
 pub struct CostModel {
     feature_indices: Vec<(String, usize)>,
     weights: Vec<f64>,
@@ -58,15 +59,26 @@ impl CostModel {
         let mut vehicle_rates = vec![];
         let mut network_rates = vec![];
 
-        for (index, (name, _)) in state_model.indexed_iter() {
+        for (index, (name, feature)) in state_model.indexed_iter() {
             // always instantiate a value for each vector, diverting to default (zero-valued) if not provided
             // which has the following effect:
             // - weight: deactivates costs for this feature (product)
             // - v_rate: ignores vehicle costs for this feature (sum)
             // - n_rate: ignores network costs for this feature (sum)
             let weight = weights_mapping.get(name).cloned().unwrap_or_default();
-            let v_rate = vehicle_rate_mapping.get(name).cloned().unwrap_or_default();
-            let n_rate = network_rate_mapping.get(name).cloned().unwrap_or_default();
+            if let Some(rate) = vehicle_rate_mapping.get(name) {
+                rate.validate()?;
+            }
+            let v_rate = vehicle_rate_mapping
+                .get(name)
+                .map(|r| r.with_feature_units(feature))
+                .transpose()?
+                .unwrap_or_default();
+            let n_rate = network_rate_mapping
+                .get(name)
+                .map(|r| r.with_state_feature(name, feature))
+                .transpose()?
+                .unwrap_or_default();
 
             indices.push((name.clone(), index));
             weights.push(weight);
@@ -167,6 +179,38 @@ impl CostModel {
         Ok(pos_cost)
     }
 
+    /// true if any of the network cost rates charge tolls
+    pub fn has_tolls(&self) -> bool {
+        self.network_rates.iter().any(|r| r.has_tolls())
+    }
+
+    /// Calculates the tolls paid to traverse an edge. Tolls are reported in
+    /// the units of the toll table and are not multiplied by any weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - edge traversed
+    /// * `prev_state` - state of the search at the beginning of this edge
+    ///
+    /// # Returns
+    ///
+    /// Either the tolls for this edge or an error.
+    pub fn toll_cost(&self, edge: &Edge, prev_state: &[StateVar]) -> Result<Currency, CostError> {
+        let mut toll = Currency::ZERO;
+        for (name, idx) in self.feature_indices.iter() {
+            let rate = self.network_rates.get(*idx).ok_or_else(|| {
+                CostError::CostVectorOutOfBounds(*idx, String::from(Self::NETWORK_RATES))
+            })?;
+            if rate.has_tolls() {
+                let state_var = prev_state
+                    .get(*idx)
+                    .ok_or_else(|| CostError::StateIndexOutOfBounds(*idx, name.clone()))?;
+                toll = toll + rate.toll(*state_var, edge);
+            }
+        }
+        Ok(toll)
+    }
+
     /// Calculates a cost estimate for traversing between a source and destination
     /// vertex without actually doing the work of traversing the edges.
     /// This estimate is used in search algorithms such as a-star algorithm, where
@@ -0,0 +1,27 @@
+use super::cost_error::CostError;
+use serde::{Deserialize, Serialize};
+
+/// the expected maximum cost of a state feature over a trip, used to scale
+/// features measured in different units onto a common range before they are
+/// weighted. for example, time costs of up to 120 minutes and energy costs of
+/// up to 30 kilowatt hours both contribute in the range [0, 1]. costs accumulate
+/// from zero, so there is no minimum.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CostNormalization {
+    pub max: f64,
+}
+
+impl CostNormalization {
+    /// the coefficient applied to the weight of this feature, which is the
+    /// inverse of the expected maximum.
+    pub fn coefficient(&self) -> Result<f64, CostError> {
+        if !self.max.is_finite() || self.max <= 0.0 {
+            return Err(CostError::InvalidConfiguration(format!(
+                "cost normalization max must be a positive number, found {}",
+                self.max
+            )));
+        }
+        Ok(1.0 / self.max)
+    }
+}
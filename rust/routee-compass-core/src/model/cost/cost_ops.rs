@@ -2,7 +2,11 @@ use super::{
     cost_aggregation::CostAggregation, cost_error::CostError,
     network::network_cost_rate::NetworkCostRate, vehicle::vehicle_cost_rate::VehicleCostRate,
 };
-use crate::model::{property::edge::Edge, traversal::state::state_variable::StateVar, unit::Cost};
+use crate::model::{
+    property::edge::Edge,
+    traversal::state::state_variable::StateVar,
+    unit::{as_f64::AsF64, Cost},
+};
 
 /// steps through each state variable and assigns vehicle costs related to that variable
 /// due to an edge access + traversal event.
@@ -39,7 +43,7 @@ pub fn calculate_vehicle_costs(
 
         // compute cost
         let delta_cost = mapping.map_value(delta);
-        let cost = delta_cost * weight;
+        let cost = Cost::new(delta_cost.as_f64() * weight);
         Ok((name, cost))
     });
 
@@ -71,7 +75,7 @@ pub fn calculate_network_traversal_costs(
             CostError::CostVectorOutOfBounds(*state_idx, String::from("network_cost_rate"))
         })?;
         let access_cost = rate.traversal_cost(*prev_state_var, *next_state_var, edge)?;
-        let cost = access_cost * weight;
+        let cost = Cost::new(access_cost.as_f64() * weight);
         Ok((name, cost))
     });
 
@@ -100,7 +104,7 @@ pub fn calculate_network_access_costs(
             let access_cost =
                 m.access_cost(*prev_state_var, *next_state_var, prev_edge, next_edge)?;
             let coefficient = weights.get(*idx).unwrap_or(&1.0);
-            let cost = access_cost * coefficient;
+            let cost = Cost::new(access_cost.as_f64() * coefficient);
             Ok((name, cost))
         }
     });
@@ -1,6 +1,7 @@
 pub mod cost_aggregation;
 pub mod cost_error;
 pub mod cost_model;
+pub mod cost_normalization;
 pub mod cost_ops;
 pub mod network;
 pub mod vehicle;
@@ -0,0 +1,55 @@
+use crate::model::access::default::turn_delays::edge_heading::EdgeHeading;
+use crate::model::cost::cost_error::CostError;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::util::fs::read_utils;
+use crate::util::geo::geo_io_utils;
+use geo::LineString;
+use std::path::Path;
+
+/// start and end headings for each edge, indexed by EdgeId, computed from
+/// the edge geometries. edges with degenerate geometries have no heading.
+#[derive(Debug)]
+pub struct EdgeHeadingTable {
+    pub headings: Box<[Option<EdgeHeading>]>,
+}
+
+impl EdgeHeadingTable {
+    /// computes edge headings from a file of WKT linestrings, one per edge,
+    /// ordered by EdgeId.
+    pub fn from_geometry_file<P: AsRef<Path>>(path: P) -> Result<EdgeHeadingTable, CostError> {
+        let geometries: Box<[LineString<f32>]> =
+            read_utils::read_raw_file(&path, geo_io_utils::parse_linestring, None).map_err(
+                |e| {
+                    CostError::InvalidConfiguration(format!(
+                        "failure reading edge geometries from {}: {}",
+                        path.as_ref().to_string_lossy(),
+                        e
+                    ))
+                },
+            )?;
+        Ok(EdgeHeadingTable::from_linestrings(&geometries))
+    }
+
+    pub fn from_linestrings(geometries: &[LineString<f32>]) -> EdgeHeadingTable {
+        let headings = geometries
+            .iter()
+            .map(EdgeHeading::from_linestring)
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+        EdgeHeadingTable { headings }
+    }
+
+    /// the heading of an edge, or None if the edge geometry has no heading
+    pub fn get(&self, edge_id: EdgeId) -> Result<Option<EdgeHeading>, CostError> {
+        self.headings
+            .get(edge_id.as_usize())
+            .cloned()
+            .ok_or_else(|| {
+                CostError::InvalidConfiguration(format!(
+                    "edge id {} not found in edge heading table with {} entries",
+                    edge_id,
+                    self.headings.len()
+                ))
+            })
+    }
+}
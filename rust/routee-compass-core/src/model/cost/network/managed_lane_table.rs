@@ -0,0 +1,306 @@
+use crate::model::cost::cost_error::CostError;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::unit::Currency;
+use crate::util::fs::read_utils;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+
+/// the type of a managed lane
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ManagedLaneType {
+    /// a high-occupancy vehicle lane, only open to vehicles with at least the
+    /// minimum occupancy
+    Hov,
+    /// a high-occupancy toll lane, free for vehicles with at least the minimum
+    /// occupancy and tolled for other vehicles with a managed lane pass. without
+    /// a minimum occupancy, every vehicle pays the toll, as on an express toll lane.
+    Hot,
+}
+
+/// a row of a managed lane file, which lists only the edges that are managed lanes
+///
+/// ```csv
+/// edge_id,lane_type,min_occupancy,toll
+/// 10,hov,2,
+/// 11,hot,3,2.50
+/// 12,hot,,4.00
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ManagedLaneRow {
+    pub edge_id: EdgeId,
+    pub lane_type: ManagedLaneType,
+    pub min_occupancy: Option<u64>,
+    pub toll: Option<Currency>,
+}
+
+/// the traveler parameters that decide how managed lanes may be used, read from
+/// the `occupancy` and `managed_lane_pass` keys of a query. the frontier model and
+/// the cost model share the defaults of any parameter their configuration omits.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct ManagedLaneTraveler {
+    /// number of people in the vehicle
+    pub occupancy: u64,
+    /// true if the vehicle has a pass, such as a toll transponder, to pay tolls on
+    /// high-occupancy toll lanes
+    pub managed_lane_pass: bool,
+}
+
+impl Default for ManagedLaneTraveler {
+    fn default() -> Self {
+        ManagedLaneTraveler {
+            occupancy: 1,
+            managed_lane_pass: false,
+        }
+    }
+}
+
+impl ManagedLaneTraveler {
+    pub const OCCUPANCY_KEY: &'static str = "occupancy";
+    pub const MANAGED_LANE_PASS_KEY: &'static str = "managed_lane_pass";
+
+    /// true if the query provides any traveler parameters
+    pub fn in_query(query: &serde_json::Value) -> bool {
+        query.get(Self::OCCUPANCY_KEY).is_some() || query.get(Self::MANAGED_LANE_PASS_KEY).is_some()
+    }
+
+    /// reads the traveler parameters of a query, using the given defaults for any
+    /// parameter the query does not provide
+    pub fn from_query(
+        query: &serde_json::Value,
+        defaults: &ManagedLaneTraveler,
+    ) -> Result<ManagedLaneTraveler, String> {
+        let occupancy = match query.get(Self::OCCUPANCY_KEY) {
+            None => defaults.occupancy,
+            Some(value) => value.as_u64().filter(|o| *o > 0).ok_or_else(|| {
+                format!(
+                    "expected '{}' to be a positive integer, found {}",
+                    Self::OCCUPANCY_KEY,
+                    value
+                )
+            })?,
+        };
+        let managed_lane_pass = match query.get(Self::MANAGED_LANE_PASS_KEY) {
+            None => defaults.managed_lane_pass,
+            Some(value) => value.as_bool().ok_or_else(|| {
+                format!(
+                    "expected '{}' to be a boolean, found {}",
+                    Self::MANAGED_LANE_PASS_KEY,
+                    value
+                )
+            })?,
+        };
+        Ok(ManagedLaneTraveler {
+            occupancy,
+            managed_lane_pass,
+        })
+    }
+}
+
+/// how a traveler may use an edge
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ManagedLaneAccess {
+    /// the edge is open to the traveler without a toll
+    Free,
+    /// the edge is open to the traveler, who pays a toll
+    Tolled(Currency),
+    /// the edge is closed to the traveler
+    Prohibited,
+}
+
+/// managed lane tables loaded by file, so that the frontier model and the cost model
+/// which read the same file share one table. a table is dropped with its last user.
+static LOADED_TABLES: Mutex<BTreeMap<PathBuf, Weak<ManagedLaneTable>>> =
+    Mutex::new(BTreeMap::new());
+
+/// the managed lanes of a road network, such as HOV and HOT lanes
+#[derive(Debug)]
+pub struct ManagedLaneTable {
+    pub lanes: HashMap<EdgeId, ManagedLaneRow>,
+    pub filepath: Option<PathBuf>,
+}
+
+impl ManagedLaneTable {
+    /// minimum occupancy of an HOV lane without one in its row
+    pub const DEFAULT_HOV_OCCUPANCY: u64 = 2;
+
+    /// reads managed lanes from a CSV file with header `edge_id,lane_type,min_occupancy,toll`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ManagedLaneTable, CostError> {
+        let rows: Box<[ManagedLaneRow]> = read_utils::from_csv(&path, true, None)?;
+        let mut table = ManagedLaneTable::from_rows(rows.into_vec())?;
+        table.filepath = Some(path.as_ref().to_path_buf());
+        Ok(table)
+    }
+
+    /// reads managed lanes from a file as with [`ManagedLaneTable::from_file`], or
+    /// shares the table already loaded from the same file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Arc<ManagedLaneTable>, CostError> {
+        let key = path
+            .as_ref()
+            .canonicalize()
+            .unwrap_or_else(|_| path.as_ref().to_path_buf());
+        let mut loaded = LOADED_TABLES.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(table) = loaded.get(&key).and_then(Weak::upgrade) {
+            return Ok(table);
+        }
+        let table = Arc::new(ManagedLaneTable::from_file(&path)?);
+        loaded.retain(|_, t| t.strong_count() > 0);
+        loaded.insert(key, Arc::downgrade(&table));
+        Ok(table)
+    }
+
+    pub fn from_rows(rows: Vec<ManagedLaneRow>) -> Result<ManagedLaneTable, CostError> {
+        let mut lanes = HashMap::new();
+        for row in rows.into_iter() {
+            if row.toll.is_some_and(|t| t < Currency::ZERO) {
+                return Err(CostError::InvalidConfiguration(format!(
+                    "managed lane toll for edge {} must not be negative",
+                    row.edge_id
+                )));
+            }
+            let edge_id = row.edge_id;
+            if lanes.insert(edge_id, row).is_some() {
+                return Err(CostError::InvalidConfiguration(format!(
+                    "edge {} is listed more than once in the managed lane table",
+                    edge_id
+                )));
+            }
+        }
+        Ok(ManagedLaneTable {
+            lanes,
+            filepath: None,
+        })
+    }
+
+    /// how a traveler may use an edge. edges which are not managed lanes are free.
+    pub fn access(&self, edge_id: EdgeId, traveler: &ManagedLaneTraveler) -> ManagedLaneAccess {
+        let row = match self.lanes.get(&edge_id) {
+            Some(row) => row,
+            None => return ManagedLaneAccess::Free,
+        };
+        match row.lane_type {
+            ManagedLaneType::Hov => {
+                let min_occupancy = row.min_occupancy.unwrap_or(Self::DEFAULT_HOV_OCCUPANCY);
+                if traveler.occupancy >= min_occupancy {
+                    ManagedLaneAccess::Free
+                } else {
+                    ManagedLaneAccess::Prohibited
+                }
+            }
+            ManagedLaneType::Hot => {
+                let exempt = row
+                    .min_occupancy
+                    .is_some_and(|min| traveler.occupancy >= min);
+                if exempt {
+                    ManagedLaneAccess::Free
+                } else if traveler.managed_lane_pass {
+                    ManagedLaneAccess::Tolled(row.toll.unwrap_or(Currency::ZERO))
+                } else {
+                    ManagedLaneAccess::Prohibited
+                }
+            }
+        }
+    }
+
+    /// the toll a traveler pays to traverse an edge
+    pub fn get_toll(&self, edge_id: EdgeId, traveler: &ManagedLaneTraveler) -> Currency {
+        match self.access(edge_id, traveler) {
+            ManagedLaneAccess::Tolled(toll) => toll,
+            _ => Currency::ZERO,
+        }
+    }
+}
+
+/// serializes a managed lane table as the path to its file and deserializes it by
+/// loading the managed lanes found at that path, see [`ManagedLaneTable::load`].
+pub mod managed_lane_file {
+    use super::ManagedLaneTable;
+    use serde::{de::Error as DeError, ser::Error as SerError};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{path::PathBuf, sync::Arc};
+
+    pub fn serialize<S>(table: &Arc<ManagedLaneTable>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &table.filepath {
+            Some(path) => path.serialize(s),
+            None => Err(S::Error::custom(
+                "managed lane table was not loaded from a file",
+            )),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Arc<ManagedLaneTable>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = PathBuf::deserialize(d)?;
+        ManagedLaneTable::load(path).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_managed_lane_access() {
+        let row = |edge_id: usize, lane_type, min_occupancy, toll: Option<f64>| ManagedLaneRow {
+            edge_id: EdgeId(edge_id),
+            lane_type,
+            min_occupancy,
+            toll: toll.map(Currency::new),
+        };
+        let table = ManagedLaneTable::from_rows(vec![
+            row(1, ManagedLaneType::Hov, None, None),
+            row(2, ManagedLaneType::Hot, Some(3), Some(2.5)),
+            row(3, ManagedLaneType::Hot, None, Some(4.0)),
+        ])
+        .unwrap();
+        let traveler = |occupancy, managed_lane_pass| ManagedLaneTraveler {
+            occupancy,
+            managed_lane_pass,
+        };
+        use ManagedLaneAccess::*;
+        let cases = [
+            (0, traveler(1, false), Free),
+            (1, traveler(1, true), Prohibited),
+            (1, traveler(2, false), Free),
+            (2, traveler(2, false), Prohibited),
+            (2, traveler(2, true), Tolled(Currency::new(2.5))),
+            (2, traveler(3, false), Free),
+            (3, traveler(4, false), Prohibited),
+            (3, traveler(4, true), Tolled(Currency::new(4.0))),
+        ];
+        for (edge_id, traveler, expected) in cases {
+            let access = table.access(EdgeId(edge_id), &traveler);
+            assert_eq!(access, expected, "edge {} for {:?}", edge_id, traveler);
+        }
+
+        let query = serde_json::json!({ "occupancy": 3 });
+        let defaults = traveler(1, true);
+        let from_query = ManagedLaneTraveler::from_query(&query, &defaults).unwrap();
+        assert_eq!(from_query, traveler(3, true));
+        let bad_query = serde_json::json!({ "occupancy": 0 });
+        assert!(ManagedLaneTraveler::from_query(&bad_query, &defaults).is_err());
+    }
+
+    #[test]
+    fn test_load_shares_tables_by_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("managed-lanes.csv");
+        std::fs::write(&path, "edge_id,lane_type,min_occupancy,toll\n1,hov,2,\n").unwrap();
+        let table = ManagedLaneTable::load(&path).unwrap();
+        let shared = ManagedLaneTable::load(tmp.path().join(".").join("managed-lanes.csv"));
+        assert!(Arc::ptr_eq(&table, &shared.unwrap()));
+
+        // a table is read again once no one holds it
+        drop(table);
+        std::fs::write(&path, "edge_id,lane_type,min_occupancy,toll\n").unwrap();
+        assert!(ManagedLaneTable::load(&path).unwrap().lanes.is_empty());
+    }
+}
@@ -1,4 +1,7 @@
+pub mod edge_heading_table;
+pub mod managed_lane_table;
 pub mod network_access_cost_row;
 pub mod network_cost_rate;
 pub mod network_cost_rate_builder;
 pub mod network_traversal_cost_row;
+pub mod toll_table;
@@ -1,9 +1,17 @@
+use super::edge_heading_table::EdgeHeadingTable;
+use super::managed_lane_table::{self, ManagedLaneAccess, ManagedLaneTable, ManagedLaneTraveler};
+use super::toll_table::{self, TollTable};
+use crate::model::access::default::turn_delays::turn::Turn;
 use crate::model::cost::cost_error::CostError;
 use crate::model::property::edge::Edge;
-use crate::model::unit::Cost;
+use crate::model::road_network::graph::Graph;
+use crate::model::state::state_feature::StateFeature;
+use crate::model::unit::{Cost, Currency, Time, TimeUnit};
 use crate::model::{road_network::edge_id::EdgeId, traversal::state::state_variable::StateVar};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// a mapping for how to transform network state values into a Cost.
 /// mappings come via lookup functions.
@@ -21,10 +29,250 @@ pub enum NetworkCostRate {
     EdgeEdgeLookup {
         lookup: HashMap<(EdgeId, EdgeId), Cost>,
     },
+    /// access cost from the angle of the turn between two edges. the angle is found
+    /// from the edge geometries and classified as a [Turn], which is assigned a
+    /// penalty. turns without a configured penalty have zero cost. the geometries
+    /// loaded with the graph are used when present, or else the geometry file.
+    TurnAngle {
+        #[serde(rename = "geometry_input_file", default)]
+        geometry_file: Option<PathBuf>,
+        /// the headings of each edge, set by [`NetworkCostRate::with_graph`]
+        #[serde(skip)]
+        headings: Option<Arc<EdgeHeadingTable>>,
+        penalties: HashMap<Turn, Cost>,
+    },
+    /// monetary cost of the tolls charged on each edge, which may vary by time of day.
+    /// the time of day is the query departure time plus the elapsed time read from
+    /// `time_feature`, which must be the state feature this rate is assigned to.
+    TollLookup {
+        #[serde(rename = "toll_input_file", with = "toll_table::toll_file")]
+        tolls: Arc<TollTable>,
+        /// name of the time state feature
+        time_feature: String,
+        /// unit of the time state feature, set from the state model
+        #[serde(skip)]
+        time_unit: TimeUnit,
+        /// departure time of the query in seconds since midnight, if known
+        #[serde(default)]
+        departure_seconds: Option<f64>,
+    },
+    /// monetary cost of the tolls charged on managed lanes, such as high-occupancy toll
+    /// lanes, which depend on the vehicle occupancy and whether it has a managed lane
+    /// pass. these are configured here and may be set by each query with its
+    /// `occupancy` and `managed_lane_pass` values. a lane the traveler may not use has
+    /// an infinite cost, so that a search without the managed lane frontier model
+    /// still avoids it.
+    ManagedLaneToll {
+        #[serde(
+            rename = "managed_lane_input_file",
+            with = "managed_lane_table::managed_lane_file"
+        )]
+        lanes: Arc<ManagedLaneTable>,
+        #[serde(flatten)]
+        traveler: ManagedLaneTraveler,
+    },
     Combined(Vec<NetworkCostRate>),
 }
 
 impl NetworkCostRate {
+    /// sets the departure time used by any time-of-day dependent rates
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds_since_midnight` - departure time of the query
+    pub fn with_departure_seconds(&self, seconds_since_midnight: f64) -> NetworkCostRate {
+        match self {
+            NetworkCostRate::TollLookup {
+                tolls,
+                time_feature,
+                time_unit,
+                ..
+            } => NetworkCostRate::TollLookup {
+                tolls: tolls.clone(),
+                time_feature: time_feature.clone(),
+                time_unit: *time_unit,
+                departure_seconds: Some(seconds_since_midnight),
+            },
+            NetworkCostRate::Combined(rates) => NetworkCostRate::Combined(
+                rates
+                    .iter()
+                    .map(|r| r.with_departure_seconds(seconds_since_midnight))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// checks that any toll lookup rate is assigned to the time feature it declares,
+    /// and sets its time unit from that feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the state feature this rate is assigned to
+    /// * `feature` - the state feature this rate is assigned to
+    pub fn with_state_feature(
+        &self,
+        name: &str,
+        feature: &StateFeature,
+    ) -> Result<NetworkCostRate, CostError> {
+        match self {
+            NetworkCostRate::TollLookup {
+                tolls,
+                time_feature,
+                departure_seconds,
+                ..
+            } => {
+                if time_feature != name {
+                    return Err(CostError::InvalidConfiguration(format!(
+                        "toll_lookup rate declares time feature '{}' but is assigned to feature '{}'",
+                        time_feature, name
+                    )));
+                }
+                let time_unit = feature.get_time_unit().map_err(|e| {
+                    CostError::InvalidConfiguration(format!(
+                        "toll_lookup time feature '{}' is not a time feature: {}",
+                        name, e
+                    ))
+                })?;
+                Ok(NetworkCostRate::TollLookup {
+                    tolls: tolls.clone(),
+                    time_feature: time_feature.clone(),
+                    time_unit,
+                    departure_seconds: *departure_seconds,
+                })
+            }
+            NetworkCostRate::Combined(rates) => {
+                let rates = rates
+                    .iter()
+                    .map(|r| r.with_state_feature(name, feature))
+                    .collect::<Result<Vec<_>, CostError>>()?;
+                Ok(NetworkCostRate::Combined(rates))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// sets the edge headings of any turn angle rate from the geometries of the graph,
+    /// or else from its geometry file, and checks that there is one heading entry per
+    /// edge of the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - the road network graph of the app
+    pub fn with_graph(&self, graph: &Graph) -> Result<NetworkCostRate, CostError> {
+        match self {
+            NetworkCostRate::TurnAngle {
+                geometry_file,
+                headings,
+                penalties,
+            } => {
+                let headings = match (headings, graph.geometries(), geometry_file) {
+                    (Some(headings), _, _) => headings.clone(),
+                    (None, Ok(geometries), file) => {
+                        if let Some(file) = file {
+                            log::warn!(
+                                "edge geometries are loaded with the graph, ignoring {:?}",
+                                file
+                            );
+                        }
+                        Arc::new(EdgeHeadingTable::from_linestrings(geometries.as_slice()))
+                    }
+                    (None, Err(_), Some(file)) => {
+                        Arc::new(EdgeHeadingTable::from_geometry_file(file)?)
+                    }
+                    (None, Err(e), None) => {
+                        return Err(CostError::InvalidConfiguration(format!(
+                            "turn_angle rate has no geometry_input_file and {}",
+                            e
+                        )))
+                    }
+                };
+                if headings.headings.len() != graph.n_edges() {
+                    return Err(CostError::InvalidConfiguration(format!(
+                        "turn_angle rate has {} edge geometries for a graph with {} edges",
+                        headings.headings.len(),
+                        graph.n_edges()
+                    )));
+                }
+                Ok(NetworkCostRate::TurnAngle {
+                    geometry_file: geometry_file.clone(),
+                    headings: Some(headings),
+                    penalties: penalties.clone(),
+                })
+            }
+            NetworkCostRate::Combined(rates) => {
+                let rates = rates
+                    .iter()
+                    .map(|r| r.with_graph(graph))
+                    .collect::<Result<Vec<_>, CostError>>()?;
+                Ok(NetworkCostRate::Combined(rates))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// sets the traveler parameters used by managed lane rates from a query, keeping
+    /// the configured parameters for any value the query does not provide
+    pub fn with_managed_lane_query(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<NetworkCostRate, CostError> {
+        match self {
+            NetworkCostRate::ManagedLaneToll { lanes, traveler } => {
+                let traveler = ManagedLaneTraveler::from_query(query, traveler)
+                    .map_err(CostError::InvalidConfiguration)?;
+                Ok(NetworkCostRate::ManagedLaneToll {
+                    lanes: lanes.clone(),
+                    traveler,
+                })
+            }
+            NetworkCostRate::Combined(rates) => {
+                let rates = rates
+                    .iter()
+                    .map(|r| r.with_managed_lane_query(query))
+                    .collect::<Result<Vec<_>, CostError>>()?;
+                Ok(NetworkCostRate::Combined(rates))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// true if this rate charges tolls
+    pub fn has_tolls(&self) -> bool {
+        match self {
+            NetworkCostRate::TollLookup { .. } => true,
+            NetworkCostRate::ManagedLaneToll { .. } => true,
+            NetworkCostRate::Combined(rates) => rates.iter().any(|r| r.has_tolls()),
+            _ => false,
+        }
+    }
+
+    /// the tolls charged to traverse an edge, ignoring any other network costs.
+    pub fn toll(&self, prev_state_var: StateVar, edge: &Edge) -> Currency {
+        match self {
+            NetworkCostRate::TollLookup {
+                tolls,
+                time_unit,
+                departure_seconds,
+                ..
+            } => {
+                let time_of_day = departure_seconds.map(|departure| {
+                    let elapsed =
+                        time_unit.convert(&Time::from(prev_state_var), &TimeUnit::Seconds);
+                    departure + elapsed.to_f64()
+                });
+                tolls.get_toll(edge.edge_id, time_of_day)
+            }
+            NetworkCostRate::ManagedLaneToll { lanes, traveler } => {
+                lanes.get_toll(edge.edge_id, traveler)
+            }
+            NetworkCostRate::Combined(rates) => rates
+                .iter()
+                .fold(Currency::ZERO, |acc, r| acc + r.toll(prev_state_var, edge)),
+            _ => Currency::ZERO,
+        }
+    }
+
     pub fn traversal_cost(
         &self,
         _prev_state_var: StateVar,
@@ -34,10 +282,19 @@ impl NetworkCostRate {
         match self {
             NetworkCostRate::Zero => Ok(Cost::ZERO),
             NetworkCostRate::EdgeEdgeLookup { lookup: _ } => Ok(Cost::ZERO),
+            NetworkCostRate::TurnAngle { .. } => Ok(Cost::ZERO),
             NetworkCostRate::EdgeLookup { lookup } => {
                 let cost = lookup.get(&edge.edge_id).unwrap_or(&Cost::ZERO).to_owned();
                 Ok(cost)
             }
+            NetworkCostRate::TollLookup { .. } => Ok(Cost::from(self.toll(_prev_state_var, edge))),
+            NetworkCostRate::ManagedLaneToll { lanes, traveler } => {
+                match lanes.access(edge.edge_id, traveler) {
+                    ManagedLaneAccess::Free => Ok(Cost::ZERO),
+                    ManagedLaneAccess::Tolled(toll) => Ok(Cost::from(toll)),
+                    ManagedLaneAccess::Prohibited => Ok(Cost::INFINITY),
+                }
+            }
             NetworkCostRate::Combined(mappings) => {
                 let mapped = mappings
                     .iter()
@@ -58,7 +315,7 @@ impl NetworkCostRate {
     /// * `next_state_var` - the state variable after accessing the next edge origin
     /// * `prev_edge` - the edge traversed to reach the next_edge (or none if at origin)
     /// * `next_edge` - the edge we are attempting to access (not yet traversed)
-
+    ///
     /// # Result
     ///
     /// the Cost value for that state, a real number that is aggregated with
@@ -73,12 +330,36 @@ impl NetworkCostRate {
         match self {
             NetworkCostRate::Zero => Ok(Cost::ZERO),
             NetworkCostRate::EdgeLookup { lookup: _ } => Ok(Cost::ZERO),
+            NetworkCostRate::TollLookup { .. } => Ok(Cost::ZERO),
+            NetworkCostRate::ManagedLaneToll { .. } => Ok(Cost::ZERO),
             NetworkCostRate::EdgeEdgeLookup { lookup } => {
                 let result = lookup
                     .get(&(prev_edge.edge_id, next_edge.edge_id))
                     .unwrap_or(&Cost::ZERO);
                 Ok(*result)
             }
+            NetworkCostRate::TurnAngle {
+                headings,
+                penalties,
+                ..
+            } => {
+                let headings = headings.as_ref().ok_or_else(|| {
+                    CostError::InvalidConfiguration(String::from(
+                        "turn_angle rate was not built with the edge geometries of the graph",
+                    ))
+                })?;
+                let (prev_heading, next_heading) = match (
+                    headings.get(prev_edge.edge_id)?,
+                    headings.get(next_edge.edge_id)?,
+                ) {
+                    (Some(prev), Some(next)) => (prev, next),
+                    _ => return Ok(Cost::ZERO),
+                };
+                let angle = prev_heading.bearing_to_destination(&next_heading);
+                let turn = Turn::from_angle(angle)
+                    .map_err(|e| CostError::InvalidConfiguration(e.to_string()))?;
+                Ok(*penalties.get(&turn).unwrap_or(&Cost::ZERO))
+            }
             NetworkCostRate::Combined(mappings) => {
                 let mapped = mappings
                     .iter()
@@ -91,3 +372,127 @@ impl NetworkCostRate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::test_fixtures::graph_at_origin;
+    use crate::model::cost::network::toll_table::TollRow;
+    use crate::model::road_network::edge_geometry_store::EdgeGeometryStore;
+    use geo::LineString;
+
+    #[test]
+    fn test_turn_angle_penalties() {
+        // edge 0 heads north into the origin, edge 1 heads east (right turn),
+        // edge 2 heads west (left turn) and edge 3 heads back south (u-turn)
+        let geometries = vec![
+            LineString::from(vec![(0.0, -0.001), (0.0, 0.0)]),
+            LineString::from(vec![(0.0, 0.0), (0.001, 0.0)]),
+            LineString::from(vec![(0.0, 0.0), (-0.001, 0.0)]),
+            LineString::from(vec![(0.0, 0.0), (0.0, -0.001)]),
+        ];
+        let edges = (0..4).map(|i| Edge::new(i, 0, 0, 1.0)).collect::<Vec<_>>();
+        let rate: NetworkCostRate = serde_json::from_value(serde_json::json!({
+            "type": "turn_angle",
+            "penalties": { "left": 10.0, "u_turn": 100.0 }
+        }))
+        .unwrap();
+
+        // without a geometry file, the headings are read from the graph geometries
+        let graph = graph_at_origin(1, edges.clone());
+        assert!(rate.with_graph(&graph).is_err());
+        let geometries = EdgeGeometryStore::new(geometries.into_boxed_slice(), 4).unwrap();
+        let graph = graph.with_geometries(geometries).unwrap();
+        let rate = rate.with_graph(&graph).unwrap();
+        let s = StateVar::ZERO;
+        let right = rate.access_cost(s, s, &edges[0], &edges[1]).unwrap();
+        let left = rate.access_cost(s, s, &edges[0], &edges[2]).unwrap();
+        let u_turn = rate.access_cost(s, s, &edges[0], &edges[3]).unwrap();
+        assert_eq!(right, Cost::ZERO);
+        assert_eq!(left, Cost::new(10.0));
+        assert_eq!(u_turn, Cost::new(100.0));
+    }
+
+    #[test]
+    fn test_toll_by_time_of_day() {
+        let row = |toll: f64, window: Option<(f64, f64)>| TollRow {
+            edge_id: EdgeId(0),
+            toll: Currency::new(toll),
+            start_hour: window.map(|w| w.0),
+            end_hour: window.map(|w| w.1),
+        };
+        // a peak toll from 7 to 10am, an overnight toll from 10pm to 6am, otherwise 2.0
+        let tolls = TollTable::from_rows(vec![
+            row(5.0, Some((7.0, 10.0))),
+            row(1.0, Some((22.0, 6.0))),
+            row(2.0, None),
+        ])
+        .unwrap();
+        let rate = NetworkCostRate::TollLookup {
+            tolls: Arc::new(tolls),
+            time_feature: String::from("time"),
+            time_unit: TimeUnit::Hours,
+            departure_seconds: None,
+        };
+        let time = StateFeature::Time {
+            time_unit: TimeUnit::Minutes,
+            initial: Time::ZERO,
+        };
+        assert!(rate.with_state_feature("distance", &time).is_err());
+        let rate = rate.with_state_feature("time", &time).unwrap();
+        let edge = Edge::new(0, 0, 1, 1.0);
+        let other_edge = Edge::new(1, 1, 2, 1.0);
+        let toll_at = |rate: &NetworkCostRate, elapsed_minutes: f64| {
+            rate.traversal_cost(StateVar::new(elapsed_minutes), StateVar::ZERO, &edge)
+                .unwrap()
+        };
+
+        // without a departure time, only tolls without a time window apply
+        assert_eq!(toll_at(&rate, 0.0), Cost::new(2.0));
+
+        // departing at 6:30am, the edge is reached 45 minutes later during peak pricing
+        let morning = rate.with_departure_seconds(6.5 * 3600.0);
+        assert_eq!(toll_at(&morning, 0.0), Cost::new(2.0));
+        assert_eq!(toll_at(&morning, 45.0), Cost::new(5.0));
+        let night = rate.with_departure_seconds(23.0 * 3600.0);
+        assert_eq!(toll_at(&night, 120.0), Cost::new(1.0));
+        let toll = morning
+            .traversal_cost(StateVar::ZERO, StateVar::ZERO, &other_edge)
+            .unwrap();
+        assert_eq!(toll, Cost::ZERO);
+    }
+
+    #[test]
+    fn test_managed_lane_toll() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("managed-lanes.csv");
+        std::fs::write(
+            &path,
+            "edge_id,lane_type,min_occupancy,toll\n1,hov,2,\n2,hot,3,2.5\n",
+        )
+        .unwrap();
+        let config = serde_json::json!({
+            "type": "managed_lane_toll",
+            "managed_lane_input_file": path,
+            "managed_lane_pass": true,
+        });
+        let rate: NetworkCostRate = serde_json::from_value(config).unwrap();
+        let cost = |rate: &NetworkCostRate, edge_id: usize| {
+            rate.traversal_cost(
+                StateVar::ZERO,
+                StateVar::ZERO,
+                &Edge::new(edge_id, 0, 1, 1.0),
+            )
+            .unwrap()
+        };
+        // occupancy falls back to the default traveler when the configuration omits it
+        assert_eq!(cost(&rate, 0), Cost::ZERO);
+        assert_eq!(cost(&rate, 1), Cost::INFINITY);
+        assert_eq!(cost(&rate, 2), Cost::new(2.5));
+
+        let query = serde_json::json!({ "occupancy": 3 });
+        let carpool = rate.with_managed_lane_query(&query).unwrap();
+        assert_eq!(cost(&carpool, 1), Cost::ZERO);
+        assert_eq!(cost(&carpool, 2), Cost::ZERO);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::model::cost::cost_error::CostError;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::unit::Currency;
+use crate::util::fs::read_utils;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const SECONDS_PER_HOUR: f64 = 3600.0;
+const HOURS_PER_DAY: f64 = 24.0;
+
+/// a row of a toll file. a toll without a start and end hour applies at all
+/// times of day. a window where the start hour is after the end hour wraps
+/// around midnight, such as 22 to 6.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TollRow {
+    pub edge_id: EdgeId,
+    pub toll: Currency,
+    pub start_hour: Option<f64>,
+    pub end_hour: Option<f64>,
+}
+
+impl TollRow {
+    /// true if this toll applies at the given hour of the day
+    fn applies_at(&self, hour: f64) -> bool {
+        match (self.start_hour, self.end_hour) {
+            (Some(start), Some(end)) if start <= end => start <= hour && hour < end,
+            (Some(start), Some(end)) => hour >= start || hour < end,
+            _ => true,
+        }
+    }
+}
+
+/// tolls charged on each edge, which may vary by time of day.
+#[derive(Debug)]
+pub struct TollTable {
+    pub tolls: HashMap<EdgeId, Vec<TollRow>>,
+    pub filepath: Option<PathBuf>,
+}
+
+impl TollTable {
+    /// reads tolls from a CSV file with header `edge_id,toll,start_hour,end_hour`.
+    /// an edge may have several rows with different time windows.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<TollTable, CostError> {
+        let rows: Box<[TollRow]> = read_utils::from_csv(&path, true, None)?;
+        let mut table = TollTable::from_rows(rows.into_vec())?;
+        table.filepath = Some(path.as_ref().to_path_buf());
+        Ok(table)
+    }
+
+    pub fn from_rows(rows: Vec<TollRow>) -> Result<TollTable, CostError> {
+        let mut tolls: HashMap<EdgeId, Vec<TollRow>> = HashMap::new();
+        for row in rows.into_iter() {
+            if row.start_hour.is_some() != row.end_hour.is_some() {
+                return Err(CostError::InvalidConfiguration(format!(
+                    "toll for edge {} must have both a start_hour and end_hour, or neither",
+                    row.edge_id
+                )));
+            }
+            tolls.entry(row.edge_id).or_default().push(row);
+        }
+        Ok(TollTable {
+            tolls,
+            filepath: None,
+        })
+    }
+
+    /// the toll to traverse an edge. when several tolls apply, the first one listed is
+    /// used. when the time of day is not known, only tolls without a time window apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_id` - edge being traversed
+    /// * `seconds_since_midnight` - time of day when entering the edge, if known
+    pub fn get_toll(&self, edge_id: EdgeId, seconds_since_midnight: Option<f64>) -> Currency {
+        let rows = match self.tolls.get(&edge_id) {
+            Some(rows) => rows,
+            None => return Currency::ZERO,
+        };
+        let hour = seconds_since_midnight.map(|s| (s / SECONDS_PER_HOUR).rem_euclid(HOURS_PER_DAY));
+        rows.iter()
+            .find(|row| match hour {
+                Some(h) => row.applies_at(h),
+                None => row.start_hour.is_none(),
+            })
+            .map(|row| row.toll)
+            .unwrap_or(Currency::ZERO)
+    }
+}
+
+/// serializes a toll table as the path to its file and deserializes it by
+/// loading the tolls found at that path.
+pub mod toll_file {
+    use super::TollTable;
+    use serde::{de::Error as DeError, ser::Error as SerError};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{path::PathBuf, sync::Arc};
+
+    pub fn serialize<S>(table: &Arc<TollTable>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &table.filepath {
+            Some(path) => path.serialize(s),
+            None => Err(S::Error::custom("toll table was not loaded from a file")),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Arc<TollTable>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = PathBuf::deserialize(d)?;
+        TollTable::from_file(path)
+            .map(Arc::new)
+            .map_err(D::Error::custom)
+    }
+}
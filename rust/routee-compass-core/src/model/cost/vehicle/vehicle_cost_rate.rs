@@ -1,7 +1,16 @@
-use crate::model::{traversal::state::state_variable::StateVar, unit::as_f64::AsF64, unit::Cost};
+use crate::model::cost::cost_error::CostError;
+use crate::model::state::state_feature::StateFeature;
+use crate::model::traversal::state::state_variable::StateVar;
+use crate::model::unit::{
+    as_f64::AsF64, Cost, CostRate, CostRateBasis, CostRateUnit, DistanceUnit, EnergyUnit, TimeUnit,
+    UnitError,
+};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
 /// a mapping for how to transform vehicle state values into a Cost.
-/// mappings can be a single instance of Raw, Factor, or Offset mapping.
+/// mappings can be a single instance of Raw, Factor, Offset, UnitRate, PiecewiseLinear
+/// or Polynomial mapping.
 ///
 /// when multiple mappings are specified they are applied sequentially (in user-defined order)
 /// to the state value.
@@ -17,14 +26,41 @@ pub enum VehicleCostRate {
     Factor {
         factor: f64,
     },
-    ///
+    /// add an offset to a value to become a cost
     Offset {
         offset: f64,
     },
+    /// a monetary rate per unit of a distance, time or energy state feature, such as
+    /// dollars per hour or dollars per kilowatt hour. the unit is a [`CostRateUnit`]
+    /// such as `dollars_per_hour`, or the unit charged over such as `hours`. the rate
+    /// is converted to a monetary rate in the unit of the state feature when the cost
+    /// model is built.
+    UnitRate {
+        rate: f64,
+        unit: String,
+    },
+    /// an amount of money per unit of the state feature this is applied to, which is
+    /// what a unit rate becomes when the cost model is built
+    #[serde(skip_deserializing)]
+    Monetary {
+        rate: CostRate,
+    },
+    /// map a value through a piecewise-linear function given as (value, cost) points
+    /// sorted by value. values outside of the points extend the first or last segment.
+    /// like every vehicle rate, this is applied to the change in a feature over each
+    /// edge, not to the trip total, so it must pass through zero and be convex and
+    /// nondecreasing, which keeps edge costs non-negative.
+    PiecewiseLinear {
+        points: Vec<(f64, f64)>,
+    },
+    /// map a value through a polynomial with coefficients in increasing order of
+    /// degree, so `[c0, c1, c2]` is `c0 + c1 * x + c2 * x^2`. this is applied to the
+    /// change in a feature over each edge, so `c0` must be zero, or a constant would
+    /// be charged on every edge, and the other coefficients must not be negative.
+    Polynomial {
+        coefficients: Vec<f64>,
+    },
     Combined(Vec<VehicleCostRate>),
-    // leaving room for extension if we need to do any fancier math, maybe not needed
-    // Poly2 { x0: f64, x1: f64 },
-    // Exp { base: f64, exp_coefficient: f64 },
 }
 
 impl VehicleCostRate {
@@ -39,16 +75,274 @@ impl VehicleCostRate {
     /// the Cost value for that state, a real number that is aggregated with
     /// other Cost values in a common unit space.
     pub fn map_value(&self, state: StateVar) -> Cost {
+        let value = state.as_f64();
         match self {
             VehicleCostRate::Zero => Cost::ZERO,
-            VehicleCostRate::Raw => Cost::new(state.0),
-            VehicleCostRate::Factor { factor } => Cost::new(state.0 * factor),
-            VehicleCostRate::Offset { offset } => Cost::new(state.0 + offset),
+            VehicleCostRate::Raw => Cost::new(value),
+            VehicleCostRate::Factor { factor } => Cost::new(value * factor),
+            VehicleCostRate::Offset { offset } => Cost::new(value + offset),
+            // a unit rate that was not converted assumes the state is in the same unit
+            VehicleCostRate::UnitRate { rate, .. } => Cost::new(value * rate),
+            VehicleCostRate::Monetary { rate } => Cost::from(rate.charge(value)),
+            VehicleCostRate::PiecewiseLinear { points } => {
+                Cost::new(piecewise_linear(points, value))
+            }
+            VehicleCostRate::Polynomial { coefficients } => {
+                // horner's method
+                let y = coefficients
+                    .iter()
+                    .rev()
+                    .fold(0.0, |acc, c| acc * value + c);
+                Cost::new(y)
+            }
             VehicleCostRate::Combined(mappings) => {
-                mappings.iter().fold(Cost::new(state.0), |acc, f| {
-                    f.map_value(StateVar(acc.as_f64()))
+                mappings.iter().fold(Cost::new(value), |acc, f| {
+                    f.map_value(StateVar::new(acc.as_f64()))
                 })
             }
         }
     }
+
+    /// confirms that this rate is well-formed
+    pub fn validate(&self) -> Result<(), CostError> {
+        match self {
+            VehicleCostRate::PiecewiseLinear { points } => {
+                if points.len() < 2 {
+                    return Err(CostError::InvalidConfiguration(format!(
+                        "piecewise linear cost rate requires at least 2 points, found {}",
+                        points.len()
+                    )));
+                }
+                let sorted = points.windows(2).all(|w| w[0].0 < w[1].0);
+                if !sorted {
+                    return Err(CostError::InvalidConfiguration(String::from(
+                        "piecewise linear cost rate points must be sorted by strictly increasing value",
+                    )));
+                }
+                let at_zero = piecewise_linear(points, 0.0);
+                if at_zero.abs() > 1e-9 {
+                    return Err(CostError::InvalidConfiguration(format!(
+                        "piecewise linear cost rate is applied to each edge and must have zero cost at zero, found {}",
+                        at_zero
+                    )));
+                }
+                let slopes = points
+                    .windows(2)
+                    .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+                    .collect::<Vec<_>>();
+                let convex = slopes.windows(2).all(|w| w[0] <= w[1]);
+                if !convex || slopes.iter().any(|m| *m < 0.0) {
+                    return Err(CostError::InvalidConfiguration(String::from(
+                        "piecewise linear cost rate is applied to each edge and must be convex and nondecreasing",
+                    )));
+                }
+                Ok(())
+            }
+            VehicleCostRate::Polynomial { coefficients } => match coefficients.split_first() {
+                None => Err(CostError::InvalidConfiguration(String::from(
+                    "polynomial cost rate requires at least one coefficient",
+                ))),
+                Some((c0, _)) if *c0 != 0.0 => Err(CostError::InvalidConfiguration(format!(
+                    "polynomial cost rate is applied to each edge and its constant coefficient must be zero, found {}",
+                    c0
+                ))),
+                Some((_, rest)) if rest.iter().any(|c| *c < 0.0) => {
+                    Err(CostError::InvalidConfiguration(String::from(
+                        "polynomial cost rate is applied to each edge and its coefficients must not be negative",
+                    )))
+                }
+                Some(_) => Ok(()),
+            },
+            VehicleCostRate::Combined(rates) => rates.iter().try_for_each(|r| r.validate()),
+            _ => Ok(()),
+        }
+    }
+
+    /// converts any unit rates into monetary rates in the unit of the state feature
+    /// they are applied to.
+    ///
+    /// # Arguments
+    ///
+    /// * `feature` - the state feature this rate is applied to
+    ///
+    /// # Result
+    ///
+    /// the rate with unit rates replaced by monetary rates, or an error if the unit
+    /// of a rate does not match the state feature, such as a rate per kilowatt hour
+    /// applied to gallons of gasoline.
+    pub fn with_feature_units(&self, feature: &StateFeature) -> Result<VehicleCostRate, CostError> {
+        match self {
+            VehicleCostRate::UnitRate { rate, unit } => {
+                let invalid_unit = |e: UnitError| {
+                    CostError::InvalidConfiguration(format!(
+                        "unit rate with unit '{}' does not match state feature {:?}: {}",
+                        unit, feature, e
+                    ))
+                };
+                let feature_basis = match feature {
+                    StateFeature::Distance { distance_unit, .. } => {
+                        CostRateBasis::Distance(*distance_unit)
+                    }
+                    StateFeature::Time { time_unit, .. } => CostRateBasis::Time(*time_unit),
+                    StateFeature::Energy { energy_unit, .. } => {
+                        CostRateBasis::Energy(*energy_unit)
+                    }
+                    StateFeature::Custom { .. } => {
+                        return Err(CostError::InvalidConfiguration(format!(
+                            "unit rate with unit '{}' cannot be applied to custom state feature {:?}",
+                            unit, feature
+                        )))
+                    }
+                };
+                // the unit is either a cost rate unit such as dollars_per_hour, or the
+                // unit of the feature that the rate is charged over, such as hours
+                let rate_basis = match (CostRateUnit::from_str(unit), feature_basis) {
+                    (Ok(rate_unit), _) => rate_unit.basis(),
+                    (Err(_), CostRateBasis::Distance(_)) => {
+                        CostRateBasis::Distance(DistanceUnit::from_str(unit).map_err(invalid_unit)?)
+                    }
+                    (Err(_), CostRateBasis::Time(_)) => {
+                        CostRateBasis::Time(TimeUnit::from_str(unit).map_err(invalid_unit)?)
+                    }
+                    (Err(_), CostRateBasis::Energy(_)) => {
+                        CostRateBasis::Energy(EnergyUnit::from_str(unit).map_err(invalid_unit)?)
+                    }
+                };
+                let rate = rate_basis
+                    .convert(&CostRate::new(*rate), &feature_basis)
+                    .ok_or_else(|| {
+                        CostError::InvalidConfiguration(format!(
+                            "unit rate with unit '{}' does not match state feature {:?}",
+                            unit, feature
+                        ))
+                    })?;
+                Ok(VehicleCostRate::Monetary { rate })
+            }
+            VehicleCostRate::Combined(rates) => {
+                let converted = rates
+                    .iter()
+                    .map(|r| r.with_feature_units(feature))
+                    .collect::<Result<Vec<_>, CostError>>()?;
+                Ok(VehicleCostRate::Combined(converted))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+/// interpolates between points sorted by x, extrapolating from the end segments.
+/// assumes at least two points.
+fn piecewise_linear(points: &[(f64, f64)], x: f64) -> f64 {
+    if points.len() < 2 {
+        return points.first().map(|(_, y)| *y).unwrap_or_default();
+    }
+    let segment = points
+        .windows(2)
+        .find(|w| x <= w[1].0)
+        .unwrap_or(&points[points.len() - 2..]);
+    let (x0, y0) = segment[0];
+    let (x1, y1) = segment[1];
+    y0 + (x - x0) * (y1 - y0) / (x1 - x0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::unit::{Distance, Energy, Time};
+
+    #[test]
+    fn test_unit_rate_with_feature_units() {
+        // $20 per hour applied to a time feature in minutes
+        let rate = VehicleCostRate::UnitRate {
+            rate: 20.0,
+            unit: String::from("hours"),
+        };
+        let feature = StateFeature::Time {
+            time_unit: TimeUnit::Minutes,
+            initial: Time::ZERO,
+        };
+        let converted = rate.with_feature_units(&feature).unwrap();
+        let cost = converted.map_value(StateVar(30.0));
+        assert!((cost.as_f64() - 10.0).abs() < 1e-6);
+
+        let feature = StateFeature::Distance {
+            distance_unit: DistanceUnit::Kilometers,
+            initial: Distance::ZERO,
+        };
+        assert!(rate.with_feature_units(&feature).is_err());
+
+        // $0.50 per kilowatt hour given as a cost rate unit, applied to megajoules
+        let rate = VehicleCostRate::UnitRate {
+            rate: 0.5,
+            unit: String::from("dollars_per_kilowatt_hour"),
+        };
+        let feature = StateFeature::Energy {
+            energy_unit: EnergyUnit::Megajoules,
+            initial: Energy::ZERO,
+        };
+        let converted = rate.with_feature_units(&feature).unwrap();
+        let cost = converted.map_value(StateVar(3.6));
+        assert!((cost.as_f64() - 0.5).abs() < 1e-6);
+
+        // the price of electricity is not the price of gasoline
+        let feature = StateFeature::Energy {
+            energy_unit: EnergyUnit::GallonsGasoline,
+            initial: Energy::ZERO,
+        };
+        assert!(rate.with_feature_units(&feature).is_err());
+
+        // $3.12 per gallon of gasoline applied to liters of gasoline
+        let rate = VehicleCostRate::UnitRate {
+            rate: 3.12,
+            unit: String::from("dollars_per_gallon_gasoline"),
+        };
+        let feature = StateFeature::Energy {
+            energy_unit: EnergyUnit::LitersGasoline,
+            initial: Energy::ZERO,
+        };
+        let converted = rate.with_feature_units(&feature).unwrap();
+        let cost = converted.map_value(StateVar(3.785411784));
+        assert!((cost.as_f64() - 3.12).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nonlinear_rates() {
+        // no penalty up to 8 hours, then 10 per hour over 8 hours
+        let piecewise = VehicleCostRate::PiecewiseLinear {
+            points: vec![(0.0, 0.0), (8.0, 8.0), (9.0, 18.0)],
+        };
+        piecewise.validate().unwrap();
+        for (x, expected) in [(-1.0, -1.0), (4.0, 4.0), (8.5, 13.0), (10.0, 28.0)] {
+            let cost = piecewise.map_value(StateVar(x));
+            assert!((cost.as_f64() - expected).abs() < 1e-9, "{} -> {}", x, cost);
+        }
+        let unsorted = VehicleCostRate::PiecewiseLinear {
+            points: vec![(1.0, 0.0), (0.0, 1.0)],
+        };
+        assert!(unsorted.validate().is_err());
+
+        let concave = VehicleCostRate::PiecewiseLinear {
+            points: vec![(0.0, 0.0), (8.0, 16.0), (9.0, 17.0)],
+        };
+        assert!(concave.validate().is_err());
+        let offset = VehicleCostRate::PiecewiseLinear {
+            points: vec![(0.0, 1.0), (1.0, 2.0)],
+        };
+        assert!(offset.validate().is_err());
+
+        let polynomial = VehicleCostRate::Polynomial {
+            coefficients: vec![0.0, 2.0, 3.0],
+        };
+        polynomial.validate().unwrap();
+        let cost = polynomial.map_value(StateVar(2.0));
+        assert!((cost.as_f64() - 16.0).abs() < 1e-9);
+        let constant = VehicleCostRate::Polynomial {
+            coefficients: vec![1.0, 2.0, 3.0],
+        };
+        assert!(constant.validate().is_err());
+        let negative = VehicleCostRate::Polynomial {
+            coefficients: vec![0.0, 2.0, -3.0],
+        };
+        assert!(negative.validate().is_err());
+    }
 }
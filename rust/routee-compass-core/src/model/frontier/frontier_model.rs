@@ -31,4 +31,25 @@ pub trait FrontierModel: Send + Sync {
     ) -> Result<bool, FrontierModelError> {
         Ok(true)
     }
+
+    /// Validates the result of traversing an edge before the traversal is added
+    /// to the search. Use this for restrictions that depend on the state at the
+    /// end of the edge, such as a minimum battery state of charge.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - the edge that was traversed
+    /// * `result_state` - the state of the traversal at the end of this edge
+    ///
+    /// # Returns
+    ///
+    /// True if the traversal is valid, false otherwise; Or, an error from processing
+    fn valid_traversal(
+        &self,
+        _edge: &Edge,
+        _result_state: &[StateVar],
+        _state_model: &StateModel,
+    ) -> Result<bool, FrontierModelError> {
+        Ok(true)
+    }
 }
@@ -1,18 +1,21 @@
 use std::sync::Arc;
 
+use crate::model::road_network::graph::Graph;
+
 use super::{
     frontier_model_error::FrontierModelError, frontier_model_service::FrontierModelService,
 };
 
 /// A [`FrontierModelBuilder`] takes a JSON object describing the configuration of a
-/// frontier model and builds a [FrontierModel].
+/// frontier model and builds a [`FrontierModelService`], which in turn builds a
+/// [FrontierModel] for each query.
 ///
 /// A [`FrontierModelBuilder`] instance should be an empty struct that implements
 /// this trait.
 ///
-/// [FrontierModel]: routee_compass_core::model::frontier::frontier_model::FrontierModel
+/// [FrontierModel]: crate::model::frontier::frontier_model::FrontierModel
 pub trait FrontierModelBuilder {
-    /// Builds a [FrontierModel] from JSON configuration.
+    /// Builds a [`FrontierModelService`] from JSON configuration.
     ///
     /// # Arguments
     ///
@@ -20,11 +23,38 @@ pub trait FrontierModelBuilder {
     ///
     /// # Returns
     ///
-    /// A [FrontierModel] designed to persist the duration of the CompassApp.
-    ///
-    /// [FrontierModel]: routee_compass_core::model::frontier::frontier_model::FrontierModel
+    /// A [`FrontierModelService`] designed to persist the duration of the CompassApp.
     fn build(
         &self,
         parameters: &serde_json::Value,
     ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError>;
+
+    /// Builds a [`FrontierModelService`] which may read the road network graph of the
+    /// app, such as its edge count or its edge attributes. by default, the graph is not
+    /// used and this is the same as [`FrontierModelBuilder::build`].
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - the contents of the "frontier" TOML config section
+    /// * `graph` - the road network graph loaded by the app
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        _graph: Arc<Graph>,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        self.build(parameters)
+    }
+
+    /// the keys this builder reads from its configuration section, other than `type`.
+    /// used to warn about unknown keys, such as misspellings, before any data loads.
+    /// builders which return `None` are not checked.
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// the keys which must be present in this builder's configuration section, which
+    /// are also listed by `config_keys`. a missing key is an error before any data loads.
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec![]
+    }
 }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::model::state::state_model::StateModel;
+use crate::model::{road_network::edge_id::EdgeId, state::state_model::StateModel};
 
 use super::{frontier_model::FrontierModel, frontier_model_error::FrontierModelError};
 
@@ -11,7 +11,7 @@ use super::{frontier_model::FrontierModel, frontier_model_error::FrontierModelEr
 /// [`FrontierModelService`] must be read across the thread pool and so it implements
 /// Send and Sync.
 ///
-/// [FrontierModel]: routee_compass_core::model::traversal::traversal_model::FrontierModel
+/// [FrontierModel]: crate::model::frontier::frontier_model::FrontierModel
 pub trait FrontierModelService: Send + Sync {
     /// Builds a [FrontierModel] for the incoming query, used as parameters for this
     /// build operation.
@@ -27,10 +27,17 @@ pub trait FrontierModelService: Send + Sync {
     ///
     /// The [FrontierModel] instance for this query, or an error
     ///
-    /// [FrontierModel]: routee_compass_core::model::traversal::traversal_model::FrontierModel
+    /// [FrontierModel]: crate::model::frontier::frontier_model::FrontierModel
     fn build(
         &self,
         query: &serde_json::Value,
         state_model: Arc<StateModel>,
     ) -> Result<Arc<dyn FrontierModel>, FrontierModelError>;
+
+    /// The road class of an edge, for services which restrict travel by road class,
+    /// such as for inspecting the network from the language bindings. Returns None
+    /// by default.
+    fn edge_road_class(&self, _edge_id: EdgeId) -> Result<Option<u8>, FrontierModelError> {
+        Ok(None)
+    }
 }
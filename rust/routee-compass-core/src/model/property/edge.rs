@@ -6,7 +6,9 @@ use serde::{Deserialize, Serialize};
 /// represents a single edge in a Graph.
 /// this struct implements Serialize and Deserialize to support reading
 /// edge records from CSV files.
+/// the layout is fixed so that compiled graph files can be viewed in place.
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, Allocative)]
+#[repr(C)]
 pub struct Edge {
     pub edge_id: EdgeId,
     pub src_vertex_id: VertexId,
@@ -0,0 +1,27 @@
+use super::edge_attribute_store::EdgeAttributeColumnType;
+use crate::model::road_network::edge_id::EdgeId;
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EdgeAttributeError {
+    #[error("edge attribute column {0} not found, available columns: {1}")]
+    ColumnNotFound(String, String),
+    #[error("edge attribute column {column} has type {found}, expected {expected}")]
+    WrongType {
+        column: String,
+        expected: EdgeAttributeColumnType,
+        found: EdgeAttributeColumnType,
+    },
+    #[error("edge {0} missing from edge attribute column {1}")]
+    MissingIndex(EdgeId, String),
+    #[error("failure reading edge attributes from {0:?}: {1}")]
+    ReadError(PathBuf, String),
+    #[error("row {row} of {file:?} has invalid {column_type} value '{value}' for column {column}")]
+    ParseError {
+        file: PathBuf,
+        row: usize,
+        column: String,
+        column_type: EdgeAttributeColumnType,
+        value: String,
+    },
+}
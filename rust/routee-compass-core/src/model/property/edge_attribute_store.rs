@@ -0,0 +1,558 @@
+use super::edge_attribute_error::EdgeAttributeError;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::util::fs::read_utils;
+use allocative::Allocative;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+/// the type of the values of an edge attribute column
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Allocative)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeAttributeColumnType {
+    F64,
+    I64,
+    Bool,
+    /// text values from a small set, such as a surface type, stored as an index
+    /// into the set of distinct values
+    Categorical,
+}
+
+impl Display for EdgeAttributeColumnType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            EdgeAttributeColumnType::F64 => "f64",
+            EdgeAttributeColumnType::I64 => "i64",
+            EdgeAttributeColumnType::Bool => "bool",
+            EdgeAttributeColumnType::Categorical => "categorical",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// a column to load into an [`EdgeAttributeStore`]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EdgeAttributeColumnConfig {
+    /// name used to look up the column
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: EdgeAttributeColumnType,
+    /// name of the column in the file, if different from `name`
+    #[serde(default)]
+    pub source_column: Option<String>,
+    /// value for edges missing from the file or with an empty value. without a
+    /// default, a missing value is an error.
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+}
+
+/// values of one column for every edge, indexed by `EdgeId`
+#[derive(Debug, Clone, Allocative)]
+pub enum EdgeAttributeColumn {
+    F64(Box<[f64]>),
+    I64(Box<[i64]>),
+    Bool(Box<[bool]>),
+    Categorical {
+        categories: Box<[String]>,
+        codes: Box<[u32]>,
+    },
+}
+
+/// the value of an edge attribute column for one edge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeAttributeValue<'a> {
+    F64(f64),
+    I64(i64),
+    Bool(bool),
+    Categorical(&'a str),
+}
+
+impl EdgeAttributeColumn {
+    pub fn column_type(&self) -> EdgeAttributeColumnType {
+        match self {
+            EdgeAttributeColumn::F64(_) => EdgeAttributeColumnType::F64,
+            EdgeAttributeColumn::I64(_) => EdgeAttributeColumnType::I64,
+            EdgeAttributeColumn::Bool(_) => EdgeAttributeColumnType::Bool,
+            EdgeAttributeColumn::Categorical { .. } => EdgeAttributeColumnType::Categorical,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            EdgeAttributeColumn::F64(values) => values.len(),
+            EdgeAttributeColumn::I64(values) => values.len(),
+            EdgeAttributeColumn::Bool(values) => values.len(),
+            EdgeAttributeColumn::Categorical { codes, .. } => codes.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, edge_id: EdgeId) -> Option<EdgeAttributeValue<'_>> {
+        let idx = edge_id.as_usize();
+        match self {
+            EdgeAttributeColumn::F64(values) => {
+                values.get(idx).map(|v| EdgeAttributeValue::F64(*v))
+            }
+            EdgeAttributeColumn::I64(values) => {
+                values.get(idx).map(|v| EdgeAttributeValue::I64(*v))
+            }
+            EdgeAttributeColumn::Bool(values) => {
+                values.get(idx).map(|v| EdgeAttributeValue::Bool(*v))
+            }
+            EdgeAttributeColumn::Categorical { categories, codes } => codes
+                .get(idx)
+                .map(|c| EdgeAttributeValue::Categorical(&categories[*c as usize])),
+        }
+    }
+}
+
+/// named columns of per-edge data, such as a surface type or a toll flag, loaded from
+/// a CSV or Parquet file so that models can read a new per-edge dataset by column name
+/// instead of each needing its own loader.
+///
+/// the file has one row per edge. when it has an `edge_id` column, each row is placed
+/// at that edge id, otherwise rows are read in edge id order. a store may be loaded
+/// with the graph, and is then shared by every model through [`Graph::edge_attributes`].
+///
+/// [`Graph::edge_attributes`]: crate::model::road_network::graph::Graph::edge_attributes
+#[derive(Debug, Clone, Allocative)]
+pub struct EdgeAttributeStore {
+    columns: HashMap<String, EdgeAttributeColumn>,
+}
+
+impl EdgeAttributeStore {
+    /// column holding the edge id of each row, if present
+    pub const EDGE_ID_COLUMN: &'static str = "edge_id";
+
+    /// loads columns from a file, read as Parquet when its extension is `.parquet` and
+    /// as a CSV with a header row otherwise, which may be gzip or zstd compressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `filepath` - the attribute file
+    /// * `columns` - the columns to load
+    /// * `n_edges` - number of edges in the graph. a row for an edge id outside of the
+    ///   graph, or a second row for the same edge, is an error.
+    pub fn from_file<P: AsRef<Path>>(
+        filepath: &P,
+        columns: &[EdgeAttributeColumnConfig],
+        n_edges: usize,
+    ) -> Result<EdgeAttributeStore, EdgeAttributeError> {
+        let path = filepath.as_ref();
+        let sources = columns
+            .iter()
+            .map(|c| c.source_column.clone().unwrap_or_else(|| c.name.clone()))
+            .collect::<Vec<_>>();
+        let rows = read_rows(path, &sources)?;
+        let mut seen = vec![false; n_edges];
+        for (edge_id, _) in rows.iter() {
+            let row_error = |msg: String| EdgeAttributeError::ReadError(path.to_path_buf(), msg);
+            match seen.get_mut(*edge_id) {
+                None => {
+                    return Err(row_error(format!(
+                        "edge id {} is out of range for a graph with {} edges",
+                        edge_id, n_edges
+                    )))
+                }
+                Some(true) => {
+                    return Err(row_error(format!(
+                        "edge id {} appears in more than one row",
+                        edge_id
+                    )))
+                }
+                Some(seen) => *seen = true,
+            }
+        }
+
+        let mut loaded = HashMap::new();
+        for (col_idx, config) in columns.iter().enumerate() {
+            let mut values: Vec<Option<&Value>> = vec![None; n_edges];
+            for (edge_id, row) in rows.iter() {
+                values[*edge_id] = row.get(col_idx).filter(|v| !v.is_null());
+            }
+            let column = build_column(path, config, &values)?;
+            loaded.insert(config.name.clone(), column);
+        }
+        Ok(EdgeAttributeStore { columns: loaded })
+    }
+
+    pub fn column(&self, name: &str) -> Result<&EdgeAttributeColumn, EdgeAttributeError> {
+        self.columns.get(name).ok_or_else(|| {
+            EdgeAttributeError::ColumnNotFound(
+                name.to_string(),
+                self.columns.keys().sorted().join(", "),
+            )
+        })
+    }
+
+    pub fn column_names(&self) -> impl Iterator<Item = &String> {
+        self.columns.keys()
+    }
+
+    pub fn get(
+        &self,
+        name: &str,
+        edge_id: EdgeId,
+    ) -> Result<EdgeAttributeValue<'_>, EdgeAttributeError> {
+        self.column(name)?
+            .get(edge_id)
+            .ok_or_else(|| EdgeAttributeError::MissingIndex(edge_id, name.to_string()))
+    }
+
+    pub fn get_f64(&self, name: &str, edge_id: EdgeId) -> Result<f64, EdgeAttributeError> {
+        match self.get(name, edge_id)? {
+            EdgeAttributeValue::F64(v) => Ok(v),
+            _ => Err(self.wrong_type(name, EdgeAttributeColumnType::F64)),
+        }
+    }
+
+    pub fn get_i64(&self, name: &str, edge_id: EdgeId) -> Result<i64, EdgeAttributeError> {
+        match self.get(name, edge_id)? {
+            EdgeAttributeValue::I64(v) => Ok(v),
+            _ => Err(self.wrong_type(name, EdgeAttributeColumnType::I64)),
+        }
+    }
+
+    pub fn get_bool(&self, name: &str, edge_id: EdgeId) -> Result<bool, EdgeAttributeError> {
+        match self.get(name, edge_id)? {
+            EdgeAttributeValue::Bool(v) => Ok(v),
+            _ => Err(self.wrong_type(name, EdgeAttributeColumnType::Bool)),
+        }
+    }
+
+    pub fn get_categorical(&self, name: &str, edge_id: EdgeId) -> Result<&str, EdgeAttributeError> {
+        match self.get(name, edge_id)? {
+            EdgeAttributeValue::Categorical(v) => Ok(v),
+            _ => Err(self.wrong_type(name, EdgeAttributeColumnType::Categorical)),
+        }
+    }
+
+    fn wrong_type(&self, name: &str, expected: EdgeAttributeColumnType) -> EdgeAttributeError {
+        match self.columns.get(name) {
+            Some(column) => EdgeAttributeError::WrongType {
+                column: name.to_string(),
+                expected,
+                found: column.column_type(),
+            },
+            None => EdgeAttributeError::ColumnNotFound(name.to_string(), String::new()),
+        }
+    }
+}
+
+/// a row of an attribute file: its edge id and the value of each requested column,
+/// which is text for a CSV file and typed for a Parquet file
+type AttributeRow = (usize, Vec<Value>);
+
+fn read_rows(path: &Path, sources: &[String]) -> Result<Vec<AttributeRow>, EdgeAttributeError> {
+    let read_error =
+        |e: &dyn Display| EdgeAttributeError::ReadError(path.to_path_buf(), e.to_string());
+    let (headers, records) = read_utils::read_value_records(path).map_err(|e| read_error(&e))?;
+    let find = |name: &str| headers.iter().position(|h| h == name);
+    let edge_id_idx = find(EdgeAttributeStore::EDGE_ID_COLUMN);
+    let indices = sources
+        .iter()
+        .map(|s| find(s).ok_or_else(|| column_missing(s, &headers)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rows = vec![];
+    for (row_idx, record) in records.enumerate() {
+        let mut record = record.map_err(|e| read_error(&e))?;
+        let edge_id = match edge_id_idx {
+            None => row_idx,
+            Some(idx) => parse_edge_id(path, row_idx, record.get(idx))?,
+        };
+        let values = indices
+            .iter()
+            .map(|idx| record.get_mut(*idx).map(Value::take).unwrap_or_default())
+            .collect();
+        rows.push((edge_id, values));
+    }
+    Ok(rows)
+}
+
+fn column_missing(name: &str, available: &[String]) -> EdgeAttributeError {
+    EdgeAttributeError::ColumnNotFound(name.to_string(), available.join(", "))
+}
+
+/// reads an integer from a number or from text, also accepting whole numbers written
+/// as floats, such as "3.0", since tables written from dataframes often store integer
+/// columns as floats
+fn parse_integer(value: &Value) -> Option<i64> {
+    let from_float = |v: f64| (v.fract() == 0.0 && v.abs() < i64::MAX as f64).then_some(v as i64);
+    match value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().and_then(from_float)),
+        Value::String(s) => s
+            .parse::<i64>()
+            .ok()
+            .or_else(|| s.parse::<f64>().ok().and_then(from_float)),
+        _ => None,
+    }
+}
+
+fn parse_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+fn parse_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(1) => Some(true),
+            Some(0) => Some(false),
+            _ => None,
+        },
+        Value::String(s) => match s.to_lowercase().as_str() {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// the text of a value, for error messages and categories
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_edge_id(
+    path: &Path,
+    row: usize,
+    value: Option<&Value>,
+) -> Result<usize, EdgeAttributeError> {
+    let edge_id = value
+        .and_then(parse_integer)
+        .and_then(|v| usize::try_from(v).ok());
+    edge_id.ok_or_else(|| EdgeAttributeError::ParseError {
+        file: path.to_path_buf(),
+        row,
+        column: EdgeAttributeStore::EDGE_ID_COLUMN.to_string(),
+        column_type: EdgeAttributeColumnType::I64,
+        value: value.map(value_text).unwrap_or_default(),
+    })
+}
+
+fn build_column(
+    path: &Path,
+    config: &EdgeAttributeColumnConfig,
+    values: &[Option<&Value>],
+) -> Result<EdgeAttributeColumn, EdgeAttributeError> {
+    let values = values
+        .iter()
+        .enumerate()
+        .map(|(row, value)| {
+            value
+                .or(config.default.as_ref())
+                .map(|v| (row, v))
+                .ok_or_else(|| EdgeAttributeError::MissingIndex(EdgeId(row), config.name.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let parse_error = |row: usize, value: &Value| EdgeAttributeError::ParseError {
+        file: path.to_path_buf(),
+        row,
+        column: config.name.clone(),
+        column_type: config.column_type,
+        value: value_text(value),
+    };
+    let column = match config.column_type {
+        EdgeAttributeColumnType::F64 => EdgeAttributeColumn::F64(
+            values
+                .iter()
+                .map(|(row, v)| parse_f64(v).ok_or_else(|| parse_error(*row, v)))
+                .collect::<Result<_, _>>()?,
+        ),
+        EdgeAttributeColumnType::I64 => EdgeAttributeColumn::I64(
+            values
+                .iter()
+                .map(|(row, v)| parse_integer(v).ok_or_else(|| parse_error(*row, v)))
+                .collect::<Result<_, _>>()?,
+        ),
+        EdgeAttributeColumnType::Bool => EdgeAttributeColumn::Bool(
+            values
+                .iter()
+                .map(|(row, v)| parse_bool(v).ok_or_else(|| parse_error(*row, v)))
+                .collect::<Result<_, _>>()?,
+        ),
+        EdgeAttributeColumnType::Categorical => {
+            let mut categories: Vec<String> = vec![];
+            let mut lookup: HashMap<String, u32> = HashMap::new();
+            let mut codes = Vec::with_capacity(values.len());
+            for (_, v) in values.iter() {
+                let text = value_text(v);
+                let code = match lookup.get(&text) {
+                    Some(code) => *code,
+                    None => {
+                        let code = categories.len() as u32;
+                        categories.push(text.clone());
+                        lookup.insert(text, code);
+                        code
+                    }
+                };
+                codes.push(code);
+            }
+            EdgeAttributeColumn::Categorical {
+                categories: categories.into_boxed_slice(),
+                codes: codes.into_boxed_slice(),
+            }
+        }
+    };
+    Ok(column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, column_type: EdgeAttributeColumnType) -> EdgeAttributeColumnConfig {
+        EdgeAttributeColumnConfig {
+            name: name.to_string(),
+            column_type,
+            source_column: None,
+            default: None,
+        }
+    }
+
+    #[test]
+    fn test_from_csv_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("edge-attributes.csv");
+        std::fs::write(
+            &path,
+            "edge_id,toll,surface,lanes,max_weight\n2,false,gravel,1,\n0,true,paved,2,10.5\n1,0,paved,3,20\n",
+        )
+        .unwrap();
+        let mut max_weight = column("max_weight", EdgeAttributeColumnType::F64);
+        max_weight.default = Some(serde_json::json!(40.0));
+        let mut lane_count = column("lane_count", EdgeAttributeColumnType::I64);
+        lane_count.source_column = Some(String::from("lanes"));
+        let columns = vec![
+            column("toll", EdgeAttributeColumnType::Bool),
+            column("surface", EdgeAttributeColumnType::Categorical),
+            lane_count,
+            max_weight,
+        ];
+        let store = EdgeAttributeStore::from_file(&path, &columns, 3).expect("should load");
+
+        assert!(store.get_bool("toll", EdgeId(0)).unwrap());
+        assert!(!store.get_bool("toll", EdgeId(1)).unwrap());
+        assert_eq!(
+            store.get_categorical("surface", EdgeId(2)).unwrap(),
+            "gravel"
+        );
+        assert_eq!(store.get_i64("lane_count", EdgeId(1)).unwrap(), 3);
+        assert_eq!(store.get_f64("max_weight", EdgeId(2)).unwrap(), 40.0);
+        assert!(matches!(
+            store.get_f64("surface", EdgeId(0)),
+            Err(EdgeAttributeError::WrongType { .. })
+        ));
+        assert!(matches!(
+            store.get("toll", EdgeId(3)),
+            Err(EdgeAttributeError::MissingIndex(..))
+        ));
+
+        let bad_columns = vec![column("surface", EdgeAttributeColumnType::F64)];
+        let result = EdgeAttributeStore::from_file(&path, &bad_columns, 3);
+        assert!(matches!(result, Err(EdgeAttributeError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_edge_ids_must_match_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("edge-attributes.csv");
+        let columns = vec![column("toll", EdgeAttributeColumnType::Bool)];
+
+        std::fs::write(&path, "edge_id,toll\n0,true\n1,false\n").unwrap();
+        // an edge of the graph without a row and without a default
+        let result = EdgeAttributeStore::from_file(&path, &columns, 3);
+        assert!(matches!(result, Err(EdgeAttributeError::MissingIndex(..))));
+        // a row for an edge outside of the graph
+        let result = EdgeAttributeStore::from_file(&path, &columns, 1);
+        assert!(matches!(result, Err(EdgeAttributeError::ReadError(..))));
+
+        std::fs::write(&path, "edge_id,toll\n0,true\n1,false\n0,false\n").unwrap();
+        let result = EdgeAttributeStore::from_file(&path, &columns, 2);
+        assert!(matches!(result, Err(EdgeAttributeError::ReadError(..))));
+    }
+
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_from_parquet_file() {
+        use parquet::{
+            data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type},
+            file::{properties::WriterProperties, writer::SerializedFileWriter},
+            schema::parser::parse_message_type,
+        };
+        use std::sync::Arc;
+
+        let schema = "message edges {
+            REQUIRED INT64 edge_id;
+            REQUIRED BOOLEAN toll;
+            REQUIRED BINARY surface (UTF8);
+            OPTIONAL DOUBLE lanes;
+        }";
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("edge-attributes.parquet");
+        let file = std::fs::File::create(&path).unwrap();
+        let schema = Arc::new(parse_message_type(schema).unwrap());
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        let mut writer_column = row_group.next_column().unwrap().unwrap();
+        writer_column
+            .typed::<Int64Type>()
+            .write_batch(&[1, 0], None, None)
+            .unwrap();
+        writer_column.close().unwrap();
+        let mut writer_column = row_group.next_column().unwrap().unwrap();
+        writer_column
+            .typed::<BoolType>()
+            .write_batch(&[true, false], None, None)
+            .unwrap();
+        writer_column.close().unwrap();
+        let mut writer_column = row_group.next_column().unwrap().unwrap();
+        let surfaces = [ByteArray::from("gravel"), ByteArray::from("paved")];
+        writer_column
+            .typed::<ByteArrayType>()
+            .write_batch(&surfaces, None, None)
+            .unwrap();
+        writer_column.close().unwrap();
+        // lanes are stored as floats, as dataframes store integer columns with nulls,
+        // and edge 0 has no value
+        let mut writer_column = row_group.next_column().unwrap().unwrap();
+        writer_column
+            .typed::<DoubleType>()
+            .write_batch(&[2.0], Some(&[1, 0]), None)
+            .unwrap();
+        writer_column.close().unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        let mut lanes = column("lanes", EdgeAttributeColumnType::I64);
+        lanes.default = Some(serde_json::json!(1));
+        let columns = vec![
+            column("toll", EdgeAttributeColumnType::Bool),
+            column("surface", EdgeAttributeColumnType::Categorical),
+            lanes,
+        ];
+        let store = EdgeAttributeStore::from_file(&path, &columns, 2).expect("should load");
+        assert!(store.get_bool("toll", EdgeId(1)).unwrap());
+        assert!(!store.get_bool("toll", EdgeId(0)).unwrap());
+        assert_eq!(
+            store.get_categorical("surface", EdgeId(1)).unwrap(),
+            "gravel"
+        );
+        assert_eq!(store.get_i64("lanes", EdgeId(1)).unwrap(), 2);
+        assert_eq!(store.get_i64("lanes", EdgeId(0)).unwrap(), 1);
+    }
+}
@@ -1,2 +1,4 @@
 pub mod edge;
+pub mod edge_attribute_error;
+pub mod edge_attribute_store;
 pub mod vertex;
@@ -2,11 +2,14 @@ use crate::{model::road_network::vertex_id::VertexId, util::geo::coord::Internal
 use allocative::Allocative;
 use geo::coord;
 use serde::de;
+use std::{fmt::Display, str::FromStr};
 
 /// represents a vertex in a Graph
 /// this struct implements Serialize and Deserialize to support reading
 /// vertex records from CSV files.
+/// the layout is fixed so that compiled graph files can be viewed in place.
 #[derive(Copy, Clone, Default, Debug, Allocative)]
+#[repr(C)]
 pub struct Vertex {
     pub vertex_id: VertexId,
     pub coordinate: InternalCoord<f32>,
@@ -62,36 +65,29 @@ impl<'de> de::Deserialize<'de> for Vertex {
                 let mut x_result: Option<f32> = None;
                 let mut y_result: Option<f32> = None;
                 let mut vertex_result: Option<Vertex> = None;
-                let mut next: Option<(&str, &str)> = map.next_entry()?;
+                let mut next: Option<(&str, VertexField)> = map.next_entry()?;
                 while next.is_some() {
                     // match on expected key names
                     if let Some((key, value)) = next {
                         match key {
                             VERTEX_ID => {
-                                let id: usize = value.parse().map_err(|e| {
-                                    de::Error::custom(format!(
-                                        "unable to parse vertex_id '{}': {}",
-                                        &value, e
-                                    ))
-                                })?;
+                                let id: usize = value
+                                    .parse(key, |v| {
+                                        (v.fract() == 0.0 && v >= 0.0).then_some(v as usize)
+                                    })
+                                    .map_err(de::Error::custom)?;
                                 vertex_id_result = Some(id);
                             }
                             X_COORDINATE => {
-                                let x_coord: f32 = value.parse().map_err(|e| {
-                                    de::Error::custom(format!(
-                                        "unable to parse x '{}': {}",
-                                        &value, e
-                                    ))
-                                })?;
+                                let x_coord: f32 = value
+                                    .parse(key, |v| Some(v as f32))
+                                    .map_err(de::Error::custom)?;
                                 x_result = Some(x_coord);
                             }
                             Y_COORDINATE => {
-                                let y_coord: f32 = value.parse().map_err(|e| {
-                                    de::Error::custom(format!(
-                                        "unable to parse y '{}': {}",
-                                        &value, e
-                                    ))
-                                })?;
+                                let y_coord: f32 = value
+                                    .parse(key, |v| Some(v as f32))
+                                    .map_err(de::Error::custom)?;
                                 y_result = Some(y_coord);
                             }
                             &_ => {} // ignore unknown key/value pairs
@@ -123,6 +119,83 @@ impl<'de> de::Deserialize<'de> for Vertex {
     }
 }
 
+/// the value of a vertex field, which is text in a CSV file and may be a typed
+/// number in a Parquet file. unknown fields of any type are read and ignored.
+enum VertexField<'a> {
+    Text(&'a str),
+    Number(f64),
+    Other,
+}
+
+impl VertexField<'_> {
+    /// parses text as T, or converts a number with `from_number`
+    fn parse<T: FromStr>(
+        &self,
+        key: &str,
+        from_number: impl Fn(f64) -> Option<T>,
+    ) -> Result<T, String>
+    where
+        T::Err: Display,
+    {
+        match self {
+            VertexField::Text(text) => text
+                .parse()
+                .map_err(|e| format!("unable to parse {} '{}': {}", key, text, e)),
+            VertexField::Number(number) => {
+                from_number(*number).ok_or_else(|| format!("unable to parse {} '{}'", key, number))
+            }
+            VertexField::Other => Err(format!("unable to parse {}, expected a number", key)),
+        }
+    }
+}
+
+impl<'de> de::Deserialize<'de> for VertexField<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct FieldVisitor;
+
+        impl<'de> de::Visitor<'de> for FieldVisitor {
+            type Value = VertexField<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a number or text")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(VertexField::Text(v))
+            }
+
+            fn visit_str<E: de::Error>(self, _v: &str) -> Result<Self::Value, E> {
+                Ok(VertexField::Other)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(VertexField::Number(v as f64))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(VertexField::Number(v as f64))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(VertexField::Number(v))
+            }
+
+            fn visit_bool<E: de::Error>(self, _v: bool) -> Result<Self::Value, E> {
+                Ok(VertexField::Other)
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(VertexField::Other)
+            }
+        }
+
+        deserializer.deserialize_any(FieldVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,74 @@
+use super::{edge_id::EdgeId, records::Records, vertex_id::VertexId};
+use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+use allocative::Allocative;
+
+/// the edges incident to each vertex of a graph in one direction, in edge id order.
+#[derive(Debug, Allocative)]
+pub enum Adjacency {
+    /// for each vertex, a map from each incident edge to the vertex at its other end
+    Map(Box<[CompactOrderedHashMap<EdgeId, VertexId>]>),
+    /// compressed sparse rows, where the incident edges of vertex `v` are
+    /// `edge_ids[offsets[v]..offsets[v + 1]]`, as stored in a compiled graph file
+    Csr {
+        offsets: Records<u64>,
+        edge_ids: Records<EdgeId>,
+    },
+}
+
+impl Adjacency {
+    /// number of vertices in the adjacency
+    pub fn len(&self) -> usize {
+        match self {
+            Adjacency::Map(maps) => maps.len(),
+            Adjacency::Csr { offsets, .. } => offsets.len().saturating_sub(1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the edges incident to a vertex, or None if the vertex is not in the adjacency
+    pub fn edge_ids(&self, vertex_id: VertexId) -> Option<Box<dyn Iterator<Item = &EdgeId> + '_>> {
+        match self {
+            Adjacency::Map(maps) => maps.get(vertex_id.0).map(|m| m.keys()),
+            Adjacency::Csr { .. } => self
+                .csr_row(vertex_id)
+                .map(|row| Box::new(row.iter()) as Box<dyn Iterator<Item = &EdgeId>>),
+        }
+    }
+
+    /// the number of edges incident to a vertex, or None if the vertex is not in the adjacency
+    pub fn degree(&self, vertex_id: VertexId) -> Option<usize> {
+        match self {
+            Adjacency::Map(maps) => maps.get(vertex_id.0).map(|m| m.len()),
+            Adjacency::Csr { .. } => self.csr_row(vertex_id).map(|row| row.len()),
+        }
+    }
+
+    /// the row of a vertex in compressed sparse rows. offsets are read from the file
+    /// of a compiled graph without being validated, so a row which is out of bounds
+    /// is treated as missing.
+    fn csr_row(&self, vertex_id: VertexId) -> Option<&[EdgeId]> {
+        match self {
+            Adjacency::Map(_) => None,
+            Adjacency::Csr { offsets, edge_ids } => {
+                let start = *offsets.get(vertex_id.0)?;
+                let end = *offsets.get(vertex_id.0.checked_add(1)?)?;
+                edge_ids.get(usize::try_from(start).ok()?..usize::try_from(end).ok()?)
+            }
+        }
+    }
+}
+
+impl From<Box<[CompactOrderedHashMap<EdgeId, VertexId>]>> for Adjacency {
+    fn from(maps: Box<[CompactOrderedHashMap<EdgeId, VertexId>]>) -> Self {
+        Adjacency::Map(maps)
+    }
+}
+
+impl From<Vec<CompactOrderedHashMap<EdgeId, VertexId>>> for Adjacency {
+    fn from(maps: Vec<CompactOrderedHashMap<EdgeId, VertexId>>) -> Self {
+        Adjacency::Map(maps.into_boxed_slice())
+    }
+}
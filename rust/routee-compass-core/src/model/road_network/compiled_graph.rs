@@ -0,0 +1,387 @@
+//! a binary graph format which is memory-mapped and read in place at startup
+//! instead of parsed from text.
+//!
+//! the file is a fixed-size header followed by fixed-width vertex and edge
+//! records and the forward and reverse adjacency as compressed sparse rows,
+//! all little-endian:
+//!
+//! | section         | layout                                                                 |
+//! |-----------------|------------------------------------------------------------------------|
+//! | header          | magic `b"CMPSGRPH"`, version `u32`, reserved `u32`, n_vertices `u64`, n_edges `u64` |
+//! | vertices        | vertex_id `u64`, x `f32`, y `f32`                                      |
+//! | edges           | edge_id `u64`, src_vertex_id `u64`, dst_vertex_id `u64`, distance `f64` |
+//! | out offsets     | n_vertices + 1 `u64`, the out edges of vertex `v` are entries `offsets[v]..offsets[v + 1]` |
+//! | out edges       | edge_id `u64` of each edge, grouped by source vertex                  |
+//! | in offsets      | n_vertices + 1 `u64`, as the out offsets                               |
+//! | in edges        | edge_id `u64` of each edge, grouped by destination vertex             |
+//!
+//! records are stored in id order, so the id of each record is its index. on
+//! 64-bit little-endian targets every section is viewed in place in the mapped
+//! file, so loading a graph does not read or copy its records. other targets
+//! decode the sections into memory. records are not validated when the graph is
+//! loaded; an adjacency row which is out of bounds is reported as a missing vertex
+//! when it is read.
+use super::{
+    adjacency::Adjacency, edge_id::EdgeId, graph::Graph, graph_error::GraphError, records::Records,
+    vertex_id::VertexId,
+};
+use crate::model::property::{edge::Edge, vertex::Vertex};
+use crate::model::unit::as_f64::AsF64;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::mem::{offset_of, size_of};
+use std::path::Path;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 8] = b"CMPSGRPH";
+const VERSION: u32 = 2;
+const HEADER_SIZE: usize = 32;
+const VERTEX_SIZE: usize = 16;
+const EDGE_SIZE: usize = 32;
+const ENTRY_SIZE: usize = 8;
+
+/// writes a graph to a compiled graph file
+pub fn write_compiled_graph<P: AsRef<Path>>(graph: &Graph, path: &P) -> Result<(), GraphError> {
+    let (out_offsets, out_edges) = compressed_rows(
+        graph.n_vertices(),
+        graph.edges.iter().map(|e| e.src_vertex_id),
+    )?;
+    let (in_offsets, in_edges) = compressed_rows(
+        graph.n_vertices(),
+        graph.edges.iter().map(|e| e.dst_vertex_id),
+    )?;
+
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&0_u32.to_le_bytes())?;
+    writer.write_all(&(graph.n_vertices() as u64).to_le_bytes())?;
+    writer.write_all(&(graph.n_edges() as u64).to_le_bytes())?;
+    for vertex in graph.vertices.iter() {
+        writer.write_all(&(vertex.vertex_id.0 as u64).to_le_bytes())?;
+        writer.write_all(&vertex.x().to_le_bytes())?;
+        writer.write_all(&vertex.y().to_le_bytes())?;
+    }
+    for edge in graph.edges.iter() {
+        writer.write_all(&(edge.edge_id.0 as u64).to_le_bytes())?;
+        writer.write_all(&(edge.src_vertex_id.0 as u64).to_le_bytes())?;
+        writer.write_all(&(edge.dst_vertex_id.0 as u64).to_le_bytes())?;
+        writer.write_all(&edge.distance.as_f64().to_le_bytes())?;
+    }
+    for entry in [out_offsets, out_edges, in_offsets, in_edges]
+        .iter()
+        .flatten()
+    {
+        writer.write_all(&entry.to_le_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// reads a graph from a compiled graph file. the file is memory-mapped, and on
+/// targets where the records of the file have the layout of `Vertex` and `Edge` in
+/// memory, the graph views its records and adjacency in place, so they are paged
+/// in by the operating system as searches read them. the file must not be modified
+/// while the graph is in use.
+pub fn read_compiled_graph<P: AsRef<Path>>(path: &P) -> Result<Graph, GraphError> {
+    let invalid = |msg: String| GraphError::CompiledGraphError(path.as_ref().to_path_buf(), msg);
+    let file = File::open(path)?;
+    // safety: the mapped file is only read, and the caller is told not to modify it
+    let map = Arc::new(unsafe { Mmap::map(&file)? });
+    let data: &[u8] = &map;
+
+    if data.len() < HEADER_SIZE || &data[0..8] != MAGIC {
+        return Err(invalid(String::from("not a compiled graph file")));
+    }
+    let version = u32::from_le_bytes(bytes(&data[8..12]));
+    if version != VERSION {
+        return Err(invalid(format!(
+            "unsupported version {}, expected {}",
+            version, VERSION
+        )));
+    }
+    let n_vertices = u64::from_le_bytes(bytes(&data[16..24]));
+    let n_edges = u64::from_le_bytes(bytes(&data[24..32]));
+    // the counts come from the file, so the section sizes may overflow
+    let sections = match Sections::new(n_vertices, n_edges) {
+        Some(sections) if sections.end == data.len() => sections,
+        _ => {
+            return Err(invalid(format!(
+                "file of {} bytes cannot hold {} vertices and {} edges",
+                data.len(),
+                n_vertices,
+                n_edges
+            )))
+        }
+    };
+    let (n_vertices, n_edges) = (sections.n_vertices, sections.n_edges);
+    for offsets_start in [sections.out_offsets, sections.in_offsets] {
+        let first = read_u64(data, offsets_start);
+        let last = read_u64(data, offsets_start + n_vertices * ENTRY_SIZE);
+        if first != 0 || last != n_edges as u64 {
+            return Err(invalid(format!(
+                "adjacency offsets run from {} to {}, expected 0 to {}",
+                first, last, n_edges
+            )));
+        }
+    }
+
+    if !native_layout() {
+        return decode_graph(data, &sections);
+    }
+    let mapped_error = || invalid(String::from("sections are not aligned for their records"));
+    // safety: `native_layout` confirms that the little-endian records of each section
+    // have the layout of their type in memory, and every bit pattern is a valid value
+    // of the integer and float fields of each type
+    let graph = unsafe {
+        let map = || map.clone();
+        let csr = |offsets: usize, edge_ids: usize| -> Option<Adjacency> {
+            Some(Adjacency::Csr {
+                offsets: Records::mapped(map(), offsets, n_vertices + 1)?,
+                edge_ids: Records::mapped(map(), edge_ids, n_edges)?,
+            })
+        };
+        Graph {
+            adj: csr(sections.out_offsets, sections.out_edges).ok_or_else(mapped_error)?,
+            rev: csr(sections.in_offsets, sections.in_edges).ok_or_else(mapped_error)?,
+            edges: Records::mapped(map(), sections.edges, n_edges).ok_or_else(mapped_error)?,
+            vertices: Records::mapped(map(), sections.vertices, n_vertices)
+                .ok_or_else(mapped_error)?,
+            geometries: None,
+            edge_attributes: None,
+        }
+    };
+    Ok(graph)
+}
+
+/// the byte offset of each section of a compiled graph file
+struct Sections {
+    n_vertices: usize,
+    n_edges: usize,
+    vertices: usize,
+    edges: usize,
+    out_offsets: usize,
+    out_edges: usize,
+    in_offsets: usize,
+    in_edges: usize,
+    end: usize,
+}
+
+impl Sections {
+    fn new(n_vertices: u64, n_edges: u64) -> Option<Sections> {
+        let n_vertices = usize::try_from(n_vertices).ok()?;
+        let n_edges = usize::try_from(n_edges).ok()?;
+        let offsets_size = n_vertices.checked_add(1)?.checked_mul(ENTRY_SIZE)?;
+        let entries_size = n_edges.checked_mul(ENTRY_SIZE)?;
+        let vertices = HEADER_SIZE;
+        let edges = vertices.checked_add(n_vertices.checked_mul(VERTEX_SIZE)?)?;
+        let out_offsets = edges.checked_add(n_edges.checked_mul(EDGE_SIZE)?)?;
+        let out_edges = out_offsets.checked_add(offsets_size)?;
+        let in_offsets = out_edges.checked_add(entries_size)?;
+        let in_edges = in_offsets.checked_add(offsets_size)?;
+        let end = in_edges.checked_add(entries_size)?;
+        Some(Sections {
+            n_vertices,
+            n_edges,
+            vertices,
+            edges,
+            out_offsets,
+            out_edges,
+            in_offsets,
+            in_edges,
+            end,
+        })
+    }
+}
+
+/// whether the records of a compiled graph file have the layout of their types in
+/// memory, so that they can be viewed in place
+fn native_layout() -> bool {
+    cfg!(target_endian = "little")
+        && size_of::<usize>() == ENTRY_SIZE
+        && size_of::<Vertex>() == VERTEX_SIZE
+        && offset_of!(Vertex, coordinate) == 8
+        && offset_of!(geo::Coord<f32>, x) == 0
+        && offset_of!(geo::Coord<f32>, y) == 4
+        && size_of::<Edge>() == EDGE_SIZE
+        && offset_of!(Edge, src_vertex_id) == 8
+        && offset_of!(Edge, dst_vertex_id) == 16
+        && offset_of!(Edge, distance) == 24
+}
+
+/// decodes the sections of a compiled graph file into memory, for targets where
+/// they cannot be viewed in place
+fn decode_graph(data: &[u8], sections: &Sections) -> Result<Graph, GraphError> {
+    let vertices = data[sections.vertices..sections.edges]
+        .chunks_exact(VERTEX_SIZE)
+        .enumerate()
+        .map(|(idx, r)| {
+            Vertex::new(
+                idx,
+                f32::from_le_bytes(bytes(&r[8..12])),
+                f32::from_le_bytes(bytes(&r[12..16])),
+            )
+        })
+        .collect::<Vec<_>>();
+    let edges = data[sections.edges..sections.out_offsets]
+        .chunks_exact(EDGE_SIZE)
+        .enumerate()
+        .map(|(idx, r)| {
+            Edge::new(
+                idx,
+                u64::from_le_bytes(bytes(&r[8..16])) as usize,
+                u64::from_le_bytes(bytes(&r[16..24])) as usize,
+                f64::from_le_bytes(bytes(&r[24..32])),
+            )
+        })
+        .collect::<Vec<_>>();
+    let entries = |start: usize, end: usize| {
+        data[start..end]
+            .chunks_exact(ENTRY_SIZE)
+            .map(|r| u64::from_le_bytes(bytes(r)))
+    };
+    let csr = |offsets: usize, edge_ids: usize, end: usize| Adjacency::Csr {
+        offsets: entries(offsets, edge_ids).collect::<Vec<_>>().into(),
+        edge_ids: entries(edge_ids, end)
+            .map(|e| EdgeId(e as usize))
+            .collect::<Vec<_>>()
+            .into(),
+    };
+    Ok(Graph {
+        adj: csr(
+            sections.out_offsets,
+            sections.out_edges,
+            sections.in_offsets,
+        ),
+        rev: csr(sections.in_offsets, sections.in_edges, sections.end),
+        edges: edges.into(),
+        vertices: vertices.into(),
+        geometries: None,
+        edge_attributes: None,
+    })
+}
+
+/// the compressed sparse rows of the edges incident to each vertex, from the vertex
+/// each edge is incident to in edge id order
+fn compressed_rows(
+    n_vertices: usize,
+    incident_vertices: impl Iterator<Item = VertexId> + Clone,
+) -> Result<(Vec<u64>, Vec<u64>), GraphError> {
+    let mut offsets = vec![0_u64; n_vertices + 1];
+    for vertex_id in incident_vertices.clone() {
+        *offsets
+            .get_mut(vertex_id.0.saturating_add(1))
+            .ok_or(GraphError::AdjacencyVertexMissing(vertex_id))? += 1;
+    }
+    for idx in 1..offsets.len() {
+        offsets[idx] += offsets[idx - 1];
+    }
+    let mut next = offsets.clone();
+    let mut edge_ids = vec![0_u64; offsets[n_vertices] as usize];
+    for (edge_id, vertex_id) in incident_vertices.enumerate() {
+        edge_ids[next[vertex_id.0] as usize] = edge_id as u64;
+        next[vertex_id.0] += 1;
+    }
+    Ok((offsets, edge_ids))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes(&data[offset..offset + ENTRY_SIZE]))
+}
+
+/// copies a slice of a record into a fixed-size array. record slices are
+/// always taken at constant offsets, so the lengths always match.
+fn bytes<const N: usize>(slice: &[u8]) -> [u8; N] {
+    let mut arr = [0_u8; N];
+    arr.copy_from_slice(slice);
+    arr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+
+    fn test_graph() -> Graph {
+        let vertices = vec![
+            Vertex::new(0, -105.1, 39.7),
+            Vertex::new(1, -105.2, 39.8),
+            Vertex::new(2, -105.3, 39.9),
+        ];
+        let edges = vec![Edge::new(0, 0, 1, 10.5), Edge::new(1, 1, 2, 20.25)];
+        let mut adj = vec![CompactOrderedHashMap::empty(); 3];
+        let mut rev = vec![CompactOrderedHashMap::empty(); 3];
+        for e in edges.iter() {
+            adj[e.src_vertex_id.0].insert(e.edge_id, e.dst_vertex_id);
+            rev[e.dst_vertex_id.0].insert(e.edge_id, e.src_vertex_id);
+        }
+        Graph {
+            adj: adj.into(),
+            rev: rev.into(),
+            edges: edges.into(),
+            vertices: vertices.into(),
+            geometries: None,
+            edge_attributes: None,
+        }
+    }
+
+    #[test]
+    fn test_compiled_graph_round_trip() {
+        let graph = test_graph();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph-compiled.bin");
+        write_compiled_graph(&graph, &path).unwrap();
+        let result = read_compiled_graph(&path).unwrap();
+
+        assert_eq!(result.edges.is_mapped(), native_layout());
+        assert_eq!(result.vertices.is_mapped(), native_layout());
+        assert_eq!(result.n_vertices(), 3);
+        assert_eq!(result.n_edges(), 2);
+        assert_eq!(result.get_vertex(VertexId(2)).unwrap().x(), -105.3);
+        assert_eq!(result.get_edge(EdgeId(1)).unwrap().distance.as_f64(), 20.25);
+        assert_eq!(result.out_edges(VertexId(1)).unwrap(), vec![EdgeId(1)]);
+        assert_eq!(result.in_edges(VertexId(1)).unwrap(), vec![EdgeId(0)]);
+        assert!(result.out_edges(VertexId(2)).unwrap().is_empty());
+        assert!(result.in_edges(VertexId(0)).unwrap().is_empty());
+        assert!(result.out_edges(VertexId(3)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_header_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph-compiled.bin");
+        write_compiled_graph(&test_graph(), &path).unwrap();
+        let mut data = std::fs::read(&path).unwrap();
+        // a vertex count that overflows the size of the vertex section
+        data[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &data).unwrap();
+        assert!(read_compiled_graph(&path).is_err());
+    }
+
+    #[test]
+    fn test_invalid_adjacency_offsets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph-compiled.bin");
+        write_compiled_graph(&test_graph(), &path).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let out_offsets = HEADER_SIZE + 3 * VERTEX_SIZE + 2 * EDGE_SIZE;
+
+        // offsets which do not end at the edge count are rejected when the graph is read
+        let mut invalid = data.clone();
+        let last = out_offsets + 3 * ENTRY_SIZE;
+        invalid[last..last + 8].copy_from_slice(&5_u64.to_le_bytes());
+        std::fs::write(&path, &invalid).unwrap();
+        assert!(read_compiled_graph(&path).is_err());
+
+        // a row which runs past the edges is reported as a missing vertex when it is read
+        let mut invalid = data;
+        let second = out_offsets + 2 * ENTRY_SIZE;
+        invalid[second..second + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &invalid).unwrap();
+        let graph = read_compiled_graph(&path).unwrap();
+        assert!(graph.out_edges(VertexId(1)).is_err());
+        assert_eq!(graph.out_edges(VertexId(0)).unwrap(), vec![EdgeId(0)]);
+    }
+}
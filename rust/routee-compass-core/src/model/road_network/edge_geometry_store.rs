@@ -0,0 +1,234 @@
+use super::{edge_id::EdgeId, graph::Graph, graph_error::GraphError};
+use crate::model::unit::{as_f64::AsF64, Distance};
+use crate::util::geo::{geo_io_utils::read_linestring_text_file, haversine};
+use allocative::Allocative;
+use geo::{Closest, ClosestPoint, Coord, LineString, Point};
+use std::{path::Path, sync::Arc};
+
+/// the LINESTRING geometry of each edge of a graph, indexed by `EdgeId`, with utilities
+/// to place points on an edge by their distance along it. distances are measured with
+/// the haversine formula, so coordinates are assumed to be in WGS84.
+#[derive(Debug)]
+pub struct EdgeGeometryStore {
+    geometries: Box<[LineString<f32>]>,
+}
+
+impl Allocative for EdgeGeometryStore {
+    fn visit<'a, 'b: 'a>(&self, visitor: &'a mut allocative::Visitor<'b>) {
+        let mut visitor = visitor.enter_self_sized::<Self>();
+        let coords = self.geometries.iter().map(|g| g.0.len()).sum::<usize>();
+        visitor.visit_simple(
+            allocative::Key::new("geometries"),
+            std::mem::size_of::<LineString<f32>>() * self.geometries.len()
+                + std::mem::size_of::<Coord<f32>>() * coords,
+        );
+        visitor.exit();
+    }
+}
+
+impl EdgeGeometryStore {
+    /// creates a store from one geometry per edge of a graph with `n_edges` edges
+    pub fn new(
+        geometries: Box<[LineString<f32>]>,
+        n_edges: usize,
+    ) -> Result<EdgeGeometryStore, GraphError> {
+        if geometries.len() != n_edges {
+            return Err(GraphError::AttributeError(
+                String::from("geometry"),
+                format!(
+                    "found {} edge geometries for a graph with {} edges",
+                    geometries.len(),
+                    n_edges
+                ),
+            ));
+        }
+        if let Some(idx) = geometries.iter().position(|g| g.0.len() < 2) {
+            return Err(GraphError::AttributeError(
+                String::from("geometry"),
+                format!("geometry of edge {} has fewer than two points", idx),
+            ));
+        }
+        Ok(EdgeGeometryStore { geometries })
+    }
+
+    /// reads a file with one WKT LINESTRING per line, in edge id order
+    pub fn from_file<P: AsRef<Path>>(
+        geometry_file: &P,
+        n_edges: usize,
+    ) -> Result<EdgeGeometryStore, GraphError> {
+        let geometries = read_linestring_text_file(geometry_file)?;
+        EdgeGeometryStore::new(geometries, n_edges)
+    }
+
+    /// the geometries of a graph when it has them, or else those read from
+    /// `geometry_file`, so that a model configured with its own geometry file shares
+    /// the copy loaded with the graph.
+    pub fn from_graph_or_file<P: AsRef<Path>>(
+        graph: &Graph,
+        geometry_file: Option<&P>,
+    ) -> Result<Arc<EdgeGeometryStore>, GraphError> {
+        match (graph.geometries(), geometry_file) {
+            (Ok(geometries), file) => {
+                if let Some(file) = file {
+                    log::warn!(
+                        "edge geometries are loaded with the graph, ignoring {:?}",
+                        file.as_ref()
+                    );
+                }
+                Ok(geometries.clone())
+            }
+            (Err(_), Some(file)) => Ok(Arc::new(EdgeGeometryStore::from_file(
+                &file,
+                graph.n_edges(),
+            )?)),
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.geometries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.geometries.is_empty()
+    }
+
+    /// the geometry of every edge, in edge id order
+    pub fn as_slice(&self) -> &[LineString<f32>] {
+        &self.geometries
+    }
+
+    /// the geometry of an edge
+    pub fn get(&self, edge_id: EdgeId) -> Result<&LineString<f32>, GraphError> {
+        self.geometries
+            .get(edge_id.0)
+            .ok_or(GraphError::EdgeIdNotFound { edge_id })
+    }
+
+    /// the length of the geometry of an edge, in meters
+    pub fn length(&self, edge_id: EdgeId) -> Result<Distance, GraphError> {
+        let lengths = segment_lengths(self.get(edge_id)?)?;
+        Ok(lengths.into_iter().sum())
+    }
+
+    /// the point at a fraction of the length of an edge from its start, where 0.0 is
+    /// the first point and 1.0 the last point of its geometry
+    pub fn interpolate(&self, edge_id: EdgeId, fraction: f64) -> Result<Point<f32>, GraphError> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(GraphError::AttributeError(
+                String::from("fraction"),
+                format!("{} is not in the range [0, 1]", fraction),
+            ));
+        }
+        let geometry = self.get(edge_id)?;
+        let lengths = segment_lengths(geometry)?;
+        let total: Distance = lengths.iter().copied().sum();
+        let mut remaining = total.as_f64() * fraction;
+        for (line, length) in geometry.lines().zip(lengths) {
+            if remaining <= length.as_f64() {
+                let t = if length > Distance::ZERO {
+                    (remaining / length.as_f64()) as f32
+                } else {
+                    0.0
+                };
+                return Ok(Point::from(line.start + line.delta() * t));
+            }
+            remaining -= length.as_f64();
+        }
+        Ok(Point::from(geometry[geometry.0.len() - 1]))
+    }
+
+    /// the fraction of the length of an edge from its start to the point of its
+    /// geometry closest to `point`, the inverse of `interpolate`
+    pub fn locate(&self, edge_id: EdgeId, point: &Point<f32>) -> Result<f64, GraphError> {
+        let geometry = self.get(edge_id)?;
+        let lengths = segment_lengths(geometry)?;
+        let total: Distance = lengths.iter().copied().sum();
+        if total <= Distance::ZERO {
+            return Ok(0.0);
+        }
+        let mut best: Option<(Distance, f64)> = None;
+        let mut along = 0.0;
+        for (line, length) in geometry.lines().zip(lengths) {
+            let closest = match line.closest_point(point) {
+                Closest::Intersection(p) | Closest::SinglePoint(p) => p,
+                Closest::Indeterminate => Point::from(line.start),
+            };
+            let offset = haversine::coord_distance_meters(&line.start, &closest.0)
+                .map_err(|e| GraphError::AttributeError(String::from("geometry"), e))?;
+            let separation = haversine::coord_distance_meters(&closest.0, &point.0)
+                .map_err(|e| GraphError::AttributeError(String::from("geometry"), e))?;
+            if best.map(|(s, _)| separation < s).unwrap_or(true) {
+                best = Some((separation, along + offset.as_f64().min(length.as_f64())));
+            }
+            along += length.as_f64();
+        }
+        let distance = best.map(|(_, d)| d).unwrap_or_default();
+        Ok((distance / total.as_f64()).clamp(0.0, 1.0))
+    }
+}
+
+/// the haversine length of each segment of a geometry, in meters
+fn segment_lengths(geometry: &LineString<f32>) -> Result<Vec<Distance>, GraphError> {
+    geometry
+        .lines()
+        .map(|line| {
+            haversine::coord_distance_meters(&line.start, &line.end)
+                .map_err(|e| GraphError::AttributeError(String::from("geometry"), e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::line_string;
+
+    #[test]
+    fn test_interpolate_and_locate() {
+        // an edge running east then north, with a first segment twice as long
+        let geometry = line_string![
+            (x: 0.0, y: 0.0),
+            (x: 0.002, y: 0.0),
+            (x: 0.002, y: 0.001),
+        ];
+        let store = EdgeGeometryStore::new(vec![geometry].into_boxed_slice(), 1).unwrap();
+        let edge_id = EdgeId(0);
+        let approx = |p: Point<f32>, x: f32, y: f32| {
+            assert!(
+                (p.x() - x).abs() < 1e-6 && (p.y() - y).abs() < 1e-6,
+                "{:?}",
+                p
+            )
+        };
+        approx(store.interpolate(edge_id, 0.0).unwrap(), 0.0, 0.0);
+        approx(store.interpolate(edge_id, 1.0 / 3.0).unwrap(), 0.001, 0.0);
+        approx(
+            store.interpolate(edge_id, 5.0 / 6.0).unwrap(),
+            0.002,
+            0.0005,
+        );
+        approx(store.interpolate(edge_id, 1.0).unwrap(), 0.002, 0.001);
+        assert!(store.interpolate(edge_id, 1.5).is_err());
+        assert!(store.interpolate(EdgeId(1), 0.5).is_err());
+
+        let fraction = store.locate(edge_id, &Point::new(0.0021, 0.0005)).unwrap();
+        assert!((fraction - 5.0 / 6.0).abs() < 1e-3, "{}", fraction);
+        let length = store.length(edge_id).unwrap().as_f64();
+        assert!((length - 333.6).abs() < 1.0, "{}", length);
+
+        // at 60 degrees north a degree of longitude is half the length of a degree of
+        // latitude, so the point is closer in meters to the northward segment
+        let northern = line_string![
+            (x: 0.0, y: 60.0),
+            (x: 0.004, y: 60.0),
+            (x: 0.004, y: 60.002),
+        ];
+        let store = EdgeGeometryStore::new(vec![northern].into_boxed_slice(), 1).unwrap();
+        let fraction = store.locate(edge_id, &Point::new(0.003, 60.0008)).unwrap();
+        assert!((fraction - 0.7).abs() < 0.01, "{}", fraction);
+
+        let too_few = EdgeGeometryStore::new(vec![].into_boxed_slice(), 1);
+        assert!(too_few.is_err());
+    }
+}
@@ -4,11 +4,12 @@ use allocative::Allocative;
 use serde::{Deserialize, Serialize};
 
 #[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Default, Allocative)]
+#[repr(transparent)]
 pub struct EdgeId(pub usize);
 
 impl PartialOrd for EdgeId {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
@@ -1,18 +1,25 @@
 use crate::algorithm::search::direction::Direction;
 use crate::model::property::edge::Edge;
+use crate::model::property::edge_attribute_store::EdgeAttributeStore;
 use crate::model::property::vertex::Vertex;
+use crate::model::road_network::edge_geometry_store::EdgeGeometryStore;
 use crate::model::road_network::graph_error::GraphError;
+use crate::model::road_network::{adjacency::Adjacency, records::Records};
 use crate::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
 use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
 use std::path::Path;
+use std::sync::Arc;
 
+#[cfg(feature = "fs")]
+use super::compiled_graph::{read_compiled_graph, write_compiled_graph};
 use super::graph_loader::graph_from_files;
 
 use allocative::Allocative;
 
 /// Road network topology represented as an adjacency list.
 /// The `EdgeId` and `VertexId` values correspond to edge and
-/// vertex indices in the `edges` and `vertices` vectors.
+/// vertex indices in the `edges` and `vertices` vectors, which are
+/// held in memory or, for a compiled graph, viewed in place in the mapped file.
 ///
 /// # Arguments
 ///
@@ -20,6 +27,8 @@ use allocative::Allocative;
 /// * `rev` - the reverse-oriented adjacency list
 /// * `edges` - for each `EdgeId`, the corresponding `Edge` record
 /// * `vertices` - for each `VertexId`, the corresponding `Vertex` record
+/// * `geometries` - optionally, the LINESTRING geometry of each edge
+/// * `edge_attributes` - optionally, named columns of per-edge data shared by every model
 ///
 /// # Performance
 ///
@@ -31,10 +40,12 @@ use allocative::Allocative;
 
 #[derive(Debug, Allocative)]
 pub struct Graph {
-    pub adj: Box<[CompactOrderedHashMap<EdgeId, VertexId>]>,
-    pub rev: Box<[CompactOrderedHashMap<EdgeId, VertexId>]>,
-    pub edges: Box<[Edge]>,
-    pub vertices: Box<[Vertex]>,
+    pub adj: Adjacency,
+    pub rev: Adjacency,
+    pub edges: Records<Edge>,
+    pub vertices: Records<Vertex>,
+    pub geometries: Option<Arc<EdgeGeometryStore>>,
+    pub edge_attributes: Option<Arc<EdgeAttributeStore>>,
 }
 
 impl Graph {
@@ -64,6 +75,137 @@ impl Graph {
     ) -> Result<Graph, GraphError> {
         graph_from_files(edge_list_csv, vertex_list_csv, n_edges, n_vertices, verbose)
     }
+
+    /// Build a `Graph` from edge and vertex records already held in memory, such
+    /// as a small network loaded in a web browser. Each record's id must match its
+    /// position in its list, as when reading from files.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - for each `EdgeId`, the corresponding `Edge` record
+    /// * `vertices` - for each `VertexId`, the corresponding `Vertex` record
+    ///
+    /// # Returns
+    ///
+    /// A graph instance, or an error if an id does not match its position or an
+    /// edge references a vertex that does not exist.
+    pub fn from_edges_and_vertices(
+        edges: Box<[Edge]>,
+        vertices: Box<[Vertex]>,
+    ) -> Result<Graph, GraphError> {
+        if let Some((idx, vertex)) = vertices
+            .iter()
+            .enumerate()
+            .find(|(idx, v)| v.vertex_id.0 != *idx)
+        {
+            return Err(GraphError::AttributeError(
+                String::from("vertex_id"),
+                format!("vertex {} found at position {}", vertex.vertex_id, idx),
+            ));
+        }
+        let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        for (idx, edge) in edges.iter().enumerate() {
+            if edge.edge_id.0 != idx {
+                return Err(GraphError::AttributeError(
+                    String::from("edge_id"),
+                    format!("edge {} found at position {}", edge.edge_id, idx),
+                ));
+            }
+            adj.get_mut(edge.src_vertex_id.0)
+                .ok_or(GraphError::VertexIdNotFound {
+                    vertex_id: edge.src_vertex_id,
+                })?
+                .insert(edge.edge_id, edge.dst_vertex_id);
+            rev.get_mut(edge.dst_vertex_id.0)
+                .ok_or(GraphError::VertexIdNotFound {
+                    vertex_id: edge.dst_vertex_id,
+                })?
+                .insert(edge.edge_id, edge.src_vertex_id);
+        }
+        Ok(Graph {
+            adj: adj.into(),
+            rev: rev.into(),
+            edges: edges.into(),
+            vertices: vertices.into(),
+            geometries: None,
+            edge_attributes: None,
+        })
+    }
+
+    /// Attach the geometry of each edge to this graph, replacing any geometries
+    /// it already has.
+    ///
+    /// # Arguments
+    ///
+    /// * `geometries` - for each `EdgeId`, the corresponding LINESTRING
+    ///
+    /// # Returns
+    ///
+    /// The graph with its geometries, or an error if there is not one geometry
+    /// for each edge.
+    pub fn with_geometries(mut self, geometries: EdgeGeometryStore) -> Result<Graph, GraphError> {
+        if geometries.len() != self.edges.len() {
+            return Err(GraphError::AttributeError(
+                String::from("geometry"),
+                format!(
+                    "found {} edge geometries for a graph with {} edges",
+                    geometries.len(),
+                    self.edges.len()
+                ),
+            ));
+        }
+        self.geometries = Some(Arc::new(geometries));
+        Ok(self)
+    }
+
+    /// Attach an edge attribute store to this graph, so that models read per-edge
+    /// data from one copy. The store should be loaded with the edge count of this graph.
+    pub fn with_edge_attributes(mut self, edge_attributes: EdgeAttributeStore) -> Graph {
+        self.edge_attributes = Some(Arc::new(edge_attributes));
+        self
+    }
+
+    /// The edge attributes of this graph, or an error if none were loaded.
+    pub fn edge_attributes(&self) -> Result<&Arc<EdgeAttributeStore>, GraphError> {
+        self.edge_attributes
+            .as_ref()
+            .ok_or(GraphError::EdgeAttributesNotLoaded)
+    }
+
+    /// The edge geometries of this graph, or an error if none were loaded.
+    pub fn geometries(&self) -> Result<&Arc<EdgeGeometryStore>, GraphError> {
+        self.geometries
+            .as_ref()
+            .ok_or(GraphError::EdgeGeometriesNotLoaded)
+    }
+
+    /// Build a `Graph` from a compiled graph file, which is memory-mapped and
+    /// read in place instead of parsed from text. See `compiled_graph` for the file layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `compiled_graph_file` - path to a file written by `Graph::write_compiled`
+    ///
+    /// # Returns
+    ///
+    /// A graph instance, or an error if the file is not a valid compiled graph.
+    #[cfg(feature = "fs")]
+    pub fn from_compiled_file<P: AsRef<Path>>(
+        compiled_graph_file: &P,
+    ) -> Result<Graph, GraphError> {
+        read_compiled_graph(compiled_graph_file)
+    }
+
+    /// writes this graph to a compiled graph file, which loads much faster than CSV.
+    #[cfg(feature = "fs")]
+    pub fn write_compiled<P: AsRef<Path>>(
+        &self,
+        compiled_graph_file: &P,
+    ) -> Result<(), GraphError> {
+        write_compiled_graph(self, compiled_graph_file)
+    }
+
     /// number of edges in the Graph
     pub fn n_edges(&self) -> usize {
         self.edges.len()
@@ -133,26 +275,19 @@ impl Graph {
     /// A list of `EdgeIds` for outbound edges that leave this `VertexId`, or an error
     /// if the vertex is missing from the Graph adjacency matrix.
     pub fn out_edges(&self, src: VertexId) -> Result<Vec<EdgeId>, GraphError> {
-        match self.adj.get(src.0) {
+        match self.adj.edge_ids(src) {
             None => Err(GraphError::VertexWithoutOutEdges { vertex_id: src }),
-            Some(out_map) => {
-                let edge_ids = out_map.keys().cloned().collect();
-                Ok(edge_ids)
-            }
+            Some(edge_ids) => Ok(edge_ids.cloned().collect()),
         }
     }
 
     pub fn out_edges_iter<'a>(
         &'a self,
         src: VertexId,
-    ) -> Result<Box<dyn Iterator<Item = &EdgeId> + 'a>, GraphError> {
-        match self.adj.get(src.0) {
-            None => Err(GraphError::VertexWithoutOutEdges { vertex_id: src }),
-            Some(out_map) => {
-                let edge_ids = out_map.keys();
-                Ok(edge_ids)
-            }
-        }
+    ) -> Result<Box<dyn Iterator<Item = &'a EdgeId> + 'a>, GraphError> {
+        self.adj
+            .edge_ids(src)
+            .ok_or(GraphError::VertexWithoutOutEdges { vertex_id: src })
     }
 
     /// retrieve a list of `EdgeId`s for edges that arrive at the given `VertexId`
@@ -166,26 +301,19 @@ impl Graph {
     /// A list of `EdgeIds` for inbound edges that arrive at this `VertexId`, or an error
     /// if the vertex is missing from the Graph adjacency matrix.
     pub fn in_edges(&self, dst: VertexId) -> Result<Vec<EdgeId>, GraphError> {
-        match self.rev.get(dst.0) {
+        match self.rev.edge_ids(dst) {
             None => Err(GraphError::VertexWithoutInEdges { vertex_id: dst }),
-            Some(in_map) => {
-                let edge_ids = in_map.keys().cloned().collect();
-                Ok(edge_ids)
-            }
+            Some(edge_ids) => Ok(edge_ids.cloned().collect()),
         }
     }
 
     pub fn in_edges_iter<'a>(
         &'a self,
         dst: VertexId,
-    ) -> Result<Box<dyn Iterator<Item = &EdgeId> + 'a>, GraphError> {
-        match self.rev.get(dst.0) {
-            None => Err(GraphError::VertexWithoutInEdges { vertex_id: dst }),
-            Some(in_map) => {
-                let edge_ids = in_map.keys();
-                Ok(edge_ids)
-            }
-        }
+    ) -> Result<Box<dyn Iterator<Item = &'a EdgeId> + 'a>, GraphError> {
+        self.rev
+            .edge_ids(dst)
+            .ok_or(GraphError::VertexWithoutInEdges { vertex_id: dst })
     }
 
     /// retrieve the source vertex id of an edge
@@ -339,3 +467,32 @@ impl Graph {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_edges_and_vertices() {
+        let vertices = vec![
+            Vertex::new(0, 0.0, 0.0),
+            Vertex::new(1, 1.0, 0.0),
+            Vertex::new(2, 1.0, 1.0),
+        ];
+        let edges = vec![Edge::new(0, 0, 1, 10.0), Edge::new(1, 1, 2, 10.0)];
+        let graph = Graph::from_edges_and_vertices(
+            edges.clone().into_boxed_slice(),
+            vertices.clone().into_boxed_slice(),
+        )
+        .expect("should build graph");
+        assert_eq!(graph.out_edges(VertexId(1)).unwrap(), vec![EdgeId(1)]);
+        assert_eq!(graph.in_edges(VertexId(1)).unwrap(), vec![EdgeId(0)]);
+
+        let bad_edges = vec![Edge::new(0, 0, 3, 10.0)];
+        let result = Graph::from_edges_and_vertices(
+            bad_edges.into_boxed_slice(),
+            vertices.into_boxed_slice(),
+        );
+        assert!(matches!(result, Err(GraphError::VertexIdNotFound { .. })));
+    }
+}
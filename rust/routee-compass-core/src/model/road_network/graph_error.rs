@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use crate::model::property::edge_attribute_error::EdgeAttributeError;
 use crate::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
 
 #[derive(thiserror::Error, Debug)]
@@ -34,6 +35,14 @@ pub enum GraphError {
     },
     #[error("internal error: adjacency list missing vertex {0}")]
     AdjacencyVertexMissing(VertexId),
+    #[error("edge geometries were not loaded with the graph")]
+    EdgeGeometriesNotLoaded,
+    #[error("edge attributes were not loaded with the graph, add an edge_attribute_input_file to the [graph] section")]
+    EdgeAttributesNotLoaded,
+    #[error(transparent)]
+    EdgeAttributeError(#[from] EdgeAttributeError),
+    #[error("invalid compiled graph file {0:?}: {1}")]
+    CompiledGraphError(PathBuf, String),
     #[error("error creating progress bar for {0}: {1}")]
     ProgressBarBuildError(String, String),
 }
@@ -2,7 +2,10 @@ use std::path::Path;
 
 use log::warn;
 
-use crate::{model::property::vertex::Vertex, util::fs::fs_utils::line_count};
+use crate::{
+    model::property::vertex::Vertex,
+    util::fs::{fs_utils, read_utils},
+};
 
 use super::{
     edge_loader::{EdgeLoader, EdgeLoaderConfig},
@@ -54,43 +57,34 @@ pub fn graph_from_files<P: AsRef<Path>>(
     let vertices: Box<[Vertex]> = v_conf.try_into()?;
 
     let graph = Graph {
-        adj: e_result.adj,
-        rev: e_result.rev,
-        edges: e_result.edges,
-        vertices,
+        adj: e_result.adj.into(),
+        rev: e_result.rev.into(),
+        edges: e_result.edges.into(),
+        vertices: vertices.into(),
+        geometries: None,
+        edge_attributes: None,
     };
 
     Ok(graph)
 }
 
 fn get_n_edges<P: AsRef<Path>>(edge_list_csv: &P) -> Result<usize, GraphError> {
-    // check if the extension is .gz
-    let is_gzip = edge_list_csv
-        .as_ref()
-        .to_path_buf()
-        .extension()
-        .map(|ext| ext.to_str() == Some("gz"))
-        .unwrap_or(false);
-    let n = line_count(edge_list_csv, is_gzip)?;
-    if n < 1 {
-        return Err(GraphError::EmptyFileSource {
-            filename: edge_list_csv.as_ref().to_path_buf(),
-        });
-    }
-    Ok(n - 1) // drop count of header line
+    count_rows(edge_list_csv)
 }
 
 fn get_n_vertices<P: AsRef<Path>>(vertex_list_csv: &P) -> Result<usize, GraphError> {
-    let is_gzip = vertex_list_csv
-        .as_ref()
-        .to_path_buf()
-        .extension()
-        .map(|ext| ext.to_str() == Some("gz"))
-        .unwrap_or(false);
-    let n = line_count(vertex_list_csv, is_gzip)?;
+    count_rows(vertex_list_csv)
+}
+
+/// counts the rows of a graph file, which for a CSV file excludes the header line
+fn count_rows<P: AsRef<Path>>(filepath: &P) -> Result<usize, GraphError> {
+    if fs_utils::is_parquet(filepath) {
+        return Ok(read_utils::row_count(filepath, true)?);
+    }
+    let n = fs_utils::line_count(filepath)?;
     if n < 1 {
         return Err(GraphError::EmptyFileSource {
-            filename: vertex_list_csv.as_ref().to_path_buf(),
+            filename: filepath.as_ref().to_path_buf(),
         });
     }
     Ok(n - 1) // drop count of header line
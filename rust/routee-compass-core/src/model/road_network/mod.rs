@@ -1,7 +1,13 @@
+pub mod adjacency;
+#[cfg(feature = "fs")]
+pub mod compiled_graph;
+pub mod edge_geometry_store;
 pub mod edge_id;
 pub mod edge_loader;
 pub mod graph;
 pub mod graph_error;
 pub mod graph_loader;
+pub mod records;
+pub mod strongly_connected_components;
 pub mod vertex_id;
 pub mod vertex_loader;
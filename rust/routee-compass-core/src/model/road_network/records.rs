@@ -0,0 +1,104 @@
+use allocative::Allocative;
+use std::fmt::Debug;
+use std::ops::Deref;
+#[cfg(feature = "fs")]
+use std::{marker::PhantomData, sync::Arc};
+
+/// a slice of graph records, either held in memory or viewed in place in a
+/// memory-mapped compiled graph file. dereferences to `[T]` in both cases.
+pub enum Records<T> {
+    Owned(Box<[T]>),
+    /// `len` records starting `offset` bytes into the mapped file
+    #[cfg(feature = "fs")]
+    Mapped {
+        map: Arc<memmap2::Mmap>,
+        offset: usize,
+        len: usize,
+        records: PhantomData<T>,
+    },
+}
+
+impl<T> Records<T> {
+    /// views `len` records starting `offset` bytes into a mapped file.
+    ///
+    /// # Safety
+    ///
+    /// the bytes of the section must be a valid value of `[T; len]` in the native
+    /// layout of `T`, and the file must not be modified while it is mapped.
+    /// the section bounds and alignment are checked here.
+    #[cfg(feature = "fs")]
+    pub(crate) unsafe fn mapped(
+        map: Arc<memmap2::Mmap>,
+        offset: usize,
+        len: usize,
+    ) -> Option<Records<T>> {
+        let end = len
+            .checked_mul(std::mem::size_of::<T>())
+            .and_then(|size| size.checked_add(offset))?;
+        let aligned = (map.as_ptr() as usize + offset).is_multiple_of(std::mem::align_of::<T>());
+        if end > map.len() || !aligned {
+            return None;
+        }
+        Some(Records::Mapped {
+            map,
+            offset,
+            len,
+            records: PhantomData,
+        })
+    }
+
+    /// whether the records are viewed in place in a mapped file
+    pub fn is_mapped(&self) -> bool {
+        !matches!(self, Records::Owned(_))
+    }
+}
+
+impl<T> Deref for Records<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Records::Owned(records) => records,
+            #[cfg(feature = "fs")]
+            Records::Mapped {
+                map, offset, len, ..
+            } => {
+                // safety: the section was checked to be in bounds and aligned when it was
+                // mapped, and the caller of `Records::mapped` guarantees its contents
+                unsafe { std::slice::from_raw_parts(map.as_ptr().add(*offset) as *const T, *len) }
+            }
+        }
+    }
+}
+
+impl<T> From<Box<[T]>> for Records<T> {
+    fn from(records: Box<[T]>) -> Self {
+        Records::Owned(records)
+    }
+}
+
+impl<T> From<Vec<T>> for Records<T> {
+    fn from(records: Vec<T>) -> Self {
+        Records::Owned(records.into_boxed_slice())
+    }
+}
+
+impl<T: Debug> Debug for Records<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Allocative> Allocative for Records<T> {
+    /// mapped records are paged in from the file by the operating system rather
+    /// than allocated, so only owned records are counted
+    fn visit<'a, 'b: 'a>(&self, visitor: &'a mut allocative::Visitor<'b>) {
+        let mut visitor = visitor.enter_self_sized::<Self>();
+        match self {
+            Records::Owned(records) => visitor.visit_field(allocative::Key::new("owned"), records),
+            #[cfg(feature = "fs")]
+            Records::Mapped { .. } => {}
+        }
+        visitor.exit();
+    }
+}
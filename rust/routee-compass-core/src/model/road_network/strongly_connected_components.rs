@@ -0,0 +1,136 @@
+use super::{graph::Graph, vertex_id::VertexId};
+
+/// finds the strongly connected components of a graph with Tarjan's algorithm,
+/// where every vertex of a component can reach every other vertex of the component.
+/// the search is iterative so that large road networks do not overflow the stack.
+///
+/// # Arguments
+///
+/// * `graph` - the road network
+///
+/// # Returns
+///
+/// the components, each a list of vertex ids, ordered from largest to smallest
+pub fn strongly_connected_components(graph: &Graph) -> Vec<Vec<VertexId>> {
+    const UNVISITED: usize = usize::MAX;
+    let n = graph.n_vertices();
+    let mut index = vec![UNVISITED; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack: Vec<usize> = vec![];
+    let mut components: Vec<Vec<VertexId>> = vec![];
+    let mut next_index = 0;
+    let out_vertices = |v: usize| -> Vec<usize> {
+        graph
+            .adj
+            .edge_ids(VertexId(v))
+            .into_iter()
+            .flatten()
+            .filter_map(|e| graph.edges.get(e.0))
+            .map(|e| e.dst_vertex_id.0)
+            .collect()
+    };
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // each frame is a vertex, its out-vertices and the next out-vertex to visit
+        let mut call_stack = vec![(root, out_vertices(root), 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((v, neighbors, pos)) = call_stack.last_mut() {
+            let v = *v;
+            if let Some(&w) = neighbors.get(*pos) {
+                *pos += 1;
+                if index[w] == UNVISITED {
+                    index[w] = next_index;
+                    lowlink[w] = next_index;
+                    next_index += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    call_stack.push((w, out_vertices(w), 0));
+                } else if on_stack[w] {
+                    lowlink[v] = lowlink[v].min(index[w]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some((parent, _, _)) = call_stack.last() {
+                lowlink[*parent] = lowlink[*parent].min(lowlink[v]);
+            }
+            if lowlink[v] == index[v] {
+                let mut component = vec![];
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component.into_iter().map(VertexId).collect());
+            }
+        }
+    }
+    components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+    components
+}
+
+/// the vertices of the largest strongly connected component of a graph
+pub fn largest_strongly_connected_component(graph: &Graph) -> Vec<VertexId> {
+    strongly_connected_components(graph)
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::property::{edge::Edge, vertex::Vertex};
+    use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // a cycle 0 -> 1 -> 2 -> 0, a one-way spur 2 -> 3, and a pair 4 <-> 5
+        let links = [(0, 1), (1, 2), (2, 0), (2, 3), (4, 5), (5, 4)];
+        let edges = links
+            .iter()
+            .enumerate()
+            .map(|(idx, (src, dst))| Edge::new(idx, *src, *dst, 1.0))
+            .collect::<Vec<_>>();
+        let mut adj = vec![CompactOrderedHashMap::empty(); 6];
+        let mut rev = vec![CompactOrderedHashMap::empty(); 6];
+        for e in edges.iter() {
+            adj[e.src_vertex_id.0].insert(e.edge_id, e.dst_vertex_id);
+            rev[e.dst_vertex_id.0].insert(e.edge_id, e.src_vertex_id);
+        }
+        let graph = Graph {
+            adj: adj.into(),
+            rev: rev.into(),
+            edges: edges.into(),
+            vertices: (0..6)
+                .map(|i| Vertex::new(i, 0.0, 0.0))
+                .collect::<Vec<_>>()
+                .into(),
+            geometries: None,
+            edge_attributes: None,
+        };
+
+        let components = strongly_connected_components(&graph);
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0], vec![VertexId(0), VertexId(1), VertexId(2)]);
+        assert_eq!(components[1], vec![VertexId(4), VertexId(5)]);
+        assert_eq!(components[2], vec![VertexId(3)]);
+        assert_eq!(
+            largest_strongly_connected_component(&graph),
+            components[0].clone()
+        );
+    }
+}
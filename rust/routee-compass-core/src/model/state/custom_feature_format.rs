@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use super::{state_error::StateError, unit_codec_name::UnitCodecType};
-use crate::model::traversal::state::state_variable::StateVar;
+use crate::model::{traversal::state::state_variable::StateVar, unit::as_f64::AsF64};
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
@@ -17,9 +17,8 @@ use serde::{Deserialize, Serialize};
 /// and also a codec value that can be instantiated from configuration for a
 /// given StateModel.
 ///
-/// warning: this could backfire, but probably in extreme cases that can be avoided.
-/// for example, if the user selects esoteric integer values that are not well-represented
-/// in floating point, then an encode -> decode roundtrip may not be idempotent.
+/// integers are only stored exactly up to [`StateVar::MAX_EXACT_INTEGER`], which is 2^53,
+/// or 2^24 with the `compact` feature, and encoding a larger integer fails.
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum CustomFeatureFormat {
@@ -74,7 +73,7 @@ impl CustomFeatureFormat {
 
     pub fn encode_f64(&self, value: &f64) -> Result<StateVar, StateError> {
         match self {
-            CustomFeatureFormat::FloatingPoint { initial: _ } => Ok(StateVar(*value)),
+            CustomFeatureFormat::FloatingPoint { initial: _ } => StateVar::try_new(*value),
             _ => Err(StateError::EncodeError(
                 UnitCodecType::FloatingPoint.to_string(),
                 self.name(),
@@ -84,7 +83,9 @@ impl CustomFeatureFormat {
 
     pub fn encode_i64(&self, value: &i64) -> Result<StateVar, StateError> {
         match self {
-            CustomFeatureFormat::SignedInteger { initial: _ } => Ok(StateVar(*value as f64)),
+            CustomFeatureFormat::SignedInteger { initial: _ } => {
+                encode_integer(value.unsigned_abs(), *value as f64)
+            }
             _ => Err(StateError::EncodeError(
                 UnitCodecType::SignedInteger.to_string(),
                 self.name(),
@@ -94,7 +95,9 @@ impl CustomFeatureFormat {
 
     pub fn encode_u64(&self, value: &u64) -> Result<StateVar, StateError> {
         match self {
-            CustomFeatureFormat::UnsignedInteger { initial: _ } => Ok(StateVar(*value as f64)),
+            CustomFeatureFormat::UnsignedInteger { initial: _ } => {
+                encode_integer(*value, *value as f64)
+            }
             _ => Err(StateError::EncodeError(
                 UnitCodecType::UnsignedInteger.to_string(),
                 self.name(),
@@ -120,7 +123,7 @@ impl CustomFeatureFormat {
 
     pub fn decode_f64(&self, value: &StateVar) -> Result<f64, StateError> {
         match self {
-            CustomFeatureFormat::FloatingPoint { initial: _ } => Ok(value.0),
+            CustomFeatureFormat::FloatingPoint { initial: _ } => Ok(value.as_f64()),
             _ => Err(StateError::DecodeError(
                 *value,
                 UnitCodecType::FloatingPoint.to_string(),
@@ -175,3 +178,15 @@ impl CustomFeatureFormat {
         }
     }
 }
+
+/// encodes an integer, failing when it is too large to be stored exactly, which
+/// happens beyond 2^24 with the `compact` feature
+fn encode_integer(magnitude: u64, value: f64) -> Result<StateVar, StateError> {
+    if magnitude > StateVar::MAX_EXACT_INTEGER {
+        return Err(StateError::OutOfRange(
+            value.to_string(),
+            format!("integers exactly up to {}", StateVar::MAX_EXACT_INTEGER),
+        ));
+    }
+    Ok(StateVar::new(value))
+}
@@ -8,6 +8,8 @@ pub enum StateError {
     DecodeError(StateVar, String, String),
     #[error("value {0} is not a valid {1}")]
     ValueError(StateVar, String),
+    #[error("value {0} is out of range for a state variable, which stores {1}")]
+    OutOfRange(String, String),
     #[error("unknown state variable name {0}, should be one of {1}")]
     UnknownStateVariableName(String, String),
     #[error("invalid state variable index {0}, should be in range [0, {1})")]
@@ -16,6 +18,8 @@ pub enum StateError {
     UnexpectedFeatureType(String, String),
     #[error("expected feature unit to be {0} but found {1}")]
     UnexpectedFeatureUnit(String, String),
+    #[error("cannot convert feature in {0} to {1}: {2}")]
+    IncompatibleUnit(String, String, String),
     #[error("{0}")]
     BuildError(String),
     #[error("{0}")]
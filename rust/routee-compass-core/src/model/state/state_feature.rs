@@ -1,7 +1,14 @@
 use std::fmt::Display;
 
-use super::{custom_feature_format::CustomFeatureFormat, state_error::StateError};
-use crate::model::{traversal::state::state_variable::StateVar, unit};
+use super::{
+    custom_feature_format::CustomFeatureFormat, state_error::StateError,
+    update_operation::UpdateOperation,
+};
+use crate::model::{
+    traversal::state::state_variable::StateVar,
+    unit::{self, as_f64::AsF64, Unit},
+};
+use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
 /// a state variable unit tracks the domain of a StateVar in a
@@ -195,6 +202,52 @@ impl StateFeature {
         }
     }
 
+    /// the operation which updates this feature. distance, time and energy features
+    /// accumulate over a route, while custom features such as a state of charge are
+    /// replaced by the value computed by the model which owns them.
+    pub fn update_operation(&self) -> UpdateOperation {
+        match self {
+            StateFeature::Distance { .. }
+            | StateFeature::Time { .. }
+            | StateFeature::Energy { .. } => UpdateOperation::Add,
+            StateFeature::Custom { .. } => UpdateOperation::Replace,
+        }
+    }
+
+    /// converts a value of this feature into another unit of the same kind, named as
+    /// in a configuration, such as `miles` for a distance feature. a custom feature
+    /// has no conversions, so the unit must be the unit of the feature.
+    pub fn convert(&self, value: &StateVar, to_unit: &str) -> Result<f64, StateError> {
+        let incompatible = |e: String| {
+            StateError::IncompatibleUnit(self.get_feature_unit_name(), to_unit.to_string(), e)
+        };
+        match self {
+            StateFeature::Distance { distance_unit, .. } => {
+                let to =
+                    unit::DistanceUnit::parse(to_unit).map_err(|e| incompatible(e.to_string()))?;
+                Ok(distance_unit.convert(&(*value).into(), &to).as_f64())
+            }
+            StateFeature::Time { time_unit, .. } => {
+                let to = unit::TimeUnit::parse(to_unit).map_err(|e| incompatible(e.to_string()))?;
+                Ok(time_unit.convert(&(*value).into(), &to).as_f64())
+            }
+            StateFeature::Energy { energy_unit, .. } => {
+                let to =
+                    unit::EnergyUnit::parse(to_unit).map_err(|e| incompatible(e.to_string()))?;
+                Ok(energy_unit.convert(&(*value).into(), &to).as_f64())
+            }
+            StateFeature::Custom { unit, .. } => {
+                if unit.trim().eq_ignore_ascii_case(to_unit.trim()) {
+                    Ok(value.as_f64())
+                } else {
+                    Err(incompatible(String::from(
+                        "custom features cannot be converted to other units",
+                    )))
+                }
+            }
+        }
+    }
+
     pub fn get_initial(&self) -> Result<StateVar, StateError> {
         match self {
             StateFeature::Distance {
@@ -217,6 +270,56 @@ impl StateFeature {
         }
     }
 
+    /// copies this feature with its initial value replaced by a state variable, such
+    /// as the value a feature reached at the end of a previous search.
+    pub fn with_initial(&self, value: &StateVar) -> Result<StateFeature, StateError> {
+        match self {
+            StateFeature::Distance { distance_unit, .. } => Ok(StateFeature::Distance {
+                distance_unit: *distance_unit,
+                initial: unit::Distance::from(*value),
+            }),
+            StateFeature::Time { time_unit, .. } => Ok(StateFeature::Time {
+                time_unit: *time_unit,
+                initial: unit::Time::from(*value),
+            }),
+            StateFeature::Energy { energy_unit, .. } => Ok(StateFeature::Energy {
+                energy_unit: *energy_unit,
+                initial: unit::Energy::from(*value),
+            }),
+            StateFeature::Custom {
+                r#type,
+                unit,
+                format,
+            } => {
+                let format = match format {
+                    CustomFeatureFormat::FloatingPoint { .. } => {
+                        CustomFeatureFormat::FloatingPoint {
+                            initial: OrderedFloat(format.decode_f64(value)?),
+                        }
+                    }
+                    CustomFeatureFormat::SignedInteger { .. } => {
+                        CustomFeatureFormat::SignedInteger {
+                            initial: format.decode_i64(value)?,
+                        }
+                    }
+                    CustomFeatureFormat::UnsignedInteger { .. } => {
+                        CustomFeatureFormat::UnsignedInteger {
+                            initial: format.decode_u64(value)?,
+                        }
+                    }
+                    CustomFeatureFormat::Boolean { .. } => CustomFeatureFormat::Boolean {
+                        initial: format.decode_bool(value)?,
+                    },
+                };
+                Ok(StateFeature::Custom {
+                    r#type: r#type.clone(),
+                    unit: unit.clone(),
+                    format,
+                })
+            }
+        }
+    }
+
     pub fn get_distance_unit(&self) -> Result<unit::DistanceUnit, StateError> {
         match self {
             StateFeature::Distance {
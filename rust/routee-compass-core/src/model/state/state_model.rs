@@ -6,7 +6,7 @@ use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
 use crate::{
     model::{
         traversal::state::state_variable::StateVar,
-        unit::{Distance, DistanceUnit, Energy, EnergyUnit, Time, TimeUnit},
+        unit::{as_f64::AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Time, TimeUnit},
     },
     util::compact_ordered_hash_map::IndexedEntry,
 };
@@ -90,12 +90,12 @@ impl StateModel {
     }
 
     /// iterates over the features in this state in their state vector index ordering.
-    pub fn iter(&self) -> FeatureIterator {
+    pub fn iter(&self) -> FeatureIterator<'_> {
         self.0.iter()
     }
 
     /// iterator that includes the state vector index along with the feature name and StateFeature
-    pub fn indexed_iter(&self) -> IndexedFeatureIterator {
+    pub fn indexed_iter(&self) -> IndexedFeatureIterator<'_> {
         self.0.indexed_iter()
     }
 
@@ -115,6 +115,32 @@ impl StateModel {
             .collect::<Result<Vec<_>, _>>()
     }
 
+    /// copies this state model with the initial value of each feature set from a
+    /// state vector, so that a new search continues from where another search ended.
+    ///
+    /// # Arguments
+    /// * `state` - state vector holding the initial value of each feature
+    ///
+    /// # Returns
+    ///
+    /// the state model with updated initial values, or an error if the state does
+    /// not match this model
+    pub fn with_initial_state(&self, state: &[StateVar]) -> Result<StateModel, StateError> {
+        if state.len() != self.len() {
+            return Err(StateError::RuntimeError(format!(
+                "state vector has {} features but state model has {}",
+                state.len(),
+                self.len()
+            )));
+        }
+        let features = self
+            .iter()
+            .zip(state.iter())
+            .map(|((name, feature), value)| Ok((name.clone(), feature.with_initial(value)?)))
+            .collect::<Result<Vec<_>, StateError>>()?;
+        Ok(StateModel::new(features))
+    }
+
     /// retrieves a state variable that is expected to have a type of Distance
     ///
     /// # Arguments
@@ -178,6 +204,30 @@ impl StateModel {
         let result = feature.get_energy_unit()?.convert(&value.into(), unit);
         Ok(result)
     }
+    /// retrieves any state variable converted into a unit given by name, such as
+    /// `miles` for a distance feature or `kilowatt_hours` for an energy feature.
+    /// use this where the type of the feature is not known in advance, such as for
+    /// features named in an output plugin configuration.
+    ///
+    /// # Arguments
+    /// * `state` - state vector to inspect
+    /// * `name`  - feature name to extract
+    /// * `unit`  - name of the unit to convert the feature into
+    ///
+    /// # Returns
+    ///
+    /// the feature value in the requested unit, or an error if the feature does not
+    /// exist or cannot be converted into that unit
+    pub fn get_value(
+        &self,
+        state: &[StateVar],
+        name: &String,
+        unit: &str,
+    ) -> Result<f64, StateError> {
+        let value = self.get_state_variable(state, name)?;
+        let feature = self.get_feature(name)?;
+        feature.convert(&value, unit)
+    }
     /// retrieves a state variable that is expected to have a type of f64.
     ///
     /// # Arguments
@@ -279,7 +329,27 @@ impl StateModel {
         Ok(next_val - prev_val)
     }
 
-    /// adds a distance value with distance unit to this feature vector
+    /// updates a feature with a value in the unit of the feature, using the update
+    /// operation of the feature, such as accumulating a distance onto the distance
+    /// traveled so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - the state to update
+    /// * `name`  - name of the feature to update
+    /// * `value` - the value to apply, in the unit of the feature
+    pub fn update(
+        &self,
+        state: &mut [StateVar],
+        name: &String,
+        value: &StateVar,
+    ) -> Result<(), StateError> {
+        let op = self.get_feature(name)?.update_operation();
+        self.update_state(state, name, value, op)
+    }
+
+    /// adds a distance value with distance unit to this feature vector, converted to
+    /// the unit of the feature and accumulated by its update operation
     pub fn add_distance(
         &self,
         state: &mut [StateVar],
@@ -287,12 +357,12 @@ impl StateModel {
         distance: &Distance,
         from_unit: &DistanceUnit,
     ) -> Result<(), StateError> {
-        let prev_distance = self.get_distance(state, name, from_unit)?;
-        let next_distance = prev_distance + *distance;
-        self.set_distance(state, name, &next_distance, from_unit)
+        let to_unit = self.get_feature(name)?.get_distance_unit()?;
+        let value = StateVar::try_new(from_unit.convert(distance, &to_unit).as_f64())?;
+        self.update(state, name, &value)
     }
 
-    /// adds a time value with time unit to this feature vector
+    /// adds a time value with time unit to this feature vector, as with [`StateModel::add_distance`]
     pub fn add_time(
         &self,
         state: &mut [StateVar],
@@ -300,12 +370,12 @@ impl StateModel {
         time: &Time,
         from_unit: &TimeUnit,
     ) -> Result<(), StateError> {
-        let prev_time = self.get_time(state, name, from_unit)?;
-        let next_time = prev_time + *time;
-        self.set_time(state, name, &next_time, from_unit)
+        let to_unit = self.get_feature(name)?.get_time_unit()?;
+        let value = StateVar::try_new(from_unit.convert(time, &to_unit).as_f64())?;
+        self.update(state, name, &value)
     }
 
-    /// adds a energy value with energy unit to this feature vector
+    /// adds an energy value with energy unit to this feature vector, as with [`StateModel::add_distance`]
     pub fn add_energy(
         &self,
         state: &mut [StateVar],
@@ -313,9 +383,9 @@ impl StateModel {
         energy: &Energy,
         from_unit: &EnergyUnit,
     ) -> Result<(), StateError> {
-        let prev_energy = self.get_energy(state, name, from_unit)?;
-        let next_energy = prev_energy + *energy;
-        self.set_energy(state, name, &next_energy, from_unit)
+        let to_unit = self.get_feature(name)?.get_energy_unit()?;
+        let value = StateVar::try_new(from_unit.convert(energy, &to_unit).as_f64())?;
+        self.update(state, name, &value)
     }
 
     pub fn set_distance(
@@ -328,7 +398,8 @@ impl StateModel {
         let feature = self.get_feature(name)?;
         let to_unit = feature.get_distance_unit()?;
         let value = from_unit.convert(distance, &to_unit);
-        self.update_state(state, name, &value.into(), UpdateOperation::Replace)
+        let value = StateVar::try_new(value.as_f64())?;
+        self.update_state(state, name, &value, UpdateOperation::Replace)
     }
 
     pub fn set_time(
@@ -341,7 +412,8 @@ impl StateModel {
         let feature = self.get_feature(name)?;
         let to_unit = feature.get_time_unit()?;
         let value = from_unit.convert(time, &to_unit);
-        self.update_state(state, name, &value.into(), UpdateOperation::Replace)
+        let value = StateVar::try_new(value.as_f64())?;
+        self.update_state(state, name, &value, UpdateOperation::Replace)
     }
 
     pub fn set_energy(
@@ -354,7 +426,8 @@ impl StateModel {
         let feature = self.get_feature(name)?;
         let to_unit = feature.get_energy_unit()?;
         let value = from_unit.convert(energy, &to_unit);
-        self.update_state(state, name, &value.into(), UpdateOperation::Replace)
+        let value = StateVar::try_new(value.as_f64())?;
+        self.update_state(state, name, &value, UpdateOperation::Replace)
     }
 
     pub fn set_custom_f64(
@@ -438,7 +511,7 @@ impl StateModel {
     }
 
     /// gets a state variable from a state vector by name
-    fn get_state_variable(
+    pub fn get_state_variable(
         &self,
         state: &[StateVar],
         name: &String,
@@ -458,7 +531,9 @@ impl StateModel {
         Ok(*value)
     }
 
-    fn update_state(
+    /// updates a feature with a value in the unit of the feature using the given
+    /// operation, in place of the update operation of the feature
+    pub fn update_state(
         &self,
         state: &mut [StateVar],
         name: &String,
@@ -546,3 +621,59 @@ impl From<Vec<(String, StateFeature)>> for StateModel {
         StateModel::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::state::custom_feature_format::CustomFeatureFormat;
+
+    #[test]
+    fn test_update_and_get_value_by_name() {
+        let state_model = StateModel::new(vec![
+            (
+                String::from("distance"),
+                StateFeature::Distance {
+                    distance_unit: DistanceUnit::Kilometers,
+                    initial: Distance::ZERO,
+                },
+            ),
+            (
+                String::from("soc"),
+                StateFeature::Custom {
+                    r#type: String::from("soc"),
+                    unit: String::from("percent"),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 100.0.into(),
+                    },
+                },
+            ),
+        ]);
+        let mut state = state_model.initial_state().unwrap();
+        let distance = String::from("distance");
+        let soc = String::from("soc");
+
+        // distance accumulates while a custom feature is replaced
+        state_model
+            .update(&mut state, &distance, &StateVar(1.609344))
+            .unwrap();
+        state_model
+            .update(&mut state, &distance, &StateVar(1.609344))
+            .unwrap();
+        state_model
+            .update(&mut state, &soc, &StateVar(80.0))
+            .unwrap();
+        let miles = state_model.get_value(&state, &distance, "miles").unwrap();
+        assert!((miles - 2.0).abs() < 1e-3);
+        assert_eq!(
+            state_model.get_value(&state, &soc, "percent").unwrap(),
+            80.0
+        );
+
+        // a unit of another kind is rejected rather than silently reinterpreted
+        assert!(state_model.get_value(&state, &distance, "hours").is_err());
+        assert!(state_model.get_value(&state, &soc, "miles").is_err());
+        assert!(state_model
+            .get_value(&state, &String::from("time"), "hours")
+            .is_err());
+    }
+}
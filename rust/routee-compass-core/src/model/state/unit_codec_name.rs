@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -9,13 +10,14 @@ pub enum UnitCodecType {
     Boolean,
 }
 
-impl ToString for UnitCodecType {
-    fn to_string(&self) -> String {
-        match self {
-            UnitCodecType::FloatingPoint => String::from("floating_point"),
-            UnitCodecType::SignedInteger => String::from("signed_integer"),
-            UnitCodecType::UnsignedInteger => String::from("unsigned_integer"),
-            UnitCodecType::Boolean => String::from("boolean"),
-        }
+impl Display for UnitCodecType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            UnitCodecType::FloatingPoint => "floating_point",
+            UnitCodecType::SignedInteger => "signed_integer",
+            UnitCodecType::UnsignedInteger => "unsigned_integer",
+            UnitCodecType::Boolean => "boolean",
+        };
+        write!(f, "{}", s)
     }
 }
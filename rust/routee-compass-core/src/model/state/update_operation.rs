@@ -1,31 +1,40 @@
 use crate::model::traversal::state::state_variable::StateVar;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
-/// describes an arbitrary state update operation.
+// describes an arbitrary state update operation.
 // pub(crate) type GenericStateUpdateOp = Box<dyn Fn(&StateVar, &StateVar) -> StateVar>;
 
 /// represents the type of arithmetic operation used to update a state variable.
 /// the specific index of a state variable is hidden via the StateModel, which
 /// makes life harder, but protects against all sorts of indexing errors.
 ///
-/// the StateModel exposes these operations through it's interface.
-pub(crate) enum UpdateOperation {
+/// each state feature has an update operation (see [`super::state_feature::StateFeature::update_operation`])
+/// which the StateModel exposes through [`super::state_model::StateModel::update`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateOperation {
+    /// the new value replaces the previous value
     Replace,
-    // Add,
+    /// the new value is accumulated onto the previous value
+    Add,
+    /// keeps the larger of the previous and new values
+    Max,
+    /// keeps the smaller of the previous and new values
+    Min,
     // Multiply,
-    // Max,
-    // Min,
     // AddBounded(StateVar, StateVar),
     // Function(GenericStateUpdateOp),
 }
 
 impl UpdateOperation {
-    pub fn perform_operation(&self, _prev: &StateVar, next: &StateVar) -> StateVar {
+    pub fn perform_operation(&self, prev: &StateVar, next: &StateVar) -> StateVar {
         match self {
             UpdateOperation::Replace => *next,
-            // UpdateOperation::Add => *prev + *next,
+            UpdateOperation::Add => *prev + *next,
+            UpdateOperation::Max => StateVar(prev.0.max(next.0)),
+            UpdateOperation::Min => StateVar(prev.0.min(next.0)),
             // UpdateOperation::Multiply => StateVar(prev.0 * next.0),
-            // UpdateOperation::Max => StateVar(prev.0.max(next.0)),
-            // UpdateOperation::Min => StateVar(prev.0.min(next.0)),
             // UpdateOperation::AddBounded(min, max) => {
             //     StateVar(min.0.max(max.0.min(prev.0 + next.0)))
             // }
@@ -33,3 +42,12 @@ impl UpdateOperation {
         }
     }
 }
+
+impl Display for UpdateOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(self)
+            .map(|s| s.replace('\"', ""))
+            .unwrap_or_else(|_| String::from("<unknown>"));
+        write!(f, "{}", s)
+    }
+}
@@ -1,12 +1,17 @@
 use super::termination_model_error::TerminationModelError;
+use crate::model::state::state_model::StateModel;
+use crate::model::traversal::state::state_variable::StateVar;
+use crate::model::unit::{as_f64::AsF64, Distance, DistanceUnit};
 use crate::util::duration_extension::DurationExtension;
+use crate::util::instant::Instant;
 use serde::Deserialize;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 /// the termination model for the application should be evaluated at the top of each iteration
 /// of a search. if it returns true, an error response should be created for the user using the
-/// explain method.
-#[derive(Debug, Deserialize)]
+/// explain method. models may be nested into a boolean expression with `combined` (any) and
+/// `all` conditions.
+#[derive(Debug, Clone, Deserialize)]
 pub enum TerminationModel {
     /// terminates a query if the runtime exceeds some limit.
     /// only checks at some provided iteration frequency, since the computation is expensive.
@@ -19,8 +24,20 @@ pub enum TerminationModel {
     /// iterations begin at 0, so we add 1 to the iteration to make this comparison
     #[serde(rename = "iterations")]
     IterationsLimit { limit: u64 },
+    /// terminates if the distance state feature at the vertex being expanded exceeds
+    /// (greater than) some limit. only checked by searches which provide their state.
+    #[serde(rename = "distance")]
+    DistanceLimit {
+        limit: Distance,
+        distance_unit: DistanceUnit,
+        feature: String,
+    },
+    /// terminates if any of the models would terminate
     #[serde(rename = "combined")]
     Combined { models: Vec<TerminationModel> },
+    /// terminates only if every one of the models would terminate
+    #[serde(rename = "all")]
+    All { models: Vec<TerminationModel> },
 }
 
 impl TerminationModel {
@@ -31,9 +48,23 @@ impl TerminationModel {
         solution_size: usize,
         iterations: u64,
     ) -> Result<(), TerminationModelError> {
-        let should_terminate = self.terminate_search(start_time, solution_size, iterations)?;
+        self.test_with_state(start_time, solution_size, iterations, None)
+    }
+
+    /// Tests if the search should terminate, including any limits on the state
+    /// at the vertex being expanded.
+    pub fn test_with_state(
+        &self,
+        start_time: &Instant,
+        solution_size: usize,
+        iterations: u64,
+        state: Option<(&[StateVar], &StateModel)>,
+    ) -> Result<(), TerminationModelError> {
+        let should_terminate =
+            self.terminate_search_with_state(start_time, solution_size, iterations, state)?;
         if should_terminate {
-            let explanation = self.explain_termination(start_time, solution_size, iterations);
+            let explanation =
+                self.explain_termination_with_state(start_time, solution_size, iterations, state);
             match explanation {
                 None => {
                     return Err(TerminationModelError::RuntimeError(format!(
@@ -56,11 +87,23 @@ impl TerminationModel {
         start_time: &Instant,
         solution_size: usize,
         iteration: u64,
+    ) -> Result<bool, TerminationModelError> {
+        self.terminate_search_with_state(start_time, solution_size, iteration, None)
+    }
+
+    /// predicate to test whether a query should terminate, where the state at the
+    /// vertex being expanded is provided when the search tracks one
+    pub fn terminate_search_with_state(
+        &self,
+        start_time: &Instant,
+        solution_size: usize,
+        iteration: u64,
+        state: Option<(&[StateVar], &StateModel)>,
     ) -> Result<bool, TerminationModelError> {
         use TerminationModel as T;
         match self {
             T::QueryRuntimeLimit { limit, frequency } => {
-                if iteration % frequency == 0 {
+                if iteration.is_multiple_of(*frequency) {
                     let dur = Instant::now().duration_since(*start_time);
                     Ok(dur > *limit)
                 } else {
@@ -69,10 +112,27 @@ impl TerminationModel {
             }
             T::SolutionSizeLimit { limit } => Ok(solution_size > *limit),
             T::IterationsLimit { limit } => Ok(iteration + 1 > *limit),
+            T::DistanceLimit {
+                limit,
+                distance_unit,
+                feature,
+            } => match state {
+                None => Ok(false),
+                Some((state, state_model)) => {
+                    let distance = state_model
+                        .get_distance(state, feature, distance_unit)
+                        .map_err(|e| TerminationModelError::RuntimeError(e.to_string()))?;
+                    Ok(distance > *limit)
+                }
+            },
             T::Combined { models } => models.iter().try_fold(false, |acc, m| {
-                m.terminate_search(start_time, solution_size, iteration)
+                m.terminate_search_with_state(start_time, solution_size, iteration, state)
                     .map(|r| acc || r)
             }),
+            T::All { models } => models.iter().try_fold(!models.is_empty(), |acc, m| {
+                m.terminate_search_with_state(start_time, solution_size, iteration, state)
+                    .map(|r| acc && r)
+            }),
         }
     }
 
@@ -83,22 +143,44 @@ impl TerminationModel {
         start_time: &Instant,
         solution_size: usize,
         iterations: u64,
+    ) -> Option<String> {
+        self.explain_termination_with_state(start_time, solution_size, iterations, None)
+    }
+
+    /// explains why a model terminated as with [`TerminationModel::explain_termination`],
+    /// including any limits on the state at the vertex being expanded.
+    pub fn explain_termination_with_state(
+        &self,
+        start_time: &Instant,
+        solution_size: usize,
+        iterations: u64,
+        state: Option<(&[StateVar], &StateModel)>,
     ) -> Option<String> {
         use TerminationModel as T;
         let caused_termination = self
-            .terminate_search(start_time, solution_size, iterations)
+            .terminate_search_with_state(start_time, solution_size, iterations, state)
             .unwrap_or(false);
+        let explain_all = |models: &Vec<TerminationModel>, separator: &str| {
+            let explanations = models
+                .iter()
+                .filter_map(|m| {
+                    m.explain_termination_with_state(start_time, solution_size, iterations, state)
+                })
+                .collect::<Vec<_>>()
+                .join(separator);
+            if explanations.is_empty() {
+                None
+            } else {
+                Some(explanations)
+            }
+        };
         match self {
-            T::Combined { models } => {
-                let combined_explanations: String = models
-                    .iter()
-                    .filter_map(|m| m.explain_termination(start_time, solution_size, iterations))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                if combined_explanations.is_empty() {
-                    None
+            T::Combined { models } => explain_all(models, ", "),
+            T::All { models } => {
+                if caused_termination {
+                    explain_all(models, " and ")
                 } else {
-                    Some(combined_explanations)
+                    None
                 }
             }
             T::QueryRuntimeLimit { limit, .. } => {
@@ -122,6 +204,31 @@ impl TerminationModel {
                     None
                 }
             }
+            T::DistanceLimit {
+                limit,
+                distance_unit,
+                feature,
+            } => {
+                if caused_termination {
+                    Some(format!(
+                        "exceeded {} limit of {} {}",
+                        feature,
+                        limit.as_f64(),
+                        distance_unit
+                    ))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// combines a limit provided with a query with this model. the search terminates
+    /// when either model would terminate, so the query may tighten but never loosen
+    /// the limits of this model.
+    pub fn tighten(&self, query_model: TerminationModel) -> TerminationModel {
+        TerminationModel::Combined {
+            models: vec![self.clone(), query_model],
         }
     }
 }
@@ -270,4 +377,59 @@ mod tests {
         );
         assert_eq!(msg, expected);
     }
+
+    #[test]
+    fn test_all_with_distance() {
+        use crate::model::state::state_feature::StateFeature;
+        use crate::model::state::state_model::StateModel;
+        use crate::model::unit::{Distance, DistanceUnit};
+
+        // stop after 5 iterations, but only once the search is more than 2 km out
+        let m = T::All {
+            models: vec![
+                T::IterationsLimit { limit: 5 },
+                T::DistanceLimit {
+                    limit: Distance::new(2.0),
+                    distance_unit: DistanceUnit::Kilometers,
+                    feature: String::from("distance"),
+                },
+            ],
+        };
+        let state_model = StateModel::empty()
+            .extend(vec![(
+                String::from("distance"),
+                StateFeature::Distance {
+                    distance_unit: DistanceUnit::Meters,
+                    initial: Distance::new(0.0),
+                },
+            )])
+            .unwrap();
+        let near = state_model.initial_state().unwrap();
+        let mut far = state_model.initial_state().unwrap();
+        state_model
+            .add_distance(
+                &mut far,
+                &String::from("distance"),
+                &Distance::new(2500.0),
+                &DistanceUnit::Meters,
+            )
+            .unwrap();
+        let i = Instant::now();
+        let test = |iteration: u64, state: &Vec<_>| {
+            m.terminate_search_with_state(&i, 0, iteration, Some((state, &state_model)))
+                .unwrap()
+        };
+        assert!(!test(10, &near));
+        assert!(!test(2, &far));
+        assert!(test(10, &far));
+        // without a state, the distance limit never applies
+        assert!(!m.terminate_search(&i, 0, 10).unwrap());
+        let msg = m.explain_termination_with_state(&i, 0, 10, Some((&far, &state_model)));
+        assert_eq!(
+            msg,
+            Some(String::from(
+                "exceeded iteration limit of 5 and exceeded distance limit of 2 kilometers"
+            ))
+        );
+    }
 }
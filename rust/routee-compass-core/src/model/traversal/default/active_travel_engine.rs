@@ -0,0 +1,201 @@
+use super::grade_table::GradeTable;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::traversal::traversal_model_error::TraversalModelError;
+use crate::model::unit::{as_f64::AsF64, DistanceUnit, GradeUnit, Speed, SpeedUnit, TimeUnit};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// a traveler who moves under their own power
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActiveTravelMode {
+    Walk,
+    Bike,
+}
+
+impl ActiveTravelMode {
+    /// speed on flat ground used when none is configured, in kilometers per hour
+    pub fn default_speed_kph(&self) -> f64 {
+        match self {
+            ActiveTravelMode::Walk => 5.0,
+            ActiveTravelMode::Bike => 16.0,
+        }
+    }
+
+    /// flat distance that is as much effort as one unit of climb. for walking this is
+    /// Naismith's rule of one hour per 5 km plus one hour per 600 m of ascent.
+    pub fn default_climb_factor(&self) -> f64 {
+        match self {
+            ActiveTravelMode::Walk => 8.33,
+            ActiveTravelMode::Bike => 10.0,
+        }
+    }
+
+    /// the fraction of the flat speed traveled on a grade, given as a decimal
+    pub fn slope_speed_factor(&self, grade: f64) -> f64 {
+        match self {
+            // Tobler's hiking function, scaled to 1.0 on flat ground
+            ActiveTravelMode::Walk => (-3.5 * (grade + 0.05).abs() + 3.5 * 0.05).exp(),
+            // 6% slower for each percent uphill and 3% faster for each percent downhill
+            ActiveTravelMode::Bike if grade >= 0.0 => (1.0 - 6.0 * grade).max(0.2),
+            ActiveTravelMode::Bike => (1.0 - 3.0 * grade).min(Self::MAX_BIKE_SPEED_FACTOR),
+        }
+    }
+
+    /// the largest value of the slope speed factor over all grades
+    pub fn max_slope_speed_factor(&self) -> f64 {
+        match self {
+            ActiveTravelMode::Walk => self.slope_speed_factor(-0.05),
+            ActiveTravelMode::Bike => Self::MAX_BIKE_SPEED_FACTOR,
+        }
+    }
+
+    const MAX_BIKE_SPEED_FACTOR: f64 = 1.5;
+}
+
+impl Display for ActiveTravelMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActiveTravelMode::Walk => write!(f, "walk"),
+            ActiveTravelMode::Bike => write!(f, "bike"),
+        }
+    }
+}
+
+/// how the speed of an active traveler responds to the grade of an edge
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ActiveSpeedModel {
+    /// travel every edge at the flat ground speed
+    #[default]
+    Constant,
+    /// scale the flat ground speed by the slope speed factor of the travel mode
+    SlopeAdjusted,
+}
+
+/// walking or cycling speeds and effort for each edge, shared across queries
+pub struct ActiveTravelEngine {
+    pub mode: ActiveTravelMode,
+    pub speed: Speed,
+    pub speed_unit: SpeedUnit,
+    pub speed_model: ActiveSpeedModel,
+    pub grade_table: Option<GradeTable>,
+    pub climb_factor: f64,
+    pub distance_unit: DistanceUnit,
+    pub time_unit: TimeUnit,
+}
+
+impl ActiveTravelEngine {
+    /// # Arguments
+    ///
+    /// * `mode` - walk or bike
+    /// * `speed` - speed on flat ground, or the default of the mode when not provided
+    /// * `speed_model` - whether speed depends on grade, which requires a grade table
+    /// * `grade_table` - grades of each edge, which are also used to compute effort
+    /// * `climb_factor` - flat distance as much effort as one unit of climb, or the
+    ///   default of the mode when not provided
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mode: ActiveTravelMode,
+        speed: Option<(Speed, SpeedUnit)>,
+        speed_model: ActiveSpeedModel,
+        grade_table: Option<GradeTable>,
+        climb_factor: Option<f64>,
+        distance_unit: DistanceUnit,
+        time_unit: TimeUnit,
+    ) -> Result<ActiveTravelEngine, TraversalModelError> {
+        let (speed, speed_unit) = speed.unwrap_or((
+            Speed::new(mode.default_speed_kph()),
+            SpeedUnit::KilometersPerHour,
+        ));
+        if speed <= Speed::ZERO {
+            return Err(TraversalModelError::BuildError(format!(
+                "{} speed must be positive, found {}",
+                mode, speed
+            )));
+        }
+        if speed_model == ActiveSpeedModel::SlopeAdjusted && grade_table.is_none() {
+            return Err(TraversalModelError::BuildError(String::from(
+                "a slope adjusted speed model requires a grade table",
+            )));
+        }
+        let climb_factor = climb_factor.unwrap_or(mode.default_climb_factor());
+        if climb_factor < 0.0 {
+            return Err(TraversalModelError::BuildError(format!(
+                "climb factor must be non-negative, found {}",
+                climb_factor
+            )));
+        }
+        Ok(ActiveTravelEngine {
+            mode,
+            speed,
+            speed_unit,
+            speed_model,
+            grade_table,
+            climb_factor,
+            distance_unit,
+            time_unit,
+        })
+    }
+
+    /// the grade of an edge as a decimal, or flat when there is no grade table
+    pub fn get_grade(&self, edge_id: EdgeId) -> Result<f64, TraversalModelError> {
+        match &self.grade_table {
+            None => Ok(0.0),
+            Some(table) => {
+                let grade = table.get_grade(edge_id)?;
+                Ok(table
+                    .grade_unit
+                    .convert(&grade, &GradeUnit::Decimal)
+                    .as_f64())
+            }
+        }
+    }
+
+    /// the speed of travel on an edge with the given grade
+    pub fn get_speed(&self, grade: f64) -> Speed {
+        match self.speed_model {
+            ActiveSpeedModel::Constant => self.speed,
+            ActiveSpeedModel::SlopeAdjusted => self.speed * self.mode.slope_speed_factor(grade),
+        }
+    }
+
+    /// the fastest speed of travel on any edge
+    pub fn max_speed(&self) -> Speed {
+        match self.speed_model {
+            ActiveSpeedModel::Constant => self.speed,
+            ActiveSpeedModel::SlopeAdjusted => self.speed * self.mode.max_slope_speed_factor(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slope_speed_factor() {
+        let walk = ActiveTravelMode::Walk;
+        assert!((walk.slope_speed_factor(0.0) - 1.0).abs() < 1e-9);
+        // walking is fastest on a gentle downhill and slows on steep climbs
+        assert!((walk.max_slope_speed_factor() - 0.175_f64.exp()).abs() < 1e-9);
+        assert!((walk.slope_speed_factor(0.1) - (-0.35_f64).exp()).abs() < 1e-9);
+
+        let bike = ActiveTravelMode::Bike;
+        assert!((bike.slope_speed_factor(0.05) - 0.7).abs() < 1e-9);
+        assert!((bike.slope_speed_factor(-0.05) - 1.15).abs() < 1e-9);
+        assert_eq!(bike.slope_speed_factor(0.5), 0.2);
+        assert_eq!(bike.slope_speed_factor(-0.5), 1.5);
+
+        let missing_grades = ActiveTravelEngine::new(
+            bike,
+            None,
+            ActiveSpeedModel::SlopeAdjusted,
+            None,
+            None,
+            DistanceUnit::Kilometers,
+            TimeUnit::Minutes,
+        );
+        assert!(missing_grades.is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use super::active_travel_engine::ActiveTravelEngine;
+use crate::model::state::state_feature::StateFeature;
+use crate::model::state::state_model::StateModel;
+use crate::model::traversal::traversal_model::TraversalModel;
+use crate::model::unit::{Distance, Time, BASE_DISTANCE_UNIT};
+use crate::model::{
+    property::{edge::Edge, vertex::Vertex},
+    traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
+};
+use crate::util::geo::haversine;
+use std::sync::Arc;
+
+/// traversal model for walking and cycling. along with time and distance, it tracks
+/// effort as the flat distance that takes as much work as the edge, so that a cost
+/// rate on effort steers routes away from climbs.
+pub struct ActiveTravelModel {
+    engine: Arc<ActiveTravelEngine>,
+}
+
+impl ActiveTravelModel {
+    pub fn new(engine: Arc<ActiveTravelEngine>) -> ActiveTravelModel {
+        ActiveTravelModel { engine }
+    }
+    const DISTANCE: &'static str = "distance";
+    const TIME: &'static str = "time";
+    const EFFORT: &'static str = "effort";
+}
+
+impl TraversalModel for ActiveTravelModel {
+    fn traverse_edge(
+        &self,
+        trajectory: (&Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (_, edge, _) = trajectory;
+        let distance = BASE_DISTANCE_UNIT.convert(&edge.distance, &self.engine.distance_unit);
+        let grade = self.engine.get_grade(edge.edge_id)?;
+        let speed = self.engine.get_speed(grade);
+        let edge_time = Time::create(
+            &speed,
+            &self.engine.speed_unit,
+            &distance,
+            &self.engine.distance_unit,
+            &self.engine.time_unit,
+        )?;
+        let effort = distance * (1.0 + self.engine.climb_factor * grade.max(0.0));
+
+        state_model.add_time(
+            state,
+            &Self::TIME.into(),
+            &edge_time,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::EFFORT.into(),
+            &effort,
+            &self.engine.distance_unit,
+        )?;
+        Ok(())
+    }
+
+    /// effort is estimated as the remaining distance, which assumes a flat route
+    fn estimate_traversal(
+        &self,
+        od: (&Vertex, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (src, dst) = od;
+        let distance =
+            haversine::coord_distance(&src.coordinate, &dst.coordinate, self.engine.distance_unit)
+                .map_err(TraversalModelError::NumericError)?;
+        if distance == Distance::ZERO {
+            return Ok(());
+        }
+        let estimated_time = Time::create(
+            &self.engine.max_speed(),
+            &self.engine.speed_unit,
+            &distance,
+            &self.engine.distance_unit,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_time(
+            state,
+            &Self::TIME.into(),
+            &estimated_time,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::EFFORT.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        Ok(())
+    }
+
+    /// track the time, distance and effort state features
+    fn state_features(&self) -> Vec<(String, StateFeature)> {
+        let distance_feature = StateFeature::Distance {
+            distance_unit: self.engine.distance_unit,
+            initial: Distance::ZERO,
+        };
+        vec![
+            (
+                String::from(Self::TIME),
+                StateFeature::Time {
+                    time_unit: self.engine.time_unit,
+                    initial: Time::ZERO,
+                },
+            ),
+            (String::from(Self::DISTANCE), distance_feature.clone()),
+            (String::from(Self::EFFORT), distance_feature),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::traversal::default::active_travel_engine::{
+        ActiveSpeedModel, ActiveTravelMode,
+    };
+    use crate::model::traversal::default::grade_table::GradeTable;
+    use crate::model::unit::{as_f64::AsF64, DistanceUnit, GradeUnit, TimeUnit};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_bike_climb() {
+        let grade_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join("grades.txt");
+        let grade_table = GradeTable::new(&grade_file, GradeUnit::Decimal, false).unwrap();
+        let engine = ActiveTravelEngine::new(
+            ActiveTravelMode::Bike,
+            None,
+            ActiveSpeedModel::SlopeAdjusted,
+            Some(grade_table),
+            None,
+            DistanceUnit::Kilometers,
+            TimeUnit::Minutes,
+        )
+        .unwrap();
+        let model = ActiveTravelModel::new(Arc::new(engine));
+        let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+        let mut state = state_model.initial_state().unwrap();
+        let v = Vertex::new(0, -105.0, 39.7);
+        // 1.6km up a 5% grade at 70% of 16kph
+        let edge = Edge::new(1, 0, 1, 1600.0);
+        model
+            .traverse_edge((&v, &edge, &v), &mut state, &state_model)
+            .unwrap();
+
+        let time = state_model
+            .get_time(&state, &"time".into(), &TimeUnit::Minutes)
+            .unwrap();
+        let distance = |name: &str| {
+            state_model
+                .get_distance(&state, &name.into(), &DistanceUnit::Kilometers)
+                .unwrap()
+                .as_f64()
+        };
+        assert!((time.as_f64() - 1.6 / 11.2 * 60.0).abs() < 1e-6);
+        // distances are rounded to the precision of a state variable
+        let tolerance = 1e-9 + 2.4 * StateVar::EPSILON;
+        assert!((distance("distance") - 1.6).abs() < tolerance);
+        assert!((distance("effort") - 1.6 * 1.5).abs() < tolerance);
+        assert!(model.engine.max_speed().as_f64() > 16.0);
+    }
+}
@@ -0,0 +1,19 @@
+use super::{active_travel_engine::ActiveTravelEngine, active_travel_model::ActiveTravelModel};
+use crate::model::traversal::{
+    traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
+    traversal_model_service::TraversalModelService,
+};
+use std::sync::Arc;
+
+pub struct ActiveTravelService {
+    pub e: Arc<ActiveTravelEngine>,
+}
+
+impl TraversalModelService for ActiveTravelService {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
+        Ok(Arc::new(ActiveTravelModel::new(self.e.clone())))
+    }
+}
@@ -0,0 +1,300 @@
+use super::custom_state_expression::{Expression, ExpressionValue};
+use crate::model::property::edge::Edge;
+use crate::model::property::edge_attribute_store::{EdgeAttributeStore, EdgeAttributeValue};
+use crate::model::state::{
+    custom_feature_format::CustomFeatureFormat, state_feature::StateFeature,
+    state_model::StateModel, update_operation::UpdateOperation,
+};
+use crate::model::traversal::{
+    state::state_variable::StateVar, traversal_model_error::TraversalModelError,
+};
+use crate::model::unit::{Distance, DistanceUnit, Energy, EnergyUnit, Time, TimeUnit, Unit};
+use itertools::Itertools;
+use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// configuration of one state feature computed from an expression
+///
+/// ```toml
+/// features = [
+///   { name = "unpaved_distance", unit = "miles", expression = "distance * (surface != 'paved')" },
+///   { name = "max_lanes", unit = "lanes", expression = "lanes", update = "max" },
+/// ]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomStateFeatureConfig {
+    /// name of the state feature
+    pub name: String,
+    /// unit of the feature. a distance, time or energy unit makes a feature of that
+    /// kind which converts to other units, while any other name makes a custom feature.
+    pub unit: String,
+    /// expression computing the value of the feature for each edge
+    pub expression: String,
+    /// how the value of each edge updates the feature, `add` by default
+    #[serde(default)]
+    pub update: Option<UpdateOperation>,
+    /// the value of the feature before the first edge. by default, positive infinity
+    /// for a `min` feature, negative infinity for a `max` feature and otherwise zero.
+    #[serde(default)]
+    pub initial: Option<f64>,
+    /// the unit to read each state feature named in the expression in, by feature name
+    #[serde(default)]
+    pub units: HashMap<String, String>,
+}
+
+/// a state feature computed from an expression
+pub struct CustomStateFeature {
+    pub name: String,
+    pub feature: StateFeature,
+    pub update: UpdateOperation,
+    pub expression: Expression,
+    pub units: HashMap<String, String>,
+}
+
+/// computes additional state features from expressions over the attributes of each
+/// edge and the change of the other state features over the edge, shared across queries
+pub struct CustomStateEngine {
+    pub features: Vec<CustomStateFeature>,
+    pub edge_attributes: Option<Arc<EdgeAttributeStore>>,
+    /// names of the features of the `[state]` configuration, which the expressions
+    /// may read along with the features of the traversal model
+    pub configured_features: Vec<String>,
+}
+
+impl CustomStateEngine {
+    pub fn new(
+        configs: &[CustomStateFeatureConfig],
+        edge_attributes: Option<Arc<EdgeAttributeStore>>,
+        configured_features: Vec<String>,
+    ) -> Result<CustomStateEngine, TraversalModelError> {
+        let features = configs
+            .iter()
+            .map(|config| {
+                let expression = Expression::parse(&config.expression).map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "custom state feature {}: {}",
+                        config.name, e
+                    ))
+                })?;
+                let update = config.update.unwrap_or(UpdateOperation::Add);
+                let initial = config.initial.unwrap_or(match update {
+                    UpdateOperation::Min => f64::INFINITY,
+                    UpdateOperation::Max => f64::NEG_INFINITY,
+                    UpdateOperation::Add | UpdateOperation::Replace => 0.0,
+                });
+                Ok(CustomStateFeature {
+                    name: config.name.clone(),
+                    feature: feature_for_unit(&config.name, &config.unit, initial),
+                    update,
+                    expression,
+                    units: config.units.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, TraversalModelError>>()?;
+        if let Some(duplicate) = features.iter().map(|f| &f.name).duplicates().next() {
+            return Err(TraversalModelError::BuildError(format!(
+                "custom state feature {} is declared more than once",
+                duplicate
+            )));
+        }
+        Ok(CustomStateEngine {
+            features,
+            edge_attributes,
+            configured_features,
+        })
+    }
+
+    /// the state features added by this engine
+    pub fn state_features(&self) -> Vec<(String, StateFeature)> {
+        self.features
+            .iter()
+            .map(|f| (f.name.clone(), f.feature.clone()))
+            .collect()
+    }
+
+    /// checks that no custom feature replaces a feature of the traversal model, and
+    /// that each variable of the expressions is an edge attribute column or a feature
+    /// of the traversal model, of the `[state]` configuration or of this engine. the
+    /// features of the traversal model may depend on the query, so this runs as each
+    /// model is built.
+    pub fn validate(
+        &self,
+        model_features: &[(String, StateFeature)],
+    ) -> Result<(), TraversalModelError> {
+        for feature in self.features.iter() {
+            if model_features.iter().any(|(n, _)| *n == feature.name) {
+                return Err(TraversalModelError::BuildError(format!(
+                    "custom state feature {} has the name of a feature of the traversal model",
+                    feature.name
+                )));
+            }
+        }
+        let state_names = model_features
+            .iter()
+            .map(|(n, _)| n)
+            .chain(self.configured_features.iter())
+            .chain(self.features.iter().map(|f| &f.name))
+            .collect::<Vec<_>>();
+        for feature in self.features.iter() {
+            let unknown = feature
+                .expression
+                .variables()
+                .into_iter()
+                .find(|v| !self.is_column(v) && !state_names.iter().any(|n| n == v));
+            if let Some(name) = unknown {
+                return Err(TraversalModelError::BuildError(format!(
+                    "custom state feature {} reads {}, which is not an edge attribute column [{}] or a state feature [{}]",
+                    feature.name,
+                    name,
+                    self.column_names(),
+                    state_names.iter().join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// updates each custom feature for the traversal of an edge
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - the edge traversed
+    /// * `prev` - the state at the start of the edge
+    /// * `state` - the state at the end of the edge, before the custom features are updated
+    /// * `state_model` - the state model of the search
+    pub fn update(
+        &self,
+        edge: &Edge,
+        prev: &[StateVar],
+        state: &mut [StateVar],
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        for feature in self.features.iter() {
+            let lookup = |name: &str| self.lookup(feature, name, edge, prev, state, state_model);
+            let value = match feature.expression.evaluate(&lookup) {
+                Ok(ExpressionValue::Number(n)) => Ok(n),
+                Ok(ExpressionValue::Text(t)) => {
+                    Err(format!("expected a number, found text '{}'", t))
+                }
+                Err(e) => Err(e),
+            }
+            .map_err(|e| {
+                TraversalModelError::InternalError(format!(
+                    "custom state feature {} on edge {}: {}",
+                    feature.name, edge.edge_id, e
+                ))
+            })?;
+            state_model.update_state(
+                state,
+                &feature.name,
+                &StateVar::try_new(value)?,
+                feature.update,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn is_column(&self, name: &str) -> bool {
+        self.edge_attributes
+            .as_ref()
+            .map(|a| a.column(name).is_ok())
+            .unwrap_or_default()
+    }
+
+    fn column_names(&self) -> String {
+        self.edge_attributes
+            .iter()
+            .flat_map(|a| a.column_names())
+            .sorted()
+            .join(", ")
+    }
+
+    /// the value of a variable: the value of an edge attribute column for the edge, or
+    /// else the change of a state feature over the edge. a state feature is read in its
+    /// configured unit, or else in the unit of the custom feature when both are of the
+    /// same kind, or else in its own unit.
+    fn lookup(
+        &self,
+        feature: &CustomStateFeature,
+        name: &str,
+        edge: &Edge,
+        prev: &[StateVar],
+        next: &[StateVar],
+        state_model: &StateModel,
+    ) -> Result<ExpressionValue, String> {
+        if let Some(attributes) = self
+            .edge_attributes
+            .as_ref()
+            .filter(|_| self.is_column(name))
+        {
+            let value = attributes
+                .get(name, edge.edge_id)
+                .map_err(|e| e.to_string())?;
+            let value = match value {
+                EdgeAttributeValue::F64(v) => ExpressionValue::Number(v),
+                EdgeAttributeValue::I64(v) => ExpressionValue::Number(v as f64),
+                EdgeAttributeValue::Bool(v) => v.into(),
+                EdgeAttributeValue::Categorical(v) => ExpressionValue::Text(v.to_string()),
+            };
+            return Ok(value);
+        }
+        let name = name.to_string();
+        let referenced = state_model
+            .iter()
+            .find(|(n, _)| **n == name)
+            .map(|(_, f)| f)
+            .ok_or_else(|| {
+                format!(
+                    "{} is not an edge attribute column [{}] or a state feature [{}]",
+                    name,
+                    self.column_names(),
+                    state_model.get_names()
+                )
+            })?;
+        let unit = match feature.units.get(&name) {
+            Some(unit) => unit.clone(),
+            None if referenced.get_feature_type() == feature.feature.get_feature_type() => {
+                feature.feature.get_feature_unit_name()
+            }
+            None => referenced.get_feature_unit_name(),
+        };
+        let prev_value = state_model
+            .get_value(prev, &name, &unit)
+            .map_err(|e| e.to_string())?;
+        let next_value = state_model
+            .get_value(next, &name, &unit)
+            .map_err(|e| e.to_string())?;
+        Ok(ExpressionValue::Number(next_value - prev_value))
+    }
+}
+
+/// a distance, time or energy feature for a unit of one of those kinds, or else a
+/// floating point custom feature with the unit as its name
+fn feature_for_unit(name: &str, unit: &str, initial: f64) -> StateFeature {
+    if let Ok(distance_unit) = DistanceUnit::parse(unit) {
+        StateFeature::Distance {
+            distance_unit,
+            initial: Distance::new(initial),
+        }
+    } else if let Ok(time_unit) = TimeUnit::parse(unit) {
+        StateFeature::Time {
+            time_unit,
+            initial: Time::new(initial),
+        }
+    } else if let Ok(energy_unit) = EnergyUnit::parse(unit) {
+        StateFeature::Energy {
+            energy_unit,
+            initial: Energy::new(initial),
+        }
+    } else {
+        StateFeature::Custom {
+            r#type: name.to_string(),
+            unit: unit.to_string(),
+            format: CustomFeatureFormat::FloatingPoint {
+                initial: OrderedFloat(initial),
+            },
+        }
+    }
+}
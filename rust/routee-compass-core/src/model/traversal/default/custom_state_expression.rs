@@ -0,0 +1,354 @@
+use std::fmt::Display;
+
+/// a value of an expression. comparisons and logical operators produce numbers,
+/// 1.0 for true and 0.0 for false, so that a condition can scale a quantity, as in
+/// `distance * (surface != 'paved')`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionValue {
+    Number(f64),
+    Text(String),
+}
+
+impl ExpressionValue {
+    fn as_number(&self) -> Result<f64, String> {
+        match self {
+            ExpressionValue::Number(n) => Ok(*n),
+            ExpressionValue::Text(t) => Err(format!("expected a number, found text '{}'", t)),
+        }
+    }
+}
+
+impl From<bool> for ExpressionValue {
+    fn from(value: bool) -> Self {
+        ExpressionValue::Number(if value { 1.0 } else { 0.0 })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    And,
+    Or,
+}
+
+/// a parsed arithmetic expression over named variables, used to compute custom state
+/// features. supports numbers, quoted text, `true` and `false`, the arithmetic operators
+/// `+ - * /`, the comparisons `== != < <= > >=`, the logical operators `&& || !` and
+/// parentheses, with the usual precedence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    Text(String),
+    Variable(String),
+    Negate(Box<Expression>),
+    Not(Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// parses an expression, such as `time * (highway == 'motorway')`
+    pub fn parse(source: &str) -> Result<Expression, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let expression = parser.or()?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected '{}' in expression '{}'", token, source)),
+        }
+    }
+
+    /// the names of the variables of this expression, in the order they appear
+    pub fn variables(&self) -> Vec<&str> {
+        match self {
+            Expression::Number(_) | Expression::Text(_) => vec![],
+            Expression::Variable(name) => vec![name.as_str()],
+            Expression::Negate(e) | Expression::Not(e) => e.variables(),
+            Expression::Binary(_, a, b) => {
+                let mut names = a.variables();
+                names.extend(b.variables());
+                names
+            }
+        }
+    }
+
+    /// evaluates this expression, reading each variable through a lookup function
+    pub fn evaluate<F>(&self, lookup: &F) -> Result<ExpressionValue, String>
+    where
+        F: Fn(&str) -> Result<ExpressionValue, String>,
+    {
+        use BinaryOperator as B;
+        match self {
+            Expression::Number(n) => Ok(ExpressionValue::Number(*n)),
+            Expression::Text(t) => Ok(ExpressionValue::Text(t.clone())),
+            Expression::Variable(name) => lookup(name),
+            Expression::Negate(e) => Ok(ExpressionValue::Number(-e.evaluate(lookup)?.as_number()?)),
+            Expression::Not(e) => Ok((e.evaluate(lookup)?.as_number()? == 0.0).into()),
+            Expression::Binary(op, a, b) => {
+                let a = a.evaluate(lookup)?;
+                let b = b.evaluate(lookup)?;
+                match op {
+                    B::Equal => return Ok((a == b).into()),
+                    B::NotEqual => return Ok((a != b).into()),
+                    _ => {}
+                }
+                let (a, b) = (a.as_number()?, b.as_number()?);
+                let result = match op {
+                    B::Add => ExpressionValue::Number(a + b),
+                    B::Subtract => ExpressionValue::Number(a - b),
+                    B::Multiply => ExpressionValue::Number(a * b),
+                    B::Divide => ExpressionValue::Number(a / b),
+                    B::Less => (a < b).into(),
+                    B::LessOrEqual => (a <= b).into(),
+                    B::Greater => (a > b).into(),
+                    B::GreaterOrEqual => (a >= b).into(),
+                    B::And => (a != 0.0 && b != 0.0).into(),
+                    B::Or => (a != 0.0 || b != 0.0).into(),
+                    B::Equal | B::NotEqual => unreachable!("equality is handled above"),
+                };
+                Ok(result)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Identifier(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Text(t) => write!(f, "'{}'", t),
+            Token::Identifier(i) => write!(f, "{}", i),
+            Token::Operator(o) => write!(f, "{}", o),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+        }
+    }
+}
+
+/// operators, with two-character operators first so they are matched before their prefixes
+const OPERATORS: [&str; 14] = [
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "+", "-", "*", "/", "!", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            i += 1;
+        } else if c == '\'' || c == '"' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|q| *q == c)
+                .ok_or_else(|| format!("unterminated text in expression '{}'", source))?;
+            tokens.push(Token::Text(chars[i + 1..i + 1 + end].iter().collect()));
+            i += end + 2;
+        } else if c.is_ascii_digit() || c == '.' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|d| d.is_ascii_digit() || **d == '.')
+                .count();
+            let text = chars[i..i + len].iter().collect::<String>();
+            let number = text
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number '{}' in expression '{}'", text, source))?;
+            tokens.push(Token::Number(number));
+            i += len;
+        } else if c.is_alphabetic() || c == '_' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|d| d.is_alphanumeric() || **d == '_')
+                .count();
+            tokens.push(Token::Identifier(chars[i..i + len].iter().collect()));
+            i += len;
+        } else {
+            let rest = chars[i..].iter().take(2).collect::<String>();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| format!("unexpected '{}' in expression '{}'", c, source))?;
+            if *op == "=" {
+                return Err(format!(
+                    "use '==' to compare values in expression '{}'",
+                    source
+                ));
+            }
+            tokens.push(Token::Operator(op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+/// a recursive descent parser, with one method per level of precedence
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// consumes the next token if it is one of the given operators
+    fn operator(&mut self, operators: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Operator(op)) if operators.contains(op) => {
+                let op = *op;
+                self.position += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn binary(
+        &mut self,
+        operators: &[&'static str],
+        operand: fn(&mut Parser) -> Result<Expression, String>,
+    ) -> Result<Expression, String> {
+        let mut expression = operand(self)?;
+        while let Some(op) = self.operator(operators) {
+            let rhs = operand(self)?;
+            let op = match op {
+                "||" => BinaryOperator::Or,
+                "&&" => BinaryOperator::And,
+                "==" => BinaryOperator::Equal,
+                "!=" => BinaryOperator::NotEqual,
+                "<" => BinaryOperator::Less,
+                "<=" => BinaryOperator::LessOrEqual,
+                ">" => BinaryOperator::Greater,
+                ">=" => BinaryOperator::GreaterOrEqual,
+                "+" => BinaryOperator::Add,
+                "-" => BinaryOperator::Subtract,
+                "*" => BinaryOperator::Multiply,
+                _ => BinaryOperator::Divide,
+            };
+            expression = Expression::Binary(op, Box::new(expression), Box::new(rhs));
+        }
+        Ok(expression)
+    }
+
+    fn or(&mut self) -> Result<Expression, String> {
+        self.binary(&["||"], Parser::and)
+    }
+
+    fn and(&mut self) -> Result<Expression, String> {
+        self.binary(&["&&"], Parser::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expression, String> {
+        self.binary(&["==", "!=", "<", "<=", ">", ">="], Parser::sum)
+    }
+
+    fn sum(&mut self) -> Result<Expression, String> {
+        self.binary(&["+", "-"], Parser::product)
+    }
+
+    fn product(&mut self) -> Result<Expression, String> {
+        self.binary(&["*", "/"], Parser::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expression, String> {
+        match self.operator(&["-", "!"]) {
+            Some("-") => Ok(Expression::Negate(Box::new(self.unary()?))),
+            Some(_) => Ok(Expression::Not(Box::new(self.unary()?))),
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expression::Number(n)),
+            Some(Token::Text(t)) => Ok(Expression::Text(t)),
+            Some(Token::Identifier(name)) => match name.as_str() {
+                "true" => Ok(Expression::Number(1.0)),
+                "false" => Ok(Expression::Number(0.0)),
+                _ => Ok(Expression::Variable(name)),
+            },
+            Some(Token::Open) => {
+                let expression = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expression),
+                    _ => Err(String::from("expected ')' in expression")),
+                }
+            }
+            Some(token) => Err(format!("unexpected '{}' in expression", token)),
+            None => Err(String::from("unexpected end of expression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_evaluate() {
+        let lookup = |name: &str| match name {
+            "distance" => Ok(ExpressionValue::Number(2.5)),
+            "lanes" => Ok(ExpressionValue::Number(3.0)),
+            "surface" => Ok(ExpressionValue::Text(String::from("gravel"))),
+            _ => Err(format!("unknown variable {}", name)),
+        };
+        let eval = |source: &str| {
+            Expression::parse(source)
+                .unwrap()
+                .evaluate(&lookup)
+                .unwrap()
+        };
+        let number = ExpressionValue::Number;
+        assert_eq!(eval("distance * (surface != 'paved')"), number(2.5));
+        assert_eq!(eval("distance * (surface == \"paved\")"), number(0.0));
+        assert_eq!(eval("1 + 2 * 3 - -lanes / 2"), number(8.5));
+        assert_eq!(
+            eval("lanes >= 2 && !(surface == 'paved') || false"),
+            number(1.0)
+        );
+
+        let expression = Expression::parse("distance * (surface != 'paved')").unwrap();
+        assert_eq!(expression.variables(), vec!["distance", "surface"]);
+
+        assert!(Expression::parse("distance * (lanes").is_err());
+        assert!(Expression::parse("lanes = 2").is_err());
+        assert!(Expression::parse("surface == 'paved").is_err());
+        let text_arithmetic = Expression::parse("surface + 1").unwrap();
+        assert!(text_arithmetic.evaluate(&lookup).is_err());
+    }
+}
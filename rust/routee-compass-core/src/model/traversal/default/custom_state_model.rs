@@ -0,0 +1,156 @@
+use super::custom_state_engine::CustomStateEngine;
+use crate::model::property::{edge::Edge, vertex::Vertex};
+use crate::model::state::{state_feature::StateFeature, state_model::StateModel};
+use crate::model::traversal::{
+    state::state_variable::StateVar, traversal_model::TraversalModel,
+    traversal_model_error::TraversalModelError,
+};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+thread_local! {
+    /// the state at the start of the edge being traversed, kept between edges so that
+    /// it is not reallocated for every edge of every search
+    static PREV_STATE: RefCell<Vec<StateVar>> = const { RefCell::new(Vec::new()) };
+}
+
+/// wraps a traversal model, adding the state features of a [`CustomStateEngine`] which
+/// are updated after the wrapped model traverses each edge.
+pub struct CustomStateModel {
+    pub inner: Arc<dyn TraversalModel>,
+    pub engine: Arc<CustomStateEngine>,
+}
+
+impl TraversalModel for CustomStateModel {
+    fn is_time_dependent(&self) -> bool {
+        self.inner.is_time_dependent()
+    }
+
+    fn state_features(&self) -> Vec<(String, StateFeature)> {
+        let mut features = self.inner.state_features();
+        features.extend(self.engine.state_features());
+        features
+    }
+
+    fn traverse_edge(
+        &self,
+        trajectory: (&Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (_, edge, _) = trajectory;
+        let mut prev = PREV_STATE.with(|p| p.take());
+        prev.clear();
+        prev.extend_from_slice(state);
+        let result = self
+            .inner
+            .traverse_edge(trajectory, state, state_model)
+            .and_then(|_| self.engine.update(edge, &prev, state, state_model));
+        PREV_STATE.with(|p| p.replace(prev));
+        result
+    }
+
+    fn access_edge(
+        &self,
+        traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        self.inner.access_edge(traversal, state, state_model)
+    }
+
+    /// custom features are not estimated, which keeps the estimate a lower bound when
+    /// they accumulate non-negative values
+    fn estimate_traversal(
+        &self,
+        od: (&Vertex, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        self.inner.estimate_traversal(od, state, state_model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::property::edge_attribute_store::{
+        EdgeAttributeColumnConfig, EdgeAttributeStore,
+    };
+    use crate::model::traversal::default::{
+        custom_state_engine::CustomStateFeatureConfig,
+        distance_traversal_model::DistanceTraversalModel,
+    };
+    use crate::model::unit::{Distance, DistanceUnit};
+    use serde_json::json;
+
+    #[test]
+    fn test_custom_features_accumulate_over_edges() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("edge-attributes.csv");
+        std::fs::write(&path, "surface,lanes\npaved,2\ngravel,1\n").unwrap();
+        let columns: Vec<EdgeAttributeColumnConfig> = serde_json::from_value(json!([
+            { "name": "surface", "type": "categorical" },
+            { "name": "lanes", "type": "i64" },
+        ]))
+        .unwrap();
+        let store = EdgeAttributeStore::from_file(&path, &columns, 2).unwrap();
+        let distance = (
+            String::from("distance"),
+            StateFeature::Distance {
+                distance_unit: DistanceUnit::Kilometers,
+                initial: Distance::ZERO,
+            },
+        );
+        let configs: Vec<CustomStateFeatureConfig> = serde_json::from_value(json!([
+            { "name": "unpaved", "unit": "meters", "expression": "distance * (surface != 'paved')" },
+            { "name": "min_lanes", "unit": "lanes", "expression": "lanes", "update": "min" },
+        ]))
+        .unwrap();
+        let engine = CustomStateEngine::new(&configs, Some(Arc::new(store)), vec![]).unwrap();
+        engine.validate(std::slice::from_ref(&distance)).unwrap();
+        let model = CustomStateModel {
+            inner: Arc::new(DistanceTraversalModel::new(DistanceUnit::Kilometers)),
+            engine: Arc::new(engine),
+        };
+        let mut features = vec![distance.clone()];
+        features.extend(model.state_features());
+        let state_model = StateModel::new(features);
+        // a min feature starts at infinity so that the first edge sets it
+        let mut state = state_model.initial_state().unwrap();
+
+        let v = Vertex::new(0, 0.0, 0.0);
+        for (edge_id, meters) in [(0, 1000.0), (1, 250.0)] {
+            let edge = Edge::new(edge_id, 0, 0, meters);
+            model
+                .traverse_edge((&v, &edge, &v), &mut state, &state_model)
+                .unwrap();
+        }
+        // the distance is read in the unit of the custom feature
+        let unpaved = state_model.get_value(&state, &String::from("unpaved"), "meters");
+        assert!((unpaved.unwrap() - 250.0).abs() < 1e-6);
+        let min_lanes = state_model.get_custom_f64(&state, &String::from("min_lanes"));
+        assert_eq!(min_lanes.unwrap(), 1.0);
+
+        // a custom feature may not replace a feature of the wrapped model
+        let clash: Vec<CustomStateFeatureConfig> = serde_json::from_value(json!([
+            { "name": "distance", "unit": "meters", "expression": "1" },
+        ]))
+        .unwrap();
+        let engine = CustomStateEngine::new(&clash, None, vec![]).unwrap();
+        assert!(engine.validate(std::slice::from_ref(&distance)).is_err());
+
+        // each variable must be a column or a state feature
+        let unknown: Vec<CustomStateFeatureConfig> = serde_json::from_value(json!([
+            { "name": "slow_time", "unit": "seconds", "expression": "time" },
+        ]))
+        .unwrap();
+        let engine = CustomStateEngine::new(&unknown, None, vec![]).unwrap();
+        let error = engine
+            .validate(std::slice::from_ref(&distance))
+            .unwrap_err();
+        assert!(error.to_string().contains("reads time"));
+        let engine = CustomStateEngine::new(&unknown, None, vec![String::from("time")]).unwrap();
+        assert!(engine.validate(std::slice::from_ref(&distance)).is_ok());
+    }
+}
@@ -0,0 +1,46 @@
+use super::{custom_state_engine::CustomStateEngine, custom_state_model::CustomStateModel};
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::traversal::{
+    traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
+    traversal_model_service::TraversalModelService,
+};
+use crate::model::unit::{Grade, GradeUnit, Speed, SpeedUnit};
+use std::sync::Arc;
+
+/// wraps the service of any traversal model so that its models also compute the
+/// custom state features of a [`CustomStateEngine`]
+pub struct CustomStateService {
+    pub inner: Arc<dyn TraversalModelService>,
+    pub engine: Arc<CustomStateEngine>,
+}
+
+impl TraversalModelService for CustomStateService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
+        let inner = self.inner.build(query)?;
+        self.engine.validate(&inner.state_features())?;
+        let model = CustomStateModel {
+            inner,
+            engine: self.engine.clone(),
+        };
+        Ok(Arc::new(model))
+    }
+
+    fn update_edge_speeds(
+        &self,
+        speeds: &[(EdgeId, Speed)],
+        speed_unit: &SpeedUnit,
+    ) -> Result<(), TraversalModelError> {
+        self.inner.update_edge_speeds(speeds, speed_unit)
+    }
+
+    fn edge_speed(&self, edge_id: EdgeId) -> Result<(Speed, SpeedUnit), TraversalModelError> {
+        self.inner.edge_speed(edge_id)
+    }
+
+    fn edge_grade(&self, edge_id: EdgeId) -> Result<(Grade, GradeUnit), TraversalModelError> {
+        self.inner.edge_grade(edge_id)
+    }
+}
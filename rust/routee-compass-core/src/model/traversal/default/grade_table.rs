@@ -0,0 +1,122 @@
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::traversal::traversal_model_error::TraversalModelError;
+use crate::model::unit::{as_f64::AsF64, Grade, GradeUnit, Unit};
+use crate::util::fs::{read_decoders, read_utils};
+use std::path::Path;
+
+/// the steepest average grade, as a decimal, that a road network is expected to have.
+/// real networks average a few percent, so a table averaging more than this was almost
+/// certainly written in a different unit than the one it was declared with.
+const MAX_MEAN_ABSOLUTE_GRADE: f64 = 0.15;
+
+/// the flattest average grade, as a decimal, that a road network with any grade is
+/// expected to have. a table averaging less than this, such as percents read as millis,
+/// was likely also written in a different unit.
+const MIN_MEAN_ABSOLUTE_GRADE: f64 = 0.005;
+
+/// road grade for each edge in the graph, enumerated by EdgeId like the speed table.
+pub struct GradeTable {
+    pub grades: Box<[Grade]>,
+    pub grade_unit: GradeUnit,
+}
+
+impl GradeTable {
+    /// reads a grade table. unless `skip_unit_check` is set, the grades must be
+    /// plausible in their declared unit, see [`validate_grade_unit`].
+    pub fn new<P: AsRef<Path>>(
+        grade_table_path: &P,
+        grade_unit: GradeUnit,
+        skip_unit_check: bool,
+    ) -> Result<GradeTable, TraversalModelError> {
+        let grades: Box<[Grade]> =
+            read_utils::read_raw_file(grade_table_path, read_decoders::default, None).map_err(
+                |e| {
+                    TraversalModelError::FileReadError(
+                        grade_table_path.as_ref().to_path_buf(),
+                        e.to_string(),
+                    )
+                },
+            )?;
+        if !skip_unit_check {
+            validate_grade_unit(&grades, &grade_unit, grade_table_path.as_ref())?;
+        }
+        Ok(GradeTable { grades, grade_unit })
+    }
+
+    /// look up the grade of an edge in the table's grade unit
+    pub fn get_grade(&self, edge_id: EdgeId) -> Result<Grade, TraversalModelError> {
+        let grade = self.grades.get(edge_id.as_usize()).ok_or_else(|| {
+            TraversalModelError::MissingIdInTabularCostFunction(
+                format!("{}", edge_id),
+                String::from("EdgeId"),
+                String::from("grade table"),
+            )
+        })?;
+        Ok(*grade)
+    }
+}
+
+/// checks that the grades of a table are plausible in their declared unit, as grades
+/// written as percents but read as decimals would be 100 times too steep, and grades
+/// written as percents but read as millis 10 times too flat. a table of only zero
+/// grades is valid in any unit. suggests a unit in which the grades would be plausible.
+pub fn validate_grade_unit(
+    grades: &[Grade],
+    grade_unit: &GradeUnit,
+    path: &Path,
+) -> Result<(), TraversalModelError> {
+    let mean_absolute_grade = |unit: &GradeUnit| {
+        let total: f64 = grades
+            .iter()
+            .map(|g| unit.convert(g, &GradeUnit::Decimal).as_f64().abs())
+            .filter(|g| g.is_finite())
+            .sum();
+        total / grades.len().max(1) as f64
+    };
+    let plausible = |mean: f64| {
+        mean == 0.0 || (MIN_MEAN_ABSOLUTE_GRADE..=MAX_MEAN_ABSOLUTE_GRADE).contains(&mean)
+    };
+    let mean = mean_absolute_grade(grade_unit);
+    if plausible(mean) {
+        return Ok(());
+    }
+    let problem = if mean > MAX_MEAN_ABSOLUTE_GRADE {
+        "too steep"
+    } else {
+        "implausibly flat"
+    };
+    let suggestion = GradeUnit::ALL
+        .iter()
+        .find(|u| plausible(mean_absolute_grade(u)))
+        .map(|u| format!(", the grades look like they are in {}", u))
+        .unwrap_or_default();
+    Err(TraversalModelError::BuildError(format!(
+        "grade table {:?} read as {} has an average grade of {:.2}%, which is {} for a road network. check that grade_table_grade_unit matches the file, or set grade_table_skip_unit_check = true if it does{}",
+        path,
+        grade_unit,
+        mean * 100.0,
+        problem,
+        suggestion
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_grade_unit() {
+        let path = Path::new("grades.csv");
+        let percents = [2.5, -4.0, 0.0, 8.0, -1.5].map(Grade::new);
+        assert!(validate_grade_unit(&percents, &GradeUnit::Percent, path).is_ok());
+        let error = validate_grade_unit(&percents, &GradeUnit::Decimal, path).unwrap_err();
+        assert!(error.to_string().contains("too steep"));
+        assert!(error.to_string().contains("look like they are in percent"));
+        let error = validate_grade_unit(&percents, &GradeUnit::Millis, path).unwrap_err();
+        assert!(error.to_string().contains("implausibly flat"));
+        assert!(error.to_string().contains("look like they are in percent"));
+
+        let flat = [0.0, 0.0, 0.0].map(Grade::new);
+        assert!(validate_grade_unit(&flat, &GradeUnit::Millis, path).is_ok());
+    }
+}
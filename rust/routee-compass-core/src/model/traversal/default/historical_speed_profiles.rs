@@ -0,0 +1,174 @@
+use super::time_dependent_speed_engine::SECONDS_PER_DAY;
+use super::weekly_speed_profile_engine::{ProfileSpeed, WeeklySpeedProfileEngine, DAYS_PER_WEEK};
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::traversal::traversal_model_error::TraversalModelError;
+use crate::model::unit::{as_f64::AsF64, DistanceUnit, Speed, SpeedUnit, Time, TimeUnit};
+use crate::util::fs::{read_decoders, read_utils};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// a row of a historical speed file, such as HERE Traffic Patterns or NPMRDS speeds
+/// that were matched to the edges of the graph and averaged by day of week and epoch.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct HistoricalSpeedRecord {
+    pub edge_id: EdgeId,
+    /// day of the week, from 0 (Sunday) to 6 (Saturday)
+    #[serde(alias = "day")]
+    pub day_of_week: usize,
+    /// time bin of the day starting from 0 at midnight
+    #[serde(alias = "time_bin")]
+    pub epoch: usize,
+    #[serde(alias = "average_speed")]
+    pub speed: Speed,
+}
+
+/// reads per-edge historical speeds into a weekly speed profile engine. each edge and
+/// day of the week with at least one row gets its own profile, and epochs without a
+/// row travel at the free flow speed of the edge.
+///
+/// # Arguments
+///
+/// * `free_flow_speed_path` - one free flow speed per line, enumerated by EdgeId
+/// * `speed_profile_path` - CSV with a header of `edge_id,day_of_week,epoch,speed`
+/// * `epoch_duration` - duration of each epoch, such as 15 minutes for HERE Traffic
+///   Patterns or 5 minutes for NPMRDS
+/// * `epoch_duration_unit` - unit of the epoch duration
+/// * `speed_unit` - unit of both the free flow and historical speeds
+pub fn read_historical_speed_profiles<P: AsRef<Path>>(
+    free_flow_speed_path: &P,
+    speed_profile_path: &P,
+    epoch_duration: f64,
+    epoch_duration_unit: TimeUnit,
+    speed_unit: SpeedUnit,
+    distance_unit: DistanceUnit,
+    time_unit: TimeUnit,
+) -> Result<WeeklySpeedProfileEngine, TraversalModelError> {
+    let epoch_seconds = epoch_duration_unit
+        .convert(&Time::new(epoch_duration), &TimeUnit::Seconds)
+        .as_f64();
+    let n_epochs = (SECONDS_PER_DAY / epoch_seconds).round() as usize;
+    if epoch_seconds <= 0.0 || n_epochs as f64 * epoch_seconds != SECONDS_PER_DAY {
+        return Err(TraversalModelError::BuildError(format!(
+            "epoch duration of {} seconds does not evenly divide a day",
+            epoch_seconds
+        )));
+    }
+
+    let free_flow_speeds: Box<[Speed]> =
+        read_utils::read_raw_file(free_flow_speed_path, read_decoders::default, None).map_err(
+            |e| {
+                TraversalModelError::FileReadError(
+                    free_flow_speed_path.as_ref().to_path_buf(),
+                    e.to_string(),
+                )
+            },
+        )?;
+
+    let read_error = |e: String| {
+        TraversalModelError::FileReadError(speed_profile_path.as_ref().to_path_buf(), e)
+    };
+    let rows =
+        read_utils::iterator_from_csv::<_, HistoricalSpeedRecord>(speed_profile_path, true, None)
+            .map_err(|e| read_error(e.to_string()))?;
+
+    let mut weekly_profiles = vec![[None; DAYS_PER_WEEK]; free_flow_speeds.len()];
+    let mut profiles: Vec<Vec<f64>> = vec![];
+    let mut profile_index: HashMap<(usize, usize), usize> = HashMap::new();
+    for row in rows {
+        let row = row.map_err(|e| read_error(e.to_string()))?;
+        let free_flow = free_flow_speeds
+            .get(row.edge_id.as_usize())
+            .ok_or_else(|| {
+                read_error(format!(
+                    "edge {} is not in the free flow speed table with {} edges",
+                    row.edge_id,
+                    free_flow_speeds.len()
+                ))
+            })?;
+        if row.day_of_week >= DAYS_PER_WEEK || row.epoch >= n_epochs {
+            return Err(read_error(format!(
+                "edge {} has day_of_week {} and epoch {}, expected days 0-6 and epochs 0-{}",
+                row.edge_id,
+                row.day_of_week,
+                row.epoch,
+                n_epochs - 1
+            )));
+        }
+        let key = (row.edge_id.as_usize(), row.day_of_week);
+        let idx = *profile_index.entry(key).or_insert_with(|| {
+            profiles.push(vec![free_flow.as_f64(); n_epochs]);
+            profiles.len() - 1
+        });
+        weekly_profiles[key.0][key.1] = Some(idx);
+        profiles[idx][row.epoch] = row.speed.as_f64();
+    }
+    log::info!(
+        "read {} historical speed profiles for {} edges",
+        profiles.len(),
+        free_flow_speeds.len()
+    );
+
+    WeeklySpeedProfileEngine::new(
+        free_flow_speeds,
+        weekly_profiles.into_boxed_slice(),
+        profiles.into_iter().map(|p| p.into_boxed_slice()).collect(),
+        ProfileSpeed::Absolute,
+        speed_unit,
+        distance_unit,
+        time_unit,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn test_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+    }
+
+    #[test]
+    fn test_historical_speed_profiles() {
+        // six-hour epochs keep the test file small
+        let engine = read_historical_speed_profiles(
+            &test_dir().join("velocities.txt"),
+            &test_dir().join("historical_speeds.csv"),
+            6.0,
+            TimeUnit::Hours,
+            SpeedUnit::KilometersPerHour,
+            DistanceUnit::Kilometers,
+            TimeUnit::Hours,
+        )
+        .unwrap();
+        assert_eq!(engine.slot_duration, 6.0 * 3600.0);
+
+        let monday_8am = SECONDS_PER_DAY + 8.0 * 3600.0;
+        let speed = |edge_id: usize, t: f64| engine.get_speed(EdgeId(edge_id), t).unwrap();
+        // edge 0 has a monday profile with a slow morning epoch
+        assert_eq!(speed(0, monday_8am), Speed::new(5.0));
+        // other epochs of that profile, and other days, use the free flow speed
+        assert_eq!(speed(0, monday_8am + 6.0 * 3600.0), Speed::new(10.0));
+        assert_eq!(speed(0, 8.0 * 3600.0), Speed::new(10.0));
+        // historical speeds above the free flow speed raise the max speed
+        assert_eq!(speed(1, monday_8am), Speed::new(50.0));
+        assert_eq!(engine.max_speed, Speed::new(50.0));
+
+        let invalid = read_historical_speed_profiles(
+            &test_dir().join("velocities.txt"),
+            &test_dir().join("historical_speeds.csv"),
+            7.0,
+            TimeUnit::Hours,
+            SpeedUnit::KilometersPerHour,
+            DistanceUnit::Kilometers,
+            TimeUnit::Hours,
+        );
+        assert!(invalid.is_err());
+    }
+}
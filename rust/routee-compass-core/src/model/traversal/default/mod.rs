@@ -1,5 +1,25 @@
+pub mod active_travel_engine;
+pub mod active_travel_model;
+pub mod active_travel_service;
+pub mod custom_state_engine;
+pub mod custom_state_expression;
+pub mod custom_state_model;
+pub mod custom_state_service;
 pub mod distance_traversal_model;
 pub mod distance_traversal_service;
+pub mod grade_table;
+pub mod historical_speed_profiles;
+pub mod query_time;
 pub mod speed_traversal_engine;
 pub mod speed_traversal_model;
 pub mod speed_traversal_service;
+pub mod time_dependent_speed_engine;
+pub mod time_dependent_speed_model;
+pub mod time_dependent_speed_service;
+pub mod tomtom_speed_profiles;
+pub mod transit_engine;
+pub mod transit_traversal_model;
+pub mod transit_traversal_service;
+pub mod weekly_speed_profile_engine;
+pub mod weekly_speed_profile_model;
+pub mod weekly_speed_profile_service;
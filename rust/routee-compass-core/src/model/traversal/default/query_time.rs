@@ -0,0 +1,29 @@
+/// the time a query is anchored to by its `departure_time` or `arrival_time`, in
+/// seconds since the start of the period of the speed data, such as since midnight.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QueryTime {
+    /// the query departs at this time and is searched forward from the origin, so
+    /// the state accumulates the time from the origin to each edge
+    Departure(f64),
+    /// the query arrives at this time and is searched in reverse from the
+    /// destination, so the state accumulates the time from each edge to the destination
+    Arrival(f64),
+}
+
+impl Default for QueryTime {
+    fn default() -> Self {
+        QueryTime::Departure(0.0)
+    }
+}
+
+impl QueryTime {
+    /// the time at an edge, given the time accumulated on the search state before
+    /// the edge is traversed. a forward search enters the edge at this time, and a
+    /// reverse search leaves it at this time.
+    pub fn time_at(&self, elapsed_seconds: f64) -> f64 {
+        match self {
+            QueryTime::Departure(departure) => departure + elapsed_seconds,
+            QueryTime::Arrival(arrival) => arrival - elapsed_seconds,
+        }
+    }
+}
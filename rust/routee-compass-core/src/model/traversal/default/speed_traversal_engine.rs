@@ -1,4 +1,7 @@
-use crate::model::unit::DistanceUnit;
+use super::grade_table::GradeTable;
+use super::speed_traversal_model::get_speed;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::unit::{as_f64::AsF64, DistanceUnit, GradeUnit};
 use crate::model::unit::{SpeedUnit, TimeUnit, BASE_DISTANCE_UNIT, BASE_TIME_UNIT};
 use crate::util::fs::read_decoders;
 use crate::{
@@ -6,6 +9,7 @@ use crate::{
     util::fs::read_utils,
 };
 use std::path::Path;
+use std::sync::Arc;
 
 pub struct SpeedTraversalEngine {
     pub speed_table: Box<[Speed]>,
@@ -13,6 +17,8 @@ pub struct SpeedTraversalEngine {
     pub time_unit: TimeUnit,
     pub distance_unit: DistanceUnit,
     pub max_speed: Speed,
+    pub grade_table: Option<Arc<GradeTable>>,
+    pub grade_speed_factor: f64,
 }
 
 impl SpeedTraversalEngine {
@@ -40,9 +46,91 @@ impl SpeedTraversalEngine {
             time_unit,
             speed_unit,
             max_speed,
+            grade_table: None,
+            grade_speed_factor: 0.0,
         };
         Ok(model)
     }
+
+    /// the smallest fraction of the speed table value used on a steep uphill edge
+    const MIN_GRADE_SPEED_FACTOR: f64 = 0.1;
+
+    /// adds a grade table so that speeds are reduced on uphill edges. the speed is
+    /// scaled down by `grade_speed_factor` for each percent of uphill grade, so a factor
+    /// of 0.02 travels a 5% grade at 90% of the speed from the speed table. downhill
+    /// edges use the speed table value, which keeps the max speed an upper bound.
+    pub fn with_grade_table(
+        self,
+        grade_table: GradeTable,
+        grade_speed_factor: f64,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        if grade_speed_factor < 0.0 {
+            return Err(TraversalModelError::BuildError(format!(
+                "grade_speed_factor must be non-negative, found {}",
+                grade_speed_factor
+            )));
+        }
+        Ok(SpeedTraversalEngine {
+            grade_table: Some(Arc::new(grade_table)),
+            grade_speed_factor,
+            ..self
+        })
+    }
+
+    /// creates a copy of this engine with the speeds of some edges replaced, such as
+    /// from a live traffic feed. speeds are given in `speed_unit` and converted to the
+    /// unit of the speed table. the grade table is shared with this engine.
+    pub fn with_speed_updates(
+        &self,
+        updates: &[(EdgeId, Speed)],
+        speed_unit: &SpeedUnit,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let mut speed_table = self.speed_table.clone();
+        for (edge_id, speed) in updates.iter() {
+            if *speed < Speed::ZERO {
+                return Err(TraversalModelError::BuildError(format!(
+                    "speed update for edge {} is negative: {}",
+                    edge_id, speed
+                )));
+            }
+            let row = speed_table.get_mut(edge_id.0).ok_or_else(|| {
+                TraversalModelError::BuildError(format!(
+                    "speed update for edge {} is outside of the speed table with {} entries",
+                    edge_id,
+                    self.speed_table.len()
+                ))
+            })?;
+            *row = speed_unit.convert(speed, &self.speed_unit);
+        }
+        let max_speed = get_max_speed(&speed_table)?;
+        Ok(SpeedTraversalEngine {
+            speed_table,
+            speed_unit: self.speed_unit,
+            time_unit: self.time_unit,
+            distance_unit: self.distance_unit,
+            max_speed,
+            grade_table: self.grade_table.clone(),
+            grade_speed_factor: self.grade_speed_factor,
+        })
+    }
+
+    /// looks up the speed of an edge, adjusted for grade when a grade table is present
+    pub fn get_speed(&self, edge_id: EdgeId) -> Result<Speed, TraversalModelError> {
+        let speed = get_speed(&self.speed_table, edge_id)?;
+        match &self.grade_table {
+            Some(grade_table) if self.grade_speed_factor > 0.0 => {
+                let grade = grade_table.get_grade(edge_id)?;
+                let grade_percent = grade_table
+                    .grade_unit
+                    .convert(&grade, &GradeUnit::Percent)
+                    .as_f64();
+                let factor = (1.0 - self.grade_speed_factor * grade_percent.max(0.0))
+                    .max(Self::MIN_GRADE_SPEED_FACTOR);
+                Ok(speed * factor)
+            }
+            _ => Ok(speed),
+        }
+    }
 }
 
 pub fn get_max_speed(speed_table: &[Speed]) -> Result<Speed, TraversalModelError> {
@@ -33,7 +33,7 @@ impl TraversalModel for SpeedTraversalModel {
     ) -> Result<(), TraversalModelError> {
         let (_, edge, _) = trajectory;
         let distance = BASE_DISTANCE_UNIT.convert(&edge.distance, &self.engine.distance_unit);
-        let speed = get_speed(&self.engine.speed_table, edge.edge_id)?;
+        let speed = self.engine.get_speed(edge.edge_id)?;
         let edge_time = Time::create(
             &speed,
             &self.engine.speed_unit,
@@ -130,7 +130,10 @@ pub fn get_speed(speed_table: &[Speed], edge_id: EdgeId) -> Result<Speed, Traver
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::unit::{Distance, DistanceUnit, SpeedUnit, TimeUnit};
+    use crate::model::traversal::default::grade_table::GradeTable;
+    use crate::model::unit::{
+        as_f64::AsF64, Distance, DistanceUnit, GradeUnit, SpeedUnit, TimeUnit,
+    };
     use crate::model::{
         property::{edge::Edge, vertex::Vertex},
         road_network::{edge_id::EdgeId, vertex_id::VertexId},
@@ -264,4 +267,29 @@ mod tests {
         // approx_eq(result.updated_state[1].into(), expected, 0.001);
         approx_eq(state[1].into(), expected, 0.001);
     }
+
+    #[test]
+    fn test_edge_speed_with_grade_table() {
+        let grade_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join("grades.txt");
+        let grade_table = GradeTable::new(&grade_file, GradeUnit::Decimal, false).unwrap();
+        let engine =
+            SpeedTraversalEngine::new(&filepath(), SpeedUnit::KilometersPerHour, None, None)
+                .unwrap()
+                .with_grade_table(grade_table, 0.02)
+                .unwrap();
+        // flat, 5% uphill, 5% downhill and 50% uphill edges
+        let speeds = (0..4)
+            .map(|i| engine.get_speed(EdgeId(i)).unwrap())
+            .collect::<Vec<_>>();
+        approx_eq(speeds[0].as_f64(), 10.0, 1e-9);
+        approx_eq(speeds[1].as_f64(), 18.0, 1e-9);
+        approx_eq(speeds[2].as_f64(), 30.0, 1e-9);
+        approx_eq(speeds[3].as_f64(), 4.0, 1e-9);
+    }
 }
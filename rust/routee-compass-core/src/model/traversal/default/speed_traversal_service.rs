@@ -1,14 +1,38 @@
 use super::{
     speed_traversal_engine::SpeedTraversalEngine, speed_traversal_model::SpeedTraversalModel,
 };
-use crate::model::traversal::{
-    traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
-    traversal_model_service::TraversalModelService,
+use crate::model::{
+    road_network::edge_id::EdgeId,
+    traversal::{
+        traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
+        traversal_model_service::TraversalModelService,
+    },
+    unit::{Grade, GradeUnit, Speed, SpeedUnit},
 };
+use crate::util::read_only_lock::DriverReadOnlyLock;
 use std::sync::Arc;
 
+/// builds [SpeedTraversalModel]s from a shared engine. the engine sits behind a lock so
+/// that its speeds can be swapped while the app is running. each query takes a snapshot
+/// of the engine, so an update only affects queries built after it.
 pub struct SpeedLookupService {
-    pub e: Arc<SpeedTraversalEngine>,
+    e: DriverReadOnlyLock<Arc<SpeedTraversalEngine>>,
+}
+
+impl SpeedLookupService {
+    pub fn new(e: Arc<SpeedTraversalEngine>) -> SpeedLookupService {
+        SpeedLookupService {
+            e: DriverReadOnlyLock::new(e),
+        }
+    }
+
+    /// the current engine of this service
+    pub fn engine(&self) -> Result<Arc<SpeedTraversalEngine>, TraversalModelError> {
+        let engine = self.e.read().map_err(|e| {
+            TraversalModelError::InternalError(format!("speed table lock poisoned: {}", e))
+        })?;
+        Ok(engine.clone())
+    }
 }
 
 impl TraversalModelService for SpeedLookupService {
@@ -16,6 +40,77 @@ impl TraversalModelService for SpeedLookupService {
         &self,
         _parameters: &serde_json::Value,
     ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
-        Ok(Arc::new(SpeedTraversalModel::new(self.e.clone())))
+        Ok(Arc::new(SpeedTraversalModel::new(self.engine()?)))
+    }
+
+    fn update_edge_speeds(
+        &self,
+        speeds: &[(EdgeId, Speed)],
+        speed_unit: &SpeedUnit,
+    ) -> Result<(), TraversalModelError> {
+        let mut engine = self.e.write().map_err(|e| {
+            TraversalModelError::InternalError(format!("speed table lock poisoned: {}", e))
+        })?;
+        let updated = engine.with_speed_updates(speeds, speed_unit)?;
+        *engine = Arc::new(updated);
+        Ok(())
+    }
+
+    fn edge_speed(&self, edge_id: EdgeId) -> Result<(Speed, SpeedUnit), TraversalModelError> {
+        let engine = self.engine()?;
+        Ok((engine.get_speed(edge_id)?, engine.speed_unit))
+    }
+
+    fn edge_grade(&self, edge_id: EdgeId) -> Result<(Grade, GradeUnit), TraversalModelError> {
+        let engine = self.engine()?;
+        match &engine.grade_table {
+            Some(grade_table) => Ok((grade_table.get_grade(edge_id)?, grade_table.grade_unit)),
+            None => Err(TraversalModelError::UnsupportedOperation(String::from(
+                "this speed model does not have a grade table",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::unit::{DistanceUnit, TimeUnit};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_update_edge_speeds() {
+        let speed_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join("velocities.txt");
+        let engine = SpeedTraversalEngine::new(
+            &speed_file,
+            SpeedUnit::KilometersPerHour,
+            Some(DistanceUnit::Kilometers),
+            Some(TimeUnit::Hours),
+        )
+        .unwrap();
+        let service = SpeedLookupService::new(Arc::new(engine));
+        let before = service.engine().unwrap();
+
+        service
+            .update_edge_speeds(&[(EdgeId(0), Speed::new(60.0))], &SpeedUnit::MilesPerHour)
+            .unwrap();
+        let after = service.engine().unwrap();
+        let expected = Speed::new(60.0 * 1.60934);
+        assert_eq!(after.get_speed(EdgeId(0)).unwrap(), expected);
+        assert_eq!(after.max_speed, expected);
+        // snapshots taken before the update are unchanged
+        assert_ne!(before.get_speed(EdgeId(0)).unwrap(), expected);
+
+        let missing = service.update_edge_speeds(
+            &[(EdgeId(before.speed_table.len()), Speed::new(10.0))],
+            &SpeedUnit::KilometersPerHour,
+        );
+        assert!(missing.is_err());
     }
 }
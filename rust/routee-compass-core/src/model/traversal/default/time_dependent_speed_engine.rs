@@ -0,0 +1,122 @@
+use super::speed_traversal_engine::get_max_speed;
+use crate::model::unit::{
+    DistanceUnit, SpeedUnit, Time, TimeUnit, BASE_DISTANCE_UNIT, BASE_TIME_UNIT,
+};
+use crate::{
+    model::{traversal::traversal_model_error::TraversalModelError, unit::Speed},
+    util::fs::read_utils,
+};
+use std::path::Path;
+use std::str::FromStr;
+
+/// number of seconds in a day, the period of a speed profile
+pub const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// speed lookup where each edge has a daily speed profile made up of
+/// fixed-duration time slots. the profile file has one row per edge
+/// (ordered by EdgeId) containing a comma-delimited speed for each slot,
+/// starting from midnight.
+pub struct TimeDependentSpeedEngine {
+    pub speed_profiles: Box<[Box<[Speed]>]>,
+    pub slot_duration: Time,
+    pub speed_unit: SpeedUnit,
+    pub time_unit: TimeUnit,
+    pub distance_unit: DistanceUnit,
+    pub max_speed: Speed,
+}
+
+impl TimeDependentSpeedEngine {
+    pub fn new<P: AsRef<Path>>(
+        speed_profile_path: &P,
+        speed_unit: SpeedUnit,
+        slot_duration: Time,
+        slot_duration_unit: TimeUnit,
+        distance_unit_opt: Option<DistanceUnit>,
+        time_unit_opt: Option<TimeUnit>,
+    ) -> Result<TimeDependentSpeedEngine, TraversalModelError> {
+        let speed_profiles: Box<[Box<[Speed]>]> =
+            read_utils::read_raw_file(speed_profile_path, decode_profile, None).map_err(|e| {
+                TraversalModelError::FileReadError(
+                    speed_profile_path.as_ref().to_path_buf(),
+                    e.to_string(),
+                )
+            })?;
+
+        let n_slots = speed_profiles.first().map(|p| p.len()).unwrap_or_default();
+        if let Some((idx, _)) = speed_profiles
+            .iter()
+            .enumerate()
+            .find(|(_, p)| p.len() != n_slots)
+        {
+            let msg = format!(
+                "speed profile for edge {} does not have the expected {} time slots",
+                idx, n_slots
+            );
+            return Err(TraversalModelError::BuildError(msg));
+        }
+
+        let slot_duration = slot_duration_unit.convert(&slot_duration, &TimeUnit::Seconds);
+        if slot_duration <= Time::ZERO {
+            let msg = format!(
+                "speed profile slot duration must be positive, found {} seconds",
+                slot_duration
+            );
+            return Err(TraversalModelError::BuildError(msg));
+        }
+        let profile_seconds = n_slots as f64 * slot_duration.to_f64();
+        if (profile_seconds - SECONDS_PER_DAY).abs() > 1e-6 {
+            let msg = format!(
+                "speed profiles have {} time slots of {} seconds which cover {} seconds, expected a full day of {} seconds",
+                n_slots, slot_duration, profile_seconds, SECONDS_PER_DAY
+            );
+            return Err(TraversalModelError::BuildError(msg));
+        }
+
+        let all_speeds = speed_profiles
+            .iter()
+            .flat_map(|p| p.iter().cloned())
+            .collect::<Vec<_>>();
+        let max_speed = get_max_speed(&all_speeds)?;
+        let time_unit = time_unit_opt.unwrap_or(BASE_TIME_UNIT);
+        let distance_unit = distance_unit_opt.unwrap_or(BASE_DISTANCE_UNIT);
+        let engine = TimeDependentSpeedEngine {
+            speed_profiles,
+            slot_duration,
+            speed_unit,
+            time_unit,
+            distance_unit,
+            max_speed,
+        };
+        Ok(engine)
+    }
+
+    /// number of time slots in each speed profile
+    pub fn n_slots(&self) -> usize {
+        self.speed_profiles
+            .first()
+            .map(|p| p.len())
+            .unwrap_or_default()
+    }
+
+    /// finds the slot index for a time of day, in seconds since midnight.
+    /// times beyond the end of the day wrap around to the following day.
+    pub fn slot_index(&self, seconds_since_midnight: f64) -> usize {
+        let time_of_day = seconds_since_midnight.rem_euclid(SECONDS_PER_DAY);
+        let slot = (time_of_day / self.slot_duration.to_f64()).floor() as usize;
+        // the profile covers a whole day, so only float rounding at the end of the
+        // day can land past the last slot
+        slot.min(self.n_slots().saturating_sub(1))
+    }
+}
+
+/// decodes a row of comma-delimited speed values
+fn decode_profile(idx: usize, row: String) -> Result<Box<[Speed]>, std::io::Error> {
+    row.split(',')
+        .map(|s| {
+            Speed::from_str(s.trim()).map_err(|e| {
+                let msg = format!("failure decoding speed profile row {} due to: {}", idx, e);
+                std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+            })
+        })
+        .collect()
+}
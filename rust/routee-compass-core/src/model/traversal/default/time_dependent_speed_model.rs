@@ -0,0 +1,335 @@
+use super::query_time::QueryTime;
+use super::time_dependent_speed_engine::TimeDependentSpeedEngine;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::state::state_feature::StateFeature;
+use crate::model::state::state_model::StateModel;
+use crate::model::traversal::traversal_model::TraversalModel;
+use crate::model::unit::{Distance, Time, TimeUnit, BASE_DISTANCE_UNIT};
+use crate::model::{
+    property::{edge::Edge, vertex::Vertex},
+    traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
+    unit::Speed,
+};
+use crate::util::geo::haversine;
+use std::sync::Arc;
+
+/// traversal model where edge speeds vary by time of day. the time of day
+/// when entering an edge is the query departure time plus the time
+/// accumulated on the search state so far. for arrive-by queries, which are
+/// searched in reverse, speeds are indexed backward from the arrival time.
+pub struct TimeDependentSpeedModel {
+    engine: Arc<TimeDependentSpeedEngine>,
+    query_time: QueryTime,
+}
+
+impl TimeDependentSpeedModel {
+    /// # Arguments
+    ///
+    /// * `engine` - speed profiles shared across queries
+    /// * `query_time` - departure or arrival time of this query, in seconds since midnight
+    pub fn new(
+        engine: Arc<TimeDependentSpeedEngine>,
+        query_time: QueryTime,
+    ) -> TimeDependentSpeedModel {
+        TimeDependentSpeedModel { engine, query_time }
+    }
+    const DISTANCE: &'static str = "distance";
+    const TIME: &'static str = "time";
+}
+
+impl TraversalModel for TimeDependentSpeedModel {
+    fn is_time_dependent(&self) -> bool {
+        true
+    }
+
+    fn traverse_edge(
+        &self,
+        trajectory: (&Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (_, edge, _) = trajectory;
+        let elapsed = state_model.get_time(state, &Self::TIME.into(), &TimeUnit::Seconds)?;
+        let slot = self
+            .engine
+            .slot_index(self.query_time.time_at(elapsed.to_f64()));
+        let speed = get_profile_speed(&self.engine.speed_profiles, edge.edge_id, slot)?;
+        let distance = BASE_DISTANCE_UNIT.convert(&edge.distance, &self.engine.distance_unit);
+        let edge_time = Time::create(
+            &speed,
+            &self.engine.speed_unit,
+            &distance,
+            &self.engine.distance_unit,
+            &self.engine.time_unit,
+        )?;
+
+        state_model.add_time(
+            state,
+            &Self::TIME.into(),
+            &edge_time,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        Ok(())
+    }
+
+    fn estimate_traversal(
+        &self,
+        od: (&Vertex, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (src, dst) = od;
+        let distance =
+            haversine::coord_distance(&src.coordinate, &dst.coordinate, self.engine.distance_unit)
+                .map_err(TraversalModelError::NumericError)?;
+
+        if distance == Distance::ZERO {
+            return Ok(());
+        }
+
+        // the max speed over all time slots keeps the estimate admissible
+        let estimated_time = Time::create(
+            &self.engine.max_speed,
+            &self.engine.speed_unit,
+            &distance,
+            &self.engine.distance_unit,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_time(
+            state,
+            &Self::TIME.into(),
+            &estimated_time,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+
+        Ok(())
+    }
+
+    /// track the time and distance state features
+    fn state_features(&self) -> Vec<(String, StateFeature)> {
+        vec![
+            (
+                String::from(Self::TIME),
+                StateFeature::Time {
+                    time_unit: self.engine.time_unit,
+                    initial: Time::ZERO,
+                },
+            ),
+            (
+                String::from(Self::DISTANCE),
+                StateFeature::Distance {
+                    distance_unit: self.engine.distance_unit,
+                    initial: Distance::ZERO,
+                },
+            ),
+        ]
+    }
+}
+
+/// look up the speed of an edge during a time slot
+pub fn get_profile_speed(
+    speed_profiles: &[Box<[Speed]>],
+    edge_id: EdgeId,
+    slot: usize,
+) -> Result<Speed, TraversalModelError> {
+    let speed = speed_profiles
+        .get(edge_id.as_usize())
+        .and_then(|profile| profile.get(slot))
+        .ok_or_else(|| {
+            TraversalModelError::MissingIdInTabularCostFunction(
+                format!("{} (time slot {})", edge_id, slot),
+                String::from("EdgeId"),
+                String::from("speed profile table"),
+            )
+        })?;
+    Ok(*speed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::traversal::default::time_dependent_speed_service::TimeDependentSpeedService;
+    use crate::model::traversal::traversal_model_service::TraversalModelService;
+    use crate::model::unit::{SpeedUnit, TimeUnit};
+    use crate::model::{
+        property::{edge::Edge, vertex::Vertex},
+        road_network::{edge_id::EdgeId, vertex_id::VertexId},
+    };
+    use crate::util::geo::coord::InternalCoord;
+    use geo::coord;
+    use std::path::PathBuf;
+
+    fn mock_vertex() -> Vertex {
+        Vertex {
+            vertex_id: VertexId(0),
+            coordinate: InternalCoord(coord! {x: -86.67, y: 36.12}),
+        }
+    }
+    fn mock_edge(edge_id: usize) -> Edge {
+        Edge {
+            edge_id: EdgeId(edge_id),
+            src_vertex_id: VertexId(0),
+            dst_vertex_id: VertexId(1),
+            distance: Distance::new(1000.0),
+        }
+    }
+    fn mock_engine() -> TimeDependentSpeedEngine {
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join("speed_profiles.csv");
+        // six 4-hour time slots per day
+        TimeDependentSpeedEngine::new(
+            &file,
+            SpeedUnit::KilometersPerHour,
+            Time::new(4.0),
+            TimeUnit::Hours,
+            None,
+            Some(TimeUnit::Seconds),
+        )
+        .unwrap()
+    }
+    fn mock_state_model(model: &TimeDependentSpeedModel) -> StateModel {
+        StateModel::empty().extend(model.state_features()).unwrap()
+    }
+
+    #[test]
+    fn test_speed_varies_with_departure_time() {
+        let service = TimeDependentSpeedService {
+            e: Arc::new(mock_engine()),
+        };
+        let v = mock_vertex();
+        let e = mock_edge(0);
+        // edge 0 runs at 60kph from 04:00 to 08:00 and 20kph from 08:00 to 12:00
+        for (departure_time, expected_seconds) in [
+            ("2024-03-01T05:00:00", 60.0),
+            ("2024-03-01T08:15:00", 180.0),
+        ] {
+            let query = serde_json::json!({ "departure_time": departure_time });
+            let model = service.build(&query).unwrap();
+            let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+            let mut state = state_model.initial_state().unwrap();
+            model
+                .traverse_edge((&v, &e, &v), &mut state, &state_model)
+                .unwrap();
+            let time = state_model
+                .get_time(&state, &"time".into(), &TimeUnit::Seconds)
+                .unwrap();
+            assert!((time.to_f64() - expected_seconds).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_accumulated_time_moves_into_next_slot() {
+        let model = TimeDependentSpeedModel::new(Arc::new(mock_engine()), QueryTime::default());
+        let state_model = mock_state_model(&model);
+        let mut state = state_model.initial_state().unwrap();
+        // 4 hours after a midnight departure the next edge is entered in the second slot
+        state_model
+            .add_time(
+                &mut state,
+                &"time".into(),
+                &Time::new(4.0 * 3600.0),
+                &TimeUnit::Seconds,
+            )
+            .unwrap();
+        let v = mock_vertex();
+        let e = mock_edge(1);
+        model
+            .traverse_edge((&v, &e, &v), &mut state, &state_model)
+            .unwrap();
+        // edge 1 runs at 30kph in the second slot: 1km takes 120 seconds
+        let time = state_model
+            .get_time(&state, &"time".into(), &TimeUnit::Seconds)
+            .unwrap();
+        assert!((time.to_f64() - (4.0 * 3600.0 + 120.0)).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_speed_indexed_backward_from_arrival_time() {
+        let service = TimeDependentSpeedService {
+            e: Arc::new(mock_engine()),
+        };
+        let query = serde_json::json!({ "arrival_time": "2024-03-01T08:15:00" });
+        let model = service.build(&query).unwrap();
+        let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+        let mut state = state_model.initial_state().unwrap();
+        let v = mock_vertex();
+        let e = mock_edge(0);
+        // the last edge of the route is left at 08:15, when edge 0 runs at 20kph
+        model
+            .traverse_edge((&v, &e, &v), &mut state, &state_model)
+            .unwrap();
+        let time = |state: &[StateVar]| {
+            state_model
+                .get_time(state, &"time".into(), &TimeUnit::Seconds)
+                .unwrap()
+                .to_f64()
+        };
+        assert!((time(&state) - 180.0).abs() < 0.001);
+        // an hour earlier, before 08:00, edge 0 runs at 60kph
+        state_model
+            .add_time(
+                &mut state,
+                &"time".into(),
+                &Time::new(3600.0 - 180.0),
+                &TimeUnit::Seconds,
+            )
+            .unwrap();
+        model
+            .traverse_edge((&v, &e, &v), &mut state, &state_model)
+            .unwrap();
+        assert!((time(&state) - 3660.0).abs() < 0.001);
+
+        let both = serde_json::json!({
+            "departure_time": "2024-03-01T08:15:00",
+            "arrival_time": "2024-03-01T09:15:00"
+        });
+        assert!(service.build(&both).is_err());
+    }
+
+    #[test]
+    fn test_invalid_departure_time() {
+        let service = TimeDependentSpeedService {
+            e: Arc::new(mock_engine()),
+        };
+        let query = serde_json::json!({ "departure_time": "08:15 on a tuesday" });
+        assert!(service.build(&query).is_err());
+    }
+
+    #[test]
+    fn test_profile_shorter_than_a_day_is_rejected() {
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join("speed_profiles.csv");
+        // six 1-hour time slots only cover a quarter of the day
+        let result = TimeDependentSpeedEngine::new(
+            &file,
+            SpeedUnit::KilometersPerHour,
+            Time::new(1.0),
+            TimeUnit::Hours,
+            None,
+            Some(TimeUnit::Seconds),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use super::{
+    query_time::QueryTime, time_dependent_speed_engine::TimeDependentSpeedEngine,
+    time_dependent_speed_model::TimeDependentSpeedModel,
+};
+use crate::model::traversal::{
+    traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
+    traversal_model_service::TraversalModelService,
+};
+use chrono::{NaiveDateTime, Timelike};
+use std::sync::Arc;
+
+pub struct TimeDependentSpeedService {
+    pub e: Arc<TimeDependentSpeedEngine>,
+}
+
+impl TimeDependentSpeedService {
+    pub const DEPARTURE_TIME: &'static str = "departure_time";
+    pub const ARRIVAL_TIME: &'static str = "arrival_time";
+    pub const DEPARTURE_TIME_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S";
+
+    /// reads the optional `departure_time` field of a query in ISO 8601 format
+    /// (e.g. "2024-03-01T08:15:00").
+    pub fn departure_time(
+        query: &serde_json::Value,
+    ) -> Result<Option<NaiveDateTime>, TraversalModelError> {
+        Self::read_time(query, Self::DEPARTURE_TIME)
+    }
+
+    /// reads the optional `arrival_time` field of an arrive-by query, in the same
+    /// format as the `departure_time`.
+    pub fn arrival_time(
+        query: &serde_json::Value,
+    ) -> Result<Option<NaiveDateTime>, TraversalModelError> {
+        Self::read_time(query, Self::ARRIVAL_TIME)
+    }
+
+    /// reads the departure or arrival time of a query, which may not have both.
+    /// queries without either are treated as departing at the start of the period.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - the search query
+    /// * `to_seconds` - converts a time to seconds since the start of the period of the speed data
+    pub fn query_time(
+        query: &serde_json::Value,
+        to_seconds: fn(&NaiveDateTime) -> f64,
+    ) -> Result<QueryTime, TraversalModelError> {
+        match (Self::departure_time(query)?, Self::arrival_time(query)?) {
+            (Some(_), Some(_)) => Err(TraversalModelError::BuildError(format!(
+                "query may have a '{}' or an '{}', but not both",
+                Self::DEPARTURE_TIME,
+                Self::ARRIVAL_TIME
+            ))),
+            (_, Some(arrival)) => Ok(QueryTime::Arrival(to_seconds(&arrival))),
+            (departure, None) => Ok(QueryTime::Departure(
+                departure.as_ref().map(to_seconds).unwrap_or_default(),
+            )),
+        }
+    }
+
+    fn read_time(
+        query: &serde_json::Value,
+        field: &str,
+    ) -> Result<Option<NaiveDateTime>, TraversalModelError> {
+        let value = match query.get(field) {
+            None => return Ok(None),
+            Some(value) => value,
+        };
+        let time_str = value.as_str().ok_or_else(|| {
+            TraversalModelError::BuildError(format!(
+                "query field '{}' must be a string, found {}",
+                field, value
+            ))
+        })?;
+        let time =
+            NaiveDateTime::parse_from_str(time_str, Self::DEPARTURE_TIME_FORMAT).map_err(|e| {
+                TraversalModelError::BuildError(format!(
+                    "unable to parse query field '{}' with value '{}': {}",
+                    field, time_str, e
+                ))
+            })?;
+        Ok(Some(time))
+    }
+
+    /// reads the optional `departure_time` field of a query as seconds since midnight.
+    pub fn departure_seconds(
+        query: &serde_json::Value,
+    ) -> Result<Option<f64>, TraversalModelError> {
+        let departure_time = Self::departure_time(query)?;
+        Ok(departure_time.map(|t| t.num_seconds_from_midnight() as f64))
+    }
+}
+
+impl TraversalModelService for TimeDependentSpeedService {
+    /// builds a model for a query with an optional `departure_time` or `arrival_time`
+    /// field. queries without either are treated as departing at midnight.
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
+        let query_time = Self::query_time(parameters, |t| t.num_seconds_from_midnight() as f64)?;
+        let model = TimeDependentSpeedModel::new(self.e.clone(), query_time);
+        Ok(Arc::new(model))
+    }
+}
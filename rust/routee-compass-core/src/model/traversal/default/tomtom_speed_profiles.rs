@@ -0,0 +1,193 @@
+use super::weekly_speed_profile_engine::{ProfileSpeed, WeeklySpeedProfileEngine, DAYS_PER_WEEK};
+use crate::model::traversal::traversal_model_error::TraversalModelError;
+use crate::model::unit::{DistanceUnit, Speed, SpeedUnit, TimeUnit};
+use crate::util::fs::{fs_utils, read_utils};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// a row of a TomTom network-to-profile table, one per edge ordered by EdgeId.
+/// each day refers to a speed profile by id, or is empty to travel at the free
+/// flow speed. other columns, such as the TomTom network id, are ignored.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct TomTomNetworkProfile {
+    #[serde(alias = "spfreeflow")]
+    pub free_flow_speed: Speed,
+    #[serde(alias = "profile_1")]
+    pub sunday: Option<u64>,
+    #[serde(alias = "profile_2")]
+    pub monday: Option<u64>,
+    #[serde(alias = "profile_3")]
+    pub tuesday: Option<u64>,
+    #[serde(alias = "profile_4")]
+    pub wednesday: Option<u64>,
+    #[serde(alias = "profile_5")]
+    pub thursday: Option<u64>,
+    #[serde(alias = "profile_6")]
+    pub friday: Option<u64>,
+    #[serde(alias = "profile_7")]
+    pub saturday: Option<u64>,
+}
+
+impl TomTomNetworkProfile {
+    /// profile ids from Sunday to Saturday
+    pub fn week(&self) -> [Option<u64>; DAYS_PER_WEEK] {
+        [
+            self.sunday,
+            self.monday,
+            self.tuesday,
+            self.wednesday,
+            self.thursday,
+            self.friday,
+            self.saturday,
+        ]
+    }
+}
+
+/// reads TomTom Speed Profiles into a weekly speed profile engine.
+///
+/// # Arguments
+///
+/// * `network_profile_path` - network-to-profile CSV with a header of
+///   `free_flow_speed,sunday,monday,tuesday,wednesday,thursday,friday,saturday`
+///   (or the TomTom names `spfreeflow,profile_1,...,profile_7`), one row per edge
+/// * `speed_profile_path` - speed profile CSV with a header row, where each row is a
+///   profile id followed by the percent of free flow speed for each time slot of a
+///   day, such as 96 slots of 15 minutes
+/// * `speed_unit` - unit of the free flow speeds
+pub fn read_tomtom_speed_profiles<P: AsRef<Path>>(
+    network_profile_path: &P,
+    speed_profile_path: &P,
+    speed_unit: SpeedUnit,
+    distance_unit: DistanceUnit,
+    time_unit: TimeUnit,
+) -> Result<WeeklySpeedProfileEngine, TraversalModelError> {
+    let (profile_ids, profiles) = read_profiles(speed_profile_path)?;
+
+    let network: Box<[TomTomNetworkProfile]> =
+        read_utils::from_csv(network_profile_path, true, None).map_err(|e| {
+            TraversalModelError::FileReadError(
+                network_profile_path.as_ref().to_path_buf(),
+                e.to_string(),
+            )
+        })?;
+    let free_flow_speeds = network.iter().map(|row| row.free_flow_speed).collect();
+    let weekly_profiles = network
+        .iter()
+        .enumerate()
+        .map(|(edge_idx, row)| {
+            let mut week = [None; DAYS_PER_WEEK];
+            for (day, profile_id) in row.week().iter().enumerate() {
+                week[day] = match profile_id {
+                    None => None,
+                    Some(id) => Some(*profile_ids.get(id).ok_or_else(|| {
+                        TraversalModelError::BuildError(format!(
+                            "edge {} refers to speed profile {} which is not in {:?}",
+                            edge_idx,
+                            id,
+                            speed_profile_path.as_ref()
+                        ))
+                    })?),
+                };
+            }
+            Ok(week)
+        })
+        .collect::<Result<Vec<_>, TraversalModelError>>()?
+        .into_boxed_slice();
+
+    WeeklySpeedProfileEngine::new(
+        free_flow_speeds,
+        weekly_profiles,
+        profiles,
+        ProfileSpeed::PercentOfFreeFlow,
+        speed_unit,
+        distance_unit,
+        time_unit,
+    )
+}
+
+/// reads the speed profile table, returning the profiles along with the position of
+/// each profile id
+#[allow(clippy::type_complexity)]
+fn read_profiles<P: AsRef<Path>>(
+    speed_profile_path: &P,
+) -> Result<(HashMap<u64, usize>, Box<[Box<[f64]>]>), TraversalModelError> {
+    let read_error = |e: String| {
+        TraversalModelError::FileReadError(speed_profile_path.as_ref().to_path_buf(), e)
+    };
+    let reader =
+        fs_utils::open_reader(speed_profile_path).map_err(|e| read_error(e.to_string()))?;
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let mut profile_ids = HashMap::new();
+    let mut profiles = vec![];
+    for (idx, record) in csv_reader.records().enumerate() {
+        let record = record.map_err(|e| read_error(e.to_string()))?;
+        let mut fields = record.iter();
+        let profile_id = fields
+            .next()
+            .unwrap_or_default()
+            .parse::<u64>()
+            .map_err(|e| read_error(format!("row {} has an invalid profile id: {}", idx, e)))?;
+        let profile = fields
+            .map(|v| v.parse::<f64>())
+            .collect::<Result<Box<[f64]>, _>>()
+            .map_err(|e| read_error(format!("row {} has an invalid speed: {}", idx, e)))?;
+        if profile_ids.insert(profile_id, profiles.len()).is_some() {
+            return Err(read_error(format!("duplicate profile id {}", profile_id)));
+        }
+        profiles.push(profile);
+    }
+    Ok((profile_ids, profiles.into_boxed_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::road_network::edge_id::EdgeId;
+    use crate::model::traversal::default::time_dependent_speed_engine::SECONDS_PER_DAY;
+    use crate::model::traversal::default::weekly_speed_profile_service::WeeklySpeedProfileService;
+    use std::path::PathBuf;
+
+    fn test_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+    }
+
+    #[test]
+    fn test_tomtom_speed_profiles() {
+        let engine = read_tomtom_speed_profiles(
+            &test_dir().join("tomtom_network_profiles.csv"),
+            &test_dir().join("tomtom_speed_profiles.csv"),
+            SpeedUnit::KilometersPerHour,
+            DistanceUnit::Kilometers,
+            TimeUnit::Hours,
+        )
+        .unwrap();
+        // four 6-hour time slots per day
+        assert_eq!(engine.slot_duration, 6.0 * 3600.0);
+        assert_eq!(engine.max_speed, Speed::new(100.0));
+
+        // edge 0 follows profile 10 on weekdays, 20 on saturday and free flow on sunday
+        let monday_8am = SECONDS_PER_DAY + 8.0 * 3600.0;
+        let saturday_8am = 6.0 * SECONDS_PER_DAY + 8.0 * 3600.0;
+        let sunday_8am = 8.0 * 3600.0;
+        let speed = |t: f64| engine.get_speed(EdgeId(0), t).unwrap();
+        assert_eq!(speed(monday_8am), Speed::new(40.0));
+        assert_eq!(speed(saturday_8am), Speed::new(80.0));
+        assert_eq!(speed(sunday_8am), Speed::new(80.0));
+        // a week later is the same as the first week
+        assert_eq!(speed(monday_8am + 7.0 * SECONDS_PER_DAY), Speed::new(40.0));
+
+        let query = serde_json::json!({ "departure_time": "2024-03-04T08:00:00" });
+        let departure = WeeklySpeedProfileService::departure_seconds(&query).unwrap();
+        assert_eq!(departure, Some(monday_8am));
+    }
+}
@@ -0,0 +1,252 @@
+use super::time_dependent_speed_engine::SECONDS_PER_DAY;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::traversal::traversal_model_error::TraversalModelError;
+use crate::model::unit::{DistanceUnit, Speed, SpeedUnit, TimeUnit};
+use crate::util::fs::{read_decoders, read_utils};
+use serde::Deserialize;
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+/// how an edge of a multimodal graph is traveled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitEdgeMode {
+    /// walked at the walk speed
+    Road,
+    /// walked between a transit stop and the road network
+    Link,
+    /// ridden on a scheduled transit trip between two stops
+    Transit,
+}
+
+impl FromStr for TransitEdgeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "road" => Ok(TransitEdgeMode::Road),
+            "link" => Ok(TransitEdgeMode::Link),
+            "transit" => Ok(TransitEdgeMode::Transit),
+            other => Err(format!(
+                "unknown edge mode '{}', expected road, link or transit",
+                other
+            )),
+        }
+    }
+}
+
+impl Display for TransitEdgeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TransitEdgeMode::Road => "road",
+            TransitEdgeMode::Link => "link",
+            TransitEdgeMode::Transit => "transit",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// a row of a transit departures file
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct TransitDepartureRow {
+    pub edge_id: EdgeId,
+    /// seconds since midnight when a trip leaves the edge source stop
+    pub departure: f64,
+    /// seconds since midnight when that trip reaches the edge destination stop
+    pub arrival: f64,
+}
+
+/// a scheduled departure along a transit edge, in seconds since midnight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransitDeparture {
+    pub departure: f64,
+    /// the earliest arrival of this or any later departure, so that a traveler who
+    /// can board this departure never arrives later than necessary
+    pub earliest_arrival: f64,
+}
+
+/// a road network combined with a transit layer, where transit edges follow a daily
+/// timetable and all other edges are walked.
+pub struct TransitEngine {
+    pub edge_modes: Box<[TransitEdgeMode]>,
+    /// departures of each transit edge sorted by departure time, by EdgeId
+    pub departures: Box<[Box<[TransitDeparture]>]>,
+    pub walk_speed: Speed,
+    pub walk_speed_unit: SpeedUnit,
+    pub distance_unit: DistanceUnit,
+    pub time_unit: TimeUnit,
+}
+
+impl TransitEngine {
+    pub fn new<P: AsRef<Path>>(
+        edge_mode_path: &P,
+        departures_path: &P,
+        walk_speed: Speed,
+        walk_speed_unit: SpeedUnit,
+        distance_unit: DistanceUnit,
+        time_unit: TimeUnit,
+    ) -> Result<TransitEngine, TraversalModelError> {
+        let edge_modes: Box<[TransitEdgeMode]> = read_utils::read_raw_file(
+            edge_mode_path,
+            read_decoders::default,
+            None,
+        )
+        .map_err(|e| {
+            TraversalModelError::FileReadError(edge_mode_path.as_ref().to_path_buf(), e.to_string())
+        })?;
+        let rows: Box<[TransitDepartureRow]> = read_utils::from_csv(departures_path, true, None)
+            .map_err(|e| {
+                TraversalModelError::FileReadError(
+                    departures_path.as_ref().to_path_buf(),
+                    e.to_string(),
+                )
+            })?;
+        TransitEngine::from_rows(
+            edge_modes,
+            &rows,
+            walk_speed,
+            walk_speed_unit,
+            distance_unit,
+            time_unit,
+        )
+    }
+
+    /// builds an engine from edge modes and departure rows in any order
+    pub fn from_rows(
+        edge_modes: Box<[TransitEdgeMode]>,
+        rows: &[TransitDepartureRow],
+        walk_speed: Speed,
+        walk_speed_unit: SpeedUnit,
+        distance_unit: DistanceUnit,
+        time_unit: TimeUnit,
+    ) -> Result<TransitEngine, TraversalModelError> {
+        if walk_speed <= Speed::ZERO {
+            return Err(TraversalModelError::BuildError(format!(
+                "walk speed must be positive, found {}",
+                walk_speed
+            )));
+        }
+        let mut schedules: Vec<Vec<(f64, f64)>> = vec![vec![]; edge_modes.len()];
+        for row in rows.iter() {
+            let edge_idx = row.edge_id.as_usize();
+            match edge_modes.get(edge_idx) {
+                Some(TransitEdgeMode::Transit) => {}
+                _ => {
+                    return Err(TraversalModelError::BuildError(format!(
+                        "departure listed for edge {} which is not a transit edge",
+                        row.edge_id
+                    )))
+                }
+            }
+            if row.arrival < row.departure {
+                return Err(TraversalModelError::BuildError(format!(
+                    "departure on edge {} arrives at {} before it departs at {}",
+                    row.edge_id, row.arrival, row.departure
+                )));
+            }
+            // timetables repeat daily, so departures after midnight of the service
+            // day (such as 25:10:00 in GTFS) are moved to the start of the day
+            let departure = row.departure.rem_euclid(SECONDS_PER_DAY);
+            let arrival = departure + (row.arrival - row.departure);
+            schedules[edge_idx].push((departure, arrival));
+        }
+
+        let departures = schedules
+            .into_iter()
+            .map(|mut schedule| {
+                schedule.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let mut earliest_arrival = f64::INFINITY;
+                let mut departures = schedule
+                    .iter()
+                    .rev()
+                    .map(|(departure, arrival)| {
+                        earliest_arrival = earliest_arrival.min(*arrival);
+                        TransitDeparture {
+                            departure: *departure,
+                            earliest_arrival,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                departures.reverse();
+                departures.into_boxed_slice()
+            })
+            .collect();
+
+        Ok(TransitEngine {
+            edge_modes,
+            departures,
+            walk_speed,
+            walk_speed_unit,
+            distance_unit,
+            time_unit,
+        })
+    }
+
+    /// the mode of an edge
+    pub fn edge_mode(&self, edge_id: EdgeId) -> Result<TransitEdgeMode, TraversalModelError> {
+        self.edge_modes
+            .get(edge_id.as_usize())
+            .cloned()
+            .ok_or_else(|| {
+                TraversalModelError::MissingIdInTabularCostFunction(
+                    format!("{}", edge_id),
+                    String::from("EdgeId"),
+                    String::from("transit edge mode table"),
+                )
+            })
+    }
+
+    /// the time in seconds to reach the end of a transit edge when arriving at its
+    /// source stop at some time, including the wait for the next departure. when no
+    /// departures remain that day, the traveler waits for the first one the next day.
+    /// returns None for an edge without any departures.
+    pub fn transit_travel_seconds(&self, edge_id: EdgeId, seconds: f64) -> Option<f64> {
+        let departures = self.departures.get(edge_id.as_usize())?;
+        let first = departures.first()?;
+        let time_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+        let next = departures.partition_point(|d| d.departure < time_of_day);
+        let arrival = match departures.get(next) {
+            Some(d) => d.earliest_arrival,
+            None => first.earliest_arrival + SECONDS_PER_DAY,
+        };
+        Some(arrival - time_of_day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transit_travel_seconds() {
+        let modes = vec![TransitEdgeMode::Road, TransitEdgeMode::Transit].into_boxed_slice();
+        let row = |departure: f64, arrival: f64| TransitDepartureRow {
+            edge_id: EdgeId(1),
+            departure,
+            arrival,
+        };
+        // an express at 08:10 overtakes the local at 08:00
+        let rows = vec![
+            row(8.0 * 3600.0, 8.0 * 3600.0 + 1800.0),
+            row(8.0 * 3600.0 + 600.0, 8.0 * 3600.0 + 1200.0),
+            row(25.0 * 3600.0, 25.0 * 3600.0 + 600.0),
+        ];
+        let engine = TransitEngine::from_rows(
+            modes,
+            &rows,
+            Speed::new(5.0),
+            SpeedUnit::KilometersPerHour,
+            DistanceUnit::Meters,
+            TimeUnit::Seconds,
+        )
+        .unwrap();
+        let travel = |t: f64| engine.transit_travel_seconds(EdgeId(1), t).unwrap();
+        // waiting for the express arrives earlier than boarding the local
+        assert_eq!(travel(8.0 * 3600.0 - 60.0), 1260.0);
+        // the 25:00 departure runs at 01:00
+        assert_eq!(travel(0.0), 3600.0 + 600.0);
+        // after the last departure, wait for the first one the next day
+        assert_eq!(travel(9.0 * 3600.0), 16.0 * 3600.0 + 600.0);
+        assert_eq!(engine.transit_travel_seconds(EdgeId(0), 0.0), None);
+    }
+}
@@ -0,0 +1,142 @@
+use super::transit_engine::{TransitEdgeMode, TransitEngine};
+use crate::model::state::state_feature::StateFeature;
+use crate::model::state::state_model::StateModel;
+use crate::model::traversal::traversal_model::TraversalModel;
+use crate::model::unit::{Distance, Time, TimeUnit, BASE_DISTANCE_UNIT};
+use crate::model::{
+    property::{edge::Edge, vertex::Vertex},
+    traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
+};
+use crate::util::geo::haversine;
+use std::sync::Arc;
+
+/// traversal model for walk and transit trips. road and link edges are walked, and
+/// transit edges wait for the next scheduled departure after the time of arrival at
+/// the stop, which is the query departure time plus the time accumulated so far.
+pub struct TransitTraversalModel {
+    engine: Arc<TransitEngine>,
+    departure_seconds: f64,
+}
+
+impl TransitTraversalModel {
+    /// # Arguments
+    ///
+    /// * `engine` - transit timetable shared across queries
+    /// * `departure_seconds` - departure time of this query, in seconds since midnight
+    pub fn new(engine: Arc<TransitEngine>, departure_seconds: f64) -> TransitTraversalModel {
+        TransitTraversalModel {
+            engine,
+            departure_seconds,
+        }
+    }
+    const DISTANCE: &'static str = "distance";
+    const TIME: &'static str = "time";
+    const WALK_DISTANCE: &'static str = "walk_distance";
+    const TRANSIT_DISTANCE: &'static str = "transit_distance";
+}
+
+impl TraversalModel for TransitTraversalModel {
+    fn is_time_dependent(&self) -> bool {
+        true
+    }
+
+    fn traverse_edge(
+        &self,
+        trajectory: (&Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (_, edge, _) = trajectory;
+        let distance = BASE_DISTANCE_UNIT.convert(&edge.distance, &self.engine.distance_unit);
+        let mode = self.engine.edge_mode(edge.edge_id)?;
+        let (edge_time, mode_feature) = match mode {
+            TransitEdgeMode::Road | TransitEdgeMode::Link => {
+                let walk_time = Time::create(
+                    &self.engine.walk_speed,
+                    &self.engine.walk_speed_unit,
+                    &distance,
+                    &self.engine.distance_unit,
+                    &self.engine.time_unit,
+                )?;
+                (walk_time, Self::WALK_DISTANCE)
+            }
+            TransitEdgeMode::Transit => {
+                let elapsed =
+                    state_model.get_time(state, &Self::TIME.into(), &TimeUnit::Seconds)?;
+                let seconds = self
+                    .engine
+                    .transit_travel_seconds(edge.edge_id, self.departure_seconds + elapsed.to_f64())
+                    .ok_or_else(|| {
+                        TraversalModelError::InternalError(format!(
+                            "transit edge {} has no departures",
+                            edge.edge_id
+                        ))
+                    })?;
+                let ride_time =
+                    TimeUnit::Seconds.convert(&Time::new(seconds), &self.engine.time_unit);
+                (ride_time, Self::TRANSIT_DISTANCE)
+            }
+        };
+
+        state_model.add_time(
+            state,
+            &Self::TIME.into(),
+            &edge_time,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &mode_feature.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        Ok(())
+    }
+
+    /// only distance is estimated, since waiting for a departure means there is no
+    /// useful lower bound on the remaining travel time
+    fn estimate_traversal(
+        &self,
+        od: (&Vertex, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (src, dst) = od;
+        let distance =
+            haversine::coord_distance(&src.coordinate, &dst.coordinate, self.engine.distance_unit)
+                .map_err(TraversalModelError::NumericError)?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        Ok(())
+    }
+
+    /// track the time and distance state features, with distance split by mode
+    fn state_features(&self) -> Vec<(String, StateFeature)> {
+        let distance_feature = StateFeature::Distance {
+            distance_unit: self.engine.distance_unit,
+            initial: Distance::ZERO,
+        };
+        vec![
+            (
+                String::from(Self::TIME),
+                StateFeature::Time {
+                    time_unit: self.engine.time_unit,
+                    initial: Time::ZERO,
+                },
+            ),
+            (String::from(Self::DISTANCE), distance_feature.clone()),
+            (String::from(Self::WALK_DISTANCE), distance_feature.clone()),
+            (String::from(Self::TRANSIT_DISTANCE), distance_feature),
+        ]
+    }
+}
@@ -0,0 +1,38 @@
+use super::{
+    query_time::QueryTime, time_dependent_speed_service::TimeDependentSpeedService,
+    transit_engine::TransitEngine, transit_traversal_model::TransitTraversalModel,
+};
+use crate::model::traversal::{
+    traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
+    traversal_model_service::TraversalModelService,
+};
+use chrono::Timelike;
+use std::sync::Arc;
+
+pub struct TransitTraversalService {
+    pub e: Arc<TransitEngine>,
+}
+
+impl TraversalModelService for TransitTraversalService {
+    /// builds a model for a query with an optional `departure_time` field.
+    /// queries without a departure time are treated as departing at midnight.
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
+        let query_time = TimeDependentSpeedService::query_time(parameters, |t| {
+            t.num_seconds_from_midnight() as f64
+        })?;
+        let departure_seconds = match query_time {
+            QueryTime::Departure(seconds) => seconds,
+            QueryTime::Arrival(_) => {
+                return Err(TraversalModelError::BuildError(format!(
+                    "transit traversal model does not support '{}' queries",
+                    TimeDependentSpeedService::ARRIVAL_TIME
+                )))
+            }
+        };
+        let model = TransitTraversalModel::new(self.e.clone(), departure_seconds);
+        Ok(Arc::new(model))
+    }
+}
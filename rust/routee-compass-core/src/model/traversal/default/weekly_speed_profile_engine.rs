@@ -0,0 +1,166 @@
+use super::time_dependent_speed_engine::SECONDS_PER_DAY;
+use crate::model::road_network::edge_id::EdgeId;
+use crate::model::traversal::traversal_model_error::TraversalModelError;
+use crate::model::unit::{DistanceUnit, Speed, SpeedUnit, TimeUnit};
+
+/// number of days in a week, the period of a weekly speed profile
+pub const DAYS_PER_WEEK: usize = 7;
+
+/// number of seconds in a week
+pub const SECONDS_PER_WEEK: f64 = SECONDS_PER_DAY * DAYS_PER_WEEK as f64;
+
+/// how the values of a speed profile are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileSpeed {
+    /// percent of the free flow speed of the edge, so one profile may be shared by
+    /// many edges, as in TomTom Speed Profiles
+    PercentOfFreeFlow,
+    /// speeds in the speed unit of the engine, as in HERE or NPMRDS historical speeds
+    Absolute,
+}
+
+/// speed lookup where each edge has a free flow speed and, for each day of the week,
+/// an optional daily profile of speeds for consecutive time slots starting at
+/// midnight. days without a profile travel at the free flow speed.
+///
+/// this is the layout of historical speed products such as TomTom Speed Profiles
+/// and HERE Traffic Patterns.
+pub struct WeeklySpeedProfileEngine {
+    /// free flow speed of each edge, by EdgeId
+    pub free_flow_speeds: Box<[Speed]>,
+    /// index into `profiles` for each day of the week starting on Sunday, by EdgeId
+    pub weekly_profiles: Box<[[Option<usize>; DAYS_PER_WEEK]]>,
+    /// speed for each time slot of a day, read according to `profile_speed`
+    pub profiles: Box<[Box<[f64]>]>,
+    pub profile_speed: ProfileSpeed,
+    /// duration of each time slot, in seconds
+    pub slot_duration: f64,
+    pub speed_unit: SpeedUnit,
+    pub time_unit: TimeUnit,
+    pub distance_unit: DistanceUnit,
+    pub max_speed: Speed,
+}
+
+impl WeeklySpeedProfileEngine {
+    /// builds an engine, checking that all profiles have the same number of time slots
+    /// and that every edge refers to a profile that exists.
+    pub fn new(
+        free_flow_speeds: Box<[Speed]>,
+        weekly_profiles: Box<[[Option<usize>; DAYS_PER_WEEK]]>,
+        profiles: Box<[Box<[f64]>]>,
+        profile_speed: ProfileSpeed,
+        speed_unit: SpeedUnit,
+        distance_unit: DistanceUnit,
+        time_unit: TimeUnit,
+    ) -> Result<WeeklySpeedProfileEngine, TraversalModelError> {
+        if free_flow_speeds.len() != weekly_profiles.len() {
+            return Err(TraversalModelError::BuildError(format!(
+                "found {} free flow speeds but {} weekly profiles",
+                free_flow_speeds.len(),
+                weekly_profiles.len()
+            )));
+        }
+        let n_slots = profiles.first().map(|p| p.len()).unwrap_or(1);
+        if let Some((idx, _)) = profiles
+            .iter()
+            .enumerate()
+            .find(|(_, p)| p.len() != n_slots || n_slots == 0)
+        {
+            let msg = format!(
+                "speed profile {} does not have the expected {} time slots",
+                idx, n_slots
+            );
+            return Err(TraversalModelError::BuildError(msg));
+        }
+
+        // the fastest speed of each edge over the week bounds the search heuristic
+        let mut max_speed = Speed::ZERO;
+        for (edge_idx, (free_flow, week)) in free_flow_speeds
+            .iter()
+            .zip(weekly_profiles.iter())
+            .enumerate()
+        {
+            for profile_idx in week.iter() {
+                let day_max = match profile_idx {
+                    None => *free_flow,
+                    Some(p) => {
+                        let profile = profiles.get(*p).ok_or_else(|| {
+                            TraversalModelError::BuildError(format!(
+                                "edge {} refers to missing speed profile {}",
+                                edge_idx, p
+                            ))
+                        })?;
+                        let profile_max = profile.iter().cloned().fold(0.0, f64::max);
+                        profile_speed.speed(*free_flow, profile_max)
+                    }
+                };
+                if day_max > max_speed {
+                    max_speed = day_max;
+                }
+            }
+        }
+        if max_speed == Speed::ZERO {
+            let msg = format!(
+                "max speed was zero in weekly speed profiles with {} edges",
+                free_flow_speeds.len()
+            );
+            return Err(TraversalModelError::BuildError(msg));
+        }
+
+        Ok(WeeklySpeedProfileEngine {
+            free_flow_speeds,
+            weekly_profiles,
+            profiles,
+            profile_speed,
+            slot_duration: SECONDS_PER_DAY / n_slots as f64,
+            speed_unit,
+            time_unit,
+            distance_unit,
+            max_speed,
+        })
+    }
+
+    /// looks up the speed of an edge at a time of the week, in seconds since Sunday at
+    /// midnight. times beyond the end of the week wrap around to the following week.
+    pub fn get_speed(
+        &self,
+        edge_id: EdgeId,
+        seconds_since_sunday: f64,
+    ) -> Result<Speed, TraversalModelError> {
+        let missing = || {
+            TraversalModelError::MissingIdInTabularCostFunction(
+                format!("{}", edge_id),
+                String::from("EdgeId"),
+                String::from("weekly speed profile table"),
+            )
+        };
+        let free_flow = self
+            .free_flow_speeds
+            .get(edge_id.as_usize())
+            .ok_or_else(missing)?;
+        let week = self
+            .weekly_profiles
+            .get(edge_id.as_usize())
+            .ok_or_else(missing)?;
+
+        let time_of_week = seconds_since_sunday.rem_euclid(SECONDS_PER_WEEK);
+        let day = ((time_of_week / SECONDS_PER_DAY).floor() as usize).min(DAYS_PER_WEEK - 1);
+        let profile = match week[day].and_then(|p| self.profiles.get(p)) {
+            None => return Ok(*free_flow),
+            Some(profile) => profile,
+        };
+        let time_of_day = time_of_week - day as f64 * SECONDS_PER_DAY;
+        let slot = ((time_of_day / self.slot_duration).floor() as usize).min(profile.len() - 1);
+        Ok(self.profile_speed.speed(*free_flow, profile[slot]))
+    }
+}
+
+impl ProfileSpeed {
+    /// the speed of an edge with some free flow speed for a value of a profile
+    pub fn speed(&self, free_flow: Speed, value: f64) -> Speed {
+        match self {
+            ProfileSpeed::PercentOfFreeFlow => free_flow * (value / 100.0),
+            ProfileSpeed::Absolute => Speed::new(value),
+        }
+    }
+}
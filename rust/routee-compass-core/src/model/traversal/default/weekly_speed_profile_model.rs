@@ -0,0 +1,137 @@
+use super::query_time::QueryTime;
+use super::weekly_speed_profile_engine::WeeklySpeedProfileEngine;
+use crate::model::state::state_feature::StateFeature;
+use crate::model::state::state_model::StateModel;
+use crate::model::traversal::traversal_model::TraversalModel;
+use crate::model::unit::{Distance, Time, TimeUnit, BASE_DISTANCE_UNIT};
+use crate::model::{
+    property::{edge::Edge, vertex::Vertex},
+    traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
+};
+use crate::util::geo::haversine;
+use std::sync::Arc;
+
+/// traversal model where edge speeds vary by day of week and time of day. the time
+/// when entering an edge is the query departure time plus the time accumulated on
+/// the search state so far, so a trip may continue into the following day. for
+/// arrive-by queries, which are searched in reverse, speeds are indexed backward
+/// from the arrival time.
+pub struct WeeklySpeedProfileModel {
+    engine: Arc<WeeklySpeedProfileEngine>,
+    query_time: QueryTime,
+}
+
+impl WeeklySpeedProfileModel {
+    /// # Arguments
+    ///
+    /// * `engine` - speed profiles shared across queries
+    /// * `query_time` - departure or arrival time of this query, in seconds since Sunday at midnight
+    pub fn new(
+        engine: Arc<WeeklySpeedProfileEngine>,
+        query_time: QueryTime,
+    ) -> WeeklySpeedProfileModel {
+        WeeklySpeedProfileModel { engine, query_time }
+    }
+    const DISTANCE: &'static str = "distance";
+    const TIME: &'static str = "time";
+}
+
+impl TraversalModel for WeeklySpeedProfileModel {
+    fn is_time_dependent(&self) -> bool {
+        true
+    }
+
+    fn traverse_edge(
+        &self,
+        trajectory: (&Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (_, edge, _) = trajectory;
+        let elapsed = state_model.get_time(state, &Self::TIME.into(), &TimeUnit::Seconds)?;
+        let speed = self
+            .engine
+            .get_speed(edge.edge_id, self.query_time.time_at(elapsed.to_f64()))?;
+        let distance = BASE_DISTANCE_UNIT.convert(&edge.distance, &self.engine.distance_unit);
+        let edge_time = Time::create(
+            &speed,
+            &self.engine.speed_unit,
+            &distance,
+            &self.engine.distance_unit,
+            &self.engine.time_unit,
+        )?;
+
+        state_model.add_time(
+            state,
+            &Self::TIME.into(),
+            &edge_time,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+        Ok(())
+    }
+
+    fn estimate_traversal(
+        &self,
+        od: (&Vertex, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (src, dst) = od;
+        let distance =
+            haversine::coord_distance(&src.coordinate, &dst.coordinate, self.engine.distance_unit)
+                .map_err(TraversalModelError::NumericError)?;
+
+        if distance == Distance::ZERO {
+            return Ok(());
+        }
+
+        // the max speed over the whole week keeps the estimate admissible
+        let estimated_time = Time::create(
+            &self.engine.max_speed,
+            &self.engine.speed_unit,
+            &distance,
+            &self.engine.distance_unit,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_time(
+            state,
+            &Self::TIME.into(),
+            &estimated_time,
+            &self.engine.time_unit,
+        )?;
+        state_model.add_distance(
+            state,
+            &Self::DISTANCE.into(),
+            &distance,
+            &self.engine.distance_unit,
+        )?;
+
+        Ok(())
+    }
+
+    /// track the time and distance state features
+    fn state_features(&self) -> Vec<(String, StateFeature)> {
+        vec![
+            (
+                String::from(Self::TIME),
+                StateFeature::Time {
+                    time_unit: self.engine.time_unit,
+                    initial: Time::ZERO,
+                },
+            ),
+            (
+                String::from(Self::DISTANCE),
+                StateFeature::Distance {
+                    distance_unit: self.engine.distance_unit,
+                    initial: Distance::ZERO,
+                },
+            ),
+        ]
+    }
+}
@@ -0,0 +1,44 @@
+use super::{
+    time_dependent_speed_engine::SECONDS_PER_DAY,
+    time_dependent_speed_service::TimeDependentSpeedService,
+    weekly_speed_profile_engine::WeeklySpeedProfileEngine,
+    weekly_speed_profile_model::WeeklySpeedProfileModel,
+};
+use crate::model::traversal::{
+    traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
+    traversal_model_service::TraversalModelService,
+};
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use std::sync::Arc;
+
+pub struct WeeklySpeedProfileService {
+    pub e: Arc<WeeklySpeedProfileEngine>,
+}
+
+impl WeeklySpeedProfileService {
+    /// reads the optional `departure_time` field of a query as seconds since Sunday at midnight.
+    pub fn departure_seconds(
+        query: &serde_json::Value,
+    ) -> Result<Option<f64>, TraversalModelError> {
+        let departure_time = TimeDependentSpeedService::departure_time(query)?;
+        Ok(departure_time.as_ref().map(seconds_since_sunday))
+    }
+}
+
+impl TraversalModelService for WeeklySpeedProfileService {
+    /// builds a model for a query with an optional `departure_time` or `arrival_time`
+    /// field. queries without either are treated as departing on Sunday at midnight.
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
+        let query_time = TimeDependentSpeedService::query_time(parameters, seconds_since_sunday)?;
+        let model = WeeklySpeedProfileModel::new(self.e.clone(), query_time);
+        Ok(Arc::new(model))
+    }
+}
+
+fn seconds_since_sunday(time: &NaiveDateTime) -> f64 {
+    time.weekday().num_days_from_sunday() as f64 * SECONDS_PER_DAY
+        + time.num_seconds_from_midnight() as f64
+}
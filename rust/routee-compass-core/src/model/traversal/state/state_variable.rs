@@ -1,9 +1,19 @@
-use crate::model::unit::{as_f64::AsF64, Distance, Energy, Time};
+use crate::model::{
+    state::state_error::StateError,
+    unit::{as_f64::AsF64, Distance, Energy, Time},
+};
 use allocative::Allocative;
 use derive_more::{Add, Div, Mul, Neg, Sub, Sum};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
+/// the float type stored in a [`StateVar`], which is 32 bits when routee-compass-core
+/// is built with the `compact` feature
+#[cfg(not(feature = "compact"))]
+pub type StateFloat = f64;
+#[cfg(feature = "compact")]
+pub type StateFloat = f32;
+
 #[derive(
     Copy,
     Clone,
@@ -20,13 +30,40 @@ use std::fmt::Display;
     Serialize,
     Allocative,
 )]
-pub struct StateVar(pub f64);
+pub struct StateVar(pub StateFloat);
 
 impl StateVar {
     pub const ZERO: StateVar = StateVar(0.0);
     pub const ONE: StateVar = StateVar(1.0);
     pub const ONE_HUNDRED: StateVar = StateVar(100.0);
-    pub const MAX: StateVar = StateVar(f64::MAX);
+    pub const MAX: StateVar = StateVar(StateFloat::MAX);
+
+    /// the relative precision of a state variable
+    // a conversion from f32 when built with the `compact` feature
+    #[allow(clippy::unnecessary_cast)]
+    pub const EPSILON: f64 = StateFloat::EPSILON as f64;
+
+    /// the largest integer magnitude that a state variable stores exactly
+    pub const MAX_EXACT_INTEGER: u64 = 1 << StateFloat::MANTISSA_DIGITS;
+
+    /// builds a state variable from an f64, rounding it to the precision of
+    /// [`StateFloat`].
+    pub fn new(value: f64) -> StateVar {
+        StateVar(value as StateFloat)
+    }
+
+    /// builds a state variable from an f64, failing when a finite value is too
+    /// large to be stored, which can only happen with the `compact` feature.
+    pub fn try_new(value: f64) -> Result<StateVar, StateError> {
+        let state_var = StateVar::new(value);
+        if value.is_finite() && !state_var.0.is_finite() {
+            return Err(StateError::OutOfRange(
+                value.to_string(),
+                format!("values up to {}", StateFloat::MAX),
+            ));
+        }
+        Ok(state_var)
+    }
 }
 
 impl Display for StateVar {
@@ -37,21 +74,47 @@ impl Display for StateVar {
 
 impl From<StateVar> for f64 {
     fn from(val: StateVar) -> Self {
-        val.0
+        val.as_f64()
+    }
+}
+impl AsF64 for StateVar {
+    // a conversion from f32 when built with the `compact` feature
+    #[allow(clippy::useless_conversion)]
+    fn as_f64(&self) -> f64 {
+        f64::from(self.0)
     }
 }
 impl From<Distance> for StateVar {
     fn from(value: Distance) -> Self {
-        StateVar(value.as_f64())
+        StateVar::new(value.as_f64())
     }
 }
 impl From<Time> for StateVar {
     fn from(value: Time) -> Self {
-        StateVar(value.as_f64())
+        StateVar::new(value.as_f64())
     }
 }
 impl From<Energy> for StateVar {
     fn from(value: Energy) -> Self {
-        StateVar(value.as_f64())
+        StateVar::new(value.as_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::state::custom_feature_format::CustomFeatureFormat;
+
+    #[test]
+    fn test_out_of_range_values() {
+        let compact = cfg!(feature = "compact");
+        assert!(StateVar::try_new(1e30).is_ok());
+        assert!(StateVar::try_new(f64::INFINITY).is_ok());
+        assert_eq!(StateVar::try_new(1e300).is_err(), compact);
+
+        let format = CustomFeatureFormat::SignedInteger { initial: 0 };
+        assert!(format.encode_i64(&-(1 << 20)).is_ok());
+        assert_eq!(format.encode_i64(&-(1 << 30)).is_err(), compact);
+        assert!(format.encode_i64(&i64::MAX).is_err());
     }
 }
@@ -38,6 +38,38 @@ pub trait TraversalModel: Send + Sync {
         state_model: &StateModel,
     ) -> Result<(), TraversalModelError>;
 
+    /// Updates the traversal state when accessing some destination edge from some
+    /// previous edge, before the destination edge is traversed. This lets a traversal
+    /// model charge for the transition between two edges, such as turn delays, stop
+    /// sign or traffic signal penalties, or the energy of stopping and starting at an
+    /// intersection, which cannot be seen when traversing one edge at a time.
+    ///
+    /// The traversal argument represents a set of vertices and
+    /// edges connected in the network:
+    /// `(v1) -[prev]-> (v2) -[next]-> (v3)`
+    /// Where `next` is the edge we want to access.
+    ///
+    /// This is applied after any [`AccessModel`] and is a no-op by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `traversal` - the vertex/edge traversal
+    /// * `state` - state of the search at the end of the previous edge
+    ///
+    /// # Returns
+    ///
+    /// Either an access result or an error.
+    ///
+    /// [`AccessModel`]: crate::model::access::access_model::AccessModel
+    fn access_edge(
+        &self,
+        _traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+        _state: &mut Vec<StateVar>,
+        _state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        Ok(())
+    }
+
     /// Estimates the traversal state by traversing between two vertices without
     /// performing any graph traversals.
     ///
@@ -56,4 +88,11 @@ pub trait TraversalModel: Send + Sync {
         state: &mut Vec<StateVar>,
         state_model: &StateModel,
     ) -> Result<(), TraversalModelError>;
+
+    /// whether the cost of an edge depends on the time it is reached, such as with
+    /// time-dependent speeds or transit schedules. heuristics precomputed from one
+    /// static cost for each edge cannot bound the cost of a route for these models.
+    fn is_time_dependent(&self) -> bool {
+        false
+    }
 }
@@ -22,4 +22,17 @@ pub trait TraversalModelBuilder {
         &self,
         parameters: &serde_json::Value,
     ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError>;
+
+    /// the keys this builder reads from its configuration section, other than `type`.
+    /// used to warn about unknown keys, such as misspellings, before any data loads.
+    /// builders which return `None` are not checked.
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// the keys which must be present in this builder's configuration section, which
+    /// are also listed by `config_keys`. a missing key is an error before any data loads.
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec![]
+    }
 }
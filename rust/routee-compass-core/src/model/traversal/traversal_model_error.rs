@@ -17,6 +17,8 @@ pub enum TraversalModelError {
     StateVectorIndexOutOfBounds(usize, String, TraversalState),
     #[error("id {0} for id type {1} not found in tabular edge cost function {2}")]
     MissingIdInTabularCostFunction(String, String, String),
+    #[error("unsupported operation: {0}")]
+    UnsupportedOperation(String),
     #[error("internal error: {0}")]
     InternalError(String),
     #[error(transparent)]
@@ -1,4 +1,8 @@
 use super::{traversal_model::TraversalModel, traversal_model_error::TraversalModelError};
+use crate::model::{
+    road_network::edge_id::EdgeId,
+    unit::{Grade, GradeUnit, Speed, SpeedUnit},
+};
 use std::sync::Arc;
 
 /// A [`TraversalModelService`] is a persistent builder of [TraversalModel] instances.
@@ -29,4 +33,45 @@ pub trait TraversalModelService: Send + Sync {
         &self,
         query: &serde_json::Value,
     ) -> Result<Arc<dyn TraversalModel>, TraversalModelError>;
+
+    /// Replaces the speeds of some edges in a running service, such as from a live
+    /// traffic feed, without rebuilding the graph. Queries that have already built
+    /// their [TraversalModel] keep the speeds they started with.
+    ///
+    /// Services without a speed table return an error by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `speeds` - edge ids paired with their new speed
+    /// * `speed_unit` - unit of the new speeds
+    ///
+    /// [TraversalModel]: compass_core::model::traversal::traversal_model::TraversalModel
+    fn update_edge_speeds(
+        &self,
+        _speeds: &[(EdgeId, Speed)],
+        _speed_unit: &SpeedUnit,
+    ) -> Result<(), TraversalModelError> {
+        Err(TraversalModelError::UnsupportedOperation(String::from(
+            "this traversal model does not support edge speed updates",
+        )))
+    }
+
+    /// The speed of an edge as used by the queries of this service, along with its unit,
+    /// such as for inspecting the network from the language bindings.
+    ///
+    /// Services without a speed table return an error by default.
+    fn edge_speed(&self, _edge_id: EdgeId) -> Result<(Speed, SpeedUnit), TraversalModelError> {
+        Err(TraversalModelError::UnsupportedOperation(String::from(
+            "this traversal model does not have edge speeds",
+        )))
+    }
+
+    /// The grade of an edge as used by the queries of this service, along with its unit.
+    ///
+    /// Services without a grade table return an error by default.
+    fn edge_grade(&self, _edge_id: EdgeId) -> Result<(Grade, GradeUnit), TraversalModelError> {
+        Err(TraversalModelError::UnsupportedOperation(String::from(
+            "this traversal model does not have edge grades",
+        )))
+    }
 }
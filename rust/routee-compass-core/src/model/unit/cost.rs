@@ -1,13 +1,19 @@
 use crate::model::unit::{as_f64::AsF64, *};
 use allocative::Allocative;
 use derive_more::{Add, Div, Mul, Neg, Sub, Sum};
+use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::Display,
     ops::{Deref, DerefMut},
 };
 
-use self::internal_float::InternalFloat;
+/// the float type stored in a [`Cost`], which is 32 bits when routee-compass-core
+/// is built with the `compact` feature
+#[cfg(not(feature = "compact"))]
+pub type CostFloat = f64;
+#[cfg(feature = "compact")]
+pub type CostFloat = f32;
 
 /// Represents the cost for traversing a graph edge.
 /// A cost does not carry any units but can be built from a unit type like [`Time`] or [`Energy`]  
@@ -28,27 +34,26 @@ use self::internal_float::InternalFloat;
     Neg,
     Serialize,
     Deserialize,
-    Allocative,
 )]
-pub struct Cost(InternalFloat);
+pub struct Cost(OrderedFloat<CostFloat>);
 
 impl Cost {
     /// represents zero cost, unit of addition operation
-    pub const ZERO: Cost = Cost(InternalFloat::ZERO);
+    pub const ZERO: Cost = Cost(OrderedFloat(0.0));
 
     /// represents one cost, unit of multiplication operation
-    pub const ONE: Cost = Cost(InternalFloat::ONE);
+    pub const ONE: Cost = Cost(OrderedFloat(1.0));
 
     /// represents the maximum possible cost
-    pub const INFINITY: Cost = Cost(InternalFloat::INFINITY);
+    pub const INFINITY: Cost = Cost(OrderedFloat(CostFloat::INFINITY));
 
     /// when path search costs must be strictly positive, this value
     /// is used as a sentinel in place of non-positive costs
-    pub const MIN_COST: Cost = Cost(InternalFloat::MIN);
+    pub const MIN_COST: Cost = Cost(OrderedFloat(0.0000000001));
 
-    /// helper to construct a Cost from an f64
+    /// helper to construct a Cost from an f64, rounded to the precision of [`CostFloat`]
     pub fn new(value: f64) -> Cost {
-        Cost(InternalFloat::new(value))
+        Cost(OrderedFloat(value as CostFloat))
     }
 
     /// helper to enforce costs that are strictly positive
@@ -85,6 +90,11 @@ impl From<Energy> for Cost {
         Cost::new(value.as_f64())
     }
 }
+impl From<Currency> for Cost {
+    fn from(value: Currency) -> Self {
+        Cost::new(value.as_f64())
+    }
+}
 impl From<Speed> for Cost {
     fn from(value: Speed) -> Self {
         Cost::new(value.as_f64())
@@ -93,19 +103,27 @@ impl From<Speed> for Cost {
 
 impl From<f64> for Cost {
     fn from(f: f64) -> Self {
-        Cost(InternalFloat::new(f))
+        Cost::new(f)
     }
 }
 
 impl AsF64 for Cost {
+    // a conversion from f32 when built with the `compact` feature
+    #[allow(clippy::useless_conversion)]
     fn as_f64(&self) -> f64 {
-        self.0 .0
+        f64::from(self.0 .0)
     }
 }
 
 impl From<Cost> for f64 {
     fn from(val: Cost) -> Self {
-        val.0.into_inner()
+        val.as_f64()
+    }
+}
+
+impl Allocative for Cost {
+    fn visit<'a, 'b: 'a>(&self, visitor: &'a mut allocative::Visitor<'b>) {
+        visitor.visit_simple_sized::<Self>()
     }
 }
 
@@ -0,0 +1,64 @@
+use allocative::Allocative;
+use derive_more::{Add, Div, Mul, Neg, Sub, Sum};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt::Display};
+
+use super::{as_f64::AsF64, internal_float::InternalFloat, Currency};
+
+/// an amount of money per unit of distance, time or energy, such as dollars per hour
+#[derive(
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Sum,
+    Neg,
+    Allocative,
+)]
+pub struct CostRate(pub InternalFloat);
+
+impl AsF64 for CostRate {
+    fn as_f64(&self) -> f64 {
+        (self.0).0
+    }
+}
+
+impl PartialOrd for CostRate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CostRate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Display for CostRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl CostRate {
+    pub fn new(value: f64) -> CostRate {
+        CostRate(InternalFloat::new(value))
+    }
+    pub const ZERO: CostRate = CostRate(InternalFloat::ZERO);
+    pub const ONE: CostRate = CostRate(InternalFloat::ONE);
+
+    /// the amount of money charged at this rate over a quantity in the unit of the rate
+    pub fn charge(&self, quantity: f64) -> Currency {
+        Currency::new(self.as_f64() * quantity)
+    }
+}
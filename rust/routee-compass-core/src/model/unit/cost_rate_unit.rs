@@ -0,0 +1,189 @@
+use super::{
+    as_f64::AsF64, CostRate, CurrencyUnit, Distance, DistanceUnit, Energy, EnergyUnit, Time,
+    TimeUnit, Unit, UnitError,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// the unit of a [`CostRate`], an amount of money per unit of distance, time or energy.
+/// rates may also be written as `usd_per_*`, such as `usd_per_kilowatt_hour`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CostRateUnit {
+    #[serde(alias = "usd_per_mile")]
+    DollarsPerMile,
+    #[serde(alias = "usd_per_kilometer")]
+    DollarsPerKilometer,
+    #[serde(alias = "usd_per_meter")]
+    DollarsPerMeter,
+    #[serde(alias = "usd_per_hour")]
+    DollarsPerHour,
+    #[serde(alias = "usd_per_minute")]
+    DollarsPerMinute,
+    #[serde(alias = "usd_per_second")]
+    DollarsPerSecond,
+    #[serde(alias = "usd_per_kilowatt_hour")]
+    DollarsPerKilowattHour,
+    #[serde(alias = "usd_per_gallon_gasoline")]
+    DollarsPerGallonGasoline,
+    #[serde(alias = "usd_per_gallon_diesel")]
+    DollarsPerGallonDiesel,
+}
+
+/// the quantity that a cost rate is charged over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostRateBasis {
+    Distance(DistanceUnit),
+    Time(TimeUnit),
+    Energy(EnergyUnit),
+}
+
+impl CostRateUnit {
+    /// cost rates are defined with respect to a currency unit
+    pub fn associated_currency_unit(&self) -> CurrencyUnit {
+        CurrencyUnit::Dollars
+    }
+
+    /// the unit of distance, time or energy that this rate is charged over
+    pub fn basis(&self) -> CostRateBasis {
+        use CostRateBasis as B;
+        use CostRateUnit as CRU;
+        match self {
+            CRU::DollarsPerMile => B::Distance(DistanceUnit::Miles),
+            CRU::DollarsPerKilometer => B::Distance(DistanceUnit::Kilometers),
+            CRU::DollarsPerMeter => B::Distance(DistanceUnit::Meters),
+            CRU::DollarsPerHour => B::Time(TimeUnit::Hours),
+            CRU::DollarsPerMinute => B::Time(TimeUnit::Minutes),
+            CRU::DollarsPerSecond => B::Time(TimeUnit::Seconds),
+            CRU::DollarsPerKilowattHour => B::Energy(EnergyUnit::KilowattHours),
+            CRU::DollarsPerGallonGasoline => B::Energy(EnergyUnit::GallonsGasoline),
+            CRU::DollarsPerGallonDiesel => B::Energy(EnergyUnit::GallonsDiesel),
+        }
+    }
+
+    /// converts a rate to another unit over the same kind of quantity, such as
+    /// dollars per hour to dollars per minute. energy rates are only converted between
+    /// units of the same fuel, since the price of one fuel is not the price of another.
+    pub fn convert(&self, value: &CostRate, target: &CostRateUnit) -> Result<CostRate, UnitError> {
+        self.basis()
+            .convert(value, &target.basis())
+            .ok_or(UnitError::IncompatibleCostRateUnits(*self, *target))
+    }
+}
+
+impl CostRateBasis {
+    /// the factor that turns a rate per unit of this basis into a rate per unit of the
+    /// target basis, or None if they measure different kinds of quantities or the
+    /// energy of different fuels.
+    pub fn conversion_factor(&self, target: &CostRateBasis) -> Option<f64> {
+        use CostRateBasis as B;
+        // a rate per source unit is charged over each source unit in one target unit
+        match (self, target) {
+            (B::Distance(s), B::Distance(t)) => Some(t.convert(&Distance::new(1.0), s).as_f64()),
+            (B::Time(s), B::Time(t)) => Some(t.convert(&Time::new(1.0), s).as_f64()),
+            (B::Energy(s), B::Energy(t)) if s.fuel() == t.fuel() => {
+                Some(t.convert(&Energy::new(1.0), s).as_f64())
+            }
+            _ => None,
+        }
+    }
+
+    /// converts a rate per unit of this basis into a rate per unit of the target basis
+    pub fn convert(&self, rate: &CostRate, target: &CostRateBasis) -> Option<CostRate> {
+        self.conversion_factor(target).map(|factor| *rate * factor)
+    }
+}
+
+impl Unit for CostRateUnit {
+    const KIND: &'static str = "cost rate";
+    const ALL: &'static [CostRateUnit] = &[
+        CostRateUnit::DollarsPerMile,
+        CostRateUnit::DollarsPerKilometer,
+        CostRateUnit::DollarsPerMeter,
+        CostRateUnit::DollarsPerHour,
+        CostRateUnit::DollarsPerMinute,
+        CostRateUnit::DollarsPerSecond,
+        CostRateUnit::DollarsPerKilowattHour,
+        CostRateUnit::DollarsPerGallonGasoline,
+        CostRateUnit::DollarsPerGallonDiesel,
+    ];
+}
+
+impl std::fmt::Display for CostRateUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(self)
+            .map_err(|_| std::fmt::Error)?
+            .replace('\"', "");
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for CostRateUnit {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CostRateUnit::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CostRateUnit as U;
+    use super::*;
+
+    fn assert_converts(from: U, to: U, expected: f64) {
+        let result = from.convert(&CostRate::new(1.0), &to).unwrap().as_f64();
+        assert!(
+            (result - expected).abs() < 0.0001,
+            "1 {} = {} {}, expected {}",
+            from,
+            result,
+            to,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_converts(U::DollarsPerHour, U::DollarsPerHour, 1.0);
+        assert_converts(U::DollarsPerHour, U::DollarsPerMinute, 1.0 / 60.0);
+        assert_converts(U::DollarsPerSecond, U::DollarsPerHour, 3600.0);
+        assert_converts(U::DollarsPerMile, U::DollarsPerKilometer, 0.6215);
+        assert_converts(U::DollarsPerKilometer, U::DollarsPerMeter, 0.001);
+        assert!(U::DollarsPerHour
+            .convert(&CostRate::ONE, &U::DollarsPerMile)
+            .is_err());
+        // the price of one fuel is not the price of another
+        assert!(U::DollarsPerGallonGasoline
+            .convert(&CostRate::ONE, &U::DollarsPerKilowattHour)
+            .is_err());
+        assert!(U::DollarsPerGallonGasoline
+            .convert(&CostRate::ONE, &U::DollarsPerGallonDiesel)
+            .is_err());
+        // a fuel priced by the gallon is charged by the liter
+        let per_liter = CostRateBasis::Energy(EnergyUnit::GallonsGasoline)
+            .convert(
+                &CostRate::ONE,
+                &CostRateBasis::Energy(EnergyUnit::LitersGasoline),
+            )
+            .unwrap();
+        assert!((per_liter.as_f64() - 0.26417).abs() < 0.0001);
+        assert!((CostRate::new(0.5).charge(4.0).as_f64() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            U::from_str("usd_per_kilowatt_hour").unwrap(),
+            U::DollarsPerKilowattHour
+        );
+        assert_eq!(
+            U::DollarsPerGallonDiesel.to_string(),
+            "dollars_per_gallon_diesel"
+        );
+        assert_eq!(
+            CurrencyUnit::from_str("usd").unwrap(),
+            CurrencyUnit::Dollars
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use allocative::Allocative;
+use derive_more::{Add, Div, Mul, Neg, Sub, Sum};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::model::traversal::state::state_variable::StateVar;
+
+use super::{as_f64::AsF64, internal_float::InternalFloat};
+
+/// an amount of money, such as the monetary cost of a trip
+#[derive(
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Sum,
+    Neg,
+    Allocative,
+)]
+pub struct Currency(pub InternalFloat);
+
+impl AsF64 for Currency {
+    fn as_f64(&self) -> f64 {
+        (self.0).0
+    }
+}
+
+impl From<StateVar> for Currency {
+    fn from(value: StateVar) -> Self {
+        Currency::new(value.as_f64())
+    }
+}
+
+impl PartialOrd for Currency {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Currency {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl Currency {
+    pub fn new(value: f64) -> Currency {
+        Currency(InternalFloat::new(value))
+    }
+    pub const ZERO: Currency = Currency(InternalFloat::ZERO);
+    pub const ONE: Currency = Currency(InternalFloat::ONE);
+}
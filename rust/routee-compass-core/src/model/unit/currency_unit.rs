@@ -0,0 +1,46 @@
+use super::{Currency, Unit, UnitError};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrencyUnit {
+    #[default]
+    #[serde(alias = "usd")]
+    Dollars,
+    Cents,
+}
+
+impl CurrencyUnit {
+    pub fn convert(&self, value: &Currency, target: &CurrencyUnit) -> Currency {
+        use CurrencyUnit as S;
+        match (self, target) {
+            (S::Dollars, S::Dollars) => *value,
+            (S::Dollars, S::Cents) => *value * 100.0,
+            (S::Cents, S::Dollars) => *value * 0.01,
+            (S::Cents, S::Cents) => *value,
+        }
+    }
+}
+
+impl Unit for CurrencyUnit {
+    const KIND: &'static str = "currency";
+    const ALL: &'static [CurrencyUnit] = &[CurrencyUnit::Dollars, CurrencyUnit::Cents];
+}
+
+impl std::fmt::Display for CurrencyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(self)
+            .map_err(|_| std::fmt::Error)?
+            .replace('\"', "");
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for CurrencyUnit {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CurrencyUnit::parse(s)
+    }
+}
@@ -26,6 +26,7 @@ use super::{as_f64::AsF64, internal_float::InternalFloat};
     Neg,
     Allocative,
 )]
+#[repr(transparent)]
 pub struct Distance(InternalFloat);
 
 impl AsF64 for Distance {
@@ -35,7 +36,7 @@ impl AsF64 for Distance {
 }
 impl PartialOrd for Distance {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 impl Ord for Distance {
@@ -50,7 +51,7 @@ impl Display for Distance {
 }
 impl From<StateVar> for Distance {
     fn from(value: StateVar) -> Self {
-        Distance::new(value.0)
+        Distance::new(value.as_f64())
     }
 }
 
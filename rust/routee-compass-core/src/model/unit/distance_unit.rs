@@ -1,5 +1,4 @@
-use super::Distance;
-use crate::util::serde::serde_ops::string_deserialize;
+use super::{Distance, Unit, UnitError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -11,6 +10,8 @@ pub enum DistanceUnit {
     Miles,
     Inches,
     Feet,
+    Yards,
+    NauticalMiles,
 }
 
 impl DistanceUnit {
@@ -42,10 +43,40 @@ impl DistanceUnit {
             (S::Feet, S::Miles) => *value * 0.000189394,
             (S::Feet, S::Inches) => *value * 12.0,
             (S::Feet, S::Feet) => *value,
+            // yards and nautical miles convert through their length in meters
+            (s, t) if s == t => *value,
+            (s, t) => *value * (s.meters() / t.meters()),
+        }
+    }
+
+    /// the length of one of this unit in meters
+    fn meters(&self) -> f64 {
+        use DistanceUnit as S;
+        match self {
+            S::Meters => 1.0,
+            S::Kilometers => 1000.0,
+            S::Miles => 1609.344,
+            S::Inches => 0.0254,
+            S::Feet => 0.3048,
+            S::Yards => 0.9144,
+            S::NauticalMiles => 1852.0,
         }
     }
 }
 
+impl Unit for DistanceUnit {
+    const KIND: &'static str = "distance";
+    const ALL: &'static [DistanceUnit] = &[
+        DistanceUnit::Meters,
+        DistanceUnit::Kilometers,
+        DistanceUnit::Miles,
+        DistanceUnit::Inches,
+        DistanceUnit::Feet,
+        DistanceUnit::Yards,
+        DistanceUnit::NauticalMiles,
+    ];
+}
+
 impl std::fmt::Display for DistanceUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = serde_json::to_string(self)
@@ -56,10 +87,10 @@ impl std::fmt::Display for DistanceUnit {
 }
 
 impl FromStr for DistanceUnit {
-    type Err = serde_json::Error;
+    type Err = UnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        string_deserialize(s)
+        DistanceUnit::parse(s)
     }
 }
 
@@ -132,4 +163,42 @@ mod test {
             0.001,
         );
     }
+
+    #[test]
+    fn test_pairwise_conversions() {
+        use crate::model::unit::Unit;
+        assert_approx_eq(
+            D::Yards.convert(&Distance::ONE, &D::Feet),
+            Distance::new(3.0),
+            0.0001,
+        );
+        assert_approx_eq(
+            D::NauticalMiles.convert(&Distance::ONE, &D::Miles),
+            Distance::new(1.150779),
+            0.0001,
+        );
+        for a in D::ALL {
+            for b in D::ALL {
+                let there = a.convert(&Distance::new(7.5), b);
+                assert_approx_eq(b.convert(&there, a), Distance::new(7.5), 7.5 * 0.001);
+                for c in D::ALL {
+                    let through = b.convert(&there, c);
+                    let direct = a.convert(&Distance::new(7.5), c);
+                    assert_approx_eq(through, direct, direct.as_f64() * 0.001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        use crate::model::unit::Unit;
+        for unit in D::ALL {
+            assert_eq!(D::parse(&unit.to_string()).unwrap(), *unit);
+            let json = serde_json::to_string(unit).unwrap();
+            assert_eq!(serde_json::from_str::<D>(&json).unwrap(), *unit);
+        }
+        assert_eq!(D::parse(" Nautical_Miles ").unwrap(), D::NauticalMiles);
+        assert!(D::parse("furlongs").is_err());
+    }
 }
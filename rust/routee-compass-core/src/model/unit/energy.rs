@@ -45,12 +45,12 @@ impl From<(EnergyRate, Distance)> for Energy {
 }
 impl From<StateVar> for Energy {
     fn from(value: StateVar) -> Self {
-        Energy::new(value.0)
+        Energy::new(value.as_f64())
     }
 }
 impl PartialOrd for Energy {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
@@ -33,7 +33,7 @@ impl AsF64 for EnergyRate {
 
 impl PartialOrd for EnergyRate {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
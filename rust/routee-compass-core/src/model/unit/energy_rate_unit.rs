@@ -1,5 +1,4 @@
-use super::{DistanceUnit, EnergyUnit};
-use crate::util::serde::serde_ops::string_deserialize;
+use super::{DistanceUnit, EnergyUnit, Unit, UnitError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -41,6 +40,17 @@ impl EnergyRateUnit {
     }
 }
 
+impl Unit for EnergyRateUnit {
+    const KIND: &'static str = "energy rate";
+    const ALL: &'static [EnergyRateUnit] = &[
+        EnergyRateUnit::GallonsGasolinePerMile,
+        EnergyRateUnit::GallonsDieselPerMile,
+        EnergyRateUnit::KilowattHoursPerMile,
+        EnergyRateUnit::KilowattHoursPerKilometer,
+        EnergyRateUnit::KilowattHoursPerMeter,
+    ];
+}
+
 impl std::fmt::Display for EnergyRateUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = serde_json::to_string(self)
@@ -51,9 +61,9 @@ impl std::fmt::Display for EnergyRateUnit {
 }
 
 impl FromStr for EnergyRateUnit {
-    type Err = serde_json::Error;
+    type Err = UnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        string_deserialize(s)
+        EnergyRateUnit::parse(s)
     }
 }
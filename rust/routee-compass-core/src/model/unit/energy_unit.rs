@@ -1,5 +1,4 @@
-use super::Energy;
-use crate::util::serde::serde_ops::string_deserialize;
+use super::{Energy, Unit, UnitError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -9,9 +8,36 @@ pub enum EnergyUnit {
     GallonsGasoline,
     GallonsDiesel,
     KilowattHours,
+    Megajoules,
+    Btu,
+    LitersGasoline,
+    LitersDiesel,
+    /// the energy of a gallon of gasoline, used to compare the energy of other sources
+    #[serde(alias = "gge")]
+    GallonsGasolineEquivalent,
+}
+
+/// a source of energy, such as a fuel burned by a vehicle or the electricity drawn
+/// from its battery
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fuel {
+    Gasoline,
+    Diesel,
+    Electricity,
 }
 
 impl EnergyUnit {
+    /// the fuel measured by this unit, or None for units such as kilowatt hours or
+    /// megajoules which measure energy from any source, including electricity
+    pub fn fuel(&self) -> Option<Fuel> {
+        use EnergyUnit as S;
+        match self {
+            S::GallonsGasoline | S::LitersGasoline => Some(Fuel::Gasoline),
+            S::GallonsDiesel | S::LitersDiesel => Some(Fuel::Diesel),
+            S::KilowattHours | S::Megajoules | S::Btu | S::GallonsGasolineEquivalent => None,
+        }
+    }
+
     // see https://epact.energy.gov/fuel-conversion-factors
     pub fn convert(&self, value: &Energy, target: &EnergyUnit) -> Energy {
         use EnergyUnit as S;
@@ -25,8 +51,43 @@ impl EnergyUnit {
             (S::KilowattHours, S::GallonsDiesel) => *value * 0.02457,
             (S::GallonsDiesel, S::GallonsGasoline) => *value * 1.155,
             (S::GallonsGasoline, S::GallonsDiesel) => *value * 0.866,
+            // the other units convert through their energy in kilowatt hours
+            (s, t) if s == t => *value,
+            (s, t) => *value * (s.kilowatt_hours() / t.kilowatt_hours()),
         }
     }
+
+    /// the energy of one of this unit in kilowatt hours
+    fn kilowatt_hours(&self) -> f64 {
+        use EnergyUnit as S;
+        const KWH_PER_GALLON_GASOLINE: f64 = 32.26;
+        const KWH_PER_GALLON_DIESEL: f64 = 40.7;
+        const LITERS_PER_GALLON: f64 = 3.785411784;
+        match self {
+            S::GallonsGasoline => KWH_PER_GALLON_GASOLINE,
+            S::GallonsDiesel => KWH_PER_GALLON_DIESEL,
+            S::KilowattHours => 1.0,
+            S::Megajoules => 1.0 / 3.6,
+            S::Btu => 1.0 / 3412.142,
+            S::LitersGasoline => KWH_PER_GALLON_GASOLINE / LITERS_PER_GALLON,
+            S::LitersDiesel => KWH_PER_GALLON_DIESEL / LITERS_PER_GALLON,
+            S::GallonsGasolineEquivalent => KWH_PER_GALLON_GASOLINE,
+        }
+    }
+}
+
+impl Unit for EnergyUnit {
+    const KIND: &'static str = "energy";
+    const ALL: &'static [EnergyUnit] = &[
+        EnergyUnit::GallonsGasoline,
+        EnergyUnit::GallonsDiesel,
+        EnergyUnit::KilowattHours,
+        EnergyUnit::Megajoules,
+        EnergyUnit::Btu,
+        EnergyUnit::LitersGasoline,
+        EnergyUnit::LitersDiesel,
+        EnergyUnit::GallonsGasolineEquivalent,
+    ];
 }
 
 impl std::fmt::Display for EnergyUnit {
@@ -39,9 +100,76 @@ impl std::fmt::Display for EnergyUnit {
 }
 
 impl FromStr for EnergyUnit {
-    type Err = serde_json::Error;
+    type Err = UnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        string_deserialize(s)
+        EnergyUnit::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnergyUnit as E;
+    use super::*;
+    use crate::model::unit::as_f64::AsF64;
+
+    fn assert_approx_eq(a: Energy, b: Energy, error: f64) {
+        assert!(
+            (a.as_f64() - b.as_f64()).abs() <= error,
+            "{} ~= {} is not true within an error of {}",
+            a,
+            b,
+            error
+        )
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_approx_eq(
+            E::GallonsGasoline.convert(&Energy::ONE, &E::KilowattHours),
+            Energy::new(32.26),
+            0.001,
+        );
+        assert_approx_eq(
+            E::KilowattHours.convert(&Energy::ONE, &E::Megajoules),
+            Energy::new(3.6),
+            0.001,
+        );
+        assert_approx_eq(
+            E::KilowattHours.convert(&Energy::ONE, &E::Btu),
+            Energy::new(3412.142),
+            0.001,
+        );
+        assert_approx_eq(
+            E::GallonsDiesel.convert(&Energy::ONE, &E::LitersDiesel),
+            Energy::new(3.785411784),
+            0.001,
+        );
+        assert_approx_eq(
+            E::GallonsGasolineEquivalent.convert(&Energy::ONE, &E::GallonsGasoline),
+            Energy::ONE,
+            0.001,
+        );
+    }
+
+    #[test]
+    fn test_pairwise_conversions() {
+        for a in E::ALL {
+            for b in E::ALL {
+                let there = a.convert(&Energy::new(12.5), b);
+                assert_approx_eq(b.convert(&there, a), Energy::new(12.5), 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for unit in E::ALL {
+            assert_eq!(E::parse(&unit.to_string()).unwrap(), *unit);
+            let json = serde_json::to_string(unit).unwrap();
+            assert_eq!(serde_json::from_str::<E>(&json).unwrap(), *unit);
+        }
+        assert_eq!(E::parse("GGE").unwrap(), E::GallonsGasolineEquivalent);
+        assert!(E::parse("calories").is_err());
     }
 }
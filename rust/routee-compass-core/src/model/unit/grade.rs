@@ -33,7 +33,7 @@ impl AsF64 for Grade {
 
 impl PartialOrd for Grade {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
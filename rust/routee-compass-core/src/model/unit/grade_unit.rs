@@ -1,13 +1,17 @@
-use super::Grade;
-use crate::util::serde::serde_ops::string_deserialize;
+use super::{Grade, Unit, UnitError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+/// the encoding of a road grade, the rise of a road over its run. a rise of 5 meters
+/// over 100 meters is a grade of 0.05 decimal, 5 percent or 50 millis (per mille).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum GradeUnit {
+    #[serde(alias = "percentage")]
     Percent,
+    #[serde(alias = "rise_over_run")]
     Decimal,
+    #[serde(alias = "millirise", alias = "per_mille")]
     Millis,
 }
 
@@ -28,6 +32,11 @@ impl GradeUnit {
     }
 }
 
+impl Unit for GradeUnit {
+    const KIND: &'static str = "grade";
+    const ALL: &'static [GradeUnit] = &[GradeUnit::Percent, GradeUnit::Decimal, GradeUnit::Millis];
+}
+
 impl std::fmt::Display for GradeUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = serde_json::to_string(self)
@@ -38,10 +47,10 @@ impl std::fmt::Display for GradeUnit {
 }
 
 impl FromStr for GradeUnit {
-    type Err = serde_json::Error;
+    type Err = UnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        string_deserialize(s)
+        GradeUnit::parse(s)
     }
 }
 
@@ -99,4 +108,16 @@ mod test {
             0.001,
         );
     }
+
+    #[test]
+    fn test_parse() {
+        use crate::model::unit::Unit;
+        for unit in G::ALL {
+            assert_eq!(G::parse(&unit.to_string()).unwrap(), *unit);
+        }
+        assert_eq!(G::parse("millirise").unwrap(), G::Millis);
+        assert_eq!(G::parse("per_mille").unwrap(), G::Millis);
+        assert_eq!(G::parse("percentage").unwrap(), G::Percent);
+        assert_eq!(G::parse("rise_over_run").unwrap(), G::Decimal);
+    }
 }
@@ -23,6 +23,7 @@ use std::ops::{Deref, DerefMut};
     Ord,
     PartialOrd,
 )]
+#[repr(transparent)]
 pub struct InternalFloat(OrderedFloat<f64>);
 
 impl InternalFloat {
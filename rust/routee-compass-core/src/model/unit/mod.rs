@@ -1,6 +1,10 @@
 pub mod as_f64;
 pub mod builders;
 pub mod cost;
+pub mod cost_rate;
+pub mod cost_rate_unit;
+pub mod currency;
+pub mod currency_unit;
 pub mod distance;
 pub mod distance_unit;
 pub mod energy;
@@ -15,17 +19,22 @@ pub mod speed_unit;
 pub mod time;
 pub mod time_unit;
 pub mod unit_error;
+pub mod unit_parse;
 pub mod untyped;
 pub mod weight;
 pub mod weight_unit;
 pub use builders::{BASE_DISTANCE_UNIT, BASE_SPEED_UNIT, BASE_TIME_UNIT};
 pub use cost::Cost;
+pub use cost_rate::CostRate;
+pub use cost_rate_unit::{CostRateBasis, CostRateUnit};
+pub use currency::Currency;
+pub use currency_unit::CurrencyUnit;
 pub use distance::Distance;
 pub use distance_unit::DistanceUnit;
 pub use energy::Energy;
 pub use energy_rate::EnergyRate;
 pub use energy_rate_unit::EnergyRateUnit;
-pub use energy_unit::EnergyUnit;
+pub use energy_unit::{EnergyUnit, Fuel};
 pub use grade::Grade;
 pub use grade_unit::GradeUnit;
 pub use speed::Speed;
@@ -33,6 +42,7 @@ pub use speed_unit::SpeedUnit;
 pub use time::Time;
 pub use time_unit::TimeUnit;
 pub use unit_error::UnitError;
+pub use unit_parse::Unit;
 pub use untyped::Untyped;
 pub use weight::Weight;
 pub use weight_unit::WeightUnit;
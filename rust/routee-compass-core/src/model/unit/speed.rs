@@ -43,7 +43,7 @@ impl From<(Distance, Time)> for Speed {
 
 impl PartialOrd for Speed {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
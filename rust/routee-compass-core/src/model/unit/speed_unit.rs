@@ -1,15 +1,25 @@
 use super::Speed;
-use super::{DistanceUnit, TimeUnit};
-use crate::util::serde::serde_ops::string_deserialize;
+use super::{DistanceUnit, TimeUnit, Unit, UnitError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum SpeedUnit {
     KilometersPerHour,
     MilesPerHour,
     MetersPerSecond,
+    Knots,
+}
+
+impl Unit for SpeedUnit {
+    const KIND: &'static str = "speed";
+    const ALL: &'static [SpeedUnit] = &[
+        SpeedUnit::KilometersPerHour,
+        SpeedUnit::MilesPerHour,
+        SpeedUnit::MetersPerSecond,
+        SpeedUnit::Knots,
+    ];
 }
 
 impl std::fmt::Display for SpeedUnit {
@@ -22,39 +32,28 @@ impl std::fmt::Display for SpeedUnit {
 }
 
 impl FromStr for SpeedUnit {
-    type Err = serde_json::Error;
+    type Err = UnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        string_deserialize(s)
+        SpeedUnit::parse(s)
     }
 }
 
-impl From<(DistanceUnit, TimeUnit)> for SpeedUnit {
-    fn from(value: (DistanceUnit, TimeUnit)) -> Self {
+/// the speed unit which measures a distance unit per time unit, such as kilometers per
+/// hour. combinations without a speed unit, such as feet per minute, are an error.
+impl TryFrom<(DistanceUnit, TimeUnit)> for SpeedUnit {
+    type Error = UnitError;
+
+    fn try_from(value: (DistanceUnit, TimeUnit)) -> Result<Self, Self::Error> {
         use DistanceUnit as D;
         use SpeedUnit as S;
         use TimeUnit as T;
         match value {
-            (D::Meters, T::Hours) => todo!(),
-            (D::Meters, T::Minutes) => todo!(),
-            (D::Meters, T::Seconds) => S::MetersPerSecond,
-            (D::Meters, T::Milliseconds) => todo!(),
-            (D::Kilometers, T::Hours) => S::KilometersPerHour,
-            (D::Kilometers, T::Minutes) => todo!(),
-            (D::Kilometers, T::Seconds) => todo!(),
-            (D::Kilometers, T::Milliseconds) => todo!(),
-            (D::Miles, T::Hours) => S::MilesPerHour,
-            (D::Miles, T::Minutes) => todo!(),
-            (D::Miles, T::Seconds) => todo!(),
-            (D::Miles, T::Milliseconds) => todo!(),
-            (D::Inches, T::Hours) => todo!(),
-            (D::Inches, T::Minutes) => todo!(),
-            (D::Inches, T::Seconds) => todo!(),
-            (D::Inches, T::Milliseconds) => todo!(),
-            (D::Feet, T::Hours) => todo!(),
-            (D::Feet, T::Minutes) => todo!(),
-            (D::Feet, T::Seconds) => todo!(),
-            (D::Feet, T::Milliseconds) => todo!(),
+            (D::Kilometers, T::Hours) => Ok(S::KilometersPerHour),
+            (D::Miles, T::Hours) => Ok(S::MilesPerHour),
+            (D::Meters, T::Seconds) => Ok(S::MetersPerSecond),
+            (D::NauticalMiles, T::Hours) => Ok(S::Knots),
+            (distance_unit, time_unit) => Err(UnitError::NoSpeedUnit(distance_unit, time_unit)),
         }
     }
 }
@@ -67,6 +66,7 @@ impl SpeedUnit {
             S::KilometersPerHour => TimeUnit::Hours,
             S::MilesPerHour => TimeUnit::Hours,
             S::MetersPerSecond => TimeUnit::Seconds,
+            S::Knots => TimeUnit::Hours,
         }
     }
 
@@ -77,6 +77,7 @@ impl SpeedUnit {
             S::KilometersPerHour => DistanceUnit::Kilometers,
             S::MilesPerHour => DistanceUnit::Miles,
             S::MetersPerSecond => DistanceUnit::Meters,
+            S::Knots => DistanceUnit::NauticalMiles,
         }
     }
 
@@ -93,6 +94,20 @@ impl SpeedUnit {
             (S::MetersPerSecond, S::KilometersPerHour) => *value * 3.6,
             (S::MetersPerSecond, S::MilesPerHour) => *value * 2.237,
             (S::MetersPerSecond, S::MetersPerSecond) => *value,
+            // knots convert through their speed in meters per second
+            (s, t) if s == t => *value,
+            (s, t) => *value * (s.meters_per_second() / t.meters_per_second()),
+        }
+    }
+
+    /// one of this unit in meters per second
+    fn meters_per_second(&self) -> f64 {
+        use SpeedUnit as S;
+        match self {
+            S::KilometersPerHour => 1.0 / 3.6,
+            S::MilesPerHour => 0.44704,
+            S::MetersPerSecond => 1.0,
+            S::Knots => 1852.0 / 3600.0,
         }
     }
 
@@ -103,6 +118,7 @@ impl SpeedUnit {
             S::KilometersPerHour => Speed::new(120.675),
             S::MilesPerHour => Speed::new(75.0),
             S::MetersPerSecond => Speed::new(33.528),
+            S::Knots => Speed::new(65.175),
         }
     }
 }
@@ -111,6 +127,7 @@ impl SpeedUnit {
 mod test {
 
     use super::{SpeedUnit as S, *};
+    use crate::model::unit::{as_f64::AsF64, Distance, Time};
 
     fn assert_approx_eq(a: Speed, b: Speed, error: f64) {
         let result = match (a, b) {
@@ -125,6 +142,19 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_try_from_distance_and_time() {
+        assert_eq!(
+            S::try_from((DistanceUnit::Kilometers, TimeUnit::Hours)).unwrap(),
+            S::KilometersPerHour
+        );
+        assert_eq!(
+            S::try_from((DistanceUnit::NauticalMiles, TimeUnit::Hours)).unwrap(),
+            S::Knots
+        );
+        assert!(S::try_from((DistanceUnit::Feet, TimeUnit::Minutes)).is_err());
+    }
+
     #[test]
     fn test_conversions() {
         assert_approx_eq(
@@ -173,4 +203,38 @@ mod test {
             0.001,
         );
     }
+
+    #[test]
+    fn test_pairwise_conversions() {
+        assert_approx_eq(
+            S::Knots.convert(&Speed::ONE, &S::KilometersPerHour),
+            Speed::new(1.852),
+            0.0001,
+        );
+        for a in S::ALL {
+            for b in S::ALL {
+                let there = a.convert(&Speed::new(42.0), b);
+                assert_approx_eq(b.convert(&there, a), Speed::new(42.0), 0.01);
+                // a speed unit converts like its distance unit over its time unit
+                let distance = a
+                    .associated_distance_unit()
+                    .convert(&Distance::ONE, &b.associated_distance_unit());
+                let time = a
+                    .associated_time_unit()
+                    .convert(&Time::ONE, &b.associated_time_unit());
+                let expected = 42.0 * distance.as_f64() / time.as_f64();
+                assert_approx_eq(there, Speed::new(expected), expected * 0.001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for unit in S::ALL {
+            assert_eq!(S::parse(&unit.to_string()).unwrap(), *unit);
+            let json = serde_json::to_string(unit).unwrap();
+            assert_eq!(serde_json::from_str::<S>(&json).unwrap(), *unit);
+        }
+        assert!(S::parse("furlongs_per_fortnight").is_err());
+    }
 }
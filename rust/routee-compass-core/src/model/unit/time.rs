@@ -45,12 +45,12 @@ impl From<(Distance, Speed)> for Time {
 }
 impl From<StateVar> for Time {
     fn from(value: StateVar) -> Self {
-        Time::new(value.0)
+        Time::new(value.as_f64())
     }
 }
 impl PartialOrd for Time {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
@@ -1,5 +1,4 @@
-use super::Time;
-use crate::util::serde::serde_ops::string_deserialize;
+use super::{Time, Unit, UnitError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -37,6 +36,16 @@ impl TimeUnit {
     }
 }
 
+impl Unit for TimeUnit {
+    const KIND: &'static str = "time";
+    const ALL: &'static [TimeUnit] = &[
+        TimeUnit::Hours,
+        TimeUnit::Minutes,
+        TimeUnit::Seconds,
+        TimeUnit::Milliseconds,
+    ];
+}
+
 impl std::fmt::Display for TimeUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = serde_json::to_string(self)
@@ -47,10 +56,10 @@ impl std::fmt::Display for TimeUnit {
 }
 
 impl FromStr for TimeUnit {
-    type Err = serde_json::Error;
+    type Err = UnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        string_deserialize(s)
+        TimeUnit::parse(s)
     }
 }
 
@@ -1,4 +1,4 @@
-use super::{Distance, DistanceUnit, Speed, SpeedUnit, Time};
+use super::{CostRateUnit, Distance, DistanceUnit, Speed, SpeedUnit, Time, TimeUnit};
 
 #[derive(thiserror::Error, Debug)]
 pub enum UnitError {
@@ -10,4 +10,12 @@ pub enum UnitError {
     SpeedFromTimeAndDistanceError(Time, Distance),
     #[error("cannot create time from speed {0} {1} and distance {2} {3}")]
     TimeFromSpeedAndDistanceError(Speed, SpeedUnit, Distance, DistanceUnit),
+    #[error(
+        "cannot convert a cost rate in {0} to {1}, which is charged over a different quantity"
+    )]
+    IncompatibleCostRateUnits(CostRateUnit, CostRateUnit),
+    #[error("there is no speed unit of {0} per {1}")]
+    NoSpeedUnit(DistanceUnit, TimeUnit),
+    #[error("unrecognized {0} unit '{1}', expected one of: {2}")]
+    UnrecognizedUnit(&'static str, String, String),
 }
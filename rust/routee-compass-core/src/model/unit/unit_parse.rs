@@ -0,0 +1,37 @@
+use super::UnitError;
+use itertools::Itertools;
+use serde::de::{value::StrDeserializer, DeserializeOwned, IntoDeserializer};
+use serde::Serialize;
+
+/// a unit which is named by a snake_case string in configurations and queries,
+/// such as `kilometers_per_hour`.
+pub trait Unit: Serialize + DeserializeOwned + Copy + 'static {
+    /// the kind of quantity measured in this unit, used in error messages
+    const KIND: &'static str;
+
+    /// every unit of this kind
+    const ALL: &'static [Self];
+
+    /// parses a unit from its name, ignoring case and surrounding whitespace. any
+    /// alias accepted when deserializing the unit is also accepted here.
+    fn parse(name: &str) -> Result<Self, UnitError> {
+        let normalized = name.trim().to_lowercase();
+        let deserializer: StrDeserializer<'_, serde::de::value::Error> =
+            normalized.as_str().into_deserializer();
+        Self::deserialize(deserializer).map_err(|_| {
+            UnitError::UnrecognizedUnit(
+                Self::KIND,
+                name.to_string(),
+                Self::ALL.iter().map(|u| u.name()).join(", "),
+            )
+        })
+    }
+
+    /// the name of this unit, which [`Unit::parse`] reads back into the same unit
+    fn name(&self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => String::new(),
+        }
+    }
+}
@@ -27,7 +27,7 @@ pub struct Untyped(pub InternalFloat);
 
 impl PartialOrd for Untyped {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
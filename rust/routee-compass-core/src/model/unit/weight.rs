@@ -35,7 +35,7 @@ impl AsF64 for Weight {
 }
 impl PartialOrd for Weight {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 impl Ord for Weight {
@@ -50,7 +50,7 @@ impl Display for Weight {
 }
 impl From<StateVar> for Weight {
     fn from(value: StateVar) -> Self {
-        Weight::new(value.0)
+        Weight::new(value.as_f64())
     }
 }
 
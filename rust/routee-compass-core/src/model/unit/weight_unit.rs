@@ -1,5 +1,4 @@
-use super::Weight;
-use crate::util::serde::serde_ops::string_deserialize;
+use super::{Unit, UnitError, Weight};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -28,6 +27,11 @@ impl WeightUnit {
     }
 }
 
+impl Unit for WeightUnit {
+    const KIND: &'static str = "weight";
+    const ALL: &'static [WeightUnit] = &[WeightUnit::Pounds, WeightUnit::Tons, WeightUnit::Kg];
+}
+
 impl std::fmt::Display for WeightUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = serde_json::to_string(self)
@@ -38,10 +42,10 @@ impl std::fmt::Display for WeightUnit {
 }
 
 impl FromStr for WeightUnit {
-    type Err = serde_json::Error;
+    type Err = UnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        string_deserialize(s)
+        WeightUnit::parse(s)
     }
 }
 
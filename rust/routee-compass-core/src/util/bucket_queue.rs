@@ -0,0 +1,143 @@
+use allocative::Allocative;
+use std::{collections::HashMap, hash::Hash};
+
+/// number of buckets: one for keys equal to the last popped key, and one for each
+/// bit position at which a key may first differ from it
+const N_BUCKETS: usize = u64::BITS as usize + 1;
+
+/// a monotone priority queue over integer keys, popping the item with the smallest key,
+/// where no key is smaller than the key last popped. this holds for a search without a
+/// cost estimate over non-negative costs, which makes it a drop-in replacement for a
+/// binary heap in dijkstra's algorithm without comparing keys on each push and pop.
+///
+/// implemented as a multi-level bucket queue (a radix heap). bucket `i` holds the
+/// items whose key first differs from the last popped key at bit `i - 1`, and when
+/// the lowest bucket is empty, the next non-empty bucket is split among the lower
+/// buckets. each item moves down at most 64 times, so pushes and pops take amortized
+/// constant time.
+///
+/// lowering the key of a queued item leaves its old entry in place, which is skipped
+/// when it is popped.
+pub struct BucketQueue<I: Hash + Eq + Copy> {
+    buckets: Vec<Vec<(u64, I)>>,
+    last: u64,
+    queued: HashMap<I, u64>,
+}
+
+impl<I: Hash + Eq + Copy> BucketQueue<I> {
+    pub fn new() -> BucketQueue<I> {
+        BucketQueue {
+            buckets: vec![vec![]; N_BUCKETS],
+            last: 0,
+            queued: HashMap::new(),
+        }
+    }
+
+    /// number of items in the queue
+    pub fn len(&self) -> usize {
+        self.queued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+
+    /// number of items the queue can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.queued.capacity()
+    }
+
+    /// empties the queue, keeping its memory for reuse
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.queued.clear();
+        self.last = 0;
+    }
+
+    /// adds an item, or lowers its key if it is queued with a larger key. a key below
+    /// the last popped key is raised to it, as the queue cannot go back.
+    pub fn push_decrease(&mut self, item: I, key: u64) {
+        let key = key.max(self.last);
+        match self.queued.get(&item) {
+            Some(queued) if *queued <= key => {}
+            _ => {
+                self.queued.insert(item, key);
+                self.buckets[bucket_index(self.last, key)].push((key, item));
+            }
+        }
+    }
+
+    /// removes the item with the smallest key
+    pub fn pop(&mut self) -> Option<(I, u64)> {
+        loop {
+            if self.buckets[0].is_empty() {
+                let next = (1..N_BUCKETS).find(|i| !self.buckets[*i].is_empty())?;
+                let mut bucket = std::mem::take(&mut self.buckets[next]);
+                self.last = bucket
+                    .iter()
+                    .map(|(key, _)| *key)
+                    .min()
+                    .unwrap_or(self.last);
+                for (key, item) in bucket.drain(..) {
+                    self.buckets[bucket_index(self.last, key)].push((key, item));
+                }
+                // keep the emptied bucket's memory
+                self.buckets[next] = bucket;
+            }
+            let (key, item) = self.buckets[0].pop()?;
+            if self.queued.get(&item) == Some(&key) {
+                self.queued.remove(&item);
+                return Some((item, key));
+            }
+        }
+    }
+}
+
+impl<I: Hash + Eq + Copy> Default for BucketQueue<I> {
+    fn default() -> Self {
+        BucketQueue::new()
+    }
+}
+
+impl<I: Hash + Eq + Copy> Allocative for BucketQueue<I> {
+    fn visit<'a, 'b: 'a>(&self, visitor: &'a mut allocative::Visitor<'b>) {
+        let _visitor = visitor.enter_self_sized::<Self>();
+    }
+}
+
+fn bucket_index(last: u64, key: u64) -> usize {
+    if key == last {
+        0
+    } else {
+        (u64::BITS - (key ^ last).leading_zeros()) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pops_in_key_order() {
+        let mut queue = BucketQueue::new();
+        let keys = [37u64, 5, 1 << 40, 5, 912, 0, 64, 63, 1_000_001];
+        for (item, key) in keys.iter().enumerate() {
+            queue.push_decrease(item, *key);
+        }
+        // lowering a key replaces the queued entry, raising it is ignored
+        queue.push_decrease(2, 6);
+        queue.push_decrease(0, 1000);
+        assert_eq!(queue.len(), keys.len());
+
+        let mut popped = vec![];
+        while let Some((item, key)) = queue.pop() {
+            popped.push(key);
+            // keys pushed after a pop may not be smaller than the popped key
+            if item == 5 {
+                queue.push_decrease(100, 0);
+            }
+        }
+        assert_eq!(popped, vec![0, 0, 5, 5, 6, 37, 63, 64, 912, 1_000_001]);
+        assert!(queue.is_empty());
+    }
+}
@@ -146,7 +146,7 @@ impl<K: Hash + Ord + PartialEq + Clone, V: Clone> CompactOrderedHashMap<K, V> {
         self.get(k).is_some()
     }
 
-    pub fn keys(&self) -> KeyIterator<K> {
+    pub fn keys(&self) -> KeyIterator<'_, K> {
         match self {
             CompactOrderedHashMap::OneEntry { k1, v1: _ } => Box::new([k1].into_iter()),
             CompactOrderedHashMap::TwoEntries {
@@ -375,7 +375,7 @@ impl<K: Hash + Ord + PartialEq + Clone, V: Clone> CompactOrderedHashMap<K, V> {
                         (k, IndexedEntry::new(v, 4)),
                     ]);
 
-                    std::mem::swap(self, &mut CompactOrderedHashMap::NEntries(five));
+                    *self = CompactOrderedHashMap::NEntries(five);
                     None
                 }
             }
@@ -538,7 +538,7 @@ impl<K: Hash + Ord + PartialEq + Clone, V: Clone> CompactOrderedHashMap<K, V> {
     }
 
     /// iterates over the entries in this collection in their index ordering.
-    pub fn iter(&self) -> ValueIterator<K, V> {
+    pub fn iter(&self) -> ValueIterator<'_, K, V> {
         let iter = CompactOrderedHashMapIter {
             iterable: self,
             index: 0,
@@ -547,7 +547,7 @@ impl<K: Hash + Ord + PartialEq + Clone, V: Clone> CompactOrderedHashMap<K, V> {
     }
 
     /// iterator that includes the IndexedEntry wrapper around each value
-    pub fn indexed_iter(&self) -> IndexedFeatureIterator<K, V> {
+    pub fn indexed_iter(&self) -> IndexedFeatureIterator<'_, K, V> {
         self.iter().enumerate()
     }
 }
@@ -765,9 +765,7 @@ mod test {
 
         // test that ordering is correct
         let expected_values_sorted = vec![&v1, &v2, &v3, &v4, &v5];
-        for ((_stored_k, stored_v), expected_v) in
-            map.iter().zip(expected_values_sorted.into_iter())
-        {
+        for ((_stored_k, stored_v), expected_v) in map.iter().zip(expected_values_sorted) {
             assert_eq!(
                 stored_v.field, expected_v.field,
                 "stored values do not match, could be due to ordering logic"
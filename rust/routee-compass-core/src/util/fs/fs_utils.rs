@@ -1,31 +1,100 @@
 use std::{
     fs::File,
-    io::{self, BufRead, BufReader},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
     path::Path,
 };
 
-use flate2::read::GzDecoder;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 
-/// The output is wrapped in a Result to allow matching on errors
-/// Returns an Iterator to the Reader of the lines of the file.
-/// based on https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
-///
-pub fn line_count<P>(filename: P, is_gzip: bool) -> std::io::Result<usize>
+/// the compression applied to an input file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// the first bytes of every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// detects the compression of a file from its extension, `.gz` or `.zst`.
+/// files without either extension are inspected for a gzip or zstd header.
+pub fn file_compression<P>(filepath: P) -> FileCompression
 where
     P: AsRef<Path>,
 {
-    let file = File::open(filename)?;
-
-    if is_gzip {
-        let reader = BufReader::new(GzDecoder::new(file));
-        Ok(reader.lines().count())
-    } else {
-        let reader = BufReader::new(file);
-        let count = reader.lines().count();
-        Ok(count)
+    match filepath.as_ref().extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => FileCompression::Gzip,
+        Some("zst") | Some("zstd") => FileCompression::Zstd,
+        _ if is_gzip(&filepath) => FileCompression::Gzip,
+        _ if is_zstd(&filepath) => FileCompression::Zstd,
+        _ => FileCompression::None,
     }
 }
 
+/// opens a file for buffered reading, decompressing gzip and zstd files
+/// as they are read.
+pub fn open_reader<P>(filepath: P) -> io::Result<Box<dyn BufRead>>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(&filepath)?;
+    let reader: Box<dyn BufRead> = match file_compression(&filepath) {
+        FileCompression::Gzip => Box::new(BufReader::new(GzDecoder::new(file))),
+        #[cfg(feature = "fs")]
+        FileCompression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+        #[cfg(not(feature = "fs"))]
+        FileCompression::Zstd => return Err(zstd_unsupported()),
+        FileCompression::None => Box::new(BufReader::new(file)),
+    };
+    Ok(reader)
+}
+
+/// creates a file for buffered writing, compressing with gzip or zstd when the
+/// file extension is `.gz` or `.zst`. the compressed stream is finished when
+/// the writer is dropped.
+pub fn create_writer<P>(filepath: P) -> io::Result<Box<dyn Write>>
+where
+    P: AsRef<Path>,
+{
+    let file = BufWriter::new(File::create(&filepath)?);
+    let writer: Box<dyn Write> = match filepath.as_ref().extension().and_then(|e| e.to_str()) {
+        Some("gz") => Box::new(GzEncoder::new(file, Compression::default())),
+        #[cfg(feature = "fs")]
+        Some("zst") | Some("zstd") => Box::new(zstd::Encoder::new(file, 0)?.auto_finish()),
+        #[cfg(not(feature = "fs"))]
+        Some("zst") | Some("zstd") => return Err(zstd_unsupported()),
+        _ => Box::new(file),
+    };
+    Ok(writer)
+}
+
+/// zstd is a C library and is only built with the `fs` feature
+#[cfg(not(feature = "fs"))]
+fn zstd_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "zstd files require routee-compass-core to be built with the `fs` feature",
+    )
+}
+
+/// checks if a file is a Parquet file by its `.parquet` extension
+pub fn is_parquet<P>(filepath: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    filepath.as_ref().extension().and_then(|ext| ext.to_str()) == Some("parquet")
+}
+
+/// counts the lines of a file, decompressing gzip and zstd files.
+/// used to size collections and progress bars before reading a file.
+pub fn line_count<P>(filename: P) -> std::io::Result<usize>
+where
+    P: AsRef<Path>,
+{
+    Ok(open_reader(filename)?.lines().count())
+}
+
 /// attempts to read a gzip header from the file. if it is found,
 /// then returns true. some inefficiency here due to throwing out the
 /// stream object that could have been used later, but in typical Compass
@@ -43,3 +112,15 @@ where
         }
     }
 }
+
+/// checks if a file starts with the zstd frame header
+pub fn is_zstd<P>(filepath: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    let mut magic = [0_u8; 4];
+    match File::open(filepath) {
+        Err(_) => false,
+        Ok(mut file) => file.read_exact(&mut magic).is_ok() && magic == ZSTD_MAGIC,
+    }
+}
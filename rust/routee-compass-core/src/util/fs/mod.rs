@@ -1,3 +1,5 @@
 pub mod fs_utils;
+#[cfg(feature = "parquet")]
+pub mod parquet_utils;
 pub mod read_decoders;
 pub mod read_utils;
@@ -0,0 +1,148 @@
+//! reads Parquet files by decoding each value from the type of its column, rather than
+//! writing it as text and parsing it again as a CSV field would be.
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::{Field, Row};
+use serde::de::{self, value::Error as DeError, IntoDeserializer};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{fs::File, io, path::Path};
+
+type Rows = Box<dyn Iterator<Item = io::Result<Row>>>;
+type ValueRows = Box<dyn Iterator<Item = io::Result<Vec<Value>>>>;
+
+/// opens a Parquet file, returning its column names and an iterator over its rows.
+pub fn read_rows<P: AsRef<Path>>(filepath: P) -> io::Result<(Vec<String>, Rows)> {
+    let reader = SerializedFileReader::new(File::open(filepath)?).map_err(io::Error::other)?;
+    let headers = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .root_schema()
+        .get_fields()
+        .iter()
+        .map(|f| f.name().to_string())
+        .collect::<Vec<_>>();
+    let rows = reader.into_iter().map(|row| row.map_err(io::Error::other));
+    Ok((headers, Box::new(rows)))
+}
+
+/// reads each row of a Parquet file as a record of type T, with each column of the
+/// row as a field of the record
+pub fn deserialize_rows<'a, P, T>(
+    filepath: P,
+) -> io::Result<Box<dyn Iterator<Item = io::Result<T>> + 'a>>
+where
+    P: AsRef<Path>,
+    T: de::DeserializeOwned + 'a,
+{
+    let (_, rows) = read_rows(filepath)?;
+    let records = rows.map(|row| T::deserialize(RowDeserializer(&row?)).map_err(io::Error::other));
+    Ok(Box::new(records))
+}
+
+/// reads each row of a Parquet file as a JSON value per column, with null values as
+/// JSON null, for tables whose columns are chosen at runtime
+pub fn read_value_rows<P: AsRef<Path>>(filepath: P) -> io::Result<(Vec<String>, ValueRows)> {
+    let (headers, rows) = read_rows(filepath)?;
+    let records = rows.map(|row| {
+        row?.get_column_iter()
+            .map(|(_, field)| Value::deserialize(FieldDeserializer(field)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io::Error::other)
+    });
+    Ok((headers, Box::new(records)))
+}
+
+/// the number of rows of a Parquet file, read from its footer
+pub fn row_count<P: AsRef<Path>>(filepath: P) -> io::Result<usize> {
+    let reader = SerializedFileReader::new(File::open(filepath)?).map_err(io::Error::other)?;
+    Ok(reader.metadata().file_metadata().num_rows() as usize)
+}
+
+/// deserializes a row as a map from column name to value
+struct RowDeserializer<'a>(&'a Row);
+
+impl<'de> de::Deserializer<'de> for RowDeserializer<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let entries = self.0.get_column_iter().map(|(name, field)| {
+            let name = de::value::BorrowedStrDeserializer::<DeError>::new(name.as_str());
+            (name, FieldDeserializer(field))
+        });
+        visitor.visit_map(de::value::MapDeserializer::new(entries))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// deserializes one value of a row by the type of its column. whole numbers stored as
+/// floats, as dataframes often store integer columns, are read as integers so that
+/// they can fill integer fields such as ids.
+struct FieldDeserializer<'a>(&'a Field);
+
+impl<'de> IntoDeserializer<'de, DeError> for FieldDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for FieldDeserializer<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Field::Null => visitor.visit_unit(),
+            Field::Bool(v) => visitor.visit_bool(*v),
+            Field::Byte(v) => visitor.visit_i64(*v as i64),
+            Field::Short(v) => visitor.visit_i64(*v as i64),
+            Field::Int(v) => visitor.visit_i64(*v as i64),
+            Field::Long(v) => visitor.visit_i64(*v),
+            Field::UByte(v) => visitor.visit_u64(*v as u64),
+            Field::UShort(v) => visitor.visit_u64(*v as u64),
+            Field::UInt(v) => visitor.visit_u64(*v as u64),
+            Field::ULong(v) => visitor.visit_u64(*v),
+            Field::Float16(v) => visit_float(v.to_f64(), visitor),
+            Field::Float(v) => visit_float(*v as f64, visitor),
+            Field::Double(v) => visit_float(*v, visitor),
+            Field::Str(v) => visitor.visit_borrowed_str(v),
+            other => visitor.visit_string(other.to_string()),
+        }
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Field::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+fn visit_float<'de, V: de::Visitor<'de>>(value: f64, visitor: V) -> Result<V::Value, DeError> {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        visitor.visit_i64(value as i64)
+    } else {
+        visitor.visit_f64(value)
+    }
+}
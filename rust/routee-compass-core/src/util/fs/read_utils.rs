@@ -1,19 +1,21 @@
 use super::fs_utils;
 use csv::ReaderBuilder;
-use flate2::read::GzDecoder;
+use serde_json::Value;
 
 use std::{
-    fs::File,
-    io::{self, BufRead, BufReader},
+    io::{self, BufRead},
     path::Path,
 };
 
 type RowCallback<'a, T> = Option<Box<dyn FnMut(&T) + 'a>>;
+type ValueRecords = Box<dyn Iterator<Item = Result<Vec<Value>, io::Error>>>;
 
 /// reads from a CSV into an iterator of T records.
 /// building the iterator may fail with an io::Error.
 /// each row hasn't yet been decoded so it is provided in a Result<T, csv::Error>
 ///
+/// files with a `.parquet` extension are read as Parquet, with each field decoded from
+/// the type of its column, and always use their column names as headers.
 pub fn iterator_from_csv<'a, F, T>(
     filepath: F,
     has_headers: bool,
@@ -23,29 +25,120 @@ where
     F: AsRef<Path>,
     T: serde::de::DeserializeOwned + 'a,
 {
-    let f = File::open(filepath.as_ref())?;
-    let r: Box<dyn io::Read> = if fs_utils::is_gzip(filepath) {
-        Box::new(BufReader::new(GzDecoder::new(f)))
-    } else {
-        Box::new(f)
-    };
-    let reader = ReaderBuilder::new()
-        .has_headers(has_headers)
-        .trim(csv::Trim::Fields)
-        .from_reader(r)
-        .into_deserialize::<T>()
-        .map(move |r| {
-            if let Ok(t) = &r {
-                if let Some(cb) = &mut row_callback {
-                    cb(t);
-                }
+    let rows: Box<dyn Iterator<Item = Result<T, csv::Error>> + 'a> =
+        if fs_utils::is_parquet(&filepath) {
+            Box::new(deserialize_parquet_rows(filepath)?.map(|r| r.map_err(csv::Error::from)))
+        } else {
+            let r = fs_utils::open_reader(filepath)?;
+            let reader = ReaderBuilder::new()
+                .has_headers(has_headers)
+                .trim(csv::Trim::Fields)
+                .from_reader(r)
+                .into_deserialize::<T>();
+            Box::new(reader)
+        };
+    let reader = rows.inspect(move |r| {
+        if let Ok(t) = r {
+            if let Some(cb) = &mut row_callback {
+                cb(t);
             }
-            r
-        });
+        }
+    });
 
     Ok(Box::new(reader))
 }
 
+/// reads the header row and the rows of a table without decoding them into a type,
+/// for tables whose columns are chosen at runtime. the table is a CSV file with a
+/// header row, which may be gzip or zstd compressed, with each field read as a JSON
+/// string, or a Parquet file when its extension is `.parquet`, with each field read
+/// as the JSON value of its column type. empty and null values are JSON null.
+pub fn read_value_records<F>(filepath: F) -> Result<(Vec<String>, ValueRecords), io::Error>
+where
+    F: AsRef<Path>,
+{
+    if fs_utils::is_parquet(&filepath) {
+        read_parquet_value_records(filepath)
+    } else {
+        let r = fs_utils::open_reader(filepath)?;
+        let mut reader = ReaderBuilder::new().trim(csv::Trim::All).from_reader(r);
+        let headers = reader.headers()?.iter().map(String::from).collect();
+        let records = reader.into_records().map(|record| {
+            let values = record?
+                .iter()
+                .map(|v| match v {
+                    "" => Value::Null,
+                    v => Value::String(v.to_string()),
+                })
+                .collect();
+            Ok(values)
+        });
+        Ok((headers, Box::new(records)))
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn read_parquet_value_records<F>(filepath: F) -> Result<(Vec<String>, ValueRecords), io::Error>
+where
+    F: AsRef<Path>,
+{
+    super::parquet_utils::read_value_rows(filepath)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn read_parquet_value_records<F>(_filepath: F) -> Result<(Vec<String>, ValueRecords), io::Error>
+where
+    F: AsRef<Path>,
+{
+    Err(parquet_unsupported())
+}
+
+#[cfg(feature = "parquet")]
+fn deserialize_parquet_rows<'a, F, T>(
+    filepath: F,
+) -> Result<Box<dyn Iterator<Item = Result<T, io::Error>> + 'a>, io::Error>
+where
+    F: AsRef<Path>,
+    T: serde::de::DeserializeOwned + 'a,
+{
+    super::parquet_utils::deserialize_rows(filepath)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn deserialize_parquet_rows<'a, F, T>(
+    _filepath: F,
+) -> Result<Box<dyn Iterator<Item = Result<T, io::Error>> + 'a>, io::Error>
+where
+    F: AsRef<Path>,
+    T: serde::de::DeserializeOwned + 'a,
+{
+    Err(parquet_unsupported())
+}
+
+/// counts the rows of a table, not counting the header row of a CSV file when
+/// `has_headers` is set. Parquet files are counted from their metadata.
+pub fn row_count<F>(filepath: F, has_headers: bool) -> Result<usize, io::Error>
+where
+    F: AsRef<Path>,
+{
+    if fs_utils::is_parquet(&filepath) {
+        #[cfg(feature = "parquet")]
+        return super::parquet_utils::row_count(filepath);
+        #[cfg(not(feature = "parquet"))]
+        return Err(parquet_unsupported());
+    }
+    let n = fs_utils::line_count(filepath)?;
+    Ok(if has_headers { n.saturating_sub(1) } else { n })
+}
+
+#[cfg(not(feature = "parquet"))]
+fn parquet_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "parquet files require routee-compass-core to be built with the `parquet` feature",
+    )
+}
+
 /// reads a csv file into a vector. not space-optimized since size is not
 /// known.
 pub fn from_csv<'a, T>(
@@ -67,25 +160,11 @@ where
 
 /// reads in a raw file and deserializes each line of the file into a type T
 /// using the provided operation.
-/// inspects the file to determine if it should read as a raw or gzip stream.
+/// gzip and zstd files are decompressed, see [`fs_utils::file_compression`].
 /// the row index (starting from zero) is passed to the deserialization op
 /// as in most cases, the row number is an id.
-pub fn read_raw_file<'a, F: AsRef<Path>, T>(
-    filepath: F,
-    op: impl Fn(usize, String) -> Result<T, io::Error>,
-    row_callback: Option<Box<dyn FnMut() + 'a>>,
-) -> Result<Box<[T]>, io::Error>
-where
-    F: AsRef<Path>,
-{
-    if fs_utils::is_gzip(filepath.as_ref()) {
-        Ok(read_gzip(filepath, op, row_callback)?)
-    } else {
-        Ok(read_regular(filepath, op, row_callback)?)
-    }
-}
-
-fn read_regular<'a, F, T>(
+/// a Parquet file, by its `.parquet` extension, is read from its first column.
+pub fn read_raw_file<'a, F, T>(
     filepath: F,
     op: impl Fn(usize, String) -> Result<T, io::Error>,
     mut row_callback: Option<Box<dyn FnMut() + 'a>>,
@@ -93,10 +172,21 @@ fn read_regular<'a, F, T>(
 where
     F: AsRef<Path>,
 {
-    let file = File::open(filepath)?;
-    let reader = BufReader::new(file);
-    let result: Result<Box<[T]>, std::io::Error> = reader
-        .lines()
+    let lines: Box<dyn Iterator<Item = Result<String, io::Error>>> =
+        if fs_utils::is_parquet(&filepath) {
+            let (_, records) = read_value_records(filepath)?;
+            Box::new(records.map(|r| {
+                let line = match r?.into_iter().next() {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(v)) => v,
+                    Some(other) => other.to_string(),
+                };
+                Ok(line)
+            }))
+        } else {
+            Box::new(fs_utils::open_reader(filepath)?.lines())
+        };
+    let result: Result<Box<[T]>, std::io::Error> = lines
         .enumerate()
         .map(|(idx, row)| {
             let parsed = row?;
@@ -110,33 +200,11 @@ where
     result
 }
 
-fn read_gzip<'a, F, T>(
-    filepath: F,
-    op: impl Fn(usize, String) -> Result<T, io::Error>,
-    mut row_callback: Option<Box<dyn FnMut() + 'a>>,
-) -> Result<Box<[T]>, io::Error>
-where
-    F: AsRef<Path>,
-{
-    let file = File::open(filepath)?;
-    let mut result = vec![];
-    let reader = BufReader::new(GzDecoder::new(file));
-    for (idx, row) in reader.lines().enumerate() {
-        let parsed = row?;
-        let deserialized = op(idx, parsed)?;
-        if let Some(cb) = &mut row_callback {
-            cb();
-        }
-        result.push(deserialized);
-    }
-    Ok(result.into_boxed_slice())
-}
-
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use super::read_raw_file;
+    use super::{iterator_from_csv, read_raw_file, row_count};
 
     #[test]
     fn test_read_raw_file() {
@@ -187,4 +255,78 @@ mod tests {
             "result should include each row from the source file along with the bonus word"
         );
     }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn test_read_raw_file_zstd() {
+        let filepath = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("util")
+            .join("fs")
+            .join("test")
+            .join("test.txt.zst");
+        let op = |_idx: usize, row: String| Ok(row);
+        let result = read_raw_file(&filepath, op, None).unwrap();
+        let expected = vec![
+            String::from("RouteE"),
+            String::from("FASTSim"),
+            String::from("HIVE"),
+            String::from("ADOPT"),
+        ]
+        .into_boxed_slice();
+        assert_eq!(result, expected);
+        assert_eq!(super::fs_utils::line_count(&filepath).unwrap(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_read_parquet() {
+        use crate::model::property::vertex::Vertex;
+        use parquet::{
+            data_type::{DoubleType, Int64Type},
+            file::{properties::WriterProperties, writer::SerializedFileWriter},
+            schema::parser::parse_message_type,
+        };
+        use std::sync::Arc;
+
+        let schema = "message vertices {
+            REQUIRED INT64 vertex_id;
+            REQUIRED DOUBLE x;
+            REQUIRED DOUBLE y;
+        }";
+        let tmp = tempfile::tempdir().unwrap();
+        let filepath = tmp.path().join("vertices.parquet");
+        let file = std::fs::File::create(&filepath).unwrap();
+        let schema = Arc::new(parse_message_type(schema).unwrap());
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<Int64Type>()
+            .write_batch(&[0, 1, 2], None, None)
+            .unwrap();
+        column.close().unwrap();
+        for values in [[-105.0, -105.5, -106.0], [39.0, 39.5, 40.0]] {
+            let mut column = row_group.next_column().unwrap().unwrap();
+            column
+                .typed::<DoubleType>()
+                .write_batch(&values, None, None)
+                .unwrap();
+            column.close().unwrap();
+        }
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        let vertices = iterator_from_csv::<_, Vertex>(&filepath, true, None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vertices.len(), 3);
+        assert_eq!(vertices[2].x(), -106.0);
+        assert_eq!(vertices[1].y(), 39.5);
+        let ids = read_raw_file(&filepath, |_, row| Ok(row), None).unwrap();
+        assert_eq!(ids.as_ref(), ["0", "1", "2"]);
+        assert_eq!(row_count(&filepath, true).unwrap(), 3);
+    }
 }
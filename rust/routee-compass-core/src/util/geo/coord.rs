@@ -4,6 +4,7 @@ use allocative::Allocative;
 use geo::{Coord, CoordNum};
 
 #[derive(Copy, Clone, Default, Debug, PartialEq)]
+#[repr(transparent)]
 pub struct InternalCoord<T: CoordNum>(pub Coord<T>);
 
 impl<T: CoordNum> Allocative for InternalCoord<T> {
@@ -9,8 +9,7 @@ pub fn read_linestring_text_file<F: AsRef<Path>>(
     file: F,
 ) -> Result<Box<[LineString<f32>]>, std::io::Error> {
     let filepath: &Path = file.as_ref();
-    let is_gzip = fs_utils::is_gzip(filepath);
-    let count = fs_utils::line_count(filepath, is_gzip)?;
+    let count = fs_utils::line_count(filepath)?;
 
     let mut pb = Bar::builder()
         .total(count)
@@ -83,6 +82,29 @@ pub fn parse_linestring(_idx: usize, row: String) -> Result<LineString<f32>, std
     Ok(geom)
 }
 
+/// encodes a linestring as little-endian well-known binary (WKB), which can be read by
+/// shapely.wkb.loads and other GIS libraries
+///
+/// # Arguments
+///
+/// * `linestring` - the linestring to encode
+///
+/// # Returns
+///
+/// * the WKB bytes of the linestring, with coordinates as 64-bit floats
+pub fn linestring_to_wkb(linestring: &LineString<f32>) -> Vec<u8> {
+    // byte order marker, geometry type, number of points, then each x and y
+    let mut wkb = Vec::with_capacity(9 + 16 * linestring.0.len());
+    wkb.push(1u8);
+    wkb.extend_from_slice(&2u32.to_le_bytes());
+    wkb.extend_from_slice(&(linestring.0.len() as u32).to_le_bytes());
+    for coord in linestring.0.iter() {
+        wkb.extend_from_slice(&(coord.x as f64).to_le_bytes());
+        wkb.extend_from_slice(&(coord.y as f64).to_le_bytes());
+    }
+    wkb
+}
+
 #[cfg(test)]
 mod test {
 
@@ -111,4 +133,14 @@ mod test {
         assert_eq!(points[0], Point::from((0.0, 0.0)));
         assert_eq!(points[8], Point::from((8.0, 8.0)));
     }
+
+    #[test]
+    fn test_linestring_to_wkb() {
+        let line = LineString::from(vec![(1.0, 2.0), (3.0, 4.0)]);
+        let wkb = linestring_to_wkb(&line);
+        assert_eq!(wkb.len(), 9 + 32);
+        assert_eq!(&wkb[0..9], &[1, 2, 0, 0, 0, 2, 0, 0, 0]);
+        assert_eq!(&wkb[9..17], &1.0f64.to_le_bytes());
+        assert_eq!(&wkb[33..41], &4.0f64.to_le_bytes());
+    }
 }
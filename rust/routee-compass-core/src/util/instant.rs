@@ -0,0 +1,32 @@
+//! a clock for timing searches which also runs in the browser. the standard clock is
+//! not available on `wasm32-unknown-unknown`, where [`Instant`] reads the JavaScript
+//! clock instead. everywhere else it is [`std::time::Instant`].
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub use wasm::Instant;
+
+#[cfg(target_arch = "wasm32")]
+mod wasm {
+    use std::time::Duration;
+
+    /// milliseconds since the epoch from the JavaScript clock
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct Instant(f64);
+
+    impl Instant {
+        pub fn now() -> Instant {
+            Instant(js_sys::Date::now())
+        }
+
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            Duration::from_secs_f64(((self.0 - earlier.0) / 1000.0).max(0.0))
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Instant::now().duration_since(*self)
+        }
+    }
+}
@@ -1,9 +1,11 @@
+pub mod bucket_queue;
 pub mod cache_policy;
 pub mod compact_ordered_hash_map;
 pub mod conversion;
 pub mod duration_extension;
 pub mod fs;
 pub mod geo;
+pub mod instant;
 pub mod io_utils;
 pub mod multiset;
 pub mod priority_queue;
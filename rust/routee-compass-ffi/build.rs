@@ -0,0 +1,18 @@
+use std::{env, path::PathBuf};
+
+/// writes the C header for this crate's exported functions to `$OUT_DIR/routee_compass.h`,
+/// so that building the crate never modifies the source tree
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("set by cargo"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("set by cargo"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml should be a valid cbindgen config");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failure generating C header")
+        .write_to_file(out_dir.join("routee_compass.h"));
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
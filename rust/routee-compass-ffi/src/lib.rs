@@ -0,0 +1,233 @@
+//! C bindings for embedding a [`routee_compass`] app in services written in other
+//! languages. the header `routee_compass.h` is generated by cbindgen into the build
+//! output directory when this crate is built.
+//!
+//! every function returning a pointer returns NULL on failure, after which
+//! [`compass_last_error`] describes the failure. strings returned by the library
+//! must be released with [`compass_string_free`] and apps with [`compass_app_free`].
+
+use routee_compass::app::compass::compass_app::CompassApp as App;
+use serde_json::Value;
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    ptr,
+};
+
+/// an opaque handle to a loaded app
+pub struct CompassApp {
+    app: App,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// loads an app from a configuration TOML file.
+///
+/// returns NULL if the app could not be loaded.
+///
+/// # Safety
+///
+/// `config_path` must be a valid, NUL-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn compass_app_new(config_path: *const c_char) -> *mut CompassApp {
+    guard(|| {
+        let config_path = read_str(config_path, "config_path")?;
+        let app = App::try_from(Path::new(config_path)).map_err(|e| e.to_string())?;
+        Ok(Box::into_raw(Box::new(CompassApp { app })))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// runs a query, or a JSON array of queries, returning a JSON array with every result.
+/// a single query may have many results, such as when an input plugin expands it. a
+/// query that fails has an `error` in its result, as with the command line application.
+///
+/// returns NULL if the input is not valid JSON or the run could not start.
+///
+/// # Safety
+///
+/// `app` must have been returned by [`compass_app_new`] and not yet freed, and
+/// `query_json` must be a valid, NUL-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn compass_app_run(
+    app: *const CompassApp,
+    query_json: *const c_char,
+) -> *mut c_char {
+    guard(|| {
+        let app = app.as_ref().ok_or_else(|| String::from("app is NULL"))?;
+        let query_json = read_str(query_json, "query_json")?;
+        let query: Value = serde_json::from_str(query_json)
+            .map_err(|e| format!("query is not valid JSON: {}", e))?;
+        let queries = match query {
+            Value::Array(queries) => queries,
+            query => vec![query],
+        };
+        let results = Value::Array(run(&app.app, queries)?);
+        let result = CString::new(results.to_string()).map_err(|e| e.to_string())?;
+        Ok(result.into_raw())
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// finishes the output plugins of an app, such as writing its batch summary, and writes
+/// its reproducibility manifest. call
+/// this once when the app is no longer used, before [`compass_app_free`].
+///
+/// returns 0 on success, or -1 on failure.
+///
+/// # Safety
+///
+/// `app` must have been returned by [`compass_app_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn compass_app_finish(app: *const CompassApp) -> c_int {
+    guard(|| {
+        let app = app.as_ref().ok_or_else(|| String::from("app is NULL"))?;
+        app.app.finish().map_err(|e| e.to_string())
+    })
+    .map_or(-1, |_| 0)
+}
+
+/// releases an app. passing NULL does nothing.
+///
+/// # Safety
+///
+/// `app` must have been returned by [`compass_app_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn compass_app_free(app: *mut CompassApp) {
+    if !app.is_null() {
+        drop(Box::from_raw(app));
+    }
+}
+
+/// releases a string returned by this library. passing NULL does nothing.
+///
+/// # Safety
+///
+/// `s` must have been returned by this library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn compass_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// describes the failure of the last call on the calling thread, or NULL if it succeeded.
+/// the string is owned by the library and is valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn compass_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+fn run(app: &App, queries: Vec<Value>) -> Result<Vec<Value>, String> {
+    app.run(queries, None).map_err(|e| e.to_string())
+}
+
+/// runs a function, recording its error or panic as the last error, since neither
+/// may cross the C boundary. the error of any earlier call is cleared first.
+fn guard<T>(f: impl FnOnce() -> Result<T, String>) -> Option<T> {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+    let result = catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let msg = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown cause"));
+        Err(format!("panic in routee-compass: {}", msg))
+    });
+    match result {
+        Ok(value) => Some(value),
+        Err(msg) => {
+            // an error message cannot hold a NUL byte
+            let msg = CString::new(msg.replace('\0', " ")).unwrap_or_default();
+            LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+            None
+        }
+    }
+}
+
+unsafe fn read_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("{} is NULL", name));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|e| format!("{} is not valid UTF-8: {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_new_run_and_free() {
+        let test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../routee-compass/src/app/compass/test/speeds_test");
+        let config = format!(
+            r#"
+[graph]
+edge_list_input_file = "{dir}/test_edges.csv"
+vertex_list_input_file = "{dir}/test_vertices.csv"
+[traversal]
+type = "speed_table"
+speed_table_input_file = "{dir}/test_edge_speeds.csv"
+speed_unit = "kilometers_per_hour"
+[access]
+type = "no_access_model"
+[cost.weights]
+time = 1
+[cost.vehicle_rates.time]
+type = "raw"
+[plugin]
+input_plugins = []
+output_plugins = [
+    {{ type = "traversal", route = "edge_id", geometry_input_file = "{dir}/edge_geometries.txt" }},
+]
+"#,
+            dir = test_dir.display()
+        );
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        std::fs::write(&config_path, config).unwrap();
+        let config_path = CString::new(config_path.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let app = compass_app_new(config_path.as_ptr());
+            assert!(!app.is_null(), "{:?}", CStr::from_ptr(compass_last_error()));
+
+            let query = CString::new(r#"{"origin_vertex": 0, "destination_vertex": 2}"#).unwrap();
+            let result = compass_app_run(app, query.as_ptr());
+            assert!(!result.is_null());
+            let result_json: Value =
+                serde_json::from_str(CStr::from_ptr(result).to_str().unwrap()).unwrap();
+            assert_eq!(result_json[0]["route"]["path"], serde_json::json!([0, 2]));
+            compass_string_free(result);
+            assert!(compass_last_error().is_null());
+
+            let invalid = CString::new("not json").unwrap();
+            assert!(compass_app_run(app, invalid.as_ptr()).is_null());
+            let error = CStr::from_ptr(compass_last_error()).to_str().unwrap();
+            assert!(error.starts_with("query is not valid JSON"));
+
+            // a successful call clears the error of the call before it
+            let queries = CString::new(
+                r#"[{"origin_vertex": 0, "destination_vertex": 2}, {"origin_vertex": 2, "destination_vertex": 0}]"#,
+            )
+            .unwrap();
+            let result = compass_app_run(app, queries.as_ptr());
+            assert!(!result.is_null());
+            assert!(compass_last_error().is_null());
+            let result_json: Value =
+                serde_json::from_str(CStr::from_ptr(result).to_str().unwrap()).unwrap();
+            assert_eq!(result_json.as_array().map(Vec::len), Some(2));
+            compass_string_free(result);
+
+            assert_eq!(compass_app_finish(app), 0);
+            compass_app_free(app);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::proto::{compass_server::Compass, RouteBatchRequest, RouteRequest, RouteResponse};
+use routee_compass::app::compass::compass_app::CompassApp;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+/// serves queries against a CompassApp which is loaded once and shared by every call.
+pub struct CompassService {
+    app: Arc<CompassApp>,
+}
+
+impl CompassService {
+    /// queries run per streamed chunk when a batch request does not set a chunk size
+    pub const DEFAULT_CHUNK_SIZE: usize = 100;
+
+    /// serves an app which is shared with its owner, so that the owner can finish it
+    /// once the server stops
+    pub fn new(app: Arc<CompassApp>) -> CompassService {
+        CompassService { app }
+    }
+}
+
+type RouteBatchResult = Result<RouteResponse, Status>;
+
+#[tonic::async_trait]
+impl Compass for CompassService {
+    async fn route(
+        &self,
+        request: Request<RouteRequest>,
+    ) -> Result<Response<RouteResponse>, Status> {
+        let query = parse_query(&request.get_ref().query_json)?;
+        let app = self.app.clone();
+        let mut results = tokio::task::spawn_blocking(move || run_queries(&app, vec![query]))
+            .await
+            .map_err(|e| Status::internal(format!("route task failed: {}", e)))??;
+        // a single query may be expanded by input plugins into many
+        let result = match results.len() {
+            1 => results.remove(0),
+            _ => json![results],
+        };
+        Ok(Response::new(route_response(&result)))
+    }
+
+    type RouteBatchStream = ReceiverStream<RouteBatchResult>;
+
+    async fn route_batch(
+        &self,
+        request: Request<RouteBatchRequest>,
+    ) -> Result<Response<Self::RouteBatchStream>, Status> {
+        let request = request.into_inner();
+        let queries = request
+            .queries_json
+            .iter()
+            .map(|q| parse_query(q))
+            .collect::<Result<Vec<_>, _>>()?;
+        let chunk_size = match request.chunk_size {
+            0 => Self::DEFAULT_CHUNK_SIZE,
+            n => n as usize,
+        };
+
+        let (tx, rx) = mpsc::channel::<RouteBatchResult>(chunk_size);
+        let app = self.app.clone();
+        tokio::task::spawn_blocking(move || {
+            for chunk in queries.chunks(chunk_size) {
+                let results = match run_queries(&app, chunk.to_vec()) {
+                    Ok(results) => results,
+                    Err(status) => {
+                        let _ = tx.blocking_send(Err(status));
+                        return;
+                    }
+                };
+                for result in results.iter() {
+                    if tx.blocking_send(Ok(route_response(result))).is_err() {
+                        // the client has gone away, so stop running queries
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+/// runs queries, returning each response to the caller rather than writing it to
+/// the configured output file
+fn run_queries(app: &CompassApp, queries: Vec<Value>) -> Result<Vec<Value>, Status> {
+    let run_config = json!({
+        "response_persistence_policy": "persist_response_in_memory",
+        "response_output_policy": { "type": "none" }
+    });
+    app.run(queries, Some(&run_config))
+        .map_err(|e| Status::internal(e.to_string()))
+}
+
+fn parse_query(query_json: &str) -> Result<Value, Status> {
+    match serde_json::from_str::<Value>(query_json) {
+        Ok(query @ Value::Object(_)) => Ok(query),
+        Ok(_) => Err(Status::invalid_argument("query must be a JSON object")),
+        Err(e) => Err(Status::invalid_argument(format!(
+            "invalid query JSON: {}",
+            e
+        ))),
+    }
+}
+
+fn route_response(result: &Value) -> RouteResponse {
+    RouteResponse {
+        result_json: result.to_string(),
+        has_error: result.get("error").is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert!(parse_query(r#"{"origin_vertex": 0, "destination_vertex": 2}"#).is_ok());
+        let not_object = parse_query("[1, 2]").unwrap_err();
+        assert_eq!(not_object.code(), tonic::Code::InvalidArgument);
+        let invalid = parse_query("{").unwrap_err();
+        assert_eq!(invalid.code(), tonic::Code::InvalidArgument);
+        let response = route_response(&json!({"error": "no route"}));
+        assert!(response.has_error);
+    }
+}
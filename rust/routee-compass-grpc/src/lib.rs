@@ -0,0 +1,6 @@
+pub mod compass_service;
+
+/// types and service definitions generated from `proto/compass.proto`
+pub mod proto {
+    tonic::include_proto!("routee.compass.v1");
+}
@@ -0,0 +1,59 @@
+use clap::Parser;
+use log::{error, info};
+use routee_compass::app::compass::compass_app::CompassApp;
+use routee_compass::app::compass::compass_app_ops as ops;
+use routee_compass::app::compass::config::compass_app_builder::CompassAppBuilder;
+use routee_compass_grpc::compass_service::CompassService;
+use routee_compass_grpc::proto::compass_server::CompassServer;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct GrpcArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 50051)]
+    pub port: u16,
+}
+
+/// loads a CompassApp once and serves it over gRPC until the process receives ctrl-c,
+/// then finishes the app once the calls in flight have completed.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+    let args = GrpcArgs::parse();
+
+    let config = ops::read_config_from_file(Path::new(&args.config_file))?;
+    let builder = CompassAppBuilder::default();
+    let app = match CompassApp::try_from((&config, &builder)) {
+        Ok(app) => Arc::new(app),
+        Err(e) => {
+            error!("Could not build CompassApp from config file: {}", e);
+            return Err(e.into());
+        }
+    };
+
+    let address: SocketAddr = format!("{}:{}", args.host, args.port).parse()?;
+    info!("compass gRPC server listening on {}", address);
+    tonic::transport::Server::builder()
+        .add_service(CompassServer::new(CompassService::new(app.clone())))
+        .serve_with_shutdown(address, async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                error!("could not listen for ctrl-c, stopping the server: {}", e);
+            }
+        })
+        .await?;
+    info!("compass gRPC server shut down");
+    app.finish()?;
+    Ok(())
+}
@@ -0,0 +1,330 @@
+use super::gtfs_record::{
+    parse_gtfs_time, GtfsCalendar, GtfsCalendarDate, GtfsFrequency, GtfsStop, GtfsStopTime,
+    GtfsTrip,
+};
+use super::service_calendar::active_services;
+use crate::gtfs_error::GtfsError;
+use chrono::NaiveDate;
+use geo::{coord, Coord};
+use routee_compass_core::model::unit::as_f64::AsF64;
+use routee_compass_core::util::fs::read_utils;
+use routee_compass_core::util::geo::haversine;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// a stop visited by a trip, with times in seconds since the start of the service day
+#[derive(Debug, Clone)]
+pub struct TripStop {
+    pub stop_id: String,
+    pub arrival: u32,
+    pub departure: u32,
+}
+
+/// the parts of a GTFS feed used to build a transit layer
+pub struct GtfsFeed {
+    /// coordinates of each stop
+    pub stops: HashMap<String, Coord<f32>>,
+    /// the timed stops of each trip, ordered by stop sequence
+    pub trips: Vec<Vec<TripStop>>,
+}
+
+impl GtfsFeed {
+    /// reads an unzipped GTFS feed from a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - directory holding stops.txt, trips.txt, stop_times.txt and
+    ///   optionally calendar.txt, calendar_dates.txt and frequencies.txt
+    /// * `service_date` - when provided, only trips running on this date are kept
+    pub fn read(directory: &Path, service_date: Option<&NaiveDate>) -> Result<GtfsFeed, GtfsError> {
+        let stop_rows: Box<[GtfsStop]> = read_table(directory, "stops.txt")?;
+        let stops = stop_rows
+            .iter()
+            .filter_map(|s| match (s.stop_lon, s.stop_lat) {
+                (Some(lon), Some(lat)) => {
+                    Some((s.stop_id.clone(), coord! { x: lon as f32, y: lat as f32 }))
+                }
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let trip_rows: Box<[GtfsTrip]> = read_table(directory, "trips.txt")?;
+        let services = match service_date {
+            None => None,
+            Some(date) => {
+                let calendar: Box<[GtfsCalendar]> = read_optional_table(directory, "calendar.txt")?;
+                let calendar_dates: Box<[GtfsCalendarDate]> =
+                    read_optional_table(directory, "calendar_dates.txt")?;
+                Some(active_services(&calendar, &calendar_dates, date)?)
+            }
+        };
+        let trip_ids = trip_rows
+            .iter()
+            .filter(|t| services.as_ref().is_none_or(|s| s.contains(&t.service_id)))
+            .map(|t| t.trip_id.clone())
+            .collect::<HashSet<_>>();
+
+        let stop_time_rows: Box<[GtfsStopTime]> = read_table(directory, "stop_times.txt")?;
+        let mut by_trip: HashMap<String, Vec<(u32, UntimedStop)>> = HashMap::new();
+        for row in stop_time_rows.iter() {
+            if !trip_ids.contains(&row.trip_id) || !stops.contains_key(&row.stop_id) {
+                continue;
+            }
+            let parse = |time: &Option<String>| match time {
+                Some(t) if !t.is_empty() => parse_gtfs_time(t).map(Some),
+                _ => Ok(None),
+            };
+            // a stop with only one of its times arrives and departs at that time
+            let times = match (parse(&row.arrival_time)?, parse(&row.departure_time)?) {
+                (Some(a), Some(d)) => Some((a, d)),
+                (Some(t), None) | (None, Some(t)) => Some((t, t)),
+                (None, None) => None,
+            };
+            by_trip
+                .entry(row.trip_id.clone())
+                .or_default()
+                .push((row.stop_sequence, (row.stop_id.clone(), times)));
+        }
+
+        let frequency_rows: Box<[GtfsFrequency]> =
+            read_optional_table(directory, "frequencies.txt")?;
+        let mut frequencies: HashMap<&str, Vec<&GtfsFrequency>> = HashMap::new();
+        for row in frequency_rows.iter() {
+            frequencies
+                .entry(row.trip_id.as_str())
+                .or_default()
+                .push(row);
+        }
+
+        let mut untimed = 0;
+        let mut trips = vec![];
+        for (trip_id, mut trip_stops) in by_trip.into_iter() {
+            trip_stops.sort_by_key(|(sequence, _)| *sequence);
+            let trip_stops = trip_stops.into_iter().map(|(_, s)| s).collect::<Vec<_>>();
+            let (trip, n_dropped) = interpolate_times(&trip_stops, &stops)?;
+            untimed += n_dropped;
+            if trip.len() < 2 {
+                continue;
+            }
+            match frequencies.get(trip_id.as_str()) {
+                None => trips.push(trip),
+                Some(rows) => {
+                    for row in rows.iter() {
+                        trips.extend(expand_frequency(&trip, row)?);
+                    }
+                }
+            }
+        }
+        if untimed > 0 {
+            log::warn!(
+                "skipped {} stop times before the first or after the last timed stop of their trip",
+                untimed
+            );
+        }
+        log::info!(
+            "read {} stops and {} trips from {:?}",
+            stops.len(),
+            trips.len(),
+            directory
+        );
+        Ok(GtfsFeed { stops, trips })
+    }
+}
+
+/// a stop of a trip whose arrival and departure times may be missing
+type UntimedStop = (String, Option<(u32, u32)>);
+
+/// fills in the times of stops between two timed stops of a trip, which GTFS
+/// allows for stops that are not timepoints. the time between the timed stops is
+/// shared in proportion to the straight-line distance between the stops, or
+/// evenly when the stops are in the same place. stops before the first or after
+/// the last timed stop cannot be interpolated and are dropped.
+///
+/// # Returns
+///
+/// the timed stops of the trip and the number of stops dropped
+fn interpolate_times(
+    trip_stops: &[UntimedStop],
+    coords: &HashMap<String, Coord<f32>>,
+) -> Result<(Vec<TripStop>, usize), GtfsError> {
+    let timed = trip_stops
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, times))| times.is_some())
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let (first, last) = match (timed.first(), timed.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok((vec![], trip_stops.len())),
+    };
+    let n_dropped = first + (trip_stops.len() - 1 - last);
+
+    let mut result = vec![];
+    for pair in timed.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let (_, start_departure) = trip_stops[start].1.unwrap_or_default();
+        let (end_arrival, _) = trip_stops[end].1.unwrap_or_default();
+        let mut cumulative = vec![0.0];
+        for idx in start..end {
+            let src = coords.get(&trip_stops[idx].0);
+            let dst = coords.get(&trip_stops[idx + 1].0);
+            let step = match (src, dst) {
+                (Some(src), Some(dst)) => haversine::coord_distance_meters(src, dst)
+                    .map(|d| d.as_f64())
+                    .map_err(GtfsError::InvalidData)?,
+                _ => 0.0,
+            };
+            cumulative.push(cumulative[cumulative.len() - 1] + step);
+        }
+        let total = cumulative[cumulative.len() - 1];
+        let duration = end_arrival.saturating_sub(start_departure) as f64;
+        for (offset, idx) in (start..end).enumerate() {
+            let (stop_id, times) = &trip_stops[idx];
+            let (arrival, departure) = match times {
+                Some(times) => *times,
+                None => {
+                    let fraction = if total > 0.0 {
+                        cumulative[offset] / total
+                    } else {
+                        offset as f64 / (end - start) as f64
+                    };
+                    let time = start_departure + (duration * fraction).round() as u32;
+                    (time, time)
+                }
+            };
+            result.push(TripStop {
+                stop_id: stop_id.clone(),
+                arrival,
+                departure,
+            });
+        }
+    }
+    let (stop_id, times) = &trip_stops[last];
+    let (arrival, departure) = times.unwrap_or_default();
+    result.push(TripStop {
+        stop_id: stop_id.clone(),
+        arrival,
+        departure,
+    });
+    Ok((result, n_dropped))
+}
+
+/// copies a trip of frequencies.txt to start at every headway between the start
+/// and end times of a frequency row. the stop times of the trip only give the
+/// time between its stops, so each copy is shifted to depart its first stop at
+/// its start time.
+fn expand_frequency(
+    trip: &[TripStop],
+    frequency: &GtfsFrequency,
+) -> Result<Vec<Vec<TripStop>>, GtfsError> {
+    if frequency.headway_secs == 0 {
+        return Err(GtfsError::InvalidData(format!(
+            "frequency of trip {} has a headway of 0 seconds",
+            frequency.trip_id
+        )));
+    }
+    let first_departure = match trip.first() {
+        Some(stop) => stop.departure,
+        None => return Ok(vec![]),
+    };
+    let start_time = parse_gtfs_time(&frequency.start_time)?;
+    let end_time = parse_gtfs_time(&frequency.end_time)?;
+    let copies = (start_time..end_time)
+        .step_by(frequency.headway_secs as usize)
+        .map(|start| {
+            trip.iter()
+                .map(|stop| TripStop {
+                    stop_id: stop.stop_id.clone(),
+                    arrival: start + stop.arrival.saturating_sub(first_departure),
+                    departure: start + stop.departure.saturating_sub(first_departure),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    Ok(copies)
+}
+
+fn read_table<T: serde::de::DeserializeOwned>(
+    directory: &Path,
+    filename: &str,
+) -> Result<Box<[T]>, GtfsError> {
+    let path = directory.join(filename);
+    read_utils::from_csv(&path, true, None).map_err(|e| GtfsError::ReadError(path, e.to_string()))
+}
+
+fn read_optional_table<T: serde::de::DeserializeOwned>(
+    directory: &Path,
+    filename: &str,
+) -> Result<Box<[T]>, GtfsError> {
+    if directory.join(filename).exists() {
+        read_table(directory, filename)
+    } else {
+        Ok(Box::new([]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coords() -> HashMap<String, Coord<f32>> {
+        HashMap::from([
+            (String::from("a"), coord! { x: -105.0, y: 39.70 }),
+            (String::from("b"), coord! { x: -105.0, y: 39.71 }),
+            (String::from("c"), coord! { x: -105.0, y: 39.73 }),
+            (String::from("d"), coord! { x: -105.0, y: 39.74 }),
+        ])
+    }
+
+    #[test]
+    fn test_interpolate_times() {
+        let trip_stops = vec![
+            (String::from("a"), Some((100, 100))),
+            (String::from("b"), None),
+            (String::from("c"), Some((400, 420))),
+            (String::from("d"), None),
+        ];
+        let (trip, n_dropped) = interpolate_times(&trip_stops, &coords()).unwrap();
+        let times = trip
+            .iter()
+            .map(|s| (s.stop_id.as_str(), s.arrival, s.departure))
+            .collect::<Vec<_>>();
+        // b is a third of the distance from a to c, and d follows the last timed stop
+        assert_eq!(
+            times,
+            vec![("a", 100, 100), ("b", 200, 200), ("c", 400, 420)]
+        );
+        assert_eq!(n_dropped, 1);
+    }
+
+    #[test]
+    fn test_expand_frequency() {
+        let trip = vec![
+            TripStop {
+                stop_id: String::from("a"),
+                arrival: 0,
+                departure: 0,
+            },
+            TripStop {
+                stop_id: String::from("b"),
+                arrival: 300,
+                departure: 300,
+            },
+        ];
+        let frequency = GtfsFrequency {
+            trip_id: String::from("t"),
+            start_time: String::from("06:00:00"),
+            end_time: String::from("07:00:00"),
+            headway_secs: 1200,
+        };
+        let copies = expand_frequency(&trip, &frequency).unwrap();
+        let departures = copies
+            .iter()
+            .map(|t| (t[0].departure, t[1].arrival))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            departures,
+            vec![(21600, 21900), (22800, 23100), (24000, 24300)]
+        );
+    }
+}
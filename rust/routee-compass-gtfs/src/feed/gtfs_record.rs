@@ -0,0 +1,94 @@
+use crate::gtfs_error::GtfsError;
+use serde::Deserialize;
+
+/// a row of stops.txt. stations and entrances without coordinates are kept so
+/// that rows still decode, but only stops with coordinates are used.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GtfsStop {
+    pub stop_id: String,
+    pub stop_lat: Option<f64>,
+    pub stop_lon: Option<f64>,
+}
+
+/// a row of trips.txt
+#[derive(Deserialize, Debug, Clone)]
+pub struct GtfsTrip {
+    pub route_id: String,
+    pub service_id: String,
+    pub trip_id: String,
+}
+
+/// a row of stop_times.txt. times are optional in GTFS for stops between
+/// timepoints.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GtfsStopTime {
+    pub trip_id: String,
+    pub arrival_time: Option<String>,
+    pub departure_time: Option<String>,
+    pub stop_id: String,
+    pub stop_sequence: u32,
+}
+
+/// a row of frequencies.txt. the trip runs every `headway_secs` seconds from
+/// `start_time` until `end_time`, with the stop times of the trip giving the
+/// time between its stops.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GtfsFrequency {
+    pub trip_id: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub headway_secs: u32,
+}
+
+/// a row of calendar.txt
+#[derive(Deserialize, Debug, Clone)]
+pub struct GtfsCalendar {
+    pub service_id: String,
+    pub monday: u8,
+    pub tuesday: u8,
+    pub wednesday: u8,
+    pub thursday: u8,
+    pub friday: u8,
+    pub saturday: u8,
+    pub sunday: u8,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+/// a row of calendar_dates.txt
+#[derive(Deserialize, Debug, Clone)]
+pub struct GtfsCalendarDate {
+    pub service_id: String,
+    pub date: String,
+    /// 1 when service is added on this date, 2 when it is removed
+    pub exception_type: u8,
+}
+
+/// parses a GTFS time such as "08:15:00" as seconds since the start of the service
+/// day. times past midnight of the service day, such as "25:10:00", are allowed.
+pub fn parse_gtfs_time(time: &str) -> Result<u32, GtfsError> {
+    let invalid = || GtfsError::InvalidData(format!("invalid GTFS time '{}'", time));
+    let parts = time
+        .trim()
+        .split(':')
+        .map(|p| p.parse::<u32>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match parts[..] {
+        [h, m, s] if m < 60 && s < 60 => Ok(h * 3600 + m * 60 + s),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gtfs_time() {
+        assert_eq!(parse_gtfs_time("08:15:00").unwrap(), 29700);
+        assert_eq!(parse_gtfs_time(" 7:05:30").unwrap(), 25530);
+        assert_eq!(parse_gtfs_time("25:10:00").unwrap(), 90600);
+        assert!(parse_gtfs_time("08:75:00").is_err());
+        assert!(parse_gtfs_time("8am").is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod gtfs_feed;
+pub mod gtfs_record;
+pub mod service_calendar;
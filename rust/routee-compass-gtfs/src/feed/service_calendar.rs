@@ -0,0 +1,58 @@
+use super::gtfs_record::{GtfsCalendar, GtfsCalendarDate};
+use crate::gtfs_error::GtfsError;
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashSet;
+
+const GTFS_DATE_FORMAT: &str = "%Y%m%d";
+
+/// parses a GTFS date such as "20240304"
+pub fn parse_gtfs_date(date: &str) -> Result<NaiveDate, GtfsError> {
+    NaiveDate::parse_from_str(date.trim(), GTFS_DATE_FORMAT)
+        .map_err(|e| GtfsError::InvalidData(format!("invalid GTFS date '{}': {}", date, e)))
+}
+
+/// finds the services which run on a date, from the weekly patterns in calendar.txt
+/// along with the added and removed dates in calendar_dates.txt
+pub fn active_services(
+    calendar: &[GtfsCalendar],
+    calendar_dates: &[GtfsCalendarDate],
+    date: &NaiveDate,
+) -> Result<HashSet<String>, GtfsError> {
+    let mut services = HashSet::new();
+    for row in calendar.iter() {
+        let start = parse_gtfs_date(&row.start_date)?;
+        let end = parse_gtfs_date(&row.end_date)?;
+        let runs_on_weekday = match date.weekday() {
+            Weekday::Mon => row.monday,
+            Weekday::Tue => row.tuesday,
+            Weekday::Wed => row.wednesday,
+            Weekday::Thu => row.thursday,
+            Weekday::Fri => row.friday,
+            Weekday::Sat => row.saturday,
+            Weekday::Sun => row.sunday,
+        } == 1;
+        if start <= *date && *date <= end && runs_on_weekday {
+            services.insert(row.service_id.clone());
+        }
+    }
+    for row in calendar_dates.iter() {
+        if parse_gtfs_date(&row.date)? != *date {
+            continue;
+        }
+        match row.exception_type {
+            1 => {
+                services.insert(row.service_id.clone());
+            }
+            2 => {
+                services.remove(&row.service_id);
+            }
+            other => {
+                return Err(GtfsError::InvalidData(format!(
+                    "calendar_dates.txt has exception_type {} for service {}, expected 1 or 2",
+                    other, row.service_id
+                )))
+            }
+        }
+    }
+    Ok(services)
+}
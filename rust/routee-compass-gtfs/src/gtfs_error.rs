@@ -0,0 +1,14 @@
+use routee_compass_core::model::road_network::graph_error::GraphError;
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GtfsError {
+    #[error("failure reading {0:?}: {1}")]
+    ReadError(PathBuf, String),
+    #[error("failure writing {0:?}: {1}")]
+    WriteError(PathBuf, std::io::Error),
+    #[error("failure reading road network: {0}")]
+    GraphError(#[from] GraphError),
+    #[error("invalid GTFS data: {0}")]
+    InvalidData(String),
+}
@@ -0,0 +1,2 @@
+pub mod transit_layer;
+pub mod transit_layer_writer;
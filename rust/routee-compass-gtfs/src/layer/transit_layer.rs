@@ -0,0 +1,246 @@
+use crate::feed::gtfs_feed::GtfsFeed;
+use crate::gtfs_error::GtfsError;
+use geo::Coord;
+use routee_compass_core::model::road_network::graph::Graph;
+use routee_compass_core::model::traversal::default::transit_engine::TransitEdgeMode;
+use routee_compass_core::model::unit::as_f64::AsF64;
+use routee_compass_core::util::geo::haversine;
+use rstar::{primitives::GeomWithData, RTree};
+use std::collections::{BTreeSet, HashMap};
+
+/// an edge added to the road network by the transit layer
+#[derive(Debug, Clone, Copy)]
+pub struct TransitLayerEdge {
+    pub src_vertex_id: usize,
+    pub dst_vertex_id: usize,
+    pub distance_meters: f64,
+    pub mode: TransitEdgeMode,
+}
+
+/// a scheduled trip along a transit edge, in seconds since the start of the service day
+#[derive(Debug, Clone, Copy)]
+pub struct TransitLayerDeparture {
+    pub edge_id: usize,
+    pub departure: u32,
+    pub arrival: u32,
+}
+
+/// a transit layer to append to a road network. each stop becomes a vertex after the
+/// road vertices, linked in both directions to its nearest road vertex. each pair of
+/// stops visited in a row by some trip becomes a transit edge after the road edges,
+/// with a departure for every trip along it.
+pub struct TransitLayer<'a> {
+    pub road: &'a Graph,
+    pub stop_ids: Vec<String>,
+    pub stop_coords: Vec<Coord<f32>>,
+    pub edges: Vec<TransitLayerEdge>,
+    pub departures: Vec<TransitLayerDeparture>,
+}
+
+impl<'a> TransitLayer<'a> {
+    /// builds a transit layer over a road network.
+    ///
+    /// # Arguments
+    ///
+    /// * `road` - the road network, usually built for walking
+    /// * `feed` - the transit schedule
+    /// * `max_link_distance_meters` - stops farther than this from any road vertex are
+    ///   only reachable by transit
+    pub fn build(
+        road: &'a Graph,
+        feed: &GtfsFeed,
+        max_link_distance_meters: f64,
+    ) -> Result<TransitLayer<'a>, GtfsError> {
+        let n_road_vertices = road.n_vertices();
+        let n_road_edges = road.n_edges();
+
+        // only stops visited by some trip are added, in a stable order
+        let stop_ids = feed
+            .trips
+            .iter()
+            .flat_map(|trip| trip.iter().map(|s| s.stop_id.clone()))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let stop_vertex = stop_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, stop_id)| (stop_id.as_str(), n_road_vertices + idx))
+            .collect::<HashMap<_, _>>();
+        let stop_coords = stop_ids
+            .iter()
+            .map(|stop_id| {
+                feed.stops.get(stop_id).cloned().ok_or_else(|| {
+                    GtfsError::InvalidData(format!("stop {} has no coordinates", stop_id))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut edges = vec![];
+        let rtree = RTree::bulk_load(
+            road.vertices
+                .iter()
+                .map(|v| GeomWithData::new([v.x(), v.y()], v.vertex_id.0))
+                .collect(),
+        );
+        let mut unlinked = 0;
+        for (idx, coord) in stop_coords.iter().enumerate() {
+            let stop_vertex_id = n_road_vertices + idx;
+            let nearest = match rtree.nearest_neighbor(&[coord.x, coord.y]) {
+                Some(nearest) => nearest,
+                None => break,
+            };
+            let road_coord = road.vertices[nearest.data].coordinate.0;
+            let distance_meters = distance(coord, &road_coord)?;
+            if distance_meters > max_link_distance_meters {
+                unlinked += 1;
+                continue;
+            }
+            for (src_vertex_id, dst_vertex_id) in [
+                (stop_vertex_id, nearest.data),
+                (nearest.data, stop_vertex_id),
+            ] {
+                edges.push(TransitLayerEdge {
+                    src_vertex_id,
+                    dst_vertex_id,
+                    distance_meters,
+                    mode: TransitEdgeMode::Link,
+                });
+            }
+        }
+        if unlinked > 0 {
+            log::warn!(
+                "{} stops are more than {} meters from the road network and are only reachable by transit",
+                unlinked,
+                max_link_distance_meters
+            );
+        }
+
+        let mut transit_edges: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut departures = vec![];
+        for trip in feed.trips.iter() {
+            for pair in trip.windows(2) {
+                let (from, to) = (&pair[0], &pair[1]);
+                let src_vertex_id = stop_vertex[from.stop_id.as_str()];
+                let dst_vertex_id = stop_vertex[to.stop_id.as_str()];
+                if src_vertex_id == dst_vertex_id {
+                    continue;
+                }
+                let edge_id = match transit_edges.get(&(src_vertex_id, dst_vertex_id)) {
+                    Some(edge_id) => *edge_id,
+                    None => {
+                        let edge_id = n_road_edges + edges.len();
+                        let src = &stop_coords[src_vertex_id - n_road_vertices];
+                        let dst = &stop_coords[dst_vertex_id - n_road_vertices];
+                        edges.push(TransitLayerEdge {
+                            src_vertex_id,
+                            dst_vertex_id,
+                            distance_meters: distance(src, dst)?,
+                            mode: TransitEdgeMode::Transit,
+                        });
+                        transit_edges.insert((src_vertex_id, dst_vertex_id), edge_id);
+                        edge_id
+                    }
+                };
+                if to.arrival < from.departure {
+                    return Err(GtfsError::InvalidData(format!(
+                        "trip arrives at stop {} before departing stop {}",
+                        to.stop_id, from.stop_id
+                    )));
+                }
+                departures.push(TransitLayerDeparture {
+                    edge_id,
+                    departure: from.departure,
+                    arrival: to.arrival,
+                });
+            }
+        }
+        departures.sort_by_key(|d| (d.edge_id, d.departure));
+        log::info!(
+            "built transit layer with {} stops, {} edges and {} departures",
+            stop_ids.len(),
+            edges.len(),
+            departures.len()
+        );
+
+        Ok(TransitLayer {
+            road,
+            stop_ids,
+            stop_coords,
+            edges,
+            departures,
+        })
+    }
+}
+
+fn distance(src: &Coord<f32>, dst: &Coord<f32>) -> Result<f64, GtfsError> {
+    haversine::coord_distance_meters(src, dst)
+        .map(|d| d.as_f64())
+        .map_err(GtfsError::InvalidData)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::gtfs_feed::TripStop;
+    use geo::coord;
+    use routee_compass_core::model::property::{edge::Edge, vertex::Vertex};
+    use routee_compass_core::util::compact_ordered_hash_map::CompactOrderedHashMap;
+
+    #[test]
+    fn test_build_transit_layer() {
+        // two road vertices about 1.1km apart, with a stop beside each
+        let vertices = vec![Vertex::new(0, -105.0, 39.70), Vertex::new(1, -105.0, 39.71)];
+        let edges = vec![Edge::new(0, 0, 1, 1113.0), Edge::new(1, 1, 0, 1113.0)];
+        // adjacency is not used to build the layer
+        let road = Graph {
+            adj: vec![CompactOrderedHashMap::empty(); 2].into(),
+            rev: vec![CompactOrderedHashMap::empty(); 2].into(),
+            edges: edges.into(),
+            vertices: vertices.into(),
+            geometries: None,
+            edge_attributes: None,
+        };
+
+        let stops = HashMap::from([
+            (String::from("a"), coord! { x: -105.0001, y: 39.70 }),
+            (String::from("b"), coord! { x: -105.0001, y: 39.71 }),
+        ]);
+        let trip = |departure: u32| {
+            vec![
+                TripStop {
+                    stop_id: String::from("a"),
+                    arrival: departure,
+                    departure,
+                },
+                TripStop {
+                    stop_id: String::from("b"),
+                    arrival: departure + 120,
+                    departure: departure + 120,
+                },
+            ]
+        };
+        let feed = GtfsFeed {
+            stops,
+            trips: vec![trip(28800), trip(27000)],
+        };
+        let layer = TransitLayer::build(&road, &feed, 100.0).unwrap();
+
+        assert_eq!(layer.stop_ids, vec!["a", "b"]);
+        // a link in each direction for both stops, and one transit edge from a to b
+        assert_eq!(layer.edges.len(), 5);
+        let transit = layer.edges[4];
+        assert_eq!(transit.mode, TransitEdgeMode::Transit);
+        assert_eq!((transit.src_vertex_id, transit.dst_vertex_id), (2, 3));
+        let departures = layer
+            .departures
+            .iter()
+            .map(|d| (d.edge_id, d.departure, d.arrival))
+            .collect::<Vec<_>>();
+        assert_eq!(departures, vec![(6, 27000, 27120), (6, 28800, 28920)]);
+
+        // stops beyond the link distance are left unlinked
+        let layer = TransitLayer::build(&road, &feed, 1.0).unwrap();
+        assert_eq!(layer.edges.len(), 1);
+    }
+}
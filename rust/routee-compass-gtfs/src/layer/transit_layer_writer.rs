@@ -0,0 +1,154 @@
+use super::transit_layer::TransitLayer;
+use crate::gtfs_error::GtfsError;
+use routee_compass_core::model::traversal::default::transit_engine::TransitEdgeMode;
+use routee_compass_core::model::unit::as_f64::AsF64;
+use routee_compass_core::util::fs::fs_utils;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+pub const VERTICES_FILE: &str = "vertices-compass.csv.gz";
+pub const EDGES_FILE: &str = "edges-compass.csv.gz";
+pub const EDGE_MODE_FILE: &str = "edges-mode-enumerated.txt.gz";
+pub const DEPARTURES_FILE: &str = "edges-transit-departures.csv.gz";
+pub const STOP_VERTEX_FILE: &str = "stops-vertex-id.csv.gz";
+pub const EDGE_GEOMETRY_FILE: &str = "edges-geometries-enumerated.txt.gz";
+
+impl TransitLayer<'_> {
+    /// writes the road network combined with this transit layer to a directory.
+    /// distances are in meters and times in seconds since the start of the service day.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - where to write the files
+    /// * `road_geometry_file` - optional geometries of the road edges. when provided,
+    ///   the geometries file is extended with straight lines for the new edges.
+    pub fn write(
+        &self,
+        directory: &Path,
+        road_geometry_file: Option<&Path>,
+    ) -> Result<(), GtfsError> {
+        std::fs::create_dir_all(directory)
+            .map_err(|e| GtfsError::WriteError(directory.to_path_buf(), e))?;
+        let n_road_vertices = self.road.n_vertices();
+        let n_road_edges = self.road.n_edges();
+
+        let road_vertices = self
+            .road
+            .vertices
+            .iter()
+            .map(|v| format!("{},{},{}", v.vertex_id, v.x(), v.y()));
+        let stop_vertices = self
+            .stop_coords
+            .iter()
+            .enumerate()
+            .map(|(idx, c)| format!("{},{},{}", n_road_vertices + idx, c.x, c.y));
+        write_rows(
+            &directory.join(VERTICES_FILE),
+            Some("vertex_id,x,y"),
+            road_vertices.chain(stop_vertices),
+        )?;
+
+        let road_edges = self.road.edges.iter().map(|e| {
+            format!(
+                "{},{},{},{}",
+                e.edge_id,
+                e.src_vertex_id,
+                e.dst_vertex_id,
+                e.distance.as_f64()
+            )
+        });
+        let layer_edges = self.edges.iter().enumerate().map(|(idx, e)| {
+            format!(
+                "{},{},{},{}",
+                n_road_edges + idx,
+                e.src_vertex_id,
+                e.dst_vertex_id,
+                e.distance_meters
+            )
+        });
+        write_rows(
+            &directory.join(EDGES_FILE),
+            Some("edge_id,src_vertex_id,dst_vertex_id,distance"),
+            road_edges.chain(layer_edges),
+        )?;
+
+        let road_modes = (0..n_road_edges).map(|_| TransitEdgeMode::Road.to_string());
+        let layer_modes = self.edges.iter().map(|e| e.mode.to_string());
+        write_rows(
+            &directory.join(EDGE_MODE_FILE),
+            None,
+            road_modes.chain(layer_modes),
+        )?;
+
+        write_rows(
+            &directory.join(DEPARTURES_FILE),
+            Some("edge_id,departure,arrival"),
+            self.departures
+                .iter()
+                .map(|d| format!("{},{},{}", d.edge_id, d.departure, d.arrival)),
+        )?;
+
+        write_rows(
+            &directory.join(STOP_VERTEX_FILE),
+            Some("stop_id,vertex_id"),
+            self.stop_ids
+                .iter()
+                .enumerate()
+                .map(|(idx, stop_id)| format!("{},{}", stop_id, n_road_vertices + idx)),
+        )?;
+
+        if let Some(road_geometry_file) = road_geometry_file {
+            let reader = fs_utils::open_reader(road_geometry_file).map_err(|e| {
+                GtfsError::ReadError(road_geometry_file.to_path_buf(), e.to_string())
+            })?;
+            let road_geometries = reader.lines().collect::<Result<Vec<_>, _>>().map_err(|e| {
+                GtfsError::ReadError(road_geometry_file.to_path_buf(), e.to_string())
+            })?;
+            if road_geometries.len() != n_road_edges {
+                return Err(GtfsError::InvalidData(format!(
+                    "found {} road geometries for {} road edges",
+                    road_geometries.len(),
+                    n_road_edges
+                )));
+            }
+            let layer_geometries = self.edges.iter().map(|e| {
+                let (src, dst) = (self.coord(e.src_vertex_id), self.coord(e.dst_vertex_id));
+                format!("LINESTRING ({} {}, {} {})", src.0, src.1, dst.0, dst.1)
+            });
+            write_rows(
+                &directory.join(EDGE_GEOMETRY_FILE),
+                None,
+                road_geometries.into_iter().chain(layer_geometries),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// coordinates of a vertex of the road network or of a stop
+    fn coord(&self, vertex_id: usize) -> (f32, f32) {
+        match self.road.vertices.get(vertex_id) {
+            Some(v) => (v.x(), v.y()),
+            None => {
+                let c = self.stop_coords[vertex_id - self.road.n_vertices()];
+                (c.x, c.y)
+            }
+        }
+    }
+}
+
+/// writes one row per line, compressed according to the file extension
+fn write_rows<I>(path: &Path, header: Option<&str>, rows: I) -> Result<(), GtfsError>
+where
+    I: Iterator<Item = String>,
+{
+    let write_error = |e| GtfsError::WriteError(path.to_path_buf(), e);
+    let mut writer = fs_utils::create_writer(path).map_err(write_error)?;
+    if let Some(header) = header {
+        writeln!(writer, "{}", header).map_err(write_error)?;
+    }
+    for row in rows {
+        writeln!(writer, "{}", row).map_err(write_error)?;
+    }
+    writer.flush().map_err(write_error)?;
+    Ok(())
+}
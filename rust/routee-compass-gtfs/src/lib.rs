@@ -0,0 +1,3 @@
+pub mod feed;
+pub mod gtfs_error;
+pub mod layer;
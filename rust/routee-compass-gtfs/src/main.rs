@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+use clap::Parser;
+use routee_compass_core::model::road_network::graph::Graph;
+use routee_compass_gtfs::feed::gtfs_feed::GtfsFeed;
+use routee_compass_gtfs::feed::service_calendar::parse_gtfs_date;
+use routee_compass_gtfs::gtfs_error::GtfsError;
+use routee_compass_gtfs::layer::transit_layer::TransitLayer;
+use std::path::PathBuf;
+
+/// Appends a transit layer built from a GTFS feed to a RouteE Compass road network
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct ImportArgs {
+    /// edge list of the road network, usually built for walking
+    #[arg(long, value_name = "*.csv.gz")]
+    edge_file: PathBuf,
+
+    /// vertex list of the road network
+    #[arg(long, value_name = "*.csv.gz")]
+    vertex_file: PathBuf,
+
+    /// directory holding an unzipped GTFS feed
+    #[arg(long)]
+    gtfs_directory: PathBuf,
+
+    /// only keep trips running on this date, as YYYY-MM-DD or YYYYMMDD. all trips
+    /// are kept when omitted.
+    #[arg(long)]
+    service_date: Option<String>,
+
+    /// stops farther than this many meters from the road network are not linked to it
+    #[arg(long, default_value_t = 400.0)]
+    max_link_distance: f64,
+
+    /// geometries of the road edges, extended with the transit layer when provided
+    #[arg(long, value_name = "*.txt.gz")]
+    geometry_file: Option<PathBuf>,
+
+    /// directory to write the Compass input files to
+    #[arg(short, long)]
+    output_directory: PathBuf,
+}
+
+fn main() -> Result<(), GtfsError> {
+    env_logger::init();
+    let args = ImportArgs::parse();
+    let service_date = args
+        .service_date
+        .as_ref()
+        .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").or_else(|_| parse_gtfs_date(d)))
+        .transpose()?;
+
+    let road = Graph::from_files(&args.edge_file, &args.vertex_file, None, None, Some(false))?;
+    let feed = GtfsFeed::read(&args.gtfs_directory, service_date.as_ref())?;
+    let layer = TransitLayer::build(&road, &feed, args.max_link_distance)?;
+    layer.write(&args.output_directory, args.geometry_file.as_deref())?;
+    log::info!("wrote graph to {:?}", args.output_directory);
+    Ok(())
+}
@@ -65,6 +65,78 @@ pub fn pybindings(_attr: TokenStream, item: TokenStream) -> TokenStream {
                     ))
                 })
             }
+            fn graph_vertex_coordinate(&self, vertex_id: usize) -> PyResult<(f64, f64)> {
+                CompassAppBindings::graph_vertex_coordinate(self, vertex_id).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving coordinate for vertex_id {}: {}",
+                        vertex_id, e
+                    ))
+                })
+            }
+            fn graph_edge_geometry_wkt(&self, edge_id: usize) -> PyResult<String> {
+                CompassAppBindings::graph_edge_geometry_wkt(self, edge_id).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving edge geometry for edge_id {}: {}",
+                        edge_id, e
+                    ))
+                })
+            }
+            fn graph_edge_geometry_wkb(&self, py: Python<'_>, edge_id: usize) -> PyResult<PyObject> {
+                let wkb = CompassAppBindings::graph_edge_geometry_wkb(self, edge_id).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving edge geometry for edge_id {}: {}",
+                        edge_id, e
+                    ))
+                })?;
+                Ok(pyo3::types::PyBytes::new_bound(py, &wkb).into_any().unbind())
+            }
+            fn graph_edge_speed(&self, edge_id: usize, speed_unit: Option<String>) -> PyResult<f64> {
+                CompassAppBindings::graph_edge_speed(self, edge_id, speed_unit).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving edge speed for edge_id {}: {}",
+                        edge_id, e
+                    ))
+                })
+            }
+            fn graph_edge_grade(&self, edge_id: usize, grade_unit: Option<String>) -> PyResult<f64> {
+                CompassAppBindings::graph_edge_grade(self, edge_id, grade_unit).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving edge grade for edge_id {}: {}",
+                        edge_id, e
+                    ))
+                })
+            }
+            fn graph_edge_road_class(&self, edge_id: usize) -> PyResult<Option<u8>> {
+                CompassAppBindings::graph_edge_road_class(self, edge_id).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving edge road class for edge_id {}: {}",
+                        edge_id, e
+                    ))
+                })
+            }
+            #[allow(clippy::type_complexity)]
+            fn graph_edges(&self) -> PyResult<(Vec<usize>, Vec<usize>, Vec<usize>, Vec<f64>)> {
+                CompassAppBindings::graph_edges(self).map_err(|e| {
+                    PyException::new_err(format!("error retrieving graph edges: {}", e))
+                })
+            }
+            #[allow(clippy::type_complexity)]
+            fn graph_vertices(&self) -> PyResult<(Vec<usize>, Vec<f64>, Vec<f64>)> {
+                CompassAppBindings::graph_vertices(self).map_err(|e| {
+                    PyException::new_err(format!("error retrieving graph vertices: {}", e))
+                })
+            }
+            fn update_edge_speeds(
+                &self,
+                edge_ids: Vec<usize>,
+                speeds: Vec<f64>,
+                speed_unit: String,
+            ) -> PyResult<()> {
+                CompassAppBindings::update_edge_speeds(self, edge_ids, speeds, speed_unit)
+                    .map_err(|e| {
+                        PyException::new_err(format!("error updating edge speeds: {}", e))
+                    })
+            }
             #[staticmethod]
             pub fn _from_config_toml_string(
                 config_string: String,
@@ -80,13 +152,259 @@ pub fn pybindings(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 )
             }
 
+            #[staticmethod]
+            #[pyo3(signature = (config_string, working_dir=None))]
+            pub fn _from_config_json_string(
+                config_string: String,
+                working_dir: Option<String>,
+            ) -> PyResult<#name> {
+                CompassAppBindings::from_config_json_string(config_string, working_dir.unwrap_or_default())
+                    .map_err(|e| {
+                        PyException::new_err(format!(
+                            "Error while creating CompassApp from config json string: {}",
+                            e
+                        ))
+                    })
+            }
+
+            #[staticmethod]
+            #[pyo3(signature = (config, working_dir=None))]
+            pub fn _from_config_dict(
+                config: &Bound<'_, PyAny>,
+                working_dir: Option<String>,
+            ) -> PyResult<#name> {
+                let config_json = #name::py_to_json(config)?;
+                CompassAppBindings::from_config_json_string(config_json.to_string(), working_dir.unwrap_or_default())
+                    .map_err(|e| {
+                        PyException::new_err(format!(
+                            "Error while creating CompassApp from config dict: {}",
+                            e
+                        ))
+                    })
+            }
+
+            #[pyo3(signature = (queries, config=None, progress_callback=None, progress_interval=None))]
             pub fn _run_queries(
                 &self,
+                py: Python<'_>,
                 queries: Vec<String>,
                 config: Option<String>,
+                progress_callback: Option<PyObject>,
+                progress_interval: Option<usize>,
             ) -> PyResult<Vec<String>> {
-                CompassAppBindings::run_queries(self, queries, config)
-                    .map_err(|e| PyException::new_err(format!("Error while running queries: {}", e)))
+                let results = #name::run_without_gil(
+                    self,
+                    py,
+                    queries,
+                    config,
+                    progress_callback,
+                    progress_interval,
+                )?;
+                Ok(results.iter().map(|r| r.to_string()).collect())
+            }
+
+            #[pyo3(signature = (queries, config=None, progress_callback=None, progress_interval=None))]
+            pub fn _run_queries_dicts(
+                &self,
+                py: Python<'_>,
+                queries: Vec<String>,
+                config: Option<String>,
+                progress_callback: Option<PyObject>,
+                progress_interval: Option<usize>,
+            ) -> PyResult<PyObject> {
+                let results = #name::run_without_gil(
+                    self,
+                    py,
+                    queries,
+                    config,
+                    progress_callback,
+                    progress_interval,
+                )?;
+                let list = pyo3::types::PyList::empty_bound(py);
+                for result in results.iter() {
+                    list.append(#name::json_to_py(py, result)?)?;
+                }
+                Ok(list.into_any().unbind())
+            }
+
+            #[allow(clippy::too_many_arguments)]
+            pub fn _run_od_matrix(
+                &self,
+                py: Python<'_>,
+                origins_x: Vec<f64>,
+                origins_y: Vec<f64>,
+                destinations_x: Vec<f64>,
+                destinations_y: Vec<f64>,
+                features: Vec<String>,
+                query: Option<String>,
+                config: Option<String>,
+            ) -> PyResult<Vec<Vec<f64>>> {
+                py.allow_threads(|| {
+                    CompassAppBindings::run_od_matrix(
+                        self,
+                        origins_x,
+                        origins_y,
+                        destinations_x,
+                        destinations_y,
+                        features,
+                        query,
+                        config,
+                    )
+                    .map_err(|e| e.to_string())
+                })
+                .map_err(|e| PyException::new_err(format!("Error while running od matrix: {}", e)))
+            }
+
+            pub fn _finish(&self) -> PyResult<()> {
+                CompassAppBindings::finish(self)
+                    .map_err(|e| PyException::new_err(format!("Error while finishing app: {}", e)))
+            }
+        }
+
+        impl #name {
+            /// runs queries with the GIL released, so that other python threads keep running
+            /// during the search. the optional python callback is called with a dict of the
+            /// stage, completed, total and errors every `progress_interval` completed queries.
+            ///
+            /// the queries run on another thread while this thread checks for python signals,
+            /// so that a keyboard interrupt, such as from a notebook, cancels the running
+            /// searches and raises KeyboardInterrupt once they have stopped.
+            fn run_without_gil(
+                &self,
+                py: Python<'_>,
+                queries: Vec<String>,
+                config: Option<String>,
+                progress_callback: Option<PyObject>,
+                progress_interval: Option<usize>,
+            ) -> PyResult<Vec<serde_json::Value>> {
+                let callback = progress_callback.map(|callback| {
+                    routee_compass::app::compass::run_progress::ProgressCallback::new(
+                        progress_interval.unwrap_or(100),
+                        move |update| {
+                            Python::with_gil(|py| {
+                                let update = serde_json::to_value(update)
+                                    .unwrap_or(serde_json::Value::Null);
+                                let result = #name::json_to_py(py, &update)
+                                    .and_then(|u| callback.call1(py, (u,)));
+                                if let Err(e) = result {
+                                    e.print(py);
+                                }
+                            })
+                        },
+                    )
+                });
+                let cancellation =
+                    routee_compass_core::algorithm::search::cancellation_token::CancellationToken::new();
+                let (sender, receiver) = std::sync::mpsc::channel();
+                let result = std::thread::scope(|scope| {
+                    scope.spawn(|| {
+                        let result = CompassAppBindings::run_queries_cancellable(
+                            self,
+                            queries,
+                            config,
+                            callback,
+                            &cancellation,
+                        )
+                        .map_err(|e| e.to_string());
+                        let _ = sender.send(result);
+                    });
+                    // the receiver is moved into each wait, as it cannot be shared
+                    // with the code that runs without the GIL
+                    let mut receiver = receiver;
+                    loop {
+                        let (returned, received) = py.allow_threads(move || {
+                            let received =
+                                receiver.recv_timeout(std::time::Duration::from_millis(100));
+                            (receiver, received)
+                        });
+                        receiver = returned;
+                        match received {
+                            Ok(result) => return Ok(result),
+                            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                                return Ok(Err(String::from("query thread stopped without a result")))
+                            }
+                        }
+                        if let Err(interrupt) = py.check_signals() {
+                            // wait for the searches to stop, without the GIL so that
+                            // progress callbacks in flight can finish
+                            cancellation.cancel();
+                            let _ = py.allow_threads(move || receiver.recv());
+                            return Err(interrupt);
+                        }
+                    }
+                })?;
+                result.map_err(|e| PyException::new_err(format!("Error while running queries: {}", e)))
+            }
+
+            /// converts a python object of dicts, lists and scalars into the equivalent JSON
+            /// value. paths, or any object with `__fspath__`, become strings.
+            fn py_to_json(value: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+                use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple};
+                if value.is_none() {
+                    Ok(serde_json::Value::Null)
+                } else if let Ok(b) = value.downcast::<PyBool>() {
+                    Ok(serde_json::Value::Bool(b.is_true()))
+                } else if value.is_instance_of::<PyLong>() {
+                    match value.extract::<i64>() {
+                        Ok(i) => Ok(serde_json::json!(i)),
+                        Err(_) => Ok(serde_json::json!(value.extract::<u64>()?)),
+                    }
+                } else if let Ok(f) = value.downcast::<PyFloat>() {
+                    serde_json::Number::from_f64(f.value())
+                        .map(serde_json::Value::Number)
+                        .ok_or_else(|| PyException::new_err(format!("cannot convert {} to JSON", f.value())))
+                } else if let Ok(s) = value.downcast::<PyString>() {
+                    Ok(serde_json::Value::String(s.to_str()?.to_string()))
+                } else if let Ok(d) = value.downcast::<PyDict>() {
+                    let mut map = serde_json::Map::new();
+                    for (k, v) in d.iter() {
+                        map.insert(k.str()?.to_str()?.to_string(), #name::py_to_json(&v)?);
+                    }
+                    Ok(serde_json::Value::Object(map))
+                } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+                    let values = value
+                        .iter()?
+                        .map(|v| #name::py_to_json(&v?))
+                        .collect::<PyResult<Vec<_>>>()?;
+                    Ok(serde_json::Value::Array(values))
+                } else if value.hasattr("__fspath__")? {
+                    Ok(serde_json::Value::String(value.call_method0("__fspath__")?.str()?.to_str()?.to_string()))
+                } else {
+                    Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                        "cannot convert {} to JSON",
+                        value.get_type().name()?
+                    )))
+                }
+            }
+
+            /// converts a JSON value into the equivalent python object, avoiding a
+            /// round trip through a JSON string
+            fn json_to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+                match value {
+                    serde_json::Value::Null => Ok(py.None()),
+                    serde_json::Value::Bool(b) => Ok(b.into_py(py)),
+                    serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                        (Some(i), _) => Ok(i.into_py(py)),
+                        (None, Some(u)) => Ok(u.into_py(py)),
+                        _ => Ok(n.as_f64().unwrap_or(f64::NAN).into_py(py)),
+                    },
+                    serde_json::Value::String(s) => Ok(s.into_py(py)),
+                    serde_json::Value::Array(values) => {
+                        let list = pyo3::types::PyList::empty_bound(py);
+                        for v in values.iter() {
+                            list.append(#name::json_to_py(py, v)?)?;
+                        }
+                        Ok(list.into_any().unbind())
+                    }
+                    serde_json::Value::Object(map) => {
+                        let dict = pyo3::types::PyDict::new_bound(py);
+                        for (k, v) in map.iter() {
+                            dict.set_item(k, #name::json_to_py(py, v)?)?;
+                        }
+                        Ok(dict.into_any().unbind())
+                    }
+                }
             }
         }
     };
@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // use a vendored protoc so builds do not depend on a system installation
+    let protoc = protoc_bin_vendored::protoc_bin_path()?;
+    std::env::set_var("PROTOC", protoc);
+    prost_build::compile_protos(
+        &["proto/fileformat.proto", "proto/osmformat.proto"],
+        &["proto/"],
+    )?;
+    Ok(())
+}
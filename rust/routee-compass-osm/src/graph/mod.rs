@@ -0,0 +1,3 @@
+pub mod osm_graph;
+pub mod osm_graph_writer;
+pub mod osm_road;
@@ -0,0 +1,222 @@
+use super::osm_road::OsmRoad;
+use crate::osm_error::OsmError;
+use crate::pbf::{osm_element, pbf_reader};
+use crate::profile::osm_profile::OsmProfile;
+use geo::{Coord, LineString};
+use routee_compass_core::model::unit::as_f64::AsF64;
+use routee_compass_core::util::geo::haversine;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// an intersection or dead end of the road network
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OsmVertex {
+    pub node_id: i64,
+    pub coord: Coord<f32>,
+}
+
+/// a directed road segment between two vertices
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsmEdge {
+    pub way_id: i64,
+    pub src_vertex_id: usize,
+    pub dst_vertex_id: usize,
+    pub distance_meters: f64,
+    pub geometry: LineString<f32>,
+    pub road_class: u8,
+    pub speed_kph: f64,
+}
+
+/// a road network built from OpenStreetMap data, where vertex and edge ids
+/// are the positions in each list
+#[derive(Debug, Default)]
+pub struct OsmGraph {
+    pub vertices: Vec<OsmVertex>,
+    pub edges: Vec<OsmEdge>,
+}
+
+impl OsmGraph {
+    /// builds the network of a profile from a PBF file. the file is read twice,
+    /// first for the ways of the network and then for the positions of their nodes.
+    pub fn from_pbf(path: &Path, profile: &OsmProfile) -> Result<OsmGraph, OsmError> {
+        let mut roads = vec![];
+        pbf_reader::read_primitive_blocks(path, |block| {
+            let ways = osm_element::block_ways(&block)?;
+            roads.extend(
+                ways.into_iter()
+                    .filter_map(|w| OsmRoad::from_way(w, profile)),
+            );
+            Ok(())
+        })?;
+        log::info!("found {} ways for the {:?} profile", roads.len(), profile);
+
+        let road_nodes = roads
+            .iter()
+            .flat_map(|r| r.refs.iter().copied())
+            .collect::<HashSet<_>>();
+        let mut coords = HashMap::with_capacity(road_nodes.len());
+        pbf_reader::read_primitive_blocks(path, |block| {
+            for node in osm_element::block_nodes(&block) {
+                if road_nodes.contains(&node.id) {
+                    coords.insert(node.id, node.coord);
+                }
+            }
+            Ok(())
+        })?;
+        OsmGraph::from_roads(&roads, &coords)
+    }
+
+    /// splits roads into edges at every node shared by more than one road, and
+    /// at the ends of each road. nodes missing from `coords`, which happens
+    /// where an extract clips a way, are skipped.
+    pub fn from_roads(
+        roads: &[OsmRoad],
+        coords: &HashMap<i64, Coord<f32>>,
+    ) -> Result<OsmGraph, OsmError> {
+        let roads = roads
+            .iter()
+            .map(|r| {
+                let refs = r
+                    .refs
+                    .iter()
+                    .copied()
+                    .filter(|n| coords.contains_key(n))
+                    .collect::<Vec<_>>();
+                (r, refs)
+            })
+            .filter(|(_, refs)| refs.len() > 1)
+            .collect::<Vec<_>>();
+
+        // a node is a vertex if it is used more than once, counting road ends twice
+        let mut uses: HashMap<i64, usize> = HashMap::new();
+        for (_, refs) in roads.iter() {
+            for node in refs.iter() {
+                *uses.entry(*node).or_default() += 1;
+            }
+            for end in [refs[0], refs[refs.len() - 1]] {
+                *uses.entry(end).or_default() += 1;
+            }
+        }
+
+        let mut graph = OsmGraph::default();
+        let mut vertex_ids: HashMap<i64, usize> = HashMap::new();
+        for (road, refs) in roads.iter() {
+            let mut start = 0;
+            for end in 1..refs.len() {
+                if uses[&refs[end]] < 2 {
+                    continue;
+                }
+                let segment = &refs[start..=end];
+                let src = graph.vertex_id(segment[0], coords, &mut vertex_ids);
+                let dst = graph.vertex_id(segment[segment.len() - 1], coords, &mut vertex_ids);
+                let geometry = LineString::from_iter(segment.iter().map(|n| coords[n]));
+                let distance_meters = linestring_distance_meters(&geometry)?;
+                let mut add_edge = |src_vertex_id, dst_vertex_id, geometry| {
+                    graph.edges.push(OsmEdge {
+                        way_id: road.way_id,
+                        src_vertex_id,
+                        dst_vertex_id,
+                        distance_meters,
+                        geometry,
+                        road_class: road.road_class,
+                        speed_kph: road.speed_kph,
+                    })
+                };
+                if road.direction.forward() {
+                    add_edge(src, dst, geometry.clone());
+                }
+                if road.direction.reverse() {
+                    let mut reversed = geometry;
+                    reversed.0.reverse();
+                    add_edge(dst, src, reversed);
+                }
+                start = end;
+            }
+        }
+        log::info!(
+            "built graph with {} vertices and {} edges",
+            graph.vertices.len(),
+            graph.edges.len()
+        );
+        Ok(graph)
+    }
+
+    /// the id of the vertex at a node, adding the vertex on first use
+    fn vertex_id(
+        &mut self,
+        node_id: i64,
+        coords: &HashMap<i64, Coord<f32>>,
+        vertex_ids: &mut HashMap<i64, usize>,
+    ) -> usize {
+        *vertex_ids.entry(node_id).or_insert_with(|| {
+            self.vertices.push(OsmVertex {
+                node_id,
+                coord: coords[&node_id],
+            });
+            self.vertices.len() - 1
+        })
+    }
+}
+
+fn linestring_distance_meters(geometry: &LineString<f32>) -> Result<f64, OsmError> {
+    geometry
+        .lines()
+        .map(|line| {
+            haversine::coord_distance_meters(&line.start, &line.end)
+                .map(|d| d.as_f64())
+                .map_err(OsmError::InvalidData)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::way_direction::WayDirection;
+
+    fn road(way_id: i64, refs: Vec<i64>, direction: WayDirection) -> OsmRoad {
+        OsmRoad {
+            way_id,
+            refs,
+            road_class: 7,
+            speed_kph: 35.0,
+            direction,
+        }
+    }
+
+    #[test]
+    fn test_split_roads_at_intersections() {
+        // a two-way road 1-2-3-4 crossed at node 3 by a one-way road 5-3,
+        // where node 4 is outside of the extract
+        let coords = [(1, -105.000), (2, -104.999), (3, -104.998), (5, -104.998)]
+            .into_iter()
+            .map(|(id, x)| {
+                let y = if id == 5 { 39.701 } else { 39.7 };
+                (id, Coord { x, y })
+            })
+            .collect::<HashMap<_, _>>();
+        let roads = vec![
+            road(100, vec![1, 2, 3, 4], WayDirection::Both),
+            road(200, vec![5, 3], WayDirection::Forward),
+        ];
+        let graph = OsmGraph::from_roads(&roads, &coords).unwrap();
+
+        // node 2 is not shared, so the first road is a single edge in each direction
+        let node_ids = graph.vertices.iter().map(|v| v.node_id).collect::<Vec<_>>();
+        assert_eq!(node_ids, vec![1, 3, 5]);
+        let edges = graph
+            .edges
+            .iter()
+            .map(|e| {
+                (
+                    e.way_id,
+                    e.src_vertex_id,
+                    e.dst_vertex_id,
+                    e.geometry.0.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(edges, vec![(100, 0, 1, 3), (100, 1, 0, 3), (200, 2, 1, 2)]);
+        assert!((graph.edges[0].distance_meters - 171.3).abs() < 1.0);
+    }
+}
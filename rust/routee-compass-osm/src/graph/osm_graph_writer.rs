@@ -0,0 +1,94 @@
+use super::osm_graph::OsmGraph;
+use crate::osm_error::OsmError;
+use flate2::{write::GzEncoder, Compression};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub const VERTICES_FILE: &str = "vertices-compass.csv.gz";
+pub const VERTEX_UUID_FILE: &str = "vertices-uuid-enumerated.txt.gz";
+pub const EDGES_FILE: &str = "edges-compass.csv.gz";
+pub const EDGE_UUID_FILE: &str = "edges-uuid-enumerated.txt.gz";
+pub const EDGE_GEOMETRY_FILE: &str = "edges-geometries-enumerated.txt.gz";
+pub const EDGE_ROAD_CLASS_FILE: &str = "edges-road-class-enumerated.txt.gz";
+pub const EDGE_SPEED_FILE: &str = "edges-posted-speed-enumerated.txt.gz";
+
+impl OsmGraph {
+    /// writes the graph to a directory, in the same files the Python tooling
+    /// produces. speeds are in kilometers per hour and distances in meters.
+    pub fn write(&self, directory: &Path) -> Result<(), OsmError> {
+        std::fs::create_dir_all(directory)
+            .map_err(|e| OsmError::WriteError(directory.to_path_buf(), e))?;
+
+        write_rows(
+            &directory.join(VERTICES_FILE),
+            Some("vertex_id,x,y"),
+            self.vertices
+                .iter()
+                .enumerate()
+                .map(|(idx, v)| format!("{},{},{}", idx, v.coord.x, v.coord.y)),
+        )?;
+        write_rows(
+            &directory.join(VERTEX_UUID_FILE),
+            None,
+            self.vertices.iter().map(|v| v.node_id.to_string()),
+        )?;
+        write_rows(
+            &directory.join(EDGES_FILE),
+            Some("edge_id,src_vertex_id,dst_vertex_id,distance"),
+            self.edges.iter().enumerate().map(|(idx, e)| {
+                format!(
+                    "{},{},{},{}",
+                    idx, e.src_vertex_id, e.dst_vertex_id, e.distance_meters
+                )
+            }),
+        )?;
+        write_rows(
+            &directory.join(EDGE_UUID_FILE),
+            None,
+            self.edges.iter().map(|e| e.way_id.to_string()),
+        )?;
+        write_rows(
+            &directory.join(EDGE_GEOMETRY_FILE),
+            None,
+            self.edges.iter().map(|e| {
+                let coords = e
+                    .geometry
+                    .coords()
+                    .map(|c| format!("{} {}", c.x, c.y))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("LINESTRING ({})", coords)
+            }),
+        )?;
+        write_rows(
+            &directory.join(EDGE_ROAD_CLASS_FILE),
+            None,
+            self.edges.iter().map(|e| e.road_class.to_string()),
+        )?;
+        write_rows(
+            &directory.join(EDGE_SPEED_FILE),
+            None,
+            self.edges.iter().map(|e| e.speed_kph.to_string()),
+        )?;
+        Ok(())
+    }
+}
+
+/// writes one row per line to a gzipped file
+fn write_rows<I>(path: &Path, header: Option<&str>, rows: I) -> Result<(), OsmError>
+where
+    I: Iterator<Item = String>,
+{
+    let write_error = |e| OsmError::WriteError(path.to_path_buf(), e);
+    let file = File::create(path).map_err(write_error)?;
+    let mut writer = GzEncoder::new(BufWriter::new(file), Compression::default());
+    for row in header.map(String::from).into_iter().chain(rows) {
+        writeln!(writer, "{}", row).map_err(write_error)?;
+    }
+    writer
+        .finish()
+        .map_err(write_error)?
+        .flush()
+        .map_err(write_error)
+}
@@ -0,0 +1,28 @@
+use crate::pbf::osm_element::OsmWay;
+use crate::profile::{osm_profile::OsmProfile, road_class, way_direction::WayDirection};
+
+/// a way which is part of the network of a profile, with the attributes the
+/// graph needs
+#[derive(Debug, Clone)]
+pub struct OsmRoad {
+    pub way_id: i64,
+    pub refs: Vec<i64>,
+    pub road_class: u8,
+    pub speed_kph: f64,
+    pub direction: WayDirection,
+}
+
+impl OsmRoad {
+    /// the road for a way, or None if the profile does not use the way
+    pub fn from_way(way: OsmWay, profile: &OsmProfile) -> Option<OsmRoad> {
+        let direction = profile.direction(&way.tags)?;
+        let highway = way.tags.get("highway")?;
+        Some(OsmRoad {
+            way_id: way.id,
+            road_class: road_class::road_class(highway),
+            speed_kph: profile.speed_kph(&way.tags),
+            direction,
+            refs: way.refs,
+        })
+    }
+}
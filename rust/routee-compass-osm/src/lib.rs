@@ -0,0 +1,4 @@
+pub mod graph;
+pub mod osm_error;
+pub mod pbf;
+pub mod profile;
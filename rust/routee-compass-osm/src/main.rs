@@ -0,0 +1,31 @@
+use clap::Parser;
+use routee_compass_osm::graph::osm_graph::OsmGraph;
+use routee_compass_osm::osm_error::OsmError;
+use routee_compass_osm::profile::osm_profile::OsmProfile;
+use std::path::PathBuf;
+
+/// Builds a RouteE Compass road network from an OpenStreetMap PBF extract
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct ImportArgs {
+    /// the OpenStreetMap extract to read, such as one from download.geofabrik.de
+    #[arg(short, long, value_name = "*.osm.pbf")]
+    pbf_file: PathBuf,
+
+    /// the kind of traveler to build the network for
+    #[arg(long, value_enum, default_value_t = OsmProfile::Drive)]
+    profile: OsmProfile,
+
+    /// directory to write the Compass input files to
+    #[arg(short, long)]
+    output_directory: PathBuf,
+}
+
+fn main() -> Result<(), OsmError> {
+    env_logger::init();
+    let args = ImportArgs::parse();
+    let graph = OsmGraph::from_pbf(&args.pbf_file, &args.profile)?;
+    graph.write(&args.output_directory)?;
+    log::info!("wrote graph to {:?}", args.output_directory);
+    Ok(())
+}
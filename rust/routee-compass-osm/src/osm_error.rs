@@ -0,0 +1,15 @@
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub enum OsmError {
+    #[error("failure reading {0:?}: {1}")]
+    ReadError(PathBuf, std::io::Error),
+    #[error("failure writing {0:?}: {1}")]
+    WriteError(PathBuf, std::io::Error),
+    #[error("failure decoding PBF data: {0}")]
+    DecodeError(#[from] prost::DecodeError),
+    #[error("unsupported PBF feature: {0}")]
+    UnsupportedFeature(String),
+    #[error("invalid OSM data: {0}")]
+    InvalidData(String),
+}
@@ -0,0 +1,7 @@
+pub mod osm_element;
+pub mod pbf_reader;
+
+/// the PBF protobuf messages, generated from the files in `proto/`
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/osmpbf.rs"));
+}
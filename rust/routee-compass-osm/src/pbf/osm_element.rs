@@ -0,0 +1,90 @@
+use super::proto::PrimitiveBlock;
+use crate::osm_error::OsmError;
+use geo::Coord;
+use std::collections::HashMap;
+
+/// an OpenStreetMap node, with its position as x=longitude, y=latitude
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OsmNode {
+    pub id: i64,
+    pub coord: Coord<f32>,
+}
+
+/// an OpenStreetMap way, the ordered list of nodes it passes through and its tags
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsmWay {
+    pub id: i64,
+    pub refs: Vec<i64>,
+    pub tags: HashMap<String, String>,
+}
+
+/// the nodes of a block, including those stored in the dense format
+pub fn block_nodes(block: &PrimitiveBlock) -> Vec<OsmNode> {
+    let granularity = block.granularity() as f64;
+    let lat_offset = block.lat_offset() as f64;
+    let lon_offset = block.lon_offset() as f64;
+    let coord = |lat: i64, lon: i64| Coord {
+        x: (1e-9 * (lon_offset + granularity * lon as f64)) as f32,
+        y: (1e-9 * (lat_offset + granularity * lat as f64)) as f32,
+    };
+    let mut nodes = vec![];
+    for group in block.primitivegroup.iter() {
+        for node in group.nodes.iter() {
+            nodes.push(OsmNode {
+                id: node.id,
+                coord: coord(node.lat, node.lon),
+            });
+        }
+        if let Some(dense) = &group.dense {
+            // ids and positions of dense nodes are delta-encoded
+            let (mut id, mut lat, mut lon) = (0_i64, 0_i64, 0_i64);
+            for ((d_id, d_lat), d_lon) in dense.id.iter().zip(&dense.lat).zip(&dense.lon) {
+                id += d_id;
+                lat += d_lat;
+                lon += d_lon;
+                nodes.push(OsmNode {
+                    id,
+                    coord: coord(lat, lon),
+                });
+            }
+        }
+    }
+    nodes
+}
+
+/// the ways of a block, with tags resolved from the string table of the block
+pub fn block_ways(block: &PrimitiveBlock) -> Result<Vec<OsmWay>, OsmError> {
+    let strings = &block.stringtable.s;
+    let lookup = |idx: u32| -> Result<String, OsmError> {
+        let bytes = strings
+            .get(idx as usize)
+            .ok_or_else(|| OsmError::InvalidData(format!("string table has no entry {}", idx)))?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    };
+    let mut ways = vec![];
+    for group in block.primitivegroup.iter() {
+        for way in group.ways.iter() {
+            let tags = way
+                .keys
+                .iter()
+                .zip(&way.vals)
+                .map(|(k, v)| Ok((lookup(*k)?, lookup(*v)?)))
+                .collect::<Result<HashMap<_, _>, OsmError>>()?;
+            // node references are delta-encoded
+            let refs = way
+                .refs
+                .iter()
+                .scan(0_i64, |acc, delta| {
+                    *acc += delta;
+                    Some(*acc)
+                })
+                .collect();
+            ways.push(OsmWay {
+                id: way.id,
+                refs,
+                tags,
+            });
+        }
+    }
+    Ok(ways)
+}
@@ -0,0 +1,178 @@
+use super::proto::{blob, Blob, BlobHeader, HeaderBlock, PrimitiveBlock};
+use crate::osm_error::OsmError;
+use flate2::read::ZlibDecoder;
+use prost::Message;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+
+/// features a file may require which this reader understands
+const SUPPORTED_FEATURES: [&str; 2] = ["OsmSchema-V0.6", "DenseNodes"];
+
+/// reads each data block of an OpenStreetMap PBF file in order, passing it to `f`.
+///
+/// a PBF file is a sequence of blobs, each preceded by its length and a header.
+/// the first blob describes the file, the rest hold nodes, ways and relations.
+pub fn read_primitive_blocks<F>(path: &Path, mut f: F) -> Result<(), OsmError>
+where
+    F: FnMut(PrimitiveBlock) -> Result<(), OsmError>,
+{
+    let read_error = |e: std::io::Error| OsmError::ReadError(path.to_path_buf(), e);
+    let file = File::open(path).map_err(read_error)?;
+    let mut reader = BufReader::new(file);
+    while let Some(header) = read_blob_header(&mut reader).map_err(read_error)? {
+        let mut buf = vec![0; header.datasize as usize];
+        reader.read_exact(&mut buf).map_err(read_error)?;
+        let data = blob_data(Blob::decode(buf.as_slice())?)?;
+        match header.r#type.as_str() {
+            "OSMHeader" => check_features(&HeaderBlock::decode(data.as_slice())?)?,
+            "OSMData" => f(PrimitiveBlock::decode(data.as_slice())?)?,
+            // the format allows unknown blob types, which readers should skip
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// reads the next blob header, or None at the end of the file
+fn read_blob_header<R: Read>(reader: &mut R) -> Result<Option<BlobHeader>, std::io::Error> {
+    let mut len = [0_u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut buf = vec![0; u32::from_be_bytes(len) as usize];
+    reader.read_exact(&mut buf)?;
+    BlobHeader::decode(buf.as_slice())
+        .map(Some)
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// the uncompressed contents of a blob
+fn blob_data(blob: Blob) -> Result<Vec<u8>, OsmError> {
+    match blob.data {
+        Some(blob::Data::Raw(raw)) => Ok(raw),
+        Some(blob::Data::ZlibData(compressed)) => {
+            let mut data = Vec::with_capacity(blob.raw_size.unwrap_or_default() as usize);
+            ZlibDecoder::new(compressed.as_slice())
+                .read_to_end(&mut data)
+                .map_err(|e| OsmError::InvalidData(format!("invalid zlib blob: {}", e)))?;
+            Ok(data)
+        }
+        Some(_) => Err(OsmError::UnsupportedFeature(String::from(
+            "blob compression other than zlib",
+        ))),
+        None => Err(OsmError::InvalidData(String::from("blob has no data"))),
+    }
+}
+
+fn check_features(header: &HeaderBlock) -> Result<(), OsmError> {
+    match header
+        .required_features
+        .iter()
+        .find(|f| !SUPPORTED_FEATURES.contains(&f.as_str()))
+    {
+        Some(feature) => Err(OsmError::UnsupportedFeature(feature.clone())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbf::osm_element::{block_nodes, block_ways};
+    use crate::pbf::proto::{DenseNodes, PrimitiveGroup, StringTable, Way};
+    use flate2::{write::ZlibEncoder, Compression};
+    use std::io::Write;
+
+    fn write_blob(file: &mut File, blob_type: &str, blob: Blob) {
+        let data = blob.encode_to_vec();
+        let header = BlobHeader {
+            r#type: String::from(blob_type),
+            indexdata: None,
+            datasize: data.len() as i32,
+        }
+        .encode_to_vec();
+        file.write_all(&(header.len() as u32).to_be_bytes())
+            .unwrap();
+        file.write_all(&header).unwrap();
+        file.write_all(&data).unwrap();
+    }
+
+    #[test]
+    fn test_read_dense_nodes_and_ways() {
+        let block = PrimitiveBlock {
+            stringtable: StringTable {
+                s: vec![b"".to_vec(), b"highway".to_vec(), b"residential".to_vec()],
+            },
+            primitivegroup: vec![
+                PrimitiveGroup {
+                    nodes: vec![],
+                    dense: Some(DenseNodes {
+                        id: vec![10, 1],
+                        lat: vec![397_000_000, 10_000],
+                        lon: vec![-1_051_000_000, -20_000],
+                        keys_vals: vec![0, 0],
+                    }),
+                    ways: vec![],
+                },
+                PrimitiveGroup {
+                    nodes: vec![],
+                    dense: None,
+                    ways: vec![Way {
+                        id: 7,
+                        keys: vec![1],
+                        vals: vec![2],
+                        refs: vec![10, 1],
+                    }],
+                },
+            ],
+            granularity: None,
+            lat_offset: None,
+            lon_offset: None,
+        };
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(&block.encode_to_vec()).unwrap();
+        let header = HeaderBlock {
+            required_features: vec![String::from("OsmSchema-V0.6"), String::from("DenseNodes")],
+            optional_features: vec![],
+            writingprogram: None,
+            source: None,
+        };
+
+        let path = std::env::temp_dir().join("routee-compass-osm-test.osm.pbf");
+        let mut file = File::create(&path).unwrap();
+        let raw = |data: Vec<u8>| Blob {
+            raw_size: None,
+            data: Some(blob::Data::Raw(data)),
+        };
+        write_blob(&mut file, "OSMHeader", raw(header.encode_to_vec()));
+        write_blob(
+            &mut file,
+            "OSMData",
+            Blob {
+                raw_size: Some(block.encoded_len() as i32),
+                data: Some(blob::Data::ZlibData(encoder.finish().unwrap())),
+            },
+        );
+        drop(file);
+
+        let mut nodes = vec![];
+        let mut ways = vec![];
+        read_primitive_blocks(&path, |block| {
+            nodes.extend(block_nodes(&block));
+            ways.extend(block_ways(&block)?);
+            Ok(())
+        })
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(nodes.iter().map(|n| n.id).collect::<Vec<_>>(), vec![10, 11]);
+        assert!((nodes[0].coord.y - 39.7).abs() < 1e-5);
+        assert!((nodes[1].coord.x - -105.102).abs() < 1e-5);
+        assert_eq!(ways.len(), 1);
+        assert_eq!(ways[0].refs, vec![10, 11]);
+        assert_eq!(ways[0].tags.get("highway").unwrap(), "residential");
+    }
+}
@@ -0,0 +1,3 @@
+pub mod osm_profile;
+pub mod road_class;
+pub mod way_direction;
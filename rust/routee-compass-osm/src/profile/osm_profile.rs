@@ -0,0 +1,178 @@
+use super::way_direction::WayDirection;
+use std::collections::HashMap;
+
+/// the kind of traveler a graph is built for, which decides the ways that are
+/// included, the directions they may be traveled and their speeds. the filters
+/// match the network types of OSMnx, which the Python tooling is built on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OsmProfile {
+    Drive,
+    Bike,
+    Walk,
+}
+
+const DRIVE_HIGHWAYS: [&str; 14] = [
+    "motorway",
+    "motorway_link",
+    "trunk",
+    "trunk_link",
+    "primary",
+    "primary_link",
+    "secondary",
+    "secondary_link",
+    "tertiary",
+    "tertiary_link",
+    "unclassified",
+    "residential",
+    "living_street",
+    "road",
+];
+
+/// highway values which are never part of a network
+const EXCLUDED_HIGHWAYS: [&str; 9] = [
+    "abandoned",
+    "bus_guideway",
+    "construction",
+    "no",
+    "planned",
+    "platform",
+    "proposed",
+    "raceway",
+    "razed",
+];
+
+const BIKE_SPEED_KPH: f64 = 16.0;
+const WALK_SPEED_KPH: f64 = 5.0;
+
+impl OsmProfile {
+    /// the directions a way may be traveled with this profile, or None if
+    /// the way is not part of the network
+    pub fn direction(&self, tags: &HashMap<String, String>) -> Option<WayDirection> {
+        let tag = |key: &str| tags.get(key).map(String::as_str);
+        let highway = tag("highway")?;
+        if tag("area") == Some("yes")
+            || EXCLUDED_HIGHWAYS.contains(&highway)
+            || matches!(tag("access"), Some("no") | Some("private"))
+            || tag("service") == Some("private")
+        {
+            return None;
+        }
+        let implied_oneway = highway == "motorway" || tag("junction") == Some("roundabout");
+        let oneway = WayDirection::from_oneway_tag(tag("oneway"), implied_oneway);
+        match self {
+            OsmProfile::Drive => {
+                let allowed = DRIVE_HIGHWAYS.contains(&highway)
+                    && tag("motor_vehicle") != Some("no")
+                    && tag("motorcar") != Some("no");
+                allowed.then_some(oneway)
+            }
+            OsmProfile::Bike => {
+                let allowed = !highway.starts_with("motor")
+                    && !matches!(
+                        highway,
+                        "corridor" | "elevator" | "escalator" | "footway" | "steps"
+                    )
+                    && tag("bicycle") != Some("no");
+                match tag("oneway:bicycle") {
+                    Some("no") => allowed.then_some(WayDirection::Both),
+                    _ => allowed.then_some(oneway),
+                }
+            }
+            OsmProfile::Walk => {
+                let allowed = !highway.starts_with("motor")
+                    && highway != "cycleway"
+                    && tag("foot") != Some("no");
+                allowed.then_some(WayDirection::Both)
+            }
+        }
+    }
+
+    /// the travel speed of a way in kilometers per hour, from the `maxspeed`
+    /// tag when present, otherwise from a typical speed for the road class
+    pub fn speed_kph(&self, tags: &HashMap<String, String>) -> f64 {
+        let maxspeed = tags.get("maxspeed").and_then(|s| parse_maxspeed(s));
+        match self {
+            OsmProfile::Drive => maxspeed.unwrap_or_else(|| {
+                default_drive_speed_kph(tags.get("highway").map(String::as_str).unwrap_or_default())
+            }),
+            OsmProfile::Bike => maxspeed.map_or(BIKE_SPEED_KPH, |s| s.min(BIKE_SPEED_KPH)),
+            OsmProfile::Walk => WALK_SPEED_KPH,
+        }
+    }
+}
+
+/// typical speeds for roads without a `maxspeed` tag
+fn default_drive_speed_kph(highway: &str) -> f64 {
+    match highway {
+        "motorway" => 105.0,
+        "motorway_link" => 65.0,
+        "trunk" => 90.0,
+        "trunk_link" => 55.0,
+        "primary" | "primary_link" => 65.0,
+        "secondary" | "secondary_link" => 55.0,
+        "tertiary" | "tertiary_link" => 45.0,
+        "residential" => 35.0,
+        "living_street" => 15.0,
+        _ => 40.0,
+    }
+}
+
+/// parses a `maxspeed` value such as `50` or `30 mph`. multiple values, such as
+/// `50;30`, are averaged, and values such as `signals` or `none` are ignored.
+fn parse_maxspeed(value: &str) -> Option<f64> {
+    let speeds = value
+        .split(';')
+        .filter_map(|v| {
+            let v = v.trim();
+            match v.strip_suffix("mph") {
+                Some(mph) => mph.trim().parse::<f64>().ok().map(|s| s * 1.609344),
+                None => v.trim_end_matches("km/h").trim().parse::<f64>().ok(),
+            }
+        })
+        .collect::<Vec<_>>();
+    if speeds.is_empty() {
+        None
+    } else {
+        Some(speeds.iter().sum::<f64>() / speeds.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_profiles() {
+        let motorway = tags(&[("highway", "motorway"), ("maxspeed", "65 mph")]);
+        let footway = tags(&[("highway", "footway")]);
+        let oneway = tags(&[("highway", "residential"), ("oneway", "-1")]);
+
+        assert_eq!(
+            OsmProfile::Drive.direction(&motorway),
+            Some(WayDirection::Forward)
+        );
+        assert_eq!(OsmProfile::Bike.direction(&motorway), None);
+        assert_eq!(OsmProfile::Drive.direction(&footway), None);
+        assert_eq!(
+            OsmProfile::Walk.direction(&footway),
+            Some(WayDirection::Both)
+        );
+        assert_eq!(
+            OsmProfile::Drive.direction(&oneway),
+            Some(WayDirection::Reverse)
+        );
+        assert_eq!(
+            OsmProfile::Walk.direction(&oneway),
+            Some(WayDirection::Both)
+        );
+        assert!((OsmProfile::Drive.speed_kph(&motorway) - 104.607).abs() < 1e-3);
+        assert_eq!(OsmProfile::Drive.speed_kph(&oneway), 35.0);
+    }
+}
@@ -0,0 +1,26 @@
+/// the road classes written to the road class file, in the order of the
+/// highway hierarchy. link roads share the class of the road they connect to
+/// and all other highway values are written as class 11.
+pub const ROAD_CLASSES: [&str; 11] = [
+    "motorway",
+    "trunk",
+    "primary",
+    "secondary",
+    "tertiary",
+    "unclassified",
+    "residential",
+    "living_street",
+    "service",
+    "cycleway",
+    "other",
+];
+
+/// the road class of a value of the `highway` tag, starting at 1 for motorways
+pub fn road_class(highway: &str) -> u8 {
+    let base = highway.strip_suffix("_link").unwrap_or(highway);
+    let idx = ROAD_CLASSES
+        .iter()
+        .position(|c| *c == base)
+        .unwrap_or(ROAD_CLASSES.len() - 1);
+    idx as u8 + 1
+}
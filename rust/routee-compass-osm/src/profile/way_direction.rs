@@ -0,0 +1,29 @@
+/// the directions a way may be traveled, relative to the order of its nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WayDirection {
+    Forward,
+    Reverse,
+    Both,
+}
+
+impl WayDirection {
+    /// reads the `oneway` tag. `implied` is used when the tag is missing, such
+    /// as for motorways and roundabouts.
+    pub fn from_oneway_tag(oneway: Option<&str>, implied: bool) -> WayDirection {
+        match oneway {
+            Some("yes") | Some("true") | Some("1") => WayDirection::Forward,
+            Some("-1") | Some("reverse") => WayDirection::Reverse,
+            Some("no") | Some("false") | Some("0") => WayDirection::Both,
+            _ if implied => WayDirection::Forward,
+            _ => WayDirection::Both,
+        }
+    }
+
+    pub fn forward(&self) -> bool {
+        matches!(self, WayDirection::Forward | WayDirection::Both)
+    }
+
+    pub fn reverse(&self) -> bool {
+        matches!(self, WayDirection::Reverse | WayDirection::Both)
+    }
+}
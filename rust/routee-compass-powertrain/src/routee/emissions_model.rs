@@ -0,0 +1,195 @@
+use routee_compass_core::model::{
+    state::{
+        custom_feature_format::CustomFeatureFormat, state_feature::StateFeature,
+        state_model::StateModel,
+    },
+    traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
+    unit::{as_f64::AsF64, Energy, EnergyUnit, Fuel},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// grams of a pollutant emitted per unit of energy consumed, by energy source.
+/// electricity rates should reflect the emissions intensity of the local grid.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EmissionsRate {
+    pub grams_per_gallon_gasoline: Option<f64>,
+    pub grams_per_gallon_diesel: Option<f64>,
+    pub grams_per_kilowatt_hour: Option<f64>,
+}
+
+impl EmissionsRate {
+    /// grams emitted when consuming some energy of a fuel, at the rate of that fuel
+    /// whatever unit the energy is in, so that gasoline measured in megajoules emits
+    /// as gasoline. fuels without a rate do not emit this pollutant.
+    pub fn grams(&self, energy: Energy, energy_unit: &EnergyUnit, fuel: Fuel) -> f64 {
+        let (rate, rate_unit) = match fuel {
+            Fuel::Gasoline => (self.grams_per_gallon_gasoline, EnergyUnit::GallonsGasoline),
+            Fuel::Diesel => (self.grams_per_gallon_diesel, EnergyUnit::GallonsDiesel),
+            Fuel::Electricity => (self.grams_per_kilowatt_hour, EnergyUnit::KilowattHours),
+        };
+        rate.unwrap_or_default() * energy_unit.convert(&energy, &rate_unit).as_f64()
+    }
+}
+
+/// accumulates the emissions of each configured pollutant as a state feature named
+/// `emissions_<pollutant>`, in grams, from the energy consumed on each edge. these
+/// features can be weighted in the cost model to find emissions-optimal routes.
+#[derive(Clone, Debug)]
+pub struct EmissionsModel {
+    pub pollutants: Vec<(String, EmissionsRate)>,
+}
+
+impl EmissionsModel {
+    const CO2: &'static str = "co2";
+    /// EPA tailpipe CO2 per gallon, see https://www.epa.gov/greenvehicles
+    const CO2_GRAMS_PER_GALLON_GASOLINE: f64 = 8887.0;
+    const CO2_GRAMS_PER_GALLON_DIESEL: f64 = 10180.0;
+
+    /// builds an emissions model for a set of pollutants. the tailpipe CO2 rates of
+    /// gasoline and diesel are filled in for `co2` if not provided.
+    pub fn new(
+        pollutants: HashMap<String, EmissionsRate>,
+    ) -> Result<EmissionsModel, TraversalModelError> {
+        let mut pollutants = pollutants
+            .into_iter()
+            .map(|(name, rate)| {
+                let rates = [
+                    rate.grams_per_gallon_gasoline,
+                    rate.grams_per_gallon_diesel,
+                    rate.grams_per_kilowatt_hour,
+                ];
+                if rates.iter().flatten().any(|r| *r < 0.0) {
+                    return Err(TraversalModelError::BuildError(format!(
+                        "emissions rates for '{}' must be non-negative",
+                        name
+                    )));
+                }
+                if name == Self::CO2 {
+                    let co2 = EmissionsRate {
+                        grams_per_gallon_gasoline: rate
+                            .grams_per_gallon_gasoline
+                            .or(Some(Self::CO2_GRAMS_PER_GALLON_GASOLINE)),
+                        grams_per_gallon_diesel: rate
+                            .grams_per_gallon_diesel
+                            .or(Some(Self::CO2_GRAMS_PER_GALLON_DIESEL)),
+                        grams_per_kilowatt_hour: rate.grams_per_kilowatt_hour,
+                    };
+                    Ok((name, co2))
+                } else {
+                    Ok((name, rate))
+                }
+            })
+            .collect::<Result<Vec<_>, TraversalModelError>>()?;
+        pollutants.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(EmissionsModel { pollutants })
+    }
+
+    /// name of the state feature for a pollutant
+    pub fn feature_name(pollutant: &str) -> String {
+        format!("emissions_{}", pollutant)
+    }
+
+    pub fn state_features(&self) -> Vec<(String, StateFeature)> {
+        self.pollutants
+            .iter()
+            .map(|(name, _)| {
+                let feature = StateFeature::Custom {
+                    r#type: String::from("emissions"),
+                    unit: String::from("grams"),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 0.0.into(),
+                    },
+                };
+                (Self::feature_name(name), feature)
+            })
+            .collect()
+    }
+
+    /// adds the emissions from the energy consumed over an edge to the state
+    ///
+    /// # Arguments
+    ///
+    /// * `energy` - energy consumed from each fuel over the edge
+    /// * `state` - state to update
+    /// * `state_model` - state model with the emissions features
+    pub fn add_emissions(
+        &self,
+        energy: &[(Energy, EnergyUnit, Fuel)],
+        state: &mut [StateVar],
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        for (name, rate) in self.pollutants.iter() {
+            let grams: f64 = energy
+                .iter()
+                .map(|(energy, unit, fuel)| rate.grams(*energy, unit, *fuel))
+                .sum();
+            let feature_name = Self::feature_name(name);
+            let current = state_model.get_custom_f64(state, &feature_name)?;
+            state_model.set_custom_f64(state, &feature_name, &(current + grams))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_emissions() {
+        let model = EmissionsModel::new(HashMap::from([
+            (String::from("co2"), EmissionsRate::default()),
+            (
+                String::from("nox"),
+                EmissionsRate {
+                    grams_per_gallon_gasoline: Some(2.0),
+                    ..Default::default()
+                },
+            ),
+        ]))
+        .unwrap();
+        let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+        let mut state = state_model.initial_state().unwrap();
+        let energy = [
+            (
+                Energy::new(0.5),
+                EnergyUnit::GallonsGasoline,
+                Fuel::Gasoline,
+            ),
+            (
+                Energy::new(1.0),
+                EnergyUnit::KilowattHours,
+                Fuel::Electricity,
+            ),
+        ];
+        model
+            .add_emissions(&energy, &mut state, &state_model)
+            .unwrap();
+        let co2 = state_model
+            .get_custom_f64(&state, &String::from("emissions_co2"))
+            .unwrap();
+        let nox = state_model
+            .get_custom_f64(&state, &String::from("emissions_nox"))
+            .unwrap();
+        // no grid intensity was configured, so only the gasoline emits co2
+        assert!((co2 - 4443.5).abs() < 1e-9);
+        assert!((nox - 1.0).abs() < 1e-9);
+
+        // gasoline measured in megajoules emits as gasoline rather than as electricity
+        let grid = EmissionsRate {
+            grams_per_gallon_gasoline: Some(8887.0),
+            grams_per_kilowatt_hour: Some(400.0),
+            ..Default::default()
+        };
+        let megajoules_per_gallon = 32.26 * 3.6;
+        let grams = grid.grams(
+            Energy::new(megajoules_per_gallon),
+            &EnergyUnit::Megajoules,
+            Fuel::Gasoline,
+        );
+        assert!((grams - 8887.0).abs() < 1e-6);
+        let grams = grid.grams(Energy::new(3.6), &EnergyUnit::Megajoules, Fuel::Electricity);
+        assert!((grams - 400.0).abs() < 1e-6);
+    }
+}
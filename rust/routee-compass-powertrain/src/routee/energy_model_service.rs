@@ -1,5 +1,9 @@
+use super::emissions_model::EmissionsModel;
+use super::energy_model_ops::get_grade;
 use super::energy_traversal_model::EnergyTraversalModel;
 use super::vehicle::VehicleType;
+use routee_compass_core::model::road_network::edge_id::EdgeId;
+use routee_compass_core::model::traversal::default::grade_table::validate_grade_unit;
 use routee_compass_core::model::traversal::traversal_model::TraversalModel;
 use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
 use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
@@ -19,6 +23,8 @@ pub struct EnergyModelService {
     pub time_unit: TimeUnit,
     pub distance_unit: DistanceUnit,
     pub vehicle_library: HashMap<String, Arc<dyn VehicleType>>,
+    pub default_vehicle: Option<String>,
+    pub emissions: Option<Arc<EmissionsModel>>,
 }
 
 impl EnergyModelService {
@@ -28,19 +34,39 @@ impl EnergyModelService {
         time_model_speed_unit: SpeedUnit,
         grade_table_path_option: &Option<P>,
         grade_table_grade_unit: GradeUnit,
+        skip_grade_unit_check: bool,
         output_time_unit_option: Option<TimeUnit>,
         output_distance_unit_option: Option<DistanceUnit>,
         vehicle_library: HashMap<String, Arc<dyn VehicleType>>,
+        default_vehicle: Option<String>,
     ) -> Result<Self, TraversalModelError> {
+        if let Some(name) = &default_vehicle {
+            if !vehicle_library.contains_key(name) {
+                return Err(TraversalModelError::BuildError(format!(
+                    "default_vehicle '{}' not found in vehicle library, try one of: {:?}",
+                    name,
+                    vehicle_library.keys().collect::<Vec<_>>()
+                )));
+            }
+        }
+
         let output_time_unit = output_time_unit_option.unwrap_or(BASE_TIME_UNIT);
         let output_distance_unit = output_distance_unit_option.unwrap_or(BASE_DISTANCE_UNIT);
 
         let grade_table: Arc<Option<Box<[Grade]>>> = match grade_table_path_option {
-            Some(gtp) => Arc::new(Some(
-                read_utils::read_raw_file(gtp, read_decoders::default, None).map_err(|e| {
-                    TraversalModelError::FileReadError(gtp.as_ref().to_path_buf(), e.to_string())
-                })?,
-            )),
+            Some(gtp) => {
+                let grades: Box<[Grade]> =
+                    read_utils::read_raw_file(gtp, read_decoders::default, None).map_err(|e| {
+                        TraversalModelError::FileReadError(
+                            gtp.as_ref().to_path_buf(),
+                            e.to_string(),
+                        )
+                    })?;
+                if !skip_grade_unit_check {
+                    validate_grade_unit(&grades, &grade_table_grade_unit, gtp.as_ref())?;
+                }
+                Arc::new(Some(grades))
+            }
             None => Arc::new(None),
         };
 
@@ -52,8 +78,18 @@ impl EnergyModelService {
             time_unit: output_time_unit,
             distance_unit: output_distance_unit,
             vehicle_library,
+            default_vehicle,
+            emissions: None,
         })
     }
+
+    /// tracks the emissions of each pollutant in the emissions model as state features
+    pub fn with_emissions(self, emissions: Option<EmissionsModel>) -> Self {
+        EnergyModelService {
+            emissions: emissions.map(Arc::new),
+            ..self
+        }
+    }
 }
 
 impl TraversalModelService for EnergyModelService {
@@ -65,4 +101,29 @@ impl TraversalModelService for EnergyModelService {
         let model = EnergyTraversalModel::new(arc_self, parameters)?;
         Ok(Arc::new(model))
     }
+
+    /// speeds are owned by the time model, so updates are passed along to it
+    fn update_edge_speeds(
+        &self,
+        speeds: &[(EdgeId, Speed)],
+        speed_unit: &SpeedUnit,
+    ) -> Result<(), TraversalModelError> {
+        self.time_model_service
+            .update_edge_speeds(speeds, speed_unit)
+    }
+
+    fn edge_speed(&self, edge_id: EdgeId) -> Result<(Speed, SpeedUnit), TraversalModelError> {
+        self.time_model_service.edge_speed(edge_id)
+    }
+
+    /// the grade table of the energy model, or else of its time model
+    fn edge_grade(&self, edge_id: EdgeId) -> Result<(Grade, GradeUnit), TraversalModelError> {
+        match self.grade_table.as_ref() {
+            Some(_) => Ok((
+                get_grade(&self.grade_table, edge_id)?,
+                self.grade_table_grade_unit,
+            )),
+            None => self.time_model_service.edge_grade(edge_id),
+        }
+    }
 }
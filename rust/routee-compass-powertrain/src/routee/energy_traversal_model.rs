@@ -16,13 +16,23 @@ pub struct EnergyTraversalModel {
     pub energy_model_service: Arc<EnergyModelService>,
     pub time_model: Arc<dyn TraversalModel>,
     pub vehicle: Arc<dyn VehicleType>,
+    /// name, unit and fuel of each energy consumption feature of the vehicle, used to
+    /// find emissions
+    energy_features: Vec<(String, EnergyUnit, Fuel)>,
 }
 
 impl TraversalModel for EnergyTraversalModel {
+    fn is_time_dependent(&self) -> bool {
+        self.time_model.is_time_dependent()
+    }
+
     /// inject the state features required by the VehicleType
     fn state_features(&self) -> Vec<(String, StateFeature)> {
         let mut features = self.vehicle.state_features();
         features.extend(self.time_model.state_features());
+        if let Some(emissions) = &self.energy_model_service.emissions {
+            features.extend(emissions.state_features());
+        }
         features
     }
 
@@ -77,9 +87,33 @@ impl TraversalModel for EnergyTraversalModel {
             state_model,
         )?;
 
+        if let Some(emissions) = &self.energy_model_service.emissions {
+            let energy = self
+                .energy_features
+                .iter()
+                .map(|(name, unit, fuel)| {
+                    let prev_energy = state_model.get_energy(&prev, name, unit)?;
+                    let next_energy = state_model.get_energy(state, name, unit)?;
+                    Ok((next_energy - prev_energy, *unit, *fuel))
+                })
+                .collect::<Result<Vec<_>, TraversalModelError>>()?;
+            emissions.add_emissions(&energy, state, state_model)?;
+        }
+
         Ok(())
     }
 
+    /// the time model may charge for the transition between edges, such as a delay at
+    /// an intersection
+    fn access_edge(
+        &self,
+        traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVar>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        self.time_model.access_edge(traversal, state, state_model)
+    }
+
     fn estimate_traversal(
         &self,
         od: (&Vertex, &Vertex),
@@ -118,18 +152,26 @@ impl EnergyTraversalModel {
     ) -> Result<EnergyTraversalModel, TraversalModelError> {
         let time_model = energy_model_service.time_model_service.build(conf)?;
 
-        let prediction_model_name = conf
-            .get("model_name".to_string())
-            .ok_or_else(|| {
-                TraversalModelError::BuildError("No 'model_name' key provided in query".to_string())
-            })?
-            .as_str()
-            .ok_or_else(|| {
-                TraversalModelError::BuildError(
-                    "Expected 'model_name' value to be string".to_string(),
-                )
-            })?
-            .to_string();
+        // the query selects a vehicle by name, falling back to the configured default
+        let prediction_model_name = match conf.get("model_name".to_string()) {
+            Some(name) => name
+                .as_str()
+                .ok_or_else(|| {
+                    TraversalModelError::BuildError(
+                        "Expected 'model_name' value to be string".to_string(),
+                    )
+                })?
+                .to_string(),
+            None => energy_model_service
+                .default_vehicle
+                .clone()
+                .ok_or_else(|| {
+                    TraversalModelError::BuildError(
+                        "No 'model_name' key provided in query and no default_vehicle configured"
+                            .to_string(),
+                    )
+                })?,
+        };
 
         let vehicle = match energy_model_service
             .vehicle_library
@@ -147,10 +189,25 @@ impl EnergyTraversalModel {
         }?
         .update_from_query(conf)?;
 
+        // only the energy the vehicle consumes produces emissions
+        let consumption_features = vehicle.consumption_features();
+        let energy_features = vehicle
+            .state_features()
+            .into_iter()
+            .filter_map(|(name, feature)| {
+                let (_, fuel) = consumption_features.iter().find(|(n, _)| *n == name)?;
+                match feature {
+                    StateFeature::Energy { energy_unit, .. } => Some((name, energy_unit, *fuel)),
+                    _ => None,
+                }
+            })
+            .collect();
+
         Ok(EnergyTraversalModel {
             energy_model_service,
             time_model,
             vehicle,
+            energy_features,
         })
     }
 }
@@ -228,7 +285,7 @@ mod tests {
             SpeedTraversalEngine::new(&speed_file_path, SpeedUnit::KilometersPerHour, None, None)
                 .unwrap(),
         );
-        let time_service = SpeedLookupService { e: time_engine };
+        let time_service = SpeedLookupService::new(time_engine);
 
         let service = EnergyModelService::new(
             Arc::new(time_service),
@@ -237,9 +294,11 @@ mod tests {
             &Some(grade_file_path),
             // SpeedUnit::KilometersPerHour,
             GradeUnit::Millis,
+            false,
             None,
             None,
             model_library,
+            None,
         )
         .unwrap();
         let arc_service = Arc::new(service);
@@ -1,3 +1,4 @@
+pub mod emissions_model;
 pub mod energy_model_ops;
 pub mod energy_model_service;
 pub mod energy_traversal_model;
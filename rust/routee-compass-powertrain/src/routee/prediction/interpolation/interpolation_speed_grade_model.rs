@@ -67,6 +67,19 @@ impl InterpolationSpeedGradeModel {
         grade_bins: usize,
         energy_rate_unit: EnergyRateUnit,
     ) -> Result<Self, TraversalModelError> {
+        validate_grid(
+            "speed",
+            speed_bounds.0.as_f64(),
+            speed_bounds.1.as_f64(),
+            speed_bins,
+        )?;
+        validate_grid(
+            "grade",
+            grade_bounds.0.as_f64(),
+            grade_bounds.1.as_f64(),
+            grade_bins,
+        )?;
+
         // load underlying model to build the interpolation grid
         let model = load_prediction_model(
             underlying_model_name,
@@ -114,6 +127,33 @@ impl InterpolationSpeedGradeModel {
                 ))
             })?;
 
+        // the interpolation error is largest away from the grid points, so compare
+        // against the underlying model at the center of each cell to report it
+        let mut max_error: f64 = 0.0;
+        for xs in interpolator.x.windows(2) {
+            for ys in interpolator.y.windows(2) {
+                let speed_value = (xs[0].0 + xs[1].0) / 2.0;
+                let grade_value = (ys[0].0 + ys[1].0) / 2.0;
+                let (expected, _) = model.predict(
+                    (Speed::new(speed_value), speed_unit),
+                    (Grade::new(grade_value), grade_unit),
+                    (distance, distance_unit),
+                )?;
+                let interpolated = interpolator
+                    .interpolate(speed_value, grade_value)
+                    .map_err(|e| TraversalModelError::PredictionModel(e.to_string()))?;
+                max_error = max_error.max((expected.as_f64() - interpolated).abs());
+            }
+        }
+        log::info!(
+            "built {}x{} interpolation grid for model {} with max cell-center error of {} {}",
+            speed_bins,
+            grade_bins,
+            model.name,
+            max_error,
+            energy_rate_unit
+        );
+
         Ok(InterpolationSpeedGradeModel {
             interpolator,
             speed_unit,
@@ -123,6 +163,28 @@ impl InterpolationSpeedGradeModel {
     }
 }
 
+/// the grid for each dimension needs at least two points spanning a non-empty range
+fn validate_grid(
+    name: &str,
+    lower_bound: f64,
+    upper_bound: f64,
+    bins: usize,
+) -> Result<(), TraversalModelError> {
+    if bins < 2 {
+        return Err(TraversalModelError::BuildError(format!(
+            "interpolation model requires at least 2 {} bins, found {}",
+            name, bins
+        )));
+    }
+    if lower_bound >= upper_bound {
+        return Err(TraversalModelError::BuildError(format!(
+            "interpolation model {} lower bound {} must be less than upper bound {}",
+            name, lower_bound, upper_bound
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
@@ -168,4 +230,27 @@ mod test {
         assert!(energy_rate >= expected_lower);
         assert!(energy_rate <= expected_upper);
     }
+
+    #[test]
+    fn test_interpolation_invalid_grid() {
+        let model_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("routee")
+            .join("test")
+            .join("Toyota_Camry.bin");
+
+        let result = InterpolationSpeedGradeModel::new(
+            &model_path,
+            ModelType::Smartcore,
+            "Toyota Camry".to_string(),
+            SpeedUnit::MilesPerHour,
+            (Speed::new(0.0), Speed::new(100.0)),
+            1,
+            GradeUnit::Decimal,
+            (Grade::new(-0.20), Grade::new(0.20)),
+            41,
+            EnergyRateUnit::GallonsGasolinePerMile,
+        );
+        assert!(result.is_err());
+    }
 }
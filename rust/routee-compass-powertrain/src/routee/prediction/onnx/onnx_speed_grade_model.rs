@@ -1,15 +1,27 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use crate::routee::prediction::prediction_model::PredictionModel;
 
-use ort::{GraphOptimizationLevel, Session, Value};
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::Tensor;
 use routee_compass_core::{
     model::traversal::traversal_model_error::TraversalModelError,
     model::unit::{as_f64::AsF64, EnergyRate, EnergyRateUnit, Grade, GradeUnit, Speed, SpeedUnit},
 };
 
+/// energy rate prediction model backed by the ONNX runtime. the model is expected
+/// to take a single float32 input tensor of shape [1, 2] holding speed and grade
+/// and to return the energy rate as the first element of its first output.
+///
+/// running a session needs exclusive access to it, so the model keeps a pool of
+/// sessions. a prediction takes an idle session from the pool, or builds a new one
+/// when every session is in use, and returns it once done, so that predictions on
+/// different threads run concurrently and the pool grows to the number of threads
+/// predicting at once.
 pub struct OnnxSpeedGradeModel {
-    session: Session,
+    onnx_model_path: PathBuf,
+    idle_sessions: Mutex<Vec<Session>>,
     speed_unit: SpeedUnit,
     grade_unit: GradeUnit,
     energy_rate_unit: EnergyRateUnit,
@@ -24,33 +36,25 @@ impl PredictionModel for OnnxSpeedGradeModel {
         let (speed, speed_unit) = speed;
         let (grade, grade_unit) = grade;
 
-        let speed_value: f32 = speed_unit.convert(speed, self.speed_unit).as_f64() as f32;
-        let grade_value: f32 = grade_unit.convert(grade, self.grade_unit).as_f64() as f32;
-        let array = ndarray::Array1::from(vec![speed_value, grade_value])
-            .into_shape((1, 2))
-            .map_err(|e| {
+        let speed_value: f32 = speed_unit.convert(&speed, &self.speed_unit).as_f64() as f32;
+        let grade_value: f32 = grade_unit.convert(&grade, &self.grade_unit).as_f64() as f32;
+
+        let value =
+            Tensor::from_array(([1usize, 2], vec![speed_value, grade_value])).map_err(|e| {
                 TraversalModelError::PredictionModel(format!(
-                    "Failed to reshape input for prediction: {}",
+                    "Failed to create input value for prediction: {}",
                     e
                 ))
             })?;
 
-        let value = Value::from_array(array).map_err(|e| {
-            TraversalModelError::PredictionModel(format!(
-                "Failed to create input value for prediction: {}",
-                e
-            ))
-        })?;
-        let input = ort::inputs![value].map_err(|e| {
-            TraversalModelError::PredictionModel(format!(
-                "Failed to create input for prediction: {}",
-                e
-            ))
-        })?;
-
-        let results = self.session.run(input).unwrap();
-        let result = results[0].extract_tensor::<f32>().unwrap();
-        let output_f64 = result.view().to_owned().into_raw_vec()[0] as f64;
+        let mut session = match self.idle_sessions()?.pop() {
+            Some(session) => session,
+            None => build_session(&self.onnx_model_path)
+                .map_err(|e| TraversalModelError::PredictionModel(e.to_string()))?,
+        };
+        let output = run_session(&mut session, value);
+        self.idle_sessions()?.push(session);
+        let output_f64 = output? as f64;
 
         let energy_rate = EnergyRate::new(output_f64);
         Ok((energy_rate, self.energy_rate_unit))
@@ -64,22 +68,48 @@ impl OnnxSpeedGradeModel {
         grade_unit: GradeUnit,
         energy_rate_unit: EnergyRateUnit,
     ) -> Result<Self, TraversalModelError> {
-        let session = Session::builder()
-            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
-            .with_intra_threads(1)
-            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
-            .with_optimization_level(GraphOptimizationLevel::Level3)
-            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
-            .with_model_from_file(onnx_model_path)
+        // the first session is built eagerly so that an invalid model fails the build
+        let session = build_session(onnx_model_path.as_ref())
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
         Ok(OnnxSpeedGradeModel {
-            session,
+            onnx_model_path: onnx_model_path.as_ref().to_path_buf(),
+            idle_sessions: Mutex::new(vec![session]),
             speed_unit,
             grade_unit,
             energy_rate_unit,
         })
     }
+
+    fn idle_sessions(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, Vec<Session>>, TraversalModelError> {
+        self.idle_sessions.lock().map_err(|e| {
+            TraversalModelError::PredictionModel(format!("Failed to acquire onnx session: {}", e))
+        })
+    }
+}
+
+/// builds a session which runs on the calling thread, since predictions are already
+/// made in parallel by the searches
+fn build_session(onnx_model_path: &Path) -> Result<Session, ort::Error> {
+    Session::builder()?
+        .with_intra_threads(1)?
+        .with_optimization_level(GraphOptimizationLevel::Level3)?
+        .commit_from_file(onnx_model_path)
+}
+
+/// runs a session on a single input, returning the first element of its first output
+fn run_session(session: &mut Session, value: Tensor<f32>) -> Result<f32, TraversalModelError> {
+    let results = session.run(ort::inputs![value]).map_err(|e| {
+        TraversalModelError::PredictionModel(format!("Failed to run prediction: {}", e))
+    })?;
+    let (_, output) = results[0].try_extract_tensor::<f32>().map_err(|e| {
+        TraversalModelError::PredictionModel(format!("Failed to extract prediction output: {}", e))
+    })?;
+    output.first().copied().ok_or_else(|| {
+        TraversalModelError::PredictionModel(String::from("onnx model returned no output"))
+    })
 }
 
 #[cfg(test)]
@@ -0,0 +1,195 @@
+use routee_compass_core::model::{
+    traversal::traversal_model_error::TraversalModelError,
+    unit::{
+        as_f64::AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Speed, SpeedUnit, Time, TimeUnit,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// conditions outside of the vehicle, provided on the query, which add an auxiliary
+/// load for heating or air conditioning on top of the energy used to move the vehicle.
+/// the load is either given directly as `hvac_power_kw` or estimated from the
+/// `ambient_temperature_celsius` as a linear function of the difference from a
+/// comfortable cabin temperature, with coefficients configured for each vehicle.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AmbientConditions {
+    pub ambient_temperature_celsius: Option<f64>,
+    pub hvac_power_kw: Option<f64>,
+    /// cabin temperature which needs neither heating nor cooling
+    pub comfort_temperature_celsius: f64,
+    /// heating load for each degree below the comfort temperature
+    pub heating_kw_per_degree: f64,
+    /// cooling load for each degree above the comfort temperature
+    pub cooling_kw_per_degree: f64,
+}
+
+impl Default for AmbientConditions {
+    fn default() -> Self {
+        AmbientConditions {
+            ambient_temperature_celsius: None,
+            hvac_power_kw: None,
+            comfort_temperature_celsius: Self::DEFAULT_COMFORT_TEMPERATURE_CELSIUS,
+            heating_kw_per_degree: Self::DEFAULT_HEATING_KW_PER_DEGREE,
+            cooling_kw_per_degree: Self::DEFAULT_COOLING_KW_PER_DEGREE,
+        }
+    }
+}
+
+impl AmbientConditions {
+    const TEMPERATURE_KEY: &'static str = "ambient_temperature_celsius";
+    const HVAC_POWER_KEY: &'static str = "hvac_power_kw";
+    pub const DEFAULT_COMFORT_TEMPERATURE_CELSIUS: f64 = 20.0;
+    pub const DEFAULT_HEATING_KW_PER_DEGREE: f64 = 0.15;
+    pub const DEFAULT_COOLING_KW_PER_DEGREE: f64 = 0.1;
+
+    /// builds ambient conditions with no load until a query provides a temperature
+    /// or an HVAC load, using the given coefficients for the load estimate.
+    pub fn new(
+        comfort_temperature_celsius: f64,
+        heating_kw_per_degree: f64,
+        cooling_kw_per_degree: f64,
+    ) -> Result<AmbientConditions, TraversalModelError> {
+        if !comfort_temperature_celsius.is_finite() {
+            return Err(TraversalModelError::BuildError(format!(
+                "comfort temperature must be finite, found {}",
+                comfort_temperature_celsius
+            )));
+        }
+        for (name, value) in [
+            ("heating_kw_per_degree", heating_kw_per_degree),
+            ("cooling_kw_per_degree", cooling_kw_per_degree),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(TraversalModelError::BuildError(format!(
+                    "{} must be non-negative, found {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(AmbientConditions {
+            comfort_temperature_celsius,
+            heating_kw_per_degree,
+            cooling_kw_per_degree,
+            ..Default::default()
+        })
+    }
+
+    /// reads the ambient conditions from a query, falling back to the current values
+    /// for any that are not provided.
+    pub fn update_from_query(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<AmbientConditions, TraversalModelError> {
+        let get_f64 = |key: &str| -> Result<Option<f64>, TraversalModelError> {
+            match query.get(key) {
+                None => Ok(None),
+                Some(value) => value.as_f64().map(Some).ok_or_else(|| {
+                    TraversalModelError::BuildError(format!(
+                        "Expected '{}' value to be numeric",
+                        key
+                    ))
+                }),
+            }
+        };
+        let ambient_temperature_celsius =
+            get_f64(Self::TEMPERATURE_KEY)?.or(self.ambient_temperature_celsius);
+        let hvac_power_kw = get_f64(Self::HVAC_POWER_KEY)?.or(self.hvac_power_kw);
+        if let Some(power) = hvac_power_kw {
+            if power < 0.0 {
+                return Err(TraversalModelError::BuildError(format!(
+                    "Expected '{}' value to be non-negative, found {}",
+                    Self::HVAC_POWER_KEY,
+                    power
+                )));
+            }
+        }
+        Ok(AmbientConditions {
+            ambient_temperature_celsius,
+            hvac_power_kw,
+            ..*self
+        })
+    }
+
+    /// the auxiliary power draw in kilowatts. an explicit HVAC load takes precedence
+    /// over the estimate from temperature, and with neither there is no load.
+    pub fn hvac_power_kw(&self) -> f64 {
+        match (self.hvac_power_kw, self.ambient_temperature_celsius) {
+            (Some(power), _) => power,
+            (None, Some(temperature)) => {
+                let heating = (self.comfort_temperature_celsius - temperature).max(0.0);
+                let cooling = (temperature - self.comfort_temperature_celsius).max(0.0);
+                heating * self.heating_kw_per_degree + cooling * self.cooling_kw_per_degree
+            }
+            (None, None) => 0.0,
+        }
+    }
+
+    /// the energy used by the HVAC load over the time it takes to traverse a distance
+    /// at some speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - speed over the distance
+    /// * `distance` - distance traveled
+    /// * `energy_unit` - unit of the resulting energy
+    ///
+    /// # Returns
+    ///
+    /// the auxiliary energy, which is zero when there is no load or the vehicle is not moving
+    pub fn hvac_energy(
+        &self,
+        speed: (Speed, SpeedUnit),
+        distance: (Distance, DistanceUnit),
+        energy_unit: &EnergyUnit,
+    ) -> Result<Energy, TraversalModelError> {
+        let power = self.hvac_power_kw();
+        let (speed, speed_unit) = speed;
+        let (distance, distance_unit) = distance;
+        if power <= 0.0 || speed <= Speed::ZERO || distance <= Distance::ZERO {
+            return Ok(Energy::ZERO);
+        }
+        let hours = Time::create(
+            &speed,
+            &speed_unit,
+            &distance,
+            &distance_unit,
+            &TimeUnit::Hours,
+        )?;
+        let energy_kwh = Energy::new(power * hours.as_f64());
+        Ok(EnergyUnit::KilowattHours.convert(&energy_kwh, energy_unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hvac_energy_from_temperature() {
+        let query = serde_json::json!({ "ambient_temperature_celsius": 0.0 });
+        let ambient = AmbientConditions::default()
+            .update_from_query(&query)
+            .unwrap();
+        // 20 degrees of heating at 0.15 kW per degree for one hour
+        let energy = ambient
+            .hvac_energy(
+                (Speed::new(60.0), SpeedUnit::KilometersPerHour),
+                (Distance::new(60.0), DistanceUnit::Kilometers),
+                &EnergyUnit::KilowattHours,
+            )
+            .unwrap();
+        assert!((energy.as_f64() - 3.0).abs() < 1e-9);
+
+        // an explicit load takes precedence
+        let query = serde_json::json!({ "hvac_power_kw": 1.0 });
+        let ambient = ambient.update_from_query(&query).unwrap();
+        let energy = ambient
+            .hvac_energy(
+                (Speed::new(60.0), SpeedUnit::KilometersPerHour),
+                (Distance::new(30.0), DistanceUnit::Kilometers),
+                &EnergyUnit::KilowattHours,
+            )
+            .unwrap();
+        assert!((energy.as_f64() - 0.5).abs() < 1e-9);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::routee::{
     prediction::PredictionModelRecord,
-    vehicle::{vehicle_ops, vehicle_type::VehicleType},
+    vehicle::{
+        ambient_conditions::AmbientConditions, vehicle_ops, vehicle_type::VehicleType,
+        weight_adjustment::WeightAdjustment,
+    },
 };
 use routee_compass_core::model::{
     state::{
@@ -9,7 +12,7 @@ use routee_compass_core::model::{
     },
     traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
     unit::{
-        as_f64::AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Grade, GradeUnit, Speed,
+        as_f64::AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Fuel, Grade, GradeUnit, Speed,
         SpeedUnit,
     },
 };
@@ -21,25 +24,60 @@ pub struct BEV {
     pub battery_capacity: Energy,
     pub starting_battery_energy: Energy,
     pub battery_energy_unit: EnergyUnit,
+    pub recuperation_efficiency: f64,
+    pub ambient_conditions: AmbientConditions,
+    pub weight_adjustment: Option<WeightAdjustment>,
 }
 
 impl BEV {
     const ENERGY_FEATURE_NAME: &'static str = "energy_electric";
     const SOC_FEATURE_NAME: &'static str = "battery_state";
+    const REGEN_FEATURE_NAME: &'static str = "energy_regenerated";
 
+    /// creates a battery electric vehicle. the recuperation efficiency is the
+    /// fraction of the energy predicted on downhill links which is returned to
+    /// the battery by regenerative braking, in the range (0, 1].
     pub fn new(
         name: String,
         prediction_model_record: PredictionModelRecord,
         battery_capacity: Energy,
         starting_battery_energy: Energy,
         battery_energy_unit: EnergyUnit,
-    ) -> Self {
-        Self {
+        recuperation_efficiency: f64,
+    ) -> Result<Self, TraversalModelError> {
+        if !(recuperation_efficiency > 0.0 && recuperation_efficiency <= 1.0) {
+            return Err(TraversalModelError::BuildError(format!(
+                "recuperation efficiency must be in range (0, 1], found {}",
+                recuperation_efficiency
+            )));
+        }
+        Ok(Self {
             name,
             prediction_model_record: Arc::new(prediction_model_record),
             battery_capacity,
             starting_battery_energy,
             battery_energy_unit,
+            recuperation_efficiency,
+            ambient_conditions: AmbientConditions::default(),
+            weight_adjustment: None,
+        })
+    }
+
+    /// adjusts the predicted energy for a vehicle carrying a different weight than
+    /// the one its prediction model represents.
+    pub fn with_weight_adjustment(self, weight_adjustment: Option<WeightAdjustment>) -> Self {
+        Self {
+            weight_adjustment,
+            ..self
+        }
+    }
+
+    /// sets the coefficients used to estimate the HVAC load from the query's
+    /// ambient temperature.
+    pub fn with_ambient_conditions(self, ambient_conditions: AmbientConditions) -> Self {
+        Self {
+            ambient_conditions,
+            ..self
         }
     }
 }
@@ -70,9 +108,20 @@ impl VehicleType for BEV {
                     },
                 },
             ),
+            (
+                String::from(BEV::REGEN_FEATURE_NAME),
+                StateFeature::Energy {
+                    energy_unit: self.battery_energy_unit,
+                    initial: Energy::ZERO,
+                },
+            ),
         ]
     }
 
+    fn consumption_features(&self) -> Vec<(String, Fuel)> {
+        vec![(String::from(BEV::ENERGY_FEATURE_NAME), Fuel::Electricity)]
+    }
+
     fn best_case_energy(
         &self,
         distance: (Distance, DistanceUnit),
@@ -123,12 +172,35 @@ impl VehicleType for BEV {
         let (predicted_energy, energy_unit) = self
             .prediction_model_record
             .predict(speed, grade, distance)?;
-        let battery_delta = energy_unit.convert(&predicted_energy, &self.battery_energy_unit);
+        let predicted_energy = match &self.weight_adjustment {
+            Some(adjustment) => {
+                predicted_energy + adjustment.energy_delta(grade, distance, &energy_unit)
+            }
+            None => predicted_energy,
+        };
+        let predicted_delta = energy_unit.convert(&predicted_energy, &self.battery_energy_unit);
+        let start_soc = state_model.get_custom_f64(state, &BEV::SOC_FEATURE_NAME.into())?;
+        let (traction_delta, regenerated) = vehicle_ops::battery_delta_with_regen(
+            &predicted_delta,
+            self.recuperation_efficiency,
+            start_soc,
+            &self.battery_capacity,
+        );
+        let hvac_energy =
+            self.ambient_conditions
+                .hvac_energy(speed, distance, &self.battery_energy_unit)?;
+        let battery_delta = traction_delta + hvac_energy;
         state_model.add_energy(
             state,
             &BEV::ENERGY_FEATURE_NAME.into(),
-            &predicted_energy,
-            &energy_unit,
+            &battery_delta,
+            &self.battery_energy_unit,
+        )?;
+        state_model.add_energy(
+            state,
+            &BEV::REGEN_FEATURE_NAME.into(),
+            &regenerated,
+            &self.battery_energy_unit,
         )?;
         vehicle_ops::update_soc_percent(
             state,
@@ -151,7 +223,9 @@ impl VehicleType for BEV {
                     "Expected 'starting_soc_percent' value to be numeric".to_string(),
                 )
             })?,
-            None => 100.0,
+            None => {
+                vehicle_ops::as_soc_percent(&self.starting_battery_energy, &self.battery_capacity)
+            }
         };
         if !(0.0..=100.0).contains(&starting_soc_percent) {
             return Err(TraversalModelError::BuildError(
@@ -160,7 +234,7 @@ impl VehicleType for BEV {
         }
 
         let starting_battery_energy =
-            Energy::new(starting_soc_percent * self.battery_capacity.as_f64());
+            Energy::new(starting_soc_percent / 100.0 * self.battery_capacity.as_f64());
 
         let new_bev = BEV {
             name: self.name.clone(),
@@ -168,6 +242,12 @@ impl VehicleType for BEV {
             battery_capacity: self.battery_capacity,
             starting_battery_energy,
             battery_energy_unit: self.battery_energy_unit,
+            recuperation_efficiency: self.recuperation_efficiency,
+            ambient_conditions: self.ambient_conditions.update_from_query(query)?,
+            weight_adjustment: WeightAdjustment::update_optional_from_query(
+                &self.weight_adjustment,
+                query,
+            )?,
         };
 
         Ok(Arc::new(new_bev))
@@ -181,7 +261,7 @@ mod tests {
     use routee_compass_core::model::unit::{as_f64::AsF64, EnergyRate, EnergyRateUnit};
     use std::path::PathBuf;
 
-    fn mock_vehicle(starting_soc_percent: f64) -> BEV {
+    fn mock_vehicle(starting_soc_percent: f64, recuperation_efficiency: f64) -> BEV {
         let model_file_path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("src")
             .join("routee")
@@ -218,12 +298,14 @@ mod tests {
             battery_capacity,
             staring_battery_energy,
             EnergyUnit::KilowattHours,
+            recuperation_efficiency,
         )
+        .unwrap()
     }
 
     #[test]
     fn test_bev_energy_model() {
-        let vehicle = mock_vehicle(100.0);
+        let vehicle = mock_vehicle(100.0, 1.0);
         let state_model = StateModel::empty()
             .extend(vehicle.state_features())
             .unwrap();
@@ -258,7 +340,7 @@ mod tests {
 
     #[test]
     fn test_bev_energy_model_regen() {
-        let vehicle = mock_vehicle(20.0);
+        let vehicle = mock_vehicle(20.0, 1.0);
         let state_model = StateModel::empty()
             .extend(vehicle.state_features())
             .unwrap();
@@ -297,7 +379,7 @@ mod tests {
     #[test]
     fn test_bev_battery_in_bounds_upper() {
         // starting at 100% SOC, even going downhill with regen, we shouldn't be able to exceed 100%
-        let vehicle = mock_vehicle(100.0);
+        let vehicle = mock_vehicle(100.0, 1.0);
         let state_model = StateModel::empty()
             .extend(vehicle.state_features())
             .unwrap();
@@ -320,7 +402,7 @@ mod tests {
     #[test]
     fn test_bev_battery_in_bounds_lower() {
         // starting at 1% SOC, even going uphill, we shouldn't be able to go below 0%
-        let vehicle = mock_vehicle(1.0);
+        let vehicle = mock_vehicle(1.0, 1.0);
         let state_model = StateModel::empty()
             .extend(vehicle.state_features())
             .unwrap();
@@ -339,4 +421,116 @@ mod tests {
             .unwrap();
         assert!(battery_percent_soc >= 0.0);
     }
+
+    #[test]
+    fn test_bev_recuperation_efficiency() {
+        // with half of the regenerated energy recovered, the battery gains half as much
+        let distance = (Distance::new(10.0), DistanceUnit::Miles);
+        let speed = (Speed::new(55.0), SpeedUnit::MilesPerHour);
+        let grade = (Grade::new(-5.0), GradeUnit::Percent);
+        let mut regenerated = vec![];
+        for efficiency in [1.0, 0.5] {
+            let vehicle = mock_vehicle(20.0, efficiency);
+            let state_model = StateModel::empty()
+                .extend(vehicle.state_features())
+                .unwrap();
+            let mut state = state_model.initial_state().unwrap();
+            vehicle
+                .consume_energy(speed, grade, distance, &mut state, &state_model)
+                .unwrap();
+            let regen = state_model
+                .get_energy(
+                    &state,
+                    &BEV::REGEN_FEATURE_NAME.into(),
+                    &EnergyUnit::KilowattHours,
+                )
+                .unwrap();
+            let elec = state_model
+                .get_energy(
+                    &state,
+                    &BEV::ENERGY_FEATURE_NAME.into(),
+                    &EnergyUnit::KilowattHours,
+                )
+                .unwrap();
+            assert!(regen.as_f64() > 0.0, "regen {} should be > 0", regen);
+            assert_eq!(elec, -regen);
+            regenerated.push(regen.as_f64());
+        }
+        assert!((regenerated[1] - regenerated[0] * 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bev_regen_limited_by_capacity() {
+        // a full battery has no room to store regenerated energy
+        let vehicle = mock_vehicle(100.0, 1.0);
+        let state_model = StateModel::empty()
+            .extend(vehicle.state_features())
+            .unwrap();
+        let mut state = state_model.initial_state().unwrap();
+        let distance = (Distance::new(10.0), DistanceUnit::Miles);
+        let speed = (Speed::new(55.0), SpeedUnit::MilesPerHour);
+        let grade = (Grade::new(-5.0), GradeUnit::Percent);
+        vehicle
+            .consume_energy(speed, grade, distance, &mut state, &state_model)
+            .unwrap();
+        let regen = state_model
+            .get_energy(
+                &state,
+                &BEV::REGEN_FEATURE_NAME.into(),
+                &EnergyUnit::KilowattHours,
+            )
+            .unwrap();
+        assert_eq!(regen, Energy::ZERO);
+    }
+
+    #[test]
+    fn test_bev_hvac_load_from_query() {
+        let vehicle = mock_vehicle(100.0, 1.0);
+        let state_model = StateModel::empty()
+            .extend(vehicle.state_features())
+            .unwrap();
+        let cold_query = serde_json::json!({ "ambient_temperature_celsius": -10.0 });
+        let cold_vehicle = vehicle.update_from_query(&cold_query).unwrap();
+
+        let distance = (Distance::new(10.0), DistanceUnit::Miles);
+        let speed = (Speed::new(30.0), SpeedUnit::MilesPerHour);
+        let grade = (Grade::new(0.0), GradeUnit::Decimal);
+        let mut mild_state = state_model.initial_state().unwrap();
+        vehicle
+            .consume_energy(speed, grade, distance, &mut mild_state, &state_model)
+            .unwrap();
+        let mut cold_state = state_model.initial_state().unwrap();
+        cold_vehicle
+            .consume_energy(speed, grade, distance, &mut cold_state, &state_model)
+            .unwrap();
+
+        let get_energy = |state: &[StateVar]| {
+            state_model
+                .get_energy(
+                    state,
+                    &BEV::ENERGY_FEATURE_NAME.into(),
+                    &EnergyUnit::KilowattHours,
+                )
+                .unwrap()
+        };
+        // 30 degrees of heating at 0.15 kW per degree for 20 minutes
+        let hvac = get_energy(&cold_state) - get_energy(&mild_state);
+        assert!((hvac.as_f64() - 1.5).abs() < 1e-3, "hvac energy {}", hvac);
+    }
+
+    #[test]
+    fn test_bev_starting_soc_percent_from_query() {
+        let vehicle = mock_vehicle(100.0, 1.0);
+        let query = serde_json::json!({ "starting_soc_percent": 50.0 });
+        let updated = vehicle.update_from_query(&query).unwrap();
+        let state_model = StateModel::empty()
+            .extend(updated.state_features())
+            .unwrap();
+        let state = state_model.initial_state().unwrap();
+        // a percent from the query is a share of the battery capacity, not a multiple of it
+        let soc = state_model
+            .get_custom_f64(&state, &BEV::SOC_FEATURE_NAME.into())
+            .unwrap();
+        assert!((soc - 50.0).abs() < 1e-6, "starting soc {}", soc);
+    }
 }
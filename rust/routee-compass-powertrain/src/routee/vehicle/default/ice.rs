@@ -1,14 +1,18 @@
-use crate::routee::{prediction::PredictionModelRecord, vehicle::VehicleType};
+use crate::routee::{
+    prediction::PredictionModelRecord,
+    vehicle::{weight_adjustment::WeightAdjustment, VehicleType},
+};
 use routee_compass_core::model::{
     state::{state_feature::StateFeature, state_model::StateModel},
     traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
-    unit::{Distance, DistanceUnit, Energy, EnergyUnit, Grade, GradeUnit, Speed, SpeedUnit},
+    unit::{Distance, DistanceUnit, Energy, EnergyUnit, Fuel, Grade, GradeUnit, Speed, SpeedUnit},
 };
 use std::sync::Arc;
 
 pub struct ICE {
     pub name: String,
     pub prediction_model_record: Arc<PredictionModelRecord>,
+    pub weight_adjustment: Option<WeightAdjustment>,
 }
 
 impl ICE {
@@ -20,8 +24,18 @@ impl ICE {
         Ok(Self {
             name,
             prediction_model_record: Arc::new(prediction_model_record),
+            weight_adjustment: None,
         })
     }
+
+    /// adjusts the predicted energy for a vehicle carrying a different weight than
+    /// the one its prediction model represents.
+    pub fn with_weight_adjustment(self, weight_adjustment: Option<WeightAdjustment>) -> Self {
+        Self {
+            weight_adjustment,
+            ..self
+        }
+    }
 }
 
 impl VehicleType for ICE {
@@ -41,6 +55,18 @@ impl VehicleType for ICE {
             },
         )]
     }
+    /// the fuel is read from the energy unit of the vehicle, and a vehicle whose energy
+    /// is in a unit of any source, such as megajoules, burns gasoline
+    fn consumption_features(&self) -> Vec<(String, Fuel)> {
+        let fuel = self
+            .prediction_model_record
+            .energy_rate_unit
+            .associated_energy_unit()
+            .fuel()
+            .unwrap_or(Fuel::Gasoline);
+        vec![(String::from(ICE::ENERGY_FEATURE_NAME), fuel)]
+    }
+
     fn best_case_energy(
         &self,
         distance: (Distance, DistanceUnit),
@@ -82,29 +108,40 @@ impl VehicleType for ICE {
         state: &mut Vec<StateVar>,
         state_model: &StateModel,
     ) -> Result<(), TraversalModelError> {
-        let (energy, _energy_unit) = self
+        let (predicted_energy, energy_unit) = self
             .prediction_model_record
             .predict(speed, grade, distance)?;
+        let energy = match &self.weight_adjustment {
+            Some(adjustment) => {
+                let adjusted =
+                    predicted_energy + adjustment.energy_delta(grade, distance, &energy_unit);
+                if adjusted < Energy::ZERO {
+                    Energy::ZERO
+                } else {
+                    adjusted
+                }
+            }
+            None => predicted_energy,
+        };
         state_model.add_energy(
             state,
             &ICE::ENERGY_FEATURE_NAME.into(),
             &energy,
-            &self
-                .prediction_model_record
-                .energy_rate_unit
-                .associated_energy_unit(),
+            &energy_unit,
         )?;
         Ok(())
     }
 
     fn update_from_query(
         &self,
-        _query: &serde_json::Value,
+        query: &serde_json::Value,
     ) -> Result<Arc<dyn VehicleType>, TraversalModelError> {
-        // just return a clone of self
+        let weight_adjustment =
+            WeightAdjustment::update_optional_from_query(&self.weight_adjustment, query)?;
         Ok(Arc::new(ICE {
             name: self.name.clone(),
             prediction_model_record: self.prediction_model_record.clone(),
+            weight_adjustment,
         }))
     }
 }
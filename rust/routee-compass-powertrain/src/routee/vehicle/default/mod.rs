@@ -1,3 +1,4 @@
 pub mod bev;
 pub mod ice;
 pub mod phev;
+pub mod phev_policy;
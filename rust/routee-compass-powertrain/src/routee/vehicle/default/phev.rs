@@ -1,6 +1,9 @@
 use crate::routee::{
     prediction::PredictionModelRecord,
-    vehicle::{vehicle_ops, vehicle_type::VehicleType},
+    vehicle::{
+        ambient_conditions::AmbientConditions, vehicle_ops, vehicle_type::VehicleType,
+        weight_adjustment::WeightAdjustment,
+    },
 };
 use routee_compass_core::model::{
     state::{
@@ -9,12 +12,14 @@ use routee_compass_core::model::{
     },
     traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
     unit::{
-        as_f64::AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Grade, GradeUnit, Speed,
+        as_f64::AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Fuel, Grade, GradeUnit, Speed,
         SpeedUnit,
     },
 };
 use std::sync::Arc;
 
+use super::phev_policy::PhevPolicy;
+
 pub struct PHEV {
     pub name: String,
     pub charge_sustain_model: Arc<PredictionModelRecord>,
@@ -23,6 +28,10 @@ pub struct PHEV {
     pub starting_battery_energy: Energy,
     pub battery_energy_unit: EnergyUnit,
     pub custom_liquid_fuel_to_kwh: Option<f64>,
+    pub policy: PhevPolicy,
+    pub charge_sustaining_soc_percent: f64,
+    pub ambient_conditions: AmbientConditions,
+    pub weight_adjustment: Option<WeightAdjustment>,
 }
 
 impl PHEV {
@@ -30,6 +39,7 @@ impl PHEV {
     const ELECTRIC_FEATURE_NAME: &'static str = "energy_electric";
     const SOC_FEATURE_NAME: &'static str = "battery_state";
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         charge_sustain_model: PredictionModelRecord,
@@ -38,7 +48,15 @@ impl PHEV {
         starting_battery_energy: Energy,
         battery_energy_unit: EnergyUnit,
         custom_liquid_fuel_to_kwh: Option<f64>,
+        policy: PhevPolicy,
+        charge_sustaining_soc_percent: f64,
     ) -> Result<Self, TraversalModelError> {
+        if !(0.0..=100.0).contains(&charge_sustaining_soc_percent) {
+            return Err(TraversalModelError::BuildError(format!(
+                "phev charge_sustaining_soc_percent must be in range [0, 100], found {}",
+                charge_sustaining_soc_percent
+            )));
+        }
         Ok(Self {
             name,
             charge_sustain_model: Arc::new(charge_sustain_model),
@@ -47,8 +65,30 @@ impl PHEV {
             starting_battery_energy,
             battery_energy_unit,
             custom_liquid_fuel_to_kwh,
+            policy,
+            charge_sustaining_soc_percent,
+            ambient_conditions: AmbientConditions::default(),
+            weight_adjustment: None,
         })
     }
+
+    /// adjusts the predicted energy for a vehicle carrying a different weight than
+    /// the one its prediction model represents.
+    pub fn with_weight_adjustment(self, weight_adjustment: Option<WeightAdjustment>) -> Self {
+        Self {
+            weight_adjustment,
+            ..self
+        }
+    }
+
+    /// sets the coefficients used to estimate the HVAC load from the query's
+    /// ambient temperature.
+    pub fn with_ambient_conditions(self, ambient_conditions: AmbientConditions) -> Self {
+        Self {
+            ambient_conditions,
+            ..self
+        }
+    }
 }
 
 impl VehicleType for PHEV {
@@ -91,6 +131,21 @@ impl VehicleType for PHEV {
         ]
     }
 
+    /// the liquid fuel is read from the energy unit of the charge sustaining model, as
+    /// with an ICE vehicle
+    fn consumption_features(&self) -> Vec<(String, Fuel)> {
+        let liquid_fuel = self
+            .charge_sustain_model
+            .energy_rate_unit
+            .associated_energy_unit()
+            .fuel()
+            .unwrap_or(Fuel::Gasoline);
+        vec![
+            (String::from(PHEV::ELECTRIC_FEATURE_NAME), Fuel::Electricity),
+            (String::from(PHEV::LIQUID_FEATURE_NAME), liquid_fuel),
+        ]
+    }
+
     fn best_case_energy(
         &self,
         distance: (Distance, DistanceUnit),
@@ -170,26 +225,33 @@ impl VehicleType for PHEV {
         &self,
         query: &serde_json::Value,
     ) -> Result<Arc<dyn VehicleType>, TraversalModelError> {
-        let starting_soc_percent = query
-            .get("starting_soc_percent".to_string())
-            .ok_or_else(|| {
-                TraversalModelError::BuildError(
-                    "No 'starting_soc_percent' key provided in query".to_string(),
-                )
-            })?
-            .as_f64()
-            .ok_or_else(|| {
+        let starting_soc_percent = match query.get("starting_soc_percent".to_string()) {
+            Some(soc_string) => soc_string.as_f64().ok_or_else(|| {
                 TraversalModelError::BuildError(
                     "Expected 'starting_soc_percent' value to be numeric".to_string(),
                 )
-            })?;
+            })?,
+            None => {
+                vehicle_ops::as_soc_percent(&self.starting_battery_energy, &self.battery_capacity)
+            }
+        };
         if !(0.0..=100.0).contains(&starting_soc_percent) {
             return Err(TraversalModelError::BuildError(
                 "Expected 'starting_soc_percent' value to be between 0 and 100".to_string(),
             ));
         }
         let starting_battery_energy =
-            Energy::new(starting_soc_percent * self.battery_capacity.as_f64());
+            Energy::new(starting_soc_percent / 100.0 * self.battery_capacity.as_f64());
+        let policy = match query.get("phev_policy".to_string()) {
+            Some(policy_json) => serde_json::from_value::<PhevPolicy>(policy_json.clone())
+                .map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "Unable to parse 'phev_policy' value: {}",
+                        e
+                    ))
+                })?,
+            None => self.policy,
+        };
 
         let new_phev = PHEV {
             name: self.name.clone(),
@@ -199,6 +261,13 @@ impl VehicleType for PHEV {
             starting_battery_energy,
             battery_energy_unit: self.battery_energy_unit,
             custom_liquid_fuel_to_kwh: self.custom_liquid_fuel_to_kwh,
+            policy,
+            charge_sustaining_soc_percent: self.charge_sustaining_soc_percent,
+            ambient_conditions: self.ambient_conditions.update_from_query(query)?,
+            weight_adjustment: WeightAdjustment::update_optional_from_query(
+                &self.weight_adjustment,
+                query,
+            )?,
         };
 
         Ok(Arc::new(new_phev))
@@ -206,8 +275,9 @@ impl VehicleType for PHEV {
 }
 
 /// Compute the energy for the PHEV by converting liquid_fuel to kWh.
-/// This uses a simplified operation in which we assume that if the battery
-/// SOC is greater than zero we can just operate on battery to traverse a link.
+/// This uses a simplified operation in which we assume that if the vehicle's
+/// policy allows using the battery at the current SOC we can just operate on
+/// battery to traverse a link.
 /// This is not entirely realistic as it's possible to arrive at a link with
 /// 0.001% SOC and still need to use liquid_fuel to traverse the link.
 ///
@@ -232,11 +302,23 @@ fn get_phev_energy(
         .energy_rate_unit
         .associated_energy_unit();
 
-    if battery_soc_percent > 0.0 {
-        // assume we can just use the battery
-        let (electrical_energy, electrical_energy_unit) = vehicle
+    if vehicle
+        .policy
+        .use_battery(battery_soc_percent, vehicle.charge_sustaining_soc_percent)
+    {
+        // assume we can just use the battery, which also powers any HVAC load
+        let (traction_energy, electrical_energy_unit) = vehicle
             .charge_depleting_model
             .predict(speed, grade, distance)?;
+        let hvac_energy =
+            vehicle
+                .ambient_conditions
+                .hvac_energy(speed, distance, &electrical_energy_unit)?;
+        let weight_energy = vehicle
+            .weight_adjustment
+            .map(|w| w.energy_delta(grade, distance, &electrical_energy_unit))
+            .unwrap_or(Energy::ZERO);
+        let electrical_energy = traction_energy + weight_energy + hvac_energy;
         Ok((
             electrical_energy,
             electrical_energy_unit,
@@ -245,9 +327,19 @@ fn get_phev_energy(
         ))
     } else {
         // just use the liquid_fuel engine
-        let (liquid_fuel_energy, liquid_fuel_energy_unit) = vehicle
+        let (predicted_energy, liquid_fuel_energy_unit) = vehicle
             .charge_sustain_model
             .predict(speed, grade, distance)?;
+        let weight_energy = vehicle
+            .weight_adjustment
+            .map(|w| w.energy_delta(grade, distance, &liquid_fuel_energy_unit))
+            .unwrap_or(Energy::ZERO);
+        let liquid_fuel_energy = predicted_energy + weight_energy;
+        let liquid_fuel_energy = if liquid_fuel_energy < Energy::ZERO {
+            Energy::ZERO
+        } else {
+            liquid_fuel_energy
+        };
         Ok((
             Energy::new(0.0),
             electrical_energy_unit,
@@ -321,6 +413,8 @@ mod tests {
             Energy::new(12.0),
             EnergyUnit::KilowattHours,
             None,
+            PhevPolicy::ChargeDepleting,
+            0.0,
         )
         .unwrap()
     }
@@ -426,4 +520,65 @@ mod tests {
 
         assert!(liquid_energy_2 > Energy::ZERO);
     }
+
+    #[test]
+    fn test_phev_charge_sustaining_policy() {
+        let vehicle = mock_vehicle();
+        let query = serde_json::json!({ "phev_policy": "charge_sustaining" });
+        let vehicle = vehicle.update_from_query(&query).unwrap();
+        let state_model = StateModel::empty()
+            .extend(vehicle.state_features())
+            .unwrap();
+        let mut state = state_model.initial_state().unwrap();
+
+        // with a full battery, a charge sustaining policy only uses liquid fuel
+        let distance = (Distance::new(1000.0), DistanceUnit::Meters);
+        let speed = (Speed::new(60.0), SpeedUnit::MilesPerHour);
+        let grade = (Grade::new(0.0), GradeUnit::Decimal);
+
+        vehicle
+            .consume_energy(speed, grade, distance, &mut state, &state_model)
+            .unwrap();
+
+        let elec = state_model
+            .get_energy(
+                &state,
+                &PHEV::ELECTRIC_FEATURE_NAME.into(),
+                &EnergyUnit::KilowattHours,
+            )
+            .unwrap();
+        let liquid = state_model
+            .get_energy(
+                &state,
+                &PHEV::LIQUID_FEATURE_NAME.into(),
+                &EnergyUnit::GallonsGasoline,
+            )
+            .unwrap();
+        let soc = state_model
+            .get_custom_f64(&state, &PHEV::SOC_FEATURE_NAME.into())
+            .unwrap();
+        assert_eq!(elec, Energy::ZERO);
+        assert!(
+            liquid > Energy::ZERO,
+            "liquid energy {} should be > 0",
+            liquid
+        );
+        assert_eq!(soc, 100.0);
+    }
+
+    #[test]
+    fn test_phev_starting_soc_percent_from_query() {
+        let vehicle = mock_vehicle();
+        let query = serde_json::json!({ "starting_soc_percent": 50.0 });
+        let updated = vehicle.update_from_query(&query).unwrap();
+        let state_model = StateModel::empty()
+            .extend(updated.state_features())
+            .unwrap();
+        let state = state_model.initial_state().unwrap();
+        // a percent from the query is a share of the battery capacity, not a multiple of it
+        let soc = state_model
+            .get_custom_f64(&state, &PHEV::SOC_FEATURE_NAME.into())
+            .unwrap();
+        assert!((soc - 50.0).abs() < 1e-6, "starting soc {}", soc);
+    }
 }
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// the strategy a plug-in hybrid uses to choose between its battery and its
+/// liquid fuel engine on each edge.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PhevPolicy {
+    /// drive on the battery until the state of charge drops to the charge
+    /// sustaining threshold, then switch to the liquid fuel engine
+    #[default]
+    ChargeDepleting,
+    /// hold the battery state of charge and drive on the liquid fuel engine
+    ChargeSustaining,
+}
+
+impl PhevPolicy {
+    /// true if the vehicle should draw from its battery at the given state of charge
+    ///
+    /// # Arguments
+    ///
+    /// * `soc_percent` - current battery state of charge
+    /// * `charge_sustaining_soc_percent` - state of charge below which the battery is not used
+    pub fn use_battery(&self, soc_percent: f64, charge_sustaining_soc_percent: f64) -> bool {
+        match self {
+            PhevPolicy::ChargeDepleting => soc_percent > charge_sustaining_soc_percent,
+            PhevPolicy::ChargeSustaining => false,
+        }
+    }
+}
+
+impl std::fmt::Display for PhevPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", s)
+    }
+}
@@ -1,7 +1,9 @@
+pub mod ambient_conditions;
 pub mod default;
 pub mod vehicle_energy_result;
 pub mod vehicle_ops;
 pub mod vehicle_type;
+pub mod weight_adjustment;
 
 pub use vehicle_energy_result::VehicleEnergyResult;
 pub use vehicle_type::{VehicleState, VehicleType};
@@ -45,7 +45,7 @@ pub fn update_soc_percent(
 /// the remaining battery as a percentage [0, 100] %
 pub fn as_soc_percent(remaining_battery: &Energy, max_battery: &Energy) -> f64 {
     let percent_remaining = (remaining_battery.as_f64() / max_battery.as_f64()) * 100.0;
-    percent_remaining.max(0.0).min(100.0)
+    percent_remaining.clamp(0.0, 100.0)
 }
 
 /// a capacitated vehicle's state of charge (SOC) is the inverse of the
@@ -70,5 +70,36 @@ pub fn soc_from_battery_and_delta(
 ) -> f64 {
     let current_energy = *start_battery - *energy_used;
     let percent_remaining = (current_energy.as_f64() / max_battery.as_f64()) * 100.0;
-    percent_remaining.max(0.0).min(100.0)
+    percent_remaining.clamp(0.0, 100.0)
+}
+
+/// computes the change in battery energy over a link, crediting regenerative
+/// braking whenever the predicted energy is negative, whether from descending a
+/// grade or from slowing on a flat link. only the recuperation efficiency fraction
+/// of the predicted energy is recovered, and the battery cannot be charged beyond
+/// its capacity.
+///
+/// # Arguments
+///
+/// * `predicted`  - predicted energy used on the link, in the battery energy unit
+/// * `efficiency` - fraction of regenerated energy recovered by the battery
+/// * `start_soc`  - state of charge at the start of the link, as a percent
+/// * `max_battery` - maximum energy storage capacity
+///
+/// # Returns
+///
+/// the energy removed from the battery (negative when charging) and the
+/// energy regenerated on this link
+pub fn battery_delta_with_regen(
+    predicted: &Energy,
+    efficiency: f64,
+    start_soc: f64,
+    max_battery: &Energy,
+) -> (Energy, Energy) {
+    if *predicted >= Energy::ZERO {
+        return (*predicted, Energy::ZERO);
+    }
+    let headroom = max_battery.as_f64() * (100.0 - start_soc).max(0.0) / 100.0;
+    let regenerated = (-predicted.as_f64() * efficiency).min(headroom);
+    (Energy::new(-regenerated), Energy::new(regenerated))
 }
@@ -1,7 +1,7 @@
 use routee_compass_core::model::{
     state::{state_feature::StateFeature, state_model::StateModel},
     traversal::{state::state_variable::StateVar, traversal_model_error::TraversalModelError},
-    unit::{Distance, DistanceUnit, Energy, EnergyUnit, Grade, GradeUnit, Speed, SpeedUnit},
+    unit::{Distance, DistanceUnit, Energy, EnergyUnit, Fuel, Grade, GradeUnit, Speed, SpeedUnit},
 };
 use std::sync::Arc;
 
@@ -16,6 +16,12 @@ pub trait VehicleType: Send + Sync {
     /// appended to the base state model set at configuration time.
     fn state_features(&self) -> Vec<(String, StateFeature)>;
 
+    /// names of the energy state features which record the energy this vehicle
+    /// consumes, such as fuel or electricity drawn from the battery, with the fuel of
+    /// each. other energy features, such as energy regenerated while braking, are not
+    /// consumption.
+    fn consumption_features(&self) -> Vec<(String, Fuel)>;
+
     /// Return the energy required to travel a certain distance at a certain speed and grade.
     ///
     /// Arguments:
@@ -0,0 +1,205 @@
+use routee_compass_core::model::{
+    traversal::traversal_model_error::TraversalModelError,
+    unit::{
+        as_f64::AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Grade, GradeUnit, Weight,
+        WeightUnit,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// adjusts the energy predicted for a vehicle when it carries a different load than
+/// the vehicle its prediction model was trained on. the extra (or reduced) mass changes
+/// the work done against rolling resistance and gravity, which is divided by the
+/// drivetrain efficiency to find the change in energy drawn from the fuel or battery.
+/// aerodynamic losses do not depend on mass and are left unchanged.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct WeightAdjustment {
+    /// weight of the vehicle represented by the prediction model
+    pub reference_weight: Weight,
+    /// weight of the vehicle being routed, including its payload
+    pub vehicle_weight: Weight,
+    pub weight_unit: WeightUnit,
+    /// fraction of the energy drawn which reaches the wheels. if not provided, a
+    /// default is chosen based on the energy unit of the prediction.
+    pub drivetrain_efficiency: Option<f64>,
+    /// coefficient of rolling resistance between the tires and the road
+    pub rolling_resistance: f64,
+}
+
+impl WeightAdjustment {
+    pub const QUERY_WEIGHT_KEY: &'static str = "vehicle_weight_kg";
+    /// rolling resistance coefficient of a truck tire on asphalt
+    pub const DEFAULT_ROLLING_RESISTANCE: f64 = 0.01;
+    pub const DEFAULT_ELECTRIC_DRIVETRAIN_EFFICIENCY: f64 = 0.85;
+    pub const DEFAULT_LIQUID_DRIVETRAIN_EFFICIENCY: f64 = 0.25;
+    const GRAVITY: f64 = 9.81;
+    const JOULES_PER_KWH: f64 = 3_600_000.0;
+
+    pub fn new(
+        reference_weight: Weight,
+        vehicle_weight: Weight,
+        weight_unit: WeightUnit,
+        drivetrain_efficiency: Option<f64>,
+        rolling_resistance: f64,
+    ) -> Result<Self, TraversalModelError> {
+        if reference_weight <= Weight::ZERO || vehicle_weight <= Weight::ZERO {
+            return Err(TraversalModelError::BuildError(format!(
+                "vehicle weights must be positive, found reference weight {} and vehicle weight {}",
+                reference_weight, vehicle_weight
+            )));
+        }
+        if let Some(efficiency) = drivetrain_efficiency {
+            if efficiency <= 0.0 || efficiency > 1.0 {
+                return Err(TraversalModelError::BuildError(format!(
+                    "drivetrain efficiency must be in range (0, 1], found {}",
+                    efficiency
+                )));
+            }
+        }
+        if !(0.0..1.0).contains(&rolling_resistance) {
+            return Err(TraversalModelError::BuildError(format!(
+                "rolling resistance must be in range [0, 1), found {}",
+                rolling_resistance
+            )));
+        }
+        Ok(WeightAdjustment {
+            reference_weight,
+            vehicle_weight,
+            weight_unit,
+            drivetrain_efficiency,
+            rolling_resistance,
+        })
+    }
+
+    /// updates an optional weight adjustment from a query. a query which sets
+    /// `vehicle_weight_kg` for a vehicle without a reference weight is an error,
+    /// since there is no prediction weight to adjust from.
+    pub fn update_optional_from_query(
+        adjustment: &Option<WeightAdjustment>,
+        query: &serde_json::Value,
+    ) -> Result<Option<WeightAdjustment>, TraversalModelError> {
+        match adjustment {
+            Some(adjustment) => Ok(Some(adjustment.update_from_query(query)?)),
+            None if query.get(Self::QUERY_WEIGHT_KEY).is_some() => {
+                Err(TraversalModelError::BuildError(format!(
+                    "query has '{}' but the vehicle has no reference_weight to adjust energy from",
+                    Self::QUERY_WEIGHT_KEY
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// replaces the vehicle weight with a `vehicle_weight_kg` from the query, if provided.
+    pub fn update_from_query(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<WeightAdjustment, TraversalModelError> {
+        match query.get(Self::QUERY_WEIGHT_KEY) {
+            None => Ok(*self),
+            Some(value) => {
+                let weight_kg = value.as_f64().ok_or_else(|| {
+                    TraversalModelError::BuildError(format!(
+                        "Expected '{}' value to be numeric",
+                        Self::QUERY_WEIGHT_KEY
+                    ))
+                })?;
+                let vehicle_weight =
+                    WeightUnit::Kg.convert(&Weight::new(weight_kg), &self.weight_unit);
+                WeightAdjustment::new(
+                    self.reference_weight,
+                    vehicle_weight,
+                    self.weight_unit,
+                    self.drivetrain_efficiency,
+                    self.rolling_resistance,
+                )
+            }
+        }
+    }
+
+    /// the change in energy to traverse a distance at some grade due to the difference
+    /// between the vehicle and reference weights. only flat and uphill travel is adjusted,
+    /// since on a steep enough descent the extra weight is carried by gravity.
+    ///
+    /// # Arguments
+    ///
+    /// * `grade` - road grade
+    /// * `distance` - distance traveled
+    /// * `energy_unit` - unit of the resulting energy
+    ///
+    /// # Returns
+    ///
+    /// the energy to add to the prediction, which is negative for a lighter vehicle
+    pub fn energy_delta(
+        &self,
+        grade: (Grade, GradeUnit),
+        distance: (Distance, DistanceUnit),
+        energy_unit: &EnergyUnit,
+    ) -> Energy {
+        let (grade, grade_unit) = grade;
+        let (distance, distance_unit) = distance;
+        let grade_decimal = grade_unit.convert(&grade, &GradeUnit::Decimal).as_f64();
+        let resistance = self.rolling_resistance + grade_decimal;
+        if resistance <= 0.0 {
+            return Energy::ZERO;
+        }
+        let delta_kg = self
+            .weight_unit
+            .convert(
+                &(self.vehicle_weight - self.reference_weight),
+                &WeightUnit::Kg,
+            )
+            .as_f64();
+        let meters = distance_unit
+            .convert(&distance, &DistanceUnit::Meters)
+            .as_f64();
+        let efficiency = self.drivetrain_efficiency.unwrap_or(match energy_unit {
+            EnergyUnit::KilowattHours => Self::DEFAULT_ELECTRIC_DRIVETRAIN_EFFICIENCY,
+            _ => Self::DEFAULT_LIQUID_DRIVETRAIN_EFFICIENCY,
+        });
+        let joules = delta_kg * Self::GRAVITY * resistance * meters / efficiency;
+        let kwh = Energy::new(joules / Self::JOULES_PER_KWH);
+        EnergyUnit::KilowattHours.convert(&kwh, energy_unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energy_delta_from_query_weight() {
+        let adjustment = WeightAdjustment::new(
+            Weight::new(10000.0),
+            Weight::new(10000.0),
+            WeightUnit::Kg,
+            Some(1.0),
+            WeightAdjustment::DEFAULT_ROLLING_RESISTANCE,
+        )
+        .unwrap();
+        let grade = (Grade::new(0.0), GradeUnit::Decimal);
+        let distance = (Distance::new(1.0), DistanceUnit::Kilometers);
+        let no_change = adjustment.energy_delta(grade, distance, &EnergyUnit::KilowattHours);
+        assert_eq!(no_change, Energy::ZERO);
+
+        // an extra 3600kg on flat ground over 1km is 3600 * 9.81 * 0.01 * 1000 J
+        let query = serde_json::json!({ "vehicle_weight_kg": 13600.0 });
+        let loaded = adjustment.update_from_query(&query).unwrap();
+        let delta = loaded.energy_delta(grade, distance, &EnergyUnit::KilowattHours);
+        assert!((delta.as_f64() - 0.0981).abs() < 1e-9);
+
+        // steep descents are not adjusted
+        let downhill = (Grade::new(-0.05), GradeUnit::Decimal);
+        let delta = loaded.energy_delta(downhill, distance, &EnergyUnit::KilowattHours);
+        assert_eq!(delta, Energy::ZERO);
+    }
+
+    #[test]
+    fn test_query_weight_requires_reference_weight() {
+        let query = serde_json::json!({ "vehicle_weight_kg": 13600.0 });
+        assert!(WeightAdjustment::update_optional_from_query(&None, &query).is_err());
+        let empty = serde_json::json!({});
+        let result = WeightAdjustment::update_optional_from_query(&None, &empty).unwrap();
+        assert_eq!(result, None);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::wasm_app::WasmApp;
+use routee_compass_core::model::unit::SpeedUnit;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+/// JavaScript handle to a [`WasmApp`]. queries and results are passed as JSON strings.
+///
+/// ```js
+/// import init, { CompassApp } from "routee-compass-wasm";
+/// await init();
+/// const app = new CompassApp(edgesCsv, verticesCsv, speedsTxt, "kilometers_per_hour");
+/// const result = JSON.parse(app.run(JSON.stringify({ origin_vertex: 0, destination_vertex: 2 })));
+/// ```
+#[wasm_bindgen(js_name = CompassApp)]
+pub struct CompassAppBindings {
+    app: WasmApp,
+}
+
+#[wasm_bindgen(js_class = CompassApp)]
+impl CompassAppBindings {
+    /// loads a network from the text of its edge and vertex CSV files and, optionally,
+    /// a speed table with one speed per line in `speed_unit`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        edges_csv: &str,
+        vertices_csv: &str,
+        speeds: Option<String>,
+        speed_unit: Option<String>,
+    ) -> Result<CompassAppBindings, JsError> {
+        let speed_unit = match speed_unit {
+            Some(unit) => SpeedUnit::from_str(&unit)
+                .map_err(|e| JsError::new(&format!("invalid speed unit {}: {}", unit, e)))?,
+            None => SpeedUnit::KilometersPerHour,
+        };
+        let speeds = speeds.as_deref().map(|s| (s, speed_unit));
+        let app = WasmApp::from_csv(edges_csv, vertices_csv, speeds)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(CompassAppBindings { app })
+    }
+
+    /// runs a single query, see [`WasmApp::run`], returning the result as a JSON string
+    pub fn run(&self, query: &str) -> Result<String, JsError> {
+        let query = serde_json::from_str(query)
+            .map_err(|e| JsError::new(&format!("query is not valid JSON: {}", e)))?;
+        let result = self
+            .app
+            .run(&query)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(result.to_string())
+    }
+
+    /// id of the vertex nearest to a coordinate, if the network has any vertices
+    #[wasm_bindgen(js_name = nearestVertex)]
+    pub fn nearest_vertex(&self, x: f32, y: f32) -> Option<usize> {
+        self.app.nearest_vertex(x, y).map(|v| v.0)
+    }
+}
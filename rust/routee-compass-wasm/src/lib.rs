@@ -0,0 +1,3 @@
+pub mod bindings;
+pub mod wasm_app;
+pub mod wasm_app_error;
@@ -0,0 +1,316 @@
+use crate::wasm_app_error::WasmAppError;
+use routee_compass_core::{
+    algorithm::search::{
+        cancellation_token::CancellationToken, direction::Direction,
+        search_algorithm::SearchAlgorithm, search_instance::SearchInstance,
+    },
+    model::{
+        access::default::no_access_model::NoAccessModel,
+        cost::{
+            cost_aggregation::CostAggregation, cost_model::CostModel,
+            vehicle::vehicle_cost_rate::VehicleCostRate,
+        },
+        frontier::default::no_restriction::NoRestriction,
+        property::{edge::Edge, vertex::Vertex},
+        road_network::{graph::Graph, vertex_id::VertexId},
+        state::{state_feature::StateFeature, state_model::StateModel},
+        termination::termination_model::TerminationModel,
+        traversal::{
+            default::{
+                distance_traversal_model::DistanceTraversalModel,
+                speed_traversal_engine::{get_max_speed, SpeedTraversalEngine},
+                speed_traversal_model::SpeedTraversalModel,
+            },
+            traversal_model::TraversalModel,
+        },
+        unit::{as_f64::AsF64, Distance, DistanceUnit, Speed, SpeedUnit, TimeUnit},
+    },
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// a small road network held in memory with the distance or speed traversal model,
+/// for running single queries where the file-based `CompassApp` is not available,
+/// such as in a web browser.
+pub struct WasmApp {
+    graph: Arc<Graph>,
+    traversal_model: Arc<dyn TraversalModel>,
+    state_model: Arc<StateModel>,
+    default_feature: String,
+}
+
+impl WasmApp {
+    /// limits each query when the query does not provide its own `termination`,
+    /// so that a query on a disconnected network cannot freeze the page.
+    const DEFAULT_RUNTIME_LIMIT: Duration = Duration::from_secs(5);
+
+    /// builds an app from the contents of the edge and vertex CSV files of a Compass
+    /// dataset. when a speed table is provided, with one speed per line for each edge,
+    /// routes are timed with the speed traversal model. otherwise, only distance is
+    /// tracked.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges_csv` - edge list with `edge_id`, `src_vertex_id`, `dst_vertex_id` and `distance` in meters
+    /// * `vertices_csv` - vertex list with `vertex_id`, `x` and `y` columns
+    /// * `speeds` - the speed table contents and its speed unit, if any
+    pub fn from_csv(
+        edges_csv: &str,
+        vertices_csv: &str,
+        speeds: Option<(&str, SpeedUnit)>,
+    ) -> Result<WasmApp, WasmAppError> {
+        let edges: Vec<Edge> = read_csv_text(edges_csv, "edges")?;
+        let vertices: Vec<Vertex> = read_csv_text(vertices_csv, "vertices")?;
+        let graph =
+            Graph::from_edges_and_vertices(edges.into_boxed_slice(), vertices.into_boxed_slice())?;
+
+        let (traversal_model, default_feature): (Arc<dyn TraversalModel>, &str) = match speeds {
+            None => (
+                Arc::new(DistanceTraversalModel::new(DistanceUnit::Kilometers)),
+                "distance",
+            ),
+            Some((speed_table, speed_unit)) => {
+                let speed_table = read_speed_table(speed_table, graph.n_edges())?;
+                let max_speed = get_max_speed(&speed_table)
+                    .map_err(|e| WasmAppError::ReadError(String::from("speeds"), e.to_string()))?;
+                let engine = SpeedTraversalEngine {
+                    speed_table,
+                    speed_unit,
+                    time_unit: TimeUnit::Minutes,
+                    distance_unit: DistanceUnit::Kilometers,
+                    max_speed,
+                    grade_table: None,
+                    grade_speed_factor: 0.0,
+                };
+                (Arc::new(SpeedTraversalModel::new(Arc::new(engine))), "time")
+            }
+        };
+        // the distance traversal model expects its feature to be configured by the app
+        let mut state_features = traversal_model.state_features();
+        if state_features.is_empty() {
+            state_features.push((
+                String::from("distance"),
+                StateFeature::Distance {
+                    distance_unit: DistanceUnit::Kilometers,
+                    initial: Distance::ZERO,
+                },
+            ));
+        }
+        let state_model = StateModel::empty()
+            .extend(state_features)
+            .map_err(|e| WasmAppError::InvalidInput(e.to_string()))?;
+
+        Ok(WasmApp {
+            graph: Arc::new(graph),
+            traversal_model,
+            state_model: Arc::new(state_model),
+            default_feature: String::from(default_feature),
+        })
+    }
+
+    /// runs a single query between two vertices, found either by `origin_vertex` and
+    /// `destination_vertex` ids or as the vertices nearest to `origin_x`, `origin_y`
+    /// and `destination_x`, `destination_y`. the query may also provide cost `weights`
+    /// for the `distance` and `time` features and a `termination` model.
+    ///
+    /// # Returns
+    ///
+    /// the route as a list of edge ids along with its vertex coordinates, cost and the
+    /// summary of the state at the destination.
+    pub fn run(&self, query: &Value) -> Result<Value, WasmAppError> {
+        let origin = self.query_vertex(query, "origin")?;
+        let destination = self.query_vertex(query, "destination")?;
+
+        let weights: HashMap<String, f64> = query_field(query, "weights")?
+            .unwrap_or_else(|| HashMap::from([(self.default_feature.clone(), 1.0)]));
+        let rates = self
+            .state_model
+            .iter()
+            .map(|(name, _)| (name.clone(), VehicleCostRate::Raw))
+            .collect::<HashMap<_, _>>();
+        let cost_model = CostModel::new(
+            Arc::new(weights),
+            Arc::new(rates),
+            Arc::new(HashMap::new()),
+            CostAggregation::Sum,
+            self.state_model.clone(),
+        )?;
+        let termination_model =
+            query_field(query, "termination")?.unwrap_or(TerminationModel::QueryRuntimeLimit {
+                limit: Self::DEFAULT_RUNTIME_LIMIT,
+                frequency: 100,
+            });
+
+        let si = SearchInstance {
+            directed_graph: self.graph.clone(),
+            state_model: self.state_model.clone(),
+            traversal_model: self.traversal_model.clone(),
+            access_model: Arc::new(NoAccessModel {}),
+            cost_model,
+            frontier_model: Arc::new(NoRestriction {}),
+            termination_model: Arc::new(termination_model),
+            heuristic: None,
+            cancellation: CancellationToken::default(),
+        };
+        let result = SearchAlgorithm::AStarAlgorithm {
+            weight_factor: None,
+            edge_based: false,
+        }
+        .run_vertex_oriented(origin, Some(destination), &Direction::Forward, &si)?;
+
+        let route = result.routes.into_iter().next().unwrap_or_default();
+        let final_state = match route.last() {
+            Some(last) => last.result_state.clone(),
+            None if origin == destination => si.state_model.initial_state().map_err(|e| {
+                WasmAppError::InvalidInput(format!("failure building initial state: {}", e))
+            })?,
+            None => {
+                return Err(WasmAppError::InvalidInput(format!(
+                    "no route found from vertex {} to vertex {}",
+                    origin, destination
+                )))
+            }
+        };
+        let mut coordinates = vec![self.coordinate(origin)?];
+        for traversal in route.iter() {
+            let dst = self.graph.dst_vertex_id(traversal.edge_id)?;
+            coordinates.push(self.coordinate(dst)?);
+        }
+        let cost: f64 = route.iter().map(|t| t.total_cost().as_f64()).sum();
+        let path = route.iter().map(|t| t.edge_id.0).collect::<Vec<_>>();
+
+        Ok(json!({
+            "origin_vertex": origin.0,
+            "destination_vertex": destination.0,
+            "iterations": result.iterations,
+            "route": {
+                "path": path,
+                "coordinates": coordinates,
+                "cost": cost,
+                "traversal_summary": si.state_model.serialize_state(&final_state),
+            }
+        }))
+    }
+
+    /// finds the vertex nearest to a coordinate by checking every vertex, which is
+    /// fast enough for the small networks this app is meant for.
+    pub fn nearest_vertex(&self, x: f32, y: f32) -> Option<VertexId> {
+        self.graph
+            .vertices
+            .iter()
+            .map(|v| (v.vertex_id, (v.x() - x).powi(2) + (v.y() - y).powi(2)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(vertex_id, _)| vertex_id)
+    }
+
+    fn query_vertex(&self, query: &Value, prefix: &str) -> Result<VertexId, WasmAppError> {
+        if let Some(vertex_id) = query_field::<usize>(query, &format!("{}_vertex", prefix))? {
+            return self
+                .graph
+                .get_vertex(VertexId(vertex_id))
+                .map(|v| v.vertex_id)
+                .map_err(WasmAppError::from);
+        }
+        let x = query_field::<f32>(query, &format!("{}_x", prefix))?;
+        let y = query_field::<f32>(query, &format!("{}_y", prefix))?;
+        match (x, y) {
+            (Some(x), Some(y)) => self
+                .nearest_vertex(x, y)
+                .ok_or_else(|| WasmAppError::InvalidInput(String::from("network has no vertices"))),
+            _ => Err(WasmAppError::InvalidInput(format!(
+                "query must have either {p}_vertex or {p}_x and {p}_y",
+                p = prefix
+            ))),
+        }
+    }
+
+    fn coordinate(&self, vertex_id: VertexId) -> Result<[f32; 2], WasmAppError> {
+        let vertex = self.graph.get_vertex(vertex_id)?;
+        Ok([vertex.x(), vertex.y()])
+    }
+}
+
+fn read_csv_text<T: DeserializeOwned>(text: &str, name: &str) -> Result<Vec<T>, WasmAppError> {
+    csv::ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(|e| WasmAppError::ReadError(String::from(name), e.to_string()))
+}
+
+fn read_speed_table(text: &str, n_edges: usize) -> Result<Box<[Speed]>, WasmAppError> {
+    let speeds = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse::<f64>().map(Speed::new).map_err(|e| {
+                WasmAppError::ReadError(String::from("speeds"), format!("'{}': {}", line, e))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if speeds.len() != n_edges {
+        return Err(WasmAppError::ReadError(
+            String::from("speeds"),
+            format!("found {} speeds for {} edges", speeds.len(), n_edges),
+        ));
+    }
+    Ok(speeds.into_boxed_slice())
+}
+
+fn query_field<T: DeserializeOwned>(query: &Value, key: &str) -> Result<Option<T>, WasmAppError> {
+    match query.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| WasmAppError::InvalidInput(format!("query field {}: {}", key, e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDGES: &str = "edge_id,src_vertex_id,dst_vertex_id,distance
+0,0,1,1000.0
+1,1,2,1000.0
+2,0,2,1500.0";
+    const VERTICES: &str = "vertex_id,x,y
+0,-105.0,39.0
+1,-105.01,39.0
+2,-105.01,39.01";
+
+    #[test]
+    fn test_run_with_distance_and_time() {
+        let app = WasmApp::from_csv(EDGES, VERTICES, None).expect("should load network");
+        let query =
+            json!({ "origin_vertex": 0, "destination_x": -105.011, "destination_y": 39.011 });
+        let result = app.run(&query).expect("should run query");
+        assert_eq!(result["route"]["path"], json!([2]));
+        assert_eq!(result["route"]["coordinates"].as_array().unwrap().len(), 2);
+
+        // the direct edge is slow, so the fastest route takes the two other edges
+        let speeds = "60\n60\n10";
+        let app = WasmApp::from_csv(
+            EDGES,
+            VERTICES,
+            Some((speeds, SpeedUnit::KilometersPerHour)),
+        )
+        .expect("should load network with speeds");
+        let result = app
+            .run(&json!({ "origin_vertex": 0, "destination_vertex": 2 }))
+            .expect("should run query");
+        assert_eq!(result["route"]["path"], json!([0, 1]));
+        let time = result["route"]["traversal_summary"]["time"]
+            .as_f64()
+            .unwrap();
+        assert!(
+            (time - 2.0).abs() < 1e-6,
+            "expected 2 minutes, found {}",
+            time
+        );
+    }
+}
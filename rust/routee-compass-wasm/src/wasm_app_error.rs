@@ -0,0 +1,18 @@
+use routee_compass_core::{
+    algorithm::search::search_error::SearchError,
+    model::{cost::cost_error::CostError, road_network::graph_error::GraphError},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum WasmAppError {
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("failure reading {0}: {1}")]
+    ReadError(String, String),
+    #[error(transparent)]
+    GraphError(#[from] GraphError),
+    #[error(transparent)]
+    SearchError(#[from] SearchError),
+    #[error(transparent)]
+    CostError(#[from] CostError),
+}
@@ -1,14 +1,19 @@
-use std::str::FromStr;
+use std::path::Path;
 
 use routee_compass_core::{
-    algorithm::search::direction::Direction,
+    algorithm::search::{cancellation_token::CancellationToken, direction::Direction},
     model::{
         road_network::{edge_id::EdgeId, vertex_id::VertexId},
-        unit::{as_f64::AsF64, DistanceUnit},
+        unit::{as_f64::AsF64, DistanceUnit, GradeUnit, Speed, SpeedUnit, Unit},
     },
+    util::geo::geo_io_utils,
 };
+use wkt::ToWkt;
 
-use super::compass::{compass_app::CompassApp, compass_app_error::CompassAppError};
+use super::compass::{
+    compass_app::CompassApp, compass_app_error::CompassAppError, compass_app_ops, od_matrix,
+    run_progress::ProgressCallback,
+};
 
 use crate::app::search::search_app_graph_ops::SearchAppGraphOps;
 
@@ -90,6 +95,29 @@ pub trait CompassAppBindings {
 
     // Default functions
 
+    /// Build the compass app from a JSON configuration, such as a python dictionary,
+    /// by way of [`CompassAppBindings::from_config_toml_string`]
+    ///
+    /// # Arguments
+    /// * `config_string` - the JSON string containing the configuration
+    /// * `working_dir` - the directory which relative file paths of the configuration are
+    ///   resolved against, or a configuration file in that directory
+    ///
+    /// # Returns
+    /// * The compass app wrapper
+    fn from_config_json_string(
+        config_string: String,
+        working_dir: String,
+    ) -> Result<Self, CompassAppError>
+    where
+        Self: Sized,
+    {
+        let config: serde_json::Value = serde_json::from_str(&config_string)?;
+        let toml_string = compass_app_ops::config_json_to_toml(&config)?;
+        let config_path = compass_app_ops::config_file_path_in(Path::new(&working_dir));
+        Self::from_config_toml_string(toml_string, config_path.to_string_lossy().to_string())
+    }
+
     /// Get the origin vertex of an edge
     ///
     /// # Arguments
@@ -135,12 +163,8 @@ pub trait CompassAppBindings {
     ) -> Result<f64, CompassAppError> {
         let du_internal: Option<DistanceUnit> = match distance_unit {
             Some(du_str) => {
-                let du = DistanceUnit::from_str(du_str.as_str()).map_err(|_| {
-                    CompassAppError::InternalError(format!(
-                        "could not deserialize distance unit '{}'",
-                        du_str
-                    ))
-                })?;
+                let du = DistanceUnit::parse(&du_str)
+                    .map_err(|e| CompassAppError::InvalidInput(e.to_string()))?;
 
                 Some(du)
             }
@@ -184,6 +208,137 @@ pub trait CompassAppBindings {
             .map(|es| es.iter().map(|e| e.0).collect())
     }
 
+    /// Get the x and y coordinate of a vertex
+    ///
+    /// # Arguments
+    /// * `vertex_id` - the id of the vertex
+    ///
+    /// # Returns
+    /// * the x and y coordinate of the vertex
+    fn graph_vertex_coordinate(&self, vertex_id: usize) -> Result<(f64, f64), CompassAppError> {
+        self.app()
+            .search_app
+            .get_vertex_coordinate(VertexId(vertex_id))
+    }
+
+    /// Get the geometry of an edge as WKT, from an output plugin which loads edge geometries
+    ///
+    /// # Arguments
+    /// * `edge_id` - the id of the edge
+    ///
+    /// # Returns
+    /// * the LINESTRING of the edge as well-known text
+    fn graph_edge_geometry_wkt(&self, edge_id: usize) -> Result<String, CompassAppError> {
+        let geometry = self.app().edge_geometry(EdgeId(edge_id))?;
+        Ok(geometry.wkt_string())
+    }
+
+    /// Get the geometry of an edge as WKB, from an output plugin which loads edge geometries
+    ///
+    /// # Arguments
+    /// * `edge_id` - the id of the edge
+    ///
+    /// # Returns
+    /// * the LINESTRING of the edge as well-known binary
+    fn graph_edge_geometry_wkb(&self, edge_id: usize) -> Result<Vec<u8>, CompassAppError> {
+        let geometry = self.app().edge_geometry(EdgeId(edge_id))?;
+        Ok(geo_io_utils::linestring_to_wkb(geometry))
+    }
+
+    /// Get the speed of an edge from the traversal model
+    ///
+    /// # Arguments
+    /// * `edge_id` - the id of the edge
+    /// * `speed_unit` - the speed unit to use. If not provided, the unit of the speed table is used
+    ///
+    /// # Returns
+    /// * the speed of the edge in the specified speed unit
+    fn graph_edge_speed(
+        &self,
+        edge_id: usize,
+        speed_unit: Option<String>,
+    ) -> Result<f64, CompassAppError> {
+        let su_internal = match speed_unit {
+            Some(su_str) => Some(
+                SpeedUnit::parse(&su_str)
+                    .map_err(|e| CompassAppError::InvalidInput(e.to_string()))?,
+            ),
+            None => None,
+        };
+        self.app()
+            .search_app
+            .get_edge_speed(EdgeId(edge_id), su_internal)
+            .map(|s| s.as_f64())
+    }
+
+    /// Get the grade of an edge from the traversal model
+    ///
+    /// # Arguments
+    /// * `edge_id` - the id of the edge
+    /// * `grade_unit` - the grade unit to use. If not provided, the unit of the grade table is used
+    ///
+    /// # Returns
+    /// * the grade of the edge in the specified grade unit
+    fn graph_edge_grade(
+        &self,
+        edge_id: usize,
+        grade_unit: Option<String>,
+    ) -> Result<f64, CompassAppError> {
+        let gu_internal = match grade_unit {
+            Some(gu_str) => Some(
+                GradeUnit::parse(&gu_str)
+                    .map_err(|e| CompassAppError::InvalidInput(e.to_string()))?,
+            ),
+            None => None,
+        };
+        self.app()
+            .search_app
+            .get_edge_grade(EdgeId(edge_id), gu_internal)
+            .map(|g| g.as_f64())
+    }
+
+    /// Get the road class of an edge from the frontier model
+    ///
+    /// # Arguments
+    /// * `edge_id` - the id of the edge
+    ///
+    /// # Returns
+    /// * the road class of the edge, or None if no frontier model uses road classes
+    fn graph_edge_road_class(&self, edge_id: usize) -> Result<Option<u8>, CompassAppError> {
+        self.app().search_app.get_edge_road_class(EdgeId(edge_id))
+    }
+
+    /// Get every edge of the graph as columns
+    ///
+    /// # Returns
+    /// * the edge ids, origin vertex ids, destination vertex ids and distances in meters
+    #[allow(clippy::type_complexity)]
+    fn graph_edges(
+        &self,
+    ) -> Result<(Vec<usize>, Vec<usize>, Vec<usize>, Vec<f64>), CompassAppError> {
+        let edges = &self.app().search_app.directed_graph.edges;
+        Ok((
+            edges.iter().map(|e| e.edge_id.0).collect(),
+            edges.iter().map(|e| e.src_vertex_id.0).collect(),
+            edges.iter().map(|e| e.dst_vertex_id.0).collect(),
+            edges.iter().map(|e| e.distance.as_f64()).collect(),
+        ))
+    }
+
+    /// Get every vertex of the graph as columns
+    ///
+    /// # Returns
+    /// * the vertex ids, x coordinates and y coordinates
+    #[allow(clippy::type_complexity)]
+    fn graph_vertices(&self) -> Result<(Vec<usize>, Vec<f64>, Vec<f64>), CompassAppError> {
+        let vertices = &self.app().search_app.directed_graph.vertices;
+        Ok((
+            vertices.iter().map(|v| v.vertex_id.0).collect(),
+            vertices.iter().map(|v| v.x() as f64).collect(),
+            vertices.iter().map(|v| v.y() as f64).collect(),
+        ))
+    }
+
     /// Runs a set of queries and returns the results
     ///
     /// # Arguments
@@ -196,6 +351,69 @@ pub trait CompassAppBindings {
         queries: Vec<String>,
         config: Option<String>,
     ) -> Result<Vec<String>, CompassAppError> {
+        let results = self.run_queries_json(queries, config)?;
+        let string_results: Vec<String> = results.iter().map(|r| r.to_string()).collect();
+        Ok(string_results)
+    }
+
+    /// Runs a set of queries and returns the results as JSON values, which bindings can
+    /// convert directly into native objects instead of parsing a JSON string per result
+    ///
+    /// # Arguments
+    /// * `queries` - a list of queries to run as json strings
+    ///
+    /// # Returns
+    /// * a list of json values containing the results of the queries
+    fn run_queries_json(
+        &self,
+        queries: Vec<String>,
+        config: Option<String>,
+    ) -> Result<Vec<serde_json::Value>, CompassAppError> {
+        self.run_queries_with_progress(queries, config, None)
+    }
+
+    /// Runs a set of queries and returns the results as JSON values, reporting the
+    /// progress of the run to an optional callback
+    ///
+    /// # Arguments
+    /// * `queries` - a list of queries to run as json strings
+    /// * `config` - an optional json run configuration
+    /// * `progress_callback` - called every `interval` completed queries of each stage of the run
+    ///
+    /// # Returns
+    /// * a list of json values containing the results of the queries
+    fn run_queries_with_progress(
+        &self,
+        queries: Vec<String>,
+        config: Option<String>,
+        progress_callback: Option<ProgressCallback>,
+    ) -> Result<Vec<serde_json::Value>, CompassAppError> {
+        self.run_queries_cancellable(
+            queries,
+            config,
+            progress_callback,
+            &CancellationToken::default(),
+        )
+    }
+
+    /// Runs a set of queries as in [`CompassAppBindings::run_queries_with_progress`],
+    /// which may be aborted from another thread, such as on a keyboard interrupt
+    ///
+    /// # Arguments
+    /// * `queries` - a list of queries to run as json strings
+    /// * `config` - an optional json run configuration
+    /// * `progress_callback` - called every `interval` completed queries of each stage of the run
+    /// * `cancellation` - aborts the run when cancelled, failing each remaining query
+    ///
+    /// # Returns
+    /// * a list of json values containing the results of the queries
+    fn run_queries_cancellable(
+        &self,
+        queries: Vec<String>,
+        config: Option<String>,
+        progress_callback: Option<ProgressCallback>,
+        cancellation: &CancellationToken,
+    ) -> Result<Vec<serde_json::Value>, CompassAppError> {
         let config_inner: Option<serde_json::Value> = match config {
             Some(c) => {
                 let c_serde: serde_json::Value = serde_json::from_str(&c)?;
@@ -209,9 +427,90 @@ pub trait CompassAppBindings {
             .map(|q| serde_json::from_str(q))
             .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
 
-        let results = self.app().run(json_queries, config_inner.as_ref())?;
+        self.app().run_cancellable(
+            json_queries,
+            config_inner.as_ref(),
+            progress_callback.as_ref(),
+            cancellation,
+        )
+    }
 
-        let string_results: Vec<String> = results.iter().map(|r| r.to_string()).collect();
-        Ok(string_results)
+    /// Runs a query between every pair of origin and destination coordinates and returns
+    /// a row-major matrix of values for each requested feature of the traversal summary,
+    /// with NaN where a query failed or the feature is not in the state model
+    ///
+    /// # Arguments
+    /// * `origins_x` - the x coordinate of each origin
+    /// * `origins_y` - the y coordinate of each origin
+    /// * `destinations_x` - the x coordinate of each destination
+    /// * `destinations_y` - the y coordinate of each destination
+    /// * `features` - the state features to return, such as "distance" or "time"
+    /// * `query` - an optional json object of fields shared by every query, such as a model_name
+    /// * `config` - an optional json run configuration
+    ///
+    /// # Returns
+    /// * for each feature, the values of the origins by the destinations in row-major order
+    #[allow(clippy::too_many_arguments)]
+    fn run_od_matrix(
+        &self,
+        origins_x: Vec<f64>,
+        origins_y: Vec<f64>,
+        destinations_x: Vec<f64>,
+        destinations_y: Vec<f64>,
+        features: Vec<String>,
+        query: Option<String>,
+        config: Option<String>,
+    ) -> Result<Vec<Vec<f64>>, CompassAppError> {
+        let template: serde_json::Value = match query {
+            Some(q) => serde_json::from_str(&q)?,
+            None => serde_json::json!({}),
+        };
+        let config_inner: Option<serde_json::Value> = match config {
+            Some(c) => Some(serde_json::from_str(&c)?),
+            None => None,
+        };
+        let origins = od_matrix::zip_coordinates(origins_x, origins_y)?;
+        let destinations = od_matrix::zip_coordinates(destinations_x, destinations_y)?;
+        let queries = od_matrix::od_matrix_queries(&template, &origins, &destinations)?;
+        let n_cells = queries.len();
+        let results = self.app().run(queries, config_inner.as_ref())?;
+        Ok(od_matrix::od_matrix_features(&results, n_cells, &features))
+    }
+
+    /// Replaces the speeds of some edges in the running app, such as from a live
+    /// traffic feed, without rebuilding the graph
+    ///
+    /// # Arguments
+    /// * `edge_ids` - the ids of the edges to update
+    /// * `speeds` - the new speed of each edge
+    /// * `speed_unit` - the speed unit of the new speeds, such as "kilometers_per_hour"
+    fn update_edge_speeds(
+        &self,
+        edge_ids: Vec<usize>,
+        speeds: Vec<f64>,
+        speed_unit: String,
+    ) -> Result<(), CompassAppError> {
+        if edge_ids.len() != speeds.len() {
+            return Err(CompassAppError::InternalError(format!(
+                "found {} edge ids but {} speeds",
+                edge_ids.len(),
+                speeds.len()
+            )));
+        }
+        let su = SpeedUnit::parse(&speed_unit)
+            .map_err(|e| CompassAppError::InvalidInput(e.to_string()))?;
+        let updates = edge_ids
+            .into_iter()
+            .zip(speeds)
+            .map(|(e, s)| (EdgeId(e), Speed::new(s)))
+            .collect::<Vec<_>>();
+        self.app().update_edge_speeds(&updates, &su)
+    }
+
+    /// Finishes the output plugins, such as writing the batch summary, and writes the
+    /// reproducibility manifest. called once
+    /// when the app is no longer used, rather than after each run
+    fn finish(&self) -> Result<(), CompassAppError> {
+        self.app().finish()
     }
 }
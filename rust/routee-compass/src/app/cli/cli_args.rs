@@ -1,18 +1,44 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 
+use super::graph_command::GraphCommand;
 use crate::app::compass::{
     compass_app_error::CompassAppError,
     config::compass_configuration_error::CompassConfigurationError,
 };
+use crate::app::server::server_args::ServerArgs;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct CliArgs {
+    #[command(subcommand)]
+    pub command: CliCommand,
+}
+
+/// the operational modes of the RouteE Compass application
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    /// runs a file of queries and writes the responses
+    Route(RouteArgs),
+    /// loads the application once and answers queries over HTTP
+    Serve(ServerArgs),
+    /// checks that a configuration and its dataset load, and reports problems with the
+    /// graph and with queries that would fail before their search
+    Validate(ValidateArgs),
+    /// tools for preparing road network graphs
+    Graph {
+        #[command(subcommand)]
+        command: GraphCommand,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct RouteArgs {
     /// RouteE Compass service configuration TOML file
     #[arg(short, long, value_name = "*.toml")]
     pub config_file: String,
 
-    /// JSON file containing queries. Should be newline-delimited if chunksize is set
+    /// JSON file containing queries, as an array or newline-delimited with one query per
+    /// line, or `-` to read queries from stdin. Should be newline-delimited if chunksize is set
     #[arg(short, long, value_name = "*.json")]
     pub query_file: String,
 
@@ -20,12 +46,21 @@ pub struct CliArgs {
     #[arg(long)]
     pub chunksize: Option<i64>,
 
-    /// Format of JSON queries file, if regular JSON or newline-delimited JSON
+    /// Stream a newline-delimited JSON queries file in batches rather than reading it whole
     #[arg(short, long)]
     pub newline_delimited: bool,
+
+    /// Hide the progress bars, such as when the output of a run is captured by a pipeline
+    #[arg(long)]
+    pub no_progress: bool,
+
+    /// File to write responses to, overriding the filename of the response output policy.
+    /// May contain the tokens {timestamp} and {config_name}
+    #[arg(short, long)]
+    pub output: Option<String>,
 }
 
-impl CliArgs {
+impl RouteArgs {
     pub fn validate(&self) -> Result<(), CompassAppError> {
         match (self.chunksize, self.newline_delimited) {
             (Some(_), false) => Err(CompassAppError::CompassConfigurationError(
@@ -61,3 +96,18 @@ impl CliArgs {
         }
     }
 }
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// optional JSON file of queries to check against the input plugins
+    #[arg(short, long, value_name = "*.json")]
+    pub query_file: Option<String>,
+
+    /// fail on warnings about the graph, such as vertices outside of its largest component
+    #[arg(long)]
+    pub strict: bool,
+}
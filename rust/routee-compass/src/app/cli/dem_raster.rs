@@ -0,0 +1,255 @@
+use crate::app::compass::compass_app_error::CompassAppError;
+use geo::Coord;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tiff::decoder::{Decoder, DecodingResult, Limits};
+use tiff::tags::Tag;
+use tiff::ColorType;
+
+/// GeoKeyDirectoryTag, which holds the GeoTIFF keys
+const GEO_KEY_DIRECTORY_TAG: u16 = 34735;
+/// GeoTIFF key for the model type, which is 2 for geographic coordinates
+const GT_MODEL_TYPE_GEO_KEY: u16 = 1024;
+/// GeoTIFF key for the raster type, which is 2 when pixels are points
+const GT_RASTER_TYPE_GEO_KEY: u16 = 1025;
+
+/// a digital elevation model read from a single-band GeoTIFF in geographic
+/// (WGS84 longitude and latitude) coordinates, such as an SRTM or USGS 3DEP tile.
+pub struct DemRaster {
+    width: usize,
+    height: usize,
+    /// longitude of the west edge of the raster
+    x0: f64,
+    /// latitude of the north edge of the raster
+    y0: f64,
+    /// width of a pixel in degrees
+    dx: f64,
+    /// height of a pixel in degrees
+    dy: f64,
+    elevations: Vec<f32>,
+    nodata: Option<f32>,
+}
+
+impl DemRaster {
+    /// reads a DEM from a GeoTIFF file. the georeferencing is read from the pixel scale and
+    /// tiepoint tags, and missing elevations from the GDAL nodata tag.
+    pub fn from_geotiff(path: &Path) -> Result<DemRaster, CompassAppError> {
+        let tiff_error =
+            |e: tiff::TiffError| CompassAppError::InvalidInput(format!("{:?}: {}", path, e));
+        let file = File::open(path)?;
+        let mut decoder = Decoder::new(BufReader::new(file))
+            .map_err(tiff_error)?
+            .with_limits(Limits::unlimited());
+        match decoder.colortype().map_err(tiff_error)? {
+            ColorType::Gray(_) => {}
+            other => {
+                return Err(CompassAppError::InvalidInput(format!(
+                    "{:?} must have a single band of elevations, found {:?}",
+                    path, other
+                )))
+            }
+        }
+        let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+
+        let geo_keys = decoder
+            .find_tag_unsigned_vec::<u16>(Tag::Unknown(GEO_KEY_DIRECTORY_TAG))
+            .map_err(tiff_error)?
+            .unwrap_or_default();
+        if let Some(model_type) = geo_key(&geo_keys, GT_MODEL_TYPE_GEO_KEY) {
+            if model_type != 2 {
+                return Err(CompassAppError::InvalidInput(format!(
+                    "{:?} must be in geographic coordinates, such as EPSG:4326",
+                    path
+                )));
+            }
+        }
+        let scale = decoder
+            .get_tag_f64_vec(Tag::ModelPixelScaleTag)
+            .map_err(tiff_error)?;
+        let tiepoint = decoder
+            .get_tag_f64_vec(Tag::ModelTiepointTag)
+            .map_err(tiff_error)?;
+        if scale.len() < 2 || tiepoint.len() < 6 {
+            return Err(CompassAppError::InvalidInput(format!(
+                "{:?} has an invalid pixel scale or tiepoint",
+                path
+            )));
+        }
+        let (dx, dy) = (scale[0], scale[1]);
+        let mut x0 = tiepoint[3] - tiepoint[0] * dx;
+        let mut y0 = tiepoint[4] + tiepoint[1] * dy;
+        // a tiepoint of a point raster is the center of a pixel rather than its corner
+        if geo_key(&geo_keys, GT_RASTER_TYPE_GEO_KEY) == Some(2) {
+            x0 -= dx / 2.0;
+            y0 += dy / 2.0;
+        }
+        let nodata = match decoder.find_tag(Tag::GdalNodata).map_err(tiff_error)? {
+            None => None,
+            Some(value) => {
+                let text = value.into_string().map_err(tiff_error)?;
+                let text = text.trim_matches(char::from(0)).trim();
+                Some(text.parse::<f32>().map_err(|e| {
+                    CompassAppError::InvalidInput(format!(
+                        "{:?} has an invalid nodata value '{}': {}",
+                        path, text, e
+                    ))
+                })?)
+            }
+        };
+
+        let elevations: Vec<f32> = match decoder.read_image().map_err(tiff_error)? {
+            DecodingResult::U8(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::U16(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::U32(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::U64(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::F32(v) => v,
+            DecodingResult::F64(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::I8(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::I16(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::I32(v) => v.into_iter().map(|e| e as f32).collect(),
+            DecodingResult::I64(v) => v.into_iter().map(|e| e as f32).collect(),
+        };
+        log::info!(
+            "read {}x{} DEM from {:?} covering longitude {} to {} and latitude {} to {}",
+            width,
+            height,
+            path,
+            x0,
+            x0 + width as f64 * dx,
+            y0 - height as f64 * dy,
+            y0
+        );
+        Ok(DemRaster {
+            width: width as usize,
+            height: height as usize,
+            x0,
+            y0,
+            dx,
+            dy,
+            elevations,
+            nodata,
+        })
+    }
+
+    /// true if the coordinate is within the extent of this raster
+    pub fn contains(&self, coord: &Coord<f32>) -> bool {
+        let (x, y) = (coord.x as f64, coord.y as f64);
+        x >= self.x0
+            && x <= self.x0 + self.width as f64 * self.dx
+            && y <= self.y0
+            && y >= self.y0 - self.height as f64 * self.dy
+    }
+
+    /// the elevation at a coordinate, interpolated bilinearly between the centers of the
+    /// surrounding pixels. returns None outside of the raster or next to a missing value.
+    pub fn elevation(&self, coord: &Coord<f32>) -> Option<f64> {
+        if !self.contains(coord) {
+            return None;
+        }
+        let col = ((coord.x as f64 - self.x0) / self.dx - 0.5).clamp(0.0, (self.width - 1) as f64);
+        let row = ((self.y0 - coord.y as f64) / self.dy - 0.5).clamp(0.0, (self.height - 1) as f64);
+        let (c0, r0) = (col.floor() as usize, row.floor() as usize);
+        let (c1, r1) = ((c0 + 1).min(self.width - 1), (r0 + 1).min(self.height - 1));
+        let (fc, fr) = (col - c0 as f64, row - r0 as f64);
+
+        let value = |r: usize, c: usize| -> Option<f64> {
+            let z = *self.elevations.get(r * self.width + c)?;
+            match self.nodata {
+                Some(nodata) if z == nodata => None,
+                _ if z.is_nan() => None,
+                _ => Some(z as f64),
+            }
+        };
+        let top = value(r0, c0)? * (1.0 - fc) + value(r0, c1)? * fc;
+        let bottom = value(r1, c0)? * (1.0 - fc) + value(r1, c1)? * fc;
+        Some(top * (1.0 - fr) + bottom * fr)
+    }
+}
+
+/// a set of DEM tiles. the elevation of a coordinate comes from the first tile that covers it.
+pub struct DemRasterSet {
+    pub rasters: Vec<DemRaster>,
+}
+
+impl DemRasterSet {
+    pub fn from_geotiffs<P: AsRef<Path>>(paths: &[P]) -> Result<DemRasterSet, CompassAppError> {
+        let rasters = paths
+            .iter()
+            .map(|p| DemRaster::from_geotiff(p.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(DemRasterSet { rasters })
+    }
+
+    pub fn elevation(&self, coord: &Coord<f32>) -> Option<f64> {
+        self.rasters.iter().find_map(|r| r.elevation(coord))
+    }
+}
+
+/// finds the value of a key in a GeoKeyDirectoryTag, which holds a header of four
+/// values followed by four values for each key: id, tag location, count and value.
+/// only keys stored directly in the directory, with a tag location of 0, are read.
+fn geo_key(directory: &[u16], key: u16) -> Option<u16> {
+    directory
+        .get(4..)?
+        .chunks_exact(4)
+        .find(|entry| entry[0] == key && entry[1] == 0)
+        .map(|entry| entry[3])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::cli::graph_grade::edge_grade;
+    use geo::{coord, LineString};
+    use routee_compass_core::model::unit::as_f64::AsF64;
+    use routee_compass_core::util::geo::haversine;
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    #[test]
+    fn test_geotiff_elevation_and_grade() {
+        // a 3x3 DEM of 0.01 degree pixels rising 10m per pixel to the east
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("dem.tif");
+        {
+            let file = File::create(&path).unwrap();
+            let mut encoder = TiffEncoder::new(file).unwrap();
+            let mut image = encoder.new_image::<colortype::Gray32Float>(3, 3).unwrap();
+            let dir = image.encoder();
+            dir.write_tag(Tag::ModelPixelScaleTag, &[0.01, 0.01, 0.0][..])
+                .unwrap();
+            dir.write_tag(
+                Tag::ModelTiepointTag,
+                &[0.0, 0.0, 0.0, -105.0, 40.0, 0.0][..],
+            )
+            .unwrap();
+            dir.write_tag(Tag::GdalNodata, "-9999").unwrap();
+            let data = [
+                100.0, 110.0, 120.0, 100.0, 110.0, 120.0, 100.0, 110.0, -9999.0,
+            ];
+            image.write_data(&data).unwrap();
+        }
+        let dem = DemRaster::from_geotiff(&path).unwrap();
+
+        // pixel centers and a point halfway between them, within the precision of f32 coordinates
+        let center = dem.elevation(&coord! {x: -104.985, y: 39.995}).unwrap();
+        assert!((center - 110.0).abs() < 1e-2);
+        let between = dem.elevation(&coord! {x: -104.99, y: 39.985}).unwrap();
+        assert!((between - 105.0).abs() < 1e-2);
+        // next to the missing value, and outside the raster
+        assert!(dem.elevation(&coord! {x: -104.976, y: 39.974}).is_none());
+        assert!(dem.elevation(&coord! {x: -105.1, y: 39.99}).is_none());
+
+        // an edge across the top row climbs 20m
+        let line = LineString::new(vec![
+            coord! {x: -104.995, y: 39.995},
+            coord! {x: -104.975, y: 39.995},
+        ]);
+        let length = haversine::coord_distance_meters(&line.0[0], &line.0[1])
+            .unwrap()
+            .as_f64();
+        let dem = DemRasterSet { rasters: vec![dem] };
+        let grade = edge_grade(&line, &dem, 100.0).unwrap().unwrap();
+        assert!((grade - 20.0 / length).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,133 @@
+use super::graph_compile::compile_graph;
+use super::graph_component_filter::filter_largest_component;
+use super::graph_contract::contract_graph;
+use super::graph_grade::impute_grades;
+use super::graph_stats::graph_stats;
+use crate::app::compass::compass_app_error::CompassAppError;
+use clap::Subcommand;
+use routee_compass_core::algorithm::search::ch::contraction_hierarchy::DEFAULT_WITNESS_SEARCH_LIMIT;
+use std::path::PathBuf;
+
+/// tools for preparing RouteE Compass road network graphs
+#[derive(Subcommand, Debug)]
+pub enum GraphCommand {
+    /// compiles the CSV graph of a configuration into a binary file which loads near-instantly
+    Compile {
+        /// RouteE Compass configuration TOML file with a [graph] section
+        #[arg(short, long, value_name = "*.toml")]
+        config_file: PathBuf,
+
+        /// file to write the compiled graph to
+        #[arg(short, long, value_name = "*.bin")]
+        output_file: PathBuf,
+    },
+    /// contracts the graph of a configuration into a contraction hierarchy weighted by
+    /// the cost of each edge under its models, for use with the `ch` search algorithm
+    Contract {
+        /// RouteE Compass configuration TOML file of the app which will load the hierarchy
+        #[arg(short, long, value_name = "*.toml")]
+        config_file: PathBuf,
+
+        /// file to write the hierarchy to, gzip-compressed if it ends with .gz
+        #[arg(short, long, value_name = "*.json.gz")]
+        output_file: PathBuf,
+
+        /// max vertices settled by each witness search while contracting
+        #[arg(long, default_value_t = DEFAULT_WITNESS_SEARCH_LIMIT)]
+        witness_search_limit: usize,
+    },
+    /// removes every vertex and edge outside of the largest strongly connected component,
+    /// rewriting the graph and its edge and vertex attribute files
+    LargestComponent {
+        /// RouteE Compass configuration TOML file with a [graph] section
+        #[arg(short, long, value_name = "*.toml")]
+        config_file: PathBuf,
+
+        /// a file with one row per edge, such as speeds or geometries. may be repeated
+        #[arg(long)]
+        edge_file: Vec<PathBuf>,
+
+        /// a file with one row per vertex, such as vertex uuids. may be repeated
+        #[arg(long)]
+        vertex_file: Vec<PathBuf>,
+
+        /// directory to write the filtered graph and attribute files to
+        #[arg(short, long)]
+        output_directory: PathBuf,
+    },
+    /// samples elevations from DEM GeoTIFF tiles along each edge geometry and writes
+    /// the grade of each edge, in edge id order
+    Grade {
+        /// RouteE Compass configuration TOML file with a [graph] section
+        #[arg(short, long, value_name = "*.toml")]
+        config_file: PathBuf,
+
+        /// edge geometries as WKT LINESTRINGs, one per edge
+        #[arg(long, value_name = "*.txt.gz")]
+        geometry_file: PathBuf,
+
+        /// a GeoTIFF elevation tile in longitude and latitude. may be repeated
+        #[arg(long, required = true, value_name = "*.tif")]
+        dem_file: Vec<PathBuf>,
+
+        /// distance in meters between elevation samples along each edge
+        #[arg(long, default_value_t = 30.0)]
+        sample_spacing: f64,
+
+        /// optional limit on the magnitude of each grade, as a decimal
+        #[arg(long)]
+        max_grade: Option<f64>,
+
+        /// file to write the decimal grade of each edge to
+        #[arg(short, long, value_name = "*.txt.gz")]
+        output_file: PathBuf,
+    },
+    /// summarizes the structure of the graph of a configuration as JSON, such as the
+    /// number of strongly connected components, isolated vertices and self loops
+    Stats {
+        /// RouteE Compass configuration TOML file with a [graph] section
+        #[arg(short, long, value_name = "*.toml")]
+        config_file: PathBuf,
+    },
+}
+
+/// runs a graph tool
+pub fn run_graph_command(command: &GraphCommand) -> Result<(), CompassAppError> {
+    match command {
+        GraphCommand::Compile {
+            config_file,
+            output_file,
+        } => compile_graph(config_file, output_file),
+        GraphCommand::Contract {
+            config_file,
+            output_file,
+            witness_search_limit,
+        } => contract_graph(config_file, output_file, *witness_search_limit),
+        GraphCommand::LargestComponent {
+            config_file,
+            edge_file,
+            vertex_file,
+            output_directory,
+        } => filter_largest_component(config_file, edge_file, vertex_file, output_directory),
+        GraphCommand::Grade {
+            config_file,
+            geometry_file,
+            dem_file,
+            sample_spacing,
+            max_grade,
+            output_file,
+        } => impute_grades(
+            config_file,
+            geometry_file,
+            dem_file,
+            output_file,
+            *sample_spacing,
+            *max_grade,
+        ),
+        GraphCommand::Stats { config_file } => {
+            let stats = graph_stats(config_file)?;
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            Ok(())
+        }
+    }
+}
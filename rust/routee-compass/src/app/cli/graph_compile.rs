@@ -0,0 +1,45 @@
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::compass::compass_app_ops as ops;
+use crate::app::compass::compass_input_field::CompassInputField;
+use crate::app::compass::config::{
+    compass_configuration_field::CompassConfigurationField,
+    config_json_extension::ConfigJsonExtensions, graph_builder::DefaultGraphBuilder,
+};
+use std::path::{Path, PathBuf};
+
+/// loads the graph of a configuration file and writes it to a compiled graph
+/// file, which can be set as the `compiled_graph_input_file` of the `[graph]`
+/// section to skip parsing the CSV edge and vertex lists at startup.
+///
+/// # Arguments
+/// * `config_path` - configuration TOML file with a `[graph]` section
+/// * `output_file` - path to write the compiled graph to
+pub fn compile_graph(config_path: &Path, output_file: &Path) -> Result<(), CompassAppError> {
+    let config = ops::read_config_from_file(config_path)?;
+    let root_config_path = config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
+    let config_json = config
+        .try_deserialize::<serde_json::Value>()?
+        .normalize_file_paths(&"", &root_config_path)?;
+    let graph_params = config_json.get_config_section(CompassConfigurationField::Graph, &"TOML")?;
+    let graph = DefaultGraphBuilder::build(&graph_params)?;
+    graph.write_compiled(&output_file)?;
+    log::info!(
+        "wrote graph with {} vertices and {} edges to {:?}",
+        graph.n_vertices(),
+        graph.n_edges(),
+        output_file
+    );
+    Ok(())
+}
+
+/// reads the `[graph]` section of a configuration file, with file paths
+/// resolved relative to the configuration file
+pub fn read_graph_config(config_path: &Path) -> Result<serde_json::Value, CompassAppError> {
+    let config = ops::read_config_from_file(config_path)?;
+    let root_config_path = config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
+    let config_json = config
+        .try_deserialize::<serde_json::Value>()?
+        .normalize_file_paths(&"", &root_config_path)?;
+    let graph_params = config_json.get_config_section(CompassConfigurationField::Graph, &"TOML")?;
+    Ok(graph_params)
+}
@@ -0,0 +1,196 @@
+use super::graph_compile::read_graph_config;
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::compass::config::{
+    compass_configuration_field::CompassConfigurationField,
+    config_json_extension::ConfigJsonExtensions, graph_builder::DefaultGraphBuilder,
+};
+use routee_compass_core::model::road_network::strongly_connected_components::largest_strongly_connected_component;
+use routee_compass_core::util::fs::fs_utils;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// filters the graph of a configuration to its largest strongly connected
+/// component, so that every vertex can reach every other vertex. the edge and
+/// vertex lists are rewritten with new ids, and each edge and vertex attribute
+/// file, such as speeds or geometries, is filtered to the remaining rows.
+/// all files are written to the output directory with their original names.
+///
+/// # Arguments
+/// * `config_path` - configuration TOML file with a `[graph]` section
+/// * `edge_files` - files with one row per edge, in edge id order
+/// * `vertex_files` - files with one row per vertex, in vertex id order
+/// * `output_directory` - directory to write the filtered files to
+pub fn filter_largest_component(
+    config_path: &Path,
+    edge_files: &[PathBuf],
+    vertex_files: &[PathBuf],
+    output_directory: &Path,
+) -> Result<(), CompassAppError> {
+    let graph_key = CompassConfigurationField::Graph.to_string();
+    let graph_params = read_graph_config(config_path)?;
+    let edge_list_csv = graph_params.get_config_path(&"edge_list_input_file", &graph_key)?;
+    let vertex_list_csv = graph_params.get_config_path(&"vertex_list_input_file", &graph_key)?;
+    let graph = DefaultGraphBuilder::build(&graph_params)?;
+
+    // new ids for the vertices and edges which remain
+    let mut vertex_ids: Vec<Option<usize>> = vec![None; graph.n_vertices()];
+    for (new_id, vertex_id) in largest_strongly_connected_component(&graph)
+        .iter()
+        .enumerate()
+    {
+        vertex_ids[vertex_id.0] = Some(new_id);
+    }
+    let mut edge_ids: Vec<Option<usize>> = vec![None; graph.n_edges()];
+    let mut n_kept_edges = 0;
+    for edge in graph.edges.iter() {
+        if vertex_ids[edge.src_vertex_id.0].is_some() && vertex_ids[edge.dst_vertex_id.0].is_some()
+        {
+            edge_ids[edge.edge_id.0] = Some(n_kept_edges);
+            n_kept_edges += 1;
+        }
+    }
+    let n_kept_vertices = vertex_ids.iter().flatten().count();
+    log::info!(
+        "largest component has {} of {} vertices and {} of {} edges",
+        n_kept_vertices,
+        graph.n_vertices(),
+        n_kept_edges,
+        graph.n_edges()
+    );
+
+    std::fs::create_dir_all(output_directory)?;
+    let output_path = |path: &Path| -> Result<PathBuf, CompassAppError> {
+        let filename = path
+            .file_name()
+            .ok_or_else(|| CompassAppError::InvalidInput(format!("{:?} is not a file", path)))?;
+        Ok(output_directory.join(filename))
+    };
+
+    rewrite_csv(
+        &edge_list_csv,
+        &output_path(&edge_list_csv)?,
+        "edge_id",
+        &edge_ids,
+        &[
+            ("src_vertex_id", vertex_ids.as_slice()),
+            ("dst_vertex_id", vertex_ids.as_slice()),
+        ],
+    )?;
+    rewrite_csv(
+        &vertex_list_csv,
+        &output_path(&vertex_list_csv)?,
+        "vertex_id",
+        &vertex_ids,
+        &[],
+    )?;
+    for file in edge_files {
+        filter_rows(file, &output_path(file)?, &edge_ids)?;
+    }
+    for file in vertex_files {
+        filter_rows(file, &output_path(file)?, &vertex_ids)?;
+    }
+    Ok(())
+}
+
+/// rewrites a CSV file, keeping the rows whose id column has a new id and
+/// replacing the ids in the id column and any referencing columns
+fn rewrite_csv(
+    input: &Path,
+    output: &Path,
+    id_column: &str,
+    ids: &[Option<usize>],
+    reference_columns: &[(&str, &[Option<usize>])],
+) -> Result<(), CompassAppError> {
+    let mut reader = csv::Reader::from_reader(fs_utils::open_reader(input)?);
+    let headers = reader.headers().map_err(|e| csv_error(input, e))?.clone();
+    let column = |name: &str| {
+        headers.iter().position(|h| h == name).ok_or_else(|| {
+            CompassAppError::InvalidInput(format!("{:?} has no {} column", input, name))
+        })
+    };
+    let id_idx = column(id_column)?;
+    let references = reference_columns
+        .iter()
+        .map(|(name, ids)| Ok((column(name)?, *ids)))
+        .collect::<Result<Vec<_>, CompassAppError>>()?;
+
+    let mut writer = csv::Writer::from_writer(fs_utils::create_writer(output)?);
+    writer
+        .write_record(&headers)
+        .map_err(|e| csv_error(output, e))?;
+    for record in reader.records() {
+        let record = record.map_err(|e| csv_error(input, e))?;
+        let new_id = match lookup_id(&record, id_idx, ids, input)? {
+            Some(id) => id,
+            None => continue,
+        };
+        let mut row = record.iter().map(String::from).collect::<Vec<_>>();
+        row[id_idx] = new_id.to_string();
+        for (idx, ref_ids) in references.iter() {
+            let new_ref = lookup_id(&record, *idx, ref_ids, input)?.ok_or_else(|| {
+                CompassAppError::InternalError(format!(
+                    "row {} of {:?} references a removed id",
+                    new_id, input
+                ))
+            })?;
+            row[*idx] = new_ref.to_string();
+        }
+        writer
+            .write_record(&row)
+            .map_err(|e| csv_error(output, e))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// the new id of the id in a column of a CSV record
+fn lookup_id(
+    record: &csv::StringRecord,
+    idx: usize,
+    ids: &[Option<usize>],
+    file: &Path,
+) -> Result<Option<usize>, CompassAppError> {
+    let value = record.get(idx).unwrap_or_default();
+    let id = value.trim().parse::<usize>().map_err(|e| {
+        CompassAppError::InvalidInput(format!("invalid id '{}' in {:?}: {}", value, file, e))
+    })?;
+    ids.get(id).copied().ok_or_else(|| {
+        CompassAppError::InvalidInput(format!("id {} in {:?} is not in the graph", id, file))
+    })
+}
+
+/// keeps the rows of an attribute file whose row index has a new id. a file
+/// with one more row than there are ids is assumed to start with a header.
+fn filter_rows(input: &Path, output: &Path, ids: &[Option<usize>]) -> Result<(), CompassAppError> {
+    let n_rows = fs_utils::line_count(input)?;
+    let has_header = if n_rows == ids.len() + 1 {
+        true
+    } else if n_rows == ids.len() {
+        false
+    } else {
+        return Err(CompassAppError::InvalidInput(format!(
+            "{:?} has {} rows, which does not match the {} rows of the graph",
+            input,
+            n_rows,
+            ids.len()
+        )));
+    };
+    let mut writer = fs_utils::create_writer(output)?;
+    for (idx, line) in fs_utils::open_reader(input)?.lines().enumerate() {
+        let line = line?;
+        let keep = match (has_header, idx) {
+            (true, 0) => true,
+            (true, row) => ids[row - 1].is_some(),
+            (false, row) => ids[row].is_some(),
+        };
+        if keep {
+            writeln!(writer, "{}", line)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn csv_error(file: &Path, e: csv::Error) -> CompassAppError {
+    CompassAppError::InvalidInput(format!("failure processing CSV file {:?}: {}", file, e))
+}
@@ -0,0 +1,127 @@
+use crate::app::compass::compass_app::{ch_cost_configuration, CompassApp};
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::compass::compass_app_ops as ops;
+use crate::app::compass::config::compass_app_builder::CompassAppBuilder;
+use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
+use routee_compass_core::algorithm::search::cancellation_token::CancellationToken;
+use routee_compass_core::algorithm::search::ch::ch_search::static_edge_costs;
+use routee_compass_core::algorithm::search::ch::contraction_hierarchy::ContractionHierarchy;
+use serde_json::json;
+use std::path::Path;
+
+/// contracts the graph of a configuration into a contraction hierarchy, weighting
+/// each edge by its cost under the traversal and cost models of the configuration,
+/// and writes it to a file which can be set as the `hierarchy_input_file` of a
+/// `type = "ch"` `[algorithm]` section. the hierarchy records the configuration of
+/// those models, and an app with a different configuration will not load it.
+///
+/// # Arguments
+/// * `config_path` - configuration TOML file of the app which will load the hierarchy
+/// * `output_file` - path to write the hierarchy to, gzip-compressed if it ends with ".gz"
+/// * `witness_search_limit` - max vertices settled during each witness search
+pub fn contract_graph(
+    config_path: &Path,
+    output_file: &Path,
+    witness_search_limit: usize,
+) -> Result<(), CompassAppError> {
+    let config = ops::read_config_from_file(config_path)?;
+    let mut config_json: serde_json::Value = config.try_deserialize()?;
+    let cost_configuration = ch_cost_configuration(&config_json);
+
+    // the app is built with a dijkstra search and no plugins, since the hierarchy
+    // of a `ch` search does not exist yet
+    config_json[CompassConfigurationField::Algorithm.to_str()] = json!({"type": "dijkstra"});
+    config_json[CompassConfigurationField::Plugins.to_str()] =
+        json!({"input_plugins": [], "output_plugins": []});
+    let config = ops::read_config_from_string(
+        config_json.to_string(),
+        config::FileFormat::Json,
+        config_path.to_string_lossy().to_string(),
+    )?;
+    let app = CompassApp::try_from((&config, &CompassAppBuilder::default()))?;
+    let si = app
+        .search_app
+        .build_search_instance(&json!({}), &CancellationToken::default())?;
+    let edge_weights = static_edge_costs(&si)?;
+
+    let hierarchy =
+        ContractionHierarchy::new(&si.directed_graph, &edge_weights, witness_search_limit)?
+            .with_cost_configuration(cost_configuration);
+    hierarchy.to_file(output_file)?;
+    log::info!(
+        "wrote contraction hierarchy with {} vertices and {} edges to {:?}",
+        hierarchy.n_vertices(),
+        hierarchy.edges.len(),
+        output_file
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write_config(dir: &Path, algorithm: &str, distance_weight: f64) -> PathBuf {
+        let test_dir =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/app/compass/test/speeds_test");
+        let config = format!(
+            r#"
+[graph]
+edge_list_input_file = "{dir}/test_edges.csv"
+vertex_list_input_file = "{dir}/test_vertices.csv"
+[traversal]
+type = "speed_table"
+speed_table_input_file = "{dir}/test_edge_speeds.csv"
+speed_unit = "kilometers_per_hour"
+[access]
+type = "no_access_model"
+[cost.weights]
+distance = {distance_weight}
+time = 1
+[cost.vehicle_rates.time]
+type = "raw"
+[cost.vehicle_rates.distance]
+type = "raw"
+[algorithm]
+{algorithm}
+[plugin]
+input_plugins = []
+output_plugins = [
+    {{ type = "traversal", route = "edge_id", geometry_input_file = "{dir}/edge_geometries.txt" }},
+]
+"#,
+            dir = test_dir.display(),
+        );
+        let config_path = dir.join(format!("config-{}.toml", distance_weight));
+        std::fs::write(&config_path, config).unwrap();
+        config_path
+    }
+
+    #[test]
+    fn test_contract_with_app_costs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let hierarchy_file = tmp.path().join("hierarchy.json");
+        let algorithm = format!(
+            "type = \"ch\"\nhierarchy_input_file = \"{}\"",
+            hierarchy_file.display()
+        );
+        let config_path = write_config(tmp.path(), &algorithm, 0.0);
+        contract_graph(&config_path, &hierarchy_file, 100).unwrap();
+
+        // the route of least time, as with a dijkstra search of the same configuration
+        let ch_app = CompassApp::try_from(config_path.as_path()).unwrap();
+        let dijkstra_path = write_config(tmp.path(), "type = \"dijkstra\"", 0.0);
+        let dijkstra_app = CompassApp::try_from(dijkstra_path.as_path()).unwrap();
+        let query = serde_json::json!({"origin_vertex": 0, "destination_vertex": 2});
+        let ch_result = ch_app.run(vec![query.clone()], None).unwrap();
+        let dijkstra_result = dijkstra_app.run(vec![query], None).unwrap();
+        assert!(ch_result[0].get("error").is_none(), "{}", ch_result[0]);
+        assert_eq!(ch_result[0]["route"], dijkstra_result[0]["route"]);
+
+        // an app with other costs rejects the hierarchy
+        let other_path = write_config(tmp.path(), &algorithm, 1.0);
+        let error = CompassApp::try_from(other_path.as_path()).err().unwrap();
+        assert!(error.to_string().contains("rebuild it"), "{}", error);
+    }
+}
@@ -0,0 +1,170 @@
+use super::dem_raster::DemRasterSet;
+use super::graph_compile::read_graph_config;
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::compass::config::graph_builder::DefaultGraphBuilder;
+use geo::{Coord, LineString};
+use rayon::prelude::*;
+use routee_compass_core::model::unit::as_f64::AsF64;
+use routee_compass_core::util::fs::{fs_utils, read_utils};
+use routee_compass_core::util::geo::{geo_io_utils::parse_linestring, haversine};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// samples elevations from a set of DEM GeoTIFF tiles along the geometry of each
+/// edge and writes the grade of each edge as a decimal, one per line in edge id
+/// order, for use as the `grade_table_input_file` of a traversal model. the
+/// geometry file must have exactly one row per edge of the graph.
+///
+/// # Arguments
+/// * `config_path` - configuration TOML file with a `[graph]` section
+/// * `geometry_file` - edge geometries as WKT LINESTRINGs, in edge id order
+/// * `dem_files` - GeoTIFF elevation tiles in geographic coordinates
+/// * `output_file` - file to write the grades to
+/// * `sample_spacing_meters` - distance between elevation samples along each edge
+/// * `max_grade` - optional limit on the magnitude of each grade, as a decimal
+pub fn impute_grades(
+    config_path: &Path,
+    geometry_file: &Path,
+    dem_files: &[PathBuf],
+    output_file: &Path,
+    sample_spacing_meters: f64,
+    max_grade: Option<f64>,
+) -> Result<(), CompassAppError> {
+    if !(sample_spacing_meters > 0.0 && sample_spacing_meters.is_finite()) {
+        return Err(CompassAppError::InvalidInput(format!(
+            "sample spacing must be positive, found {}",
+            sample_spacing_meters
+        )));
+    }
+    if let Some(max) = max_grade {
+        if !(max >= 0.0 && max.is_finite()) {
+            return Err(CompassAppError::InvalidInput(format!(
+                "max grade must be a non-negative decimal, found {}",
+                max
+            )));
+        }
+    }
+    let graph = DefaultGraphBuilder::build(&read_graph_config(config_path)?)?;
+    let geometries: Box<[LineString<f32>]> =
+        read_utils::read_raw_file(geometry_file, parse_linestring, None)?;
+    if geometries.len() != graph.n_edges() {
+        return Err(CompassAppError::InvalidInput(format!(
+            "{:?} has {} geometries but the graph has {} edges",
+            geometry_file,
+            geometries.len(),
+            graph.n_edges()
+        )));
+    }
+    let dem = DemRasterSet::from_geotiffs(dem_files)?;
+
+    let grades = geometries
+        .par_iter()
+        .map(|line| edge_grade(line, &dem, sample_spacing_meters))
+        .collect::<Result<Vec<_>, _>>()?;
+    let n_missing = grades.iter().filter(|g| g.is_none()).count();
+    if n_missing > 0 {
+        log::warn!(
+            "{} of {} edges are not covered by the DEM and were given a grade of 0",
+            n_missing,
+            grades.len()
+        );
+    }
+
+    let mut writer = fs_utils::create_writer(output_file)?;
+    for grade in grades.into_iter() {
+        let grade = grade.unwrap_or(0.0);
+        let grade = match max_grade {
+            Some(max) => grade.clamp(-max, max),
+            None => grade,
+        };
+        writeln!(writer, "{:.4}", grade)?;
+    }
+    writer.flush()?;
+    log::info!(
+        "wrote grades of {} edges to {:?}",
+        graph.n_edges(),
+        output_file
+    );
+    Ok(())
+}
+
+/// the grade of an edge as a decimal, from a least squares fit of the elevations
+/// sampled along its geometry against the distance along it, which smooths out
+/// noise in the DEM. returns None when fewer than two samples have an elevation.
+pub fn edge_grade(
+    line: &LineString<f32>,
+    dem: &DemRasterSet,
+    sample_spacing_meters: f64,
+) -> Result<Option<f64>, CompassAppError> {
+    // distance from the start of the line to each of its points
+    let mut offsets = vec![0.0];
+    for segment in line.lines() {
+        let length = haversine::coord_distance_meters(&segment.start, &segment.end)
+            .map_err(CompassAppError::InternalError)?;
+        offsets.push(offsets[offsets.len() - 1] + length.as_f64());
+    }
+    let total = offsets[offsets.len() - 1];
+    if total == 0.0 {
+        return Ok(Some(0.0));
+    }
+
+    let n_samples = (total / sample_spacing_meters).ceil() as usize + 1;
+    let samples = (0..n_samples)
+        .filter_map(|i| {
+            let offset = total * i as f64 / (n_samples - 1) as f64;
+            let coord = point_at(line, &offsets, offset)?;
+            dem.elevation(&coord).map(|z| (offset, z))
+        })
+        .collect::<Vec<_>>();
+    if samples.len() < 2 {
+        return Ok(None);
+    }
+
+    let n = samples.len() as f64;
+    let mean_s = samples.iter().map(|(s, _)| s).sum::<f64>() / n;
+    let mean_z = samples.iter().map(|(_, z)| z).sum::<f64>() / n;
+    let (covariance, variance) = samples.iter().fold((0.0, 0.0), |(cov, var), (s, z)| {
+        (
+            cov + (s - mean_s) * (z - mean_z),
+            var + (s - mean_s).powi(2),
+        )
+    });
+    if variance == 0.0 {
+        return Ok(None);
+    }
+    Ok(Some(covariance / variance))
+}
+
+/// the point at a distance along a line, interpolated between its points
+fn point_at(line: &LineString<f32>, offsets: &[f64], offset: f64) -> Option<Coord<f32>> {
+    let idx = offsets.partition_point(|o| *o < offset).max(1);
+    let (start, end) = (line.0.get(idx - 1)?, line.0.get(idx)?);
+    let length = offsets[idx] - offsets[idx - 1];
+    let t = if length > 0.0 {
+        ((offset - offsets[idx - 1]) / length) as f32
+    } else {
+        0.0
+    };
+    Some(Coord {
+        x: start.x + (end.x - start.x) * t,
+        y: start.y + (end.y - start.y) * t,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_max_grade() {
+        // the arguments are checked before any file is read
+        let missing = Path::new("missing.toml");
+        for max_grade in [-0.1, f64::NAN] {
+            let result = impute_grades(missing, missing, &[], missing, 30.0, Some(max_grade));
+            match result {
+                Err(CompassAppError::InvalidInput(msg)) => assert!(msg.contains("max grade")),
+                other => panic!("expected invalid max grade, found {:?}", other.err()),
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::graph_compile::read_graph_config;
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::compass::config::graph_builder::DefaultGraphBuilder;
+use routee_compass_core::model::road_network::graph::Graph;
+use routee_compass_core::model::road_network::strongly_connected_components::strongly_connected_components;
+use routee_compass_core::model::unit::as_f64::AsF64;
+use serde::Serialize;
+use std::path::Path;
+
+/// summary of the structure of a road network graph, used to spot problems
+/// with a dataset such as islands of vertices or degenerate edges.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct GraphStats {
+    pub n_vertices: usize,
+    pub n_edges: usize,
+    /// number of strongly connected components
+    pub n_components: usize,
+    /// number of vertices in the largest strongly connected component
+    pub largest_component_size: usize,
+    /// vertices with no incident edges
+    pub isolated_vertices: usize,
+    /// edges which begin and end at the same vertex
+    pub self_loops: usize,
+    /// edges with a distance of zero or less
+    pub zero_length_edges: usize,
+    pub max_out_degree: usize,
+    pub mean_out_degree: f64,
+}
+
+impl GraphStats {
+    pub fn from_graph(graph: &Graph) -> GraphStats {
+        let components = strongly_connected_components(graph);
+        let largest_component_size = components.iter().map(|c| c.len()).max().unwrap_or(0);
+        let isolated_vertices = graph
+            .vertex_ids()
+            .filter(|v| graph.adj.degree(*v) == Some(0) && graph.rev.degree(*v) == Some(0))
+            .count();
+        let self_loops = graph
+            .edges
+            .iter()
+            .filter(|e| e.src_vertex_id == e.dst_vertex_id)
+            .count();
+        let zero_length_edges = graph
+            .edges
+            .iter()
+            .filter(|e| e.distance.as_f64() <= 0.0)
+            .count();
+        let max_out_degree = graph
+            .vertex_ids()
+            .filter_map(|v| graph.adj.degree(v))
+            .max()
+            .unwrap_or(0);
+        let mean_out_degree = if graph.n_vertices() == 0 {
+            0.0
+        } else {
+            graph.n_edges() as f64 / graph.n_vertices() as f64
+        };
+        GraphStats {
+            n_vertices: graph.n_vertices(),
+            n_edges: graph.n_edges(),
+            n_components: components.len(),
+            largest_component_size,
+            isolated_vertices,
+            self_loops,
+            zero_length_edges,
+            max_out_degree,
+            mean_out_degree,
+        }
+    }
+}
+
+/// loads the graph of a configuration file and summarizes its structure
+///
+/// # Arguments
+/// * `config_path` - configuration TOML file with a `[graph]` section
+pub fn graph_stats(config_path: &Path) -> Result<GraphStats, CompassAppError> {
+    let graph = DefaultGraphBuilder::build(&read_graph_config(config_path)?)?;
+    Ok(GraphStats::from_graph(&graph))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::property::{edge::Edge, vertex::Vertex};
+    use routee_compass_core::util::compact_ordered_hash_map::CompactOrderedHashMap;
+
+    #[test]
+    fn test_graph_stats() {
+        // a two-way street between 0 and 1, a one-way edge to 2, a self loop on 2
+        // and an isolated vertex 3
+        let vertices = (0..4).map(|i| Vertex::new(i, 0.0, 0.0)).collect::<Vec<_>>();
+        let edges = vec![
+            Edge::new(0, 0, 1, 10.0),
+            Edge::new(1, 1, 0, 10.0),
+            Edge::new(2, 1, 2, 10.0),
+            Edge::new(3, 2, 2, 0.0),
+        ];
+        let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        for edge in edges.iter() {
+            adj[edge.src_vertex_id.0].insert(edge.edge_id, edge.dst_vertex_id);
+            rev[edge.dst_vertex_id.0].insert(edge.edge_id, edge.src_vertex_id);
+        }
+        let graph = Graph {
+            adj: adj.into(),
+            rev: rev.into(),
+            edges: edges.into(),
+            vertices: vertices.into(),
+            geometries: None,
+            edge_attributes: None,
+        };
+        let stats = GraphStats::from_graph(&graph);
+        assert_eq!(stats.n_components, 3);
+        assert_eq!(stats.largest_component_size, 2);
+        assert_eq!(stats.isolated_vertices, 1);
+        assert_eq!(stats.self_loops, 1);
+        assert_eq!(stats.zero_length_edges, 1);
+        assert_eq!(stats.max_out_degree, 2);
+        assert_eq!(stats.mean_out_degree, 1.0);
+    }
+}
@@ -1,2 +1,10 @@
 pub mod cli_args;
+pub mod dem_raster;
+pub mod graph_command;
+pub mod graph_compile;
+pub mod graph_component_filter;
+pub mod graph_contract;
+pub mod graph_grade;
+pub mod graph_stats;
 pub mod run;
+pub mod validate;
@@ -1,16 +1,22 @@
-use super::cli_args::CliArgs;
+use super::cli_args::RouteArgs;
 use crate::app::compass::compass_app_ops as ops;
 use crate::app::compass::config::compass_configuration_error::CompassConfigurationError;
+use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
 use crate::app::compass::{
     compass_app::CompassApp, compass_app_error::CompassAppError,
     compass_json_extensions::CompassJsonExtensions, config::compass_app_builder::CompassAppBuilder,
+    error_taxonomy::ErrorTaxonomy, query_id,
+    response::response_output_policy::ResponseOutputPolicy,
 };
 use itertools::{Either, Itertools};
 use log::{debug, error};
 use serde_json::{json, Value};
-use std::io::BufRead;
+use std::io::{BufRead, Read};
 use std::{fs::File, io::BufReader, path::Path};
 
+/// the query file name which reads queries from stdin
+pub const STDIN_QUERY_FILE: &str = "-";
+
 /// runs CompassApp from the command line using the provided app builder and optional
 /// additional CompassApp configuration overwrites.
 ///
@@ -23,11 +29,12 @@ use std::{fs::File, io::BufReader, path::Path};
 /// After executing all queries, returns nothing, or returns an un-handled application error.
 /// Any user errors are logged and optionally written to an output file depending on the file io policy.
 pub fn command_line_runner(
-    args: &CliArgs,
+    args: &RouteArgs,
     builder: Option<CompassAppBuilder>,
     run_config: Option<&Value>,
 ) -> Result<(), CompassAppError> {
     args.validate()?;
+    let run_config = with_progress_flag(args, run_config);
 
     // build the app
     let builder_or_default = builder.unwrap_or_default();
@@ -40,39 +47,95 @@ pub fn command_line_runner(
             return Err(e);
         }
     };
+    let run_config = with_output_file(args, &compass_app, run_config)?;
+    let run_config = run_config.as_ref();
 
-    // read user file containing JSON query/queries
-    let query_file = File::open(args.query_file.clone()).map_err(|_e| {
-        CompassAppError::NoInputFile(format!("Could not find query file {}", args.query_file))
-    })?;
+    // read user file, or stdin, containing JSON query/queries
+    let query_input = open_query_input(&args.query_file)?;
 
     // execute queries on app
     match (args.chunksize, args.newline_delimited) {
-        (None, true) => Err(CompassAppError::InternalError(String::from(
-            "invalid argument combination should have been caught during CLI validation",
-        ))),
-        (None, false) => run_json(&query_file, &compass_app, run_config),
-        (Some(_), true) => {
+        (None, false) => run_json(query_input, &compass_app, run_config)?,
+        (_, true) => {
             let chunksize = args.get_chunksize_option()?;
-            run_newline_json(&query_file, chunksize, &compass_app, run_config)
+            run_newline_json(query_input, chunksize, &compass_app, run_config)?
         }
-        (Some(_), false) => Err(CompassAppError::InternalError(String::from(
-            "not yet implemented",
-        ))),
+        (Some(_), false) => {
+            return Err(CompassAppError::InternalError(String::from(
+                "not yet implemented",
+            )))
+        }
+    }
+
+    // the command line application shuts down once its queries have run
+    compass_app.finish()
+}
+
+/// applies the `--no-progress` flag to the run configuration
+fn with_progress_flag(args: &RouteArgs, run_config: Option<&Value>) -> Option<Value> {
+    if !args.no_progress {
+        return run_config.cloned();
+    }
+    let mut config = run_config.cloned().unwrap_or_else(|| json!({}));
+    config[CompassConfigurationField::Progress.to_str()] = json!(false);
+    Some(config)
+}
+
+/// opens the query file, or stdin when the file is `-`, so that queries can be piped
+/// in from another program without writing an intermediate file.
+fn open_query_input(query_file: &str) -> Result<Box<dyn BufRead>, CompassAppError> {
+    if query_file == STDIN_QUERY_FILE {
+        return Ok(Box::new(BufReader::new(std::io::stdin())));
+    }
+    let file = File::open(query_file).map_err(|_e| {
+        CompassAppError::NoInputFile(format!("Could not find query file {}", query_file))
+    })?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// reads queries from a JSON array, an object, an object with a `queries` array, or
+/// any sequence of these such as newline-delimited JSON with one query per line.
+fn read_queries(reader: impl Read) -> Result<Vec<Value>, CompassAppError> {
+    let mut queries = vec![];
+    for user_json in serde_json::Deserializer::from_reader(reader).into_iter::<Value>() {
+        queries.extend(
+            user_json
+                .map_err(CompassAppError::CodecError)?
+                .get_queries()?,
+        );
     }
+    Ok(queries)
 }
 
-/// parses a file as a valid JSON object and executes it as queries against
+/// applies the `--output` argument to the response output policy of the run configuration,
+/// or of the app when the run configuration does not set one
+fn with_output_file(
+    args: &RouteArgs,
+    compass_app: &CompassApp,
+    run_config: Option<Value>,
+) -> Result<Option<Value>, CompassAppError> {
+    let output = match &args.output {
+        None => return Ok(run_config),
+        Some(output) => output,
+    };
+    let field = CompassConfigurationField::ResponseOutputPolicy.to_str();
+    let mut config = run_config.unwrap_or_else(|| json!({}));
+    let policy = match config.get(field) {
+        Some(policy) => serde_json::from_value::<ResponseOutputPolicy>(policy.clone())?,
+        None => compass_app.response_output_policy.clone(),
+    };
+    config[field] = serde_json::to_value(policy.with_filename(output)?)?;
+    Ok(Some(config))
+}
+
+/// parses a JSON or newline-delimited JSON input and executes it as queries against
 /// the CompassApp.run command.
 fn run_json(
-    query_file: &File,
+    query_input: impl Read,
     compass_app: &CompassApp,
     run_config: Option<&Value>,
 ) -> Result<(), CompassAppError> {
-    let reader = BufReader::new(query_file);
-    let user_json: serde_json::Value =
-        serde_json::from_reader(reader).map_err(CompassAppError::CodecError)?;
-    let user_queries = user_json.get_queries()?;
+    let user_queries = read_queries(query_input)?;
     let results = compass_app.run(user_queries, run_config)?;
     for result in results.iter() {
         log_error(result);
@@ -80,56 +143,106 @@ fn run_json(
     Ok(())
 }
 
-/// parses a file as newline-delimited JSON which can be optionally chunked into sub-batches
+/// parses an input as newline-delimited JSON which can be optionally chunked into sub-batches
 /// and each sub-batch run as queries against the CompassApp.run command.
 /// chunksize should be >> the configured CompassApp parallelism (from TOML file) for best
 /// performance.
 fn run_newline_json(
-    query_file: &File,
+    query_input: impl BufRead,
     chunksize_option: Option<usize>,
     compass_app: &CompassApp,
     run_config: Option<&Value>,
 ) -> Result<(), CompassAppError> {
-    let reader = BufReader::new(query_file);
-    let iterator = reader.lines();
+    let iterator = query_input.lines().filter(|row| match row {
+        Ok(string) => !string.trim().is_empty(),
+        Err(_) => true,
+    });
     let chunksize = chunksize_option.unwrap_or(usize::MAX);
-    let chunks = iterator.chunks(chunksize);
 
+    // a single response sink is shared by all chunks so that responses are streamed
+    // into one output file as each chunk completes
+    let response_writer = compass_app.build_response_sink(run_config)?;
+    let metrics = compass_app.metrics_recorder();
+    let input_errors = compass_app.input_error_recorder();
+
+    let chunks = iterator.chunks(chunksize);
     for (iteration, chunk) in chunks.into_iter().enumerate() {
         debug!("executing batch {}", iteration + 1);
         // parse JSON output
-        let (chunk_queries, errors): (Vec<Value>, Vec<CompassAppError>) =
-            chunk.partition_map(|row| match row {
+        // ids are taken in line order, so that each row keeps its place in the input
+        let (chunk_queries, errors): (Vec<Value>, Vec<(Value, CompassAppError)>) = chunk
+            .partition_map(|row| match row {
                 Ok(string) => match serde_json::from_str(&string) {
-                    Ok(query) => Either::Left(query),
-                    Err(e) => Either::Right(CompassAppError::CompassConfigurationError(
-                        CompassConfigurationError::SerdeDeserializationError(e),
+                    Ok(mut query) => {
+                        query_id::assign_query_id(&mut query);
+                        Either::Left(query)
+                    }
+                    Err(e) => Either::Right((
+                        query_id::next_query_id(),
+                        CompassAppError::CompassConfigurationError(
+                            CompassConfigurationError::SerdeDeserializationError(e),
+                        ),
                     )),
                 },
-                Err(e) => Either::Right(CompassAppError::IOError(e)),
+                Err(e) => Either::Right((query_id::next_query_id(), CompassAppError::IOError(e))),
             });
 
         // run Compass on this chunk of queries
-        for result in compass_app.run(chunk_queries, run_config)?.iter() {
+        let results = compass_app.run_with_response_sink(
+            chunk_queries,
+            run_config,
+            &response_writer,
+            metrics.as_ref(),
+            &input_errors,
+        )?;
+        for result in results.iter() {
             log_error(result)
         }
 
         // report JSON parsing errors
-        for error in errors {
-            let error_json = json!({
+        for (id, error) in errors {
+            let mut error_json = json!({
+                "query_id": id,
                 "request": "failed to parse",
-                "error": error.to_string()
+                "error": error.error_detail().to_json()
             });
-            log_error(&error_json)
+            log_error(&error_json);
+            response_writer.write_response(&mut error_json)?;
         }
     }
 
+    let output_files = response_writer.close()?;
+    if !output_files.is_empty() {
+        log::info!("responses written to {}", output_files);
+    }
+    compass_app.record_output_files(&output_files)?;
+    if let Some(recorder) = &metrics {
+        recorder.report()?;
+    }
+    input_errors.report()?;
     Ok(())
 }
 
 fn log_error(result: &Value) {
     if let Some(error) = result.get("error") {
-        let error_string = error.to_string().replace("\\n", "\n");
+        let error_string = match error.get("message") {
+            Some(Value::String(message)) => message.clone(),
+            _ => error.to_string().replace("\\n", "\n"),
+        };
         error!("Error: {}", error_string);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_queries_json_and_ndjson() {
+        let array = r#"[{"origin_vertex": 0}, {"origin_vertex": 1}]"#;
+        let ndjson = "{\"origin_vertex\": 0}\n\n{\"origin_vertex\": 1}\n";
+        let expected = vec![json!({"origin_vertex": 0}), json!({"origin_vertex": 1})];
+        assert_eq!(read_queries(array.as_bytes()).unwrap(), expected);
+        assert_eq!(read_queries(ndjson.as_bytes()).unwrap(), expected);
+    }
+}
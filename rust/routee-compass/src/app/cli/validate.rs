@@ -0,0 +1,151 @@
+use super::cli_args::ValidateArgs;
+use super::graph_stats::GraphStats;
+use crate::app::compass::{
+    compass_app::CompassApp,
+    compass_app_error::CompassAppError,
+    compass_app_ops as ops,
+    compass_json_extensions::CompassJsonExtensions,
+    config::{compass_app_builder::CompassAppBuilder, config_schema::check_config},
+};
+use crate::plugin::input::input_plugin_ops::apply_input_plugins;
+use serde::Serialize;
+use serde_json::Value;
+use std::{fs::File, io::BufReader, path::Path};
+
+/// the findings of a dataset check
+#[derive(Serialize, Debug)]
+pub struct ValidationReport {
+    pub graph: GraphStats,
+    /// problems with the configuration and graph which do not prevent the app from running
+    pub warnings: Vec<String>,
+    pub queries_checked: usize,
+    /// error responses of queries rejected by the input plugins
+    pub query_errors: Vec<Value>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self, strict: bool) -> bool {
+        self.query_errors.is_empty() && (!strict || self.warnings.is_empty())
+    }
+}
+
+/// builds the app of a configuration, which checks that all of its files load, and
+/// reports problems with the graph and with queries that would fail before their search.
+///
+/// # Arguments
+/// * `args`    - command line arguments for this check
+/// * `builder` - optional builder instance to overwrite the default
+///
+/// # Returns
+/// Prints the report as JSON, and returns an error if any query was rejected, or under
+/// `--strict`, if there are any warnings.
+pub fn validate_dataset(
+    args: &ValidateArgs,
+    builder: Option<CompassAppBuilder>,
+) -> Result<(), CompassAppError> {
+    let config = ops::read_config_from_file(Path::new(&args.config_file))?;
+    let builder = builder.unwrap_or_default();
+    let config_warnings = check_config(&config.clone().try_deserialize()?, &builder, false);
+    let app = CompassApp::try_from((&config, &builder))?;
+    let queries = match &args.query_file {
+        None => vec![],
+        Some(query_file) => {
+            let file = File::open(query_file).map_err(|_e| {
+                CompassAppError::NoInputFile(format!("Could not find query file {}", query_file))
+            })?;
+            let json: Value = serde_json::from_reader(BufReader::new(file))?;
+            json.get_queries()?
+        }
+    };
+    let mut report = validation_report(&app, &queries);
+    report.warnings.extend(
+        config_warnings
+            .iter()
+            .map(|w| format!("configuration {}", w)),
+    );
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if report.is_valid(args.strict) {
+        Ok(())
+    } else {
+        Err(CompassAppError::InvalidInput(format!(
+            "dataset check found {} query errors and {} warnings",
+            report.query_errors.len(),
+            report.warnings.len()
+        )))
+    }
+}
+
+/// checks the graph of an app and runs each query through its input plugins
+pub fn validation_report(app: &CompassApp, queries: &[Value]) -> ValidationReport {
+    let graph = GraphStats::from_graph(&app.search_app.directed_graph);
+    let query_errors = queries
+        .iter()
+        .flat_map(|q| {
+            let (_, errors) = apply_input_plugins(
+                q,
+                &app.input_plugins,
+                app.input_error_config.input_error_policy,
+            );
+            errors
+        })
+        .collect();
+    ValidationReport {
+        warnings: graph_warnings(&graph),
+        graph,
+        queries_checked: queries.len(),
+        query_errors,
+    }
+}
+
+fn graph_warnings(graph: &GraphStats) -> Vec<String> {
+    let mut warnings = vec![];
+    let unreachable = graph.n_vertices - graph.largest_component_size;
+    if unreachable > 0 {
+        warnings.push(format!(
+            "{} of {} vertices are outside of the largest strongly connected component, so some queries will find no path. see `routee-compass graph largest-component`",
+            unreachable, graph.n_vertices
+        ));
+    }
+    if graph.isolated_vertices > 0 {
+        warnings.push(format!(
+            "{} vertices have no edges",
+            graph.isolated_vertices
+        ));
+    }
+    if graph.self_loops > 0 {
+        warnings.push(format!(
+            "{} edges begin and end at the same vertex",
+            graph.self_loops
+        ));
+    }
+    if graph.zero_length_edges > 0 {
+        warnings.push(format!(
+            "{} edges have a distance of zero",
+            graph.zero_length_edges
+        ));
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_warnings() {
+        let graph = GraphStats {
+            n_vertices: 10,
+            n_edges: 12,
+            n_components: 2,
+            largest_component_size: 8,
+            isolated_vertices: 1,
+            self_loops: 0,
+            zero_length_edges: 0,
+            max_out_degree: 3,
+            mean_out_degree: 1.2,
+        };
+        let warnings = graph_warnings(&graph);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("2 of 10 vertices"));
+    }
+}
@@ -1,5 +1,14 @@
+use super::error_taxonomy::{ErrorComponent, ErrorTaxonomy};
+use super::input_error_report::{InputErrorConfig, InputErrorRecorder};
+use super::metrics::{MetricsConfig, MetricsRecorder, QueryMetrics};
+use super::query_id;
+use super::reproducibility::{Reproducibility, ReproducibilityConfig};
+use super::response::output_filename::OutputFilenameContext;
 use super::response::response_output_policy::ResponseOutputPolicy;
 use super::response::response_sink::ResponseSink;
+use super::result_cache::{ResultCache, ResultCacheConfig};
+use super::run_progress::{ProgressCallback, RunProgress};
+use super::shared_origin::{SharedOriginBatching, SharedOriginConfig};
 use super::{
     compass_app_ops as ops, config::compass_app_builder::CompassAppBuilder,
     search_orientation::SearchOrientation,
@@ -11,36 +20,47 @@ use crate::{
             compass_app_error::CompassAppError,
             compass_input_field::CompassInputField,
             config::{
+                compass_configuration_error::CompassConfigurationError,
                 compass_configuration_field::CompassConfigurationField,
-                config_json_extension::ConfigJsonExtensions,
+                config_json_extension::ConfigJsonExtensions, config_schema::validate_config,
                 cost_model::cost_model_builder::CostModelBuilder,
                 graph_builder::DefaultGraphBuilder,
                 termination_model_builder::TerminationModelBuilder,
+                traversal_model::custom_state_builder::CustomStateBuilder,
             },
         },
         search::{search_app::SearchApp, search_app_result::SearchAppResult},
     },
     plugin::{
-        input::{input_plugin::InputPlugin, input_plugin_ops as in_ops},
+        input::{
+            input_error_policy::InputErrorPolicy, input_plugin::InputPlugin,
+            input_plugin_ops as in_ops,
+        },
         output::{output_plugin::OutputPlugin, output_plugin_ops as out_ops},
     },
 };
 use chrono::{Duration, Local};
-use config::Config;
-use itertools::{Either, Itertools};
-use kdam::{Bar, BarExt};
+use config::{Config, ConfigError};
+use geo::LineString;
+use itertools::Itertools;
 use rayon::{current_num_threads, prelude::*};
+use routee_compass_core::algorithm::search::cancellation_token::CancellationToken;
+use routee_compass_core::algorithm::search::charging::charging_config::ChargingConfig;
+use routee_compass_core::algorithm::search::heuristic::heuristic_config::HeuristicConfig;
 use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use routee_compass_core::model::road_network::edge_id::EdgeId;
 use routee_compass_core::model::state::state_model::StateModel;
+use routee_compass_core::model::unit::{Speed, SpeedUnit};
 use routee_compass_core::{
     algorithm::search::search_algorithm::SearchAlgorithm,
     util::duration_extension::DurationExtension,
 };
 use serde_json::Value;
-use std::rc::Rc;
+use std::collections::HashMap;
+use std::time::Instant;
 use std::{
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::Arc,
 };
 
 /// Instance of RouteE Compass as an application.
@@ -55,10 +75,22 @@ pub struct CompassApp {
     pub search_app: SearchApp,
     pub input_plugins: Vec<Arc<dyn InputPlugin>>,
     pub output_plugins: Vec<Arc<dyn OutputPlugin>>,
+    /// how the queries which input plugins fail to process are handled and reported
+    pub input_error_config: InputErrorConfig,
     pub parallelism: usize,
+    pub chunk_size: Option<usize>,
     pub search_orientation: SearchOrientation,
     pub response_persistence_policy: ResponsePersistencePolicy,
     pub response_output_policy: ResponseOutputPolicy,
+    pub result_cache: Option<ResultCache>,
+    pub shared_origin: Option<SharedOriginBatching>,
+    /// each run records its metrics with its own recorder built from this
+    /// configuration, so that concurrent runs do not mix or drop each other's metrics
+    pub metrics: Option<MetricsConfig>,
+    pub reproducibility: Option<Reproducibility>,
+    pub progress: bool,
+    /// file stem of the configuration file, used in templated output filenames
+    pub config_name: String,
 }
 
 impl CompassApp {
@@ -126,7 +158,7 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
     /// # Arguments
     ///
     /// * `pair` - a tuple containing a config object (such as a parsed TOML file) and
-    ///            a [`super::config::compass_app_builder::CompassAppBuilder`] instance
+    ///   a [`super::config::compass_app_builder::CompassAppBuilder`] instance
     ///
     /// # Returns
     ///
@@ -139,24 +171,90 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
         let root_config_path =
             config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
 
-        let config_json = config
-            .clone()
-            .try_deserialize::<serde_json::Value>()?
-            .normalize_file_paths(&"", &root_config_path)?;
+        let config_name = root_config_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        // check the whole configuration before loading any data
+        let raw_config_json = config.clone().try_deserialize::<serde_json::Value>()?;
+        let strict_config = raw_config_json
+            .get_config_serde_optional::<bool>(&CompassConfigurationField::StrictConfig, &"TOML")?
+            .unwrap_or(false);
+        for warning in validate_config(&raw_config_json, builder, strict_config)? {
+            log::warn!("configuration {}", warning);
+        }
+
+        let config_json = raw_config_json.normalize_file_paths(&"", &root_config_path)?;
 
         let search_algorithm: SearchAlgorithm =
             config_json.get_config_serde(&CompassConfigurationField::Algorithm, &"TOML")?;
 
-        let state_model = match config_json.get(&CompassConfigurationField::State.to_string()) {
+        let state_model = match config_json.get(CompassConfigurationField::State.to_string()) {
             Some(state_config) => Arc::new(StateModel::try_from(state_config)?),
             None => Arc::new(StateModel::empty()),
         };
 
+        // build graph, which some models read, such as for its edge count
+        let graph_start = Local::now();
+        let graph_params =
+            config_json.get_config_section(CompassConfigurationField::Graph, &"TOML")?;
+        let graph = Arc::new(DefaultGraphBuilder::build(&graph_params)?);
+        let graph_duration = (Local::now() - graph_start)
+            .to_std()
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        log::info!(
+            "finished reading graph with duration {}",
+            graph_duration.hhmmss()
+        );
+
+        let graph_bytes = allocative::size_of_unique_allocated_data(graph.as_ref());
+        log::info!("graph size: {} GB", graph_bytes as f64 / 1e9);
+
+        #[cfg(debug_assertions)]
+        {
+            use std::io::Write;
+
+            log::debug!("Building flamegraph for graph memory usage..");
+
+            let mut flamegraph = allocative::FlameGraphBuilder::default();
+            flamegraph.visit_root(graph.as_ref());
+            let output = flamegraph.finish_and_write_flame_graph();
+
+            let outdir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("target")
+                .join("flamegraph");
+
+            if !outdir.exists() {
+                std::fs::create_dir(&outdir).unwrap();
+            }
+
+            let outfile = outdir.join("graph_memory_flamegraph.out");
+
+            log::debug!("writing graph flamegraph to {:?}", outfile);
+
+            let mut output_file = std::fs::File::create(outfile).unwrap();
+            output_file.write_all(output.as_bytes()).unwrap();
+        }
+
         // build traversal model
         let traversal_start = Local::now();
         let traversal_params =
             config_json.get_config_section(CompassConfigurationField::Traversal, &"TOML")?;
         let traversal_model_service = builder.build_traversal_model_service(&traversal_params)?;
+        let traversal_model_service =
+            match config_json.get(CompassConfigurationField::CustomState.to_str()) {
+                Some(custom_state_params) => CustomStateBuilder {}
+                    .build(
+                        traversal_model_service,
+                        custom_state_params,
+                        &graph,
+                        &state_model,
+                    )
+                    .map_err(CompassConfigurationError::TraversalModelError)?,
+                None => traversal_model_service,
+            };
         let traversal_duration = (Local::now() - traversal_start)
             .to_std()
             .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
@@ -181,14 +279,15 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
         // build utility model
         let cost_params =
             config_json.get_config_section(CompassConfigurationField::Cost, &"TOML")?;
-        let cost_model_service = CostModelBuilder {}.build(&cost_params)?;
+        let cost_model_service = CostModelBuilder {}.build(&cost_params, &graph)?;
 
         // build frontier model
         let frontier_start = Local::now();
         let frontier_params =
             config_json.get_config_section(CompassConfigurationField::Frontier, &"TOML")?;
 
-        let frontier_model_service = builder.build_frontier_model_service(&frontier_params)?;
+        let frontier_model_service =
+            builder.build_frontier_model_service(&frontier_params, graph.clone())?;
 
         let frontier_duration = (Local::now() - frontier_start)
             .to_std()
@@ -198,56 +297,26 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
             frontier_duration.hhmmss()
         );
 
+        // contraction hierarchies are built from static edge weights, so a query
+        // cannot respect edge restrictions or turn costs that the hierarchy ignores,
+        // and the weights must come from the same models as the costs of this app
+        if let SearchAlgorithm::ContractionHierarchy { hierarchy } = &search_algorithm {
+            validate_ch_model_type(&access_params, "access", "no_access_model")?;
+            validate_ch_model_type(&frontier_params, "frontier", "no_restriction")?;
+            validate_ch_cost_configuration(
+                hierarchy.cost_configuration.as_ref(),
+                &ch_cost_configuration(&raw_config_json),
+            )?;
+        }
+
         // build termination model
         let termination_model_json =
             config_json.get_config_section(CompassConfigurationField::Termination, &"TOML")?;
         let termination_model = TerminationModelBuilder::build(&termination_model_json, None)?;
-
-        // build graph
-        let graph_start = Local::now();
-        let graph_params =
-            config_json.get_config_section(CompassConfigurationField::Graph, &"TOML")?;
-        let graph = DefaultGraphBuilder::build(&graph_params)?;
-        let graph_duration = (Local::now() - graph_start)
-            .to_std()
-            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
-        log::info!(
-            "finished reading graph with duration {}",
-            graph_duration.hhmmss()
-        );
-
-        let graph_bytes = allocative::size_of_unique_allocated_data(&graph);
-        log::info!("graph size: {} GB", graph_bytes as f64 / 1e9);
-
-        #[cfg(debug_assertions)]
-        {
-            use std::io::Write;
-
-            log::debug!("Building flamegraph for graph memory usage..");
-
-            let mut flamegraph = allocative::FlameGraphBuilder::default();
-            flamegraph.visit_root(&graph);
-            let output = flamegraph.finish_and_write_flame_graph();
-
-            let outdir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-                .join("..")
-                .join("target")
-                .join("flamegraph");
-
-            if !outdir.exists() {
-                std::fs::create_dir(&outdir).unwrap();
-            }
-
-            let outfile = outdir.join("graph_memory_flamegraph.out");
-
-            log::debug!("writing graph flamegraph to {:?}", outfile);
-
-            let mut output_file = std::fs::File::create(outfile).unwrap();
-            output_file.write_all(output.as_bytes()).unwrap();
-        }
+        log::info!("app termination model: {:?}", termination_model);
 
         // build search app
-        let search_app: SearchApp = SearchApp::new(
+        let mut search_app: SearchApp = SearchApp::new(
             search_algorithm,
             graph,
             state_model,
@@ -258,13 +327,49 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
             termination_model,
         );
 
+        // build precomputed A* heuristic
+        let algorithm_params =
+            config_json.get_config_section(CompassConfigurationField::Algorithm, &"TOML")?;
+        let heuristic_config: Option<HeuristicConfig> = algorithm_params
+            .get_config_serde_optional(&"heuristic", &CompassConfigurationField::Algorithm)?;
+        if let Some(heuristic_config) = heuristic_config {
+            let heuristic_start = Local::now();
+            let si = search_app
+                .build_search_instance(&heuristic_config.query(), &CancellationToken::default())?;
+            let heuristic = heuristic_config.build(&si)?;
+            search_app.heuristic = Some(Arc::new(heuristic));
+            let heuristic_duration = to_std(Local::now() - heuristic_start)?;
+            log::info!(
+                "finished building heuristic with duration {}",
+                heuristic_duration.hhmmss()
+            );
+        }
+
+        if let Some(name) = algorithm_params.get_config_serde_optional::<String>(
+            &"time_feature_name",
+            &CompassConfigurationField::Algorithm,
+        )? {
+            search_app.time_feature_name = name;
+        }
+
+        // load chargers for inserting charging stops
+        let charging_config: Option<ChargingConfig> = algorithm_params
+            .get_config_serde_optional(&"charging", &CompassConfigurationField::Algorithm)?;
+        if let Some(charging_config) = charging_config {
+            let charging_network = charging_config.build()?;
+            search_app.charging_network = Some(Arc::new(charging_network));
+        }
+
         // build plugins
         let plugins_start = Local::now();
         let plugins_config =
             config_json.get_config_section(CompassConfigurationField::Plugins, &"TOML")?;
 
-        let input_plugins = builder.build_input_plugins(&plugins_config)?;
-        let output_plugins = builder.build_output_plugins(&plugins_config)?;
+        let input_plugins =
+            builder.build_input_plugins(&plugins_config, search_app.directed_graph.clone())?;
+        let output_plugins =
+            builder.build_output_plugins(&plugins_config, search_app.directed_graph.clone())?;
+        let input_error_config: InputErrorConfig = serde_json::from_value(plugins_config)?;
 
         let plugins_duration = to_std(Local::now() - plugins_start)?;
         log::info!(
@@ -274,6 +379,16 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
 
         // other parameters
         let parallelism = config.get::<usize>(CompassConfigurationField::Parallelism.to_str())?;
+        let chunk_size = match config.get::<usize>(CompassConfigurationField::ChunkSize.to_str()) {
+            Ok(size) => Some(validate_chunk_size(size)?),
+            Err(ConfigError::NotFound(_)) => None,
+            Err(e) => return Err(CompassAppError::ConfigError(e)),
+        };
+        let progress = match config.get::<bool>(CompassConfigurationField::Progress.to_str()) {
+            Ok(progress) => progress,
+            Err(ConfigError::NotFound(_)) => true,
+            Err(e) => return Err(CompassAppError::ConfigError(e)),
+        };
         let search_orientation = config
             .get::<SearchOrientation>(CompassConfigurationField::SearchOrientation.to_str())?;
         let response_persistence_policy = config.get::<ResponsePersistencePolicy>(
@@ -282,10 +397,49 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
         let response_output_policy = config.get::<ResponseOutputPolicy>(
             CompassConfigurationField::ResponseOutputPolicy.to_str(),
         )?;
+        let result_cache = match config
+            .get::<ResultCacheConfig>(CompassConfigurationField::ResultCache.to_str())
+        {
+            Ok(cache_config) => Some(ResultCache::new(&cache_config)?),
+            Err(ConfigError::NotFound(_)) => None,
+            Err(e) => return Err(CompassAppError::ConfigError(e)),
+        };
+        let shared_origin = match config
+            .get::<SharedOriginConfig>(CompassConfigurationField::SharedOrigin.to_str())
+        {
+            Ok(shared_origin_config) => {
+                let vertex_oriented = matches!(search_orientation, SearchOrientation::Vertex);
+                if !vertex_oriented || !search_app.supports_shared_origin() {
+                    return Err(CompassAppError::InvalidInput(String::from(
+                        "shared_origin requires a vertex-oriented a* or dijkstra search without charging stops",
+                    )));
+                }
+                Some(SharedOriginBatching::new(&shared_origin_config))
+            }
+            Err(ConfigError::NotFound(_)) => None,
+            Err(e) => return Err(CompassAppError::ConfigError(e)),
+        };
+
+        let metrics = match config.get::<MetricsConfig>(CompassConfigurationField::Metrics.to_str())
+        {
+            Ok(metrics_config) => Some(metrics_config),
+            Err(ConfigError::NotFound(_)) => None,
+            Err(e) => return Err(CompassAppError::ConfigError(e)),
+        };
+        let reproducibility = match config
+            .get::<ReproducibilityConfig>(CompassConfigurationField::Reproducibility.to_str())
+        {
+            Ok(reproducibility_config) => {
+                Some(Reproducibility::new(&reproducibility_config, &config_json)?)
+            }
+            Err(ConfigError::NotFound(_)) => None,
+            Err(e) => return Err(CompassAppError::ConfigError(e)),
+        };
 
         log::info!(
-            "additional parameters - parallelism={}, search orientation={:?}",
+            "additional parameters - parallelism={}, chunk size={:?}, search orientation={:?}",
             parallelism,
+            chunk_size,
             search_orientation
         );
 
@@ -293,10 +447,18 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
             search_app,
             input_plugins,
             output_plugins,
+            input_error_config,
             parallelism,
+            chunk_size,
             search_orientation,
             response_persistence_policy,
             response_output_policy,
+            result_cache,
+            shared_origin,
+            metrics,
+            reproducibility,
+            progress,
+            config_name,
         })
     }
 }
@@ -324,6 +486,250 @@ impl CompassApp {
         &self,
         queries: Vec<serde_json::Value>,
         config: Option<&serde_json::Value>,
+    ) -> Result<Vec<serde_json::Value>, CompassAppError> {
+        self.run_with_progress(queries, config, None)
+    }
+
+    /// runs a set of queries as in [`CompassApp::run`], also reporting the progress of
+    /// each stage of the run to an optional callback.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    /// * `config` - configuration for this run batch which may override default configurations
+    /// * `progress_callback` - called every `interval` completed queries of each stage
+    pub fn run_with_progress(
+        &self,
+        queries: Vec<serde_json::Value>,
+        config: Option<&serde_json::Value>,
+        progress_callback: Option<&ProgressCallback>,
+    ) -> Result<Vec<serde_json::Value>, CompassAppError> {
+        self.run_cancellable(
+            queries,
+            config,
+            progress_callback,
+            &CancellationToken::default(),
+        )
+    }
+
+    /// runs a set of queries as in [`CompassApp::run_with_progress`], which may be aborted
+    /// from another thread, such as when the client of a server disconnects. once the token
+    /// is cancelled, running searches stop at their next iteration and every remaining
+    /// query fails with a `CANCELLED` error, so that the run returns promptly.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    /// * `config` - configuration for this run batch which may override default configurations
+    /// * `progress_callback` - called every `interval` completed queries of each stage
+    /// * `cancellation` - aborts the run when cancelled
+    pub fn run_cancellable(
+        &self,
+        mut queries: Vec<serde_json::Value>,
+        config: Option<&serde_json::Value>,
+        progress_callback: Option<&ProgressCallback>,
+        cancellation: &CancellationToken,
+    ) -> Result<Vec<serde_json::Value>, CompassAppError> {
+        query_id::assign_query_ids(&mut queries);
+        let chunk_size: Option<usize> = get_optional_run_config(
+            &CompassConfigurationField::ChunkSize.to_str(),
+            &"run configuration",
+            config,
+        )?
+        .or(self.chunk_size);
+        let response_writer = self.build_response_sink(config)?;
+        let cache_counts_before = self.result_cache_counts();
+        let metrics = self.metrics_recorder();
+        let input_errors = self.input_error_recorder();
+        let run_result = match chunk_size {
+            None => self.run_batch(
+                queries,
+                config,
+                &response_writer,
+                metrics.as_ref(),
+                &input_errors,
+                progress_callback,
+                cancellation,
+            )?,
+            Some(size) => {
+                // run each chunk to completion before starting the next, so that only one
+                // chunk of processed queries and search results is held in memory at a time
+                let size = validate_chunk_size(size)?;
+                let n_chunks = queries.len().div_ceil(size);
+                let mut run_result = vec![];
+                for (idx, chunk) in queries.into_iter().chunks(size).into_iter().enumerate() {
+                    log::info!("running query chunk {} of {}", idx + 1, n_chunks);
+                    let chunk_queries = chunk.collect::<Vec<_>>();
+                    let chunk_result = self.run_batch(
+                        chunk_queries,
+                        config,
+                        &response_writer,
+                        metrics.as_ref(),
+                        &input_errors,
+                        progress_callback,
+                        cancellation,
+                    )?;
+                    run_result.extend(chunk_result);
+                    response_writer.flush()?;
+                }
+                run_result
+            }
+        };
+        let output_files = response_writer.close()?;
+        if !output_files.is_empty() {
+            log::info!("responses written to {}", output_files);
+        }
+        self.record_output_files(&output_files)?;
+        if let (Some((hits_before, misses_before)), Some((hits, misses))) =
+            (cache_counts_before, self.result_cache_counts())
+        {
+            log::info!(
+                "result cache: {} hits and {} misses",
+                hits - hits_before,
+                misses - misses_before
+            );
+        }
+        if let Some(recorder) = &metrics {
+            recorder.report()?;
+        }
+        input_errors.report()?;
+        Ok(run_result)
+    }
+
+    /// lets each output plugin complete the output it aggregates over every query the
+    /// app has run, such as a batch summary, and writes the reproducibility manifest.
+    /// called once when the app shuts down, such as at the end of a command line run or
+    /// when the server stops, rather than after each run, so that the output of many
+    /// runs is written once.
+    pub fn finish(&self) -> Result<(), CompassAppError> {
+        for plugin in self.output_plugins.iter() {
+            plugin.finish()?;
+        }
+        if let Some(reproducibility) = &self.reproducibility {
+            reproducibility.write_manifest()?;
+        }
+        Ok(())
+    }
+
+    /// a new recorder for the metrics of a run, when metrics are enabled. a run
+    /// reports its own recorder once all of its queries have finished.
+    pub fn metrics_recorder(&self) -> Option<MetricsRecorder> {
+        self.metrics.as_ref().map(MetricsRecorder::new)
+    }
+
+    /// a new recorder for the queries which input plugins fail to process during a
+    /// run. a run reports its own recorder once all of its queries have finished.
+    pub fn input_error_recorder(&self) -> InputErrorRecorder {
+        InputErrorRecorder::new(&self.input_error_config)
+    }
+
+    /// records the comma-separated files a run wrote its responses to in the
+    /// reproducibility manifest. does nothing unless the reproducibility mode is enabled.
+    pub fn record_output_files(&self, output_files: &str) -> Result<(), CompassAppError> {
+        if let Some(reproducibility) = &self.reproducibility {
+            reproducibility.record_output_files(output_files)?;
+        }
+        Ok(())
+    }
+
+    /// the number of hits and misses of the result cache since the app was built,
+    /// or None when there is no result cache
+    pub fn result_cache_counts(&self) -> Option<(usize, usize)> {
+        self.result_cache.as_ref().map(|c| (c.hits(), c.misses()))
+    }
+
+    /// replaces the speeds of some edges in the running app, as with
+    /// [`SearchApp::update_edge_speeds`], and drops any cached results which
+    /// were found with the old speeds.
+    pub fn update_edge_speeds(
+        &self,
+        speeds: &[(EdgeId, Speed)],
+        speed_unit: &SpeedUnit,
+    ) -> Result<(), CompassAppError> {
+        self.search_app.update_edge_speeds(speeds, speed_unit)?;
+        if let Some(cache) = &self.result_cache {
+            cache.clear()?;
+        }
+        Ok(())
+    }
+
+    /// the geometry of an edge, from the geometries loaded with the graph, or else
+    /// from the first output plugin which loads edge geometries, such as the traversal
+    /// plugin.
+    pub fn edge_geometry(&self, edge_id: EdgeId) -> Result<&LineString<f32>, CompassAppError> {
+        let geometries = self
+            .search_app
+            .directed_graph
+            .geometries()
+            .ok()
+            .map(|g| g.as_slice())
+            .or_else(|| self.output_plugins.iter().find_map(|p| p.edge_geometries()))
+            .ok_or_else(|| {
+                CompassAppError::InvalidInput(String::from(
+                    "no edge geometries are loaded, add a geometry_input_file to the [graph] section",
+                ))
+            })?;
+        geometries.get(edge_id.as_usize()).ok_or_else(|| {
+            CompassAppError::InvalidInput(format!("no geometry found for edge id {}", edge_id))
+        })
+    }
+
+    /// builds the destination for responses from the response output policy,
+    /// which may be overridden by the run configuration.
+    pub fn build_response_sink(
+        &self,
+        config: Option<&serde_json::Value>,
+    ) -> Result<ResponseSink, CompassAppError> {
+        let response_output_policy: ResponseOutputPolicy = get_optional_run_config(
+            &CompassConfigurationField::ResponseOutputPolicy.to_str(),
+            &"run configuration",
+            config,
+        )?
+        .unwrap_or_else(|| self.response_output_policy.clone());
+        response_output_policy.build(&OutputFilenameContext::new(&self.config_name))
+    }
+
+    /// runs a set of queries, writing each response to the provided sink as soon as it
+    /// completes. the sink is not closed, so that a single output file can be streamed
+    /// across many calls, such as each chunk of a large newline-delimited query file.
+    /// queries should already have their ids, see [`query_id::assign_query_ids`].
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    /// * `config` - configuration for this run batch which may override default configurations
+    /// * `response_writer` - destination for each response
+    /// * `metrics` - optional recorder of the metrics of the run, see [`CompassApp::metrics_recorder`]
+    /// * `input_errors` - recorder of the input plugin failures of the run, see [`CompassApp::input_error_recorder`]
+    pub fn run_with_response_sink(
+        &self,
+        queries: Vec<serde_json::Value>,
+        config: Option<&serde_json::Value>,
+        response_writer: &ResponseSink,
+        metrics: Option<&MetricsRecorder>,
+        input_errors: &InputErrorRecorder,
+    ) -> Result<Vec<serde_json::Value>, CompassAppError> {
+        self.run_batch(
+            queries,
+            config,
+            response_writer,
+            metrics,
+            input_errors,
+            None,
+            &CancellationToken::default(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn run_batch(
+        &self,
+        mut queries: Vec<serde_json::Value>,
+        config: Option<&serde_json::Value>,
+        response_writer: &ResponseSink,
+        metrics: Option<&MetricsRecorder>,
+        input_errors: &InputErrorRecorder,
+        progress_callback: Option<&ProgressCallback>,
+        cancellation: &CancellationToken,
     ) -> Result<Vec<serde_json::Value>, CompassAppError> {
         // allow the user to overwrite global configurations
         let parallelism: usize = get_optional_run_config(
@@ -338,60 +744,117 @@ impl CompassApp {
             config,
         )?
         .unwrap_or(self.response_persistence_policy);
-        let response_output_policy: ResponseOutputPolicy = get_optional_run_config(
-            &CompassConfigurationField::ResponseOutputPolicy.to_str(),
+        let progress: bool = get_optional_run_config(
+            &CompassConfigurationField::Progress.to_str(),
             &"run configuration",
             config,
         )?
-        .unwrap_or_else(|| self.response_output_policy.clone());
-        let response_writer = response_output_policy.build()?;
+        .unwrap_or(self.progress);
 
-        let input_pb = Bar::builder()
-            .total(queries.len())
-            .animation("fillup")
-            .desc("input plugins")
-            .build()
-            .map_err(CompassAppError::UXError)?;
-        let input_pb_shared = Arc::new(Mutex::new(input_pb));
+        if let Some(reproducibility) = &self.reproducibility {
+            for query in queries.iter_mut() {
+                reproducibility.seed_query(query)?;
+            }
+        }
+
+        let input_progress = RunProgress::new(queries.len(), "input plugins", progress)?
+            .with_callback(progress_callback);
 
         // input plugins need to be flattened, and queries that fail input processing need to be
-        // returned at the end.
-        let plugin_chunk_size = (queries.len() as f64 / self.parallelism as f64).ceil() as usize;
+        // returned at the end, unless the error policy stops the run.
+        let input_error_policy = input_errors.policy();
+        let plugin_chunk_size =
+            ((queries.len() as f64 / self.parallelism as f64).ceil() as usize).max(1);
         let input_plugin_result: (Vec<_>, Vec<_>) = queries
             .par_chunks(plugin_chunk_size)
             .map(|queries| {
-                let result: (Vec<Vec<Value>>, Vec<Value>) = queries
+                let result: (Vec<Vec<Value>>, Vec<Vec<Value>>) = queries
                     .iter()
                     .map(|q| {
-                        let inner_processed = apply_input_plugins(q, &self.input_plugins);
-                        if let Ok(mut pb_local) = input_pb_shared.lock() {
-                            let _ = pb_local.update(1);
+                        let input_start = Instant::now();
+                        let (processed, errors) =
+                            in_ops::apply_input_plugins(q, &self.input_plugins, input_error_policy);
+                        if let Some(recorder) = metrics {
+                            recorder.record_input_plugins(input_start.elapsed())?;
+                        }
+                        if input_error_policy == InputErrorPolicy::FailFast {
+                            if let Some(error) = errors.first() {
+                                return Err(CompassAppError::InvalidInput(format!(
+                                    "input plugins failed under the fail_fast input error policy: {}",
+                                    error
+                                )));
+                            }
                         }
-                        inner_processed
+                        input_errors.record(&processed, &errors)?;
+                        input_progress.update(1, !errors.is_empty() as usize);
+                        Ok((processed, errors))
                     })
-                    .partition_map(|r| match r {
-                        Ok(values) => Either::Left(values),
-                        Err(error_response) => Either::Right(error_response),
-                    });
+                    .collect::<Result<Vec<_>, CompassAppError>>()?
+                    .into_iter()
+                    .unzip();
 
-                result
+                Ok(result)
             })
+            .collect::<Result<Vec<_>, CompassAppError>>()?
+            .into_iter()
             .unzip();
 
-        println!();
+        input_progress.finish();
 
         // unpack input plugin results
         let (processed_inputs_nested, error_inputs_nested) = input_plugin_result;
+        // in the reproducibility mode, responses are held back and written in query order
+        let response_order = self.reproducibility.as_ref().map(|_| {
+            let processed = processed_inputs_nested.iter().flatten();
+            let errors = error_inputs_nested.iter().flatten();
+            Reproducibility::response_order(
+                processed
+                    .zip(errors)
+                    .map(|(p, e)| (p.as_slice(), e.as_slice())),
+            )
+        });
+        let search_response_writer = match response_order {
+            None => response_writer,
+            Some(_) => &ResponseSink::None,
+        };
         let processed_inputs: Vec<Value> = processed_inputs_nested
             .into_iter()
             .flatten()
             .flatten()
             .collect();
-        let load_balanced_inputs =
-            ops::apply_load_balancing_policy(&processed_inputs, parallelism, 1.0)?;
-        let error_inputs: Vec<Value> = error_inputs_nested.into_iter().flatten().collect();
+        // queries which share an origin are kept in the same batch so that they can share a search
+        let load_balanced_inputs = match &self.shared_origin {
+            None => ops::apply_load_balancing_policy(&processed_inputs, parallelism, 1.0)?,
+            Some(shared_origin) => {
+                let groups = shared_origin.group(&processed_inputs.iter().collect::<Vec<_>>());
+                log::info!(
+                    "{} queries share a search from {} origins",
+                    groups.iter().filter(|g| g.len() > 1).flatten().count(),
+                    groups.iter().filter(|g| g.len() > 1).count()
+                );
+                ops::apply_grouped_load_balancing_policy(groups, parallelism, 1.0)?
+            }
+        };
+        let mut error_inputs: Vec<Value> = error_inputs_nested
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect();
+        for error_input in error_inputs.iter_mut() {
+            let request = error_input.get("request").cloned().unwrap_or_default();
+            query_id::add_query_ids(&request, error_input);
+            if let Some(recorder) = metrics {
+                recorder.record_input_failure(error_input)?;
+            }
+            search_response_writer.write_response(error_input)?;
+        }
         if load_balanced_inputs.is_empty() {
-            return Ok(error_inputs);
+            return self.write_in_order(
+                error_inputs,
+                response_order.as_ref(),
+                response_writer,
+                response_persistence_policy,
+            );
         }
 
         log::info!(
@@ -411,37 +874,147 @@ impl CompassApp {
             .flatten()
             .collect::<Vec<_>>()
             .len();
-        let search_pb = Bar::builder()
-            .total(num_balanced_inputs)
-            .animation("fillup")
-            .desc("search")
-            .build()
-            .map_err(CompassAppError::UXError)?;
-        let search_pb_shared = Arc::new(Mutex::new(search_pb));
+        let search_progress = RunProgress::new(num_balanced_inputs, "search", progress)?
+            .with_callback(progress_callback);
 
         // run parallel searches as organized by the (optional) load balancing policy
         // across a thread pool managed by rayon
-        let run_query_result = match response_persistence_policy {
-            ResponsePersistencePolicy::PersistResponseInMemory => run_batch_with_responses(
-                &load_balanced_inputs,
-                &self.search_orientation,
-                &self.output_plugins,
-                &self.search_app,
-                &response_writer,
-                search_pb_shared,
-            )?,
-            ResponsePersistencePolicy::DiscardResponseFromMemory => run_batch_without_responses(
-                &load_balanced_inputs,
-                &self.search_orientation,
-                &self.output_plugins,
-                &self.search_app,
-                &response_writer,
-                search_pb_shared,
-            )?,
+        let run_query_result = match (response_persistence_policy, &response_order) {
+            (ResponsePersistencePolicy::PersistResponseInMemory, _) | (_, Some(_)) => {
+                run_batch_with_responses(
+                    &load_balanced_inputs,
+                    &self.search_orientation,
+                    &self.output_plugins,
+                    &self.search_app,
+                    self.result_cache.as_ref(),
+                    self.shared_origin.as_ref(),
+                    metrics,
+                    search_response_writer,
+                    &search_progress,
+                    cancellation,
+                )?
+            }
+            (ResponsePersistencePolicy::DiscardResponseFromMemory, None) => {
+                run_batch_without_responses(
+                    &load_balanced_inputs,
+                    &self.search_orientation,
+                    &self.output_plugins,
+                    &self.search_app,
+                    self.result_cache.as_ref(),
+                    self.shared_origin.as_ref(),
+                    metrics,
+                    response_writer,
+                    &search_progress,
+                    cancellation,
+                )?
+            }
         };
 
-        let run_result = run_query_result.chain(error_inputs).collect();
-        Ok(run_result)
+        search_progress.finish();
+        log::info!(
+            "{} of {} queries failed",
+            search_progress.errors() + input_progress.errors(),
+            queries.len()
+        );
+
+        self.write_in_order(
+            run_query_result.chain(error_inputs).collect(),
+            response_order.as_ref(),
+            response_writer,
+            response_persistence_policy,
+        )
+    }
+
+    /// in the reproducibility mode, sorts the responses of a batch into the order of its
+    /// queries and writes them, returning them unless they are discarded from memory.
+    /// otherwise, the responses have already been written and are returned as they are.
+    fn write_in_order(
+        &self,
+        responses: Vec<Value>,
+        response_order: Option<&HashMap<String, usize>>,
+        response_writer: &ResponseSink,
+        response_persistence_policy: ResponsePersistencePolicy,
+    ) -> Result<Vec<Value>, CompassAppError> {
+        let (reproducibility, order) = match (&self.reproducibility, response_order) {
+            (Some(reproducibility), Some(order)) => (reproducibility, order),
+            _ => return Ok(responses),
+        };
+        let mut responses = reproducibility.order_responses(responses, order);
+        for response in responses.iter_mut() {
+            response_writer.write_response(response)?;
+        }
+        match response_persistence_policy {
+            ResponsePersistencePolicy::PersistResponseInMemory => Ok(responses),
+            ResponsePersistencePolicy::DiscardResponseFromMemory => Ok(vec![]),
+        }
+    }
+}
+
+fn validate_chunk_size(chunk_size: usize) -> Result<usize, CompassAppError> {
+    if chunk_size == 0 {
+        Err(CompassAppError::InvalidInput(String::from(
+            "chunk_size must be positive",
+        )))
+    } else {
+        Ok(chunk_size)
+    }
+}
+
+/// confirms a model section used alongside a contraction hierarchy search is the
+/// model type which never restricts or penalizes a traversal.
+fn validate_ch_model_type(
+    params: &serde_json::Value,
+    section: &str,
+    allowed: &str,
+) -> Result<(), CompassAppError> {
+    let model_type = params
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or(allowed);
+    if model_type == allowed {
+        Ok(())
+    } else {
+        Err(CompassConfigurationError::UserConfigurationError(format!(
+            "the ch search algorithm ignores {} models, found [{}] type \"{}\" which requires type \"{}\"",
+            section, section, model_type, allowed
+        ))
+        .into())
+    }
+}
+
+/// the sections of a configuration which determine the cost of each edge, which a
+/// contraction hierarchy records so that an app only loads a hierarchy whose edge
+/// weights agree with its own costs
+pub fn ch_cost_configuration(config: &serde_json::Value) -> serde_json::Value {
+    let sections = [
+        CompassConfigurationField::State,
+        CompassConfigurationField::Traversal,
+        CompassConfigurationField::CustomState,
+        CompassConfigurationField::Cost,
+    ];
+    let entries = sections.iter().map(|field| {
+        let value = config.get(field.to_str()).cloned();
+        (field.to_string(), value.unwrap_or(serde_json::Value::Null))
+    });
+    serde_json::Value::Object(entries.collect())
+}
+
+/// confirms a contraction hierarchy was contracted with the cost configuration of
+/// the app which loads it
+fn validate_ch_cost_configuration(
+    hierarchy_configuration: Option<&serde_json::Value>,
+    app_configuration: &serde_json::Value,
+) -> Result<(), CompassAppError> {
+    match hierarchy_configuration {
+        Some(configuration) if configuration == app_configuration => Ok(()),
+        Some(_) => Err(CompassConfigurationError::UserConfigurationError(String::from(
+            "the contraction hierarchy was contracted with different [state], [traversal], [custom_state] or [cost] sections than this configuration, rebuild it with the graph contract command",
+        ))
+        .into()),
+        None => Err(CompassConfigurationError::UserConfigurationError(String::from(
+            "the contraction hierarchy does not record the cost configuration it was contracted with, rebuild it with the graph contract command",
+        ))
+        .into()),
     }
 }
 
@@ -466,10 +1039,14 @@ where
 /// It is assumed that all pre-processing from InputPlugins have been applied.
 /// This function runs a vertex-oriented search and feeds the result into the
 /// OutputPlugins for post-processing, returning the result as JSON.
+/// When a result cache is provided, a cached response skips the search.
 ///
 /// # Arguments
 ///
 /// * `query` - a single search query that has been processed by InputPlugins
+/// * `result_cache` - optional cache of responses to previous queries
+/// * `metrics` - optional recorder of the metrics of each query
+/// * `cancellation` - aborts the search when cancelled
 ///
 /// # Returns
 ///
@@ -479,12 +1056,83 @@ pub fn run_single_query(
     search_orientation: &SearchOrientation,
     output_plugins: &[Arc<dyn OutputPlugin>],
     search_app: &SearchApp,
+    result_cache: Option<&ResultCache>,
+    metrics: Option<&MetricsRecorder>,
+    cancellation: &CancellationToken,
 ) -> Result<serde_json::Value, CompassAppError> {
-    let search_result = search_app.run(query, search_orientation);
-    let output = apply_output_processing(query, search_result, search_app, output_plugins);
+    let cache_generation = cache_generation(result_cache)?;
+    let mut response = match result_cache.map(|c| c.get(query)).transpose()?.flatten() {
+        Some(cached) => record_cached(cached, output_plugins, metrics)?,
+        None => {
+            let search_result = search_app.run(query, search_orientation, cancellation);
+            finish_query(
+                query,
+                search_result,
+                search_app,
+                output_plugins,
+                cache_generation,
+                metrics,
+            )?
+        }
+    };
+    query_id::add_query_ids(query, &mut response);
+    Ok(response)
+}
+
+/// the result cache along with its generation before any query is run, so that
+/// responses found before the cache is cleared are not stored
+fn cache_generation(
+    result_cache: Option<&ResultCache>,
+) -> Result<Option<(&ResultCache, u64)>, CompassAppError> {
+    result_cache
+        .map(|c| c.generation().map(|g| (c, g)))
+        .transpose()
+}
+
+/// applies output processing to the result of a search, caching the response and
+/// recording its metrics when enabled. metrics are added after caching, so that a
+/// cached response never carries the metrics of the query that first produced it.
+fn finish_query(
+    query: &Value,
+    search_result: Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    search_app: &SearchApp,
+    output_plugins: &[Arc<dyn OutputPlugin>],
+    result_cache: Option<(&ResultCache, u64)>,
+    metrics: Option<&MetricsRecorder>,
+) -> Result<Value, CompassAppError> {
+    let query_metrics = metrics.map(|_| QueryMetrics::from_search_result(&search_result));
+    let output_start = Instant::now();
+    let mut output = apply_output_processing(query, search_result, search_app, output_plugins);
+    let output_plugin_runtime = output_start.elapsed();
+    if let Some((cache, generation)) = result_cache {
+        cache.insert(query, &output, generation)?;
+    }
+    if let (Some(recorder), Some(query_metrics)) = (metrics, query_metrics) {
+        let query_metrics = QueryMetrics {
+            output_plugin_runtime,
+            ..query_metrics
+        };
+        recorder.record(&mut output, query_metrics)?;
+    }
     Ok(output)
 }
 
+/// records the metrics of a response served from the result cache, and lets the output
+/// plugins count it as they would a response they processed
+fn record_cached(
+    mut cached: Value,
+    output_plugins: &[Arc<dyn OutputPlugin>],
+    metrics: Option<&MetricsRecorder>,
+) -> Result<Value, CompassAppError> {
+    for plugin in output_plugins.iter() {
+        plugin.process_cached(&cached)?;
+    }
+    if let Some(recorder) = metrics {
+        recorder.record(&mut cached, QueryMetrics::cached())?;
+    }
+    Ok(cached)
+}
+
 /// helper for handling conversion from Chrono Duration to std Duration
 fn to_std(dur: Duration) -> Result<std::time::Duration, CompassAppError> {
     dur.to_std().map_err(|e| {
@@ -495,31 +1143,149 @@ fn to_std(dur: Duration) -> Result<std::time::Duration, CompassAppError> {
     })
 }
 
+/// runs a group of queries which share an origin, as found by [`SharedOriginBatching::group`],
+/// with a single search. cached responses skip the search. if the shared search fails,
+/// each query is run on its own so that any error is reported with its query.
+///
+/// # Arguments
+///
+/// * `queries` - queries which have been processed by InputPlugins and share an origin
+/// * `result_cache` - optional cache of responses to previous queries
+/// * `metrics` - optional recorder of the metrics of each query
+/// * `cancellation` - aborts the searches when cancelled
+///
+/// # Returns
+///
+/// * The response to each query, in order, or an error
+pub fn run_query_group(
+    queries: &[&Value],
+    search_orientation: &SearchOrientation,
+    output_plugins: &[Arc<dyn OutputPlugin>],
+    search_app: &SearchApp,
+    result_cache: Option<&ResultCache>,
+    metrics: Option<&MetricsRecorder>,
+    cancellation: &CancellationToken,
+) -> Result<Vec<Value>, CompassAppError> {
+    if queries.len() < 2 {
+        return queries
+            .iter()
+            .map(|q| {
+                run_single_query(
+                    q,
+                    search_orientation,
+                    output_plugins,
+                    search_app,
+                    result_cache,
+                    metrics,
+                    cancellation,
+                )
+            })
+            .collect();
+    }
+    let cache_generation = cache_generation(result_cache)?;
+    let mut responses = queries
+        .iter()
+        .map(
+            |q| match result_cache.map(|c| c.get(q)).transpose()?.flatten() {
+                Some(cached) => record_cached(cached, output_plugins, metrics).map(Some),
+                None => Ok(None),
+            },
+        )
+        .collect::<Result<Vec<_>, CompassAppError>>()?;
+    let uncached = queries
+        .iter()
+        .zip(responses.iter())
+        .filter(|(_, r)| r.is_none())
+        .map(|(q, _)| *q)
+        .collect::<Vec<_>>();
+    let search_results = match search_app.run_shared_origin(&uncached, cancellation) {
+        Ok(results) => results,
+        Err(e) => {
+            log::debug!(
+                "shared origin search failed, running queries individually: {}",
+                e
+            );
+            uncached
+                .iter()
+                .map(|q| search_app.run(q, search_orientation, cancellation))
+                .collect()
+        }
+    };
+    let mut outputs = uncached.into_iter().zip(search_results).map(|(q, result)| {
+        finish_query(
+            q,
+            result,
+            search_app,
+            output_plugins,
+            cache_generation,
+            metrics,
+        )
+    });
+    for response in responses.iter_mut().filter(|r| r.is_none()) {
+        let output = outputs.next().ok_or_else(|| {
+            CompassAppError::InternalError(String::from("shared origin search is missing a result"))
+        })??;
+        *response = Some(output);
+    }
+    let responses = queries
+        .iter()
+        .zip(responses.into_iter().flatten())
+        .map(|(query, mut response)| {
+            query_id::add_query_ids(query, &mut response);
+            response
+        })
+        .collect();
+    Ok(responses)
+}
+
+/// splits the queries of a batch into the groups that are run together, which are single
+/// queries unless queries that share an origin are to share a search
+fn group_batch<'a>(
+    queries: &[&'a Value],
+    shared_origin: Option<&SharedOriginBatching>,
+) -> Vec<Vec<&'a Value>> {
+    match shared_origin {
+        Some(shared_origin) => shared_origin.group(queries),
+        None => queries.iter().map(|q| vec![*q]).collect(),
+    }
+}
+
 /// runs a query batch which has been sorted into parallel chunks
 /// and retains the responses from each search in memory.
+#[allow(clippy::too_many_arguments)]
 pub fn run_batch_with_responses(
     load_balanced_inputs: &Vec<Vec<&Value>>,
     search_orientation: &SearchOrientation,
     output_plugins: &[Arc<dyn OutputPlugin>],
     search_app: &SearchApp,
+    result_cache: Option<&ResultCache>,
+    shared_origin: Option<&SharedOriginBatching>,
+    metrics: Option<&MetricsRecorder>,
     response_writer: &ResponseSink,
-    pb: Arc<Mutex<Bar>>,
+    progress: &RunProgress,
+    cancellation: &CancellationToken,
 ) -> Result<Box<dyn Iterator<Item = Value>>, CompassAppError> {
     let run_query_result = load_balanced_inputs
         .par_iter()
         .map(|queries| {
-            queries
-                .iter()
-                .map(|q| {
-                    let mut response =
-                        run_single_query(q, search_orientation, output_plugins, search_app)?;
-                    if let Ok(mut pb_local) = pb.lock() {
-                        let _ = pb_local.update(1);
-                    }
+            let mut batch_responses = vec![];
+            for group in group_batch(queries, shared_origin) {
+                let responses = run_query_group(
+                    &group,
+                    search_orientation,
+                    output_plugins,
+                    search_app,
+                    result_cache,
+                    metrics,
+                    cancellation,
+                )?;
+                progress.update_responses(&responses);
+                for mut response in responses.into_iter() {
                     response_writer.write_response(&mut response)?;
-                    Ok(response)
-                })
-                .collect::<Result<Vec<serde_json::Value>, CompassAppError>>()
+                    batch_responses.push(response);
+                }
+            }
+            Ok(batch_responses)
         })
         .collect::<Result<Vec<Vec<serde_json::Value>>, CompassAppError>>()?;
 
@@ -532,53 +1298,45 @@ pub fn run_batch_with_responses(
 
 /// runs a query batch which has been sorted into parallel chunks.
 /// the search result is not persisted in memory.
+#[allow(clippy::too_many_arguments)]
 pub fn run_batch_without_responses(
     load_balanced_inputs: &Vec<Vec<&Value>>,
     search_orientation: &SearchOrientation,
     output_plugins: &[Arc<dyn OutputPlugin>],
     search_app: &SearchApp,
+    result_cache: Option<&ResultCache>,
+    shared_origin: Option<&SharedOriginBatching>,
+    metrics: Option<&MetricsRecorder>,
     response_writer: &ResponseSink,
-    pb: Arc<Mutex<Bar>>,
+    progress: &RunProgress,
+    cancellation: &CancellationToken,
 ) -> Result<Box<dyn Iterator<Item = Value>>, CompassAppError> {
-    // run the computations, discard values that do not trigger an error
-    let _ = load_balanced_inputs
-        .par_iter()
-        .map(|queries| {
-            // fold over query iterator allows us to propagate failures up while still using constant
-            // memory to hold the state of the result object. we can't similarly return error values from
-            // within a for loop or for_each call, and map creates more allocations. open to other ideas!
-            let initial: Result<(), CompassAppError> = Ok(());
-            let _ = queries.iter().fold(initial, |_, q| {
-                let mut response =
-                    run_single_query(q, search_orientation, output_plugins, search_app)?;
-                if let Ok(mut pb_local) = pb.lock() {
-                    let _ = pb_local.update(1);
-                }
-                response_writer.write_response(&mut response)?;
-                Ok(())
-            });
-            Ok(())
-        })
-        .collect::<Result<Vec<_>, CompassAppError>>()?;
+    // run the computations, writing each response as it completes and keeping only
+    // failures to write, which halt the run.
+    load_balanced_inputs.par_iter().try_for_each(|queries| {
+        group_batch(queries, shared_origin)
+            .into_iter()
+            .try_for_each(|group| {
+                let responses = run_query_group(
+                    &group,
+                    search_orientation,
+                    output_plugins,
+                    search_app,
+                    result_cache,
+                    metrics,
+                    cancellation,
+                )?;
+                progress.update_responses(&responses);
+                responses
+                    .into_iter()
+                    .try_for_each(|mut response| response_writer.write_response(&mut response))
+            })
+    })?;
 
     Ok(Box::new(std::iter::empty::<Value>()))
 }
 
 /// helper that applies the input plugins to a query, returning the result(s) or an error if failed
-pub fn apply_input_plugins(
-    query: &serde_json::Value,
-    plugins: &Vec<Arc<dyn InputPlugin>>,
-) -> Result<Vec<serde_json::Value>, serde_json::Value> {
-    let mut plugin_state = serde_json::Value::Array(vec![query.clone()]);
-    for plugin in plugins {
-        let p = plugin.clone();
-        let op: in_ops::ArrayOp = Rc::new(|q| p.process(q));
-        in_ops::json_array_op(&mut plugin_state, op)?
-    }
-    let result = in_ops::json_array_flatten(&mut plugin_state)?;
-    Ok(result)
-}
-
 // helper that applies the output processing. this includes
 // 1. summarizing from the TraversalModel
 // 2. applying the output plugins
@@ -596,7 +1354,10 @@ pub fn apply_output_processing(
     for output_plugin in output_plugins.iter() {
         match output_plugin.process(&mut initial, &result) {
             Ok(()) => {}
-            Err(e) => return out_ops::package_error(request_json, e),
+            Err(e) => {
+                let detail = e.error_detail().attributed_to(ErrorComponent::OutputPlugin);
+                return out_ops::package_error(request_json, detail);
+            }
         }
     }
 
@@ -613,9 +1374,9 @@ mod tests {
     };
 
     use super::CompassApp;
+    use routee_compass_core::algorithm::search::cancellation_token::CancellationToken;
 
-    #[test]
-    fn test_speeds() {
+    fn speeds_test_app() -> CompassApp {
         let cwd_str = match std::env::current_dir() {
             Ok(cwd_path) => String::from(cwd_path.to_str().unwrap_or("<unknown>")),
             _ => String::from("<unknown>"),
@@ -653,8 +1414,13 @@ mod tests {
                 CompassApp::try_from(conf_file_debug.as_path())
             }
             Err(other) => panic!("{}", other),
-        }
-        .unwrap();
+        };
+        app.unwrap()
+    }
+
+    #[test]
+    fn test_speeds() {
+        let app = speeds_test_app();
         let query = serde_json::json!({
             "origin_vertex": 0,
             "destination_vertex": 2
@@ -668,6 +1434,436 @@ mod tests {
         assert_eq!(path_0, &expected);
     }
 
+    #[test]
+    fn test_arrive_by() {
+        let app = speeds_test_app();
+        let depart_query = serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2 });
+        let arrive_query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "arrival_time": "2024-03-01T09:00:00"
+        });
+        let result = app.run(vec![depart_query, arrive_query], None).unwrap();
+        let (depart, arrive) = match result[0]["request"].get("arrival_time") {
+            None => (&result[0], &result[1]),
+            Some(_) => (&result[1], &result[0]),
+        };
+        // the reverse search finds the same route, which is reported from origin to destination
+        assert_eq!(arrive["route"]["path"], serde_json::json!(vec![0, 2]));
+        assert_eq!(
+            arrive["route"]["traversal_summary"],
+            depart["route"]["traversal_summary"]
+        );
+        let travel_seconds = depart["route"]["traversal_summary"]["time"]
+            .as_f64()
+            .unwrap()
+            .round() as i64;
+        let arrival =
+            chrono::NaiveDateTime::parse_from_str("2024-03-01T09:00:00", "%Y-%m-%dT%H:%M:%S")
+                .unwrap();
+        let expected = (arrival - chrono::Duration::seconds(travel_seconds))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
+        assert_eq!(arrive["departure_time"], serde_json::json!(expected));
+        // speeds do not vary by time of day, so the route arrives exactly on time
+        assert_eq!(
+            arrive["estimated_arrival_time"],
+            serde_json::json!("2024-03-01T09:00:00")
+        );
+        assert_eq!(arrive["late_seconds"], serde_json::json!(0));
+
+        let both = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "departure_time": "2024-03-01T08:00:00",
+            "arrival_time": "2024-03-01T09:00:00"
+        });
+        let result = app.run(vec![both], None).unwrap();
+        assert!(result[0].get("error").is_some());
+    }
+
+    #[test]
+    fn test_destination_vertices() {
+        let app = speeds_test_app();
+        let by_id = |mut responses: Vec<serde_json::Value>| {
+            responses.sort_by_key(|r| {
+                r["request"]["query_id"]
+                    .as_str()
+                    .and_then(|id| id.parse::<u64>().ok())
+            });
+            responses
+        };
+        let queries = vec![
+            serde_json::json!({ "origin_vertex": 0, "destination_vertex": 1, "query_id": 0 }),
+            serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2, "query_id": 1 }),
+            serde_json::json!({ "origin_vertex": 0, "destination_vertices": [1, 2], "query_id": 2 }),
+        ];
+        let result = by_id(app.run(queries, None).unwrap());
+        let time = |r: &serde_json::Value| r["route"]["traversal_summary"]["time"].as_f64();
+        // the search ends at whichever destination is nearer by cost
+        let nearest = if time(&result[0]) <= time(&result[1]) {
+            0
+        } else {
+            1
+        };
+        let chosen = &result[2];
+        assert_eq!(
+            chosen["chosen_destination_vertex"],
+            result[nearest]["request"]["destination_vertex"]
+        );
+        assert_eq!(chosen["route"]["path"], result[nearest]["route"]["path"]);
+
+        let both = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "destination_vertices": [1, 2]
+        });
+        let result = app.run(vec![both], None).unwrap();
+        assert!(result[0].get("error").is_some());
+
+        // without an input plugin to find its vertices, a polygon is not ignored
+        let polygon = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_polygon": {
+                "type": "Polygon",
+                "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]
+            }
+        });
+        let result = app.run(vec![polygon], None).unwrap();
+        assert!(result[0].get("error").is_some());
+    }
+
+    #[test]
+    fn test_multi_origin() {
+        let app = speeds_test_app();
+        let by_id = |mut responses: Vec<serde_json::Value>| {
+            responses.sort_by_key(|r| {
+                r["request"]["query_id"]
+                    .as_str()
+                    .and_then(|id| id.parse::<u64>().ok())
+            });
+            responses
+        };
+        let queries = vec![
+            serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2, "query_id": 0 }),
+            serde_json::json!({ "origin_vertex": 1, "destination_vertex": 2, "query_id": 1 }),
+            serde_json::json!({
+                "origin_vertices": [{ "vertex_id": 0 }, { "vertex_id": 1, "offset": 0.0 }],
+                "destination_vertex": 2,
+                "query_id": 2
+            }),
+        ];
+        let result = by_id(app.run(queries, None).unwrap());
+        let cost = |r: &serde_json::Value| r["route"]["cost"]["total_cost"].as_f64();
+        // the search begins from whichever origin leads to the route of least cost
+        let best = if cost(&result[0]) <= cost(&result[1]) {
+            0
+        } else {
+            1
+        };
+        let chosen = &result[2];
+        assert_eq!(
+            chosen["chosen_origin_vertex"],
+            result[best]["request"]["origin_vertex"]
+        );
+        assert_eq!(chosen["route"]["path"], result[best]["route"]["path"]);
+
+        // an offset larger than the cost of every route moves the search to the other origin
+        let other = 1 - best;
+        let offset = cost(&result[0]).unwrap() + cost(&result[1]).unwrap() + 1.0;
+        let query = serde_json::json!({
+            "origin_vertices": [
+                { "vertex_id": best, "offset": offset },
+                { "vertex_id": other, "offset": 0.0 }
+            ],
+            "destination_vertex": 2
+        });
+        let result = app.run(vec![query], None).unwrap();
+        assert_eq!(result[0]["chosen_origin_vertex"], serde_json::json!(other));
+    }
+
+    #[test]
+    fn test_chunked_run() {
+        let app = speeds_test_app();
+        let queries = (0..5)
+            .map(|_| serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2 }))
+            .collect::<Vec<_>>();
+        let run_config = serde_json::json!({ "chunk_size": 2 });
+        let result = app.run(queries, Some(&run_config)).unwrap();
+        assert_eq!(result.len(), 5);
+        for response in result.iter() {
+            assert_eq!(response["route"]["path"], serde_json::json!(vec![0, 2]));
+        }
+    }
+
+    #[test]
+    fn test_update_edge_speeds() {
+        use routee_compass_core::algorithm::search::heuristic::alt_heuristic::AltHeuristic;
+        use routee_compass_core::model::{
+            road_network::edge_id::EdgeId,
+            unit::{Cost, Speed, SpeedUnit},
+        };
+        let app = speeds_test_app();
+        // slowing down the time-optimal path makes the distance-optimal path faster
+        let speeds = vec![(EdgeId(0), Speed::new(5.0)), (EdgeId(2), Speed::new(5.0))];
+        app.search_app
+            .update_edge_speeds(&speeds, &SpeedUnit::KilometersPerHour)
+            .unwrap();
+        let query = serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2 });
+        let result = app.run(vec![query], None).unwrap();
+        assert_eq!(result[0]["route"]["path"], serde_json::json!(vec![1]));
+
+        let invalid = vec![(EdgeId(3), Speed::new(5.0))];
+        assert!(app
+            .search_app
+            .update_edge_speeds(&invalid, &SpeedUnit::KilometersPerHour)
+            .is_err());
+
+        // landmark costs computed from the startup speeds would overestimate
+        let mut app = speeds_test_app();
+        let graph = app.search_app.directed_graph.clone();
+        let costs = vec![Cost::ONE; graph.n_edges()];
+        let heuristic = AltHeuristic::from_edge_costs(&graph, &costs, 1).unwrap();
+        app.search_app.heuristic = Some(std::sync::Arc::new(heuristic));
+        assert!(app
+            .update_edge_speeds(&speeds, &SpeedUnit::KilometersPerHour)
+            .is_err());
+    }
+
+    #[test]
+    fn test_result_cache() {
+        use crate::app::compass::result_cache::{ResultCache, ResultCacheConfig};
+        use routee_compass_core::model::{
+            road_network::edge_id::EdgeId,
+            unit::{Speed, SpeedUnit},
+        };
+        let mut app = speeds_test_app();
+        let cache_config = ResultCacheConfig {
+            max_entries: 10,
+            ignored_fields: vec![String::from("query_id")],
+        };
+        app.result_cache = Some(ResultCache::new(&cache_config).unwrap());
+        let query = |id: usize| serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2, "query_id": id });
+        app.run(vec![query(0)], None).unwrap();
+        let result = app.run(vec![query(1)], None).unwrap();
+        assert_eq!(app.result_cache_counts(), Some((1, 1)));
+        assert_eq!(result[0]["request"]["query_id"], "1");
+        assert_eq!(result[0]["route"]["path"], serde_json::json!(vec![0, 2]));
+
+        // a speed update drops the cached route
+        let speeds = vec![(EdgeId(0), Speed::new(5.0)), (EdgeId(2), Speed::new(5.0))];
+        app.update_edge_speeds(&speeds, &SpeedUnit::KilometersPerHour)
+            .unwrap();
+        let result = app.run(vec![query(2)], None).unwrap();
+        assert_eq!(app.result_cache_counts(), Some((1, 2)));
+        assert_eq!(result[0]["route"]["path"], serde_json::json!(vec![1]));
+    }
+
+    #[test]
+    fn test_metrics() {
+        use crate::app::compass::metrics::MetricsConfig;
+        let tmp = tempfile::tempdir().unwrap();
+        let output_file = tmp.path().join("metrics.json");
+        let mut app = speeds_test_app();
+        app.metrics = Some(MetricsConfig {
+            output_file: Some(output_file.to_string_lossy().to_string()),
+            prometheus_file: None,
+            per_query: true,
+        });
+        let queries = vec![
+            serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2 }),
+            serde_json::json!({ "origin_vertex": 2, "destination_vertex": 0 }),
+        ];
+        let result = app.run(queries, None).unwrap();
+        let found = result.iter().find(|r| r.get("error").is_none()).unwrap();
+        assert!(found["metrics"]["expansions"].as_u64().unwrap() > 0);
+        assert!(found["metrics"]["heap_pops"].as_u64().unwrap() > 0);
+
+        let summary: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+        assert_eq!(summary["queries"], 2);
+        assert_eq!(summary["failed"], 1);
+        assert_eq!(summary["failures"]["NO_PATH_FOUND"], 1);
+        assert_eq!(summary["runtime_ms"]["input_plugin"]["count"], 2);
+    }
+
+    #[test]
+    fn test_input_error_recorder_per_run() {
+        let app = speeds_test_app();
+        let first = app.input_error_recorder();
+        let second = app.input_error_recorder();
+        let error = serde_json::json!({ "error": "failed" });
+        first.record(&[], &[error]).unwrap();
+        // a concurrent run neither reports nor clears the failures of another run
+        assert_eq!(second.report().unwrap().dropped, 0);
+        assert_eq!(first.report().unwrap().dropped, 1);
+    }
+
+    #[test]
+    fn test_reproducibility() {
+        use crate::app::compass::reproducibility::{Reproducibility, ReproducibilityConfig};
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_file = dir.path().join("manifest.json");
+        let edges_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/app/compass/test/speeds_test/test_edges.csv")
+            .to_string_lossy()
+            .to_string();
+        let mut app = speeds_test_app();
+        app.parallelism = 4;
+        app.reproducibility = Some(
+            Reproducibility::new(
+                &ReproducibilityConfig {
+                    seed: 1,
+                    manifest_file: Some(manifest_file.to_string_lossy().to_string()),
+                },
+                &serde_json::json!({ "graph": { "edge_list_input_file": edges_file } }),
+            )
+            .unwrap(),
+        );
+        let od_pairs = [(0, 2), (2, 0), (0, 1), (1, 2), (2, 1), (1, 0), (0, 0)];
+        // ids are given so that both runs derive the same seeds
+        let queries = od_pairs
+            .iter()
+            .enumerate()
+            .map(|(id, (o, d))| {
+                serde_json::json!({ "origin_vertex": o, "destination_vertex": d, "query_id": id })
+            })
+            .collect::<Vec<_>>();
+        let first = app.run(queries.clone(), None).unwrap();
+        let second = app.run(queries, None).unwrap();
+        assert_eq!(first, second);
+        // the manifest is written once, when the app shuts down
+        assert!(!manifest_file.exists());
+        app.finish().unwrap();
+        let query_ids = first
+            .iter()
+            .map(|r| r.get("query_id").or(r["request"].get("query_id")).cloned())
+            .collect::<Vec<_>>();
+        let expected = (0..od_pairs.len())
+            .map(|i| Some(serde_json::json!(i.to_string())))
+            .collect::<Vec<_>>();
+        assert_eq!(query_ids, expected);
+        assert!(first.iter().all(|r| r.get("search_runtime").is_none()));
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&manifest_file).unwrap()).unwrap();
+        assert_eq!(
+            manifest["routee_compass_version"],
+            env!("CARGO_PKG_VERSION")
+        );
+        assert_eq!(manifest["seed"], 1);
+        assert_eq!(
+            manifest["input_files"][&edges_file].as_str().unwrap().len(),
+            64
+        );
+    }
+
+    #[test]
+    fn test_query_termination() {
+        let app = speeds_test_app();
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "termination": { "type": "iterations", "limit": 1 }
+        });
+        let result = app.run(vec![query], None).unwrap();
+        let error = result[0]["error"]["message"].as_str().unwrap();
+        assert!(error.contains("exceeded iteration limit of 1"), "{}", error);
+        assert_eq!(result[0]["error"]["code"], "QUERY_TERMINATED");
+
+        // a query limit only adds to the configured limits
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "termination": {
+                "type": "all",
+                "models": [
+                    { "type": "iterations", "limit": 1 },
+                    { "type": "solution_size", "limit": 100 }
+                ]
+            }
+        });
+        let result = app.run(vec![query], None).unwrap();
+        assert_eq!(result[0]["route"]["path"], serde_json::json!(vec![0, 2]));
+    }
+
+    #[test]
+    fn test_cancelled_run() {
+        let app = speeds_test_app();
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let query = serde_json::json!({ "origin_vertex": 0, "destination_vertex": 2 });
+        let result = app
+            .run_cancellable(vec![query], None, None, &cancellation)
+            .unwrap();
+        assert_eq!(result[0]["status"], "cancelled");
+        assert_eq!(result[0]["error"]["code"], "CANCELLED");
+    }
+
+    #[test]
+    fn test_terminated_response() {
+        let app = speeds_test_app();
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "termination": { "type": "iterations", "limit": 1 }
+        });
+        let result = app.run(vec![query], None).unwrap();
+        assert_eq!(result[0]["status"], "terminated");
+        let termination = &result[0]["termination"];
+        assert_eq!(termination["reason"], "exceeded iteration limit of 1");
+        assert_eq!(termination["iterations"], 1);
+        assert!(termination["best_vertex"].is_u64());
+        assert!(termination["tree_size"].as_u64().unwrap() > 0);
+
+        // a query with no path is not a terminated query
+        let query = serde_json::json!({ "origin_vertex": 2, "destination_vertex": 0 });
+        let result = app.run(vec![query], None).unwrap();
+        assert_eq!(result[0]["error"]["code"], "NO_PATH_FOUND");
+        assert_eq!(result[0]["error"]["component"], "search");
+        assert!(result[0].get("status").is_none());
+    }
+
+    #[test]
+    fn test_shared_origin() {
+        use crate::app::compass::shared_origin::{SharedOriginBatching, SharedOriginConfig};
+        let od_pairs = [(0, 2), (0, 1), (0, 0), (1, 2), (1, 0)];
+        let queries = od_pairs
+            .iter()
+            .enumerate()
+            .map(|(id, (o, d))| {
+                serde_json::json!({ "origin_vertex": o, "destination_vertex": d, "query_id": id })
+            })
+            .collect::<Vec<_>>();
+        let by_id = |mut responses: Vec<serde_json::Value>| {
+            responses.sort_by_key(|r| {
+                r["request"]["query_id"]
+                    .as_str()
+                    .and_then(|id| id.parse::<u64>().ok())
+            });
+            responses
+        };
+        let mut app = speeds_test_app();
+        let expected = by_id(app.run(queries.clone(), None).unwrap());
+        app.shared_origin = Some(SharedOriginBatching::new(&SharedOriginConfig {
+            ignored_fields: vec![String::from("query_id")],
+        }));
+        let result = by_id(app.run(queries, None).unwrap());
+
+        // each route backtracked from a shared tree matches the route of its own search
+        assert_eq!(result.len(), expected.len());
+        for (shared, single) in result.iter().zip(expected.iter()) {
+            assert_eq!(shared["route"]["path"], single["route"]["path"]);
+            assert_eq!(
+                shared["route"]["traversal_summary"],
+                single["route"]["traversal_summary"]
+            );
+            assert_eq!(shared.get("error").is_some(), single.get("error").is_some());
+        }
+        assert_eq!(result[0]["route"]["path"], serde_json::json!(vec![0, 2]));
+        assert!(result[4].get("error").is_some());
+    }
+
     // #[test]
     // fn test_energy() {
     //     // rust runs test and debug at different locations, which breaks the URLs
@@ -26,6 +26,8 @@ pub enum CompassAppError {
     IOError(#[from] std::io::Error),
     #[error(transparent)]
     CodecError(#[from] serde_json::Error),
+    #[error("failure writing parquet output: {0}")]
+    ParquetError(#[from] parquet::errors::ParquetError),
     #[error(transparent)]
     ConfigError(#[from] ConfigError),
     #[error(transparent)]
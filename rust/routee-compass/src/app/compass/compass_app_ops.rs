@@ -1,12 +1,21 @@
+use super::config::{
+    compass_configuration_error::CompassConfigurationError,
+    compass_configuration_field::CompassConfigurationField,
+};
 use super::{compass_app_error::CompassAppError, compass_input_field::CompassInputField};
 use crate::plugin::{input::input_json_extensions::InputJsonExtensions, plugin_error::PluginError};
-use config::Config;
+use config::{builder::DefaultState, Config, ConfigBuilder, ConfigError};
 use ordered_float::OrderedFloat;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// reads the compass configuration TOML file from a path
 /// combines it with a configuration file that provides library defaults
 ///
+/// the file may list other configuration files to build on with a top-level
+/// `include = ["common.toml"]`, relative to the including file. included files are
+/// deep-merged beneath the file that includes them, so its values take precedence.
+/// string values may reference environment variables as `${NAME}` or `${NAME:-default}`.
+///
 /// # Arguments
 ///
 /// * `config` - path to the config file
@@ -29,9 +38,11 @@ pub fn read_config_from_file(config_path: &Path) -> Result<Config, CompassAppErr
         })?
         .to_string();
 
-    let config = Config::builder()
-        .add_source(default_config)
-        .add_source(config::File::from(config_path))
+    let user_config = config::File::from(config_path);
+    let builder = Config::builder().add_source(default_config);
+    let builder = add_included_sources(builder, &user_config, config_path, &mut vec![])?;
+    let config = builder
+        .add_source(user_config)
         .set_override(
             CompassInputField::ConfigInputFile.to_string(),
             conf_file_string,
@@ -39,12 +50,13 @@ pub fn read_config_from_file(config_path: &Path) -> Result<Config, CompassAppErr
         .build()
         .map_err(CompassAppError::ConfigError)?;
 
-    Ok(config)
+    interpolate_env_vars(config, |name| std::env::var(name).ok())
 }
 
 /// Reads a configuration file from a deserializable string in the specified format.
 /// This also requires the file path of where the string was loaded from since we use that
-/// to normalize paths later.
+/// to normalize paths later. Includes and environment variables are resolved as in
+/// [`read_config_from_file`], with includes relative to the original file path.
 ///
 /// # Arguments
 ///
@@ -66,9 +78,15 @@ pub fn read_config_from_string(
     );
 
     let user_config = config::File::from_str(&config_as_string, format);
+    let builder = Config::builder().add_source(default_config);
+    let builder = add_included_sources(
+        builder,
+        &user_config,
+        Path::new(&original_file_path),
+        &mut vec![],
+    )?;
 
-    let config = Config::builder()
-        .add_source(default_config)
+    let config = builder
         .add_source(user_config)
         .set_override(
             CompassInputField::ConfigInputFile.to_string(),
@@ -77,7 +95,239 @@ pub fn read_config_from_string(
         .build()
         .map_err(CompassAppError::ConfigError)?;
 
-    Ok(config)
+    interpolate_env_vars(config, |name| std::env::var(name).ok())
+}
+
+/// the name given to a configuration which is built in memory rather than read from a
+/// file, which is also its `{config_name}` in templated output filenames
+pub const IN_MEMORY_CONFIG_FILE_NAME: &str = "config.toml";
+
+/// the configuration file path used to resolve the relative file paths of an in-memory
+/// configuration. paths are resolved relative to the parent of the configuration file,
+/// so a working directory is given a configuration file name within it, while the path
+/// of an existing file is used as it is.
+///
+/// # Arguments
+///
+/// * `working_dir` - the directory, or configuration file, that paths are relative to
+///
+/// # Returns
+///
+/// The path of the configuration file
+pub fn config_file_path_in(working_dir: &Path) -> PathBuf {
+    if working_dir.as_os_str().is_empty() || working_dir.is_dir() {
+        working_dir.join(IN_MEMORY_CONFIG_FILE_NAME)
+    } else {
+        working_dir.to_path_buf()
+    }
+}
+
+/// writes a JSON configuration as a TOML string, such as a configuration built from a
+/// python dictionary. TOML has no null, so null values are treated as unset and removed.
+///
+/// # Arguments
+///
+/// * `config` - the configuration as a JSON object
+///
+/// # Returns
+///
+/// The configuration as a TOML string, or an error if it is not an object
+pub fn config_json_to_toml(config: &serde_json::Value) -> Result<String, CompassAppError> {
+    fn without_nulls(value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.iter()
+                    .filter(|(_, v)| !v.is_null())
+                    .map(|(k, v)| (k.clone(), without_nulls(v)))
+                    .collect(),
+            ),
+            serde_json::Value::Array(values) => {
+                serde_json::Value::Array(values.iter().map(without_nulls).collect())
+            }
+            other => other.clone(),
+        }
+    }
+    if !config.is_object() {
+        return Err(CompassAppError::InvalidInput(format!(
+            "configuration must be an object, found {}",
+            config
+        )));
+    }
+    toml::to_string(&without_nulls(config)).map_err(|e| {
+        CompassAppError::InvalidInput(format!("configuration cannot be written as TOML: {}", e))
+    })
+}
+
+/// adds the files listed in the `include` key of a configuration source, along with
+/// their own includes, so that each file is merged over the files it includes.
+///
+/// # Arguments
+///
+/// * `builder` - the builder to add the included sources to
+/// * `source` - the configuration which may include other files
+/// * `source_path` - path of the source, which its includes are relative to
+/// * `visited` - the canonical paths of the chain of files currently being included,
+///   used to detect cycles
+fn add_included_sources<S>(
+    mut builder: ConfigBuilder<DefaultState>,
+    source: &S,
+    source_path: &Path,
+    visited: &mut Vec<PathBuf>,
+) -> Result<ConfigBuilder<DefaultState>, CompassAppError>
+where
+    S: config::Source + Clone + Send + Sync + 'static,
+{
+    let includes = match Config::builder()
+        .add_source(source.clone())
+        .build()?
+        .get::<Vec<String>>(CompassConfigurationField::Include.to_str())
+    {
+        Ok(includes) => includes,
+        Err(ConfigError::NotFound(_)) => vec![],
+        Err(e) => return Err(CompassAppError::ConfigError(e)),
+    };
+    let parent = source_path.parent().unwrap_or_else(|| Path::new(""));
+    // an in-memory configuration may not exist on disk, so its path is compared as given
+    let canonical_source_path = source_path
+        .canonicalize()
+        .unwrap_or_else(|_| source_path.to_path_buf());
+    for include in includes {
+        let include_path = parent.join(include);
+        if !include_path.is_file() {
+            return Err(CompassAppError::NoInputFile(format!(
+                "Could not find included configuration file {}, included by {}",
+                include_path.to_string_lossy(),
+                source_path.to_string_lossy()
+            )));
+        }
+        // compare canonical paths, since the same file may be reached by different paths
+        let canonical_include_path = include_path
+            .canonicalize()
+            .map_err(CompassAppError::IOError)?;
+        if visited.contains(&canonical_include_path)
+            || canonical_include_path == canonical_source_path
+        {
+            return Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::UserConfigurationError(format!(
+                    "configuration file {} includes itself",
+                    include_path.to_string_lossy()
+                )),
+            ));
+        }
+        let include_config = config::File::from(include_path.as_path());
+        visited.push(canonical_include_path);
+        builder = add_included_sources(builder, &include_config, &include_path, visited)?;
+        visited.pop();
+        builder = builder.add_source(include_config);
+    }
+    Ok(builder)
+}
+
+/// replaces each `${NAME}` or `${NAME:-default}` in the string values of a configuration
+/// with the value of the environment variable NAME, as found by `lookup`. reports every
+/// unset variable without a default at once.
+fn interpolate_env_vars(
+    config: Config,
+    lookup: impl Fn(&str) -> Option<String> + Copy,
+) -> Result<Config, CompassAppError> {
+    let json = config.clone().try_deserialize::<serde_json::Value>()?;
+    let mut replacements = vec![];
+    let mut missing = vec![];
+    collect_env_vars(
+        &json,
+        String::new(),
+        lookup,
+        &mut replacements,
+        &mut missing,
+    );
+    if !missing.is_empty() {
+        return Err(CompassAppError::CompassConfigurationError(
+            CompassConfigurationError::UserConfigurationError(format!(
+                "configuration references unset environment variables: {}",
+                missing.join(", ")
+            )),
+        ));
+    }
+    if replacements.is_empty() {
+        return Ok(config);
+    }
+    let mut builder = Config::builder().add_source(config);
+    for (path, value) in replacements {
+        builder = builder.set_override(path, value)?;
+    }
+    Ok(builder.build()?)
+}
+
+fn collect_env_vars(
+    value: &serde_json::Value,
+    path: String,
+    lookup: impl Fn(&str) -> Option<String> + Copy,
+    replacements: &mut Vec<(String, String)>,
+    missing: &mut Vec<String>,
+) {
+    match value {
+        serde_json::Value::String(s) if s.contains("${") => match substitute_env_vars(s, lookup) {
+            Ok(substituted) => replacements.push((path, substituted)),
+            Err(names) => missing.extend(names.into_iter().map(|n| format!("{} ({})", n, path))),
+        },
+        serde_json::Value::Object(obj) => {
+            for (key, child) in obj.iter() {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                collect_env_vars(child, child_path, lookup, replacements, missing);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for (idx, child) in arr.iter().enumerate() {
+                collect_env_vars(
+                    child,
+                    format!("{}[{}]", path, idx),
+                    lookup,
+                    replacements,
+                    missing,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// substitutes the `${NAME}` and `${NAME:-default}` references of a string, or returns
+/// the names of the variables which have neither a value nor a default
+fn substitute_env_vars(
+    s: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, Vec<String>> {
+    let mut result = String::with_capacity(s.len());
+    let mut missing = vec![];
+    let mut remaining = s;
+    while let Some(start) = remaining.find("${") {
+        let end = match remaining[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        result.push_str(&remaining[..start]);
+        let reference = &remaining[start + 2..end];
+        let (name, default) = match reference.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (reference, None),
+        };
+        match (lookup(name), default) {
+            (Some(value), _) => result.push_str(&value),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => missing.push(name.to_string()),
+        }
+        remaining = &remaining[end + 1..];
+    }
+    result.push_str(remaining);
+    if missing.is_empty() {
+        Ok(result)
+    } else {
+        Err(missing)
+    }
 }
 
 /// applies the weight balancing policy set by the LoadBalancerPlugin InputPlugin.
@@ -112,6 +362,31 @@ pub fn apply_load_balancing_policy(
     Ok(assignments)
 }
 
+/// assigns groups of queries to parallel batches as with [`apply_load_balancing_policy`],
+/// keeping each group together in a single batch. the weight of a group is the sum of
+/// the weights of its queries.
+pub fn apply_grouped_load_balancing_policy(
+    groups: Vec<Vec<&serde_json::Value>>,
+    parallelism: usize,
+    default: f64,
+) -> Result<Vec<Vec<&serde_json::Value>>, CompassAppError> {
+    if groups.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut bin_totals = vec![0.0; parallelism];
+    let mut assignments: Vec<Vec<&serde_json::Value>> = vec![vec![]; parallelism];
+    for group in groups.into_iter() {
+        let mut w = 0.0;
+        for q in group.iter() {
+            w += q.get_query_weight_estimate()?.unwrap_or(default);
+        }
+        let min_bin = min_bin(&bin_totals)?;
+        bin_totals[min_bin] += w;
+        assignments[min_bin].extend(group);
+    }
+    Ok(assignments)
+}
+
 fn min_bin(bins: &[f64]) -> Result<usize, PluginError> {
     bins.iter()
         .enumerate()
@@ -124,7 +399,11 @@ fn min_bin(bins: &[f64]) -> Result<usize, PluginError> {
 
 #[cfg(test)]
 mod test {
-    use super::apply_load_balancing_policy;
+    use super::{
+        apply_load_balancing_policy, config_file_path_in, config_json_to_toml,
+        interpolate_env_vars, read_config_from_file, read_config_from_string, substitute_env_vars,
+        CompassAppError, Config,
+    };
     use crate::plugin::input::input_field::InputField;
     use serde_json::json;
 
@@ -214,4 +493,136 @@ mod test {
         let expected = vec![vec![0], vec![1, 4, 7, 10], vec![2, 5, 8, 11], vec![3, 6, 9]];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_substitute_env_vars() {
+        let lookup = |name: &str| match name {
+            "DATA_DIR" => Some(String::from("/data")),
+            _ => None,
+        };
+        assert_eq!(
+            substitute_env_vars("${DATA_DIR}/edges.csv.gz", lookup),
+            Ok(String::from("/data/edges.csv.gz"))
+        );
+        assert_eq!(
+            substitute_env_vars("${REGION:-denver}.bin", lookup),
+            Ok(String::from("denver.bin"))
+        );
+        assert_eq!(
+            substitute_env_vars("${REGION}/${KEY}", lookup),
+            Err(vec![String::from("REGION"), String::from("KEY")])
+        );
+    }
+
+    #[test]
+    fn test_include_deep_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("common.toml"),
+            "parallelism = 4\n[graph]\nverbose = false\nedge_list_input_file = \"edges.csv.gz\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("app.toml"),
+            "include = [\"common.toml\"]\n[graph]\nverbose = true\nvertex_list_input_file = \"vertices.csv.gz\"\n",
+        )
+        .unwrap();
+        let config = read_config_from_file(&dir.path().join("app.toml")).unwrap();
+        assert_eq!(config.get::<usize>("parallelism").unwrap(), 4);
+        assert!(config.get::<bool>("graph.verbose").unwrap());
+        assert_eq!(
+            config.get::<String>("graph.edge_list_input_file").unwrap(),
+            "edges.csv.gz"
+        );
+        assert_eq!(
+            config
+                .get::<String>("graph.vertex_list_input_file")
+                .unwrap(),
+            "vertices.csv.gz"
+        );
+    }
+
+    #[test]
+    fn test_include_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        // the same file by a different path
+        std::fs::write(
+            dir.path().join("app.toml"),
+            "include = [\"sub/../app.toml\"]\n",
+        )
+        .unwrap();
+        let result = read_config_from_file(&dir.path().join("app.toml"));
+        assert!(matches!(
+            result,
+            Err(CompassAppError::CompassConfigurationError(_))
+        ));
+
+        std::fs::write(dir.path().join("a.toml"), "include = [\"sub/b.toml\"]\n").unwrap();
+        std::fs::write(
+            dir.path().join("sub/b.toml"),
+            "include = [\"./../a.toml\"]\n",
+        )
+        .unwrap();
+        let result = read_config_from_file(&dir.path().join("a.toml"));
+        assert!(matches!(
+            result,
+            Err(CompassAppError::CompassConfigurationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_interpolate_env_vars() {
+        let config = Config::builder()
+            .add_source(config::File::from_str(
+                "[graph]\nvertex_list_input_file = \"${DATA_DIR}/vertices.csv.gz\"\n[plugin]\ninput_plugins = [{ type = \"vertex_rtree\", vertices_input_file = \"${DATA_DIR:-.}/v.csv.gz\" }]\n",
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+        let lookup = |name: &str| match name {
+            "DATA_DIR" => Some(String::from("/data")),
+            _ => None,
+        };
+        assert!(interpolate_env_vars(config.clone(), |_| None).is_err());
+        let config = interpolate_env_vars(config, lookup).unwrap();
+        assert_eq!(
+            config
+                .get::<String>("graph.vertex_list_input_file")
+                .unwrap(),
+            "/data/vertices.csv.gz"
+        );
+        assert_eq!(
+            config
+                .get::<String>("plugin.input_plugins[0].vertices_input_file")
+                .unwrap(),
+            "/data/v.csv.gz"
+        );
+    }
+
+    #[test]
+    fn test_in_memory_config() {
+        let config = json!({
+            "parallelism": 2,
+            "graph": { "edge_list_input_file": "edges.csv", "n_edges": null },
+            "plugin": { "input_plugins": [{ "type": "grid_search" }] }
+        });
+        let toml_string = config_json_to_toml(&config).expect("should write TOML");
+        let working_dir = tempfile::tempdir().unwrap();
+        let path = config_file_path_in(working_dir.path());
+        assert_eq!(path, working_dir.path().join("config.toml"));
+        let result = read_config_from_string(
+            toml_string,
+            config::FileFormat::Toml,
+            path.to_string_lossy().to_string(),
+        )
+        .expect("should read TOML");
+        assert_eq!(result.get::<i64>("parallelism").unwrap(), 2);
+        assert_eq!(
+            result.get::<String>("graph.edge_list_input_file").unwrap(),
+            "edges.csv"
+        );
+        assert!(result.get::<i64>("graph.n_edges").is_err());
+        assert!(config_json_to_toml(&json!([1, 2])).is_err());
+    }
 }
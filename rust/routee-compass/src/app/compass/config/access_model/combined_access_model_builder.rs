@@ -11,6 +11,10 @@ pub struct CombinedAccessModelBuilder {
 }
 
 impl AccessModelBuilder for CombinedAccessModelBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["access_models"])
+    }
+
     fn build(
         &self,
         parameters: &serde_json::Value,
@@ -0,0 +1,94 @@
+use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
+use routee_compass_core::{
+    model::{
+        access::{
+            access_model_builder::AccessModelBuilder,
+            access_model_error::AccessModelError,
+            access_model_service::AccessModelService,
+            default::intersection_control::{
+                control::{IntersectionControl, IntersectionControlRow},
+                intersection_control_access_model_engine::{
+                    IdleEnergyRate, IntersectionControlAccessModelEngine,
+                },
+                intersection_control_access_model_service::IntersectionControlAccessModelService,
+            },
+        },
+        unit::{Time, TimeUnit},
+    },
+    util::fs::read_utils,
+};
+use std::{collections::HashMap, sync::Arc};
+
+pub struct IntersectionControlAccessModelBuilder {}
+
+impl AccessModelBuilder for IntersectionControlAccessModelBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "intersection_control_input_file",
+            "delays",
+            "time_unit",
+            "idle_energy",
+            "time_feature_name",
+            "delay_feature_name",
+        ])
+    }
+
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn AccessModelService>, AccessModelError> {
+        let parent_key = "intersection control access model";
+        let config_error = |key: &str, e: String| {
+            AccessModelError::BuildError(format!(
+                "failure reading '{}' from access model configuration: {}",
+                key, e
+            ))
+        };
+        let file_path = parameters
+            .get_config_path(&"intersection_control_input_file", &parent_key)
+            .map_err(|e| config_error("intersection_control_input_file", e.to_string()))?;
+        let rows = read_utils::from_csv::<IntersectionControlRow>(&file_path.as_path(), true, None)
+            .map_err(|e| {
+                AccessModelError::BuildError(format!(
+                    "error reading intersection controls from file {:?}: {}",
+                    file_path, e
+                ))
+            })?;
+        let delays = parameters
+            .get_config_serde::<HashMap<IntersectionControl, Time>>(&"delays", &parent_key)
+            .map_err(|e| config_error("delays", e.to_string()))?;
+        let time_unit = parameters
+            .get_config_serde::<TimeUnit>(&"time_unit", &parent_key)
+            .map_err(|e| config_error("time_unit", e.to_string()))?;
+        let idle_energy = parameters
+            .get_config_serde_optional::<Vec<IdleEnergyRate>>(&"idle_energy", &parent_key)
+            .map_err(|e| config_error("idle_energy", e.to_string()))?
+            .unwrap_or_default();
+        let time_feature_name = parameters
+            .get_config_serde_optional::<String>(&"time_feature_name", &parent_key)
+            .map_err(|e| config_error("time_feature_name", e.to_string()))?
+            .unwrap_or_else(|| String::from("time"));
+        let delay_feature_name = parameters
+            .get_config_serde_optional::<String>(&"delay_feature_name", &parent_key)
+            .map_err(|e| config_error("delay_feature_name", e.to_string()))?
+            .unwrap_or_else(|| {
+                String::from(IntersectionControlAccessModelEngine::DEFAULT_DELAY_FEATURE_NAME)
+            });
+        let engine = IntersectionControlAccessModelEngine::new(
+            &rows,
+            delays,
+            time_unit,
+            idle_energy,
+            time_feature_name,
+            delay_feature_name,
+        )?;
+        log::info!(
+            "intersection control access model loaded {} controlled intersections",
+            engine.controls.len()
+        );
+        let service = IntersectionControlAccessModelService {
+            engine: Arc::new(engine),
+        };
+        Ok(Arc::new(service))
+    }
+}
@@ -17,6 +17,14 @@ use std::sync::Arc;
 pub struct TurnDelayAccessModelBuilder {}
 
 impl AccessModelBuilder for TurnDelayAccessModelBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "edge_heading_input_file",
+            "turn_delay_model",
+            "time_feature_name",
+        ])
+    }
+
     fn build(
         &self,
         parameters: &serde_json::Value,
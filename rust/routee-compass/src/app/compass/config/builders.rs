@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use super::compass_configuration_error::CompassConfigurationError;
 use crate::plugin::{input::input_plugin::InputPlugin, output::output_plugin::OutputPlugin};
+use routee_compass_core::model::road_network::graph::Graph;
 
 /// A [`InputPluginBuilder`] takes a JSON object describing the configuration of an
 /// input plugin and builds a [InputPlugin].
@@ -26,6 +27,37 @@ pub trait InputPluginBuilder {
         &self,
         parameters: &serde_json::Value,
     ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError>;
+
+    /// Builds a [InputPlugin] which may share the road network graph of the app,
+    /// such as a plugin which matches query inputs to graph edges. by default, the
+    /// graph is not used and this is the same as [`InputPluginBuilder::build`].
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - the contents of an element in the "input_plugin" array TOML config section
+    /// * `graph` - the road network graph loaded by the app
+    ///
+    /// [InputPlugin]: compass_app::plugin::input::input_plugin::InputPlugin
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        _graph: Arc<Graph>,
+    ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
+        self.build(parameters)
+    }
+
+    /// the keys this builder reads from its configuration section, other than `type`.
+    /// used to warn about unknown keys, such as misspellings, before any data loads.
+    /// builders which return `None` are not checked.
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// the keys which must be present in this builder's configuration section, which
+    /// are also listed by `config_keys`. a missing key is an error before any data loads.
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec![]
+    }
 }
 
 /// A [`OutputPluginBuilder`] takes a JSON object describing the configuration of an
@@ -51,4 +83,35 @@ pub trait OutputPluginBuilder {
         &self,
         parameters: &serde_json::Value,
     ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError>;
+
+    /// Builds a [OutputPlugin] which may share the road network graph of the app,
+    /// such as a plugin which writes the geometries of edges. by default, the graph
+    /// is not used and this is the same as [`OutputPluginBuilder::build`].
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - the contents of an element in the "output_plugin" array TOML config section
+    /// * `graph` - the road network graph loaded by the app
+    ///
+    /// [OutputPlugin]: compass_app::plugin::input::output_plugin::OutputPlugin
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        _graph: Arc<Graph>,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        self.build(parameters)
+    }
+
+    /// the keys this builder reads from its configuration section, other than `type`.
+    /// used to warn about unknown keys, such as misspellings, before any data loads.
+    /// builders which return `None` are not checked.
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// the keys which must be present in this builder's configuration section, which
+    /// are also listed by `config_keys`. a missing key is an error before any data loads.
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec![]
+    }
 }
@@ -1,6 +1,7 @@
 use super::{
     access_model::{
         combined_access_model_builder::CombinedAccessModelBuilder,
+        intersection_control_access_model_builder::IntersectionControlAccessModelBuilder,
         turn_delay_access_model_builder::TurnDelayAccessModelBuilder,
     },
     builders::{InputPluginBuilder, OutputPluginBuilder},
@@ -8,30 +9,52 @@ use super::{
     compass_configuration_field::CompassConfigurationField,
     config_json_extension::ConfigJsonExtensions,
     frontier_model::{
-        combined::combined_builder::CombinedBuilder, no_restriction_builder::NoRestrictionBuilder,
+        combined::combined_builder::CombinedBuilder,
+        edge_attribute::edge_attribute_builder::EdgeAttributeBuilder,
+        geofence::geofence_builder::GeofenceBuilder,
+        managed_lane::managed_lane_builder::ManagedLaneBuilder,
+        no_restriction_builder::NoRestrictionBuilder,
         road_class::road_class_builder::RoadClassBuilder,
+        soc_floor::soc_floor_builder::SocFloorBuilder,
         turn_restrictions::turn_restriction_builder::TurnRestrictionBuilder,
         vehicle_restrictions::vehicle_restriction_builder::VehicleRestrictionBuilder,
     },
     traversal_model::{
+        active_travel_builder::ActiveTravelBuilder,
         distance_traversal_builder::DistanceTraversalBuilder,
-        energy_model_builder::EnergyModelBuilder, speed_lookup_builder::SpeedLookupBuilder,
+        energy_model_builder::EnergyModelBuilder,
+        historical_speed_profile_builder::HistoricalSpeedProfileBuilder,
+        speed_lookup_builder::SpeedLookupBuilder,
+        time_dependent_speed_builder::TimeDependentSpeedBuilder,
+        tomtom_speed_profile_builder::TomTomSpeedProfileBuilder,
+        transit_traversal_builder::TransitTraversalBuilder,
     },
 };
 use crate::plugin::{
+    dynamic::builder::DynamicPluginBuilder,
     input::{
         default::{
             debug::debug_builder::DebugInputPluginBuilder,
             edge_rtree::edge_rtree_input_plugin_builder::EdgeRtreeInputPluginBuilder,
-            grid_search::builder::GridSearchBuilder, inject::inject_builder::InjectPluginBuilder,
-            load_balancer::builder::LoadBalancerBuilder, vertex_rtree::builder::VertexRTreeBuilder,
+            geocode::geocode_builder::GeocodeBuilder, grid_search::builder::GridSearchBuilder,
+            inject::inject_builder::InjectPluginBuilder,
+            load_balancer::builder::LoadBalancerBuilder,
+            map_matching::map_matching_builder::MapMatchingBuilder,
+            sampling::sampling_builder::SamplingBuilder, vertex_rtree::builder::VertexRTreeBuilder,
         },
         input_plugin::InputPlugin,
     },
     output::{
         default::{
+            batch_summary::builder::BatchSummaryPluginBuilder,
+            edge_attributes::builder::EdgeAttributesPluginBuilder,
+            edge_detail::builder::EdgeDetailPluginBuilder,
+            route_export::builder::RouteExportPluginBuilder,
+            router_response::builder::RouterResponsePluginBuilder,
+            search_tree::builder::SearchTreePluginBuilder,
             summary::builder::SummaryOutputPluginBuilder,
-            traversal::builder::TraversalPluginBuilder, uuid::builder::UUIDOutputPluginBuilder,
+            traversal::builder::TraversalPluginBuilder,
+            turn_by_turn::builder::TurnByTurnPluginBuilder, uuid::builder::UUIDOutputPluginBuilder,
         },
         output_plugin::OutputPlugin,
     },
@@ -45,7 +68,9 @@ use routee_compass_core::model::{
     frontier::{
         frontier_model_builder::FrontierModelBuilder, frontier_model_service::FrontierModelService,
     },
+    road_network::graph::Graph,
     traversal::{
+        default::active_travel_engine::ActiveTravelMode,
         traversal_model_builder::TraversalModelBuilder,
         traversal_model_service::TraversalModelService,
     },
@@ -80,6 +105,11 @@ pub struct CompassAppBuilder {
     pub frontier_builders: HashMap<String, Rc<dyn FrontierModelBuilder>>,
     pub input_plugin_builders: HashMap<String, Rc<dyn InputPluginBuilder>>,
     pub output_plugin_builders: HashMap<String, Rc<dyn OutputPluginBuilder>>,
+    /// the built-in `combined` access model builder, which is rebuilt as access models
+    /// are added, unless a user has registered their own `combined` builder
+    builtin_combined_access: Option<Rc<dyn AccessModelBuilder>>,
+    /// the built-in `combined` frontier model builder, rebuilt as with access models
+    builtin_combined_frontier: Option<Rc<dyn FrontierModelBuilder>>,
 }
 
 impl CompassAppBuilder {
@@ -100,27 +130,100 @@ impl CompassAppBuilder {
             frontier_builders: HashMap::new(),
             input_plugin_builders: HashMap::new(),
             output_plugin_builders: HashMap::new(),
+            builtin_combined_access: None,
+            builtin_combined_frontier: None,
         }
     }
 
-    pub fn add_traversal_model(&mut self, name: String, builder: Rc<dyn TraversalModelBuilder>) {
-        let _ = self.traversal_model_builders.insert(name, builder);
+    /// registers a traversal model builder, which is used when the `[traversal]` section
+    /// of a configuration has this `type` name. replaces any builder with the same name.
+    pub fn add_traversal_model(
+        &mut self,
+        name: impl Into<String>,
+        builder: Rc<dyn TraversalModelBuilder>,
+    ) {
+        register(
+            &mut self.traversal_model_builders,
+            name.into(),
+            builder,
+            "traversal",
+        );
     }
 
-    pub fn add_access_model(&mut self, name: String, builder: Rc<dyn AccessModelBuilder>) {
-        let _ = self.access_model_builders.insert(name, builder);
+    /// registers an access model builder for the `[access]` section, which may also be
+    /// listed in the `access_models` of the built-in `combined` access model. a builder
+    /// registered as `combined` replaces the built-in one and is not modified.
+    pub fn add_access_model(
+        &mut self,
+        name: impl Into<String>,
+        builder: Rc<dyn AccessModelBuilder>,
+    ) {
+        register(
+            &mut self.access_model_builders,
+            name.into(),
+            builder,
+            "access",
+        );
+        if is_builtin(&self.access_model_builders, &self.builtin_combined_access) {
+            let builders = without_combined(&self.access_model_builders);
+            let combined: Rc<dyn AccessModelBuilder> =
+                Rc::new(CombinedAccessModelBuilder { builders });
+            self.access_model_builders
+                .insert(String::from(COMBINED), combined.clone());
+            self.builtin_combined_access = Some(combined);
+        }
     }
 
-    pub fn add_frontier_model(&mut self, name: String, builder: Rc<dyn FrontierModelBuilder>) {
-        let _ = self.frontier_builders.insert(name, builder);
+    /// registers a frontier model builder for the `[frontier]` section, which may also be
+    /// combined with other frontier models in `[[frontier]]` sections by the built-in
+    /// `combined` builder. a builder registered as `combined` replaces the built-in one
+    /// and is not modified.
+    pub fn add_frontier_model(
+        &mut self,
+        name: impl Into<String>,
+        builder: Rc<dyn FrontierModelBuilder>,
+    ) {
+        register(
+            &mut self.frontier_builders,
+            name.into(),
+            builder,
+            "frontier",
+        );
+        if is_builtin(&self.frontier_builders, &self.builtin_combined_frontier) {
+            let builders = without_combined(&self.frontier_builders);
+            let combined: Rc<dyn FrontierModelBuilder> = Rc::new(CombinedBuilder { builders });
+            self.frontier_builders
+                .insert(String::from(COMBINED), combined.clone());
+            self.builtin_combined_frontier = Some(combined);
+        }
     }
 
-    pub fn add_input_plugin(&mut self, name: String, builder: Rc<dyn InputPluginBuilder>) {
-        let _ = self.input_plugin_builders.insert(name, builder);
+    /// registers an input plugin builder for entries of `[plugin] input_plugins`
+    pub fn add_input_plugin(
+        &mut self,
+        name: impl Into<String>,
+        builder: Rc<dyn InputPluginBuilder>,
+    ) {
+        register(
+            &mut self.input_plugin_builders,
+            name.into(),
+            builder,
+            "input plugin",
+        );
     }
 
-    pub fn add_output_plugin(&mut self, name: String, builder: Rc<dyn OutputPluginBuilder>) {
-        let _ = self.output_plugin_builders.insert(name, builder);
+    /// registers an output plugin builder for entries of `[plugin] output_plugins`
+    pub fn add_output_plugin(
+        &mut self,
+        name: impl Into<String>,
+        builder: Rc<dyn OutputPluginBuilder>,
+    ) {
+        register(
+            &mut self.output_plugin_builders,
+            name.into(),
+            builder,
+            "output plugin",
+        );
     }
 
     /// Builds the default builder.
@@ -134,28 +237,58 @@ impl CompassAppBuilder {
         // Traversal model builders
         let dist: Rc<dyn TraversalModelBuilder> = Rc::new(DistanceTraversalBuilder {});
         let speed: Rc<dyn TraversalModelBuilder> = Rc::new(SpeedLookupBuilder {});
-        let energy: Rc<dyn TraversalModelBuilder> = Rc::new(EnergyModelBuilder::new(
-            HashMap::from([(String::from("speed_table"), speed.clone())]),
-        ));
+        let speed_profile: Rc<dyn TraversalModelBuilder> = Rc::new(TimeDependentSpeedBuilder {});
+        let tomtom: Rc<dyn TraversalModelBuilder> = Rc::new(TomTomSpeedProfileBuilder {});
+        let historical: Rc<dyn TraversalModelBuilder> = Rc::new(HistoricalSpeedProfileBuilder {});
+        let energy: Rc<dyn TraversalModelBuilder> =
+            Rc::new(EnergyModelBuilder::new(HashMap::from([
+                (String::from("speed_table"), speed.clone()),
+                (String::from("speed_profile"), speed_profile.clone()),
+                (String::from("tomtom_speed_profile"), tomtom.clone()),
+                (String::from("historical_speed_profile"), historical.clone()),
+            ])));
         let tm_builders: HashMap<String, Rc<dyn TraversalModelBuilder>> = HashMap::from([
             (String::from("distance"), dist),
             (String::from("speed_table"), speed),
+            (String::from("speed_profile"), speed_profile),
+            (String::from("tomtom_speed_profile"), tomtom),
+            (String::from("historical_speed_profile"), historical),
+            (String::from("transit"), Rc::new(TransitTraversalBuilder {})),
+            (
+                String::from("walk"),
+                Rc::new(ActiveTravelBuilder {
+                    mode: ActiveTravelMode::Walk,
+                }),
+            ),
+            (
+                String::from("bike"),
+                Rc::new(ActiveTravelBuilder {
+                    mode: ActiveTravelMode::Bike,
+                }),
+            ),
             (String::from("energy_model"), energy),
         ]);
 
         // Access model builders
         let no_access_model: Rc<dyn AccessModelBuilder> = Rc::new(NoAccessModel {});
         let turn_delay: Rc<dyn AccessModelBuilder> = Rc::new(TurnDelayAccessModelBuilder {});
+        let intersection_control: Rc<dyn AccessModelBuilder> =
+            Rc::new(IntersectionControlAccessModelBuilder {});
         let combined_am: Rc<dyn AccessModelBuilder> = Rc::new(CombinedAccessModelBuilder {
             builders: HashMap::from([
                 (String::from("no_access_model"), no_access_model.clone()),
                 (String::from("turn_delay"), turn_delay.clone()),
+                (
+                    String::from("intersection_control"),
+                    intersection_control.clone(),
+                ),
             ]),
         });
         let am_builders: HashMap<String, Rc<dyn AccessModelBuilder>> = HashMap::from([
             (String::from("no_access_model"), no_access_model),
             (String::from("turn_delay"), turn_delay),
-            (String::from("combined"), combined_am),
+            (String::from("intersection_control"), intersection_control),
+            (String::from("combined"), combined_am.clone()),
         ]);
 
         // Frontier model builders
@@ -164,18 +297,26 @@ impl CompassAppBuilder {
         let turn_restriction: Rc<dyn FrontierModelBuilder> = Rc::new(TurnRestrictionBuilder {});
         let vehicle_restriction: Rc<dyn FrontierModelBuilder> =
             Rc::new(VehicleRestrictionBuilder {});
+        let soc_floor: Rc<dyn FrontierModelBuilder> = Rc::new(SocFloorBuilder {});
+        let geofence: Rc<dyn FrontierModelBuilder> = Rc::new(GeofenceBuilder {});
+        let edge_attribute: Rc<dyn FrontierModelBuilder> = Rc::new(EdgeAttributeBuilder {});
+        let managed_lane: Rc<dyn FrontierModelBuilder> = Rc::new(ManagedLaneBuilder {});
         let base_frontier_builders: HashMap<String, Rc<dyn FrontierModelBuilder>> =
             HashMap::from([
                 (String::from("no_restriction"), no_restriction),
                 (String::from("road_class"), road_class),
                 (String::from("turn_restriction"), turn_restriction),
                 (String::from("vehicle_restriction"), vehicle_restriction),
+                (String::from("soc_floor"), soc_floor),
+                (String::from("geofence"), geofence),
+                (String::from("edge_attribute"), edge_attribute),
+                (String::from("managed_lane"), managed_lane),
             ]);
-        let combined = Rc::new(CombinedBuilder {
+        let combined: Rc<dyn FrontierModelBuilder> = Rc::new(CombinedBuilder {
             builders: base_frontier_builders.clone(),
         });
         let mut all_frontier_builders = base_frontier_builders.clone();
-        all_frontier_builders.insert(String::from("combined"), combined);
+        all_frontier_builders.insert(String::from("combined"), combined.clone());
 
         // Input plugin builders
         let grid_search: Rc<dyn InputPluginBuilder> = Rc::new(GridSearchBuilder {});
@@ -184,6 +325,9 @@ impl CompassAppBuilder {
         let load_balancer: Rc<dyn InputPluginBuilder> = Rc::new(LoadBalancerBuilder {});
         let inject: Rc<dyn InputPluginBuilder> = Rc::new(InjectPluginBuilder {});
         let debug: Rc<dyn InputPluginBuilder> = Rc::new(DebugInputPluginBuilder {});
+        let map_matching: Rc<dyn InputPluginBuilder> = Rc::new(MapMatchingBuilder {});
+        let geocode: Rc<dyn InputPluginBuilder> = Rc::new(GeocodeBuilder {});
+        let sampling: Rc<dyn InputPluginBuilder> = Rc::new(SamplingBuilder {});
         let input_plugin_builders = HashMap::from([
             (String::from("grid_search"), grid_search),
             (String::from("vertex_rtree"), vertex_tree),
@@ -191,16 +335,35 @@ impl CompassAppBuilder {
             (String::from("load_balancer"), load_balancer),
             (String::from("inject"), inject),
             (String::from("debug"), debug),
+            (String::from("map_matching"), map_matching),
+            (String::from("geocode"), geocode),
+            (String::from("sampling"), sampling),
+            (String::from("dynamic"), Rc::new(DynamicPluginBuilder {})),
         ]);
 
         // Output plugin builders
         let traversal: Rc<dyn OutputPluginBuilder> = Rc::new(TraversalPluginBuilder {});
         let summary: Rc<dyn OutputPluginBuilder> = Rc::new(SummaryOutputPluginBuilder {});
         let uuid: Rc<dyn OutputPluginBuilder> = Rc::new(UUIDOutputPluginBuilder {});
+        let route_export: Rc<dyn OutputPluginBuilder> = Rc::new(RouteExportPluginBuilder {});
+        let edge_detail: Rc<dyn OutputPluginBuilder> = Rc::new(EdgeDetailPluginBuilder {});
+        let edge_attributes: Rc<dyn OutputPluginBuilder> = Rc::new(EdgeAttributesPluginBuilder {});
+        let search_tree: Rc<dyn OutputPluginBuilder> = Rc::new(SearchTreePluginBuilder {});
+        let batch_summary: Rc<dyn OutputPluginBuilder> = Rc::new(BatchSummaryPluginBuilder {});
+        let router_response: Rc<dyn OutputPluginBuilder> = Rc::new(RouterResponsePluginBuilder {});
+        let turn_by_turn: Rc<dyn OutputPluginBuilder> = Rc::new(TurnByTurnPluginBuilder {});
         let output_plugin_builders = HashMap::from([
             (String::from("traversal"), traversal),
             (String::from("summary"), summary),
             (String::from("uuid"), uuid),
+            (String::from("route_export"), route_export),
+            (String::from("edge_detail"), edge_detail),
+            (String::from("edge_attributes"), edge_attributes),
+            (String::from("search_tree"), search_tree),
+            (String::from("batch_summary"), batch_summary),
+            (String::from("router_response"), router_response),
+            (String::from("turn_by_turn"), turn_by_turn),
+            (String::from("dynamic"), Rc::new(DynamicPluginBuilder {})),
         ]);
 
         CompassAppBuilder {
@@ -209,6 +372,8 @@ impl CompassAppBuilder {
             frontier_builders: all_frontier_builders,
             input_plugin_builders,
             output_plugin_builders,
+            builtin_combined_access: Some(combined_am),
+            builtin_combined_frontier: Some(combined),
         }
     }
 
@@ -259,11 +424,21 @@ impl CompassAppBuilder {
     }
 
     /// builds a frontier model with the specified type name with the provided
-    /// frontier model configuration JSON
+    /// frontier model configuration JSON. if the configuration is an array of
+    /// frontier models, such as from `[[frontier]]` TOML sections, they are
+    /// combined so that an edge must be valid for every model.
     pub fn build_frontier_model_service(
         &self,
         config: &serde_json::Value,
+        graph: Arc<Graph>,
     ) -> Result<Arc<dyn FrontierModelService>, CompassConfigurationError> {
+        if config.is_array() {
+            let combined = serde_json::json!({
+                "type": "combined",
+                "models": config
+            });
+            return self.build_frontier_model_service(&combined, graph);
+        }
         let fm_type = config.get_config_string(&"type", &"frontier")?;
         self.frontier_builders
             .get(&fm_type)
@@ -275,14 +450,17 @@ impl CompassAppBuilder {
                 )
             })
             .and_then(|b| {
-                b.build(config)
+                b.build_with_graph(config, graph)
                     .map_err(CompassConfigurationError::FrontierModelError)
             })
     }
 
+    /// builds the input plugins of the `[plugin]` section, which may share the graph
+    /// of the app
     pub fn build_input_plugins(
         &self,
         config: &serde_json::Value,
+        graph: Arc<Graph>,
     ) -> Result<Vec<Arc<dyn InputPlugin>>, CompassConfigurationError> {
         let input_plugins = config.get_config_array(
             &CompassConfigurationField::InputPlugins,
@@ -302,15 +480,18 @@ impl CompassAppBuilder {
                         self.input_plugin_builders.keys().join(", "),
                     )
                 })?;
-            let input_plugin = builder.build(&plugin_json)?;
+            let input_plugin = builder.build_with_graph(&plugin_json, graph.clone())?;
             plugins.push(input_plugin);
         }
         Ok(plugins)
     }
 
+    /// builds the output plugins of the `[plugin]` section, which may share the graph
+    /// of the app
     pub fn build_output_plugins(
         &self,
         config: &serde_json::Value,
+        graph: Arc<Graph>,
     ) -> Result<Vec<Arc<dyn OutputPlugin>>, CompassConfigurationError> {
         let output_plugins = config.get_config_array(
             &CompassConfigurationField::OutputPlugins,
@@ -330,15 +511,128 @@ impl CompassAppBuilder {
                         self.output_plugin_builders.keys().join(", "),
                     )
                 })?;
-            let output_plugin = builder.build(&plugin_json)?;
+            let output_plugin = builder.build_with_graph(&plugin_json, graph.clone())?;
             plugins.push(output_plugin);
         }
         Ok(plugins)
     }
 }
 
+/// the `type` name of the models which combine other registered models
+const COMBINED: &str = "combined";
+
+fn register<B: ?Sized>(
+    builders: &mut HashMap<String, Rc<B>>,
+    name: String,
+    builder: Rc<B>,
+    component: &str,
+) {
+    if builders.insert(name.clone(), builder).is_some() {
+        log::warn!(
+            "replacing the {} builder registered as '{}'",
+            component,
+            name
+        );
+    }
+}
+
+/// true if the `combined` builder is the built-in one, rather than a builder registered
+/// by a user, which is left as it is
+fn is_builtin<B: ?Sized>(
+    builders: &HashMap<String, Rc<B>>,
+    builtin_combined: &Option<Rc<B>>,
+) -> bool {
+    match (builders.get(COMBINED), builtin_combined) {
+        (Some(current), Some(builtin)) => Rc::ptr_eq(current, builtin),
+        _ => false,
+    }
+}
+
+/// the registered builders which a combined model can combine
+fn without_combined<B: ?Sized>(builders: &HashMap<String, Rc<B>>) -> HashMap<String, Rc<B>> {
+    builders
+        .iter()
+        .filter(|(name, _)| name.as_str() != COMBINED)
+        .map(|(name, builder)| (name.clone(), builder.clone()))
+        .collect()
+}
+
 impl Default for CompassAppBuilder {
     fn default() -> Self {
         CompassAppBuilder::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::{
+        property::{edge::Edge, vertex::Vertex},
+        state::{
+            custom_feature_format::CustomFeatureFormat, state_feature::StateFeature,
+            state_model::StateModel,
+        },
+    };
+
+    fn test_graph() -> Arc<Graph> {
+        let edges = vec![Edge::new(0, 0, 1, 1.0)].into_boxed_slice();
+        let vertices = vec![Vertex::new(0, 0.0, 0.0), Vertex::new(1, 1.0, 0.0)].into_boxed_slice();
+        Arc::new(Graph::from_edges_and_vertices(edges, vertices).unwrap())
+    }
+
+    #[test]
+    fn test_frontier_model_array_is_combined() {
+        let builder = CompassAppBuilder::default();
+        let config = serde_json::json!([
+            { "type": "no_restriction" },
+            { "type": "soc_floor", "min_soc_percent": 20.0 }
+        ]);
+        let service = builder
+            .build_frontier_model_service(&config, test_graph())
+            .unwrap();
+        let state_model = Arc::new(
+            StateModel::empty()
+                .extend(vec![(
+                    String::from("battery_state"),
+                    StateFeature::Custom {
+                        r#type: String::from("soc"),
+                        unit: String::from("percent"),
+                        format: CustomFeatureFormat::FloatingPoint {
+                            initial: 100.0.into(),
+                        },
+                    },
+                )])
+                .unwrap(),
+        );
+        let model = service
+            .build(&serde_json::json!({}), state_model.clone())
+            .unwrap();
+        let edge = Edge::new(0, 0, 1, 1.0);
+        let mut state = state_model.initial_state().unwrap();
+        assert!(model.valid_traversal(&edge, &state, &state_model).unwrap());
+        state_model
+            .set_custom_f64(&mut state, &String::from("battery_state"), &10.0)
+            .unwrap();
+        assert!(!model.valid_traversal(&edge, &state, &state_model).unwrap());
+    }
+
+    #[test]
+    fn test_added_frontier_model_is_combined() {
+        let mut builder = CompassAppBuilder::default();
+        builder.add_frontier_model("my_model", Rc::new(NoRestrictionBuilder {}));
+        let config = serde_json::json!([{ "type": "my_model" }, { "type": "no_restriction" }]);
+        assert!(builder
+            .build_frontier_model_service(&config, test_graph())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_user_combined_frontier_model_is_kept() {
+        let mut builder = CompassAppBuilder::default();
+        let combined: Rc<dyn FrontierModelBuilder> = Rc::new(NoRestrictionBuilder {});
+        builder.add_frontier_model(COMBINED, combined.clone());
+        builder.add_frontier_model("my_model", Rc::new(NoRestrictionBuilder {}));
+        let registered = builder.frontier_builders.get(COMBINED).unwrap();
+        assert!(Rc::ptr_eq(registered, &combined));
+    }
+}
@@ -1,3 +1,4 @@
+use super::config_schema::ConfigDiagnostic;
 use crate::plugin::plugin_error::PluginError;
 use config::ConfigError;
 use routee_compass_core::{
@@ -13,6 +14,11 @@ use routee_compass_core::{
 pub enum CompassConfigurationError {
     #[error("{0}")]
     UserConfigurationError(String),
+    #[error(
+        "invalid configuration:\n{}",
+        .0.iter().map(|d| format!("  - {}", d)).collect::<Vec<_>>().join("\n")
+    )]
+    InvalidConfiguration(Vec<ConfigDiagnostic>),
     #[error("expected field {0} for {1} not found")]
     ExpectedFieldForComponent(String, String),
     #[error("expected field {0} with type {1} was unable to deserialize")]
@@ -7,6 +7,7 @@ pub enum CompassConfigurationField {
     Termination,
     State,
     Traversal,
+    CustomState,
     Access,
     Cost,
     Algorithm,
@@ -14,6 +15,7 @@ pub enum CompassConfigurationField {
     InputPlugins,
     OutputPlugins,
     Parallelism,
+    ChunkSize,
     QueryTimeoutMs,
     IncludeTree,
     ChargeDepleting,
@@ -21,13 +23,53 @@ pub enum CompassConfigurationField {
     SearchOrientation,
     ResponsePersistencePolicy,
     ResponseOutputPolicy,
+    ResultCache,
+    SharedOrigin,
+    Metrics,
+    Reproducibility,
+    Progress,
+    Include,
+    StrictConfig,
 }
 
 impl CompassConfigurationField {
+    /// every field, used to check the top-level keys of a configuration
+    pub const ALL: [CompassConfigurationField; 28] = [
+        CompassConfigurationField::Graph,
+        CompassConfigurationField::Frontier,
+        CompassConfigurationField::Termination,
+        CompassConfigurationField::State,
+        CompassConfigurationField::Traversal,
+        CompassConfigurationField::CustomState,
+        CompassConfigurationField::Access,
+        CompassConfigurationField::Cost,
+        CompassConfigurationField::Algorithm,
+        CompassConfigurationField::Plugins,
+        CompassConfigurationField::InputPlugins,
+        CompassConfigurationField::OutputPlugins,
+        CompassConfigurationField::Parallelism,
+        CompassConfigurationField::ChunkSize,
+        CompassConfigurationField::QueryTimeoutMs,
+        CompassConfigurationField::IncludeTree,
+        CompassConfigurationField::ChargeDepleting,
+        CompassConfigurationField::ChargeSustaining,
+        CompassConfigurationField::SearchOrientation,
+        CompassConfigurationField::ResponsePersistencePolicy,
+        CompassConfigurationField::ResponseOutputPolicy,
+        CompassConfigurationField::ResultCache,
+        CompassConfigurationField::SharedOrigin,
+        CompassConfigurationField::Metrics,
+        CompassConfigurationField::Reproducibility,
+        CompassConfigurationField::Progress,
+        CompassConfigurationField::Include,
+        CompassConfigurationField::StrictConfig,
+    ];
+
     pub fn to_str(&self) -> &'static str {
         match self {
             CompassConfigurationField::Graph => "graph",
             CompassConfigurationField::Traversal => "traversal",
+            CompassConfigurationField::CustomState => "custom_state",
             CompassConfigurationField::Access => "access",
             CompassConfigurationField::Cost => "cost",
             CompassConfigurationField::State => "state",
@@ -35,6 +77,7 @@ impl CompassConfigurationField {
             CompassConfigurationField::Termination => "termination",
             CompassConfigurationField::Algorithm => "algorithm",
             CompassConfigurationField::Parallelism => "parallelism",
+            CompassConfigurationField::ChunkSize => "chunk_size",
             CompassConfigurationField::QueryTimeoutMs => "query_timeout_ms",
             CompassConfigurationField::IncludeTree => "include_tree",
             CompassConfigurationField::Plugins => "plugin",
@@ -45,6 +88,13 @@ impl CompassConfigurationField {
             CompassConfigurationField::SearchOrientation => "search_orientation",
             CompassConfigurationField::ResponsePersistencePolicy => "response_persistence_policy",
             CompassConfigurationField::ResponseOutputPolicy => "response_output_policy",
+            CompassConfigurationField::ResultCache => "result_cache",
+            CompassConfigurationField::SharedOrigin => "shared_origin",
+            CompassConfigurationField::Metrics => "metrics",
+            CompassConfigurationField::Reproducibility => "reproducibility",
+            CompassConfigurationField::Progress => "progress",
+            CompassConfigurationField::Include => "include",
+            CompassConfigurationField::StrictConfig => "strict_config",
         }
     }
 }
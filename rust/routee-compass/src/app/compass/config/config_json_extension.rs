@@ -7,6 +7,7 @@ use std::{
 };
 
 const FILE_NORMALIZATION_POSTFIX: &str = "_input_file";
+const FILE_MAP_NORMALIZATION_POSTFIX: &str = "_input_files";
 
 pub trait ConfigJsonExtensions {
     fn get_config_section(
@@ -306,7 +307,9 @@ impl ConfigJsonExtensions for serde_json::Value {
     ///
     /// This function scans each key value pair in the config and for any key that
     /// ends with `_input_file`, it will attempt to normalize the path such that the application
-    /// can find the file regardless of where it is being executed.
+    /// can find the file regardless of where it is being executed. each value of a table
+    /// under a key that ends with `_input_files`, which maps names to files, is also
+    /// normalized.
     ///
     /// Arguments:
     ///
@@ -354,8 +357,12 @@ impl ConfigJsonExtensions for serde_json::Value {
             }
             serde_json::Value::Object(obj) => {
                 let mut new_obj = serde_json::map::Map::new();
+                let file_map = parent_key
+                    .as_ref()
+                    .ends_with(FILE_MAP_NORMALIZATION_POSTFIX);
                 for (key, value) in obj.iter() {
                     if key.ends_with(FILE_NORMALIZATION_POSTFIX)
+                        || (file_map && value.is_string())
                         || value.is_object()
                         || value.is_array()
                     {
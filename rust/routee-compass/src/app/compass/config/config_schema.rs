@@ -0,0 +1,393 @@
+use super::{
+    compass_app_builder::CompassAppBuilder, compass_configuration_error::CompassConfigurationError,
+    compass_configuration_field::CompassConfigurationField, graph_builder::DefaultGraphBuilder,
+    traversal_model::custom_state_builder::CustomStateBuilder,
+};
+use crate::app::compass::compass_input_field::CompassInputField;
+use itertools::Itertools;
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+/// the smallest Jaro-Winkler similarity between an unknown key and a known key for the
+/// known key to be suggested as a correction
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    /// the configuration cannot be built
+    Error,
+    /// the configuration can be built, but part of it is ignored
+    Warning,
+}
+
+/// a problem found in a configuration, located by the dot-delimited path of its field
+#[derive(Serialize, Clone, Debug)]
+pub struct ConfigDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// checks a configuration before any data loads, so that every problem is reported at
+/// once rather than the first one found by a builder. the `type` of each model and plugin
+/// must name a registered builder, and the keys its builder requires must be present.
+/// keys unknown to the builder of a section are warned about with the closest known key
+/// as a suggestion, or are errors when `strict` is set.
+///
+/// # Arguments
+///
+/// * `config` - the configuration, before file paths are normalized
+/// * `builder` - the builders which may be named by the configuration
+/// * `strict` - if true, unknown keys are errors rather than warnings
+///
+/// # Returns
+///
+/// the warnings found, or an error listing every problem if any would prevent the
+/// configuration from being built
+pub fn validate_config(
+    config: &serde_json::Value,
+    builder: &CompassAppBuilder,
+    strict: bool,
+) -> Result<Vec<ConfigDiagnostic>, CompassConfigurationError> {
+    let (errors, warnings): (Vec<_>, Vec<_>) = check_config(config, builder, strict)
+        .into_iter()
+        .partition(|d| d.severity == DiagnosticSeverity::Error);
+    if errors.is_empty() {
+        Ok(warnings)
+    } else {
+        Err(CompassConfigurationError::InvalidConfiguration(
+            errors.into_iter().chain(warnings).collect(),
+        ))
+    }
+}
+
+/// finds every problem with a configuration. see [`validate_config`].
+pub fn check_config(
+    config: &serde_json::Value,
+    builder: &CompassAppBuilder,
+    strict: bool,
+) -> Vec<ConfigDiagnostic> {
+    let unknown_key_severity = if strict {
+        DiagnosticSeverity::Error
+    } else {
+        DiagnosticSeverity::Warning
+    };
+    let mut diagnostics = vec![];
+    let top_level_keys = CompassConfigurationField::ALL
+        .iter()
+        .map(|f| f.to_str())
+        .chain([CompassInputField::ConfigInputFile.to_str()])
+        .collect_vec();
+    check_keys(
+        "",
+        config,
+        &top_level_keys,
+        DiagnosticSeverity::Error,
+        &mut diagnostics,
+    );
+
+    check_typed_section(
+        CompassConfigurationField::Traversal.to_str(),
+        config.get(CompassConfigurationField::Traversal.to_str()),
+        &builder.traversal_model_builders,
+        |b| b.config_keys(),
+        |b| b.required_config_keys(),
+        unknown_key_severity,
+        &mut diagnostics,
+    );
+    check_typed_section(
+        CompassConfigurationField::Access.to_str(),
+        config.get(CompassConfigurationField::Access.to_str()),
+        &builder.access_model_builders,
+        |b| b.config_keys(),
+        |b| b.required_config_keys(),
+        unknown_key_severity,
+        &mut diagnostics,
+    );
+    check_typed_section(
+        CompassConfigurationField::Frontier.to_str(),
+        config.get(CompassConfigurationField::Frontier.to_str()),
+        &builder.frontier_builders,
+        |b| b.config_keys(),
+        |b| b.required_config_keys(),
+        unknown_key_severity,
+        &mut diagnostics,
+    );
+    let graph_path = CompassConfigurationField::Graph.to_str();
+    match config.get(graph_path) {
+        None => diagnostics.push(error(graph_path, String::from("missing required section"))),
+        Some(graph) => {
+            // the edge and vertex lists are not read when a compiled graph is given
+            if graph.get("compiled_graph_input_file").is_none() {
+                check_required_keys(
+                    graph_path,
+                    graph,
+                    &["edge_list_input_file", "vertex_list_input_file"],
+                    &mut diagnostics,
+                );
+            }
+            check_keys(
+                graph_path,
+                graph,
+                &DefaultGraphBuilder::CONFIG_KEYS,
+                unknown_key_severity,
+                &mut diagnostics,
+            );
+        }
+    }
+
+    if let Some(custom_state) = config.get(CompassConfigurationField::CustomState.to_str()) {
+        check_keys(
+            CompassConfigurationField::CustomState.to_str(),
+            custom_state,
+            &CustomStateBuilder::CONFIG_KEYS,
+            unknown_key_severity,
+            &mut diagnostics,
+        );
+    }
+
+    let plugins = config.get(CompassConfigurationField::Plugins.to_str());
+    let plugin_sections = |field: CompassConfigurationField| {
+        plugins
+            .and_then(|p| p.get(field.to_str()))
+            .and_then(|p| p.as_array())
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(move |(idx, plugin)| {
+                let path = format!("{}.{}[{}]", CompassConfigurationField::Plugins, field, idx);
+                (path, plugin)
+            })
+    };
+    for (path, plugin) in plugin_sections(CompassConfigurationField::InputPlugins) {
+        check_typed_section(
+            &path,
+            Some(&plugin),
+            &builder.input_plugin_builders,
+            |b| b.config_keys(),
+            |b| b.required_config_keys(),
+            unknown_key_severity,
+            &mut diagnostics,
+        );
+    }
+    for (path, plugin) in plugin_sections(CompassConfigurationField::OutputPlugins) {
+        check_typed_section(
+            &path,
+            Some(&plugin),
+            &builder.output_plugin_builders,
+            |b| b.config_keys(),
+            |b| b.required_config_keys(),
+            unknown_key_severity,
+            &mut diagnostics,
+        );
+    }
+
+    diagnostics
+}
+
+/// checks that a section names a registered builder with its `type`, that it has the
+/// keys required by that builder, and that its keys are known to that builder
+fn check_typed_section<B: ?Sized>(
+    path: &str,
+    section: Option<&serde_json::Value>,
+    builders: &HashMap<String, Rc<B>>,
+    config_keys: impl Fn(&B) -> Option<Vec<&'static str>>,
+    required_config_keys: impl Fn(&B) -> Vec<&'static str>,
+    unknown_key_severity: DiagnosticSeverity,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    let section = match section {
+        None => return,
+        Some(section) => section,
+    };
+    let type_path = join_path(path, "type");
+    let type_name = match section.get("type") {
+        Some(serde_json::Value::String(type_name)) => type_name,
+        Some(other) => {
+            diagnostics.push(error(
+                &type_path,
+                format!("expected a string, found {}", other),
+            ));
+            return;
+        }
+        None => {
+            diagnostics.push(error(path, String::from("missing required key `type`")));
+            return;
+        }
+    };
+    let builder = match builders.get(type_name) {
+        Some(builder) => builder,
+        None => {
+            let names = builders.keys().map(|k| k.as_str()).sorted().collect_vec();
+            let message = match suggest(type_name, &names) {
+                Some(suggestion) => format!(
+                    "unknown type `{}`, did you mean `{}`?",
+                    type_name, suggestion
+                ),
+                None => format!(
+                    "unknown type `{}`, must be one of {}",
+                    type_name,
+                    names.join(", ")
+                ),
+            };
+            diagnostics.push(error(&type_path, message));
+            return;
+        }
+    };
+    check_required_keys(path, section, &required_config_keys(builder), diagnostics);
+    if let Some(mut keys) = config_keys(builder) {
+        keys.push("type");
+        check_keys(path, section, &keys, unknown_key_severity, diagnostics);
+    }
+}
+
+/// reports each required key missing from an object as an error
+fn check_required_keys(
+    path: &str,
+    section: &serde_json::Value,
+    required_keys: &[&str],
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    let obj = match section.as_object() {
+        Some(obj) => obj,
+        None => return,
+    };
+    for key in required_keys.iter().filter(|k| !obj.contains_key(**k)) {
+        diagnostics.push(error(path, format!("missing required key `{}`", key)));
+    }
+}
+
+/// reports each key of an object which is not one of the known keys
+fn check_keys(
+    path: &str,
+    section: &serde_json::Value,
+    known_keys: &[&str],
+    severity: DiagnosticSeverity,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    let obj = match section.as_object() {
+        Some(obj) => obj,
+        None => return,
+    };
+    for key in obj.keys().filter(|k| !known_keys.contains(&k.as_str())) {
+        let message = match suggest(key, known_keys) {
+            Some(suggestion) => format!("unknown key `{}`, did you mean `{}`?", key, suggestion),
+            None => format!("unknown key `{}`", key),
+        };
+        diagnostics.push(ConfigDiagnostic {
+            severity,
+            path: join_path(path, key),
+            message,
+        });
+    }
+}
+
+/// finds the known name most similar to an unknown one, if any is similar enough
+fn suggest<'a>(unknown: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|k| (*k, strsim::jaro_winkler(unknown, k)))
+        .filter(|(_, similarity)| *similarity >= SUGGESTION_THRESHOLD)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(k, _)| k)
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn error(path: &str, message: String) -> ConfigDiagnostic {
+    ConfigDiagnostic {
+        severity: DiagnosticSeverity::Error,
+        path: path.to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_config() {
+        let config = json!({
+            "travesal": {},
+            "traversal": { "type": "speed_table", "speed_table_file": "speeds.csv" },
+            "frontier": { "type": "road_klass" },
+            "plugin": { "input_plugins": [{ "type": "grid_search" }], "output_plugins": [{}] }
+        });
+        let diagnostics = check_config(&config, &CompassAppBuilder::default(), false);
+        let messages = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.to_string()))
+            .collect_vec();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    DiagnosticSeverity::Error,
+                    String::from("travesal: unknown key `travesal`, did you mean `traversal`?")
+                ),
+                (
+                    DiagnosticSeverity::Error,
+                    String::from("traversal: missing required key `speed_table_input_file`")
+                ),
+                (
+                    DiagnosticSeverity::Error,
+                    String::from("traversal: missing required key `speed_unit`")
+                ),
+                (
+                    DiagnosticSeverity::Warning,
+                    String::from("traversal.speed_table_file: unknown key `speed_table_file`, did you mean `speed_table_input_file`?")
+                ),
+                (
+                    DiagnosticSeverity::Error,
+                    String::from("frontier.type: unknown type `road_klass`, did you mean `road_class`?")
+                ),
+                (
+                    DiagnosticSeverity::Error,
+                    String::from("graph: missing required section")
+                ),
+                (
+                    DiagnosticSeverity::Error,
+                    String::from("plugin.output_plugins[0]: missing required key `type`")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_config_strict() {
+        let config = json!({
+            "graph": { "compiled_graph_input_file": "graph.bin", "verbos": true },
+            "traversal": { "type": "distance", "distance_units": "miles" }
+        });
+        let builder = CompassAppBuilder::default();
+        assert!(validate_config(&config, &builder, false).is_ok());
+        let errors = match validate_config(&config, &builder, true) {
+            Err(CompassConfigurationError::InvalidConfiguration(errors)) => errors,
+            other => panic!("expected invalid configuration, found {:?}", other),
+        };
+        let messages = errors.iter().map(|d| d.to_string()).collect_vec();
+        assert_eq!(
+            messages,
+            vec![
+                String::from("traversal.distance_units: unknown key `distance_units`, did you mean `distance_unit`?"),
+                String::from("graph.verbos: unknown key `verbos`, did you mean `verbose`?"),
+            ]
+        );
+    }
+}
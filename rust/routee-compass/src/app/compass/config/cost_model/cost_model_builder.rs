@@ -4,26 +4,46 @@ use crate::app::compass::config::{
     compass_configuration_field::CompassConfigurationField,
     config_json_extension::ConfigJsonExtensions,
 };
-use routee_compass_core::model::cost::{
-    cost_aggregation::CostAggregation, network::network_cost_rate::NetworkCostRate,
-    vehicle::vehicle_cost_rate::VehicleCostRate,
+use routee_compass_core::model::{
+    cost::{
+        cost_aggregation::CostAggregation, cost_normalization::CostNormalization,
+        network::network_cost_rate::NetworkCostRate, vehicle::vehicle_cost_rate::VehicleCostRate,
+    },
+    road_network::graph::Graph,
 };
 use std::{collections::HashMap, sync::Arc};
 
 pub struct CostModelBuilder {}
 
 impl CostModelBuilder {
+    /// builds the cost model of the `[cost]` section. network rates which read edge
+    /// geometries use those of the graph unless they configure their own file.
     pub fn build(
         &self,
         config: &serde_json::Value,
+        graph: &Graph,
     ) -> Result<CostModelService, CompassConfigurationError> {
         let parent_key = CompassConfigurationField::Cost.to_string();
         let vehicle_rates: HashMap<String, VehicleCostRate> = config
             .get_config_serde_optional(&"vehicle_rates", &parent_key)?
             .unwrap_or_default();
         let network_rates: HashMap<String, NetworkCostRate> = config
-            .get_config_serde_optional(&"network_rates", &parent_key)?
-            .unwrap_or_default();
+            .get_config_serde_optional::<HashMap<String, NetworkCostRate>>(
+                &"network_rates",
+                &parent_key,
+            )?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, rate)| {
+                let rate = rate.with_graph(graph).map_err(|e| {
+                    CompassConfigurationError::UserConfigurationError(format!(
+                        "invalid network rate for '{}': {}",
+                        name, e
+                    ))
+                })?;
+                Ok((name, rate))
+            })
+            .collect::<Result<_, CompassConfigurationError>>()?;
 
         let weights: HashMap<String, f64> = config
             .get_config_serde_optional(&"weights", &parent_key)?
@@ -32,6 +52,24 @@ impl CostModelBuilder {
             .get_config_serde_optional(&"cost_aggregation", &parent_key)?
             .unwrap_or_default();
 
+        let normalization = config
+            .get_config_serde_optional::<HashMap<String, CostNormalization>>(
+                &"normalization",
+                &parent_key,
+            )?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, n)| {
+                let coefficient = n.coefficient().map_err(|e| {
+                    CompassConfigurationError::UserConfigurationError(format!(
+                        "invalid normalization for '{}': {}",
+                        name, e
+                    ))
+                })?;
+                Ok((name, coefficient))
+            })
+            .collect::<Result<HashMap<_, _>, CompassConfigurationError>>()?;
+
         let ignore_unknown_weights = config
             .get_config_serde_optional(&"ignore_unknown_user_provided_weights", &parent_key)?
             .unwrap_or(true);
@@ -40,6 +78,7 @@ impl CostModelBuilder {
             vehicle_rates: Arc::new(vehicle_rates),
             network_rates: Arc::new(network_rates),
             weights: Arc::new(weights),
+            normalization: Arc::new(normalization),
             cost_aggregation,
             ignore_unknown_weights,
         };
@@ -2,10 +2,13 @@ use crate::app::compass::config::compass_configuration_error::CompassConfigurati
 use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
 use routee_compass_core::model::{
     cost::{
-        cost_aggregation::CostAggregation, cost_model::CostModel,
-        network::network_cost_rate::NetworkCostRate, vehicle::vehicle_cost_rate::VehicleCostRate,
+        cost_aggregation::CostAggregation,
+        cost_model::CostModel,
+        network::{managed_lane_table::ManagedLaneTraveler, network_cost_rate::NetworkCostRate},
+        vehicle::vehicle_cost_rate::VehicleCostRate,
     },
     state::state_model::StateModel,
+    traversal::default::time_dependent_speed_service::TimeDependentSpeedService,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -16,6 +19,8 @@ pub struct CostModelService {
     pub vehicle_rates: Arc<HashMap<String, VehicleCostRate>>,
     pub network_rates: Arc<HashMap<String, NetworkCostRate>>,
     pub weights: Arc<HashMap<String, f64>>,
+    /// coefficients which scale each feature by the inverse of its expected range
+    pub normalization: Arc<HashMap<String, f64>>,
     pub cost_aggregation: CostAggregation,
     pub ignore_unknown_weights: bool,
 }
@@ -39,7 +44,7 @@ impl CostModelService {
     ///
     /// * `query` - search query
     /// * `traversal_state_variable_names` - list of names describing each slot in the state vector
-    ///                            used by the instantiated traversal model.
+    ///   used by the instantiated traversal model.
     ///
     /// # Result
     ///
@@ -83,6 +88,20 @@ impl CostModelService {
             return Err(CompassConfigurationError::UserConfigurationError(msg));
         }
 
+        // the effective weights include any normalization coefficients
+        let weights = if self.normalization.is_empty() {
+            weights
+        } else {
+            let normalized = weights
+                .iter()
+                .map(|(name, weight)| {
+                    let coefficient = self.normalization.get(name).unwrap_or(&1.0);
+                    (name.clone(), weight * coefficient)
+                })
+                .collect::<HashMap<_, _>>();
+            Arc::new(normalized)
+        };
+
         // the user can append/replace rates from the query
         let vehicle_rates = query
             .get_config_serde_optional::<HashMap<String, VehicleCostRate>>(
@@ -98,10 +117,35 @@ impl CostModelService {
             .get_config_serde_optional(&"cost_aggregation", &"cost_model")?
             .unwrap_or(self.cost_aggregation.to_owned());
 
+        // time-of-day dependent network rates such as tolls use the query departure time,
+        // and managed lane tolls use the query occupancy and managed lane pass
+        let departure_seconds = TimeDependentSpeedService::departure_seconds(query)
+            .map_err(|e| CompassConfigurationError::UserConfigurationError(e.to_string()))?;
+        let network_rates = if departure_seconds.is_none() && !ManagedLaneTraveler::in_query(query)
+        {
+            self.network_rates.clone()
+        } else {
+            let rates = self
+                .network_rates
+                .iter()
+                .map(|(name, rate)| {
+                    let rate = match departure_seconds {
+                        Some(seconds) => rate.with_departure_seconds(seconds),
+                        None => rate.clone(),
+                    };
+                    let rate = rate.with_managed_lane_query(query).map_err(|e| {
+                        CompassConfigurationError::UserConfigurationError(e.to_string())
+                    })?;
+                    Ok((name.clone(), rate))
+                })
+                .collect::<Result<HashMap<_, _>, CompassConfigurationError>>()?;
+            Arc::new(rates)
+        };
+
         let model = CostModel::new(
             weights,
             vehicle_rates,
-            self.network_rates.clone(),
+            network_rates,
             cost_aggregation,
             state_model,
         )
@@ -115,3 +159,49 @@ impl CostModelService {
         Ok(model)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::{
+        state::state_feature::StateFeature,
+        unit::{Distance, DistanceUnit, Time, TimeUnit},
+    };
+
+    #[test]
+    fn test_query_weights_with_normalization() {
+        let service = CostModelService {
+            vehicle_rates: Arc::new(HashMap::new()),
+            network_rates: Arc::new(HashMap::new()),
+            weights: Arc::new(HashMap::from([(String::from("distance"), 1.0)])),
+            normalization: Arc::new(HashMap::from([
+                (String::from("distance"), 0.1),
+                (String::from("time"), 0.5),
+            ])),
+            cost_aggregation: CostAggregation::Sum,
+            ignore_unknown_weights: true,
+        };
+        let state_model = Arc::new(StateModel::new(vec![
+            (
+                String::from("distance"),
+                StateFeature::Distance {
+                    distance_unit: DistanceUnit::Kilometers,
+                    initial: Distance::ZERO,
+                },
+            ),
+            (
+                String::from("time"),
+                StateFeature::Time {
+                    time_unit: TimeUnit::Minutes,
+                    initial: Time::ZERO,
+                },
+            ),
+        ]));
+        let query = serde_json::json!({ "weights": { "distance": 0.4, "time": 0.6 } });
+        let model = service.build(&query, state_model).unwrap();
+        let info = model.serialize_cost_info().unwrap();
+        let weight = |name: &str| info[name]["weight"].as_f64().unwrap();
+        assert!((weight("distance") - 0.04).abs() < 1e-9);
+        assert!((weight("time") - 0.3).abs() < 1e-9);
+    }
+}
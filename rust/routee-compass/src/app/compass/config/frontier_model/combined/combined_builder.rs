@@ -4,9 +4,12 @@ use crate::app::compass::config::{
     config_json_extension::ConfigJsonExtensions,
 };
 use itertools::Itertools;
-use routee_compass_core::model::frontier::{
-    frontier_model_builder::FrontierModelBuilder, frontier_model_error::FrontierModelError,
-    frontier_model_service::FrontierModelService,
+use routee_compass_core::model::{
+    frontier::{
+        frontier_model_builder::FrontierModelBuilder, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+    road_network::graph::Graph,
 };
 use std::{collections::HashMap, rc::Rc, sync::Arc};
 
@@ -29,6 +32,7 @@ impl CombinedBuilder {
     fn build_service(
         &self,
         config: &serde_json::Value,
+        graph: Option<Arc<Graph>>,
     ) -> Result<Arc<dyn FrontierModelService>, CompassConfigurationError> {
         let fm_type_obj = config.get("type").ok_or_else(|| {
             CompassConfigurationError::ExpectedFieldForComponent(
@@ -55,16 +59,18 @@ impl CombinedBuilder {
                 )
             })
             .and_then(|b| {
-                b.build(config)
-                    .map_err(CompassConfigurationError::FrontierModelError)
+                match &graph {
+                    Some(graph) => b.build_with_graph(config, graph.clone()),
+                    None => b.build(config),
+                }
+                .map_err(CompassConfigurationError::FrontierModelError)
             })
     }
-}
 
-impl FrontierModelBuilder for CombinedBuilder {
-    fn build(
+    fn build_combined(
         &self,
         parameters: &serde_json::Value,
+        graph: Option<Arc<Graph>>,
     ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
         let frontier_key = CompassConfigurationField::Frontier;
         let params = parameters
@@ -73,7 +79,7 @@ impl FrontierModelBuilder for CombinedBuilder {
 
         let inner_services = params
             .iter()
-            .map(|p| self.build_service(p))
+            .map(|p| self.build_service(p, graph.clone()))
             .collect::<Result<Vec<Arc<dyn FrontierModelService>>, CompassConfigurationError>>()
             .map_err(|e| FrontierModelError::BuildError(e.to_string()))?;
 
@@ -82,3 +88,28 @@ impl FrontierModelBuilder for CombinedBuilder {
         Ok(Arc::new(service))
     }
 }
+
+impl FrontierModelBuilder for CombinedBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["models"])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["models"]
+    }
+
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        self.build_combined(parameters, None)
+    }
+
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        self.build_combined(parameters, Some(graph))
+    }
+}
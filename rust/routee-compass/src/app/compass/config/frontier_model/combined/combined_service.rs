@@ -4,6 +4,7 @@ use routee_compass_core::model::{
         frontier_model::FrontierModel, frontier_model_error::FrontierModelError,
         frontier_model_service::FrontierModelService,
     },
+    road_network::edge_id::EdgeId,
     state::state_model::StateModel,
 };
 use std::sync::Arc;
@@ -27,4 +28,13 @@ impl FrontierModelService for CombinedFrontierService {
         let model = CombinedFrontierModel { inner_models };
         Ok(Arc::new(model))
     }
+
+    fn edge_road_class(&self, edge_id: EdgeId) -> Result<Option<u8>, FrontierModelError> {
+        for service in self.inner_services.iter() {
+            if let Some(road_class) = service.edge_road_class(edge_id)? {
+                return Ok(Some(road_class));
+            }
+        }
+        Ok(None)
+    }
 }
@@ -0,0 +1,80 @@
+use super::{
+    edge_attribute_restriction::EdgeAttributeRestriction,
+    edge_attribute_service::EdgeAttributeFrontierService,
+};
+use crate::app::compass::config::{
+    compass_configuration_field::CompassConfigurationField,
+    config_json_extension::ConfigJsonExtensions,
+};
+use routee_compass_core::model::{
+    frontier::{
+        frontier_model_builder::FrontierModelBuilder, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+    property::edge_attribute_store::{EdgeAttributeColumnConfig, EdgeAttributeStore},
+    road_network::graph::Graph,
+};
+use std::sync::Arc;
+
+/// builds a frontier model that restricts edges by the values of columns of an
+/// edge attribute file, such as excluding toll roads or unpaved surfaces. without an
+/// `edge_attribute_input_file`, the edge attributes loaded with the graph are used.
+pub struct EdgeAttributeBuilder {}
+
+impl FrontierModelBuilder for EdgeAttributeBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["edge_attribute_input_file", "columns", "restrictions"])
+    }
+
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        Err(FrontierModelError::BuildError(String::from(
+            "the edge_attribute frontier model is sized by the graph and must be built with it",
+        )))
+    }
+
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        let frontier_key = CompassConfigurationField::Frontier.to_string();
+        let build_error = |e: &dyn std::fmt::Display| FrontierModelError::BuildError(e.to_string());
+
+        let input_file = parameters
+            .get_config_path_optional(&"edge_attribute_input_file", &frontier_key)
+            .map_err(|e| build_error(&e))?;
+        let edge_attributes = match input_file {
+            Some(input_file) => {
+                let columns: Vec<EdgeAttributeColumnConfig> = parameters
+                    .get_config_serde(&"columns", &frontier_key)
+                    .map_err(|e| build_error(&e))?;
+                let store = EdgeAttributeStore::from_file(&input_file, &columns, graph.n_edges())
+                    .map_err(|e| build_error(&e))?;
+                Arc::new(store)
+            }
+            None => graph
+                .edge_attributes()
+                .map_err(|e| build_error(&e))?
+                .clone(),
+        };
+
+        let default_restrictions: Vec<EdgeAttributeRestriction> = parameters
+            .get_config_serde_optional(&"restrictions", &frontier_key)
+            .map_err(|e| build_error(&e))?
+            .unwrap_or_default();
+        for restriction in default_restrictions.iter() {
+            restriction
+                .validate(&edge_attributes)
+                .map_err(FrontierModelError::BuildError)?;
+        }
+
+        let service = EdgeAttributeFrontierService {
+            edge_attributes,
+            default_restrictions,
+        };
+        Ok(Arc::new(service))
+    }
+}
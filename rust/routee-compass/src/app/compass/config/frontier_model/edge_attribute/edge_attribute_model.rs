@@ -0,0 +1,38 @@
+use super::{
+    edge_attribute_restriction::EdgeAttributeRestriction,
+    edge_attribute_service::EdgeAttributeFrontierService,
+};
+use routee_compass_core::model::{
+    frontier::{frontier_model::FrontierModel, frontier_model_error::FrontierModelError},
+    property::edge::Edge,
+    state::state_model::StateModel,
+    traversal::state::state_variable::StateVar,
+};
+use std::sync::Arc;
+
+pub struct EdgeAttributeFrontierModel {
+    pub service: Arc<EdgeAttributeFrontierService>,
+    pub restrictions: Vec<EdgeAttributeRestriction>,
+}
+
+impl FrontierModel for EdgeAttributeFrontierModel {
+    fn valid_frontier(
+        &self,
+        edge: &Edge,
+        _state: &[StateVar],
+        _previous_edge: Option<&Edge>,
+        _state_model: &StateModel,
+    ) -> Result<bool, FrontierModelError> {
+        for restriction in self.restrictions.iter() {
+            let value = self
+                .service
+                .edge_attributes
+                .get(&restriction.column, edge.edge_id)
+                .map_err(|e| FrontierModelError::MissingIndex(e.to_string()))?;
+            if !restriction.allows(value) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,143 @@
+use routee_compass_core::model::property::edge_attribute_store::{
+    EdgeAttributeColumnType, EdgeAttributeStore, EdgeAttributeValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// a rule on one edge attribute column which an edge must pass to be traversed.
+/// an edge passes when its value `equals` the given value, is `one_of` a list of
+/// values, and lies within `min` and `max` (inclusive), for each of these provided.
+///
+/// ```toml
+/// restrictions = [
+///   { column = "toll", equals = false },
+///   { column = "surface", one_of = ["paved", "compacted"] },
+///   { column = "max_weight_tonnes", min = 12.0 },
+/// ]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EdgeAttributeRestriction {
+    pub column: String,
+    #[serde(default)]
+    pub equals: Option<Value>,
+    #[serde(default)]
+    pub one_of: Option<Vec<Value>>,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+}
+
+impl EdgeAttributeRestriction {
+    /// checks that the column exists and that each rule applies to its type
+    pub fn validate(&self, store: &EdgeAttributeStore) -> Result<(), String> {
+        let column_type = store
+            .column(&self.column)
+            .map_err(|e| e.to_string())?
+            .column_type();
+        let numeric = matches!(
+            column_type,
+            EdgeAttributeColumnType::F64 | EdgeAttributeColumnType::I64
+        );
+        if !numeric && (self.min.is_some() || self.max.is_some()) {
+            return Err(format!(
+                "min and max only apply to numeric columns, but {} has type {}",
+                self.column, column_type
+            ));
+        }
+        let expected = self.equals.iter().chain(self.one_of.iter().flatten());
+        for value in expected {
+            let valid = match column_type {
+                EdgeAttributeColumnType::F64 => value.is_number(),
+                EdgeAttributeColumnType::I64 => value.is_i64(),
+                EdgeAttributeColumnType::Bool => value.is_boolean(),
+                EdgeAttributeColumnType::Categorical => value.is_string(),
+            };
+            if !valid {
+                return Err(format!(
+                    "value {} does not match the type {} of column {}",
+                    value, column_type, self.column
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn allows(&self, value: EdgeAttributeValue) -> bool {
+        let equals = self
+            .equals
+            .as_ref()
+            .map(|e| matches(e, value))
+            .unwrap_or(true);
+        let one_of = self
+            .one_of
+            .as_ref()
+            .map(|vs| vs.iter().any(|e| matches(e, value)))
+            .unwrap_or(true);
+        let number = match value {
+            EdgeAttributeValue::F64(v) => Some(v),
+            EdgeAttributeValue::I64(v) => Some(v as f64),
+            _ => None,
+        };
+        let above_min = match (self.min, number) {
+            (Some(min), Some(v)) => v >= min,
+            _ => true,
+        };
+        let below_max = match (self.max, number) {
+            (Some(max), Some(v)) => v <= max,
+            _ => true,
+        };
+        equals && one_of && above_min && below_max
+    }
+}
+
+fn matches(expected: &Value, value: EdgeAttributeValue) -> bool {
+    match value {
+        EdgeAttributeValue::F64(v) => expected.as_f64() == Some(v),
+        EdgeAttributeValue::I64(v) => expected.as_i64() == Some(v),
+        EdgeAttributeValue::Bool(v) => expected.as_bool() == Some(v),
+        EdgeAttributeValue::Categorical(v) => expected.as_str() == Some(v),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::{
+        property::edge_attribute_store::EdgeAttributeColumnConfig, road_network::edge_id::EdgeId,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_restrictions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("edge-attributes.csv");
+        std::fs::write(&path, "toll,surface,lanes\nfalse,paved,2\ntrue,gravel,1\n").unwrap();
+        let columns: Vec<EdgeAttributeColumnConfig> = serde_json::from_value(json!([
+            { "name": "toll", "type": "bool" },
+            { "name": "surface", "type": "categorical" },
+            { "name": "lanes", "type": "i64" },
+        ]))
+        .unwrap();
+        let store = EdgeAttributeStore::from_file(&path, &columns, 2).unwrap();
+
+        let restrictions: Vec<EdgeAttributeRestriction> = serde_json::from_value(json!([
+            { "column": "toll", "equals": false },
+            { "column": "surface", "one_of": ["paved", "compacted"] },
+            { "column": "lanes", "min": 2 },
+        ]))
+        .unwrap();
+        let allowed = |edge_id: usize| {
+            restrictions.iter().all(|r| {
+                r.validate(&store).unwrap();
+                r.allows(store.get(&r.column, EdgeId(edge_id)).unwrap())
+            })
+        };
+        assert!(allowed(0));
+        assert!(!allowed(1));
+
+        let invalid: EdgeAttributeRestriction =
+            serde_json::from_value(json!({ "column": "surface", "min": 1.0 })).unwrap();
+        assert!(invalid.validate(&store).is_err());
+    }
+}
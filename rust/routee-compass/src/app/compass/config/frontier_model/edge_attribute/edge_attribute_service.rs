@@ -0,0 +1,57 @@
+use super::{
+    edge_attribute_model::EdgeAttributeFrontierModel,
+    edge_attribute_restriction::EdgeAttributeRestriction,
+};
+use routee_compass_core::model::{
+    frontier::{
+        frontier_model::FrontierModel, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+    property::edge_attribute_store::EdgeAttributeStore,
+    state::state_model::StateModel,
+};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct EdgeAttributeFrontierService {
+    pub edge_attributes: Arc<EdgeAttributeStore>,
+    pub default_restrictions: Vec<EdgeAttributeRestriction>,
+}
+
+impl EdgeAttributeFrontierService {
+    /// restrictions provided with a query replace the configured restrictions
+    pub const QUERY_KEY: &'static str = "edge_attribute_restrictions";
+}
+
+impl FrontierModelService for EdgeAttributeFrontierService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn FrontierModel>, FrontierModelError> {
+        let restrictions = match query.get(Self::QUERY_KEY) {
+            None => self.default_restrictions.clone(),
+            Some(value) => {
+                let restrictions: Vec<EdgeAttributeRestriction> =
+                    serde_json::from_value(value.clone()).map_err(|e| {
+                        FrontierModelError::BuildError(format!(
+                            "unable to parse query {}: {}",
+                            Self::QUERY_KEY,
+                            e
+                        ))
+                    })?;
+                for restriction in restrictions.iter() {
+                    restriction
+                        .validate(&self.edge_attributes)
+                        .map_err(FrontierModelError::BuildError)?;
+                }
+                restrictions
+            }
+        };
+        let model = EdgeAttributeFrontierModel {
+            service: Arc::new(self.clone()),
+            restrictions,
+        };
+        Ok(Arc::new(model))
+    }
+}
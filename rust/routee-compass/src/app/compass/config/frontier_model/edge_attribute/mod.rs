@@ -0,0 +1,4 @@
+pub mod edge_attribute_builder;
+pub mod edge_attribute_model;
+pub mod edge_attribute_restriction;
+pub mod edge_attribute_service;
@@ -0,0 +1,94 @@
+use super::{geofence_ops::polygons_from_geojson, geofence_service::GeofenceFrontierService};
+use crate::{
+    app::compass::config::{
+        compass_configuration_field::CompassConfigurationField,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::input::default::edge_rtree::edge_rtree_record::EdgeRtreeRecord,
+};
+use geo::Polygon;
+use routee_compass_core::model::{
+    frontier::{
+        frontier_model_builder::FrontierModelBuilder, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+    road_network::{edge_geometry_store::EdgeGeometryStore, graph::Graph},
+};
+use rstar::RTree;
+use std::{path::Path, sync::Arc};
+
+/// builds a frontier model which avoids or stays within polygons. edges are found from
+/// the geometries loaded with the graph, or else from a `geometry_input_file`.
+pub struct GeofenceBuilder {}
+
+impl FrontierModelBuilder for GeofenceBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "avoid_polygons_input_file",
+            "within_polygons_input_file",
+            "geometry_input_file",
+        ])
+    }
+
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        Err(FrontierModelError::BuildError(String::from(
+            "the geofence frontier model reads the edge geometries of the graph and must be built with it",
+        )))
+    }
+
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        let frontier_key = CompassConfigurationField::Frontier.to_string();
+        let geometry_file = parameters
+            .get_config_path_optional(&"geometry_input_file", &frontier_key)
+            .map_err(|e| FrontierModelError::BuildError(e.to_string()))?;
+        let avoid_file = parameters
+            .get_config_path_optional(&"avoid_polygons_input_file", &frontier_key)
+            .map_err(|e| FrontierModelError::BuildError(e.to_string()))?;
+        let within_file = parameters
+            .get_config_path_optional(&"within_polygons_input_file", &frontier_key)
+            .map_err(|e| FrontierModelError::BuildError(e.to_string()))?;
+
+        let geometries = EdgeGeometryStore::from_graph_or_file(&graph, geometry_file.as_ref())
+            .map_err(|e| {
+                FrontierModelError::BuildError(format!("failed to load edge geometries: {}", e))
+            })?;
+        let records = EdgeRtreeRecord::from_geometries(&geometries);
+        let avoid_polygons = match avoid_file {
+            None => vec![],
+            Some(file) => read_polygons_file(&file)?,
+        };
+        let within_polygons = match within_file {
+            None => vec![],
+            Some(file) => read_polygons_file(&file)?,
+        };
+
+        let service = GeofenceFrontierService::new(
+            Arc::new(RTree::bulk_load(records)),
+            &avoid_polygons,
+            &within_polygons,
+        );
+        Ok(Arc::new(service))
+    }
+}
+
+/// reads the polygons from a GeoJSON file
+fn read_polygons_file(file: &Path) -> Result<Vec<Polygon<f32>>, FrontierModelError> {
+    let contents = std::fs::read_to_string(file).map_err(|e| {
+        FrontierModelError::BuildError(format!("failed to load file at {:?}: {}", file.to_str(), e))
+    })?;
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+        FrontierModelError::BuildError(format!(
+            "failed to parse GeoJSON file at {:?}: {}",
+            file.to_str(),
+            e
+        ))
+    })?;
+    polygons_from_geojson(&value)
+}
@@ -0,0 +1,47 @@
+use routee_compass_core::model::{
+    frontier::{frontier_model::FrontierModel, frontier_model_error::FrontierModelError},
+    property::edge::Edge,
+    road_network::edge_id::EdgeId,
+    state::state_model::StateModel,
+    traversal::state::state_variable::StateVar,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// restricts a search by the edges found in a set of polygons. edges intersecting
+/// an "avoid" polygon are never added to the frontier, and if any "within" polygons
+/// were provided, only edges intersecting them are. the edges of the configured
+/// polygons are shared by every query, and the edges of a query's own polygons are
+/// held alongside them.
+pub struct GeofenceFrontierModel {
+    pub avoid_edges: Arc<HashSet<EdgeId>>,
+    pub within_edges: Option<Arc<HashSet<EdgeId>>>,
+    pub query_avoid_edges: HashSet<EdgeId>,
+    pub query_within_edges: Option<HashSet<EdgeId>>,
+}
+
+impl FrontierModel for GeofenceFrontierModel {
+    fn valid_frontier(
+        &self,
+        edge: &Edge,
+        _state: &[StateVar],
+        _previous_edge: Option<&Edge>,
+        _state_model: &StateModel,
+    ) -> Result<bool, FrontierModelError> {
+        if self.avoid_edges.contains(&edge.edge_id)
+            || self.query_avoid_edges.contains(&edge.edge_id)
+        {
+            return Ok(false);
+        }
+        match (&self.within_edges, &self.query_within_edges) {
+            (None, None) => Ok(true),
+            (within, query_within) => {
+                let in_config = within.as_ref().is_some_and(|w| w.contains(&edge.edge_id));
+                let in_query = query_within
+                    .as_ref()
+                    .is_some_and(|w| w.contains(&edge.edge_id));
+                Ok(in_config || in_query)
+            }
+        }
+    }
+}
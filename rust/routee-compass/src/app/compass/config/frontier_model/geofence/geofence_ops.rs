@@ -0,0 +1,69 @@
+use crate::plugin::input::default::edge_rtree::edge_rtree_record::EdgeRtreeRecord;
+use geo::{BoundingRect, Geometry, Intersects, Polygon};
+use geojson::GeoJson;
+use routee_compass_core::model::{
+    frontier::frontier_model_error::FrontierModelError, road_network::edge_id::EdgeId,
+};
+use rstar::{RTree, AABB};
+use std::collections::HashSet;
+
+/// reads the polygons from a GeoJSON value, which may be a FeatureCollection,
+/// a Feature or a Geometry. any geometry that is not a Polygon or MultiPolygon
+/// is an error.
+pub fn polygons_from_geojson(
+    value: &serde_json::Value,
+) -> Result<Vec<Polygon<f32>>, FrontierModelError> {
+    let geojson = GeoJson::from_json_value(value.clone()).map_err(|e| {
+        FrontierModelError::BuildError(format!("failure reading GeoJSON polygons: {}", e))
+    })?;
+    let geometry: Geometry<f32> = Geometry::try_from(geojson).map_err(|e| {
+        FrontierModelError::BuildError(format!("failure converting GeoJSON polygons: {}", e))
+    })?;
+    let mut polygons = vec![];
+    collect_polygons(geometry, &mut polygons)?;
+    Ok(polygons)
+}
+
+fn collect_polygons(
+    geometry: Geometry<f32>,
+    polygons: &mut Vec<Polygon<f32>>,
+) -> Result<(), FrontierModelError> {
+    match geometry {
+        Geometry::Polygon(p) => polygons.push(p),
+        Geometry::MultiPolygon(mp) => polygons.extend(mp.0),
+        Geometry::GeometryCollection(gc) => {
+            for g in gc.0 {
+                collect_polygons(g, polygons)?;
+            }
+        }
+        other => {
+            return Err(FrontierModelError::BuildError(format!(
+                "geofence geometries must be polygons, found {:?}",
+                other
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// finds the set of edges whose geometry intersects any of the polygons. candidate
+/// edges are found by the bounding box of each polygon before testing the geometries.
+pub fn edges_intersecting(
+    rtree: &RTree<EdgeRtreeRecord>,
+    polygons: &[Polygon<f32>],
+) -> HashSet<EdgeId> {
+    let mut edges = HashSet::new();
+    for polygon in polygons {
+        let rect = match polygon.bounding_rect() {
+            Some(rect) => rect,
+            None => continue,
+        };
+        let envelope = AABB::from_corners(rect.min().into(), rect.max().into());
+        for record in rtree.locate_in_envelope_intersecting(&envelope) {
+            if record.geometry().intersects(polygon) {
+                edges.insert(record.edge_id);
+            }
+        }
+    }
+    edges
+}
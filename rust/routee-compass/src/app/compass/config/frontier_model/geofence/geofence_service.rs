@@ -0,0 +1,156 @@
+use super::{
+    geofence_model::GeofenceFrontierModel,
+    geofence_ops::{edges_intersecting, polygons_from_geojson},
+};
+use crate::plugin::input::default::edge_rtree::edge_rtree_record::EdgeRtreeRecord;
+use geo::Polygon;
+use routee_compass_core::model::{
+    frontier::{
+        frontier_model::FrontierModel, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+    road_network::edge_id::EdgeId,
+    state::state_model::StateModel,
+};
+use rstar::RTree;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// builds frontier models which avoid or stay within polygon regions. polygons
+/// from the configuration apply to every query, and a query may add its own as
+/// GeoJSON with the `avoid_polygons` and `within_polygons` keys. the edges of the
+/// configured polygons are found once, when the service is built.
+pub struct GeofenceFrontierService {
+    pub rtree: Arc<RTree<EdgeRtreeRecord>>,
+    pub avoid_edges: Arc<HashSet<EdgeId>>,
+    pub within_edges: Option<Arc<HashSet<EdgeId>>>,
+}
+
+impl GeofenceFrontierService {
+    const AVOID_KEY: &'static str = "avoid_polygons";
+    const WITHIN_KEY: &'static str = "within_polygons";
+
+    /// finds the edges of the configured polygons
+    pub fn new(
+        rtree: Arc<RTree<EdgeRtreeRecord>>,
+        avoid_polygons: &[Polygon<f32>],
+        within_polygons: &[Polygon<f32>],
+    ) -> GeofenceFrontierService {
+        let avoid_edges = Arc::new(edges_intersecting(&rtree, avoid_polygons));
+        let within_edges = if within_polygons.is_empty() {
+            None
+        } else {
+            Some(Arc::new(edges_intersecting(&rtree, within_polygons)))
+        };
+        GeofenceFrontierService {
+            rtree,
+            avoid_edges,
+            within_edges,
+        }
+    }
+}
+
+impl FrontierModelService for GeofenceFrontierService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn FrontierModel>, FrontierModelError> {
+        let read_query_polygons = |key: &str| -> Result<Vec<Polygon<f32>>, FrontierModelError> {
+            match query.get(key) {
+                None => Ok(vec![]),
+                Some(value) => polygons_from_geojson(value).map_err(|e| {
+                    FrontierModelError::BuildError(format!("unable to read query '{}': {}", key, e))
+                }),
+            }
+        };
+        let avoid_polygons = read_query_polygons(GeofenceFrontierService::AVOID_KEY)?;
+        let within_polygons = read_query_polygons(GeofenceFrontierService::WITHIN_KEY)?;
+        let query_within_edges = if within_polygons.is_empty() {
+            None
+        } else {
+            Some(edges_intersecting(&self.rtree, &within_polygons))
+        };
+        let model = GeofenceFrontierModel {
+            avoid_edges: self.avoid_edges.clone(),
+            within_edges: self.within_edges.clone(),
+            query_avoid_edges: edges_intersecting(&self.rtree, &avoid_polygons),
+            query_within_edges,
+        };
+        Ok(Arc::new(model))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::LineString;
+    use routee_compass_core::model::{
+        property::edge::Edge, road_network::edge_geometry_store::EdgeGeometryStore,
+    };
+
+    /// three parallel edges running north, at longitudes 0, 1 and 2
+    fn build_service(within_polygons: Vec<Polygon<f32>>) -> GeofenceFrontierService {
+        let geometries = (0..3)
+            .map(|i| {
+                let x = i as f32;
+                LineString::from(vec![(x, 0.0), (x, 1.0)])
+            })
+            .collect();
+        let geometries = Arc::new(EdgeGeometryStore::new(geometries, 3).unwrap());
+        let records = EdgeRtreeRecord::from_geometries(&geometries);
+        GeofenceFrontierService::new(Arc::new(RTree::bulk_load(records)), &[], &within_polygons)
+    }
+
+    fn valid_edges(model: &Arc<dyn FrontierModel>) -> Vec<usize> {
+        let state_model = StateModel::empty();
+        (0..3)
+            .filter(|i| {
+                let edge = Edge::new(*i, 0, 1, 1.0);
+                model
+                    .valid_frontier(&edge, &[], None, &state_model)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_geofence_avoid_and_within() {
+        // covers longitudes 0.5 to 2.5
+        let within = polygons_from_geojson(&serde_json::json!({
+            "type": "Polygon",
+            "coordinates": [[[0.5, -1.0], [2.5, -1.0], [2.5, 2.0], [0.5, 2.0], [0.5, -1.0]]]
+        }))
+        .unwrap();
+        let service = build_service(within);
+        let state_model = Arc::new(StateModel::empty());
+
+        let model = service
+            .build(&serde_json::json!({}), state_model.clone())
+            .unwrap();
+        assert_eq!(valid_edges(&model), vec![1, 2]);
+
+        // covers longitudes 1.5 to 2.5
+        let query = serde_json::json!({
+            "avoid_polygons": {
+                "type": "FeatureCollection",
+                "features": [{
+                    "type": "Feature",
+                    "properties": {},
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[1.5, -1.0], [2.5, -1.0], [2.5, 2.0], [1.5, 2.0], [1.5, -1.0]]]
+                    }
+                }]
+            }
+        });
+        let model = service.build(&query, state_model).unwrap();
+        assert_eq!(valid_edges(&model), vec![1]);
+    }
+
+    #[test]
+    fn test_geofence_rejects_non_polygon() {
+        let point = serde_json::json!({ "type": "Point", "coordinates": [0.0, 0.0] });
+        assert!(polygons_from_geojson(&point).is_err());
+    }
+}
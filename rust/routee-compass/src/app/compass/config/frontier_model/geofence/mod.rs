@@ -0,0 +1,4 @@
+pub mod geofence_builder;
+pub mod geofence_model;
+pub mod geofence_ops;
+pub mod geofence_service;
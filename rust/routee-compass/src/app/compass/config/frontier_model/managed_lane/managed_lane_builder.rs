@@ -0,0 +1,59 @@
+use super::managed_lane_service::ManagedLaneFrontierService;
+use crate::app::compass::config::{
+    compass_configuration_field::CompassConfigurationField,
+    config_json_extension::ConfigJsonExtensions,
+};
+use routee_compass_core::model::{
+    cost::network::managed_lane_table::{ManagedLaneTable, ManagedLaneTraveler},
+    frontier::{
+        frontier_model_builder::FrontierModelBuilder, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+};
+use std::sync::Arc;
+
+pub struct ManagedLaneBuilder {}
+
+impl FrontierModelBuilder for ManagedLaneBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "managed_lane_input_file",
+            ManagedLaneTraveler::OCCUPANCY_KEY,
+            ManagedLaneTraveler::MANAGED_LANE_PASS_KEY,
+        ])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["managed_lane_input_file"]
+    }
+
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        let frontier_key = CompassConfigurationField::Frontier.to_string();
+        let managed_lane_file = parameters
+            .get_config_path(&"managed_lane_input_file", &frontier_key)
+            .map_err(|e| {
+                FrontierModelError::BuildError(format!(
+                    "configuration error due to managed_lane_input_file: {}",
+                    e
+                ))
+            })?;
+        let lanes = ManagedLaneTable::load(&managed_lane_file).map_err(|e| {
+            FrontierModelError::BuildError(format!(
+                "failed to load file at {:?}: {}",
+                managed_lane_file, e
+            ))
+        })?;
+        // traveler parameters in the configuration apply to queries which do not provide their own
+        let default_traveler =
+            ManagedLaneTraveler::from_query(parameters, &ManagedLaneTraveler::default())
+                .map_err(FrontierModelError::BuildError)?;
+        let service = ManagedLaneFrontierService {
+            lanes,
+            default_traveler,
+        };
+        Ok(Arc::new(service))
+    }
+}
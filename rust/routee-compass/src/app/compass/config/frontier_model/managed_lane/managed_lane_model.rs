@@ -0,0 +1,26 @@
+use routee_compass_core::model::{
+    cost::network::managed_lane_table::{ManagedLaneAccess, ManagedLaneTable, ManagedLaneTraveler},
+    frontier::{frontier_model::FrontierModel, frontier_model_error::FrontierModelError},
+    property::edge::Edge,
+    state::state_model::StateModel,
+    traversal::state::state_variable::StateVar,
+};
+use std::sync::Arc;
+
+pub struct ManagedLaneFrontierModel {
+    pub lanes: Arc<ManagedLaneTable>,
+    pub traveler: ManagedLaneTraveler,
+}
+
+impl FrontierModel for ManagedLaneFrontierModel {
+    fn valid_frontier(
+        &self,
+        edge: &Edge,
+        _state: &[StateVar],
+        _previous_edge: Option<&Edge>,
+        _state_model: &StateModel,
+    ) -> Result<bool, FrontierModelError> {
+        let access = self.lanes.access(edge.edge_id, &self.traveler);
+        Ok(access != ManagedLaneAccess::Prohibited)
+    }
+}
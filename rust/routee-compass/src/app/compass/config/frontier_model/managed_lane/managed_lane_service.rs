@@ -0,0 +1,34 @@
+use super::managed_lane_model::ManagedLaneFrontierModel;
+use routee_compass_core::model::{
+    cost::network::managed_lane_table::{ManagedLaneTable, ManagedLaneTraveler},
+    frontier::{
+        frontier_model::FrontierModel, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+    state::state_model::StateModel,
+};
+use std::sync::Arc;
+
+/// builds frontier models which keep each query off the managed lanes it may not use,
+/// given the `occupancy` and `managed_lane_pass` of the query.
+#[derive(Clone)]
+pub struct ManagedLaneFrontierService {
+    pub lanes: Arc<ManagedLaneTable>,
+    pub default_traveler: ManagedLaneTraveler,
+}
+
+impl FrontierModelService for ManagedLaneFrontierService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn FrontierModel>, FrontierModelError> {
+        let traveler = ManagedLaneTraveler::from_query(query, &self.default_traveler)
+            .map_err(FrontierModelError::BuildError)?;
+        let model = ManagedLaneFrontierModel {
+            lanes: self.lanes.clone(),
+            traveler,
+        };
+        Ok(Arc::new(model))
+    }
+}
@@ -0,0 +1,3 @@
+pub mod managed_lane_builder;
+pub mod managed_lane_model;
+pub mod managed_lane_service;
@@ -1,5 +1,9 @@
 pub mod combined;
+pub mod edge_attribute;
+pub mod geofence;
+pub mod managed_lane;
 pub mod no_restriction_builder;
 pub mod road_class;
+pub mod soc_floor;
 pub mod turn_restrictions;
 pub mod vehicle_restrictions;
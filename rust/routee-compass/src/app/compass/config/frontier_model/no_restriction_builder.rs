@@ -7,6 +7,10 @@ use std::sync::Arc;
 pub struct NoRestrictionBuilder {}
 
 impl FrontierModelBuilder for NoRestrictionBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![])
+    }
+
     fn build(
         &self,
         _parameters: &serde_json::Value,
@@ -15,6 +15,14 @@ use std::sync::Arc;
 pub struct RoadClassBuilder {}
 
 impl FrontierModelBuilder for RoadClassBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["road_class_input_file", "road_class_parser"])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["road_class_input_file"]
+    }
+
     fn build(
         &self,
         parameters: &serde_json::Value,
@@ -54,9 +62,25 @@ impl FrontierModelBuilder for RoadClassBuilder {
             })?
             .unwrap_or_default();
 
+        // road classes in the configuration apply to queries which do not provide their own,
+        // such as excluding motorways for a walk or bike profile
+        let default_road_classes = road_class_parser.read_query(parameters).map_err(|e| {
+            FrontierModelError::BuildError(format!("unable to read road_classes: {}", e))
+        })?;
+        let default_excluded_road_classes = road_class_parser
+            .read_query_exclude(parameters)
+            .map_err(|e| {
+                FrontierModelError::BuildError(format!(
+                    "unable to read road_classes_exclude: {}",
+                    e
+                ))
+            })?;
+
         let m: Arc<dyn FrontierModelService> = Arc::new(RoadClassFrontierService {
             road_class_lookup: Arc::new(road_class_lookup),
             road_class_parser,
+            default_road_classes,
+            default_excluded_road_classes,
         });
         Ok(m)
     }
@@ -10,6 +10,7 @@ use std::{collections::HashSet, sync::Arc};
 pub struct RoadClassFrontierModel {
     pub service: Arc<RoadClassFrontierService>,
     pub road_classes: Option<HashSet<u8>>,
+    pub excluded_road_classes: Option<HashSet<u8>>,
 }
 
 impl FrontierModel for RoadClassFrontierModel {
@@ -20,14 +21,24 @@ impl FrontierModel for RoadClassFrontierModel {
         _previous_edge: Option<&Edge>,
         _state_model: &StateModel,
     ) -> Result<bool, FrontierModelError> {
-        match &self.road_classes {
-            None => Ok(true),
-            Some(road_classes) => self
-                .service
-                .road_class_lookup
-                .get(edge.edge_id.0)
-                .ok_or_else(|| FrontierModelError::MissingIndex(format!("{}", edge.edge_id)))
-                .map(|road_class| road_classes.contains(road_class)),
+        if self.road_classes.is_none() && self.excluded_road_classes.is_none() {
+            return Ok(true);
         }
+        let road_class = self
+            .service
+            .road_class_lookup
+            .get(edge.edge_id.0)
+            .ok_or_else(|| FrontierModelError::MissingIndex(format!("{}", edge.edge_id)))?;
+        let allowed = self
+            .road_classes
+            .as_ref()
+            .map(|rcs| rcs.contains(road_class))
+            .unwrap_or(true);
+        let excluded = self
+            .excluded_road_classes
+            .as_ref()
+            .map(|rcs| rcs.contains(road_class))
+            .unwrap_or(false);
+        Ok(allowed && !excluded)
     }
 }
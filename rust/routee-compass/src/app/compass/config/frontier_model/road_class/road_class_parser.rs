@@ -12,11 +12,28 @@ pub struct RoadClassParser {
 }
 
 impl RoadClassParser {
+    /// reads the set of road classes a query is allowed to use, if provided
     pub fn read_query(
         &self,
         query: &serde_json::Value,
     ) -> Result<Option<HashSet<u8>>, CompassAppError> {
-        let road_classes = match query.get("road_classes") {
+        self.read_road_classes(query, "road_classes")
+    }
+
+    /// reads the set of road classes a query must avoid, if provided
+    pub fn read_query_exclude(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<Option<HashSet<u8>>, CompassAppError> {
+        self.read_road_classes(query, "road_classes_exclude")
+    }
+
+    fn read_road_classes(
+        &self,
+        query: &serde_json::Value,
+        key: &str,
+    ) -> Result<Option<HashSet<u8>>, CompassAppError> {
+        let road_classes = match query.get(key) {
             None => None,
             Some(value) => {
                 // try parsing as a u8 first
@@ -27,7 +44,7 @@ impl RoadClassParser {
                             let value_string = value.to_string();
                             Err(CompassAppError::InvalidInput(
                                 formatdoc! {r#"
-                                    Could not parse incoming query valid road_classes of {value_string} as an array of integers 
+                                    Could not parse incoming query {key} of {value_string} as an array of integers 
                                     and this FrontierModel does not specify a mapping of string to integer. 
                                     Either pass a valid array of integers or reload the application with a 
                                     mapping from string road class to integer road class.
@@ -57,3 +74,26 @@ impl RoadClassParser {
         Ok(road_classes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_query_exclude_with_mapping() {
+        let parser = RoadClassParser {
+            mapping: HashMap::from([
+                (String::from("motorway"), 1),
+                (String::from("residential"), 5),
+                (String::from("ferry"), 9),
+            ]),
+        };
+        let query = serde_json::json!({ "road_classes_exclude": ["motorway", "ferry"] });
+        let excluded = parser.read_query_exclude(&query).unwrap();
+        assert_eq!(excluded, Some(HashSet::from([1, 9])));
+        assert_eq!(parser.read_query(&query).unwrap(), None);
+
+        let bad_query = serde_json::json!({ "road_classes_exclude": ["footway"] });
+        assert!(parser.read_query_exclude(&bad_query).is_err());
+    }
+}
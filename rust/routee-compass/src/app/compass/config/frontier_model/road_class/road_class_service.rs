@@ -4,14 +4,17 @@ use routee_compass_core::model::{
         frontier_model::FrontierModel, frontier_model_error::FrontierModelError,
         frontier_model_service::FrontierModelService,
     },
+    road_network::edge_id::EdgeId,
     state::state_model::StateModel,
 };
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 #[derive(Clone)]
 pub struct RoadClassFrontierService {
     pub road_class_lookup: Arc<Box<[u8]>>,
     pub road_class_parser: RoadClassParser,
+    pub default_road_classes: Option<HashSet<u8>>,
+    pub default_excluded_road_classes: Option<HashSet<u8>>,
 }
 
 impl FrontierModelService for RoadClassFrontierService {
@@ -21,16 +24,38 @@ impl FrontierModelService for RoadClassFrontierService {
         _state_model: Arc<StateModel>,
     ) -> Result<Arc<dyn FrontierModel>, FrontierModelError> {
         let service: Arc<RoadClassFrontierService> = Arc::new(self.clone());
-        let road_classes = self.road_class_parser.read_query(query).map_err(|e| {
-            FrontierModelError::BuildError(format!(
-                "Unable to parse incoming query road_classes due to: {}",
-                e
-            ))
-        })?;
+        let road_classes = self
+            .road_class_parser
+            .read_query(query)
+            .map_err(|e| {
+                FrontierModelError::BuildError(format!(
+                    "Unable to parse incoming query road_classes due to: {}",
+                    e
+                ))
+            })?
+            .or_else(|| self.default_road_classes.clone());
+        let excluded_road_classes = self
+            .road_class_parser
+            .read_query_exclude(query)
+            .map_err(|e| {
+                FrontierModelError::BuildError(format!(
+                    "Unable to parse incoming query road_classes_exclude due to: {}",
+                    e
+                ))
+            })?
+            .or_else(|| self.default_excluded_road_classes.clone());
         let model = RoadClassFrontierModel {
             service,
             road_classes,
+            excluded_road_classes,
         };
         Ok(Arc::new(model))
     }
+
+    fn edge_road_class(&self, edge_id: EdgeId) -> Result<Option<u8>, FrontierModelError> {
+        self.road_class_lookup
+            .get(edge_id.as_usize())
+            .map(|road_class| Some(*road_class))
+            .ok_or_else(|| FrontierModelError::MissingIndex(format!("{}", edge_id)))
+    }
 }
@@ -0,0 +1,3 @@
+pub mod soc_floor_builder;
+pub mod soc_floor_model;
+pub mod soc_floor_service;
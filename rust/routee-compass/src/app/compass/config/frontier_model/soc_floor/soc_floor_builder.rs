@@ -0,0 +1,35 @@
+use super::soc_floor_service::SocFloorFrontierService;
+use crate::app::compass::config::{
+    compass_configuration_field::CompassConfigurationField,
+    config_json_extension::ConfigJsonExtensions,
+};
+use routee_compass_core::model::frontier::{
+    frontier_model_builder::FrontierModelBuilder, frontier_model_error::FrontierModelError,
+    frontier_model_service::FrontierModelService,
+};
+use std::sync::Arc;
+
+pub struct SocFloorBuilder {}
+
+impl FrontierModelBuilder for SocFloorBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["soc_feature", "min_soc_percent"])
+    }
+
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        let frontier_key = CompassConfigurationField::Frontier.to_string();
+        let min_soc_percent = parameters
+            .get_config_serde_optional::<f64>(&"min_soc_percent", &frontier_key)
+            .map_err(|e| FrontierModelError::BuildError(e.to_string()))?
+            .unwrap_or(SocFloorFrontierService::DEFAULT_MIN_SOC_PERCENT);
+        let soc_feature = parameters
+            .get_config_serde_optional::<String>(&"soc_feature", &frontier_key)
+            .map_err(|e| FrontierModelError::BuildError(e.to_string()))?
+            .unwrap_or_else(|| String::from(SocFloorFrontierService::DEFAULT_SOC_FEATURE));
+        let service = SocFloorFrontierService::new(min_soc_percent, soc_feature)?;
+        Ok(Arc::new(service))
+    }
+}
@@ -0,0 +1,73 @@
+use routee_compass_core::model::{
+    frontier::{frontier_model::FrontierModel, frontier_model_error::FrontierModelError},
+    property::edge::Edge,
+    state::state_model::StateModel,
+    traversal::state::state_variable::StateVar,
+};
+
+pub struct SocFloorFrontierModel {
+    pub soc_feature: String,
+    pub min_soc_percent: Option<f64>,
+}
+
+impl FrontierModel for SocFloorFrontierModel {
+    /// an edge is invalid if traversing it would deplete the battery or leave
+    /// the state of charge below the minimum percent.
+    fn valid_traversal(
+        &self,
+        _edge: &Edge,
+        result_state: &[StateVar],
+        state_model: &StateModel,
+    ) -> Result<bool, FrontierModelError> {
+        match self.min_soc_percent {
+            None => Ok(true),
+            Some(min_soc_percent) => {
+                let soc = state_model
+                    .get_custom_f64(result_state, &self.soc_feature)
+                    .map_err(|e| FrontierModelError::StateError(e.to_string()))?;
+                Ok(soc > 0.0 && soc >= min_soc_percent)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::state::{
+        custom_feature_format::CustomFeatureFormat, state_feature::StateFeature,
+    };
+
+    fn soc_state_model() -> StateModel {
+        StateModel::empty()
+            .extend(vec![(
+                String::from("battery_state"),
+                StateFeature::Custom {
+                    r#type: String::from("soc"),
+                    unit: String::from("percent"),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 100.0.into(),
+                    },
+                },
+            )])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_soc_floor() {
+        let state_model = soc_state_model();
+        let model = SocFloorFrontierModel {
+            soc_feature: String::from("battery_state"),
+            min_soc_percent: Some(20.0),
+        };
+        let edge = Edge::new(0, 0, 1, 1.0);
+        let mut state = state_model.initial_state().unwrap();
+        for (soc, expected) in [(50.0, true), (20.0, true), (19.9, false), (0.0, false)] {
+            state_model
+                .set_custom_f64(&mut state, &String::from("battery_state"), &soc)
+                .unwrap();
+            let valid = model.valid_traversal(&edge, &state, &state_model).unwrap();
+            assert_eq!(valid, expected, "soc {} should be valid: {}", soc, expected);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use super::soc_floor_model::SocFloorFrontierModel;
+use routee_compass_core::model::{
+    frontier::{
+        frontier_model::FrontierModel, frontier_model_error::FrontierModelError,
+        frontier_model_service::FrontierModelService,
+    },
+    state::state_model::StateModel,
+};
+use std::sync::Arc;
+
+/// builds frontier models which reject edges that would drop the battery state
+/// of charge (SOC) below some minimum percent. the minimum can be overridden
+/// by a query with a `min_soc_percent` value.
+#[derive(Clone)]
+pub struct SocFloorFrontierService {
+    pub min_soc_percent: f64,
+    pub soc_feature: String,
+}
+
+impl SocFloorFrontierService {
+    pub const DEFAULT_MIN_SOC_PERCENT: f64 = 0.0;
+    pub const DEFAULT_SOC_FEATURE: &'static str = "battery_state";
+    const QUERY_KEY: &'static str = "min_soc_percent";
+
+    pub fn new(
+        min_soc_percent: f64,
+        soc_feature: String,
+    ) -> Result<SocFloorFrontierService, FrontierModelError> {
+        validate_soc_percent(min_soc_percent)?;
+        Ok(SocFloorFrontierService {
+            min_soc_percent,
+            soc_feature,
+        })
+    }
+}
+
+impl FrontierModelService for SocFloorFrontierService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn FrontierModel>, FrontierModelError> {
+        // vehicles without a battery are not restricted by this model
+        if !state_model.contains_key(&self.soc_feature) {
+            let model = SocFloorFrontierModel {
+                soc_feature: self.soc_feature.clone(),
+                min_soc_percent: None,
+            };
+            return Ok(Arc::new(model));
+        }
+        let min_soc_percent = match query.get(SocFloorFrontierService::QUERY_KEY) {
+            None => self.min_soc_percent,
+            Some(value) => value.as_f64().ok_or_else(|| {
+                FrontierModelError::BuildError(format!(
+                    "expected '{}' value to be numeric, found {}",
+                    SocFloorFrontierService::QUERY_KEY,
+                    value
+                ))
+            })?,
+        };
+        validate_soc_percent(min_soc_percent)?;
+        let model = SocFloorFrontierModel {
+            soc_feature: self.soc_feature.clone(),
+            min_soc_percent: Some(min_soc_percent),
+        };
+        Ok(Arc::new(model))
+    }
+}
+
+fn validate_soc_percent(soc_percent: f64) -> Result<(), FrontierModelError> {
+    if (0.0..=100.0).contains(&soc_percent) {
+        Ok(())
+    } else {
+        Err(FrontierModelError::BuildError(format!(
+            "minimum soc percent must be in range [0, 100], found {}",
+            soc_percent
+        )))
+    }
+}
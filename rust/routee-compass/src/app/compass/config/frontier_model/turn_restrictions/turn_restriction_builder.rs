@@ -16,6 +16,14 @@ use super::turn_restriction_service::{RestrictedEdgePair, TurnRestrictionFrontie
 pub struct TurnRestrictionBuilder {}
 
 impl FrontierModelBuilder for TurnRestrictionBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["turn_restriction_input_file"])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["turn_restriction_input_file"]
+    }
+
     fn build(
         &self,
         parameters: &serde_json::Value,
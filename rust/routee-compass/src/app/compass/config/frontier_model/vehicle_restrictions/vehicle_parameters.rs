@@ -4,14 +4,17 @@ use routee_compass_core::model::{
     frontier::frontier_model_error::FrontierModelError,
     unit::{Distance, DistanceUnit, Weight, WeightUnit},
 };
+use serde::de::DeserializeOwned;
 
+/// dimensions of the vehicle provided on the query. any dimension that is not provided
+/// is not checked against the restrictions on that dimension.
 pub struct VehicleParameters {
-    pub height: (Distance, DistanceUnit),
-    pub width: (Distance, DistanceUnit),
-    pub total_length: (Distance, DistanceUnit),
-    pub trailer_length: (Distance, DistanceUnit),
-    pub total_weight: (Weight, WeightUnit),
-    pub number_of_axles: u8,
+    pub height: Option<(Distance, DistanceUnit)>,
+    pub width: Option<(Distance, DistanceUnit)>,
+    pub total_length: Option<(Distance, DistanceUnit)>,
+    pub trailer_length: Option<(Distance, DistanceUnit)>,
+    pub total_weight: Option<(Weight, WeightUnit)>,
+    pub number_of_axles: Option<u8>,
 }
 
 impl VehicleParameters {
@@ -22,64 +25,19 @@ impl VehicleParameters {
             )
         })?;
 
-        let height = vehicle_params
-            .get_config_serde::<(Distance, DistanceUnit)>(&"height", &"vehicle_parameters")
-            .map_err(|e| {
-                FrontierModelError::BuildError(format!(
-                    "Unable to interpret `height` parameter: {}",
-                    e
-                ))
-            })?;
-
-        let width = vehicle_params
-            .get_config_serde::<(Distance, DistanceUnit)>(&"width", &"vehicle_parameters")
-            .map_err(|e| {
-                FrontierModelError::BuildError(format!(
-                    "Unable to interpret `width` parameter: {}",
-                    e
-                ))
-            })?;
-
-        let total_length = vehicle_params
-            .get_config_serde::<(Distance, DistanceUnit)>(&"total_length", &"vehicle_parameters")
-            .map_err(|e| {
-                FrontierModelError::BuildError(format!(
-                    "Unable to interpret `total_length` parameter: {}",
-                    e
-                ))
-            })?;
-
-        let trailer_length = vehicle_params
-            .get_config_serde::<(Distance, DistanceUnit)>(&"trailer_length", &"vehicle_parameters")
-            .map_err(|e| {
-                FrontierModelError::BuildError(format!(
-                    "Unable to interpret `trailer_length` parameter: {}",
-                    e
-                ))
-            })?;
-
-        let total_weight = vehicle_params
-            .get_config_serde::<(Weight, WeightUnit)>(&"total_weight", &"vehicle_parameters")
-            .map_err(|e| {
-                FrontierModelError::BuildError(format!(
-                    "Unable to interpret `total_weight` parameter: {}",
-                    e
-                ))
-            })?;
-
-        let number_of_axles = vehicle_params
-            .get("number_of_axles")
-            .ok_or_else(|| {
-                FrontierModelError::BuildError(
-                    "Missing field `number_of_axles` in query".to_string(),
-                )
-            })?
-            .as_u64()
-            .ok_or_else(|| {
-                FrontierModelError::BuildError(
-                    "Unable to interpret `number_of_axles` parameter as an integer".to_string(),
-                )
-            })? as u8;
+        let height = get_parameter::<(Distance, DistanceUnit)>(vehicle_params, "height")?;
+        let width = get_parameter::<(Distance, DistanceUnit)>(vehicle_params, "width")?;
+        let total_length =
+            get_parameter::<(Distance, DistanceUnit)>(vehicle_params, "total_length")?;
+        let trailer_length =
+            get_parameter::<(Distance, DistanceUnit)>(vehicle_params, "trailer_length")?;
+        let total_weight = get_parameter::<(Weight, WeightUnit)>(vehicle_params, "total_weight")?;
+        let number_of_axles = get_parameter::<u8>(vehicle_params, "number_of_axles")?;
+        if number_of_axles == Some(0) {
+            return Err(FrontierModelError::BuildError(
+                "`number_of_axles` parameter must be at least 1".to_string(),
+            ));
+        }
 
         let params = VehicleParameters {
             height,
@@ -92,3 +50,17 @@ impl VehicleParameters {
         Ok(params)
     }
 }
+
+fn get_parameter<T: DeserializeOwned>(
+    vehicle_params: &serde_json::Value,
+    key: &str,
+) -> Result<Option<T>, FrontierModelError> {
+    vehicle_params
+        .get_config_serde_optional::<T>(&key, &"vehicle_parameters")
+        .map_err(|e| {
+            FrontierModelError::BuildError(format!(
+                "Unable to interpret `{}` parameter: {}",
+                key, e
+            ))
+        })
+}
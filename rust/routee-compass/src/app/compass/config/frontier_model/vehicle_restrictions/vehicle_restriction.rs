@@ -18,51 +18,63 @@ impl VehicleRestriction {
     /// Returns true if the truck parameters are valid for the restriction.
     /// For example, if the restriction is MaximumTotalWeight(1000.0, "kg"),
     /// and the truck parameters are VehicleParameters { vehicle_total_weight: (500.0, "kg"), ... },
-    /// then the function will return true.
+    /// then the function will return true. Restrictions on a dimension that was not
+    /// provided in the truck parameters are always valid.
     pub fn valid(&self, vehicle_parameters: &vehicle_parameters::VehicleParameters) -> bool {
         match self {
             VehicleRestriction::MaximumTotalWeight((restriction_weight, restriction_unit)) => {
-                let (vehicle_weight, vehicle_unit) = vehicle_parameters.total_weight;
-                let weight_in_restriction_unit =
-                    vehicle_unit.convert(&vehicle_weight, restriction_unit);
-                weight_in_restriction_unit <= *restriction_weight
+                match vehicle_parameters.total_weight {
+                    None => true,
+                    Some((vehicle_weight, vehicle_unit)) => {
+                        vehicle_unit.convert(&vehicle_weight, restriction_unit)
+                            <= *restriction_weight
+                    }
+                }
             }
             VehicleRestriction::MaximumWeightPerAxle((restriction_weight, restriction_unit)) => {
-                let (vehicle_weight, vehicle_unit) = vehicle_parameters.total_weight;
-                let weight_in_restriction_unit =
-                    vehicle_unit.convert(&vehicle_weight, restriction_unit);
-                let weight_per_axle =
-                    weight_in_restriction_unit / vehicle_parameters.number_of_axles as f64;
-                weight_per_axle <= *restriction_weight
+                match (
+                    vehicle_parameters.total_weight,
+                    vehicle_parameters.number_of_axles,
+                ) {
+                    (Some((vehicle_weight, vehicle_unit)), Some(number_of_axles)) => {
+                        let weight_in_restriction_unit =
+                            vehicle_unit.convert(&vehicle_weight, restriction_unit);
+                        let weight_per_axle = weight_in_restriction_unit / number_of_axles as f64;
+                        weight_per_axle <= *restriction_weight
+                    }
+                    _ => true,
+                }
             }
-            VehicleRestriction::MaximumLength((restriction_length, restriction_unit)) => {
-                let (vehicle_length, vehicle_unit) = vehicle_parameters.total_length;
-                let length_in_restriction_unit =
-                    vehicle_unit.convert(&vehicle_length, restriction_unit);
-                length_in_restriction_unit <= *restriction_length
+            VehicleRestriction::MaximumLength(restriction) => {
+                valid_distance(vehicle_parameters.total_length, restriction)
             }
-            VehicleRestriction::MaximumWidth((restriction_width, restriction_unit)) => {
-                let (vehicle_width, vehicle_unit) = vehicle_parameters.width;
-                let width_in_restriction_unit =
-                    vehicle_unit.convert(&vehicle_width, restriction_unit);
-                width_in_restriction_unit <= *restriction_width
+            VehicleRestriction::MaximumWidth(restriction) => {
+                valid_distance(vehicle_parameters.width, restriction)
             }
-            VehicleRestriction::MaximumHeight((restriction_height, restriction_unit)) => {
-                let (vehicle_height, vehicle_unit) = vehicle_parameters.height;
-                let height_in_restriction_unit =
-                    vehicle_unit.convert(&vehicle_height, restriction_unit);
-                height_in_restriction_unit <= *restriction_height
+            VehicleRestriction::MaximumHeight(restriction) => {
+                valid_distance(vehicle_parameters.height, restriction)
             }
-            VehicleRestriction::MaximumTrailerLength((restriction_length, restriction_unit)) => {
-                let (vehicle_trailer_length, vehicle_unit) = vehicle_parameters.trailer_length;
-                let trailer_length_in_restriction_unit =
-                    vehicle_unit.convert(&vehicle_trailer_length, restriction_unit);
-                trailer_length_in_restriction_unit <= *restriction_length
+            VehicleRestriction::MaximumTrailerLength(restriction) => {
+                valid_distance(vehicle_parameters.trailer_length, restriction)
             }
         }
     }
 }
 
+/// true if the vehicle dimension is unknown or no greater than the restriction
+fn valid_distance(
+    vehicle_dimension: Option<(Distance, DistanceUnit)>,
+    restriction: &(Distance, DistanceUnit),
+) -> bool {
+    let (restriction_distance, restriction_unit) = restriction;
+    match vehicle_dimension {
+        None => true,
+        Some((vehicle_distance, vehicle_unit)) => {
+            vehicle_unit.convert(&vehicle_distance, restriction_unit) <= *restriction_distance
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -76,4 +88,31 @@ mod test {
             VehicleRestriction::MaximumTotalWeight((Weight::new(1000.0), WeightUnit::Kg))
         );
     }
+
+    #[test]
+    fn test_valid_with_partial_parameters() {
+        let query = serde_json::json!({
+            "vehicle_parameters": {
+                "height": [4.0, "meters"],
+                "total_weight": [20.0, "tons"],
+                "number_of_axles": 4
+            }
+        });
+        let params = vehicle_parameters::VehicleParameters::from_query(&query).unwrap();
+
+        let low_bridge =
+            VehicleRestriction::MaximumHeight((Distance::new(12.0), DistanceUnit::Feet));
+        let high_bridge =
+            VehicleRestriction::MaximumHeight((Distance::new(14.0), DistanceUnit::Feet));
+        assert!(!low_bridge.valid(&params));
+        assert!(high_bridge.valid(&params));
+
+        let axle_limit =
+            VehicleRestriction::MaximumWeightPerAxle((Weight::new(4.0), WeightUnit::Tons));
+        assert!(!axle_limit.valid(&params));
+
+        // width was not provided so it is not restricted
+        let narrow = VehicleRestriction::MaximumWidth((Distance::new(1.0), DistanceUnit::Meters));
+        assert!(narrow.valid(&params));
+    }
 }
@@ -22,6 +22,14 @@ use super::{
 pub struct VehicleRestrictionBuilder {}
 
 impl FrontierModelBuilder for VehicleRestrictionBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["vehicle_restriction_input_file"])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["vehicle_restriction_input_file"]
+    }
+
     fn build(
         &self,
         parameters: &serde_json::Value,
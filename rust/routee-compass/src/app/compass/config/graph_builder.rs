@@ -1,4 +1,7 @@
-use routee_compass_core::model::road_network::graph::Graph;
+use routee_compass_core::model::{
+    property::edge_attribute_store::{EdgeAttributeColumnConfig, EdgeAttributeStore},
+    road_network::{edge_geometry_store::EdgeGeometryStore, graph::Graph, graph_error::GraphError},
+};
 
 use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
 
@@ -10,6 +13,19 @@ use super::{
 pub struct DefaultGraphBuilder {}
 
 impl DefaultGraphBuilder {
+    /// the keys read from the `[graph]` section
+    pub const CONFIG_KEYS: [&'static str; 9] = [
+        "edge_list_input_file",
+        "vertex_list_input_file",
+        "compiled_graph_input_file",
+        "geometry_input_file",
+        "edge_attribute_input_file",
+        "edge_attribute_columns",
+        "n_edges",
+        "n_vertices",
+        "verbose",
+    ];
+
     /// tries to build a Graph from a JSON object.
     ///
     /// for both edge and vertex lists, we assume all ids can be used as indices
@@ -18,6 +34,15 @@ impl DefaultGraphBuilder {
     /// then we can build a Vec *once* and insert rows as we decode them without
     /// a sort.
     ///
+    /// if a `compiled_graph_input_file` is configured, the graph is instead
+    /// read from that file, skipping the CSV files entirely.
+    ///
+    /// if a `geometry_input_file` is configured, the LINESTRING of each edge
+    /// is loaded into the graph, which places points along partial edges.
+    ///
+    /// if an `edge_attribute_input_file` is configured, its `edge_attribute_columns`
+    /// are loaded into the graph, so that every model reads one copy of them.
+    ///
     /// # Arguments
     ///
     /// * `params` - configuration JSON object for building a `Graph` instance
@@ -27,6 +52,12 @@ impl DefaultGraphBuilder {
     /// A graph instance, or an error if an IO error occurred.
     pub fn build(params: &serde_json::Value) -> Result<Graph, CompassConfigurationError> {
         let graph_key = CompassConfigurationField::Graph.to_string();
+        if let Some(compiled_graph_file) =
+            params.get_config_path_optional(&"compiled_graph_input_file", &graph_key)?
+        {
+            let graph = Graph::from_compiled_file(&compiled_graph_file)?;
+            return with_geometries(graph, params).and_then(|g| with_edge_attributes(g, params));
+        }
         let edge_list_csv = params.get_config_path(&"edge_list_input_file", &graph_key)?;
         let vertex_list_csv = params.get_config_path(&"vertex_list_input_file", &graph_key)?;
         let n_edges = params.get_config_serde_optional(&"n_edges", &graph_key)?;
@@ -41,6 +72,39 @@ impl DefaultGraphBuilder {
             verbose,
         )?;
 
-        Ok(graph)
+        with_geometries(graph, params).and_then(|g| with_edge_attributes(g, params))
+    }
+}
+
+/// attaches the edge geometries of the `geometry_input_file`, if one is configured
+fn with_geometries(
+    graph: Graph,
+    params: &serde_json::Value,
+) -> Result<Graph, CompassConfigurationError> {
+    let graph_key = CompassConfigurationField::Graph.to_string();
+    match params.get_config_path_optional(&"geometry_input_file", &graph_key)? {
+        None => Ok(graph),
+        Some(geometry_file) => {
+            let geometries = EdgeGeometryStore::from_file(&geometry_file, graph.edges.len())?;
+            Ok(graph.with_geometries(geometries)?)
+        }
+    }
+}
+
+/// attaches the edge attributes of the `edge_attribute_input_file`, if one is configured
+fn with_edge_attributes(
+    graph: Graph,
+    params: &serde_json::Value,
+) -> Result<Graph, CompassConfigurationError> {
+    let graph_key = CompassConfigurationField::Graph.to_string();
+    match params.get_config_path_optional(&"edge_attribute_input_file", &graph_key)? {
+        None => Ok(graph),
+        Some(input_file) => {
+            let columns: Vec<EdgeAttributeColumnConfig> =
+                params.get_config_serde(&"edge_attribute_columns", &graph_key)?;
+            let store = EdgeAttributeStore::from_file(&input_file, &columns, graph.n_edges())
+                .map_err(GraphError::from)?;
+            Ok(graph.with_edge_attributes(store))
+        }
     }
 }
@@ -4,6 +4,7 @@ pub mod compass_app_builder;
 pub mod compass_configuration_error;
 pub mod compass_configuration_field;
 pub mod config_json_extension;
+pub mod config_schema;
 pub mod cost_model;
 pub mod frontier_model;
 pub mod graph_builder;
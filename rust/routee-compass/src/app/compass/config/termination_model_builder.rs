@@ -3,6 +3,7 @@ use crate::app::compass::config::compass_configuration_field::CompassConfigurati
 use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
 use log;
 use routee_compass_core::model::termination::termination_model::TerminationModel;
+use routee_compass_core::model::unit::{Distance, DistanceUnit};
 use routee_compass_core::util::conversion::duration_extension::DurationExtension;
 
 pub struct TerminationModelBuilder {}
@@ -44,27 +45,54 @@ impl TerminationModelBuilder {
                     limit: solution_size,
                 })
             }
-            "combined" => {
-                let models_val = config.get_config_array(&"models", &local_scope)?;
-
-                let models = models_val
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, c)| {
-                        let next_scope = format!("{}.combined[{}]", local_scope.clone(), idx);
-                        TerminationModelBuilder::build(c, Some(next_scope))
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
+            "distance" => {
+                let limit = config.get_config_f64(&"limit", &local_scope)?;
+                let distance_unit =
+                    config.get_config_serde::<DistanceUnit>(&"distance_unit", &local_scope)?;
+                let feature = config
+                    .get_config_string_optional(&"feature")?
+                    .unwrap_or_else(|| String::from("distance"));
+                Ok(T::DistanceLimit {
+                    limit: Distance::new(limit),
+                    distance_unit,
+                    feature,
+                })
+            }
+            "combined" | "any" => {
+                let models = TerminationModelBuilder::build_models(config, &local_scope, "any")?;
                 Ok(T::Combined { models })
             }
+            "all" => {
+                let models = TerminationModelBuilder::build_models(config, &local_scope, "all")?;
+                Ok(T::All { models })
+            }
             _ => Err(CompassConfigurationError::UnknownModelNameForComponent(
                 term_type,
                 local_scope,
-                String::from("query_runtime, iterations, solution_size, combined"),
+                String::from(
+                    "query_runtime, iterations, solution_size, distance, combined, any, all",
+                ),
             )),
         }?;
 
-        log::info!("app termination model: {:?}", result);
+        log::debug!("termination model: {:?}", result);
         Ok(result)
     }
+
+    /// builds each of the nested `models` of a combined termination model
+    fn build_models(
+        config: &serde_json::Value,
+        scope: &str,
+        operator: &str,
+    ) -> Result<Vec<TerminationModel>, CompassConfigurationError> {
+        let models_val = config.get_config_array(&"models", &scope)?;
+        models_val
+            .iter()
+            .enumerate()
+            .map(|(idx, c)| {
+                let next_scope = format!("{}.{}[{}]", scope, operator, idx);
+                TerminationModelBuilder::build(c, Some(next_scope))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
 }
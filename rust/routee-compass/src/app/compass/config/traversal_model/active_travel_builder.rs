@@ -0,0 +1,103 @@
+use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
+use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
+use routee_compass_core::model::traversal::default::active_travel_engine::{
+    ActiveSpeedModel, ActiveTravelEngine, ActiveTravelMode,
+};
+use routee_compass_core::model::traversal::default::active_travel_service::ActiveTravelService;
+use routee_compass_core::model::traversal::default::grade_table::GradeTable;
+use routee_compass_core::model::traversal::traversal_model_builder::TraversalModelBuilder;
+use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
+use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
+use routee_compass_core::model::unit::{
+    DistanceUnit, GradeUnit, Speed, SpeedUnit, TimeUnit, BASE_DISTANCE_UNIT, BASE_TIME_UNIT,
+};
+use std::sync::Arc;
+
+/// builds the walk and bike traversal models, which differ only in their defaults
+pub struct ActiveTravelBuilder {
+    pub mode: ActiveTravelMode,
+}
+
+impl TraversalModelBuilder for ActiveTravelBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "speed",
+            "speed_unit",
+            "speed_model",
+            "climb_effort_factor",
+            "grade_table_input_file",
+            "grade_table_grade_unit",
+            "grade_table_skip_unit_check",
+            "distance_unit",
+            "time_unit",
+        ])
+    }
+
+    fn build(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
+        let traversal_key = CompassConfigurationField::Traversal.to_string();
+        let speed = params
+            .get_config_serde_optional::<Speed>(&"speed", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let speed_unit = params
+            .get_config_serde_optional::<SpeedUnit>(&"speed_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let speed = match (speed, speed_unit) {
+            (Some(speed), Some(unit)) => Some((speed, unit)),
+            (None, None) => None,
+            _ => {
+                return Err(TraversalModelError::BuildError(String::from(
+                    "speed and speed_unit must be provided together",
+                )))
+            }
+        };
+        let speed_model = params
+            .get_config_serde_optional::<ActiveSpeedModel>(&"speed_model", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or_default();
+        let grade_table_path = params
+            .get_config_path_optional(&"grade_table_input_file", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let grade_table = match grade_table_path {
+            None => None,
+            Some(path) => {
+                let grade_unit = params
+                    .get_config_serde::<GradeUnit>(&"grade_table_grade_unit", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                let skip_unit_check = params
+                    .get_config_serde_optional::<bool>(
+                        &"grade_table_skip_unit_check",
+                        &traversal_key,
+                    )
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+                    .unwrap_or_default();
+                Some(GradeTable::new(&path, grade_unit, skip_unit_check)?)
+            }
+        };
+        let climb_factor = params
+            .get_config_serde_optional::<f64>(&"climb_effort_factor", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let distance_unit = params
+            .get_config_serde_optional::<DistanceUnit>(&"distance_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or(BASE_DISTANCE_UNIT);
+        let time_unit = params
+            .get_config_serde_optional::<TimeUnit>(&"time_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or(BASE_TIME_UNIT);
+
+        let e = ActiveTravelEngine::new(
+            self.mode,
+            speed,
+            speed_model,
+            grade_table,
+            climb_factor,
+            distance_unit,
+            time_unit,
+        )?;
+        let service = Arc::new(ActiveTravelService { e: Arc::new(e) });
+        Ok(service)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
+use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
+use routee_compass_core::model::property::edge_attribute_store::{
+    EdgeAttributeColumnConfig, EdgeAttributeStore,
+};
+use routee_compass_core::model::road_network::graph::Graph;
+use routee_compass_core::model::state::state_model::StateModel;
+use routee_compass_core::model::traversal::default::custom_state_engine::{
+    CustomStateEngine, CustomStateFeatureConfig,
+};
+use routee_compass_core::model::traversal::default::custom_state_service::CustomStateService;
+use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
+use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
+use std::sync::Arc;
+
+/// builds the `[custom_state]` section, which adds state features computed from
+/// expressions to any traversal model.
+///
+/// # Configuration
+///
+/// * `features` - the features to add, see [`CustomStateFeatureConfig`]
+/// * `edge_attribute_input_file` (optional) - file of per-edge attributes read by the
+///   expressions. without one, the expressions read the edge attributes of the graph.
+/// * `columns` (optional) - the columns to load from the attribute file
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [custom_state]
+/// edge_attribute_input_file = "edges-attributes.csv.gz"
+/// columns = [
+///   { name = "surface", type = "categorical", default = "paved" },
+///   { name = "highway", type = "categorical", default = "unclassified" },
+/// ]
+/// features = [
+///   { name = "unpaved_distance", unit = "miles", expression = "distance * (surface != 'paved')" },
+///   { name = "motorway_time", unit = "seconds", expression = "time * (highway == 'motorway')" },
+/// ]
+/// ```
+pub struct CustomStateBuilder {}
+
+impl CustomStateBuilder {
+    pub const CONFIG_KEYS: [&'static str; 3] = ["features", "edge_attribute_input_file", "columns"];
+
+    /// wraps a traversal model service so that its models also compute the custom
+    /// features, which may read the features of the configured state model
+    pub fn build(
+        &self,
+        inner: Arc<dyn TraversalModelService>,
+        params: &serde_json::Value,
+        graph: &Graph,
+        state_model: &StateModel,
+    ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
+        let custom_state_key = CompassConfigurationField::CustomState.to_string();
+        let build_error =
+            |e: &dyn std::fmt::Display| TraversalModelError::BuildError(e.to_string());
+        let features: Vec<CustomStateFeatureConfig> = params
+            .get_config_serde(&"features", &custom_state_key)
+            .map_err(|e| build_error(&e))?;
+        let input_file = params
+            .get_config_path_optional(&"edge_attribute_input_file", &custom_state_key)
+            .map_err(|e| build_error(&e))?;
+        let columns: Vec<EdgeAttributeColumnConfig> = params
+            .get_config_serde_optional(&"columns", &custom_state_key)
+            .map_err(|e| build_error(&e))?
+            .unwrap_or_default();
+        let edge_attributes = match input_file {
+            Some(input_file) => Some(Arc::new(
+                EdgeAttributeStore::from_file(&input_file, &columns, graph.n_edges())
+                    .map_err(|e| build_error(&e))?,
+            )),
+            None if !columns.is_empty() => {
+                return Err(TraversalModelError::BuildError(String::from(
+                    "custom_state.columns requires an edge_attribute_input_file",
+                )))
+            }
+            None => graph.edge_attributes().ok().cloned(),
+        };
+        let configured_features = state_model.iter().map(|(n, _)| n.clone()).collect();
+        let engine = CustomStateEngine::new(&features, edge_attributes, configured_features)?;
+        log::info!(
+            "custom state adds features {}",
+            features
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let service = CustomStateService {
+            inner,
+            engine: Arc::new(engine),
+        };
+        Ok(Arc::new(service))
+    }
+}
@@ -13,6 +13,10 @@ use std::sync::Arc;
 pub struct DistanceTraversalBuilder {}
 
 impl TraversalModelBuilder for DistanceTraversalBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec!["distance_unit"])
+    }
+
     fn build(
         &self,
         parameters: &serde_json::Value,
@@ -8,6 +8,7 @@ use routee_compass_core::model::traversal::traversal_model_builder::TraversalMod
 use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
 use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
 use routee_compass_core::model::unit::{DistanceUnit, GradeUnit, SpeedUnit, TimeUnit};
+use routee_compass_powertrain::routee::emissions_model::{EmissionsModel, EmissionsRate};
 use routee_compass_powertrain::routee::energy_model_service::EnergyModelService;
 
 use super::energy_model_vehicle_builders::VehicleBuilder;
@@ -23,6 +24,25 @@ impl EnergyModelBuilder {
 }
 
 impl TraversalModelBuilder for EnergyModelBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "time_model",
+            "vehicles",
+            "default_vehicle",
+            "emissions",
+            "grade_table_input_file",
+            "grade_table_grade_unit",
+            "grade_table_skip_unit_check",
+            "speed_unit",
+            "distance_unit",
+            "time_unit",
+        ])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["time_model", "vehicles"]
+    }
+
     fn build(
         &self,
         params: &serde_json::Value,
@@ -51,16 +71,52 @@ impl TraversalModelBuilder for EnergyModelBuilder {
             .get_config_serde::<SpeedUnit>(&"speed_unit", &"time_model")
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
-        let grade_table_path_option = params
+        // use the grade table of the energy model, or else the one of the time model
+        let energy_grade_table_path = params
             .get_config_path_optional(&"grade_table_input_file", &parent_key)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
-        let grade_table_grade_unit = params
-            .get_config_serde::<GradeUnit>(&"grade_table_grade_unit", &parent_key)
-            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let (grade_table_path_option, grade_params) = match energy_grade_table_path {
+            Some(path) => (Some(path), params),
+            None => {
+                let time_model_grade_table_path = time_model_params
+                    .get_config_path_optional(&"grade_table_input_file", &"time_model")
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                match time_model_grade_table_path {
+                    Some(path) => (Some(path), time_model_params),
+                    None => (None, params),
+                }
+            }
+        };
+        // the unit of a grade table must be declared, since a misread unit scales every
+        // grade. without a grade table, grades are zero and the unit has no effect.
+        let grade_table_grade_unit = match &grade_table_path_option {
+            Some(_) => grade_params
+                .get_config_serde::<GradeUnit>(&"grade_table_grade_unit", &parent_key)
+                .map_err(|e| TraversalModelError::BuildError(e.to_string()))?,
+            None => GradeUnit::Decimal,
+        };
+        let skip_grade_unit_check = grade_params
+            .get_config_serde_optional::<bool>(&"grade_table_skip_unit_check", &parent_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or_default();
 
-        let vehicle_configs = params
-            .get_config_array(&"vehicles", &parent_key)
-            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        // vehicles are either an array of vehicle configurations or a map from
+        // vehicle name to configuration
+        let vehicle_configs = match params.get("vehicles") {
+            Some(serde_json::Value::Object(vehicle_map)) => vehicle_map
+                .iter()
+                .map(|(name, vehicle_config)| {
+                    let mut vehicle_config = vehicle_config.clone();
+                    if let Some(obj) = vehicle_config.as_object_mut() {
+                        obj.insert(String::from("name"), serde_json::json!(name));
+                    }
+                    vehicle_config
+                })
+                .collect::<Vec<_>>(),
+            _ => params
+                .get_config_array(&"vehicles", &parent_key)
+                .map_err(|e| TraversalModelError::BuildError(e.to_string()))?,
+        };
 
         // read all vehicle configurations
         let mut vehicle_library = HashMap::new();
@@ -74,8 +130,20 @@ impl TraversalModelBuilder for EnergyModelBuilder {
             let vehicle = vehicle_builder
                 .build(&vehicle_config)
                 .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
-            vehicle_library.insert(vehicle.name(), vehicle);
+            let vehicle_name = vehicle.name();
+            if vehicle_library
+                .insert(vehicle_name.clone(), vehicle)
+                .is_some()
+            {
+                return Err(TraversalModelError::BuildError(format!(
+                    "{} has more than one vehicle named '{}'",
+                    parent_key, vehicle_name
+                )));
+            }
         }
+        let default_vehicle = params
+            .get_config_serde_optional::<String>(&"default_vehicle", &parent_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
         let time_unit_option = params
             .get_config_serde_optional::<TimeUnit>(&"time_unit", &parent_key)
@@ -84,15 +152,24 @@ impl TraversalModelBuilder for EnergyModelBuilder {
             .get_config_serde_optional::<DistanceUnit>(&"distance_unit", &parent_key)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
+        let emissions = params
+            .get_config_serde_optional::<HashMap<String, EmissionsRate>>(&"emissions", &parent_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .map(EmissionsModel::new)
+            .transpose()?;
+
         let service = EnergyModelService::new(
             time_model_service,
             time_model_speed_unit,
             &grade_table_path_option,
             grade_table_grade_unit,
+            skip_grade_unit_check,
             time_unit_option,
             distance_unit_option,
             vehicle_library,
-        )?;
+            default_vehicle,
+        )?
+        .with_emissions(emissions);
 
         Ok(Arc::new(service))
     }
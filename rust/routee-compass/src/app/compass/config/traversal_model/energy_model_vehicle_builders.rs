@@ -4,7 +4,7 @@ use crate::app::compass::config::{
     config_json_extension::ConfigJsonExtensions,
 };
 use routee_compass_core::model::unit::{
-    Energy, EnergyRate, EnergyRateUnit, EnergyUnit, GradeUnit, SpeedUnit,
+    Energy, EnergyRate, EnergyRateUnit, EnergyUnit, GradeUnit, SpeedUnit, Weight, WeightUnit,
 };
 use routee_compass_core::util::cache_policy::float_cache_policy::{
     FloatCachePolicy, FloatCachePolicyConfig,
@@ -12,7 +12,9 @@ use routee_compass_core::util::cache_policy::float_cache_policy::{
 use routee_compass_powertrain::routee::{
     prediction::{load_prediction_model, model_type::ModelType, PredictionModelRecord},
     vehicle::{
-        default::{bev::BEV, ice::ICE, phev::PHEV},
+        ambient_conditions::AmbientConditions,
+        default::{bev::BEV, ice::ICE, phev::PHEV, phev_policy::PhevPolicy},
+        weight_adjustment::WeightAdjustment,
         VehicleType,
     },
 };
@@ -56,7 +58,9 @@ fn build_conventional(
 
     let model_record = get_model_record_from_params(parameters, &name)?;
 
-    let vehicle = ICE::new(name, model_record)?;
+    let weight_adjustment = get_weight_adjustment_from_params(parameters, &vehicle_key)?;
+
+    let vehicle = ICE::new(name, model_record)?.with_weight_adjustment(weight_adjustment);
 
     Ok(Arc::new(vehicle))
 }
@@ -71,7 +75,19 @@ fn build_battery_electric(
     let battery_capacity = parameters.get_config_serde::<Energy>(&"battery_capacity", &"bev")?;
     let battery_energy_unit =
         parameters.get_config_serde::<EnergyUnit>(&"battery_capacity_unit", &"bev")?;
-    let starting_battery_energy = battery_capacity;
+    let initial_soc = parameters
+        .get_config_serde_optional::<f64>(&"initial_soc", &"bev")?
+        .unwrap_or(100.0);
+    if !(0.0..=100.0).contains(&initial_soc) {
+        return Err(CompassConfigurationError::UserConfigurationError(format!(
+            "bev initial_soc must be a percent in range [0, 100], found {}",
+            initial_soc
+        )));
+    }
+    let starting_battery_energy = battery_capacity * (initial_soc / 100.0);
+    let recuperation_efficiency = parameters
+        .get_config_serde_optional::<f64>(&"recuperation_efficiency", &"bev")?
+        .unwrap_or(1.0);
 
     let vehicle = BEV::new(
         name,
@@ -79,7 +95,10 @@ fn build_battery_electric(
         battery_capacity,
         starting_battery_energy,
         battery_energy_unit,
-    );
+        recuperation_efficiency,
+    )?
+    .with_weight_adjustment(get_weight_adjustment_from_params(parameters, &"bev")?)
+    .with_ambient_conditions(get_ambient_conditions_from_params(parameters, &"bev")?);
 
     Ok(Arc::new(vehicle))
 }
@@ -110,7 +129,22 @@ fn build_plugin_hybrid(
 
     let custom_liquid_fuel_to_kwh =
         parameters.get_config_serde_optional::<f64>(&"custom_liquid_fuel_to_kwh", &"phev")?;
-    let starting_battery_energy = battery_capacity;
+    let initial_soc = parameters
+        .get_config_serde_optional::<f64>(&"initial_soc", &"phev")?
+        .unwrap_or(100.0);
+    if !(0.0..=100.0).contains(&initial_soc) {
+        return Err(CompassConfigurationError::UserConfigurationError(format!(
+            "phev initial_soc must be a percent in range [0, 100], found {}",
+            initial_soc
+        )));
+    }
+    let starting_battery_energy = battery_capacity * (initial_soc / 100.0);
+    let policy = parameters
+        .get_config_serde_optional::<PhevPolicy>(&"policy", &"phev")?
+        .unwrap_or_default();
+    let charge_sustaining_soc_percent = parameters
+        .get_config_serde_optional::<f64>(&"charge_sustaining_soc_percent", &"phev")?
+        .unwrap_or(0.0);
     let phev = PHEV::new(
         name,
         charge_sustain_record,
@@ -119,7 +153,11 @@ fn build_plugin_hybrid(
         starting_battery_energy,
         battery_energy_unit,
         custom_liquid_fuel_to_kwh,
-    )?;
+        policy,
+        charge_sustaining_soc_percent,
+    )?
+    .with_weight_adjustment(get_weight_adjustment_from_params(parameters, &"phev")?)
+    .with_ambient_conditions(get_ambient_conditions_from_params(parameters, &"phev")?);
     Ok(Arc::new(phev))
 }
 
@@ -162,3 +200,66 @@ fn get_model_record_from_params(
 
     Ok(model_record)
 }
+
+/// reads the optional weight parameters of a vehicle. providing a `reference_weight`,
+/// the weight of the vehicle represented by the prediction model, enables adjusting
+/// energy for the `vehicle_weight`, which queries may override with `vehicle_weight_kg`.
+/// a `vehicle_weight` without a `reference_weight` is an error.
+fn get_weight_adjustment_from_params(
+    parameters: &serde_json::Value,
+    parent_key: &dyn AsRef<str>,
+) -> Result<Option<WeightAdjustment>, CompassConfigurationError> {
+    let vehicle_weight_opt =
+        parameters.get_config_serde_optional::<Weight>(&"vehicle_weight", parent_key)?;
+    let reference_weight =
+        match parameters.get_config_serde_optional::<Weight>(&"reference_weight", parent_key)? {
+            Some(weight) => weight,
+            None if vehicle_weight_opt.is_some() => {
+                return Err(CompassConfigurationError::UserConfigurationError(format!(
+                    "{} has a vehicle_weight but no reference_weight, the weight of the vehicle its prediction model represents",
+                    parent_key.as_ref()
+                )))
+            }
+            None => return Ok(None),
+        };
+    let vehicle_weight = vehicle_weight_opt.unwrap_or(reference_weight);
+    let weight_unit = parameters
+        .get_config_serde_optional::<WeightUnit>(&"weight_unit", parent_key)?
+        .unwrap_or(WeightUnit::Kg);
+    let drivetrain_efficiency =
+        parameters.get_config_serde_optional::<f64>(&"drivetrain_efficiency", parent_key)?;
+    let rolling_resistance = parameters
+        .get_config_serde_optional::<f64>(&"rolling_resistance", parent_key)?
+        .unwrap_or(WeightAdjustment::DEFAULT_ROLLING_RESISTANCE);
+    let adjustment = WeightAdjustment::new(
+        reference_weight,
+        vehicle_weight,
+        weight_unit,
+        drivetrain_efficiency,
+        rolling_resistance,
+    )?;
+    Ok(Some(adjustment))
+}
+
+/// reads the optional coefficients used to estimate the HVAC load of an electrified
+/// vehicle from the ambient temperature of a query.
+fn get_ambient_conditions_from_params(
+    parameters: &serde_json::Value,
+    parent_key: &dyn AsRef<str>,
+) -> Result<AmbientConditions, CompassConfigurationError> {
+    let comfort_temperature = parameters
+        .get_config_serde_optional::<f64>(&"comfort_temperature_celsius", parent_key)?
+        .unwrap_or(AmbientConditions::DEFAULT_COMFORT_TEMPERATURE_CELSIUS);
+    let heating_kw_per_degree = parameters
+        .get_config_serde_optional::<f64>(&"heating_kw_per_degree", parent_key)?
+        .unwrap_or(AmbientConditions::DEFAULT_HEATING_KW_PER_DEGREE);
+    let cooling_kw_per_degree = parameters
+        .get_config_serde_optional::<f64>(&"cooling_kw_per_degree", parent_key)?
+        .unwrap_or(AmbientConditions::DEFAULT_COOLING_KW_PER_DEGREE);
+    let ambient = AmbientConditions::new(
+        comfort_temperature,
+        heating_kw_per_degree,
+        cooling_kw_per_degree,
+    )?;
+    Ok(ambient)
+}
@@ -1,4 +1,10 @@
+pub mod active_travel_builder;
+pub mod custom_state_builder;
 pub mod distance_traversal_builder;
 pub mod energy_model_builder;
 pub mod energy_model_vehicle_builders;
+pub mod historical_speed_profile_builder;
 pub mod speed_lookup_builder;
+pub mod time_dependent_speed_builder;
+pub mod tomtom_speed_profile_builder;
+pub mod transit_traversal_builder;
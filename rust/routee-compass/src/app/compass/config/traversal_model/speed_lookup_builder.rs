@@ -1,17 +1,35 @@
 use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
 use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
+use routee_compass_core::model::traversal::default::grade_table::GradeTable;
 use routee_compass_core::model::traversal::default::speed_traversal_engine::SpeedTraversalEngine;
 
 use routee_compass_core::model::traversal::default::speed_traversal_service::SpeedLookupService;
 use routee_compass_core::model::traversal::traversal_model_builder::TraversalModelBuilder;
 use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
 use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
-use routee_compass_core::model::unit::{DistanceUnit, SpeedUnit, TimeUnit};
+use routee_compass_core::model::unit::{DistanceUnit, GradeUnit, SpeedUnit, TimeUnit};
 use std::sync::Arc;
 
 pub struct SpeedLookupBuilder {}
 
 impl TraversalModelBuilder for SpeedLookupBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "speed_table_input_file",
+            "speed_unit",
+            "distance_unit",
+            "time_unit",
+            "grade_table_input_file",
+            "grade_table_grade_unit",
+            "grade_table_skip_unit_check",
+            "grade_speed_factor",
+        ])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["speed_table_input_file", "speed_unit"]
+    }
+
     fn build(
         &self,
         params: &serde_json::Value,
@@ -31,8 +49,27 @@ impl TraversalModelBuilder for SpeedLookupBuilder {
             .get_config_serde_optional::<TimeUnit>(&"time_unit", &traversal_key)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
-        let e = SpeedTraversalEngine::new(&filename, speed_unit, distance_unit, time_unit)?;
-        let service = Arc::new(SpeedLookupService { e: Arc::new(e) });
+        let grade_table_path = params
+            .get_config_path_optional(&"grade_table_input_file", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+
+        let mut e = SpeedTraversalEngine::new(&filename, speed_unit, distance_unit, time_unit)?;
+        if let Some(path) = grade_table_path {
+            let grade_unit = params
+                .get_config_serde::<GradeUnit>(&"grade_table_grade_unit", &traversal_key)
+                .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+            let grade_speed_factor = params
+                .get_config_serde_optional::<f64>(&"grade_speed_factor", &traversal_key)
+                .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+                .unwrap_or(0.0);
+            let skip_unit_check = params
+                .get_config_serde_optional::<bool>(&"grade_table_skip_unit_check", &traversal_key)
+                .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+                .unwrap_or_default();
+            let grade_table = GradeTable::new(&path, grade_unit, skip_unit_check)?;
+            e = e.with_grade_table(grade_table, grade_speed_factor)?;
+        }
+        let service = Arc::new(SpeedLookupService::new(Arc::new(e)));
         Ok(service)
     }
 }
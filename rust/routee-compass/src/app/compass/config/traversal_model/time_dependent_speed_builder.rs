@@ -0,0 +1,64 @@
+use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
+use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
+use routee_compass_core::model::traversal::default::time_dependent_speed_engine::TimeDependentSpeedEngine;
+use routee_compass_core::model::traversal::default::time_dependent_speed_service::TimeDependentSpeedService;
+use routee_compass_core::model::traversal::traversal_model_builder::TraversalModelBuilder;
+use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
+use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
+use routee_compass_core::model::unit::{DistanceUnit, SpeedUnit, Time, TimeUnit};
+use std::sync::Arc;
+
+pub struct TimeDependentSpeedBuilder {}
+
+impl TraversalModelBuilder for TimeDependentSpeedBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "speed_profile_input_file",
+            "speed_unit",
+            "distance_unit",
+            "time_unit",
+            "time_slot_duration",
+            "time_slot_duration_unit",
+        ])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["speed_profile_input_file", "speed_unit"]
+    }
+
+    fn build(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
+        let traversal_key = CompassConfigurationField::Traversal.to_string();
+        let filename = params
+            .get_config_path(&"speed_profile_input_file", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let speed_unit = params
+            .get_config_serde::<SpeedUnit>(&"speed_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let slot_duration = params
+            .get_config_serde::<Time>(&"time_slot_duration", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let slot_duration_unit = params
+            .get_config_serde::<TimeUnit>(&"time_slot_duration_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let distance_unit = params
+            .get_config_serde_optional::<DistanceUnit>(&"distance_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let time_unit = params
+            .get_config_serde_optional::<TimeUnit>(&"time_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+
+        let e = TimeDependentSpeedEngine::new(
+            &filename,
+            speed_unit,
+            slot_duration,
+            slot_duration_unit,
+            distance_unit,
+            time_unit,
+        )?;
+        let service = Arc::new(TimeDependentSpeedService { e: Arc::new(e) });
+        Ok(service)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
+use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
+use routee_compass_core::model::traversal::default::tomtom_speed_profiles::read_tomtom_speed_profiles;
+use routee_compass_core::model::traversal::default::weekly_speed_profile_service::WeeklySpeedProfileService;
+use routee_compass_core::model::traversal::traversal_model_builder::TraversalModelBuilder;
+use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
+use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
+use routee_compass_core::model::unit::{
+    DistanceUnit, SpeedUnit, TimeUnit, BASE_DISTANCE_UNIT, BASE_TIME_UNIT,
+};
+use std::sync::Arc;
+
+pub struct TomTomSpeedProfileBuilder {}
+
+impl TraversalModelBuilder for TomTomSpeedProfileBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "speed_profile_input_file",
+            "network_profile_input_file",
+            "speed_unit",
+            "distance_unit",
+            "time_unit",
+        ])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec![
+            "speed_profile_input_file",
+            "network_profile_input_file",
+            "speed_unit",
+        ]
+    }
+
+    fn build(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
+        let traversal_key = CompassConfigurationField::Traversal.to_string();
+        let network_profile_file = params
+            .get_config_path(&"network_profile_input_file", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let speed_profile_file = params
+            .get_config_path(&"speed_profile_input_file", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let speed_unit = params
+            .get_config_serde::<SpeedUnit>(&"speed_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let distance_unit = params
+            .get_config_serde_optional::<DistanceUnit>(&"distance_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or(BASE_DISTANCE_UNIT);
+        let time_unit = params
+            .get_config_serde_optional::<TimeUnit>(&"time_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or(BASE_TIME_UNIT);
+
+        let e = read_tomtom_speed_profiles(
+            &network_profile_file,
+            &speed_profile_file,
+            speed_unit,
+            distance_unit,
+            time_unit,
+        )?;
+        let service = Arc::new(WeeklySpeedProfileService { e: Arc::new(e) });
+        Ok(service)
+    }
+}
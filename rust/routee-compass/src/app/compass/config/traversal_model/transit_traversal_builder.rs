@@ -0,0 +1,85 @@
+use crate::app::compass::config::compass_configuration_field::CompassConfigurationField;
+use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
+use routee_compass_core::model::traversal::default::transit_engine::TransitEngine;
+use routee_compass_core::model::traversal::default::transit_traversal_service::TransitTraversalService;
+use routee_compass_core::model::traversal::traversal_model_builder::TraversalModelBuilder;
+use routee_compass_core::model::traversal::traversal_model_error::TraversalModelError;
+use routee_compass_core::model::traversal::traversal_model_service::TraversalModelService;
+use routee_compass_core::model::unit::{
+    DistanceUnit, Speed, SpeedUnit, TimeUnit, BASE_DISTANCE_UNIT, BASE_TIME_UNIT,
+};
+use std::sync::Arc;
+
+pub struct TransitTraversalBuilder {}
+
+impl TransitTraversalBuilder {
+    /// a typical walking pace
+    const DEFAULT_WALK_SPEED_KPH: f64 = 5.0;
+}
+
+impl TraversalModelBuilder for TransitTraversalBuilder {
+    fn config_keys(&self) -> Option<Vec<&'static str>> {
+        Some(vec![
+            "edge_mode_input_file",
+            "departures_input_file",
+            "walk_speed",
+            "walk_speed_unit",
+            "distance_unit",
+            "time_unit",
+        ])
+    }
+
+    fn required_config_keys(&self) -> Vec<&'static str> {
+        vec!["edge_mode_input_file", "departures_input_file"]
+    }
+
+    fn build(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
+        let traversal_key = CompassConfigurationField::Traversal.to_string();
+        let edge_mode_file = params
+            .get_config_path(&"edge_mode_input_file", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let departures_file = params
+            .get_config_path(&"departures_input_file", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let walk_speed = params
+            .get_config_serde_optional::<Speed>(&"walk_speed", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let walk_speed_unit = params
+            .get_config_serde_optional::<SpeedUnit>(&"walk_speed_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let (walk_speed, walk_speed_unit) = match (walk_speed, walk_speed_unit) {
+            (Some(speed), Some(unit)) => (speed, unit),
+            (None, None) => (
+                Speed::new(Self::DEFAULT_WALK_SPEED_KPH),
+                SpeedUnit::KilometersPerHour,
+            ),
+            _ => {
+                return Err(TraversalModelError::BuildError(String::from(
+                    "walk_speed and walk_speed_unit must be provided together",
+                )))
+            }
+        };
+        let distance_unit = params
+            .get_config_serde_optional::<DistanceUnit>(&"distance_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or(BASE_DISTANCE_UNIT);
+        let time_unit = params
+            .get_config_serde_optional::<TimeUnit>(&"time_unit", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or(BASE_TIME_UNIT);
+
+        let e = TransitEngine::new(
+            &edge_mode_file,
+            &departures_file,
+            walk_speed,
+            walk_speed_unit,
+            distance_unit,
+            time_unit,
+        )?;
+        let service = Arc::new(TransitTraversalService { e: Arc::new(e) });
+        Ok(service)
+    }
+}
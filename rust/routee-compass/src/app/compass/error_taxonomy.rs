@@ -0,0 +1,277 @@
+use super::compass_app_error::CompassAppError;
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::algorithm::search::search_error::SearchError;
+use routee_compass_core::model::termination::termination_model_error::TerminationModelError;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// category of a failed query, stable across releases so that downstream
+/// pipelines can group failures without parsing error messages.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// the destination cannot be reached from the origin
+    NoPathFound,
+    /// the search was stopped by the termination model
+    QueryTerminated,
+    /// the query was aborted by the caller before it finished
+    Cancelled,
+    /// the query is malformed or asks for something that is not supported
+    InvalidInput,
+    /// the query is missing a required field
+    MissingField,
+    /// a value could not be parsed or serialized
+    ParseError,
+    /// a traversal, access, frontier, cost or state model failed
+    ModelError,
+    /// the graph is missing a requested vertex or edge
+    GraphError,
+    /// a model or plugin could not be built for the query
+    BuildError,
+    /// a plugin failed while processing the query
+    PluginFailed,
+    /// a response could not be written in the output format
+    OutputFormatError,
+    ConfigError,
+    IoError,
+    InternalError,
+}
+
+/// the part of the application where a failure occurred
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorComponent {
+    Search,
+    Query,
+    Plugin,
+    InputPlugin,
+    OutputPlugin,
+    TraversalModel,
+    AccessModel,
+    FrontierModel,
+    CostModel,
+    StateModel,
+    Termination,
+    Graph,
+    ResponseOutput,
+    Config,
+    App,
+}
+
+/// the structured form of an error in a response row
+#[derive(Serialize, Clone, Debug)]
+pub struct ErrorDetail {
+    pub code: ErrorCode,
+    pub message: String,
+    pub component: ErrorComponent,
+}
+
+impl ErrorDetail {
+    pub fn new<M: ToString>(code: ErrorCode, component: ErrorComponent, message: M) -> Self {
+        ErrorDetail {
+            code,
+            message: message.to_string(),
+            component,
+        }
+    }
+
+    /// attributes an error raised by a plugin to the kind of plugin that raised it.
+    /// errors that a plugin passed along from another component are left as they are.
+    pub fn attributed_to(mut self, component: ErrorComponent) -> Self {
+        if self.component == ErrorComponent::Plugin {
+            self.component = component;
+        }
+        self
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!(self)
+    }
+}
+
+/// assigns an error code and component to each kind of error that can fail a query
+pub trait ErrorTaxonomy: Display {
+    fn error_code(&self) -> ErrorCode;
+    fn error_component(&self) -> ErrorComponent;
+
+    fn error_detail(&self) -> ErrorDetail {
+        ErrorDetail::new(self.error_code(), self.error_component(), self)
+    }
+}
+
+impl<T: ErrorTaxonomy + ?Sized> ErrorTaxonomy for &T {
+    fn error_code(&self) -> ErrorCode {
+        (**self).error_code()
+    }
+    fn error_component(&self) -> ErrorComponent {
+        (**self).error_component()
+    }
+    fn error_detail(&self) -> ErrorDetail {
+        (**self).error_detail()
+    }
+}
+
+impl ErrorTaxonomy for ErrorDetail {
+    fn error_code(&self) -> ErrorCode {
+        self.code
+    }
+    fn error_component(&self) -> ErrorComponent {
+        self.component
+    }
+    fn error_detail(&self) -> ErrorDetail {
+        self.clone()
+    }
+}
+
+impl Display for ErrorDetail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ErrorTaxonomy for SearchError {
+    fn error_code(&self) -> ErrorCode {
+        use SearchError as E;
+        match self {
+            E::NoPathExists(_, _) | E::NoPathExistsToAny(_, _) => ErrorCode::NoPathFound,
+            E::QueryTerminated(_) | E::Terminated(_) => ErrorCode::QueryTerminated,
+            E::Cancelled => ErrorCode::Cancelled,
+            E::TerminationModelError(TerminationModelError::QueryTerminated(_)) => {
+                ErrorCode::QueryTerminated
+            }
+            E::TerminationModelError(_) => ErrorCode::InternalError,
+            E::DistanceHeuristicWithNoTarget | E::InvalidQuery(_) => ErrorCode::InvalidInput,
+            E::StateError(_)
+            | E::TraversalModelFailure(_)
+            | E::AccessModelFailure(_)
+            | E::FrontierModelFailure(_)
+            | E::CostError(_) => ErrorCode::ModelError,
+            E::GraphError(_) => ErrorCode::GraphError,
+            E::BuildError(_) => ErrorCode::BuildError,
+            E::LoopInSearchResult(_)
+            | E::VertexMissingFromSearchTree(_)
+            | E::ReadOnlyPoisonError(_)
+            | E::InternalSearchError(_) => ErrorCode::InternalError,
+        }
+    }
+
+    fn error_component(&self) -> ErrorComponent {
+        use SearchError as E;
+        match self {
+            E::StateError(_) => ErrorComponent::StateModel,
+            E::TraversalModelFailure(_) => ErrorComponent::TraversalModel,
+            E::AccessModelFailure(_) => ErrorComponent::AccessModel,
+            E::FrontierModelFailure(_) => ErrorComponent::FrontierModel,
+            E::CostError(_) => ErrorComponent::CostModel,
+            E::GraphError(_) => ErrorComponent::Graph,
+            E::TerminationModelError(_) => ErrorComponent::Termination,
+            E::InvalidQuery(_) => ErrorComponent::Query,
+            _ => ErrorComponent::Search,
+        }
+    }
+}
+
+impl ErrorTaxonomy for PluginError {
+    fn error_code(&self) -> ErrorCode {
+        use PluginError as E;
+        match self {
+            E::SearchError(e) => e.error_code(),
+            E::ParseError(_, _) | E::JsonError(_) | E::GeoJsonError(_) | E::CsvReadError(_) => {
+                ErrorCode::ParseError
+            }
+            E::MissingField(_) => ErrorCode::MissingField,
+            E::InputError(_) | E::UnexpectedQueryStructure(_) => ErrorCode::InvalidInput,
+            E::BuildError => ErrorCode::BuildError,
+            E::PluginFailed(_) | E::EdgeGeometryMissing(_) | E::UUIDMissing(_) => {
+                ErrorCode::PluginFailed
+            }
+            E::FileReadError(_, _) => ErrorCode::IoError,
+            E::InternalError(_) => ErrorCode::InternalError,
+        }
+    }
+
+    fn error_component(&self) -> ErrorComponent {
+        match self {
+            PluginError::SearchError(e) => e.error_component(),
+            _ => ErrorComponent::Plugin,
+        }
+    }
+}
+
+impl ErrorTaxonomy for CompassAppError {
+    fn error_code(&self) -> ErrorCode {
+        use CompassAppError as E;
+        match self {
+            E::SearchError(e) => e.error_code(),
+            E::PluginError(e) => e.error_code(),
+            E::FrontierModelError(_) | E::TraversalModelError(_) | E::StateError(_) => {
+                ErrorCode::ModelError
+            }
+            E::GraphError(_) => ErrorCode::GraphError,
+            E::IOError(_) | E::NoInputFile(_) | E::ParquetError(_) => ErrorCode::IoError,
+            E::CodecError(_) => ErrorCode::ParseError,
+            E::ConfigError(_) | E::CompassConfigurationError(_) => ErrorCode::ConfigError,
+            E::MissingInputField(_) => ErrorCode::MissingField,
+            E::InvalidInput(_) => ErrorCode::InvalidInput,
+            E::UXError(_) | E::InternalError(_) | E::ReadOnlyPoisonError(_) => {
+                ErrorCode::InternalError
+            }
+        }
+    }
+
+    fn error_component(&self) -> ErrorComponent {
+        use CompassAppError as E;
+        match self {
+            E::SearchError(e) => e.error_component(),
+            E::PluginError(e) => e.error_component(),
+            E::FrontierModelError(_) => ErrorComponent::FrontierModel,
+            E::TraversalModelError(_) => ErrorComponent::TraversalModel,
+            E::StateError(_) => ErrorComponent::StateModel,
+            E::GraphError(_) => ErrorComponent::Graph,
+            E::ParquetError(_) => ErrorComponent::ResponseOutput,
+            E::ConfigError(_) | E::CompassConfigurationError(_) => ErrorComponent::Config,
+            E::MissingInputField(_) | E::InvalidInput(_) | E::CodecError(_) => {
+                ErrorComponent::Query
+            }
+            E::IOError(_)
+            | E::NoInputFile(_)
+            | E::UXError(_)
+            | E::InternalError(_)
+            | E::ReadOnlyPoisonError(_) => ErrorComponent::App,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::road_network::vertex_id::VertexId;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_detail_json() {
+        let error =
+            CompassAppError::SearchError(SearchError::NoPathExists(VertexId(0), VertexId(2)));
+        assert_eq!(
+            error.error_detail().to_json(),
+            json!({
+                "code": "NO_PATH_FOUND",
+                "message": "no path exists between vertices 0 and 2",
+                "component": "search"
+            })
+        );
+
+        // plugin errors are attributed to the plugin stage, unless they wrap another component
+        let plugin_error = PluginError::MissingField(String::from("origin_x"));
+        let detail = plugin_error
+            .error_detail()
+            .attributed_to(ErrorComponent::InputPlugin);
+        assert_eq!(detail.code, ErrorCode::MissingField);
+        assert_eq!(detail.component, ErrorComponent::InputPlugin);
+        let wrapped = PluginError::SearchError(SearchError::BuildError(String::from("bad")));
+        let detail = wrapped
+            .error_detail()
+            .attributed_to(ErrorComponent::OutputPlugin);
+        assert_eq!(detail.component, ErrorComponent::Search);
+    }
+}
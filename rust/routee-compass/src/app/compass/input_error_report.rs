@@ -0,0 +1,100 @@
+use super::compass_app_error::CompassAppError;
+use crate::plugin::input::{
+    input_error_policy::InputErrorPolicy, input_plugin_ops::INPUT_PLUGIN_ERRORS_FIELD,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// the keys of the `[plugin]` section which configure the handling of input plugin errors
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputErrorConfig {
+    #[serde(default)]
+    pub input_error_policy: InputErrorPolicy,
+    /// file to write the report of dropped queries to as JSON after each run
+    #[serde(default)]
+    pub dropped_queries_file: Option<String>,
+}
+
+/// the queries that input plugins failed to process during a run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InputErrorReport {
+    pub policy: InputErrorPolicy,
+    /// number of queries dropped from the run
+    pub dropped: usize,
+    /// number of queries run with an `input_plugin_errors` field
+    pub tagged: usize,
+    /// the error response of each dropped query, only kept when the report is written to a file
+    pub dropped_queries: Vec<Value>,
+}
+
+/// collects the input plugin failures of each query across the chunks of a run
+pub struct InputErrorRecorder {
+    pub config: InputErrorConfig,
+    report: Mutex<InputErrorReport>,
+}
+
+impl InputErrorRecorder {
+    pub fn new(config: &InputErrorConfig) -> InputErrorRecorder {
+        InputErrorRecorder {
+            config: config.clone(),
+            report: Mutex::new(InputErrorReport {
+                policy: config.input_error_policy,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn policy(&self) -> InputErrorPolicy {
+        self.config.input_error_policy
+    }
+
+    /// records the queries which passed input processing and the error responses
+    /// of those which were dropped
+    pub fn record(&self, queries: &[Value], errors: &[Value]) -> Result<(), CompassAppError> {
+        let mut report = self.lock()?;
+        report.dropped += errors.len();
+        report.tagged += queries
+            .iter()
+            .filter(|q| q.get(INPUT_PLUGIN_ERRORS_FIELD).is_some())
+            .count();
+        if self.config.dropped_queries_file.is_some() {
+            report.dropped_queries.extend(errors.iter().cloned());
+        }
+        Ok(())
+    }
+
+    /// takes the failures recorded since the last report, logging them and writing them
+    /// to the configured file
+    pub fn report(&self) -> Result<InputErrorReport, CompassAppError> {
+        let report = self.take()?;
+        if report.dropped > 0 || report.tagged > 0 {
+            log::warn!(
+                "input plugins failed on {} queries which were dropped and {} queries which were run with errors",
+                report.dropped,
+                report.tagged
+            );
+        }
+        if let Some(file) = &self.config.dropped_queries_file {
+            let contents = serde_json::to_string_pretty(&report)?;
+            std::fs::write(Path::new(file), contents)?;
+        }
+        Ok(report)
+    }
+
+    fn take(&self) -> Result<InputErrorReport, CompassAppError> {
+        let mut report = self.lock()?;
+        let empty = InputErrorReport {
+            policy: report.policy,
+            ..Default::default()
+        };
+        Ok(std::mem::replace(&mut *report, empty))
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, InputErrorReport>, CompassAppError> {
+        self.report.lock().map_err(|e| {
+            CompassAppError::InternalError(format!("input error report poisoned: {}", e))
+        })
+    }
+}
@@ -0,0 +1,432 @@
+use super::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use routee_compass_core::algorithm::search::search_error::SearchError;
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// configuration of the `[metrics]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// file to write the run-level summary to as JSON
+    #[serde(default)]
+    pub output_file: Option<String>,
+    /// file to write the run-level summary to in the Prometheus text exposition format
+    #[serde(default)]
+    pub prometheus_file: Option<String>,
+    /// if true, each response has a "metrics" object describing its own query
+    #[serde(default = "default_per_query")]
+    pub per_query: bool,
+}
+
+fn default_per_query() -> bool {
+    true
+}
+
+/// measurements of the work done to answer a single query
+#[derive(Debug, Clone, Default)]
+pub struct QueryMetrics {
+    pub search_runtime: Duration,
+    pub route_runtime: Duration,
+    pub output_plugin_runtime: Duration,
+    pub expansions: u64,
+    pub tree_size: usize,
+    pub max_frontier_size: usize,
+    pub heap_pushes: u64,
+    pub heap_pops: u64,
+    /// true if the response came from the result cache
+    pub cached: bool,
+    /// the error code of a failed query
+    pub failure: Option<String>,
+}
+
+impl QueryMetrics {
+    /// the measurements of a search, which are left at zero for searches that
+    /// failed before any work was recorded
+    pub fn from_search_result(
+        result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> QueryMetrics {
+        match result {
+            Ok((result, _)) => QueryMetrics {
+                search_runtime: result.search_runtime,
+                route_runtime: result.metrics.route_runtime,
+                expansions: result.iterations,
+                tree_size: result.trees.iter().map(|t| t.len()).sum(),
+                max_frontier_size: result.metrics.max_frontier_size,
+                heap_pushes: result.metrics.heap_pushes,
+                heap_pops: result.metrics.heap_pops,
+                ..Default::default()
+            },
+            Err(CompassAppError::SearchError(SearchError::Terminated(terminated))) => {
+                QueryMetrics {
+                    search_runtime: terminated.runtime,
+                    expansions: terminated.iterations,
+                    tree_size: terminated.tree_size,
+                    ..Default::default()
+                }
+            }
+            Err(_) => QueryMetrics::default(),
+        }
+    }
+
+    /// the metrics of a response served from the result cache
+    pub fn cached() -> QueryMetrics {
+        QueryMetrics {
+            cached: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn total_runtime(&self) -> Duration {
+        self.search_runtime + self.output_plugin_runtime
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "search_runtime_ms": millis(&self.search_runtime),
+            "route_runtime_ms": millis(&self.route_runtime),
+            "output_plugin_runtime_ms": millis(&self.output_plugin_runtime),
+            "expansions": self.expansions,
+            "tree_size": self.tree_size,
+            "max_frontier_size": self.max_frontier_size,
+            "heap_pushes": self.heap_pushes,
+            "heap_pops": self.heap_pops,
+            "cached": self.cached,
+        })
+    }
+}
+
+/// summary statistics of a set of measurements
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct Distribution {
+    pub count: usize,
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    pub fn new(mut values: Vec<f64>) -> Distribution {
+        if values.is_empty() {
+            return Distribution::default();
+        }
+        values.sort_by(|a, b| a.total_cmp(b));
+        let count = values.len();
+        // nearest-rank percentile
+        let percentile = |p: f64| values[((p * count as f64).ceil() as usize).clamp(1, count) - 1];
+        Distribution {
+            count,
+            mean: values.iter().sum::<f64>() / count as f64,
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: values[count - 1],
+        }
+    }
+}
+
+/// the run-level summary of the metrics of every query. runtimes are in milliseconds.
+/// the input plugin runtime is measured once per query before input plugins expand it,
+/// while all other distributions are measured once per query sent to the search.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunMetrics {
+    pub queries: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub cached: usize,
+    pub failures: BTreeMap<String, usize>,
+    pub runtime_ms: BTreeMap<String, Distribution>,
+    pub expansions: Distribution,
+    pub tree_size: Distribution,
+    pub max_frontier_size: Distribution,
+}
+
+impl RunMetrics {
+    /// writes the summary in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP routee_compass_queries_total queries run");
+        let _ = writeln!(out, "# TYPE routee_compass_queries_total counter");
+        let _ = writeln!(out, "routee_compass_queries_total {}", self.queries);
+        let _ = writeln!(
+            out,
+            "# HELP routee_compass_query_failures_total failed queries by error code"
+        );
+        let _ = writeln!(out, "# TYPE routee_compass_query_failures_total counter");
+        for (code, count) in self.failures.iter() {
+            let _ = writeln!(
+                out,
+                "routee_compass_query_failures_total{{code=\"{}\"}} {}",
+                code, count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP routee_compass_cache_hits_total queries answered from the result cache"
+        );
+        let _ = writeln!(out, "# TYPE routee_compass_cache_hits_total counter");
+        let _ = writeln!(out, "routee_compass_cache_hits_total {}", self.cached);
+        let _ = writeln!(
+            out,
+            "# HELP routee_compass_runtime_seconds runtime of each stage of a query"
+        );
+        let _ = writeln!(out, "# TYPE routee_compass_runtime_seconds summary");
+        for (stage, dist) in self.runtime_ms.iter() {
+            let labels = format!("stage=\"{}\"", stage);
+            write_summary(
+                &mut out,
+                "routee_compass_runtime_seconds",
+                &labels,
+                dist,
+                0.001,
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP routee_compass_expansions vertices expanded by each search"
+        );
+        let _ = writeln!(out, "# TYPE routee_compass_expansions summary");
+        write_summary(
+            &mut out,
+            "routee_compass_expansions",
+            "",
+            &self.expansions,
+            1.0,
+        );
+        out
+    }
+}
+
+fn write_summary(out: &mut String, name: &str, labels: &str, dist: &Distribution, scale: f64) {
+    let sep = if labels.is_empty() { "" } else { "," };
+    for (quantile, value) in [("0.5", dist.p50), ("0.95", dist.p95), ("0.99", dist.p99)] {
+        let _ = writeln!(
+            out,
+            "{}{{{}{}quantile=\"{}\"}} {}",
+            name,
+            labels,
+            sep,
+            quantile,
+            value * scale
+        );
+    }
+    let braces = if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels)
+    };
+    let sum = dist.mean * dist.count as f64 * scale;
+    let _ = writeln!(out, "{}_sum{} {}", name, braces, sum);
+    let _ = writeln!(out, "{}_count{} {}", name, braces, dist.count);
+}
+
+/// collects the metrics of each query in a run and reports a summary when the run ends
+pub struct MetricsRecorder {
+    config: MetricsConfig,
+    queries: Mutex<Vec<QueryMetrics>>,
+    input_plugin_runtimes: Mutex<Vec<Duration>>,
+}
+
+impl MetricsRecorder {
+    pub fn new(config: &MetricsConfig) -> MetricsRecorder {
+        MetricsRecorder {
+            config: config.clone(),
+            queries: Mutex::new(vec![]),
+            input_plugin_runtimes: Mutex::new(vec![]),
+        }
+    }
+
+    /// records the metrics of a query, reading its error code from the response,
+    /// and adds the metrics to the response if configured
+    pub fn record(
+        &self,
+        response: &mut Value,
+        mut metrics: QueryMetrics,
+    ) -> Result<(), CompassAppError> {
+        metrics.failure = failure_code(response);
+        if self.config.per_query {
+            response["metrics"] = metrics.to_json();
+        }
+        self.queries
+            .lock()
+            .map_err(|e| CompassAppError::InternalError(format!("metrics poisoned: {}", e)))?
+            .push(metrics);
+        Ok(())
+    }
+
+    /// records a query which failed while running the input plugins
+    pub fn record_input_failure(&self, response: &Value) -> Result<(), CompassAppError> {
+        let metrics = QueryMetrics {
+            failure: failure_code(response),
+            ..Default::default()
+        };
+        self.queries
+            .lock()
+            .map_err(|e| CompassAppError::InternalError(format!("metrics poisoned: {}", e)))?
+            .push(metrics);
+        Ok(())
+    }
+
+    /// records the time to run the input plugins on a query
+    pub fn record_input_plugins(&self, runtime: Duration) -> Result<(), CompassAppError> {
+        self.input_plugin_runtimes
+            .lock()
+            .map_err(|e| CompassAppError::InternalError(format!("metrics poisoned: {}", e)))?
+            .push(runtime);
+        Ok(())
+    }
+
+    /// summarizes and drops the metrics recorded so far
+    pub fn summarize(&self) -> Result<RunMetrics, CompassAppError> {
+        let (queries, input_plugin_runtimes) = self.take()?;
+        let failures = queries.iter().filter_map(|q| q.failure.clone()).fold(
+            BTreeMap::new(),
+            |mut acc, code| {
+                *acc.entry(code).or_insert(0) += 1;
+                acc
+            },
+        );
+        let failed = failures.values().sum::<usize>();
+        let searched = queries.iter().filter(|q| !q.cached).collect::<Vec<_>>();
+        let runtime = |f: &dyn Fn(&QueryMetrics) -> Duration| {
+            Distribution::new(searched.iter().map(|q| millis(&f(q))).collect())
+        };
+        let runtime_ms = BTreeMap::from([
+            (String::from("total"), runtime(&|q| q.total_runtime())),
+            (String::from("search"), runtime(&|q| q.search_runtime)),
+            (String::from("route"), runtime(&|q| q.route_runtime)),
+            (
+                String::from("output_plugin"),
+                runtime(&|q| q.output_plugin_runtime),
+            ),
+            (
+                String::from("input_plugin"),
+                Distribution::new(input_plugin_runtimes.iter().map(millis).collect()),
+            ),
+        ]);
+        Ok(RunMetrics {
+            queries: queries.len(),
+            succeeded: queries.len() - failed,
+            failed,
+            cached: queries.len() - searched.len(),
+            failures,
+            runtime_ms,
+            expansions: Distribution::new(searched.iter().map(|q| q.expansions as f64).collect()),
+            tree_size: Distribution::new(searched.iter().map(|q| q.tree_size as f64).collect()),
+            max_frontier_size: Distribution::new(
+                searched
+                    .iter()
+                    .map(|q| q.max_frontier_size as f64)
+                    .collect(),
+            ),
+        })
+    }
+
+    /// summarizes the metrics recorded so far, logs the summary and writes it to
+    /// the configured files
+    pub fn report(&self) -> Result<RunMetrics, CompassAppError> {
+        let summary = self.summarize()?;
+        let total = summary.runtime_ms.get("total").cloned().unwrap_or_default();
+        log::info!(
+            "metrics: {} queries, {} failed, {} cached, query runtime p50 {:.3}ms p95 {:.3}ms",
+            summary.queries,
+            summary.failed,
+            summary.cached,
+            total.p50,
+            total.p95
+        );
+        if let Some(file) = &self.config.output_file {
+            let contents = serde_json::to_string_pretty(&summary)?;
+            std::fs::write(Path::new(file), contents)?;
+        }
+        if let Some(file) = &self.config.prometheus_file {
+            std::fs::write(Path::new(file), summary.to_prometheus())?;
+        }
+        Ok(summary)
+    }
+
+    fn take(&self) -> Result<(Vec<QueryMetrics>, Vec<Duration>), CompassAppError> {
+        let queries = std::mem::take(
+            &mut *self
+                .queries
+                .lock()
+                .map_err(|e| CompassAppError::InternalError(format!("metrics poisoned: {}", e)))?,
+        );
+        let input_plugin_runtimes = std::mem::take(
+            &mut *self
+                .input_plugin_runtimes
+                .lock()
+                .map_err(|e| CompassAppError::InternalError(format!("metrics poisoned: {}", e)))?,
+        );
+        Ok((queries, input_plugin_runtimes))
+    }
+}
+
+fn millis(duration: &Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// the error code of a failed response, or None if it succeeded
+fn failure_code(response: &Value) -> Option<String> {
+    response.get("error").map(|error| match error.get("code") {
+        Some(Value::String(code)) => code.clone(),
+        _ => String::from("UNKNOWN"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let recorder = MetricsRecorder::new(&MetricsConfig {
+            output_file: None,
+            prometheus_file: None,
+            per_query: true,
+        });
+        for ms in 1..=100 {
+            let mut response = json!({});
+            let metrics = QueryMetrics {
+                search_runtime: Duration::from_millis(ms),
+                expansions: ms,
+                ..Default::default()
+            };
+            recorder.record(&mut response, metrics).unwrap();
+            assert_eq!(response["metrics"]["expansions"], ms);
+        }
+        let mut failed = json!({ "error": { "code": "NO_PATH_FOUND" } });
+        recorder
+            .record(&mut failed, QueryMetrics::default())
+            .unwrap();
+        recorder
+            .record(&mut json!({}), QueryMetrics::cached())
+            .unwrap();
+
+        let summary = recorder.summarize().unwrap();
+        assert_eq!(
+            (summary.queries, summary.failed, summary.cached),
+            (102, 1, 1)
+        );
+        assert_eq!(summary.failures.get("NO_PATH_FOUND"), Some(&1));
+        let search = &summary.runtime_ms["search"];
+        assert_eq!(search.count, 101);
+        assert_eq!((search.p50, search.p95, search.max), (50.0, 95.0, 100.0));
+        let prometheus = summary.to_prometheus();
+        assert!(
+            prometheus.contains("routee_compass_query_failures_total{code=\"NO_PATH_FOUND\"} 1")
+        );
+        assert!(prometheus
+            .contains("routee_compass_runtime_seconds{stage=\"search\",quantile=\"0.95\"} 0.095"));
+
+        // summarizing drops the recorded metrics
+        assert_eq!(recorder.summarize().unwrap().queries, 0);
+    }
+}
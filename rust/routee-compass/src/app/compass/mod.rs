@@ -4,5 +4,14 @@ pub mod compass_app_ops;
 pub mod compass_input_field;
 pub mod compass_json_extensions;
 pub mod config;
+pub mod error_taxonomy;
+pub mod input_error_report;
+pub mod metrics;
+pub mod od_matrix;
+pub mod query_id;
+pub mod reproducibility;
 pub mod response;
+pub mod result_cache;
+pub mod run_progress;
 pub mod search_orientation;
+pub mod shared_origin;
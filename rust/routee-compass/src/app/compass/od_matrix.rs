@@ -0,0 +1,98 @@
+use super::compass_app_error::CompassAppError;
+use crate::plugin::input::input_field::InputField;
+use serde_json::{json, Value};
+
+/// builds a query for every pair of an origin and a destination coordinate, in
+/// row-major order so that the query for origin `i` and destination `j` is at
+/// `i * destinations.len() + j` and has that position as its `query_id`. every
+/// query copies the other fields of the template, such as a `model_name`.
+pub fn od_matrix_queries(
+    template: &Value,
+    origins: &[(f64, f64)],
+    destinations: &[(f64, f64)],
+) -> Result<Vec<Value>, CompassAppError> {
+    if !template.is_object() {
+        return Err(CompassAppError::InvalidInput(format!(
+            "od matrix query must be a JSON object, found {}",
+            template
+        )));
+    }
+    let mut queries = Vec::with_capacity(origins.len() * destinations.len());
+    for (o_x, o_y) in origins.iter() {
+        for (d_x, d_y) in destinations.iter() {
+            let mut query = template.clone();
+            query[InputField::OriginX.to_str()] = json!(o_x);
+            query[InputField::OriginY.to_str()] = json!(o_y);
+            query[InputField::DestinationX.to_str()] = json!(d_x);
+            query[InputField::DestinationY.to_str()] = json!(d_y);
+            query[InputField::QueryId.to_str()] = json!(queries.len());
+            queries.push(query);
+        }
+    }
+    Ok(queries)
+}
+
+/// pairs x and y coordinate lists into points
+pub fn zip_coordinates(xs: Vec<f64>, ys: Vec<f64>) -> Result<Vec<(f64, f64)>, CompassAppError> {
+    if xs.len() != ys.len() {
+        return Err(CompassAppError::InvalidInput(format!(
+            "found {} x coordinates but {} y coordinates",
+            xs.len(),
+            ys.len()
+        )));
+    }
+    Ok(xs.into_iter().zip(ys).collect())
+}
+
+/// reads each feature from the `traversal_summary` of the route of each result into a
+/// row-major matrix of `n_cells` values per feature, placing each result by its
+/// `query_id`. cells without a result, such as those of failed queries, are NaN.
+pub fn od_matrix_features(results: &[Value], n_cells: usize, features: &[String]) -> Vec<Vec<f64>> {
+    let mut matrices = vec![vec![f64::NAN; n_cells]; features.len()];
+    for result in results.iter() {
+        let cell = match result
+            .get(InputField::QueryId.to_str())
+            .and_then(Value::as_u64)
+        {
+            Some(id) if (id as usize) < n_cells => id as usize,
+            _ => continue,
+        };
+        let summary = match result.get("route").and_then(|r| r.get("traversal_summary")) {
+            Some(summary) => summary,
+            None => continue,
+        };
+        for (matrix, feature) in matrices.iter_mut().zip(features.iter()) {
+            if let Some(value) = summary.get(feature).and_then(Value::as_f64) {
+                matrix[cell] = value;
+            }
+        }
+    }
+    matrices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_od_matrix_queries_and_features() {
+        let template = json!({ "model_name": "car" });
+        let queries = od_matrix_queries(&template, &[(0.0, 1.0), (2.0, 3.0)], &[(4.0, 5.0)])
+            .expect("should build queries");
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[1]["origin_x"], json!(2.0));
+        assert_eq!(queries[1]["destination_y"], json!(5.0));
+        assert_eq!(queries[1]["model_name"], json!("car"));
+        assert_eq!(queries[1]["query_id"], json!(1));
+
+        let results = vec![
+            json!({ "query_id": 1, "route": { "traversal_summary": { "distance": 2.5 } } }),
+            json!({ "query_id": 0, "error": "no path" }),
+        ];
+        let features = vec![String::from("distance"), String::from("time")];
+        let matrices = od_matrix_features(&results, 2, &features);
+        assert!(matrices[0][0].is_nan());
+        assert_eq!(matrices[0][1], 2.5);
+        assert!(matrices[1].iter().all(|v| v.is_nan()));
+    }
+}
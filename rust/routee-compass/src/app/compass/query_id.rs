@@ -0,0 +1,100 @@
+use crate::plugin::input::input_field::InputField;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// fields which identify a query without changing its result, and so are left out of
+/// the keys of the result cache and of shared origin batching
+pub const QUERY_ID_FIELDS: [InputField; 2] = [InputField::QueryId, InputField::ParentQueryId];
+
+/// adds the query id fields to a list of fields which are ignored when comparing queries
+pub fn with_query_id_fields(ignored_fields: &[String]) -> Vec<String> {
+    let mut fields = ignored_fields.to_vec();
+    for field in QUERY_ID_FIELDS.iter() {
+        if !fields.iter().any(|f| f == field.to_str()) {
+            fields.push(field.to_str().to_string());
+        }
+    }
+    fields
+}
+
+/// the id given to the next query without a `query_id`, shared by every run of the
+/// process so that queries of different runs, such as server requests, never share an id
+static NEXT_QUERY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// gives each query a string `query_id`, see [`assign_query_id`]
+pub fn assign_query_ids(queries: &mut [Value]) {
+    for query in queries.iter_mut() {
+        assign_query_id(query);
+    }
+}
+
+/// gives a query without a `query_id` the next id of the process-wide counter. ids are
+/// always strings, so an id provided by the user as a number is kept as its string.
+pub fn assign_query_id(query: &mut Value) {
+    if let Value::Object(obj) = query {
+        let id = obj
+            .entry(InputField::QueryId.to_str())
+            .or_insert_with(next_query_id);
+        if !id.is_string() {
+            *id = Value::String(id.to_string());
+        }
+    }
+}
+
+/// takes the next id of the process-wide counter
+pub fn next_query_id() -> Value {
+    Value::String(NEXT_QUERY_ID.fetch_add(1, Ordering::Relaxed).to_string())
+}
+
+/// copies the `query_id` and `parent_query_id` of a query onto its response, so that
+/// each response can be joined back to its query even if an output plugin replaced
+/// the echoed request
+pub fn add_query_ids(query: &Value, response: &mut Value) {
+    if let Value::Object(obj) = response {
+        for field in QUERY_ID_FIELDS.iter() {
+            if let Some(id) = query.get(field.to_str()) {
+                obj.insert(field.to_str().to_string(), id.clone());
+            }
+        }
+    }
+}
+
+/// the `query_id` of a query expanded from another by an input plugin, which is the
+/// id of its parent followed by its position among the expanded queries
+pub fn expanded_query_id(parent_id: &Value, position: usize) -> Value {
+    match parent_id {
+        Value::String(id) => Value::String(format!("{}-{}", id, position)),
+        other => Value::String(format!("{}-{}", other, position)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_assign_and_add_query_ids() {
+        let mut queries = vec![
+            json!({ "query_id": "a" }),
+            json!({ "query_id": 3 }),
+            json!({}),
+            json!({}),
+        ];
+        assign_query_ids(&mut queries);
+        assert_eq!(queries[0]["query_id"], json!("a"));
+        assert_eq!(queries[1]["query_id"], json!("3"));
+        let assigned = queries[2..]
+            .iter()
+            .map(|q| q["query_id"].as_str().unwrap().parse::<u64>().unwrap())
+            .collect::<Vec<_>>();
+        assert!(assigned[0] < assigned[1]);
+
+        let query = json!({ "query_id": "a-1", "parent_query_id": "a" });
+        let mut response = json!({ "route": {} });
+        add_query_ids(&query, &mut response);
+        assert_eq!(response["query_id"], json!("a-1"));
+        assert_eq!(response["parent_query_id"], json!("a"));
+        assert_eq!(expanded_query_id(&json!("3"), 0), json!("3-0"));
+    }
+}
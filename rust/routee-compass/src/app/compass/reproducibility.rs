@@ -0,0 +1,361 @@
+use super::compass_app_error::CompassAppError;
+use crate::plugin::input::input_field::InputField;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// suffix of the configuration keys which name input files, as in path normalization
+const INPUT_FILE_SUFFIX: &str = "_input_file";
+const INPUT_FILE_MAP_SUFFIX: &str = "_input_files";
+/// suffix of other configuration keys which may name an input file, such as a model path
+const PATH_SUFFIX: &str = "_path";
+
+/// fields of a response which record when or how quickly it ran, and so differ between runs
+const TIMING_FIELDS: [&str; 3] = [
+    "search_executed_time",
+    "search_runtime",
+    "output_plugin_executed_time",
+];
+
+/// fields of the per-query metrics which differ between runs. whether a response came from
+/// the result cache depends on which of two identical queries finished first.
+const METRICS_TIMING_FIELDS: [&str; 4] = [
+    "search_runtime_ms",
+    "route_runtime_ms",
+    "output_plugin_runtime_ms",
+    "cached",
+];
+
+/// configuration of the `[reproducibility]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReproducibilityConfig {
+    /// seed of the random draws of queries which do not provide their own seed
+    #[serde(default)]
+    pub seed: u64,
+    /// file to write the manifest to. by default, `manifest.json` is written next to
+    /// the first response output file, or to the working directory.
+    #[serde(default)]
+    pub manifest_file: Option<String>,
+}
+
+/// runs queries in a deterministic mode, so that a run repeated with the same
+/// configuration, inputs and queries writes the same bytes:
+///   - queries which sample at random without a seed are given one, derived from the
+///     configured seed and the query id
+///   - responses are written in the order of their queries instead of as they complete,
+///     with the fields which record when or how quickly a query ran removed
+///   - a manifest records the configuration, the checksums of the input and output
+///     files, the version and the seeds, so that a run can be checked against it. it is
+///     written once when the app shuts down, covering every run of the app.
+pub struct Reproducibility {
+    config: ReproducibilityConfig,
+    /// the configuration after file paths were normalized
+    app_config: Value,
+    /// the SHA-256 checksum of each input file, by path
+    input_files: BTreeMap<String, String>,
+    /// the seeds given to queries, by query id
+    query_seeds: Mutex<BTreeMap<String, u64>>,
+    /// the files that runs of the app wrote their responses to
+    output_files: Mutex<BTreeSet<String>>,
+}
+
+impl Reproducibility {
+    /// reads the checksum of each input file named in the configuration
+    pub fn new(
+        config: &ReproducibilityConfig,
+        app_config: &Value,
+    ) -> Result<Reproducibility, CompassAppError> {
+        let mut input_files = BTreeMap::new();
+        for path in input_file_paths(app_config) {
+            if let Some(path) = path.as_str() {
+                input_files.insert(path.to_string(), file_checksum(Path::new(path))?);
+            }
+        }
+        log::info!(
+            "reproducibility: read checksums of {} input files",
+            input_files.len()
+        );
+        Ok(Reproducibility {
+            config: config.clone(),
+            app_config: app_config.clone(),
+            input_files,
+            query_seeds: Mutex::new(BTreeMap::new()),
+            output_files: Mutex::new(BTreeSet::new()),
+        })
+    }
+
+    /// gives a query which samples at random without a seed the seed for its query id
+    pub fn seed_query(&self, query: &mut Value) -> Result<(), CompassAppError> {
+        let query_id = query
+            .get(InputField::QueryId.to_str())
+            .map(id_key)
+            .unwrap_or_default();
+        let sample = match query.get_mut(InputField::Sample.to_str()) {
+            Some(Value::Object(sample)) if !sample.contains_key("seed") => sample,
+            _ => return Ok(()),
+        };
+        let seed = query_seed(self.config.seed, &query_id);
+        sample.insert(String::from("seed"), json!(seed));
+        self.lock_seeds()?.insert(query_id, seed);
+        Ok(())
+    }
+
+    /// records the files a run wrote its responses to, which are separated by commas,
+    /// so that the manifest holds their checksums
+    pub fn record_output_files(&self, output_files: &str) -> Result<(), CompassAppError> {
+        let mut recorded = self.output_files.lock().map_err(|e| {
+            CompassAppError::ReadOnlyPoisonError(format!(
+                "failed to acquire lock on output files: {}",
+                e
+            ))
+        })?;
+        recorded.extend(
+            output_files
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .map(String::from),
+        );
+        Ok(())
+    }
+
+    /// the position of each query in the order its responses are written, which is
+    /// the order of the queries, each followed by any failures of its input plugins.
+    /// the queries and failures are grouped by the query they were processed from.
+    pub fn response_order<'a>(
+        queries: impl Iterator<Item = (&'a [Value], &'a [Value])>,
+    ) -> HashMap<String, usize> {
+        let mut order = HashMap::new();
+        let keys = queries.flat_map(|(processed, failed)| {
+            let processed = processed
+                .iter()
+                .map(|q| q.get(InputField::QueryId.to_str()));
+            let failed = failed.iter().map(|r| request_query_id(r));
+            processed.chain(failed)
+        });
+        for (position, key) in keys.enumerate() {
+            if let Some(key) = key {
+                order.entry(id_key(key)).or_insert(position);
+            }
+        }
+        order
+    }
+
+    /// sorts responses into the order of their queries and removes their timing fields.
+    /// responses of queries which share an id are ordered by their contents.
+    pub fn order_responses(
+        &self,
+        mut responses: Vec<Value>,
+        order: &HashMap<String, usize>,
+    ) -> Vec<Value> {
+        let position = |response: &Value| {
+            request_query_id(response)
+                .and_then(|id| order.get(&id_key(id)))
+                .copied()
+                .unwrap_or(usize::MAX)
+        };
+        responses.iter_mut().for_each(remove_timing_fields);
+        responses.sort_by(|a, b| match position(a).cmp(&position(b)) {
+            Ordering::Equal => a.to_string().cmp(&b.to_string()),
+            other => other,
+        });
+        responses
+    }
+
+    /// writes the manifest of every run of the app, returning the path of the manifest
+    pub fn write_manifest(&self) -> Result<PathBuf, CompassAppError> {
+        let output_files = match self.output_files.lock() {
+            Ok(files) => files.clone(),
+            Err(e) => {
+                return Err(CompassAppError::ReadOnlyPoisonError(format!(
+                    "failed to acquire lock on output files: {}",
+                    e
+                )))
+            }
+        };
+        let manifest_file = match (&self.config.manifest_file, output_files.first()) {
+            (Some(file), _) => PathBuf::from(file),
+            (None, Some(output_file)) => Path::new(output_file).with_file_name("manifest.json"),
+            (None, None) => PathBuf::from("manifest.json"),
+        };
+        let output_checksums = output_files
+            .iter()
+            .map(|f| Ok((f.to_string(), file_checksum(Path::new(f))?)))
+            .collect::<Result<BTreeMap<_, _>, CompassAppError>>()?;
+        let query_seeds = self.lock_seeds()?.clone();
+        let manifest = json!({
+            "routee_compass_version": env!("CARGO_PKG_VERSION"),
+            "config": self.app_config,
+            "input_files": self.input_files,
+            "output_files": output_checksums,
+            "seed": self.config.seed,
+            "query_seeds": query_seeds,
+        });
+        std::fs::write(&manifest_file, serde_json::to_string_pretty(&manifest)?)?;
+        log::info!("reproducibility manifest written to {:?}", manifest_file);
+        Ok(manifest_file)
+    }
+
+    fn lock_seeds(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, BTreeMap<String, u64>>, CompassAppError> {
+        self.query_seeds.lock().map_err(|e| {
+            CompassAppError::ReadOnlyPoisonError(format!(
+                "failed to acquire lock on query seeds: {}",
+                e
+            ))
+        })
+    }
+}
+
+/// removes the fields of a response which record when or how quickly it ran
+pub fn remove_timing_fields(response: &mut Value) {
+    if let Value::Object(obj) = response {
+        for field in TIMING_FIELDS.iter() {
+            obj.remove(*field);
+        }
+        if let Some(Value::Object(metrics)) = obj.get_mut("metrics") {
+            for field in METRICS_TIMING_FIELDS.iter() {
+                metrics.remove(*field);
+            }
+        }
+    }
+}
+
+/// the query id of a response, or of the request of a response to a query that failed
+fn request_query_id(response: &Value) -> Option<&Value> {
+    let field = InputField::QueryId.to_str();
+    response
+        .get(field)
+        .or_else(|| response.get("request").and_then(|r| r.get(field)))
+}
+
+/// a query id as a map key, so that the id `1` and the id `"1"` do not collide
+fn id_key(query_id: &Value) -> String {
+    query_id.to_string()
+}
+
+/// the seed of a query, which depends only on the seed of the run and the query id
+fn query_seed(seed: u64, query_id: &str) -> u64 {
+    let digest = Sha256::new()
+        .chain_update(seed.to_le_bytes())
+        .chain_update(query_id.as_bytes())
+        .finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// the values of every key of the configuration which names an input file, including
+/// the values of tables which map names to input files. a key ending in `_path` names
+/// an input file when its value is the path of a file.
+fn input_file_paths(config: &Value) -> Vec<&Value> {
+    match config {
+        Value::Object(obj) => obj
+            .iter()
+            .flat_map(|(key, value)| {
+                let names_file = |v: &Value| v.as_str().is_some_and(|p| Path::new(p).is_file());
+                if key.ends_with(INPUT_FILE_SUFFIX) && value.is_string()
+                    || key.ends_with(PATH_SUFFIX) && names_file(value)
+                {
+                    vec![value]
+                } else if let (true, Value::Object(files)) =
+                    (key.ends_with(INPUT_FILE_MAP_SUFFIX), value)
+                {
+                    files.values().filter(|v| v.is_string()).collect()
+                } else {
+                    input_file_paths(value)
+                }
+            })
+            .collect(),
+        Value::Array(values) => values.iter().flat_map(input_file_paths).collect(),
+        _ => vec![],
+    }
+}
+
+/// the SHA-256 checksum of a file as a hex string
+fn file_checksum(path: &Path) -> Result<String, CompassAppError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    let digest = hasher.finalize();
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeds_and_response_order() {
+        let reproducibility = Reproducibility {
+            config: ReproducibilityConfig {
+                seed: 7,
+                manifest_file: None,
+            },
+            app_config: json!({}),
+            input_files: BTreeMap::new(),
+            query_seeds: Mutex::new(BTreeMap::new()),
+            output_files: Mutex::new(BTreeSet::new()),
+        };
+        let mut query = json!({ "query_id": 3, "sample": { "samples": 2 } });
+        reproducibility.seed_query(&mut query).unwrap();
+        let seed = query["sample"]["seed"].as_u64().unwrap();
+        assert_eq!(seed, query_seed(7, "3"));
+        // a seed provided with the query is kept
+        reproducibility.seed_query(&mut query).unwrap();
+        assert_eq!(query["sample"]["seed"], json!(seed));
+
+        let processed = [json!({ "query_id": "0-0" }), json!({ "query_id": "0-1" })];
+        let failed = [json!({ "request": { "query_id": 1 }, "error": {} })];
+        let order = Reproducibility::response_order(
+            [(&processed[..], &[][..]), (&[][..], &failed[..])].into_iter(),
+        );
+        let responses = vec![
+            json!({ "request": { "query_id": 1 }, "error": {} }),
+            json!({ "query_id": "0-1", "search_runtime": "00:00:01" }),
+            json!({ "query_id": "0-0", "metrics": { "cached": true, "expansions": 4 } }),
+        ];
+        let ordered = reproducibility.order_responses(responses, &order);
+        assert_eq!(
+            ordered,
+            vec![
+                json!({ "query_id": "0-0", "metrics": { "expansions": 4 } }),
+                json!({ "query_id": "0-1" }),
+                json!({ "request": { "query_id": 1 }, "error": {} }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_file_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let grade_table = dir.path().join("grades.csv");
+        std::fs::write(&grade_table, "grade,factor\n0.0,1.0\n").unwrap();
+        let config = json!({
+            "graph": { "edge_list_input_file": "edges.csv" },
+            "traversal": {
+                "grade_table_path": grade_table.to_string_lossy(),
+                "output_path": dir.path().to_string_lossy(),
+                "models": [{ "energy_input_files": { "a": "a.bin", "b": "b.bin" } }]
+            }
+        });
+        let mut paths = input_file_paths(&config)
+            .into_iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect::<Vec<_>>();
+        paths.sort();
+        let mut expected = vec![
+            String::from("a.bin"),
+            String::from("b.bin"),
+            String::from("edges.csv"),
+            grade_table.to_string_lossy().to_string(),
+        ];
+        expected.sort();
+        assert_eq!(paths, expected);
+    }
+}
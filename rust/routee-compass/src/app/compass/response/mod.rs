@@ -1,4 +1,6 @@
 pub mod csv;
+pub mod output_filename;
+pub mod parquet;
 pub mod response_output_format;
 pub mod response_output_format_json;
 pub mod response_output_policy;
@@ -0,0 +1,83 @@
+use crate::app::compass::{
+    compass_app_error::CompassAppError,
+    config::compass_configuration_error::CompassConfigurationError,
+};
+use chrono::{DateTime, Local};
+
+/// the values substituted into the tokens of an output filename, such as
+/// `results-{config_name}-{timestamp}.json`, so that runs do not overwrite
+/// each other's results.
+#[derive(Clone, Debug)]
+pub struct OutputFilenameContext {
+    /// the file stem of the configuration TOML file
+    pub config_name: String,
+    /// the time the output file was created
+    pub timestamp: DateTime<Local>,
+}
+
+impl OutputFilenameContext {
+    pub const TIMESTAMP_FORMAT: &'static str = "%Y%m%d-%H%M%S";
+
+    pub fn new(config_name: &str) -> OutputFilenameContext {
+        OutputFilenameContext {
+            config_name: config_name.to_string(),
+            timestamp: Local::now(),
+        }
+    }
+
+    /// replaces each `{token}` of a filename template with its value
+    pub fn render(&self, template: &str) -> Result<String, CompassAppError> {
+        let mut rendered = String::with_capacity(template.len());
+        let mut remaining = template;
+        while let Some(start) = remaining.find('{') {
+            rendered.push_str(&remaining[..start]);
+            let end = remaining[start..].find('}').ok_or_else(|| {
+                template_error(format!("unclosed '{{' in output filename '{}'", template))
+            })?;
+            let token = &remaining[start + 1..start + end];
+            match token {
+                "timestamp" => rendered.push_str(
+                    &self
+                        .timestamp
+                        .format(OutputFilenameContext::TIMESTAMP_FORMAT)
+                        .to_string(),
+                ),
+                "config_name" => rendered.push_str(&self.config_name),
+                other => {
+                    return Err(template_error(format!(
+                        "unknown token '{{{}}}' in output filename '{}', expected one of {{timestamp}}, {{config_name}}",
+                        other, template
+                    )))
+                }
+            }
+            remaining = &remaining[start + end + 1..];
+        }
+        rendered.push_str(remaining);
+        Ok(rendered)
+    }
+}
+
+fn template_error(msg: String) -> CompassAppError {
+    CompassAppError::CompassConfigurationError(CompassConfigurationError::UserConfigurationError(
+        msg,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render() {
+        let context = OutputFilenameContext {
+            config_name: String::from("denver"),
+            timestamp: Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap(),
+        };
+        let rendered = context
+            .render("runs/{config_name}/result-{timestamp}.json")
+            .unwrap();
+        assert_eq!(rendered, "runs/denver/result-20240309-140500.json");
+        assert!(context.render("result-{time}.json").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use crate::app::compass::compass_app_error::CompassAppError;
+use parquet::{
+    basic::{LogicalType, Repetition, Type as PhysicalType},
+    data_type::{BoolType, ByteArray, ByteArrayType, DoubleType},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::types::Type,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs::File, path::PathBuf, sync::Arc};
+
+/// the type of a Parquet column, either declared in the output format or inferred
+/// from the first row group of responses
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetColumnType {
+    Double,
+    Boolean,
+    Utf8,
+}
+
+impl ParquetColumnType {
+    /// a column of numbers or booleans keeps that type, anything else is stored as text.
+    /// columns with no values at all are stored as text.
+    pub fn infer<'a>(values: impl Iterator<Item = &'a serde_json::Value>) -> ParquetColumnType {
+        let mut inferred: Option<ParquetColumnType> = None;
+        for value in values {
+            let this_type = match value {
+                serde_json::Value::Null => continue,
+                serde_json::Value::Number(_) => ParquetColumnType::Double,
+                serde_json::Value::Bool(_) => ParquetColumnType::Boolean,
+                _ => return ParquetColumnType::Utf8,
+            };
+            match inferred {
+                Some(t) if t != this_type => return ParquetColumnType::Utf8,
+                _ => inferred = Some(this_type),
+            }
+        }
+        inferred.unwrap_or(ParquetColumnType::Utf8)
+    }
+
+    fn schema_field(&self, name: &str) -> Result<Type, CompassAppError> {
+        let builder = match self {
+            ParquetColumnType::Double => Type::primitive_type_builder(name, PhysicalType::DOUBLE),
+            ParquetColumnType::Boolean => Type::primitive_type_builder(name, PhysicalType::BOOLEAN),
+            ParquetColumnType::Utf8 => Type::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+                .with_logical_type(Some(LogicalType::String)),
+        };
+        let field = builder.with_repetition(Repetition::OPTIONAL).build()?;
+        Ok(field)
+    }
+}
+
+enum WriterState {
+    /// no row group has been written yet, so the schema is not known
+    Pending(File),
+    Open {
+        writer: Box<SerializedFileWriter<File>>,
+        types: Vec<ParquetColumnType>,
+    },
+    Closed,
+}
+
+/// writes mapped response rows to a Parquet file. rows are buffered and written
+/// as row groups. columns without a declared type have their type inferred from
+/// the first row group, and any later value which does not fit the type of its
+/// column is an error rather than being written as null.
+pub struct ParquetResponseWriter {
+    columns: Vec<String>,
+    column_types: HashMap<String, ParquetColumnType>,
+    row_group_size: usize,
+    rows: Vec<Vec<serde_json::Value>>,
+    state: WriterState,
+}
+
+impl ParquetResponseWriter {
+    pub const DEFAULT_ROW_GROUP_SIZE: usize = 10_000;
+
+    pub fn new(
+        path: &PathBuf,
+        columns: Vec<String>,
+        column_types: HashMap<String, ParquetColumnType>,
+        row_group_size: usize,
+    ) -> Result<ParquetResponseWriter, CompassAppError> {
+        if row_group_size == 0 {
+            return Err(CompassAppError::InvalidInput(String::from(
+                "parquet row_group_size must be positive",
+            )));
+        }
+        if let Some(unknown) = column_types.keys().find(|k| !columns.contains(k)) {
+            return Err(CompassAppError::InvalidInput(format!(
+                "parquet column_types has type for '{}' which is not a column of the mapping",
+                unknown
+            )));
+        }
+        let file = File::create(path)?;
+        Ok(ParquetResponseWriter {
+            columns,
+            column_types,
+            row_group_size,
+            rows: Vec::with_capacity(row_group_size),
+            state: WriterState::Pending(file),
+        })
+    }
+
+    /// buffers a row, with one value per column, writing a row group once enough rows are buffered
+    pub fn write_row(&mut self, row: Vec<serde_json::Value>) -> Result<(), CompassAppError> {
+        if row.len() != self.columns.len() {
+            return Err(CompassAppError::InternalError(format!(
+                "parquet row has {} values but the file has {} columns",
+                row.len(),
+                self.columns.len()
+            )));
+        }
+        self.rows.push(row);
+        if self.rows.len() >= self.row_group_size {
+            self.write_row_group()?;
+        }
+        Ok(())
+    }
+
+    /// writes any buffered rows and the file footer
+    pub fn close(&mut self) -> Result<(), CompassAppError> {
+        self.write_row_group()?;
+        if let WriterState::Pending(_) = self.state {
+            // nothing was written, but the file should still be a valid Parquet file
+            self.open_writer()?;
+        }
+        match std::mem::replace(&mut self.state, WriterState::Closed) {
+            WriterState::Open { writer, .. } => {
+                writer.close()?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// creates the file writer with the declared column types, inferring the type
+    /// of any other column from the buffered rows
+    fn open_writer(&mut self) -> Result<(), CompassAppError> {
+        let types = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(col, name)| match self.column_types.get(name) {
+                Some(t) => *t,
+                None => ParquetColumnType::infer(self.rows.iter().map(|row| &row[col])),
+            })
+            .collect::<Vec<_>>();
+        let fields = self
+            .columns
+            .iter()
+            .zip(types.iter())
+            .map(|(name, t)| t.schema_field(name).map(Arc::new))
+            .collect::<Result<Vec<_>, _>>()?;
+        let schema = Type::group_type_builder("response")
+            .with_fields(fields)
+            .build()?;
+        let file = match std::mem::replace(&mut self.state, WriterState::Closed) {
+            WriterState::Pending(file) => file,
+            _ => {
+                return Err(CompassAppError::InternalError(String::from(
+                    "parquet writer already opened",
+                )))
+            }
+        };
+        let writer = SerializedFileWriter::new(
+            file,
+            Arc::new(schema),
+            Arc::new(WriterProperties::builder().build()),
+        )?;
+        self.state = WriterState::Open {
+            writer: Box::new(writer),
+            types,
+        };
+        Ok(())
+    }
+
+    fn write_row_group(&mut self) -> Result<(), CompassAppError> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        if let WriterState::Pending(_) = self.state {
+            self.open_writer()?;
+        }
+        let (writer, types) = match &mut self.state {
+            WriterState::Open { writer, types } => (writer, types),
+            _ => {
+                return Err(CompassAppError::InternalError(String::from(
+                    "parquet writer is closed",
+                )))
+            }
+        };
+        let mut row_group = writer.next_row_group()?;
+        for (col, column_type) in types.iter().enumerate() {
+            let mut column = row_group.next_column()?.ok_or_else(|| {
+                CompassAppError::InternalError(format!("parquet column {} missing", col))
+            })?;
+            let name = &self.columns[col];
+            let values = self.rows.iter().map(|row| &row[col]);
+            match column_type {
+                ParquetColumnType::Double => {
+                    let (vals, defs) = levels(name, column_type, values, |v| match v {
+                        serde_json::Value::Number(n) => n.as_f64(),
+                        serde_json::Value::String(s) => s.parse::<f64>().ok(),
+                        _ => None,
+                    })?;
+                    column
+                        .typed::<DoubleType>()
+                        .write_batch(&vals, Some(&defs), None)?;
+                }
+                ParquetColumnType::Boolean => {
+                    let (vals, defs) = levels(name, column_type, values, |v| v.as_bool())?;
+                    column
+                        .typed::<BoolType>()
+                        .write_batch(&vals, Some(&defs), None)?;
+                }
+                ParquetColumnType::Utf8 => {
+                    let (vals, defs) = levels(name, column_type, values, |v| match v {
+                        serde_json::Value::String(s) => Some(ByteArray::from(s.as_str())),
+                        other => Some(ByteArray::from(other.to_string().as_str())),
+                    })?;
+                    column
+                        .typed::<ByteArrayType>()
+                        .write_batch(&vals, Some(&defs), None)?;
+                }
+            }
+            column.close()?;
+        }
+        row_group.close()?;
+        self.rows.clear();
+        Ok(())
+    }
+}
+
+/// splits optional column values into the non-null values and their definition levels.
+/// a non-null value which cannot be converted to the column type is an error.
+fn levels<'a, T>(
+    name: &str,
+    column_type: &ParquetColumnType,
+    values: impl Iterator<Item = &'a serde_json::Value>,
+    op: impl Fn(&serde_json::Value) -> Option<T>,
+) -> Result<(Vec<T>, Vec<i16>), CompassAppError> {
+    let mut vals = vec![];
+    let mut defs = vec![];
+    for value in values {
+        if value.is_null() {
+            defs.push(0);
+            continue;
+        }
+        let v = op(value).ok_or_else(|| {
+            CompassAppError::InvalidInput(format!(
+                "parquet column '{}' has type {:?} but found value {}. declare the type of this column in column_types",
+                name, column_type, value
+            ))
+        })?;
+        vals.push(v);
+        defs.push(1);
+    }
+    Ok((vals, defs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use serde_json::json;
+
+    #[test]
+    fn test_write_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("responses.parquet");
+        let columns = vec![String::from("cost"), String::from("uuid")];
+        let mut writer = ParquetResponseWriter::new(&path, columns, HashMap::new(), 2).unwrap();
+        writer.write_row(vec![json![1.5], json!["a"]]).unwrap();
+        writer.write_row(vec![json![2.5], json![null]]).unwrap();
+        writer.write_row(vec![json![null], json!["c"]]).unwrap();
+        writer.close().unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+        assert_eq!(metadata.num_row_groups(), 2);
+        let schema = metadata.file_metadata().schema_descr();
+        assert_eq!(schema.column(0).physical_type(), PhysicalType::DOUBLE);
+        assert_eq!(schema.column(1).physical_type(), PhysicalType::BYTE_ARRAY);
+    }
+
+    #[test]
+    fn test_later_row_group_type_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("responses.parquet");
+        let columns = vec![String::from("cost")];
+        let mut writer = ParquetResponseWriter::new(&path, columns, HashMap::new(), 1).unwrap();
+        writer.write_row(vec![json![1.5]]).unwrap();
+        let result = writer.write_row(vec![json!["not a number"]]);
+        assert!(
+            result.is_err(),
+            "text in an inferred double column should fail"
+        );
+    }
+
+    #[test]
+    fn test_declared_column_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("responses.parquet");
+        let columns = vec![String::from("cost")];
+        let types = HashMap::from([(String::from("cost"), ParquetColumnType::Utf8)]);
+        let mut writer = ParquetResponseWriter::new(&path, columns, types, 1).unwrap();
+        writer.write_row(vec![json![1.5]]).unwrap();
+        writer.write_row(vec![json!["not a number"]]).unwrap();
+        writer.close().unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 2);
+        let schema = metadata.file_metadata().schema_descr();
+        assert_eq!(schema.column(0).physical_type(), PhysicalType::BYTE_ARRAY);
+    }
+}
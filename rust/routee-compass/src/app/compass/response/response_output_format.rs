@@ -1,5 +1,9 @@
-use super::{csv::csv_mapping::CsvMapping, response_output_format_json as json_ops};
+use super::{
+    csv::csv_mapping::CsvMapping, parquet::parquet_response_writer::ParquetColumnType,
+    response_output_format_json as json_ops,
+};
 use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::compass::error_taxonomy::{ErrorCode, ErrorComponent, ErrorDetail};
 use itertools::Itertools;
 use ordered_hash_map::OrderedHashMap;
 use serde::{Deserialize, Serialize};
@@ -19,6 +23,17 @@ pub enum ResponseOutputFormat {
         mapping: OrderedHashMap<String, CsvMapping>,
         sorted: bool,
     },
+    /// writes outputs to an Apache Parquet file using the same mapping and column
+    /// order as CSV. rows are buffered and written in groups of "row_group_size"
+    /// rows. the type of a column may be declared in "column_types", otherwise it is
+    /// inferred from the first row group.
+    Parquet {
+        mapping: OrderedHashMap<String, CsvMapping>,
+        sorted: bool,
+        row_group_size: Option<usize>,
+        #[serde(default)]
+        column_types: HashMap<String, ParquetColumnType>,
+    },
 }
 
 impl ResponseOutputFormat {
@@ -35,6 +50,7 @@ impl ResponseOutputFormat {
                 };
                 Some(format!("{}\n", header))
             }
+            ResponseOutputFormat::Parquet { .. } => None,
         }
     }
 
@@ -47,6 +63,7 @@ impl ResponseOutputFormat {
                 mapping: _,
                 sorted: _,
             } => None,
+            ResponseOutputFormat::Parquet { .. } => None,
         }
     }
 
@@ -59,38 +76,47 @@ impl ResponseOutputFormat {
                 json_ops::format_response(response, *newline_delimited)
             }
             ResponseOutputFormat::Csv { mapping, sorted } => {
-                let mut errors: HashMap<String, String> = HashMap::new();
-                let row = if *sorted {
-                    mapping
-                        .iter()
-                        .sorted_by_key(|(k, _)| *k)
-                        .map(|(k, v)| match v.apply_mapping(response) {
-                            Ok(cell) => cell.to_string(),
-                            Err(msg) => {
-                                errors.insert(k.clone(), msg);
-                                String::from("")
-                            }
-                        })
-                        .join(",")
-                } else {
-                    mapping
-                        .iter()
-                        .rev()
-                        .map(|(k, v)| match v.apply_mapping(response) {
-                            Ok(cell) => cell.to_string(),
-                            Err(msg) => {
-                                errors.insert(k.clone(), msg);
-                                String::from("")
-                            }
-                        })
-                        .join(",")
-                };
-
-                if !errors.is_empty() {
-                    response["error"] = json![{"csv": json![errors]}];
-                }
+                let row = mapped_row(mapping, *sorted, response)
+                    .iter()
+                    .map(|cell| cell.as_ref().map(|c| c.to_string()).unwrap_or_default())
+                    .join(",");
                 Ok(row)
             }
+            ResponseOutputFormat::Parquet { .. } => Err(CompassAppError::InternalError(
+                String::from("parquet responses are written by row, not as text"),
+            )),
+        }
+    }
+
+    /// the column names of a tabular format, in the order they are written
+    pub fn columns(&self) -> Option<Vec<String>> {
+        match self {
+            ResponseOutputFormat::Json { .. } => None,
+            ResponseOutputFormat::Csv { mapping, sorted }
+            | ResponseOutputFormat::Parquet {
+                mapping, sorted, ..
+            } => Some(
+                ordered_columns(mapping, *sorted)
+                    .map(|(k, _)| k.clone())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// the values of a tabular format for a response, in column order
+    pub fn format_row(&self, response: &mut serde_json::Value) -> Option<Vec<serde_json::Value>> {
+        match self {
+            ResponseOutputFormat::Json { .. } => None,
+            ResponseOutputFormat::Csv { mapping, sorted }
+            | ResponseOutputFormat::Parquet {
+                mapping, sorted, ..
+            } => {
+                let row = mapped_row(mapping, *sorted, response)
+                    .into_iter()
+                    .map(|cell| cell.unwrap_or(serde_json::Value::Null))
+                    .collect();
+                Some(row)
+            }
         }
     }
 
@@ -102,7 +128,55 @@ impl ResponseOutputFormat {
             ResponseOutputFormat::Csv {
                 mapping: _,
                 sorted: _,
-            } => Some(String::from("\n")),
+            } => None,
+            ResponseOutputFormat::Parquet { .. } => None,
         }
     }
 }
+
+/// the mapping entries in column order. unless sorted, columns are written in
+/// the reverse of the iteration order of the mapping, which matches the order
+/// of the keys in the configuration file.
+fn ordered_columns(
+    mapping: &OrderedHashMap<String, CsvMapping>,
+    sorted: bool,
+) -> Box<dyn Iterator<Item = (&String, &CsvMapping)> + '_> {
+    if sorted {
+        Box::new(mapping.iter().sorted_by_key(|(k, _)| *k))
+    } else {
+        Box::new(mapping.iter().rev())
+    }
+}
+
+/// applies a mapping to a response, producing one value per column. any mapping
+/// failures produce no value and are reported in the "error" field of the response.
+fn mapped_row(
+    mapping: &OrderedHashMap<String, CsvMapping>,
+    sorted: bool,
+    response: &mut serde_json::Value,
+) -> Vec<Option<serde_json::Value>> {
+    let mut errors: HashMap<String, String> = HashMap::new();
+    let row = ordered_columns(mapping, sorted)
+        .map(|(k, v)| match v.apply_mapping(response) {
+            Ok(cell) => Some(cell),
+            Err(msg) => {
+                errors.insert(k.clone(), msg);
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        let msg = format!(
+            "failed to map columns {}",
+            errors.keys().sorted().join(", ")
+        );
+        let detail = ErrorDetail::new(
+            ErrorCode::OutputFormatError,
+            ErrorComponent::ResponseOutput,
+            msg,
+        );
+        response["error"] = detail.to_json();
+        response["error"]["csv"] = json![errors];
+    }
+    row
+}
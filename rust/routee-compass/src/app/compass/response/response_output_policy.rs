@@ -1,4 +1,6 @@
 use super::{
+    output_filename::OutputFilenameContext,
+    parquet::parquet_response_writer::ParquetResponseWriter,
     response_output_format::ResponseOutputFormat, response_sink::ResponseSink,
     write_mode::WriteMode,
 };
@@ -14,10 +16,14 @@ use std::{
 pub enum ResponseOutputPolicy {
     None,
     File {
+        /// path of the output file, which may contain the tokens `{timestamp}` and
+        /// `{config_name}`
         filename: String,
         format: ResponseOutputFormat,
         file_flush_rate: Option<i64>,
-        // write_mode: WriteMode,
+        /// what to do when the file already exists. appends by default.
+        #[serde(default)]
+        write_mode: Option<WriteMode>,
     },
     Combined {
         policies: Vec<Box<ResponseOutputPolicy>>,
@@ -28,17 +34,70 @@ impl ResponseOutputPolicy {
     /// creates an instance of a writer which writes responses to some destination.
     /// the act of building this writer may include writing initial content to some sink,
     /// such as a file header.
-    pub fn build(&self) -> Result<ResponseSink, CompassAppError> {
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - values for the tokens of templated filenames
+    pub fn build(&self, context: &OutputFilenameContext) -> Result<ResponseSink, CompassAppError> {
         match self {
             ResponseOutputPolicy::None => Ok(ResponseSink::None),
             ResponseOutputPolicy::File {
                 filename,
                 format,
                 file_flush_rate,
-                // write_mode,
+                write_mode,
             } => {
+                let filename = &context.render(filename)?;
                 let output_file_path = PathBuf::from(filename);
-                let file = WriteMode::Append.open_file(&output_file_path, format)?;
+                if let Some(parent) = output_file_path.parent() {
+                    if !parent.as_os_str().is_empty() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                }
+
+                // parquet files cannot be appended to, so they are overwritten unless the
+                // write mode refuses to touch an existing file
+                if let ResponseOutputFormat::Parquet {
+                    row_group_size,
+                    column_types,
+                    ..
+                } = format
+                {
+                    match write_mode {
+                        Some(WriteMode::Append) => {
+                            return Err(CompassAppError::InvalidInput(format!(
+                                "parquet output file {} cannot be appended to, use a write_mode of 'overwrite' or 'error'",
+                                filename
+                            )))
+                        }
+                        Some(WriteMode::Error) if output_file_path.exists() => {
+                            return Err(CompassAppError::InvalidInput(format!(
+                                "file exists but write mode is 'error' {}",
+                                filename
+                            )))
+                        }
+                        _ => {}
+                    }
+                    let columns = format.columns().unwrap_or_default();
+                    let row_group_size =
+                        row_group_size.unwrap_or(ParquetResponseWriter::DEFAULT_ROW_GROUP_SIZE);
+                    let writer = ParquetResponseWriter::new(
+                        &output_file_path,
+                        columns,
+                        column_types.clone(),
+                        row_group_size,
+                    )?;
+                    return Ok(ResponseSink::Parquet {
+                        filename: filename.clone(),
+                        format: format.clone(),
+                        writer: Arc::new(Mutex::new(writer)),
+                    });
+                }
+
+                let (file, has_responses) = write_mode
+                    .as_ref()
+                    .unwrap_or(&WriteMode::Append)
+                    .open_file(&output_file_path, format)?;
 
                 // wrap the file in a mutex so we can share it between threads
                 let file_shareable = Arc::new(Mutex::new(file));
@@ -61,15 +120,110 @@ impl ResponseOutputPolicy {
                     delimiter: format.delimiter(),
                     iterations_per_flush,
                     iterations,
+                    has_responses,
                 })
             }
             ResponseOutputPolicy::Combined { policies } => {
                 let policies = policies
                     .iter()
-                    .map(|p| p.build().map(Box::new))
+                    .map(|p| p.build(context).map(Box::new))
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(ResponseSink::Combined(policies))
             }
         }
     }
+
+    /// replaces the filename of the file written by this policy, such as from the
+    /// `--output` command line argument. when no file is written, responses are
+    /// written to a JSON file, or to newline-delimited JSON for a `.jsonl` or
+    /// `.ndjson` filename.
+    pub fn with_filename(&self, filename: &str) -> Result<ResponseOutputPolicy, CompassAppError> {
+        match self {
+            ResponseOutputPolicy::None => {
+                let newline_delimited =
+                    filename.ends_with(".jsonl") || filename.ends_with(".ndjson");
+                Ok(ResponseOutputPolicy::File {
+                    filename: filename.to_string(),
+                    format: ResponseOutputFormat::Json { newline_delimited },
+                    file_flush_rate: None,
+                    write_mode: None,
+                })
+            }
+            ResponseOutputPolicy::File {
+                format,
+                file_flush_rate,
+                write_mode,
+                ..
+            } => Ok(ResponseOutputPolicy::File {
+                filename: filename.to_string(),
+                format: format.clone(),
+                file_flush_rate: *file_flush_rate,
+                write_mode: write_mode.clone(),
+            }),
+            ResponseOutputPolicy::Combined { policies } => {
+                let n_files = policies
+                    .iter()
+                    .filter(|p| !matches!(p.as_ref(), ResponseOutputPolicy::None))
+                    .count();
+                if n_files > 1 {
+                    return Err(CompassAppError::InvalidInput(format!(
+                        "cannot set output filename '{}' on a combined policy which writes {} files",
+                        filename, n_files
+                    )));
+                }
+                match policies
+                    .iter()
+                    .find(|p| !matches!(p.as_ref(), ResponseOutputPolicy::None))
+                {
+                    Some(policy) => policy.with_filename(filename),
+                    None => ResponseOutputPolicy::None.with_filename(filename),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parquet_policy(filename: &str, write_mode: Option<WriteMode>) -> ResponseOutputPolicy {
+        let format = serde_json::from_value(json!({
+            "type": "parquet",
+            "mapping": {"id": "request.id"},
+            "sorted": false
+        }))
+        .unwrap();
+        ResponseOutputPolicy::File {
+            filename: filename.to_string(),
+            format,
+            file_flush_rate: None,
+            write_mode,
+        }
+    }
+
+    #[test]
+    fn test_parquet_write_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+        let filename = path.to_string_lossy().to_string();
+        let context = OutputFilenameContext::new("test");
+
+        let append = parquet_policy(&filename, Some(WriteMode::Append));
+        assert!(append.build(&context).is_err());
+
+        parquet_policy(&filename, None)
+            .build(&context)
+            .unwrap()
+            .close()
+            .unwrap();
+        assert!(path.exists());
+
+        let error = parquet_policy(&filename, Some(WriteMode::Error));
+        assert!(error.build(&context).is_err());
+
+        let overwrite = parquet_policy(&filename, Some(WriteMode::Overwrite));
+        overwrite.build(&context).unwrap().close().unwrap();
+    }
 }
@@ -1,3 +1,4 @@
+use super::parquet::parquet_response_writer::ParquetResponseWriter;
 use super::response_output_format::ResponseOutputFormat;
 use crate::app::compass::compass_app_error::CompassAppError;
 use std::io::prelude::*;
@@ -15,6 +16,13 @@ pub enum ResponseSink {
         delimiter: Option<String>,
         iterations_per_flush: u64,
         iterations: Arc<Mutex<u64>>,
+        /// true when responses were already in the file before it was opened
+        has_responses: bool,
+    },
+    Parquet {
+        filename: String,
+        format: ResponseOutputFormat,
+        writer: Arc<Mutex<ParquetResponseWriter>>,
     },
     Combined(Vec<Box<ResponseSink>>),
 }
@@ -28,9 +36,10 @@ impl ResponseSink {
                 filename: _,
                 file,
                 format,
-                delimiter: _,
+                delimiter,
                 iterations_per_flush,
                 iterations,
+                has_responses,
             } => {
                 let file_ref = Arc::clone(file);
                 let mut file_attained = file_ref.lock().map_err(|e| {
@@ -48,6 +57,11 @@ impl ResponseSink {
                 })?;
 
                 let output_row = format.format_response(response)?;
+                if *it_attained > 0 || *has_responses {
+                    if let Some(delimiter) = delimiter {
+                        write!(file_attained, "{}", delimiter).map_err(CompassAppError::IOError)?;
+                    }
+                }
                 writeln!(file_attained, "{}", output_row).map_err(CompassAppError::IOError)?;
                 *it_attained += 1;
                 if *it_attained % iterations_per_flush == 0 {
@@ -56,6 +70,24 @@ impl ResponseSink {
 
                 Ok(())
             }
+            ResponseSink::Parquet {
+                filename: _,
+                format,
+                writer,
+            } => {
+                let row = format.format_row(response).ok_or_else(|| {
+                    CompassAppError::InternalError(String::from(
+                        "parquet output requires a tabular response format",
+                    ))
+                })?;
+                let mut writer_attained = writer.lock().map_err(|e| {
+                    CompassAppError::ReadOnlyPoisonError(format!(
+                        "Could not aquire lock on parquet writer: {}",
+                        e
+                    ))
+                })?;
+                writer_attained.write_row(row)
+            }
             ResponseSink::Combined(policies) => {
                 for policy in policies {
                     policy.write_response(response)?;
@@ -65,6 +97,30 @@ impl ResponseSink {
         }
     }
 
+    /// flushes any responses written to a file but not yet flushed. parquet responses
+    /// are only written once a full row group has been buffered.
+    pub fn flush(&self) -> Result<(), CompassAppError> {
+        match self {
+            ResponseSink::None => Ok(()),
+            ResponseSink::File { file, .. } => {
+                let mut file_attained = file.lock().map_err(|e| {
+                    CompassAppError::ReadOnlyPoisonError(format!(
+                        "Could not aquire lock on output file: {}",
+                        e
+                    ))
+                })?;
+                file_attained.flush().map_err(CompassAppError::IOError)
+            }
+            ResponseSink::Parquet { .. } => Ok(()),
+            ResponseSink::Combined(policies) => {
+                for policy in policies {
+                    policy.flush()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     pub fn close(&self) -> Result<String, CompassAppError> {
         match self {
             ResponseSink::None => Ok(String::from("")),
@@ -75,6 +131,7 @@ impl ResponseSink {
                 delimiter: _,
                 iterations_per_flush: _,
                 iterations: _,
+                has_responses: _,
             } => {
                 let file_ref = Arc::clone(file);
                 let mut file_attained = file_ref.lock().map_err(|e| {
@@ -84,13 +141,28 @@ impl ResponseSink {
                     ))
                 })?;
 
-                let final_contents = format
-                    .final_file_contents()
-                    .unwrap_or_else(|| String::from(""));
-                writeln!(file_attained, "{}", final_contents).map_err(CompassAppError::IOError)?;
+                if let Some(final_contents) = format.final_file_contents() {
+                    writeln!(file_attained, "{}", final_contents)
+                        .map_err(CompassAppError::IOError)?;
+                }
+                file_attained.flush().map_err(CompassAppError::IOError)?;
 
                 Ok(filename.clone())
             }
+            ResponseSink::Parquet {
+                filename,
+                format: _,
+                writer,
+            } => {
+                let mut writer_attained = writer.lock().map_err(|e| {
+                    CompassAppError::ReadOnlyPoisonError(format!(
+                        "Could not aquire lock on parquet writer: {}",
+                        e
+                    ))
+                })?;
+                writer_attained.close()?;
+                Ok(filename.clone())
+            }
             ResponseSink::Combined(policies) => {
                 let mut out_strs = vec![];
                 for policy in policies {
@@ -105,3 +177,51 @@ impl ResponseSink {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::compass::response::{
+        output_filename::OutputFilenameContext, response_output_policy::ResponseOutputPolicy,
+    };
+    use serde_json::json;
+
+    fn write_and_read(path: &std::path::Path, newline_delimited: bool) -> String {
+        let policy = ResponseOutputPolicy::File {
+            filename: path.to_string_lossy().to_string(),
+            format: ResponseOutputFormat::Json { newline_delimited },
+            file_flush_rate: None,
+            write_mode: None,
+        };
+        let sink = policy.build(&OutputFilenameContext::new("test")).unwrap();
+        sink.write_response(&mut json!({"id": 0})).unwrap();
+        sink.write_response(&mut json!({"id": 1})).unwrap();
+        sink.close().unwrap();
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_streamed_json_is_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let array = write_and_read(&dir.path().join("array.json"), false);
+        let parsed: serde_json::Value = serde_json::from_str(&array).unwrap();
+        assert_eq!(parsed, json!([{"id": 0}, {"id": 1}]));
+
+        let ndjson = write_and_read(&dir.path().join("lines.json"), true);
+        let rows = ndjson
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![json!({"id": 0}), json!({"id": 1})]);
+    }
+
+    #[test]
+    fn test_appended_json_is_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("array.json");
+        write_and_read(&path, false);
+        let array = write_and_read(&path, false);
+        let parsed: serde_json::Value = serde_json::from_str(&array).unwrap();
+        assert_eq!(parsed, json!([{"id": 0}, {"id": 1}, {"id": 0}, {"id": 1}]));
+    }
+}
@@ -18,21 +18,26 @@ pub enum WriteMode {
 }
 
 impl WriteMode {
+    /// opens the output file for writing responses. along with the file, returns
+    /// true when an appended file already holds responses, which must be followed
+    /// by a delimiter before the next response is written.
     pub fn open_file(
         &self,
         path: &Path,
         format: &ResponseOutputFormat,
-    ) -> Result<File, CompassAppError> {
+    ) -> Result<(File, bool), CompassAppError> {
         match self {
             WriteMode::Append => {
                 if !path.exists() {
-                    write_header(path, format)?
+                    write_header(path, format)?;
+                    return Ok((open_append(path)?, false));
                 }
-                open_append(path)
+                let has_responses = reopen_closed_file(path, format)?;
+                Ok((open_append(path)?, has_responses))
             }
             WriteMode::Overwrite => {
                 write_header(path, format)?;
-                open_append(path)
+                Ok((open_append(path)?, false))
             }
             WriteMode::Error => {
                 if path.exists() {
@@ -44,12 +49,50 @@ impl WriteMode {
                     ))?
                 }
                 write_header(path, format)?;
-                open_append(path)
+                Ok((open_append(path)?, false))
             }
         }
     }
 }
 
+/// removes the final contents written when an existing file was closed, such as the
+/// closing bracket of a JSON array, so that responses can be appended to it. returns
+/// true if the file already holds responses.
+fn reopen_closed_file(path: &Path, format: &ResponseOutputFormat) -> Result<bool, CompassAppError> {
+    let final_contents = match format.final_file_contents() {
+        Some(contents) => contents,
+        None => return Ok(false),
+    };
+    let contents = std::fs::read_to_string(path).map_err(CompassAppError::IOError)?;
+    let trimmed = contents.trim_end();
+    let body = trimmed
+        .strip_suffix(final_contents.trim())
+        .unwrap_or(trimmed)
+        .trim_end();
+    let header = format.initial_file_contents().unwrap_or_default();
+    if body.is_empty() {
+        // an empty file gets the header it would have been created with
+        write_header(path, format)?;
+        return Ok(false);
+    }
+    if !body.starts_with(header.trim()) {
+        return Err(CompassAppError::CompassConfigurationError(
+            CompassConfigurationError::UserConfigurationError(format!(
+                "cannot append to file {} which was not written in the configured output format",
+                path.to_str().unwrap_or_default()
+            )),
+        ));
+    }
+    let has_responses = body != header.trim();
+    let file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(CompassAppError::IOError)?;
+    file.set_len(body.len() as u64)
+        .map_err(CompassAppError::IOError)?;
+    Ok(has_responses)
+}
+
 fn write_header(path: &Path, format: &ResponseOutputFormat) -> Result<(), CompassAppError> {
     let header = format
         .initial_file_contents()
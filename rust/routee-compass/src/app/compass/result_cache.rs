@@ -0,0 +1,258 @@
+use super::compass_app_error::CompassAppError;
+use super::query_id::with_query_id_fields;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// configuration of the `[result_cache]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultCacheConfig {
+    /// the most responses to keep, after which the least recently used is dropped
+    pub max_entries: usize,
+    /// query fields which do not change the result, such as an id, and are left
+    /// out of the cache key
+    #[serde(default)]
+    pub ignored_fields: Vec<String>,
+}
+
+/// a least recently used cache of responses, keyed on a hash of the query after
+/// input processing. queries with the same fields and values in any key order share
+/// a key, so a repeated origin and destination skips its search. each entry keeps
+/// the query it answers, so that queries whose hashes collide are not confused.
+/// only successful responses are cached.
+///
+/// each clear of the cache starts a new generation. a response found before a
+/// clear, such as with speeds that have since been updated, is not stored.
+pub struct ResultCache {
+    cache: Mutex<CacheEntries>,
+    ignored_fields: Vec<String>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// cached responses keyed on the hash of their query, along with the generation
+/// of the cache, which changes every time the cache is cleared
+struct CacheEntries {
+    entries: LruCache<u64, CacheEntry>,
+    generation: u64,
+}
+
+/// a cached response and the query it answers, without ignored fields
+struct CacheEntry {
+    query: Value,
+    response: Value,
+}
+
+impl ResultCache {
+    pub fn new(config: &ResultCacheConfig) -> Result<ResultCache, CompassAppError> {
+        let capacity = NonZeroUsize::new(config.max_entries).ok_or_else(|| {
+            CompassAppError::InvalidInput(String::from("result_cache.max_entries must be positive"))
+        })?;
+        Ok(ResultCache {
+            cache: Mutex::new(CacheEntries {
+                entries: LruCache::new(capacity),
+                generation: 0,
+            }),
+            ignored_fields: with_query_id_fields(&config.ignored_fields),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        })
+    }
+
+    /// looks up the response to a query. on a hit, the request of the cached
+    /// response is replaced with this query, so that ignored fields are echoed back.
+    pub fn get(&self, query: &Value) -> Result<Option<Value>, CompassAppError> {
+        let canonical = self.canonical_query(query);
+        let key = hash_key(&canonical);
+        let cached = self
+            .lock()?
+            .entries
+            .get(&key)
+            .filter(|entry| entry.query == canonical)
+            .map(|entry| entry.response.clone());
+        match cached {
+            Some(mut response) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                response["request"] = query.clone();
+                Ok(Some(response))
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+        }
+    }
+
+    /// the current generation of the cache. read it before running a query and
+    /// pass it to [`ResultCache::insert`], so that a response found before the
+    /// cache was cleared is not stored.
+    pub fn generation(&self) -> Result<u64, CompassAppError> {
+        Ok(self.lock()?.generation)
+    }
+
+    /// stores the response to a query, unless it is an error or the cache was
+    /// cleared since the given generation
+    pub fn insert(
+        &self,
+        query: &Value,
+        response: &Value,
+        generation: u64,
+    ) -> Result<(), CompassAppError> {
+        if response.get("error").is_some() {
+            return Ok(());
+        }
+        let canonical = self.canonical_query(query);
+        let key = hash_key(&canonical);
+        let mut cache = self.lock()?;
+        if cache.generation != generation {
+            return Ok(());
+        }
+        let entry = CacheEntry {
+            query: canonical,
+            response: response.clone(),
+        };
+        cache.entries.put(key, entry);
+        Ok(())
+    }
+
+    /// drops every cached response, such as after the network changes
+    pub fn clear(&self) -> Result<(), CompassAppError> {
+        let mut cache = self.lock()?;
+        cache.entries.clear();
+        cache.generation += 1;
+        Ok(())
+    }
+
+    /// the number of queries answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// the number of queries not found in the cache
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, CacheEntries>, CompassAppError> {
+        self.cache
+            .lock()
+            .map_err(|e| CompassAppError::InternalError(format!("result cache poisoned: {}", e)))
+    }
+
+    /// the query without its ignored fields, which is what the cache matches on
+    fn canonical_query(&self, query: &Value) -> Value {
+        match query {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(k, _)| !self.ignored_fields.contains(k))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+fn hash_key(canonical_query: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_json(canonical_query, &mut hasher);
+    hasher.finish()
+}
+
+/// hashes a JSON value with the keys of each object in sorted order
+pub(crate) fn hash_json<H: Hasher>(value: &Value, hasher: &mut H) {
+    match value {
+        Value::Null => 0u8.hash(hasher),
+        Value::Bool(b) => {
+            1u8.hash(hasher);
+            b.hash(hasher);
+        }
+        Value::Number(n) => {
+            2u8.hash(hasher);
+            n.to_string().hash(hasher);
+        }
+        Value::String(s) => {
+            3u8.hash(hasher);
+            s.hash(hasher);
+        }
+        Value::Array(values) => {
+            4u8.hash(hasher);
+            values.len().hash(hasher);
+            for v in values {
+                hash_json(v, hasher);
+            }
+        }
+        Value::Object(map) => {
+            5u8.hash(hasher);
+            map.len().hash(hasher);
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(k, _)| *k);
+            for (k, v) in entries {
+                k.hash(hasher);
+                hash_json(v, hasher);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_result_cache() {
+        let cache = ResultCache::new(&ResultCacheConfig {
+            max_entries: 1,
+            ignored_fields: vec![String::from("query_id")],
+        })
+        .unwrap();
+        let query = json!({ "origin_vertex": 0, "destination_vertex": 2, "query_id": 1 });
+        let response = json!({ "request": query, "route": { "path": [0, 2] } });
+        assert_eq!(cache.get(&query).unwrap(), None);
+        cache.insert(&query, &response, 0).unwrap();
+
+        // key order and ignored fields do not change the key
+        let repeat = json!({ "query_id": 2, "destination_vertex": 2, "origin_vertex": 0 });
+        let hit = cache.get(&repeat).unwrap().unwrap();
+        assert_eq!(hit["request"], repeat);
+        assert_eq!(hit["route"], response["route"]);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        // errors are not cached, and the least recently used entry is dropped
+        let other = json!({ "origin_vertex": 1, "destination_vertex": 2 });
+        cache
+            .insert(&other, &json!({ "request": other, "error": "no path" }), 0)
+            .unwrap();
+        assert!(cache.get(&query).unwrap().is_some());
+        cache
+            .insert(&other, &json!({ "request": other }), 0)
+            .unwrap();
+        assert!(cache.get(&query).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_insert_after_clear() {
+        let cache = ResultCache::new(&ResultCacheConfig {
+            max_entries: 2,
+            ignored_fields: vec![],
+        })
+        .unwrap();
+        let query = json!({ "origin_vertex": 0, "destination_vertex": 2 });
+        let response = json!({ "request": query });
+        // a query which started before the cache was cleared is not stored
+        let generation = cache.generation().unwrap();
+        cache.clear().unwrap();
+        cache.insert(&query, &response, generation).unwrap();
+        assert!(cache.get(&query).unwrap().is_none());
+        cache
+            .insert(&query, &response, cache.generation().unwrap())
+            .unwrap();
+        assert!(cache.get(&query).unwrap().is_some());
+    }
+}
@@ -0,0 +1,155 @@
+use super::compass_app_error::CompassAppError;
+use kdam::{Bar, BarExt};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// the progress of one stage of a batch run, passed to a [`ProgressCallback`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressUpdate {
+    /// the stage of the run, either "input plugins" or "search"
+    pub stage: String,
+    pub completed: usize,
+    pub total: usize,
+    pub errors: usize,
+}
+
+/// a function called with the progress of a run every `interval` completed queries
+/// and once each stage completes, such as to drive a progress bar in a notebook.
+/// it is called from the threads running the queries.
+#[derive(Clone)]
+pub struct ProgressCallback {
+    pub interval: usize,
+    pub callback: Arc<dyn Fn(&ProgressUpdate) + Send + Sync>,
+}
+
+impl ProgressCallback {
+    pub fn new(
+        interval: usize,
+        callback: impl Fn(&ProgressUpdate) + Send + Sync + 'static,
+    ) -> ProgressCallback {
+        ProgressCallback {
+            interval: interval.max(1),
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+/// a progress bar for one stage of a batch run, shared across threads. shows the
+/// estimated time remaining, the throughput in queries per second and the number
+/// of failed queries so far. when disabled, nothing is drawn, which suits
+/// non-interactive pipelines that capture the output of a run.
+pub struct RunProgress {
+    bar: Mutex<Bar>,
+    errors: AtomicUsize,
+    stage: String,
+    total: usize,
+    completed: AtomicUsize,
+    callback: Option<ProgressCallback>,
+}
+
+impl RunProgress {
+    pub fn new(total: usize, desc: &str, enabled: bool) -> Result<RunProgress, CompassAppError> {
+        let bar = Bar::builder()
+            .total(total)
+            .animation("fillup")
+            .desc(desc)
+            .unit(" queries")
+            .postfix("errors=0")
+            .disable(!enabled)
+            .build()
+            .map_err(CompassAppError::UXError)?;
+        Ok(RunProgress {
+            bar: Mutex::new(bar),
+            errors: AtomicUsize::new(0),
+            stage: desc.to_string(),
+            total,
+            completed: AtomicUsize::new(0),
+            callback: None,
+        })
+    }
+
+    /// also reports the progress of this stage to a callback
+    pub fn with_callback(mut self, callback: Option<&ProgressCallback>) -> RunProgress {
+        self.callback = callback.cloned();
+        self
+    }
+
+    /// advances the bar by a number of completed queries, of which some failed
+    pub fn update(&self, completed: usize, failed: usize) {
+        let errors = self.errors.fetch_add(failed, Ordering::Relaxed) + failed;
+        if let Ok(mut bar) = self.bar.lock() {
+            if failed > 0 {
+                bar.set_postfix(format!("errors={}", errors));
+            }
+            let _ = bar.update(completed);
+        }
+        let after = self.completed.fetch_add(completed, Ordering::Relaxed) + completed;
+        if let Some(callback) = &self.callback {
+            let before = after - completed;
+            if after / callback.interval > before / callback.interval || after == self.total {
+                (callback.callback)(&ProgressUpdate {
+                    stage: self.stage.clone(),
+                    completed: after,
+                    total: self.total,
+                    errors,
+                });
+            }
+        }
+    }
+
+    /// advances the bar by a set of completed responses, counting those with an error
+    pub fn update_responses(&self, responses: &[Value]) {
+        let failed = responses
+            .iter()
+            .filter(|r| r.get("error").is_some())
+            .count();
+        self.update(responses.len(), failed);
+    }
+
+    /// the number of failed queries so far
+    pub fn errors(&self) -> usize {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// ends the line of an enabled bar, so that following output starts on a new line
+    pub fn finish(&self) {
+        if let Ok(bar) = self.bar.lock() {
+            if !bar.disable {
+                println!();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_count() {
+        let progress = RunProgress::new(3, "search", false).unwrap();
+        progress.update_responses(&[json!({ "route": {} }), json!({ "error": {} })]);
+        progress.update(1, 1);
+        assert_eq!(progress.errors(), 2);
+        assert_eq!(progress.bar.lock().unwrap().counter, 3);
+    }
+
+    #[test]
+    fn test_progress_callback_interval() {
+        let updates = Arc::new(Mutex::new(vec![]));
+        let sink = updates.clone();
+        let callback = ProgressCallback::new(2, move |u: &ProgressUpdate| {
+            sink.lock().unwrap().push((u.completed, u.errors));
+        });
+        let progress = RunProgress::new(5, "search", false)
+            .unwrap()
+            .with_callback(Some(&callback));
+        for failed in [0, 1, 0, 0, 0] {
+            progress.update(1, failed);
+        }
+        assert_eq!(*updates.lock().unwrap(), vec![(2, 1), (4, 1), (5, 1)]);
+    }
+}
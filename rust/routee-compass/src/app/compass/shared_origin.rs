@@ -0,0 +1,126 @@
+use super::query_id::with_query_id_fields;
+use super::result_cache::hash_json;
+use crate::plugin::input::{input_field::InputField, input_json_extensions::InputJsonExtensions};
+use routee_compass_core::model::traversal::default::time_dependent_speed_service::TimeDependentSpeedService;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// configuration of the `[shared_origin]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedOriginConfig {
+    /// query fields which differ between queries from the same origin but do not
+    /// change the search, such as an id
+    #[serde(default)]
+    pub ignored_fields: Vec<String>,
+}
+
+/// fields that may differ between queries which share a search tree
+const DESTINATION_FIELDS: [InputField; 6] = [
+    InputField::DestinationX,
+    InputField::DestinationY,
+    InputField::DestinationVertex,
+    InputField::DestinationEdge,
+    InputField::DestinationAddress,
+    InputField::QueryWeightEstimate,
+];
+
+/// finds queries in a batch which can share a single one-to-many search, such as
+/// in a hub-and-spoke analysis. queries share a search when they have the same
+/// origin and search parameters, differing only by their destination and any
+/// ignored fields. queries with waypoints, a provided route, a list of
+/// objectives or an arrival time always run on their own.
+pub struct SharedOriginBatching {
+    ignored_fields: Vec<String>,
+}
+
+impl SharedOriginBatching {
+    pub fn new(config: &SharedOriginConfig) -> SharedOriginBatching {
+        SharedOriginBatching {
+            ignored_fields: with_query_id_fields(&config.ignored_fields),
+        }
+    }
+
+    /// a hash of every field of a query other than its destination and the ignored
+    /// fields, or None when the query cannot share a search with other queries.
+    pub fn key(&self, query: &Value) -> Option<u64> {
+        let shareable = matches!(query.get_destination_vertex(), Ok(Some(_)))
+            && matches!(query.get_waypoint_vertices(), Ok(w) if w.is_empty())
+            && matches!(query.get_route_edges(), Ok(None))
+            && matches!(query.get_objectives(), Ok(None))
+            && query.get(TimeDependentSpeedService::ARRIVAL_TIME).is_none()
+            && query.get(InputField::OriginVertices.to_str()).is_none();
+        let map = match query {
+            Value::Object(map) if shareable => map,
+            _ => return None,
+        };
+        let mut entries = map
+            .iter()
+            .filter(|(k, _)| {
+                !self.ignored_fields.contains(k)
+                    && !DESTINATION_FIELDS.iter().any(|f| f.to_str() == k.as_str())
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(k, _)| *k);
+        let mut hasher = DefaultHasher::new();
+        for (k, v) in entries {
+            k.hash(&mut hasher);
+            hash_json(v, &mut hasher);
+        }
+        Some(hasher.finish())
+    }
+
+    /// splits queries into groups which share a search, in order of first appearance.
+    /// queries which cannot share a search are placed in a group of their own.
+    pub fn group<'a>(&self, queries: &[&'a Value]) -> Vec<Vec<&'a Value>> {
+        let mut groups: Vec<Vec<&'a Value>> = vec![];
+        let mut group_index: HashMap<u64, usize> = HashMap::new();
+        for query in queries.iter() {
+            match self.key(query) {
+                Some(key) => match group_index.get(&key) {
+                    Some(idx) => groups[*idx].push(query),
+                    None => {
+                        group_index.insert(key, groups.len());
+                        groups.push(vec![query]);
+                    }
+                },
+                None => groups.push(vec![query]),
+            }
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_group_shared_origins() {
+        let batching = SharedOriginBatching::new(&SharedOriginConfig {
+            ignored_fields: vec![String::from("query_id")],
+        });
+        let queries = [
+            json!({ "origin_vertex": 0, "destination_vertex": 1, "query_id": 0 }),
+            json!({ "origin_vertex": 1, "destination_vertex": 2, "query_id": 1 }),
+            json!({ "origin_vertex": 0, "destination_vertex": 2, "query_id": 2 }),
+            json!({ "origin_vertex": 0, "destination_vertex": 3, "model_name": "2016_TOYOTA_Camry_4cyl_2WD" }),
+            json!({ "origin_vertex": 0, "destination_vertex": 3, "waypoint_vertices": [2] }),
+            json!({ "origin_vertex": 0 }),
+        ];
+        let refs = queries.iter().collect::<Vec<_>>();
+        let groups = batching
+            .group(&refs)
+            .into_iter()
+            .map(|g| {
+                g.iter()
+                    .map(|q| queries.iter().position(|o| o == *q).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(groups, vec![vec![0, 2], vec![1], vec![3], vec![4], vec![5]]);
+    }
+}
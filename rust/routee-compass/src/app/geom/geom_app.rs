@@ -23,8 +23,7 @@ impl TryFrom<&GeomAppConfig> for GeomApp {
     /// the app can then process a file which provides a list of EdgeIds and return the corresponding LINESTRINGs.
     fn try_from(conf: &GeomAppConfig) -> Result<Self, Self::Error> {
         let count =
-            fs_utils::line_count(conf.edge_file.clone(), fs_utils::is_gzip(&conf.edge_file))
-                .map_err(CompassAppError::IOError)?;
+            fs_utils::line_count(conf.edge_file.clone()).map_err(CompassAppError::IOError)?;
 
         let mut pb = Bar::builder()
             .total(count)
@@ -54,8 +53,7 @@ impl GeomApp {
     /// run the GeomApp. reads each line of a file, which is expected to be a number coorelating to
     /// some EdgeId. looks up the geometry for that EdgeId.
     pub fn run(&self, file: String) -> Result<Box<[LineString<f32>]>, CompassAppError> {
-        let count = fs_utils::line_count(file.clone(), fs_utils::is_gzip(&file))
-            .map_err(CompassAppError::IOError)?;
+        let count = fs_utils::line_count(file.clone()).map_err(CompassAppError::IOError)?;
 
         let mut pb = Bar::builder()
             .total(count)
@@ -3,3 +3,4 @@ pub mod cli;
 pub mod compass;
 pub mod geom;
 pub mod search;
+pub mod server;
@@ -2,3 +2,4 @@ pub mod search_app;
 pub mod search_app_graph_ops;
 pub mod search_app_ops;
 pub mod search_app_result;
+pub mod state_timeline;
@@ -1,28 +1,63 @@
-use super::{search_app_ops, search_app_result::SearchAppResult};
+use super::{
+    search_app_ops,
+    search_app_result::{ArriveBy, SearchAppResult},
+};
 use crate::{
     app::compass::{
         compass_app_error::CompassAppError,
-        config::cost_model::cost_model_service::CostModelService,
+        config::{
+            cost_model::cost_model_service::CostModelService,
+            termination_model_builder::TerminationModelBuilder,
+        },
         search_orientation::SearchOrientation,
     },
-    plugin::input::input_json_extensions::InputJsonExtensions,
+    plugin::input::{
+        input_field::InputField,
+        input_json_extensions::{InputJsonExtensions, OriginCandidate},
+    },
 };
-use chrono::Local;
+use chrono::{Local, NaiveDateTime};
 use routee_compass_core::{
     algorithm::search::{
-        direction::Direction, search_algorithm::SearchAlgorithm,
-        search_algorithm_result::SearchAlgorithmResult, search_error::SearchError,
+        a_star::a_star_algorithm,
+        backtrack,
+        cancellation_token::CancellationToken,
+        charging::{
+            charging_network::ChargingNetwork,
+            charging_search::{self, ChargingSearchResult},
+        },
+        dead_end_policy::DeadEndPolicy,
+        direction::Direction,
+        edge_offsets::EdgeOffsets,
+        edge_traversal::EdgeTraversal,
+        heuristic::alt_heuristic::AltHeuristic,
+        route_evaluation,
+        search_algorithm::SearchAlgorithm,
+        search_algorithm_result::SearchAlgorithmResult,
+        search_error::SearchError,
         search_instance::SearchInstance,
+        search_metrics::SearchMetrics,
+        waypoint_search,
     },
     model::{
         access::access_model_service::AccessModelService,
-        frontier::frontier_model_service::FrontierModelService, road_network::graph::Graph,
-        state::state_model::StateModel, termination::termination_model::TerminationModel,
-        traversal::traversal_model_service::TraversalModelService,
+        frontier::frontier_model_service::FrontierModelService,
+        road_network::{edge_id::EdgeId, graph::Graph, vertex_id::VertexId},
+        state::state_model::StateModel,
+        termination::termination_model::TerminationModel,
+        traversal::{
+            default::time_dependent_speed_service::TimeDependentSpeedService,
+            traversal_model_service::TraversalModelService,
+        },
+        unit::{as_f64::AsF64, Speed, SpeedUnit, TimeUnit},
     },
 };
 use std::sync::Arc;
 use std::time;
+use std::time::Instant;
+
+/// the result of one query of a shared origin search
+pub type SharedOriginQueryResult = Result<(SearchAppResult, SearchInstance), CompassAppError>;
 
 /// a configured and loaded application to execute searches.
 pub struct SearchApp {
@@ -34,15 +69,25 @@ pub struct SearchApp {
     pub cost_model_service: Arc<CostModelService>,
     pub frontier_model_service: Arc<dyn FrontierModelService>,
     pub termination_model: Arc<TerminationModel>,
+    pub heuristic: Option<Arc<AltHeuristic>>,
+    pub charging_network: Option<Arc<ChargingNetwork>>,
+    /// name of the time state feature, which arrive-by queries read travel times from
+    pub time_feature_name: String,
 }
 
 impl SearchApp {
+    /// name of the time state feature of the traversal models of this crate
+    pub const DEFAULT_TIME_FEATURE_NAME: &'static str = "time";
+
+    /// the most times an arrive-by query moves its departure earlier to arrive on time
+    const MAX_ARRIVE_BY_ITERATIONS: usize = 4;
+
     /// builds a new SearchApp from the required components.
     /// handles all of the specialized boxing that allows for simple parallelization.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         search_algorithm: SearchAlgorithm,
-        graph: Graph,
+        graph: Arc<Graph>,
         state_model: Arc<StateModel>,
         traversal_model_service: Arc<dyn TraversalModelService>,
         access_model_service: Arc<dyn AccessModelService>,
@@ -52,13 +97,16 @@ impl SearchApp {
     ) -> Self {
         SearchApp {
             search_algorithm,
-            directed_graph: Arc::new(graph),
+            directed_graph: graph,
             state_model,
             traversal_model_service,
             access_model_service,
             cost_model_service: Arc::new(cost_model_service),
             frontier_model_service,
             termination_model: Arc::new(termination_model),
+            heuristic: None,
+            charging_network: None,
+            time_feature_name: String::from(SearchApp::DEFAULT_TIME_FEATURE_NAME),
         }
     }
 
@@ -67,11 +115,17 @@ impl SearchApp {
     /// a search. if a destination is set on the query, then the route is computed.
     /// if the algorithm produces more than one route, then the result contains each route.
     /// the SearchAlgorithm determines the order and number of routes and trees in the result.
+    /// if the query provides its own `route_edges`, no search is run and that route is
+    /// evaluated instead. a query with an `arrival_time` is searched in reverse from
+    /// its destination, see [`SearchApp::run_arrive_by`]. a query with a set of
+    /// `destination_vertices` is routed to the nearest of them, see
+    /// [`SearchApp::run_vertex_oriented_to_any`].
     ///
     /// # Arguments
     ///
     /// * `query` - a JSON search query provided by the user
     /// * `search_orientation` - whether to orient by vertex or edge
+    /// * `cancellation` - aborts the search when cancelled
     ///
     /// # Results
     ///
@@ -80,12 +134,129 @@ impl SearchApp {
         &self,
         query: &serde_json::Value,
         search_orientation: &SearchOrientation,
+        cancellation: &CancellationToken,
     ) -> Result<(SearchAppResult, SearchInstance), CompassAppError> {
         let search_start_time = Local::now();
-        let (results, si) = match search_orientation {
-            SearchOrientation::Vertex => self.run_vertex_oriented(query),
-            SearchOrientation::Edge => self.run_edge_oriented(query),
-        }?;
+        let route_edges = query
+            .get_route_edges()
+            .map_err(CompassAppError::PluginError)?;
+        let arrival_time = TimeDependentSpeedService::arrival_time(query)
+            .map_err(|e| CompassAppError::InvalidInput(e.to_string()))?;
+        let departure_time = TimeDependentSpeedService::departure_time(query)
+            .map_err(|e| CompassAppError::InvalidInput(e.to_string()))?;
+        if arrival_time.is_some() && departure_time.is_some() {
+            return Err(CompassAppError::InvalidInput(format!(
+                "query may have a '{}' or an '{}', but not both",
+                TimeDependentSpeedService::DEPARTURE_TIME,
+                TimeDependentSpeedService::ARRIVAL_TIME
+            )));
+        }
+        if arrival_time.is_some() && route_edges.is_some() {
+            return Err(CompassAppError::InvalidInput(format!(
+                "a query with '{}' may not provide route edges to evaluate",
+                TimeDependentSpeedService::ARRIVAL_TIME
+            )));
+        }
+        let destinations = query
+            .get_destination_vertices()
+            .map_err(CompassAppError::PluginError)?;
+        // the polygon is resolved to destination vertices by the vertex rtree input plugin
+        if destinations.is_empty() && query.get_destination_polygon().is_some() {
+            return Err(CompassAppError::InvalidInput(format!(
+                "a query with a '{}' requires the vertex rtree input plugin to find its '{}'",
+                InputField::DestinationPolygon,
+                InputField::DestinationVertices
+            )));
+        }
+        if !destinations.is_empty() {
+            let destination = query
+                .get_destination_vertex()
+                .map_err(CompassAppError::PluginError)?;
+            if destination.is_some()
+                || arrival_time.is_some()
+                || route_edges.is_some()
+                || matches!(search_orientation, SearchOrientation::Edge)
+            {
+                return Err(CompassAppError::InvalidInput(format!(
+                    "a query with '{}' may not have a single destination, an '{}' or route edges, and requires vertex-oriented search",
+                    InputField::DestinationVertices,
+                    TimeDependentSpeedService::ARRIVAL_TIME
+                )));
+            }
+        }
+        let origins = query
+            .get_origin_vertices()
+            .map_err(CompassAppError::PluginError)?;
+        if !origins.is_empty()
+            && (!destinations.is_empty()
+                || arrival_time.is_some()
+                || route_edges.is_some()
+                || matches!(search_orientation, SearchOrientation::Edge))
+        {
+            return Err(CompassAppError::InvalidInput(format!(
+                "a query with '{}' may not have '{}', an '{}' or route edges, and requires vertex-oriented search",
+                InputField::OriginVertices,
+                InputField::DestinationVertices,
+                TimeDependentSpeedService::ARRIVAL_TIME
+            )));
+        }
+        // a provided route or a charging stop search replaces the configured search.
+        // charging stops are not planned for arrive-by queries or sets of origins or
+        // destinations.
+        let replacement_result = match (&self.charging_network, route_edges) {
+            (_, Some(route_edges)) => {
+                let si = self.build_search_instance(query, cancellation)?;
+                let result = route_evaluation::evaluate_route(&route_edges, &si)?;
+                Some(((result, vec![]), si))
+            }
+            (Some(network), None)
+                if arrival_time.is_none() && destinations.is_empty() && origins.is_empty() =>
+            {
+                self.run_charging(query, search_orientation, network, cancellation)?
+            }
+            (_, None) => None,
+        };
+        let mut arrive_by = None;
+        let mut chosen_destination = None;
+        let mut chosen_origin = None;
+        let ((results, charging_stops), si) = match replacement_result {
+            Some(replacement_result) => replacement_result,
+            None => {
+                let (results, si) = match (arrival_time, search_orientation) {
+                    (Some(arrival_time), SearchOrientation::Vertex) => {
+                        let (results, si, plan) =
+                            self.run_arrive_by(query, arrival_time, cancellation)?;
+                        arrive_by = Some(plan);
+                        (results, si)
+                    }
+                    (Some(_), SearchOrientation::Edge) => {
+                        return Err(CompassAppError::InvalidInput(format!(
+                            "'{}' queries only support vertex-oriented search",
+                            TimeDependentSpeedService::ARRIVAL_TIME
+                        )))
+                    }
+                    (None, SearchOrientation::Vertex) if !destinations.is_empty() => {
+                        let (results, si, chosen) =
+                            self.run_vertex_oriented_to_any(query, &destinations, cancellation)?;
+                        chosen_destination = Some(chosen);
+                        (results, si)
+                    }
+                    (None, SearchOrientation::Vertex) if !origins.is_empty() => {
+                        let (results, si, chosen) =
+                            self.run_multi_origin(query, &origins, cancellation)?;
+                        chosen_origin = Some(chosen);
+                        (results, si)
+                    }
+                    (None, SearchOrientation::Vertex) => {
+                        self.run_vertex_oriented(query, cancellation)?
+                    }
+                    (None, SearchOrientation::Edge) => {
+                        self.run_edge_oriented(query, cancellation)?
+                    }
+                };
+                ((results, vec![]), si)
+            }
+        };
 
         let search_end_time = Local::now();
         let search_runtime = (search_end_time - search_start_time)
@@ -103,6 +274,11 @@ impl SearchApp {
             search_executed_time: search_start_time.to_rfc3339(),
             search_runtime,
             iterations: results.iterations,
+            metrics: results.metrics,
+            charging_stops,
+            arrive_by,
+            chosen_destination,
+            chosen_origin,
         };
 
         Ok((result, si))
@@ -111,6 +287,7 @@ impl SearchApp {
     pub fn run_vertex_oriented(
         &self,
         query: &serde_json::Value,
+        cancellation: &CancellationToken,
     ) -> Result<(SearchAlgorithmResult, SearchInstance), CompassAppError> {
         let o = query
             .get_origin_vertex()
@@ -119,16 +296,256 @@ impl SearchApp {
             .get_destination_vertex()
             .map_err(CompassAppError::PluginError)?;
 
-        let search_instance = self.build_search_instance(query)?;
-        self.search_algorithm
-            .run_vertex_oriented(o, d, &Direction::Forward, &search_instance)
+        let waypoints = query
+            .get_waypoint_vertices()
+            .map_err(CompassAppError::PluginError)?;
+
+        let search_instance = self.build_search_instance(query, cancellation)?;
+        let query_algorithm = query_search_algorithm(query)?;
+        let algorithm = query_algorithm.as_ref().unwrap_or(&self.search_algorithm);
+        let result = if waypoints.is_empty() {
+            algorithm.run_vertex_oriented(o, d, &Direction::Forward, &search_instance)
+        } else {
+            let d = d.ok_or_else(|| {
+                CompassAppError::InvalidInput(String::from(
+                    "waypoint queries require a destination",
+                ))
+            })?;
+            let stops = [vec![o], waypoints, vec![d]].concat();
+            waypoint_search::run_vertex_oriented(algorithm, &stops, &search_instance)
+        };
+        result
             .map(|search_result| (search_result, search_instance))
             .map_err(CompassAppError::SearchError)
     }
 
+    /// runs a query with a set of destination vertices, such as the vertices within a
+    /// `destination_polygon` or the locations of each charger of a network. the search
+    /// stops at whichever destination it reaches first, which is the nearest by cost.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a vertex-oriented query with an origin vertex
+    /// * `destinations` - the destination vertices, any one of which ends the search
+    /// * `cancellation` - aborts the search when cancelled
+    ///
+    /// # Results
+    ///
+    /// The route to the chosen destination, the search instance, and that destination.
+    pub fn run_vertex_oriented_to_any(
+        &self,
+        query: &serde_json::Value,
+        destinations: &[VertexId],
+        cancellation: &CancellationToken,
+    ) -> Result<(SearchAlgorithmResult, SearchInstance, VertexId), CompassAppError> {
+        let o = query
+            .get_origin_vertex()
+            .map_err(CompassAppError::PluginError)?;
+        let waypoints = query
+            .get_waypoint_vertices()
+            .map_err(CompassAppError::PluginError)?;
+        if !waypoints.is_empty() {
+            return Err(CompassAppError::InvalidInput(format!(
+                "queries with '{}' do not support waypoints",
+                InputField::DestinationVertices
+            )));
+        }
+        let si = self.build_search_instance(query, cancellation)?;
+        let query_algorithm = query_search_algorithm(query)?;
+        let algorithm = query_algorithm.as_ref().unwrap_or(&self.search_algorithm);
+        let (result, chosen) =
+            algorithm.run_vertex_oriented_to_any(o, destinations, &Direction::Forward, &si)?;
+        Ok((result, si, chosen))
+    }
+
+    /// runs a query with several candidate origins, such as the nearest vertices to an
+    /// origin coordinate, in a single search seeded with every candidate at its cost
+    /// offset. the `origin_vertex` of the query, if any, is not used.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a vertex-oriented query with a destination vertex
+    /// * `origins` - the candidate origins and their cost offsets
+    /// * `cancellation` - aborts the search when cancelled
+    ///
+    /// # Results
+    ///
+    /// The route of least total cost, the search instance, and the origin it begins at.
+    pub fn run_multi_origin(
+        &self,
+        query: &serde_json::Value,
+        origins: &[OriginCandidate],
+        cancellation: &CancellationToken,
+    ) -> Result<(SearchAlgorithmResult, SearchInstance, VertexId), CompassAppError> {
+        let d = query
+            .get_destination_vertex()
+            .map_err(CompassAppError::PluginError)?
+            .ok_or_else(|| {
+                CompassAppError::InvalidInput(format!(
+                    "queries with '{}' require a destination",
+                    InputField::OriginVertices
+                ))
+            })?;
+        let waypoints = query
+            .get_waypoint_vertices()
+            .map_err(CompassAppError::PluginError)?;
+        if !waypoints.is_empty() {
+            return Err(CompassAppError::InvalidInput(format!(
+                "queries with '{}' do not support waypoints",
+                InputField::OriginVertices
+            )));
+        }
+        let si = self.build_search_instance(query, cancellation)?;
+        let query_algorithm = query_search_algorithm(query)?;
+        let algorithm = query_algorithm.as_ref().unwrap_or(&self.search_algorithm);
+        let sources = origins
+            .iter()
+            .map(|o| (o.vertex_id, o.offset))
+            .collect::<Vec<_>>();
+        let (result, chosen) = algorithm.run_vertex_oriented_multi_origin(&sources, d, &si)?;
+        Ok((result, si, chosen))
+    }
+
+    /// runs an arrive-by query, which has an `arrival_time` instead of a `departure_time`.
+    /// the search runs in reverse from the destination to the origin, so that time-dependent
+    /// speeds are indexed backward from the arrival time. the route found is then evaluated
+    /// forward from the departure time implied by its travel time. speeds differ at the
+    /// departure time, so when the forward route arrives late, the departure is moved
+    /// earlier by its forward travel time and the route evaluated again, up to a few times.
+    /// a route which still arrives late is reported with how late it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a vertex-oriented query with a destination and an `arrival_time`
+    /// * `arrival_time` - the arrival time of the query
+    /// * `cancellation` - aborts the search when cancelled
+    ///
+    /// # Results
+    ///
+    /// The forward route, the search instance it was evaluated with, and the recommended
+    /// departure with the arrival it leads to, in the format of the `departure_time` query field.
+    pub fn run_arrive_by(
+        &self,
+        query: &serde_json::Value,
+        arrival_time: NaiveDateTime,
+        cancellation: &CancellationToken,
+    ) -> Result<(SearchAlgorithmResult, SearchInstance, ArriveBy), CompassAppError> {
+        let o = query
+            .get_origin_vertex()
+            .map_err(CompassAppError::PluginError)?;
+        let d = query
+            .get_destination_vertex()
+            .map_err(CompassAppError::PluginError)?
+            .ok_or_else(|| {
+                CompassAppError::InvalidInput(String::from(
+                    "arrive-by queries require a destination",
+                ))
+            })?;
+        let waypoints = query
+            .get_waypoint_vertices()
+            .map_err(CompassAppError::PluginError)?;
+        if !waypoints.is_empty() {
+            return Err(CompassAppError::InvalidInput(String::from(
+                "arrive-by queries do not support waypoints",
+            )));
+        }
+
+        let reverse_si = self.build_search_instance(query, cancellation)?;
+        let query_algorithm = query_search_algorithm(query)?;
+        let algorithm = query_algorithm.as_ref().unwrap_or(&self.search_algorithm);
+        let reverse_result =
+            algorithm.run_vertex_oriented(d, Some(o), &Direction::Reverse, &reverse_si)?;
+        let reverse_route = reverse_result
+            .routes
+            .first()
+            .ok_or(SearchError::NoPathExists(o, d))?;
+
+        // the state of the last edge of a route holds the travel time of the whole
+        // route, which is given to the second like departure times
+        let route_seconds = |si: &SearchInstance, route: &[EdgeTraversal]| match route.last() {
+            None => Ok(0),
+            Some(traversal) => si
+                .state_model
+                .get_time(
+                    &traversal.result_state,
+                    &self.time_feature_name,
+                    &TimeUnit::Seconds,
+                )
+                .map(|t| t.as_f64().round() as i64)
+                .map_err(|e| {
+                    CompassAppError::InvalidInput(format!(
+                        "arrive-by queries require a time feature: {}",
+                        e
+                    ))
+                }),
+        };
+        // the reverse route runs from the destination back to the origin
+        let edge_ids = reverse_route
+            .iter()
+            .rev()
+            .map(|traversal| traversal.edge_id)
+            .collect::<Vec<_>>();
+        let mut travel_seconds = route_seconds(&reverse_si, reverse_route)?;
+        let mut iteration = 1;
+        let (si, routes, departure, forward_seconds) = loop {
+            let departure = arrival_time - chrono::Duration::seconds(travel_seconds);
+            let mut forward_query = query.clone();
+            if let Some(fields) = forward_query.as_object_mut() {
+                fields.remove(TimeDependentSpeedService::ARRIVAL_TIME);
+                fields.insert(
+                    String::from(TimeDependentSpeedService::DEPARTURE_TIME),
+                    serde_json::json!(departure
+                        .format(TimeDependentSpeedService::DEPARTURE_TIME_FORMAT)
+                        .to_string()),
+                );
+            }
+            let si = self.build_search_instance(&forward_query, cancellation)?;
+            let routes = if edge_ids.is_empty() {
+                vec![]
+            } else {
+                route_evaluation::evaluate_route(&edge_ids, &si)?.routes
+            };
+            let forward_seconds = match routes.first() {
+                Some(route) => route_seconds(&si, route)?,
+                None => 0,
+            };
+            if forward_seconds <= travel_seconds || iteration == Self::MAX_ARRIVE_BY_ITERATIONS {
+                break (si, routes, departure, forward_seconds);
+            }
+            travel_seconds = forward_seconds;
+            iteration += 1;
+        };
+        let estimated_arrival = departure + chrono::Duration::seconds(forward_seconds);
+        let late_seconds = (estimated_arrival - arrival_time).num_seconds().max(0);
+        if late_seconds > 0 {
+            log::warn!(
+                "arrive-by route from {} to {} arrives {} seconds after {}",
+                o,
+                d,
+                late_seconds,
+                arrival_time
+            );
+        }
+        let plan = ArriveBy {
+            departure_time: departure
+                .format(TimeDependentSpeedService::DEPARTURE_TIME_FORMAT)
+                .to_string(),
+            estimated_arrival_time: estimated_arrival
+                .format(TimeDependentSpeedService::DEPARTURE_TIME_FORMAT)
+                .to_string(),
+            late_seconds,
+        };
+        let result = SearchAlgorithmResult {
+            routes,
+            ..reverse_result
+        };
+        Ok((result, si, plan))
+    }
+
     pub fn run_edge_oriented(
         &self,
         query: &serde_json::Value,
+        cancellation: &CancellationToken,
     ) -> Result<(SearchAlgorithmResult, SearchInstance), CompassAppError> {
         let o = query
             .get_origin_edge()
@@ -136,18 +553,235 @@ impl SearchApp {
         let d_opt = query
             .get_destination_edge()
             .map_err(CompassAppError::PluginError)?;
-        let search_instance = self.build_search_instance(query)?;
-        self.search_algorithm
-            .run_edge_oriented(o, d_opt, &Direction::Forward, &search_instance)
+        let waypoints = query
+            .get_waypoint_edges()
+            .map_err(CompassAppError::PluginError)?;
+        let dead_end_policy = query
+            .get_dead_end_policy()
+            .map_err(CompassAppError::PluginError)?;
+        if dead_end_policy != DeadEndPolicy::Strict && !waypoints.is_empty() {
+            return Err(CompassAppError::InvalidInput(format!(
+                "queries with a '{}' other than strict do not support waypoints",
+                InputField::DeadEndPolicy
+            )));
+        }
+        let edge_offsets = EdgeOffsets::new(
+            query
+                .get_origin_edge_offset()
+                .map_err(CompassAppError::PluginError)?,
+            query
+                .get_destination_edge_offset()
+                .map_err(CompassAppError::PluginError)?,
+        )
+        .map_err(CompassAppError::SearchError)?;
+        if !edge_offsets.is_empty() {
+            if d_opt.is_none() {
+                return Err(CompassAppError::InvalidInput(format!(
+                    "queries with a '{}' or '{}' require a destination edge",
+                    InputField::OriginEdgeOffset,
+                    InputField::DestinationEdgeOffset
+                )));
+            }
+            if dead_end_policy != DeadEndPolicy::Strict {
+                return Err(CompassAppError::InvalidInput(format!(
+                    "queries with a '{}' other than strict do not support edge offsets",
+                    InputField::DeadEndPolicy
+                )));
+            }
+        }
+        let search_instance = self.build_search_instance(query, cancellation)?;
+        let query_algorithm = query_search_algorithm(query)?;
+        let algorithm = query_algorithm.as_ref().unwrap_or(&self.search_algorithm);
+        let result = if let (Some(d), true) = (d_opt, waypoints.is_empty()) {
+            dead_end_policy.run_edge_oriented(algorithm, o, d, &search_instance)
+        } else if waypoints.is_empty() {
+            algorithm.run_edge_oriented(o, d_opt, &Direction::Forward, &search_instance)
+        } else {
+            let d = d_opt.ok_or_else(|| {
+                CompassAppError::InvalidInput(String::from(
+                    "waypoint queries require a destination",
+                ))
+            })?;
+            let stops = [vec![o], waypoints, vec![d]].concat();
+            waypoint_search::run_edge_oriented(algorithm, &stops, &search_instance)
+        };
+        let result = match d_opt {
+            Some(d) => {
+                result.and_then(|r| edge_offsets.apply(r, o, d, algorithm, &search_instance))
+            }
+            None => result,
+        };
+        result
             .map(|search_result| (search_result, search_instance))
             .map_err(CompassAppError::SearchError)
     }
 
+    /// true if groups of queries which share an origin can be answered from a single
+    /// search tree, which requires the a* or dijkstra algorithm and no charging stops.
+    pub fn supports_shared_origin(&self) -> bool {
+        let shortest_path_tree = matches!(
+            self.search_algorithm,
+            SearchAlgorithm::Dijkstra | SearchAlgorithm::AStarAlgorithm { .. }
+        );
+        shortest_path_tree && self.charging_network.is_none()
+    }
+
+    /// runs a group of vertex-oriented queries which share an origin and search
+    /// parameters with a single search that stops once every destination has been
+    /// reached, then backtracks the route to each destination from the shared tree.
+    /// the search instance built for the first query is used for every query. the
+    /// shared tree spans every destination, so it is not included in the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - queries which differ only by their destination vertex
+    /// * `cancellation` - aborts the shared search when cancelled
+    ///
+    /// # Results
+    ///
+    /// The result of each query, in order, or an error if the shared search failed.
+    pub fn run_shared_origin(
+        &self,
+        queries: &[&serde_json::Value],
+        cancellation: &CancellationToken,
+    ) -> Result<Vec<SharedOriginQueryResult>, CompassAppError> {
+        let search_start_time = Local::now();
+        let first = queries.first().ok_or_else(|| {
+            CompassAppError::InvalidInput(String::from("shared origin search has no queries"))
+        })?;
+        let o = first
+            .get_origin_vertex()
+            .map_err(CompassAppError::PluginError)?;
+        let destinations = queries
+            .iter()
+            .map(|q| {
+                q.get_destination_vertex()
+                    .map_err(CompassAppError::PluginError)?
+                    .ok_or_else(|| {
+                        CompassAppError::InvalidInput(String::from(
+                            "shared origin search requires a destination",
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // the queries share one search instance, since the order of the state features
+        // in separately built instances may differ
+        let si = self.build_search_instance(first, cancellation)?;
+        let search_result =
+            a_star_algorithm::run_a_star_to_targets(o, &destinations, &Direction::Forward, &si)?;
+        let search_runtime = (Local::now() - search_start_time)
+            .to_std()
+            .unwrap_or(time::Duration::ZERO);
+        log::debug!(
+            "shared origin search for {} queries completed in {:?} miliseconds",
+            queries.len(),
+            search_runtime.as_millis()
+        );
+
+        let results = destinations
+            .into_iter()
+            .map(|d| {
+                if d != o && !search_result.tree.contains_key(&d) {
+                    return Err(CompassAppError::SearchError(SearchError::NoPathExists(
+                        o, d,
+                    )));
+                }
+                let route_start = Instant::now();
+                let route = backtrack::vertex_oriented_route(o, d, &search_result.tree)?;
+                let metrics = SearchMetrics {
+                    route_runtime: route_start.elapsed(),
+                    ..search_result.metrics
+                };
+                let result = SearchAppResult {
+                    routes: vec![route],
+                    trees: vec![],
+                    search_executed_time: search_start_time.to_rfc3339(),
+                    search_runtime,
+                    iterations: search_result.iterations,
+                    metrics,
+                    charging_stops: vec![],
+                    arrive_by: None,
+                    chosen_destination: None,
+                    chosen_origin: None,
+                };
+                Ok((result, si.clone()))
+            })
+            .collect();
+        Ok(results)
+    }
+
+    /// runs an energy-constrained search which inserts charging stops into the
+    /// route. only applies to vehicles with a battery state of charge feature;
+    /// for other vehicles, None is returned so the configured algorithm runs instead.
+    pub fn run_charging(
+        &self,
+        query: &serde_json::Value,
+        search_orientation: &SearchOrientation,
+        network: &ChargingNetwork,
+        cancellation: &CancellationToken,
+    ) -> Result<Option<(ChargingSearchResult, SearchInstance)>, CompassAppError> {
+        let search_instance = self.build_search_instance(query, cancellation)?;
+        if !network.applies_to(&search_instance.state_model) {
+            return Ok(None);
+        }
+        let result = match search_orientation {
+            SearchOrientation::Vertex => {
+                if !query
+                    .get_waypoint_vertices()
+                    .map_err(CompassAppError::PluginError)?
+                    .is_empty()
+                {
+                    return Err(CompassAppError::InvalidInput(String::from(
+                        "waypoint queries are not supported when inserting charging stops",
+                    )));
+                }
+                let o = query
+                    .get_origin_vertex()
+                    .map_err(CompassAppError::PluginError)?;
+                let d = query
+                    .get_destination_vertex()
+                    .map_err(CompassAppError::PluginError)?
+                    .ok_or_else(|| {
+                        CompassAppError::InvalidInput(String::from(
+                            "charging stop search requires a destination",
+                        ))
+                    })?;
+                charging_search::run_charging_search(o, d, network, &search_instance)
+            }
+            SearchOrientation::Edge => {
+                if !query
+                    .get_waypoint_edges()
+                    .map_err(CompassAppError::PluginError)?
+                    .is_empty()
+                {
+                    return Err(CompassAppError::InvalidInput(String::from(
+                        "waypoint queries are not supported when inserting charging stops",
+                    )));
+                }
+                let o = query
+                    .get_origin_edge()
+                    .map_err(CompassAppError::PluginError)?;
+                let d = query
+                    .get_destination_edge()
+                    .map_err(CompassAppError::PluginError)?
+                    .ok_or_else(|| {
+                        CompassAppError::InvalidInput(String::from(
+                            "charging stop search requires a destination",
+                        ))
+                    })?;
+                charging_search::run_charging_search_edge_oriented(o, d, network, &search_instance)
+            }
+        }
+        .map_err(CompassAppError::SearchError)?;
+        Ok(Some((result, search_instance)))
+    }
+
     /// builds the assets that will run the search for this query instance.
     ///
     /// # Arguments
     ///
     /// * `query` - the user query initiating this search
+    /// * `cancellation` - aborts the search when cancelled
     ///
     /// # Results
     ///
@@ -155,6 +789,7 @@ impl SearchApp {
     pub fn build_search_instance(
         &self,
         query: &serde_json::Value,
+        cancellation: &CancellationToken,
     ) -> Result<SearchInstance, SearchError> {
         let traversal_model = self.traversal_model_service.build(query)?;
         let access_model = self.access_model_service.build(query)?;
@@ -172,6 +807,19 @@ impl SearchApp {
             .frontier_model_service
             .build(query, state_model.clone())?;
 
+        // limits provided with the query apply in addition to the configured limits
+        let termination_model = match query.get_termination() {
+            None => self.termination_model.clone(),
+            Some(query_termination) => {
+                let query_model = TerminationModelBuilder::build(
+                    query_termination,
+                    Some(InputField::Termination.to_string()),
+                )
+                .map_err(|e| SearchError::BuildError(e.to_string()))?;
+                Arc::new(self.termination_model.tighten(query_model))
+            }
+        };
+
         let search_assets = SearchInstance {
             directed_graph: self.directed_graph.clone(),
             state_model,
@@ -179,9 +827,67 @@ impl SearchApp {
             access_model,
             cost_model,
             frontier_model,
-            termination_model: self.termination_model.clone(),
+            termination_model,
+            heuristic: self.heuristic.clone(),
+            cancellation: cancellation.clone(),
         };
 
         Ok(search_assets)
     }
+
+    /// replaces the speeds of some edges in the traversal model, such as from a live
+    /// traffic feed, without rebuilding the graph. searches already running keep the
+    /// speeds they started with, and later searches use the new speeds.
+    ///
+    /// an alt heuristic and a contraction hierarchy are precomputed from the speeds
+    /// at startup and would no longer agree with the new speeds, so updates are
+    /// rejected when either is configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `speeds` - edge ids paired with their new speed
+    /// * `speed_unit` - unit of the new speeds
+    pub fn update_edge_speeds(
+        &self,
+        speeds: &[(EdgeId, Speed)],
+        speed_unit: &SpeedUnit,
+    ) -> Result<(), CompassAppError> {
+        if self.heuristic.is_some() {
+            return Err(CompassAppError::InvalidInput(String::from(
+                "edge speeds cannot be updated with an alt heuristic, whose landmark costs were computed from the speeds at startup",
+            )));
+        }
+        if uses_contraction_hierarchy(&self.search_algorithm) {
+            return Err(CompassAppError::InvalidInput(String::from(
+                "edge speeds cannot be updated with the ch search algorithm, whose hierarchy was contracted from the speeds at startup",
+            )));
+        }
+        for (edge_id, _) in speeds.iter() {
+            self.directed_graph.get_edge(*edge_id)?;
+        }
+        self.traversal_model_service
+            .update_edge_speeds(speeds, speed_unit)?;
+        Ok(())
+    }
+}
+
+/// true if an algorithm searches a contraction hierarchy, itself or as the
+/// underlying search of another algorithm
+fn uses_contraction_hierarchy(algorithm: &SearchAlgorithm) -> bool {
+    match algorithm {
+        SearchAlgorithm::ContractionHierarchy { .. } => true,
+        SearchAlgorithm::KspSingleVia { underlying, .. } => uses_contraction_hierarchy(underlying),
+        _ => false,
+    }
+}
+
+/// a query with a list of `objectives` runs a pareto search over those state
+/// features in place of the configured search algorithm.
+fn query_search_algorithm(
+    query: &serde_json::Value,
+) -> Result<Option<SearchAlgorithm>, CompassAppError> {
+    let objectives = query
+        .get_objectives()
+        .map_err(CompassAppError::PluginError)?;
+    Ok(objectives.map(|objectives| SearchAlgorithm::Pareto { objectives }))
 }
@@ -3,7 +3,7 @@ use crate::app::compass::compass_app_error::CompassAppError;
 use routee_compass_core::{
     algorithm::search::direction::Direction,
     model::road_network::{edge_id::EdgeId, vertex_id::VertexId},
-    model::unit::{Distance, DistanceUnit},
+    model::unit::{Distance, DistanceUnit, Grade, GradeUnit, Speed, SpeedUnit},
 };
 
 pub trait SearchAppGraphOps {
@@ -19,6 +19,18 @@ pub trait SearchAppGraphOps {
         vertex_id: VertexId,
         direction: Direction,
     ) -> Result<Vec<EdgeId>, CompassAppError>;
+    fn get_vertex_coordinate(&self, vertex_id: VertexId) -> Result<(f64, f64), CompassAppError>;
+    fn get_edge_speed(
+        &self,
+        edge_id: EdgeId,
+        speed_unit: Option<SpeedUnit>,
+    ) -> Result<Speed, CompassAppError>;
+    fn get_edge_grade(
+        &self,
+        edge_id: EdgeId,
+        grade_unit: Option<GradeUnit>,
+    ) -> Result<Grade, CompassAppError>;
+    fn get_edge_road_class(&self, edge_id: EdgeId) -> Result<Option<u8>, CompassAppError>;
 }
 
 impl SearchAppGraphOps for SearchApp {
@@ -66,4 +78,50 @@ impl SearchAppGraphOps for SearchApp {
             .map_err(CompassAppError::GraphError)?;
         Ok(incident_edges)
     }
+
+    /// the x and y coordinate of a vertex
+    fn get_vertex_coordinate(&self, vertex_id: VertexId) -> Result<(f64, f64), CompassAppError> {
+        let vertex = self.directed_graph.get_vertex(vertex_id)?;
+        Ok((vertex.x() as f64, vertex.y() as f64))
+    }
+
+    /// the speed of an edge from the traversal model, in the unit of its speed table
+    /// unless a unit is provided
+    fn get_edge_speed(
+        &self,
+        edge_id: EdgeId,
+        speed_unit: Option<SpeedUnit>,
+    ) -> Result<Speed, CompassAppError> {
+        self.directed_graph.get_edge(edge_id)?;
+        let (speed, source_unit) = self.traversal_model_service.edge_speed(edge_id)?;
+        let result = match speed_unit {
+            Some(su) => source_unit.convert(&speed, &su),
+            None => speed,
+        };
+        Ok(result)
+    }
+
+    /// the grade of an edge from the traversal model, in the unit of its grade table
+    /// unless a unit is provided
+    fn get_edge_grade(
+        &self,
+        edge_id: EdgeId,
+        grade_unit: Option<GradeUnit>,
+    ) -> Result<Grade, CompassAppError> {
+        self.directed_graph.get_edge(edge_id)?;
+        let (grade, source_unit) = self.traversal_model_service.edge_grade(edge_id)?;
+        let result = match grade_unit {
+            Some(gu) => source_unit.convert(&grade, &gu),
+            None => grade,
+        };
+        Ok(result)
+    }
+
+    /// the road class of an edge from the frontier model, or None when no frontier
+    /// model restricts travel by road class
+    fn get_edge_road_class(&self, edge_id: EdgeId) -> Result<Option<u8>, CompassAppError> {
+        self.directed_graph.get_edge(edge_id)?;
+        let road_class = self.frontier_model_service.edge_road_class(edge_id)?;
+        Ok(road_class)
+    }
 }
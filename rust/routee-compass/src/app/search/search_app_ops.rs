@@ -11,23 +11,46 @@ use crate::app::compass::config::config_json_extension::ConfigJsonExtensions;
 
 /// collects the state features to use in this search. the features are collected in
 /// the following order:
-///   1. from the traversal model
-///   2. from the access model
-///   3. optionally from the query itself
+/// 1. from the traversal model
+/// 2. from the access model
+/// 3. optionally from the query itself
+///
 /// using the order above, each new source optionally overwrites any existing feature
 /// by name (tuple index 0) as long as they match in StateFeature::get_feature_name and
 /// StateFeature::get_feature_unit_name.
+///
+/// the features of the models keep the order in which the models register them, so
+/// that each feature has the same index in the state vector of every search. a feature
+/// registered by both models with different types is an error rather than being
+/// silently replaced.
 pub fn collect_features(
     query: &serde_json::Value,
     traversal_model: Arc<dyn TraversalModel>,
     access_model: Arc<dyn AccessModel>,
 ) -> Result<Vec<(String, StateFeature)>, StateError> {
     // prepare the set of features for this state model
-    let model_features = traversal_model
+    let mut model_features: Vec<(String, StateFeature)> = vec![];
+    for (name, feature) in traversal_model
         .state_features()
         .into_iter()
         .chain(access_model.state_features())
-        .collect::<HashMap<_, _>>();
+    {
+        match model_features.iter().find(|(n, _)| *n == name) {
+            None => model_features.push((name, feature)),
+            Some((_, existing)) if *existing == feature => {}
+            Some((_, existing)) => {
+                return Err(StateError::BuildError(format!(
+                    "state feature '{}' is registered as both {} ({}) and {} ({})",
+                    name,
+                    existing.get_feature_type(),
+                    existing,
+                    feature.get_feature_type(),
+                    feature
+                )))
+            }
+        }
+    }
+    let model_lookup = model_features.iter().cloned().collect::<HashMap<_, _>>();
     // build the state model. inject state features from the traversal and access models
     // and then allow the user to optionally override any initial conditions for those
     // state features.
@@ -37,9 +60,9 @@ pub fn collect_features(
     let user_features = user_features_option
         .unwrap_or_default()
         .into_iter()
-        .map(|(name, feature)| match model_features.get(&name) {
+        .map(|(name, feature)| match model_lookup.get(&name) {
             None => {
-                let fnames = model_features.keys().join(",");
+                let fnames = model_features.iter().map(|(n, _)| n).join(",");
                 Err(StateError::UnknownStateVariableName(name, fnames))
             }
             Some(existing) if existing.get_feature_type() != feature.get_feature_type() => {
@@ -51,7 +74,7 @@ pub fn collect_features(
             Some(_) => Ok((name, feature)),
         })
         .collect::<Result<Vec<_>, _>>()?;
-    let mut added_features: Vec<(String, StateFeature)> = model_features.into_iter().collect_vec();
+    let mut added_features = model_features;
     added_features.extend(user_features);
     Ok(added_features)
 }
@@ -1,8 +1,13 @@
 use allocative::Allocative;
 
+use super::state_timeline::StateTimeline;
 use routee_compass_core::{
-    algorithm::search::{edge_traversal::EdgeTraversal, search_tree_branch::SearchTreeBranch},
-    model::road_network::vertex_id::VertexId,
+    algorithm::search::{
+        charging::charging_stop::ChargingStop, edge_traversal::EdgeTraversal,
+        search_instance::SearchInstance, search_metrics::SearchMetrics,
+        search_tree_branch::SearchTreeBranch,
+    },
+    model::{road_network::vertex_id::VertexId, state::state_error::StateError},
 };
 
 use std::{collections::HashMap, time::Duration};
@@ -14,4 +19,37 @@ pub struct SearchAppResult {
     pub search_executed_time: String,
     pub search_runtime: Duration,
     pub iterations: u64,
+    pub metrics: SearchMetrics,
+    pub charging_stops: Vec<ChargingStop>,
+    /// the recommended departure of an arrive-by query
+    pub arrive_by: Option<ArriveBy>,
+    /// the destination reached by a query with a set of destination vertices
+    pub chosen_destination: Option<VertexId>,
+    /// the origin a query with several candidate origins began at
+    pub chosen_origin: Option<VertexId>,
+}
+
+/// the departure recommended for an arrive-by query and the arrival it leads to
+#[derive(Allocative, Clone, Debug)]
+pub struct ArriveBy {
+    /// the recommended departure time
+    pub departure_time: String,
+    /// the arrival time of the route evaluated forward from the departure time
+    pub estimated_arrival_time: String,
+    /// seconds that the estimated arrival is after the requested arrival time, or zero
+    /// if the route arrives on time
+    pub late_seconds: i64,
+}
+
+impl SearchAppResult {
+    /// the sequence of states along each route, see [`StateTimeline`]
+    pub fn state_timelines(
+        &self,
+        si: &SearchInstance,
+    ) -> Result<Vec<StateTimeline<'_>>, StateError> {
+        self.routes
+            .iter()
+            .map(|route| StateTimeline::new(route, si))
+            .collect()
+    }
 }
@@ -0,0 +1,116 @@
+use routee_compass_core::{
+    algorithm::search::{edge_traversal::EdgeTraversal, search_instance::SearchInstance},
+    model::{
+        state::{state_error::StateError, state_model::StateModel},
+        traversal::state::state_variable::StateVar,
+        unit::as_f64::AsF64,
+    },
+};
+use serde_json::json;
+
+/// the sequence of states along a route, from the initial state of the search through
+/// the state at the end of each edge. output plugins use this to follow a feature over
+/// a route, such as for time-stamped trajectories, state of charge by distance, or
+/// speed profiles, rather than only the totals at the end of the route.
+pub struct StateTimeline<'a> {
+    pub initial_state: Vec<StateVar>,
+    pub route: &'a [EdgeTraversal],
+}
+
+impl<'a> StateTimeline<'a> {
+    pub fn new(route: &'a [EdgeTraversal], si: &SearchInstance) -> Result<Self, StateError> {
+        let initial_state = si.state_model.initial_state()?;
+        Ok(StateTimeline {
+            initial_state,
+            route,
+        })
+    }
+
+    /// the initial state followed by the state at the end of each edge
+    pub fn states(&self) -> impl Iterator<Item = &[StateVar]> + '_ {
+        std::iter::once(self.initial_state.as_slice())
+            .chain(self.route.iter().map(|et| et.result_state.as_slice()))
+    }
+
+    /// each edge traversal along with the states at its start and end
+    pub fn edges(&self) -> impl Iterator<Item = (&EdgeTraversal, &[StateVar], &[StateVar])> + '_ {
+        self.states()
+            .zip(self.route.iter())
+            .map(|(prev, et)| (et, prev, et.result_state.as_slice()))
+    }
+
+    /// the value of one feature in each state, in the unit of the feature
+    pub fn feature(&self, name: &String, state_model: &StateModel) -> Result<Vec<f64>, StateError> {
+        self.states()
+            .map(|state| {
+                state_model
+                    .get_state_variable(state, name)
+                    .map(|v| v.as_f64())
+            })
+            .collect()
+    }
+
+    /// each state as a JSON object, with the id of the edge it ends, which is null
+    /// for the initial state
+    pub fn to_json(&self, state_model: &StateModel) -> serde_json::Value {
+        let edge_ids = std::iter::once(None).chain(self.route.iter().map(|et| Some(et.edge_id)));
+        let timeline = edge_ids
+            .zip(self.states())
+            .map(|(edge_id, state)| {
+                json!({
+                    "edge_id": edge_id,
+                    "state": state_model.serialize_state(state)
+                })
+            })
+            .collect::<Vec<_>>();
+        json![timeline]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::{
+        road_network::edge_id::EdgeId,
+        state::state_feature::StateFeature,
+        unit::{Cost, Distance, DistanceUnit},
+    };
+
+    #[test]
+    fn test_timeline_states_and_features() {
+        let distance = String::from("distance");
+        let state_model = StateModel::new(vec![(
+            distance.clone(),
+            StateFeature::Distance {
+                distance_unit: DistanceUnit::Kilometers,
+                initial: Distance::new(0.0),
+            },
+        )]);
+        let route = [1.5, 4.0]
+            .iter()
+            .enumerate()
+            .map(|(idx, d)| EdgeTraversal {
+                edge_id: EdgeId(idx),
+                access_cost: Cost::ZERO,
+                traversal_cost: Cost::ZERO,
+                result_state: vec![StateVar(*d)],
+            })
+            .collect::<Vec<_>>();
+        let timeline = StateTimeline {
+            initial_state: state_model.initial_state().unwrap(),
+            route: &route,
+        };
+        assert_eq!(
+            timeline.feature(&distance, &state_model).unwrap(),
+            vec![0.0, 1.5, 4.0]
+        );
+        let (et, prev, next) = timeline.edges().nth(1).unwrap();
+        assert_eq!(
+            (et.edge_id, prev, next),
+            (EdgeId(1), &[StateVar(1.5)][..], &[StateVar(4.0)][..])
+        );
+        let json = timeline.to_json(&state_model);
+        assert_eq!(json[0]["edge_id"], serde_json::Value::Null);
+        assert_eq!(json[2]["state"]["distance"], json!(4.0));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::app::compass::compass_app_error::CompassAppError;
+use routee_compass_core::algorithm::search::cancellation_token::CancellationToken;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// the cancellation tokens of the requests running on the server, by the id that the
+/// client gave each request in its `X-Request-Id` header.
+#[derive(Default)]
+pub struct InFlightRequests(Mutex<HashMap<String, CancellationToken>>);
+
+/// a running request, which is removed from the [`InFlightRequests`] when dropped
+pub struct InFlightRequest<'a> {
+    requests: &'a InFlightRequests,
+    request_id: String,
+    pub cancellation: CancellationToken,
+}
+
+impl InFlightRequests {
+    /// registers a request under an id, so that cancelling the id trips its
+    /// cancellation token, returning None if a request with the same id is already running.
+    pub fn register(
+        &self,
+        request_id: &str,
+        cancellation: CancellationToken,
+    ) -> Result<Option<InFlightRequest<'_>>, CompassAppError> {
+        let mut requests = self.lock()?;
+        if requests.contains_key(request_id) {
+            return Ok(None);
+        }
+        requests.insert(request_id.to_string(), cancellation.clone());
+        Ok(Some(InFlightRequest {
+            requests: self,
+            request_id: request_id.to_string(),
+            cancellation,
+        }))
+    }
+
+    /// cancels a running request, returning false if no request has the id
+    pub fn cancel(&self, request_id: &str) -> Result<bool, CompassAppError> {
+        match self.lock()?.get(request_id) {
+            Some(cancellation) => {
+                cancellation.cancel();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, CancellationToken>>, CompassAppError> {
+        self.0.lock().map_err(|e| {
+            CompassAppError::ReadOnlyPoisonError(format!(
+                "failed to acquire lock on in-flight requests: {}",
+                e
+            ))
+        })
+    }
+}
+
+impl Drop for InFlightRequest<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.remove(&self.request_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_registered_request() {
+        let requests = InFlightRequests::default();
+        let request = requests
+            .register("a", CancellationToken::new())
+            .unwrap()
+            .expect("request should be registered");
+        assert!(requests
+            .register("a", CancellationToken::new())
+            .unwrap()
+            .is_none());
+        assert!(!requests.cancel("b").unwrap());
+        assert!(requests.cancel("a").unwrap());
+        assert!(request.cancellation.is_cancelled());
+
+        // a finished request can no longer be cancelled, and its id may be reused
+        drop(request);
+        assert!(!requests.cancel("a").unwrap());
+        assert!(requests
+            .register("a", CancellationToken::new())
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod in_flight_requests;
+pub mod serve;
+pub mod server_args;
+pub mod server_endpoint;
+pub mod speed_update;
@@ -0,0 +1,562 @@
+use super::in_flight_requests::InFlightRequests;
+use super::server_args::ServerArgs;
+use super::server_endpoint::ServerEndpoint;
+use super::speed_update::SpeedUpdate;
+use crate::app::compass::compass_app_ops as ops;
+use crate::app::compass::error_taxonomy::{ErrorCode, ErrorComponent, ErrorDetail, ErrorTaxonomy};
+use crate::app::compass::{
+    compass_app::CompassApp, compass_app_error::CompassAppError,
+    compass_json_extensions::CompassJsonExtensions, config::compass_app_builder::CompassAppBuilder,
+};
+use log::{error, info};
+use routee_compass_core::algorithm::search::cancellation_token::CancellationToken;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tiny_http::{Header, Request, Response, Server};
+
+/// how long a request runs before its reply starts, and how often the reply is written
+/// to while the request runs, so that a client which disconnects is noticed
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// trailer of a streamed reply holding the status of the request, since the status
+/// line of the reply was sent before the request finished
+const STATUS_TRAILER: &str = "X-Compass-Status";
+
+/// runs CompassApp as a long-running HTTP server, so that the graph and models are
+/// loaded once and shared by every request. queries and responses use the same JSON
+/// as the command line application.
+///
+/// # Arguments
+/// * `args`    - command line arguments for the server
+/// * `builder` - optional builder instance to overwrite the default. see CompassAppBuilder for explanation.
+///
+/// # Returns
+/// Runs until the process is stopped or a `POST /shutdown` request is received, or returns
+/// an error if the app or server cannot be started.
+pub fn run_server(
+    args: &ServerArgs,
+    builder: Option<CompassAppBuilder>,
+) -> Result<(), CompassAppError> {
+    args.validate()?;
+
+    let builder_or_default = builder.unwrap_or_default();
+    let config_path = Path::new(&args.config_file);
+    let config = ops::read_config_from_file(config_path)?;
+    let compass_app = Arc::new(CompassApp::try_from((&config, &builder_or_default))?);
+    let in_flight = Arc::new(InFlightRequests::default());
+
+    let address = format!("{}:{}", args.host, args.port);
+    let server = Server::http(&address).map_err(|e| {
+        CompassAppError::InternalError(format!("failed to start server on {}: {}", address, e))
+    })?;
+    info!(
+        "compass server listening on http://{} with {} workers",
+        address, args.workers
+    );
+
+    // each worker handles one request at a time, which limits how many requests
+    // run concurrently. additional requests wait in the queue.
+    let (queue, queued) = mpsc::channel::<Request>();
+    let queued = Arc::new(Mutex::new(queued));
+    let handles = (0..args.workers)
+        .map(|_| {
+            let queued = queued.clone();
+            let app = compass_app.clone();
+            let in_flight = in_flight.clone();
+            let max_batch_size = args.max_batch_size;
+            let max_body_bytes = args.max_body_bytes;
+            std::thread::spawn(move || loop {
+                let request = match queued.lock() {
+                    Ok(queued) => queued.recv(),
+                    Err(_) => break,
+                };
+                match request {
+                    Ok(request) => {
+                        respond(request, &app, &in_flight, max_batch_size, max_body_bytes)
+                    }
+                    // the queue is closed and empty
+                    Err(_) => break,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // cancel and shutdown requests are answered as they arrive rather than queued, so
+    // that a running request can be cancelled while every worker is busy
+    while let Ok(mut request) = server.recv() {
+        match ServerEndpoint::from_request(request.method().as_str(), request.url()) {
+            Ok(ServerEndpoint::Cancel) => {
+                let body_length = request.body_length();
+                let reply = match read_body(request.as_reader(), body_length, args.max_body_bytes) {
+                    Ok(body) => cancel_request(&in_flight, &body),
+                    Err(reply) => reply,
+                };
+                send_reply(request, reply);
+            }
+            Ok(ServerEndpoint::Shutdown) => {
+                send_reply(request, (200, json!({"status": "shutting down"})));
+                break;
+            }
+            _ => {
+                if queue.send(request).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    // the workers stop once they have handled the requests already queued
+    drop(queue);
+    for handle in handles {
+        handle.join().map_err(|_| {
+            CompassAppError::InternalError(String::from("server worker thread panicked"))
+        })?;
+    }
+    info!("compass server shut down");
+    compass_app.finish()
+}
+
+/// handles a request to some endpoint with the given body, returning the HTTP status
+/// code and JSON body of the reply. user errors within a query are reported in that
+/// query's response, as with the command line application, and do not change the status.
+///
+/// the queries stop with a `CANCELLED` error once the cancellation token is tripped. a
+/// request with an id also trips it when a `POST /cancel` request with that id is received
+/// while it runs.
+pub fn handle_request(
+    app: &CompassApp,
+    in_flight: &InFlightRequests,
+    endpoint: &ServerEndpoint,
+    body: &str,
+    request_id: Option<&str>,
+    max_batch_size: usize,
+    cancellation: &CancellationToken,
+) -> (u16, Value) {
+    let queries = match endpoint {
+        ServerEndpoint::Health => return (200, json!({"status": "ok"})),
+        ServerEndpoint::Speeds => return update_speeds(app, body),
+        ServerEndpoint::Cancel => return cancel_request(in_flight, body),
+        ServerEndpoint::Shutdown => return (200, json!({"status": "shutting down"})),
+        ServerEndpoint::Route => match serde_json::from_str::<Value>(body) {
+            Ok(query @ Value::Object(_)) => vec![query],
+            Ok(_) => {
+                return error_reply(
+                    400,
+                    query_error(
+                        ErrorCode::InvalidInput,
+                        "route request must be a JSON object",
+                    ),
+                )
+            }
+            Err(e) => {
+                return error_reply(
+                    400,
+                    query_error(ErrorCode::ParseError, format!("invalid JSON: {}", e)),
+                )
+            }
+        },
+        ServerEndpoint::Batch => {
+            match serde_json::from_str::<Value>(body)
+                .map_err(CompassAppError::CodecError)
+                .and_then(|json| json.get_queries())
+            {
+                Ok(queries) if queries.len() > max_batch_size => {
+                    let msg = format!(
+                        "batch of {} queries exceeds the limit of {}",
+                        queries.len(),
+                        max_batch_size
+                    );
+                    return error_reply(413, query_error(ErrorCode::InvalidInput, msg));
+                }
+                Ok(queries) => queries,
+                Err(e) => return error_reply(400, e.error_detail()),
+            }
+        }
+    };
+
+    // responses are returned to the client, never written to the configured output file
+    let run_config = json!({
+        "response_persistence_policy": "persist_response_in_memory",
+        "response_output_policy": { "type": "none" }
+    });
+    // the request stays registered, and so may be cancelled, until it returns
+    let registration = match request_id {
+        None => None,
+        Some(id) => match in_flight.register(id, cancellation.clone()) {
+            Ok(Some(registration)) => Some(registration),
+            Ok(None) => {
+                return error_reply(
+                    409,
+                    query_error(
+                        ErrorCode::InvalidInput,
+                        format!("a request with id {} is already running", id),
+                    ),
+                )
+            }
+            Err(e) => return error_reply(500, e.error_detail()),
+        },
+    };
+    let reply = match app.run_cancellable(queries, Some(&run_config), None, cancellation) {
+        Err(e) => {
+            error!("{} request failed: {}", endpoint, e);
+            error_reply(500, e.error_detail())
+        }
+        Ok(mut results) => match (endpoint, results.len()) {
+            // a single query may be expanded by input plugins into many
+            (ServerEndpoint::Route, 1) => (200, results.remove(0)),
+            _ => (200, json![results]),
+        },
+    };
+    drop(registration);
+    reply
+}
+
+/// applies a speed update to the running app. queries already in progress keep the
+/// speeds they started with.
+fn update_speeds(app: &CompassApp, body: &str) -> (u16, Value) {
+    let update = match serde_json::from_str::<SpeedUpdate>(body) {
+        Ok(update) => update,
+        Err(e) => {
+            return error_reply(
+                400,
+                query_error(
+                    ErrorCode::ParseError,
+                    format!("invalid speed update: {}", e),
+                ),
+            )
+        }
+    };
+    let speeds = update.edge_speeds();
+    match app.update_edge_speeds(&speeds, &update.speed_unit) {
+        Ok(()) => (200, json!({ "updated": speeds.len() })),
+        Err(e) => {
+            error!("{} request failed: {}", ServerEndpoint::Speeds, e);
+            error_reply(400, e.error_detail())
+        }
+    }
+}
+
+/// cancels the running request with the id given in the body, such as
+/// `{"request_id": "abc"}`. searches of the request stop at their next iteration.
+fn cancel_request(in_flight: &InFlightRequests, body: &str) -> (u16, Value) {
+    let request_id = match serde_json::from_str::<Value>(body) {
+        Ok(json) => match json.get("request_id").and_then(Value::as_str) {
+            Some(request_id) => request_id.to_string(),
+            None => {
+                return error_reply(
+                    400,
+                    query_error(
+                        ErrorCode::MissingField,
+                        "cancel request must have a request_id string",
+                    ),
+                )
+            }
+        },
+        Err(e) => {
+            return error_reply(
+                400,
+                query_error(ErrorCode::ParseError, format!("invalid JSON: {}", e)),
+            )
+        }
+    };
+    match in_flight.cancel(&request_id) {
+        Ok(true) => (200, json!({ "cancelled": request_id })),
+        Ok(false) => error_reply(
+            404,
+            query_error(
+                ErrorCode::InvalidInput,
+                format!("no running request with id {}", request_id),
+            ),
+        ),
+        Err(e) => error_reply(500, e.error_detail()),
+    }
+}
+
+/// runs a request on a worker and replies with its result
+fn respond(
+    mut request: Request,
+    app: &CompassApp,
+    in_flight: &InFlightRequests,
+    max_batch_size: usize,
+    max_body_bytes: usize,
+) {
+    let request_id = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("X-Request-Id"))
+        .map(|h| h.value.to_string());
+    let endpoint = match ServerEndpoint::from_request(request.method().as_str(), request.url()) {
+        Ok(endpoint) => endpoint,
+        Err(not_found) => {
+            let reply = error_reply(
+                not_found.status,
+                query_error(ErrorCode::InvalidInput, not_found.message),
+            );
+            return send_reply(request, reply);
+        }
+    };
+    let body_length = request.body_length();
+    let body = match read_body(request.as_reader(), body_length, max_body_bytes) {
+        Ok(body) => body,
+        Err(reply) => return send_reply(request, reply),
+    };
+    reply_when_done(request, HEARTBEAT_INTERVAL, |cancellation| {
+        handle_request(
+            app,
+            in_flight,
+            &endpoint,
+            &body,
+            request_id.as_deref(),
+            max_batch_size,
+            cancellation,
+        )
+    });
+}
+
+/// runs a request on its own thread and replies with its result. the server cannot see
+/// the socket of a request, so a request which runs longer than `heartbeat` starts its
+/// reply with status 200 and a chunked body, and writes a space ahead of the JSON at each
+/// `heartbeat` until it finishes. the status of such a reply is always 200, even when the
+/// request fails, so its actual status is sent in the `X-Compass-Status` trailer and any
+/// failure in the `error` of its body. a failed write means that the client has
+/// disconnected, and cancels the request.
+fn reply_when_done<F>(request: Request, heartbeat: Duration, run: F)
+where
+    F: FnOnce(&CancellationToken) -> (u16, Value) + Send,
+{
+    let cancellation = CancellationToken::new();
+    std::thread::scope(|scope| {
+        let (done, reply) = mpsc::channel();
+        let run_cancellation = &cancellation;
+        scope.spawn(move || {
+            let _ = done.send(run(run_cancellation));
+        });
+        match reply.recv_timeout(heartbeat) {
+            Ok(finished) => send_reply(request, finished),
+            // a panicked request is reported when the scope ends
+            Err(RecvTimeoutError::Disconnected) => {}
+            // clients of HTTP 1.0 cannot read a chunked body, so they wait for the reply
+            Err(RecvTimeoutError::Timeout) if *request.http_version() < (1, 1) => {
+                if let Ok(finished) = reply.recv() {
+                    send_reply(request, finished);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                stream_reply(request.into_writer(), &reply, heartbeat, &cancellation)
+            }
+        }
+    });
+}
+
+/// writes a chunked reply, with a space at each `heartbeat` until the request finishes
+/// and then its JSON, followed by its status in the `X-Compass-Status` trailer. the
+/// request is cancelled as soon as a write fails.
+fn stream_reply(
+    mut writer: Box<dyn Write + Send>,
+    reply: &mpsc::Receiver<(u16, Value)>,
+    heartbeat: Duration,
+    cancellation: &CancellationToken,
+) {
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\nTrailer: {}\r\n\r\n",
+        STATUS_TRAILER
+    );
+    let mut connected = write_all_and_flush(&mut writer, head.as_bytes()).is_ok();
+    loop {
+        if !connected && !cancellation.is_cancelled() {
+            info!("client disconnected, cancelling its request");
+            cancellation.cancel();
+        }
+        match reply.recv_timeout(heartbeat) {
+            Ok((status, body)) => {
+                if connected {
+                    let body = body.to_string();
+                    let end = format!(
+                        "{:x}\r\n{}\r\n0\r\n{}: {}\r\n\r\n",
+                        body.len(),
+                        body,
+                        STATUS_TRAILER,
+                        status
+                    );
+                    if let Err(e) = write_all_and_flush(&mut writer, end.as_bytes()) {
+                        error!("failed to send response: {}", e);
+                    }
+                }
+                return;
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+            Err(RecvTimeoutError::Timeout) if connected => {
+                connected = write_all_and_flush(&mut writer, b"1\r\n \r\n").is_ok();
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+}
+
+fn write_all_and_flush(writer: &mut dyn Write, bytes: &[u8]) -> std::io::Result<()> {
+    writer.write_all(bytes)?;
+    writer.flush()
+}
+
+/// sends a JSON reply with the given status
+fn send_reply(request: Request, (status, body): (u16, Value)) {
+    let mut response = Response::from_string(body.to_string()).with_status_code(status);
+    if let Ok(header) = "Content-Type: application/json".parse::<Header>() {
+        response = response.with_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        error!("failed to send response: {}", e);
+    }
+}
+
+/// reads a request body of at most `max_body_bytes`. a body whose Content-Length
+/// exceeds the limit is rejected without being read, and a body without a length,
+/// such as a chunked body, is rejected once it grows past the limit.
+fn read_body(
+    reader: impl Read,
+    body_length: Option<usize>,
+    max_body_bytes: usize,
+) -> Result<String, (u16, Value)> {
+    let too_large = || {
+        error_reply(
+            413,
+            query_error(
+                ErrorCode::InvalidInput,
+                format!("request body exceeds the limit of {} bytes", max_body_bytes),
+            ),
+        )
+    };
+    if body_length.is_some_and(|len| len > max_body_bytes) {
+        return Err(too_large());
+    }
+    let mut body = String::new();
+    reader
+        .take(max_body_bytes as u64 + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| {
+            error_reply(
+                400,
+                query_error(
+                    ErrorCode::IoError,
+                    format!("unable to read request body: {}", e),
+                ),
+            )
+        })?;
+    if body.len() > max_body_bytes {
+        return Err(too_large());
+    }
+    Ok(body)
+}
+
+/// an error of the request itself, rather than of one of its queries
+fn query_error<M: ToString>(code: ErrorCode, message: M) -> ErrorDetail {
+    ErrorDetail::new(code, ErrorComponent::Query, message)
+}
+
+/// a reply with the same structured error as a failed query, so that clients
+/// handle errors of requests and of queries alike
+fn error_reply(status: u16, error: ErrorDetail) -> (u16, Value) {
+    (status, json!({ "error": error.to_json() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_body_limit() {
+        let body = "{\"origin_x\": 0.0}";
+        assert_eq!(
+            read_body(body.as_bytes(), Some(body.len()), 64).unwrap(),
+            body
+        );
+        let (status, _) = read_body(body.as_bytes(), Some(body.len()), 4).unwrap_err();
+        assert_eq!(status, 413);
+        // a body without a declared length is cut off at the limit
+        let (status, _) = read_body(body.as_bytes(), None, 4).unwrap_err();
+        assert_eq!(status, 413);
+    }
+
+    #[test]
+    fn test_error_reply_is_structured() {
+        let (status, reply) = error_reply(404, query_error(ErrorCode::InvalidInput, "not found"));
+        assert_eq!(status, 404);
+        assert_eq!(
+            reply,
+            json!({ "error": { "code": "INVALID_INPUT", "message": "not found", "component": "query" } })
+        );
+    }
+
+    /// sends a request to a test server, returning the server's request and the client
+    /// socket, which the server closes once it has replied
+    fn accept_request(server: &Server) -> (Request, std::net::TcpStream) {
+        let address = server
+            .server_addr()
+            .to_ip()
+            .expect("server has an ip address");
+        let mut client = std::net::TcpStream::connect(address).unwrap();
+        client
+            .write_all(b"POST /route HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        (server.recv().unwrap(), client)
+    }
+
+    #[test]
+    fn test_long_request_streams_its_reply() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let (request, mut client) = accept_request(&server);
+        reply_when_done(request, Duration::from_millis(10), |_| {
+            std::thread::sleep(Duration::from_millis(100));
+            (200, json!({"status": "done"}))
+        });
+        let mut reply = String::new();
+        client.read_to_string(&mut reply).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 200 OK"));
+        assert!(reply.contains("Transfer-Encoding: chunked"));
+        assert!(reply.contains("1\r\n \r\n"));
+        assert!(reply.contains("Trailer: X-Compass-Status"));
+        assert!(reply.ends_with("{\"status\":\"done\"}\r\n0\r\nX-Compass-Status: 200\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_long_failed_request_reports_its_status() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let (request, mut client) = accept_request(&server);
+        reply_when_done(request, Duration::from_millis(10), |_| {
+            std::thread::sleep(Duration::from_millis(100));
+            error_reply(500, query_error(ErrorCode::InternalError, "failed"))
+        });
+        let mut reply = String::new();
+        client.read_to_string(&mut reply).unwrap();
+        // the status line was sent before the request failed
+        assert!(reply.starts_with("HTTP/1.1 200 OK"));
+        assert!(reply.contains("\"error\":{"));
+        assert!(reply.ends_with("\r\n0\r\nX-Compass-Status: 500\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_client_disconnect_cancels_request() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let (request, client) = accept_request(&server);
+        drop(client);
+        let mut cancelled = false;
+        reply_when_done(request, Duration::from_millis(10), |cancellation| {
+            for _ in 0..500 {
+                if cancellation.is_cancelled() {
+                    cancelled = true;
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            (200, json!({}))
+        });
+        assert!(
+            cancelled,
+            "request should be cancelled once its client disconnects"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use clap::Parser;
+
+use crate::app::compass::{
+    compass_app_error::CompassAppError,
+    config::compass_configuration_error::CompassConfigurationError,
+};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct ServerArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    pub port: u16,
+
+    /// Number of requests handled at the same time. Additional requests wait in a queue
+    #[arg(short, long, default_value_t = 4)]
+    pub workers: usize,
+
+    /// Maximum number of queries accepted in a single batch request
+    #[arg(long, default_value_t = 10_000)]
+    pub max_batch_size: usize,
+
+    /// Maximum size of a request body in bytes. Larger requests are rejected before they are read
+    #[arg(long, default_value_t = 64 * 1024 * 1024)]
+    pub max_body_bytes: usize,
+}
+
+impl ServerArgs {
+    pub fn validate(&self) -> Result<(), CompassAppError> {
+        if self.workers == 0 {
+            return Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::UserConfigurationError(String::from(
+                    "workers must be positive",
+                )),
+            ));
+        }
+        if self.max_batch_size == 0 {
+            return Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::UserConfigurationError(String::from(
+                    "max_batch_size must be positive",
+                )),
+            ));
+        }
+        if self.max_body_bytes == 0 {
+            return Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::UserConfigurationError(String::from(
+                    "max_body_bytes must be positive",
+                )),
+            ));
+        }
+        Ok(())
+    }
+}
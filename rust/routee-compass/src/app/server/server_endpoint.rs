@@ -0,0 +1,113 @@
+use std::fmt::Display;
+
+/// the REST endpoints served by the compass server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerEndpoint {
+    /// `GET /health`, reports that the server is up
+    Health,
+    /// `POST /route`, runs a single query
+    Route,
+    /// `POST /batch`, runs an array of queries, or an object with a "queries" array
+    Batch,
+    /// `POST /speeds`, replaces the speeds of some edges in the running app
+    Speeds,
+    /// `POST /cancel`, aborts a running request by the id in its `X-Request-Id` header
+    Cancel,
+    /// `POST /shutdown`, stops the server once the requests already received have run
+    Shutdown,
+}
+
+/// a request which does not match any endpoint, along with the HTTP status to reply with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointNotFound {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ServerEndpoint {
+    /// matches the method and url of a request to an endpoint. query strings are ignored.
+    pub fn from_request(method: &str, url: &str) -> Result<ServerEndpoint, EndpointNotFound> {
+        let path = url
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        let (endpoint, expected_method) = match path {
+            "/health" => (ServerEndpoint::Health, "GET"),
+            "/route" => (ServerEndpoint::Route, "POST"),
+            "/batch" => (ServerEndpoint::Batch, "POST"),
+            "/speeds" => (ServerEndpoint::Speeds, "POST"),
+            "/cancel" => (ServerEndpoint::Cancel, "POST"),
+            "/shutdown" => (ServerEndpoint::Shutdown, "POST"),
+            _ => {
+                return Err(EndpointNotFound {
+                    status: 404,
+                    message: format!("no endpoint at {}", path),
+                })
+            }
+        };
+        if method.eq_ignore_ascii_case(expected_method) {
+            Ok(endpoint)
+        } else {
+            Err(EndpointNotFound {
+                status: 405,
+                message: format!("{} expects {}, found {}", endpoint, expected_method, method),
+            })
+        }
+    }
+}
+
+impl Display for ServerEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ServerEndpoint::Health => "/health",
+            ServerEndpoint::Route => "/route",
+            ServerEndpoint::Batch => "/batch",
+            ServerEndpoint::Speeds => "/speeds",
+            ServerEndpoint::Cancel => "/cancel",
+            ServerEndpoint::Shutdown => "/shutdown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_request() {
+        assert_eq!(
+            ServerEndpoint::from_request("POST", "/route"),
+            Ok(ServerEndpoint::Route)
+        );
+        assert_eq!(
+            ServerEndpoint::from_request("post", "/batch/?verbose=true"),
+            Ok(ServerEndpoint::Batch)
+        );
+        assert_eq!(
+            ServerEndpoint::from_request("GET", "/health"),
+            Ok(ServerEndpoint::Health)
+        );
+        assert_eq!(
+            ServerEndpoint::from_request("POST", "/speeds"),
+            Ok(ServerEndpoint::Speeds)
+        );
+        assert_eq!(
+            ServerEndpoint::from_request("POST", "/cancel"),
+            Ok(ServerEndpoint::Cancel)
+        );
+        assert_eq!(
+            ServerEndpoint::from_request("POST", "/shutdown"),
+            Ok(ServerEndpoint::Shutdown)
+        );
+        assert_eq!(
+            ServerEndpoint::from_request("GET", "/route").map_err(|e| e.status),
+            Err(405)
+        );
+        assert_eq!(
+            ServerEndpoint::from_request("POST", "/isochrone").map_err(|e| e.status),
+            Err(404)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use routee_compass_core::model::{
+    road_network::edge_id::EdgeId,
+    unit::{Speed, SpeedUnit},
+};
+use serde::Deserialize;
+
+/// body of a `POST /speeds` request, which replaces the speeds of some edges.
+///
+/// ```json
+/// {
+///   "speed_unit": "kilometers_per_hour",
+///   "speeds": [{ "edge_id": 0, "speed": 40.0 }]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct SpeedUpdate {
+    pub speed_unit: SpeedUnit,
+    pub speeds: Vec<EdgeSpeed>,
+}
+
+/// the new speed of a single edge
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct EdgeSpeed {
+    pub edge_id: EdgeId,
+    pub speed: Speed,
+}
+
+impl SpeedUpdate {
+    /// the updates as edge id and speed pairs
+    pub fn edge_speeds(&self) -> Vec<(EdgeId, Speed)> {
+        self.speeds.iter().map(|s| (s.edge_id, s.speed)).collect()
+    }
+}
@@ -0,0 +1,24 @@
+use clap::Parser;
+use log::error;
+use routee_compass::app::cli::graph_command::{run_graph_command, GraphCommand};
+use std::process::ExitCode;
+
+/// tools for preparing RouteE Compass road network graphs
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct GraphCliArgs {
+    #[command(subcommand)]
+    command: GraphCommand,
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+
+    let args = GraphCliArgs::parse();
+    if let Err(e) = run_graph_command(&args.command) {
+        error!("{}", e);
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
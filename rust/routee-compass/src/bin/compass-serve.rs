@@ -0,0 +1,19 @@
+use clap::Parser;
+use log::error;
+use routee_compass::app::compass::config::compass_app_builder::CompassAppBuilder;
+use routee_compass::app::server::serve::run_server;
+use routee_compass::app::server::server_args::ServerArgs;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    env_logger::init();
+
+    let args = ServerArgs::parse();
+    let builder = CompassAppBuilder::default();
+    if let Err(e) = run_server(&args, Some(builder)) {
+        error!("{}", e);
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -1,4 +1,5 @@
 #![doc = include_str!("doc.md")]
+#![allow(clippy::result_large_err)]
 
 pub mod app;
 pub mod plugin;
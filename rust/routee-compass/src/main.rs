@@ -1,18 +1,28 @@
 use clap::Parser;
 use log::error;
-use routee_compass::app::cli::cli_args::CliArgs;
+use routee_compass::app::cli::cli_args::{CliArgs, CliCommand};
+use routee_compass::app::cli::graph_command::run_graph_command;
 use routee_compass::app::cli::run;
+use routee_compass::app::cli::validate::validate_dataset;
 use routee_compass::app::compass::config::compass_app_builder::CompassAppBuilder;
+use routee_compass::app::server::serve::run_server;
+use std::process::ExitCode;
 
-fn main() {
+fn main() -> ExitCode {
     env_logger::init();
 
     let args = CliArgs::parse();
     let builder = CompassAppBuilder::default();
-    match run::command_line_runner(&args, Some(builder), None) {
-        Ok(_) => {}
-        Err(e) => {
-            error!("{}", e.to_string())
-        }
+    let result = match &args.command {
+        CliCommand::Route(route_args) => run::command_line_runner(route_args, Some(builder), None),
+        CliCommand::Serve(server_args) => run_server(server_args, Some(builder)),
+        CliCommand::Validate(validate_args) => validate_dataset(validate_args, Some(builder)),
+        CliCommand::Graph { command } => run_graph_command(command),
+    };
+    if let Err(e) = result {
+        error!("{}", e);
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
     }
 }
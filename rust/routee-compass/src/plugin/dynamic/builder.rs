@@ -0,0 +1,46 @@
+use super::{
+    dynamic_library::DynamicLibrary,
+    plugin::{DynamicInputPlugin, DynamicOutputPlugin},
+};
+use crate::{
+    app::compass::config::{
+        builders::{InputPluginBuilder, OutputPluginBuilder},
+        compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::{input::input_plugin::InputPlugin, output::output_plugin::OutputPlugin},
+};
+use std::sync::Arc;
+
+/// builds plugins from a shared library referenced by the `library_input_file` key. the
+/// whole plugin configuration is passed to the library's init function, so it may hold
+/// any parameters of the library.
+pub struct DynamicPluginBuilder {}
+
+impl DynamicPluginBuilder {
+    fn load(parameters: &serde_json::Value) -> Result<DynamicLibrary, CompassConfigurationError> {
+        let library_file = parameters.get_config_path(&"library_input_file", &"dynamic")?;
+        DynamicLibrary::open(&library_file, parameters)
+            .map_err(CompassConfigurationError::PluginError)
+    }
+}
+
+impl InputPluginBuilder for DynamicPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
+        let library = DynamicPluginBuilder::load(parameters)?;
+        Ok(Arc::new(DynamicInputPlugin { library }))
+    }
+}
+
+impl OutputPluginBuilder for DynamicPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let library = DynamicPluginBuilder::load(parameters)?;
+        Ok(Arc::new(DynamicOutputPlugin { library }))
+    }
+}
@@ -0,0 +1,208 @@
+use crate::plugin::plugin_error::PluginError;
+use std::path::Path;
+
+/// symbol called with a JSON document, returning a JSON document allocated by the library
+pub const PROCESS_SYMBOL: &str = "compass_plugin_process";
+/// symbol called to release a document returned by [`PROCESS_SYMBOL`]
+pub const FREE_SYMBOL: &str = "compass_plugin_free";
+/// optional symbol called once with the JSON plugin configuration, returning 0 on success
+pub const INIT_SYMBOL: &str = "compass_plugin_init";
+/// key of a returned JSON object which reports that processing failed
+pub const PLUGIN_ERROR_KEY: &str = "plugin_error";
+
+#[cfg(unix)]
+type ProcessFn = unsafe extern "C" fn(*const libc::c_char) -> *mut libc::c_char;
+#[cfg(unix)]
+type FreeFn = unsafe extern "C" fn(*mut libc::c_char);
+#[cfg(unix)]
+type InitFn = unsafe extern "C" fn(*const libc::c_char) -> libc::c_int;
+
+/// a shared library implementing a plugin with a C ABI. documents are passed to and from
+/// the library as null-terminated UTF-8 JSON strings:
+///
+/// ```c
+/// char *compass_plugin_process(const char *json);
+/// void compass_plugin_free(char *json);
+/// int compass_plugin_init(const char *config_json); // optional
+/// ```
+///
+/// `compass_plugin_process` is called from many threads at once, so it must be thread safe.
+/// it reports a failure by returning null, or an object with a `plugin_error` message.
+pub struct DynamicLibrary {
+    name: String,
+    #[cfg(unix)]
+    handle: *mut libc::c_void,
+    #[cfg(unix)]
+    process: ProcessFn,
+    #[cfg(unix)]
+    free: FreeFn,
+}
+
+// the library handle and function pointers are never mutated after loading, and the
+// library is required to be thread safe
+unsafe impl Send for DynamicLibrary {}
+unsafe impl Sync for DynamicLibrary {}
+
+impl DynamicLibrary {
+    /// loads a plugin library, calling its init function with the plugin configuration
+    pub fn open(path: &Path, config: &serde_json::Value) -> Result<DynamicLibrary, PluginError> {
+        DynamicLibrary::open_with_symbols(path, PROCESS_SYMBOL, FREE_SYMBOL, Some(config))
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn open_with_symbols(
+        path: &Path,
+        process_symbol: &str,
+        free_symbol: &str,
+        config: Option<&serde_json::Value>,
+    ) -> Result<DynamicLibrary, PluginError> {
+        use std::ffi::{CStr, CString};
+
+        let name = path.to_string_lossy().to_string();
+        let c_path = CString::new(name.clone()).map_err(|e| {
+            PluginError::PluginFailed(format!("invalid library path {}: {}", name, e))
+        })?;
+        let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+        if handle.is_null() {
+            let reason = unsafe {
+                let err = libc::dlerror();
+                if err.is_null() {
+                    String::from("unknown error")
+                } else {
+                    CStr::from_ptr(err).to_string_lossy().to_string()
+                }
+            };
+            return Err(PluginError::PluginFailed(format!(
+                "unable to load plugin library {}: {}",
+                name, reason
+            )));
+        }
+        let symbol = |symbol_name: &str| -> Result<*mut libc::c_void, PluginError> {
+            let c_name =
+                CString::new(symbol_name).map_err(|e| PluginError::InternalError(e.to_string()))?;
+            let ptr = unsafe { libc::dlsym(handle, c_name.as_ptr()) };
+            if ptr.is_null() {
+                Err(PluginError::PluginFailed(format!(
+                    "plugin library {} does not export '{}'",
+                    name, symbol_name
+                )))
+            } else {
+                Ok(ptr)
+            }
+        };
+        let loaded = symbol(process_symbol).and_then(|process| {
+            let free = symbol(free_symbol)?;
+            Ok(DynamicLibrary {
+                name: name.clone(),
+                handle,
+                process: unsafe { std::mem::transmute::<*mut libc::c_void, ProcessFn>(process) },
+                free: unsafe { std::mem::transmute::<*mut libc::c_void, FreeFn>(free) },
+            })
+        });
+        // no DynamicLibrary owns the handle until both symbols are found
+        let library = match loaded {
+            Ok(library) => library,
+            Err(e) => {
+                unsafe { libc::dlclose(handle) };
+                return Err(e);
+            }
+        };
+
+        if let Some(config) = config {
+            if let Ok(init) = symbol(INIT_SYMBOL) {
+                let init = unsafe { std::mem::transmute::<*mut libc::c_void, InitFn>(init) };
+                let c_config = CString::new(config.to_string())
+                    .map_err(|e| PluginError::InternalError(e.to_string()))?;
+                let status = unsafe { init(c_config.as_ptr()) };
+                if status != 0 {
+                    return Err(PluginError::PluginFailed(format!(
+                        "plugin library {} failed to initialize with status {}",
+                        name, status
+                    )));
+                }
+            }
+        }
+        Ok(library)
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn open_with_symbols(
+        path: &Path,
+        _process_symbol: &str,
+        _free_symbol: &str,
+        _config: Option<&serde_json::Value>,
+    ) -> Result<DynamicLibrary, PluginError> {
+        Err(PluginError::PluginFailed(format!(
+            "unable to load plugin library {}, dynamic plugins are only supported on unix platforms",
+            path.to_string_lossy()
+        )))
+    }
+
+    /// passes a JSON document through the library
+    #[cfg(unix)]
+    pub fn process(&self, input: &serde_json::Value) -> Result<serde_json::Value, PluginError> {
+        use std::ffi::{CStr, CString};
+
+        let c_input = CString::new(input.to_string())
+            .map_err(|e| PluginError::InternalError(e.to_string()))?;
+        let c_output = unsafe { (self.process)(c_input.as_ptr()) };
+        if c_output.is_null() {
+            return Err(PluginError::PluginFailed(format!(
+                "plugin library {} failed to process a document",
+                self.name
+            )));
+        }
+        let output = unsafe { CStr::from_ptr(c_output) }
+            .to_str()
+            .map(String::from)
+            .map_err(|e| PluginError::InternalError(e.to_string()));
+        unsafe { (self.free)(c_output) };
+        let output: serde_json::Value = serde_json::from_str(&output?)?;
+        match output.get(PLUGIN_ERROR_KEY) {
+            Some(message) => Err(PluginError::PluginFailed(format!(
+                "plugin library {} failed: {}",
+                self.name,
+                message
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or(message.to_string())
+            ))),
+            None => Ok(output),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn process(&self, _input: &serde_json::Value) -> Result<serde_json::Value, PluginError> {
+        Err(PluginError::InternalError(format!(
+            "plugin library {} cannot run on this platform",
+            self.name
+        )))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for DynamicLibrary {
+    fn drop(&mut self) {
+        unsafe { libc::dlclose(self.handle) };
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_process_round_trip() {
+        // libc's strdup and free follow the plugin ABI, acting as a plugin which
+        // returns each document unchanged
+        let library =
+            DynamicLibrary::open_with_symbols(Path::new("libc.so.6"), "strdup", "free", None)
+                .unwrap();
+        let query = json!({"origin_x": -105.1, "destination_x": -104.9});
+        assert_eq!(library.process(&query).unwrap(), query);
+        assert!(library
+            .process(&json!({ "plugin_error": "bad query" }))
+            .is_err());
+    }
+}
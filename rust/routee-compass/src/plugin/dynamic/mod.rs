@@ -0,0 +1,3 @@
+pub mod builder;
+pub mod dynamic_library;
+pub mod plugin;
@@ -0,0 +1,40 @@
+use super::dynamic_library::DynamicLibrary;
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::{
+    input::input_plugin::InputPlugin, output::output_plugin::OutputPlugin,
+    plugin_error::PluginError,
+};
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+
+/// an input plugin implemented by a shared library. the library receives each query
+/// and returns the processed query, or an array of queries to expand it into many.
+pub struct DynamicInputPlugin {
+    pub library: DynamicLibrary,
+}
+
+impl InputPlugin for DynamicInputPlugin {
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        match self.library.process(input)? {
+            serde_json::Value::Array(queries) => Ok(queries),
+            query => Ok(vec![query]),
+        }
+    }
+}
+
+/// an output plugin implemented by a shared library. the library receives each response,
+/// including the output of the plugins listed before it, and returns the updated response.
+pub struct DynamicOutputPlugin {
+    pub library: DynamicLibrary,
+}
+
+impl OutputPlugin for DynamicOutputPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        _result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        *output = self.library.process(output)?;
+        Ok(())
+    }
+}
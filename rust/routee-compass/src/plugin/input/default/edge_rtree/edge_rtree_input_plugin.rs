@@ -17,17 +17,16 @@ use crate::{
 };
 use geo_types::Coord;
 use routee_compass_core::{
-    model::road_network::edge_id::EdgeId,
-    model::unit::{as_f64::AsF64, Distance, DistanceUnit, BASE_DISTANCE_UNIT},
-    util::{
-        fs::{read_decoders, read_utils},
-        geo::geo_io_utils::read_linestring_text_file,
-    },
+    algorithm::search::dead_end_policy::DeadEndPolicy,
+    model::road_network::{edge_geometry_store::EdgeGeometryStore, edge_id::EdgeId},
+    model::unit::{Distance, DistanceUnit, BASE_DISTANCE_UNIT},
+    util::fs::{read_decoders, read_utils},
 };
 use rstar::RTree;
 use std::{
     collections::{HashMap, HashSet},
     path::PathBuf,
+    sync::Arc,
 };
 
 pub struct EdgeRtreeInputPlugin {
@@ -49,19 +48,23 @@ pub struct EdgeRtreeInputPlugin {
 impl InputPlugin for EdgeRtreeInputPlugin {
     /// finds the nearest edge ids to the user-provided origin and destination coordinates.
     /// optionally restricts the search to a subset of road classes tagged by the user.
-    fn process(&self, query: &mut serde_json::Value) -> Result<(), PluginError> {
-        let road_classes = self.road_class_parser.read_query(query).map_err(|e| {
+    /// when the query has a destination and a strict dead end policy, the positions of
+    /// the coordinates along their edges are added as the query's edge offsets, unless
+    /// the query already provides them.
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        let mut query = input.clone();
+        let road_classes = self.road_class_parser.read_query(&query).map_err(|e| {
             PluginError::InputError(format!(
                 "Unable to apply EdgeRtree Input Plugin due to:\n\n{}",
                 e
             ))
         })?;
-        let vehicle_parameters = VehicleParameters::from_query(query).ok();
+        let vehicle_parameters = VehicleParameters::from_query(&query).ok();
 
         let src_coord = query.get_origin_coordinate()?;
         let dst_coord_option = query.get_destination_coordinate()?;
 
-        let source_edge_id = search(
+        let source = search(
             src_coord,
             &self.rtree,
             self.tolerance,
@@ -71,7 +74,7 @@ impl InputPlugin for EdgeRtreeInputPlugin {
             &vehicle_parameters,
         )?
         .ok_or_else(|| matching_error(&src_coord, self.tolerance))?;
-        let destination_edge_id_option = match dst_coord_option {
+        let destination_option = match dst_coord_option {
             None => Ok(None),
             Some(dst_coord) => search(
                 dst_coord,
@@ -86,15 +89,45 @@ impl InputPlugin for EdgeRtreeInputPlugin {
             .ok_or_else(|| matching_error(&dst_coord, self.tolerance)),
         }?;
 
-        query.add_origin_edge(source_edge_id)?;
-        match destination_edge_id_option {
-            None => {}
-            Some(destination_edge_id) => {
-                query.add_destination_edge(destination_edge_id)?;
+        query.add_origin_edge(source.edge_id)?;
+        if let (Some(destination), Some(dst_coord)) = (destination_option, dst_coord_option) {
+            query.add_destination_edge(destination.edge_id)?;
+            let no_offsets = query.get_origin_edge_offset()?.is_none()
+                && query.get_destination_edge_offset()?.is_none();
+            if no_offsets && query.get_dead_end_policy()? == DeadEndPolicy::Strict {
+                let origin_offset = source
+                    .locate(&geo::Point(src_coord))
+                    .map_err(PluginError::PluginFailed)?;
+                let destination_offset = destination
+                    .locate(&geo::Point(dst_coord))
+                    .map_err(PluginError::PluginFailed)?;
+                query.add_origin_edge_offset(origin_offset)?;
+                query.add_destination_edge_offset(destination_offset)?;
             }
         }
 
-        Ok(())
+        let waypoint_coords = query.get_waypoint_coordinates()?;
+        if !waypoint_coords.is_empty() {
+            let waypoint_edges = waypoint_coords
+                .iter()
+                .map(|coord| {
+                    search(
+                        *coord,
+                        &self.rtree,
+                        self.tolerance,
+                        &self.road_class_lookup,
+                        &road_classes,
+                        &self.vehicle_restrictions,
+                        &vehicle_parameters,
+                    )?
+                    .map(|record| record.edge_id)
+                    .ok_or_else(|| matching_error(coord, self.tolerance))
+                })
+                .collect::<Result<Vec<_>, PluginError>>()?;
+            query.add_waypoint_edges(waypoint_edges)?;
+        }
+
+        Ok(vec![query])
     }
 }
 
@@ -102,7 +135,7 @@ impl EdgeRtreeInputPlugin {
     pub fn new(
         road_class_file: Option<String>,
         vehicle_restriction_file: Option<String>,
-        linestring_file: String,
+        geometries: Arc<EdgeGeometryStore>,
         tolerance_distance: Option<Distance>,
         distance_unit: Option<DistanceUnit>,
         road_class_parser: RoadClassParser,
@@ -123,10 +156,6 @@ impl EdgeRtreeInputPlugin {
                 }
             };
 
-        let geometries = read_linestring_text_file(linestring_file)
-            .map_err(CompassConfigurationError::IoError)?
-            .into_vec();
-
         let rcl_len_opt = road_class_lookup.as_ref().map(|l| l.len());
         let geo_len = geometries.len();
         if let Some(rcl_len) = rcl_len_opt {
@@ -139,13 +168,7 @@ impl EdgeRtreeInputPlugin {
             }
         }
 
-        let records: Vec<EdgeRtreeRecord> = geometries
-            .into_iter()
-            .enumerate()
-            .map(|(idx, geom)| EdgeRtreeRecord::new(EdgeId(idx), geom))
-            .collect();
-
-        let rtree = RTree::bulk_load(records);
+        let rtree = RTree::bulk_load(EdgeRtreeRecord::from_geometries(&geometries));
 
         let tolerance = match (tolerance_distance, distance_unit) {
             (None, None) => None,
@@ -171,7 +194,7 @@ impl EdgeRtreeInputPlugin {
 /// * `coord` - coordinate from which to find a nearest edge
 /// * `rtree` - search tree containing all road network edges
 /// * `tolerance` - distance tolerance argument. if provided, result edge must be within this
-///                 distance/distance unit of the coord provided.
+///   distance/distance unit of the coord provided.
 /// * `road_class_lookup` - optional lookup table for road classes
 /// * `road_classes` - optional set of road classes to restrict search to
 /// * `vehicle_restrictions` - optional lookup table for truck restrictions
@@ -179,18 +202,21 @@ impl EdgeRtreeInputPlugin {
 ///
 /// # Result
 ///
-/// the EdgeId of the nearest edge that meets the tolerance requirement, if provided
-fn search(
+/// the record of the nearest edge that meets the tolerance requirement, if provided
+fn search<'a>(
     coord: Coord<f32>,
-    rtree: &RTree<EdgeRtreeRecord>,
+    rtree: &'a RTree<EdgeRtreeRecord>,
     tolerance: Option<(Distance, DistanceUnit)>,
     road_class_lookup: &Option<Vec<u8>>,
     road_classes: &Option<HashSet<u8>>,
     vehicle_restrictions: &Option<HashMap<EdgeId, Vec<VehicleRestriction>>>,
     vehicle_parameters: &Option<VehicleParameters>,
-) -> Result<Option<EdgeId>, PluginError> {
+) -> Result<Option<&'a EdgeRtreeRecord>, PluginError> {
     let point = geo::Point(coord);
-    for (record, distance_meters) in rtree.nearest_neighbor_iter_with_distance_2(&point) {
+    for record in rtree.nearest_neighbor_iter(&point) {
+        let distance_meters = record
+            .distance_meters(&point)
+            .map_err(PluginError::PluginFailed)?;
         if !within_tolerance(tolerance, &distance_meters) {
             return Ok(None);
         }
@@ -220,7 +246,7 @@ fn search(
             _ => true,
         };
         if valid_class && valid_truck {
-            return Ok(Some(record.edge_id));
+            return Ok(Some(record));
         }
     }
     Ok(None)
@@ -236,14 +262,14 @@ fn matching_error(coord: &Coord<f32>, tolerance: Option<(Distance, DistanceUnit)
 }
 
 /// helper to test if some distance in meters is within the optionally-provided tolerance
-fn within_tolerance(tolerance: Option<(Distance, DistanceUnit)>, distance_meters: &f32) -> bool {
+fn within_tolerance(
+    tolerance: Option<(Distance, DistanceUnit)>,
+    distance_meters: &Distance,
+) -> bool {
     match tolerance {
         None => true,
         Some((tolerance, distance_unit)) => {
-            let tolerance_meters = distance_unit
-                .convert(&tolerance, &DistanceUnit::Meters)
-                .as_f64() as f32;
-
+            let tolerance_meters = distance_unit.convert(&tolerance, &DistanceUnit::Meters);
             distance_meters <= &tolerance_meters
         }
     }
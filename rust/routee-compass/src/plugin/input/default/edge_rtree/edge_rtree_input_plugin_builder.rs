@@ -9,17 +9,39 @@ use crate::{
     },
     plugin::input::input_plugin::InputPlugin,
 };
-use routee_compass_core::model::unit::{Distance, DistanceUnit};
+use routee_compass_core::model::{
+    road_network::{edge_geometry_store::EdgeGeometryStore, graph::Graph},
+    unit::{Distance, DistanceUnit},
+};
 
+/// builds a plugin which matches query coordinates to the nearest edge, using the
+/// geometries loaded with the graph, or else those of a `geometry_input_file`.
 pub struct EdgeRtreeInputPluginBuilder {}
 
 impl InputPluginBuilder for EdgeRtreeInputPluginBuilder {
     fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from(
+                "edge_rtree: the edge rtree plugin must be built with the graph of the app",
+            ),
+        ))
+    }
+
+    fn build_with_graph(
         &self,
         parameters: &serde_json::Value,
+        graph: Arc<Graph>,
     ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
         let parent_key = String::from("edge_rtree");
-        let linestring_file = parameters.get_config_string(&"geometry_input_file", &parent_key)?;
+        let linestring_file =
+            parameters.get_config_path_optional(&"geometry_input_file", &parent_key)?;
+        let geometries = EdgeGeometryStore::from_graph_or_file(&graph, linestring_file.as_ref())
+            .map_err(|e| {
+                CompassConfigurationError::UserConfigurationError(format!("edge_rtree: {}", e))
+            })?;
         let road_class_file = parameters.get_config_string_optional(&"road_class_input_file")?;
         let vehicle_restriction_file =
             parameters.get_config_string_optional(&"vehicle_restriction_input_file")?;
@@ -39,7 +61,7 @@ impl InputPluginBuilder for EdgeRtreeInputPluginBuilder {
         let plugin = EdgeRtreeInputPlugin::new(
             road_class_file,
             vehicle_restriction_file,
-            linestring_file,
+            geometries,
             distance_tolerance_option,
             distance_unit_option,
             road_class_parser,
@@ -1,28 +1,89 @@
-use geo::{Centroid, LineString, Point};
-use routee_compass_core::model::road_network::edge_id::EdgeId;
+use geo::{Closest, ClosestPoint, LineString, Point};
+use routee_compass_core::{
+    model::{
+        road_network::{edge_geometry_store::EdgeGeometryStore, edge_id::EdgeId},
+        unit::Distance,
+    },
+    util::geo::haversine,
+};
 use rstar::{PointDistance, RTreeObject, AABB};
+use std::sync::Arc;
 
+/// an edge in a search tree, whose geometry is read from a store shared by every
+/// record, such as the geometries loaded with the graph.
 pub struct EdgeRtreeRecord {
     pub edge_id: EdgeId,
-    pub geometry: LineString<f32>,
+    envelope: AABB<Point<f32>>,
+    geometries: Arc<EdgeGeometryStore>,
 }
 
 impl EdgeRtreeRecord {
-    pub fn new(edge_id: EdgeId, geometry: LineString<f32>) -> EdgeRtreeRecord {
-        EdgeRtreeRecord { edge_id, geometry }
+    /// a record for each edge of a geometry store
+    pub fn from_geometries(geometries: &Arc<EdgeGeometryStore>) -> Vec<EdgeRtreeRecord> {
+        geometries
+            .as_slice()
+            .iter()
+            .enumerate()
+            .map(|(idx, geometry)| EdgeRtreeRecord {
+                edge_id: EdgeId(idx),
+                envelope: geometry.envelope(),
+                geometries: geometries.clone(),
+            })
+            .collect()
+    }
+
+    /// the geometry of this edge
+    pub fn geometry(&self) -> &LineString<f32> {
+        &self.geometries.as_slice()[self.edge_id.0]
+    }
+
+    /// the fraction of the length of this edge from its start to the point of its
+    /// geometry closest to `point`
+    pub fn locate(&self, point: &Point<f32>) -> Result<f64, String> {
+        self.geometries
+            .locate(self.edge_id, point)
+            .map_err(|e| e.to_string())
     }
 }
 
 impl RTreeObject for EdgeRtreeRecord {
     type Envelope = AABB<Point<f32>>;
     fn envelope(&self) -> Self::Envelope {
-        self.geometry.envelope()
+        self.envelope
+    }
+}
+
+impl EdgeRtreeRecord {
+    /// projects a point onto this edge's LineString, finding the closest point on the line.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - point to project onto the edge
+    ///
+    /// # Returns
+    ///
+    /// * the closest point on the edge, or None if the geometry is empty
+    pub fn project(&self, point: &Point<f32>) -> Option<Point<f32>> {
+        match self.geometry().closest_point(point) {
+            Closest::Intersection(p) => Some(p),
+            Closest::SinglePoint(p) => Some(p),
+            Closest::Indeterminate => None,
+        }
+    }
+
+    /// the haversine distance in meters from a point to its projection onto this edge.
+    pub fn distance_meters(&self, point: &Point<f32>) -> Result<Distance, String> {
+        let projected = self
+            .project(point)
+            .ok_or_else(|| format!("empty linestring for edge {}", self.edge_id))?;
+        haversine::coord_distance_meters(&point.0, &projected.0)
     }
 }
 
 impl PointDistance for EdgeRtreeRecord {
-    /// compares query nearness via the "centroid" of this LineString,
-    /// the midpoint of the bounding box of the line.
+    /// compares query nearness by projecting the query point onto this LineString,
+    /// so that long edges passing close to the point are preferred over short edges
+    /// whose midpoints happen to be nearby.
     ///
     /// # Arguments
     ///
@@ -30,11 +91,11 @@ impl PointDistance for EdgeRtreeRecord {
     ///
     /// # Returns
     ///
-    /// * distance in meters (assumes points are in WGS84)
+    /// * squared euclidean distance in degrees to the closest point on the line. use
+    ///   [`EdgeRtreeRecord::distance_meters`] for the distance in meters.
     fn distance_2(&self, point: &Point<f32>) -> f32 {
         let this_point = self
-            .geometry
-            .centroid()
+            .project(point)
             .unwrap_or_else(|| panic!("empty linestring in geometry file"));
         // as noted in the comments for PointDistance, this should return the squared distance.
         // haversine *should* work but squared haversine in meters is giving weird results for
@@ -48,3 +109,23 @@ impl PointDistance for EdgeRtreeRecord {
         dx * dx + dy * dy
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::LineString;
+    use rstar::RTree;
+
+    #[test]
+    fn test_nearest_edge_by_projection() {
+        // the long edge passes within 1 unit of the point but its midpoint is 5 away,
+        // while the short edge's midpoint is about 1.6 away but the edge is 1.5 away
+        let long_edge = LineString::from(vec![(0.0, 0.0), (10.0, 0.0)]);
+        let short_edge = LineString::from(vec![(0.0, 2.5), (1.0, 2.5)]);
+        let geometries =
+            EdgeGeometryStore::new(vec![long_edge, short_edge].into_boxed_slice(), 2).unwrap();
+        let rtree = RTree::bulk_load(EdgeRtreeRecord::from_geometries(&Arc::new(geometries)));
+        let nearest = rtree.nearest_neighbor(&Point::new(0.0, 1.0)).unwrap();
+        assert_eq!(nearest.edge_id, EdgeId(0));
+    }
+}
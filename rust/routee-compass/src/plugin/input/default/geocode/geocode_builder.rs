@@ -0,0 +1,24 @@
+use super::{
+    geocode_plugin::GeocodeInputPlugin, geocoder::Geocoder, geocoder_config::GeocoderConfig,
+};
+use crate::{
+    app::compass::config::{
+        builders::InputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::input::input_plugin::InputPlugin,
+};
+use std::sync::Arc;
+
+pub struct GeocodeBuilder {}
+
+impl InputPluginBuilder for GeocodeBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
+        let config: GeocoderConfig = parameters.get_config_serde(&"geocoder", &"geocode")?;
+        let geocoder = Geocoder::try_from(&config)?;
+        Ok(Arc::new(GeocodeInputPlugin { geocoder }))
+    }
+}
@@ -0,0 +1,80 @@
+use super::geocoder::Geocoder;
+use crate::plugin::{
+    input::{
+        input_field::InputField, input_json_extensions::InputJsonExtensions,
+        input_plugin::InputPlugin,
+    },
+    plugin_error::PluginError,
+};
+use geo::Coord;
+
+pub struct GeocodeInputPlugin {
+    pub geocoder: Geocoder,
+}
+
+impl InputPlugin for GeocodeInputPlugin {
+    /// resolves an `origin_address` and `destination_address` on the query to coordinates,
+    /// which are added as `origin_x`/`origin_y` and `destination_x`/`destination_y`. this
+    /// plugin should be listed before an rtree plugin, which then matches the coordinates
+    /// to the graph.
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        let mut query = input.clone();
+        if let Some(coord) = self.resolve(&query, InputField::OriginAddress)? {
+            query.add_origin_coordinate(coord)?;
+        }
+        if let Some(coord) = self.resolve(&query, InputField::DestinationAddress)? {
+            query.add_destination_coordinate(coord)?;
+        }
+        Ok(vec![query])
+    }
+}
+
+impl GeocodeInputPlugin {
+    /// geocodes the address in some field of the query, if present
+    fn resolve(
+        &self,
+        query: &serde_json::Value,
+        field: InputField,
+    ) -> Result<Option<Coord<f32>>, PluginError> {
+        let address = match query.get(field.to_str()) {
+            None => return Ok(None),
+            Some(value) => value.as_str().ok_or_else(|| {
+                PluginError::ParseError(field.to_string(), String::from("string"))
+            })?,
+        };
+        let coord = self.geocoder.geocode(address)?.ok_or_else(|| {
+            PluginError::InputError(format!("unable to geocode {} '{}'", field, address))
+        })?;
+        Ok(Some(coord))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_geocode_gazetteer() {
+        let geocoder = Geocoder::gazetteer(vec![
+            (
+                "15013 Denver West Pkwy, Golden, CO",
+                Coord::from((-105.17, 39.74)),
+            ),
+            ("1 Main St", Coord::from((-104.9, 39.68))),
+        ]);
+        let plugin = GeocodeInputPlugin { geocoder };
+        let query = json!({
+            "origin_address": "15013 denver west pkwy,  Golden, CO",
+            "destination_address": "1 MAIN ST"
+        });
+        let query = plugin.process(&query).unwrap().remove(0);
+        let origin = query.get_origin_coordinate().unwrap();
+        let destination = query.get_destination_coordinate().unwrap().unwrap();
+        assert_eq!(origin, Coord::from((-105.17, 39.74)));
+        assert_eq!(destination, Coord::from((-104.9, 39.68)));
+
+        let query = json!({ "origin_address": "unknown" });
+        assert!(plugin.process(&query).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use super::geocoder_config::GeocoderConfig;
+use crate::{
+    app::compass::config::compass_configuration_error::CompassConfigurationError,
+    plugin::plugin_error::PluginError,
+};
+use geo::Coord;
+use routee_compass_core::util::fs::read_utils;
+use serde::Deserialize;
+use std::{collections::HashMap, time::Duration};
+
+/// resolves address strings to WGS84 coordinates.
+pub enum Geocoder {
+    /// looks up addresses in a table loaded from a CSV file. addresses are
+    /// matched ignoring case and repeated whitespace.
+    Gazetteer {
+        entries: HashMap<String, Coord<f32>>,
+    },
+    /// queries the search API of a Nominatim server, taking the top result.
+    Nominatim {
+        endpoint: String,
+        agent: ureq::Agent,
+    },
+}
+
+#[derive(Deserialize)]
+struct GazetteerRow {
+    address: String,
+    x: f32,
+    y: f32,
+}
+
+/// the fields of a Nominatim search result used here. Nominatim returns
+/// coordinates as strings.
+#[derive(Deserialize)]
+struct NominatimPlace {
+    lat: String,
+    lon: String,
+}
+
+impl Geocoder {
+    const DEFAULT_TIMEOUT_SECONDS: u64 = 10;
+
+    /// builds a gazetteer from a list of (address, coordinate) entries
+    pub fn gazetteer<'a>(entries: impl IntoIterator<Item = (&'a str, Coord<f32>)>) -> Geocoder {
+        let entries = entries
+            .into_iter()
+            .map(|(address, coord)| (normalize_address(address), coord))
+            .collect();
+        Geocoder::Gazetteer { entries }
+    }
+
+    /// finds the coordinate of an address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - the address to resolve
+    ///
+    /// # Returns
+    ///
+    /// the coordinate, None if the address is not known, or an error if the
+    /// backend could not be reached
+    pub fn geocode(&self, address: &str) -> Result<Option<Coord<f32>>, PluginError> {
+        match self {
+            Geocoder::Gazetteer { entries } => {
+                Ok(entries.get(&normalize_address(address)).copied())
+            }
+            Geocoder::Nominatim { endpoint, agent } => {
+                let url = format!("{}/search", endpoint.trim_end_matches('/'));
+                let body = agent
+                    .get(&url)
+                    .query("q", address)
+                    .query("format", "json")
+                    .query("limit", "1")
+                    .call()
+                    .map_err(|e| {
+                        PluginError::PluginFailed(format!(
+                            "geocoding request to {} failed: {}",
+                            url, e
+                        ))
+                    })?
+                    .into_string()
+                    .map_err(|e| {
+                        PluginError::PluginFailed(format!(
+                            "unable to read geocoding response from {}: {}",
+                            url, e
+                        ))
+                    })?;
+                let places: Vec<NominatimPlace> = serde_json::from_str(&body)?;
+                match places.first() {
+                    None => Ok(None),
+                    Some(place) => {
+                        let x = place.lon.parse::<f32>().map_err(|_| {
+                            PluginError::ParseError(place.lon.clone(), String::from("f32"))
+                        })?;
+                        let y = place.lat.parse::<f32>().map_err(|_| {
+                            PluginError::ParseError(place.lat.clone(), String::from("f32"))
+                        })?;
+                        Ok(Some(Coord::from((x, y))))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<&GeocoderConfig> for Geocoder {
+    type Error = CompassConfigurationError;
+
+    fn try_from(config: &GeocoderConfig) -> Result<Self, Self::Error> {
+        match config {
+            GeocoderConfig::Gazetteer {
+                gazetteer_input_file,
+            } => {
+                let rows: Box<[GazetteerRow]> =
+                    read_utils::from_csv(&gazetteer_input_file, true, None).map_err(|e| {
+                        CompassConfigurationError::PluginError(PluginError::CsvReadError(e))
+                    })?;
+                Ok(Geocoder::gazetteer(rows.iter().map(|row| {
+                    (row.address.as_str(), Coord::from((row.x, row.y)))
+                })))
+            }
+            GeocoderConfig::Nominatim {
+                endpoint,
+                timeout_seconds,
+            } => {
+                let timeout = timeout_seconds.unwrap_or(Self::DEFAULT_TIMEOUT_SECONDS);
+                let agent = ureq::AgentBuilder::new()
+                    .timeout(Duration::from_secs(timeout))
+                    .build();
+                Ok(Geocoder::Nominatim {
+                    endpoint: endpoint.clone(),
+                    agent,
+                })
+            }
+        }
+    }
+}
+
+/// lowercases an address and collapses whitespace so that lookups are
+/// insensitive to formatting
+fn normalize_address(address: &str) -> String {
+    address
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// configures the backend used by the geocode input plugin.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GeocoderConfig {
+    /// a CSV file with the header `address,x,y` listing known addresses and their
+    /// WGS84 coordinates.
+    Gazetteer { gazetteer_input_file: String },
+    /// a Nominatim server, such as a local instance at `http://localhost:8080`.
+    Nominatim {
+        endpoint: String,
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+    },
+}
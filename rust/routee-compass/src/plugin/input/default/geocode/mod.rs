@@ -0,0 +1,4 @@
+pub mod geocode_builder;
+pub mod geocode_plugin;
+pub mod geocoder;
+pub mod geocoder_config;
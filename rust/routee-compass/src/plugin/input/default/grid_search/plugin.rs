@@ -9,9 +9,9 @@ use routee_compass_core::util::multiset::MultiSet;
 pub struct GridSearchPlugin {}
 
 impl InputPlugin for GridSearchPlugin {
-    fn process(&self, input: &mut serde_json::Value) -> Result<(), PluginError> {
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
         match input.get_grid_search() {
-            None => Ok(()),
+            None => Ok(vec![input.clone()]),
             Some(grid_search_input) => {
                 // prevent recursion due to nested grid search keys
                 let recurses = serde_json::to_string(grid_search_input)
@@ -69,9 +69,7 @@ impl InputPlugin for GridSearchPlugin {
                     })
                     .collect();
 
-                let mut replacement = serde_json::json![result];
-                std::mem::swap(&mut replacement, input);
-                Ok(())
+                Ok(result)
             }
         }
     }
@@ -85,14 +83,14 @@ mod test {
 
     #[test]
     fn test_grid_search_empty_parent_object() {
-        let mut input = serde_json::json!({
+        let input = serde_json::json!({
             "grid_search": {
                 "bar": ["a", "b", "c"],
                 "foo": [1.2, 3.4]
             }
         });
         let plugin = GridSearchPlugin {};
-        plugin.process(&mut input).unwrap();
+        let result = plugin.process(&input).unwrap();
         let expected = vec![
             json![{"bar":"a","foo":1.2}],
             json![{"bar":"b","foo":1.2}],
@@ -101,15 +99,12 @@ mod test {
             json![{"bar":"b","foo":3.4}],
             json![{"bar":"c","foo":3.4}],
         ];
-        match input {
-            serde_json::Value::Array(result) => assert_eq!(result, expected),
-            other => panic!("expected array result, found {}", other),
-        }
+        assert_eq!(result, expected);
     }
 
     #[test]
     fn test_grid_search_persisted_parent_keys() {
-        let mut input = serde_json::json!({
+        let input = serde_json::json!({
             "ignored_key": "ignored_value",
             "grid_search": {
                 "bar": ["a", "b", "c"],
@@ -117,7 +112,7 @@ mod test {
             }
         });
         let plugin = GridSearchPlugin {};
-        plugin.process(&mut input).unwrap();
+        let result = plugin.process(&input).unwrap();
 
         let expected = vec![
             json![{"bar":"a","foo":1.2,"ignored_key": "ignored_value"}],
@@ -127,15 +122,12 @@ mod test {
             json![{"bar":"b","foo":3.4,"ignored_key": "ignored_value"}],
             json![{"bar":"c","foo":3.4,"ignored_key": "ignored_value"}],
         ];
-        match input {
-            serde_json::Value::Array(result) => assert_eq!(result, expected),
-            other => panic!("expected array result, found {}", other),
-        }
+        assert_eq!(result, expected);
     }
 
     #[test]
     fn test_grid_search_using_objects() {
-        let mut input = serde_json::json!({
+        let input = serde_json::json!({
             "ignored_key": "ignored_value",
             "grid_search": {
                 "a": [1, 2],
@@ -146,23 +138,19 @@ mod test {
             }
         });
         let plugin = GridSearchPlugin {};
-        plugin.process(&mut input).unwrap();
+        let result = plugin.process(&input).unwrap();
         let expected = vec![
             json![{"a":1,"ignored_key":"ignored_value","x":0,"y":0}],
             json![{"a":2,"ignored_key":"ignored_value","x":0,"y":0}],
             json![{"a":1,"ignored_key":"ignored_value","x":1,"y":1}],
             json![ {"a":2,"ignored_key":"ignored_value","x":1,"y":1}],
         ];
-        // assert_eq!(result, expected);
-        match input {
-            serde_json::Value::Array(result) => assert_eq!(result, expected),
-            other => panic!("expected array result, found {}", other),
-        }
+        assert_eq!(result, expected);
     }
 
     #[test]
     fn test_nested() {
-        let mut input = serde_json::json!({
+        let input = serde_json::json!({
             "abc": 123,
             "grid_search":{
                 "model_name": ["2016_TOYOTA_Camry_4cyl_2WD","2017_CHEVROLET_Bolt"],
@@ -174,7 +162,7 @@ mod test {
             }
         });
         let plugin = GridSearchPlugin {};
-        plugin.process(&mut input).unwrap();
+        let result = plugin.process(&input).unwrap();
         let expected = vec![
             json![{"abc":123,"model_name":"2016_TOYOTA_Camry_4cyl_2WD","name":"d1","weights":{"distance":1,"energy_electric":0,"time":0}}],
             json![{"abc":123,"model_name":"2016_TOYOTA_Camry_4cyl_2WD","name":"t1","weights":{"distance":0,"energy_electric":0,"time":1}}],
@@ -183,15 +171,12 @@ mod test {
             json![{"abc":123,"model_name":"2017_CHEVROLET_Bolt","name":"t1","weights":{"distance":0,"energy_electric":0,"time":1}}],
             json![{"abc":123,"model_name":"2017_CHEVROLET_Bolt","name":"e1","weights":{"distance":0,"energy_electric":1,"time":0}}],
         ];
-        match input {
-            serde_json::Value::Array(result) => assert_eq!(result, expected),
-            other => panic!("expected array result, found {}", other),
-        }
+        assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_handle_recursion() {
-        let mut input = serde_json::json!({
+        let input = serde_json::json!({
             "abc": 123,
             "grid_search":{
                 "grid_search": {
@@ -200,7 +185,7 @@ mod test {
             }
         });
         let plugin = GridSearchPlugin {};
-        let result = plugin.process(&mut input);
+        let result = plugin.process(&input);
         assert!(result.is_err());
     }
 }
@@ -12,8 +12,9 @@ impl InjectInputPlugin {
 }
 
 impl InputPlugin for InjectInputPlugin {
-    fn process(&self, input: &mut serde_json::Value) -> Result<(), PluginError> {
-        input[self.key.clone()] = self.value.clone();
-        Ok(())
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        let mut query = input.clone();
+        query[self.key.clone()] = self.value.clone();
+        Ok(vec![query])
     }
 }
@@ -9,7 +9,6 @@ use crate::{
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case", tag = "type")]
-
 pub enum CustomWeightType {
     /// a weight value found on each query which can be used directly as it is a numeric
     /// field. will use provided column_name or fall back to InputField::QueryWeightEstimate.
@@ -8,10 +8,10 @@ pub struct LoadBalancerPlugin {
 }
 
 impl InputPlugin for LoadBalancerPlugin {
-    fn process(&self, query: &mut serde_json::Value) -> Result<(), PluginError> {
-        let w = self.heuristic.estimate_weight(query)?;
-        let _updated = query.clone();
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        let w = self.heuristic.estimate_weight(input)?;
+        let mut query = input.clone();
         query.add_query_weight_estimate(w)?;
-        Ok(())
+        Ok(vec![query])
     }
 }
@@ -0,0 +1,467 @@
+use crate::plugin::{
+    input::default::edge_rtree::edge_rtree_record::EdgeRtreeRecord, plugin_error::PluginError,
+};
+use geo::{Coord, Point};
+use routee_compass_core::{
+    model::{
+        road_network::{edge_id::EdgeId, graph::Graph, vertex_id::VertexId},
+        unit::{as_f64::AsF64, Cost},
+    },
+    util::geo::haversine,
+};
+use rstar::{RTree, AABB};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    sync::Arc,
+};
+
+/// approximate number of meters in a degree of latitude
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// routes between consecutive candidates are abandoned once they are this many times
+/// longer than the great circle distance between the observations (plus the search radius)
+const MAX_ROUTE_FACTOR: f64 = 5.0;
+
+/// network distance in meters between two candidates along with the edges traversed
+type CandidateRoute = (f64, Vec<EdgeId>);
+
+/// index of the previous candidate on the best path to a candidate, with the connecting route
+type Backpointer = Option<(usize, Vec<EdgeId>)>;
+
+/// a GPS observation with an optional timestamp in seconds
+pub type TracePoint = (Coord<f32>, Option<f64>);
+
+/// the result of matching a trace to the road network
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchedTrace {
+    /// the sequence of edges traveled
+    pub edge_ids: Vec<EdgeId>,
+    /// indices of trace points skipped because no edge was within the search radius
+    pub unmatched: Vec<usize>,
+}
+
+/// a road network edge which may have produced a GPS observation
+#[derive(Clone, Copy, Debug)]
+struct Candidate {
+    edge_id: EdgeId,
+    /// position of the projected observation along the edge, from 0 (start) to 1 (end)
+    fraction: f64,
+    /// distance in meters from the observation to the edge
+    distance: f64,
+}
+
+/// hidden Markov model map matcher following Newson and Krumm (2009). each GPS
+/// observation is snapped to the nearby edges within a search radius, which are
+/// the hidden states. emission probabilities fall off with the distance from the
+/// observation to the edge (a gaussian with deviation `gps_sigma`), and transition
+/// probabilities fall off with the difference between the network distance and the
+/// great circle distance between observations (an exponential with scale `beta`).
+/// the most likely sequence of edges is found with the Viterbi algorithm. when the
+/// observations have timestamps, routes which could not be traveled between them
+/// at `max_speed` are not considered.
+pub struct HmmMatcher {
+    pub graph: Arc<Graph>,
+    pub rtree: RTree<EdgeRtreeRecord>,
+    /// observations are only matched to edges within this many meters
+    pub search_radius: f64,
+    /// standard deviation of GPS error, in meters
+    pub gps_sigma: f64,
+    /// scale of the difference between route and great circle distances, in meters
+    pub beta: f64,
+    /// maximum number of candidate edges kept for each observation
+    pub max_candidates: usize,
+    /// maximum speed between timestamped observations, in meters per second
+    pub max_speed: f64,
+    /// if true, observations without any candidate edges are skipped, otherwise
+    /// they are an error
+    pub skip_unmatched_points: bool,
+}
+
+impl HmmMatcher {
+    /// matches a trace of WGS84 coordinates to the road network.
+    ///
+    /// # Arguments
+    ///
+    /// * `trace` - GPS observations in the order they were recorded, with
+    ///   timestamps in seconds for either all or none of them
+    ///
+    /// # Returns
+    ///
+    /// the sequence of edges traveled, beginning with the edge matched to the first
+    /// observation and ending with the edge matched to the last. observations without
+    /// any candidate edges within the search radius are an error, unless the matcher
+    /// skips them, in which case their indices are returned.
+    pub fn match_trace(&self, trace: &[TracePoint]) -> Result<MatchedTrace, PluginError> {
+        validate_timestamps(trace)?;
+        let mut observations = vec![];
+        let mut unmatched = vec![];
+        for (idx, (coord, timestamp)) in trace.iter().enumerate() {
+            let candidates = self.candidates(coord)?;
+            if !candidates.is_empty() {
+                observations.push((*coord, *timestamp, candidates));
+            } else if self.skip_unmatched_points {
+                log::debug!("map matching skipped trace point {} at {:?}", idx, coord);
+                unmatched.push(idx);
+            } else {
+                return Err(PluginError::InputError(format!(
+                    "trace point {} at {:?} is not within {} meters of a road network edge",
+                    idx, coord, self.search_radius
+                )));
+            }
+        }
+        let (first_coord, first_timestamp, first_candidates) =
+            observations.first().ok_or_else(|| {
+                PluginError::InputError(format!(
+                    "no observation in the trace is within {} meters of a road network edge",
+                    self.search_radius
+                ))
+            })?;
+
+        // scores[i] is the best log probability of a path ending at candidate i. for each
+        // observation after the first, back[j] holds the index of the previous candidate
+        // on the best path to candidate j and the edges which connect them.
+        let mut scores = first_candidates
+            .iter()
+            .map(|c| self.emission(c))
+            .collect::<Vec<_>>();
+        let mut backpointers: Vec<Vec<Backpointer>> = vec![];
+        let mut prev_coord = *first_coord;
+        let mut prev_timestamp = *first_timestamp;
+        let mut prev_candidates = first_candidates;
+
+        for (step, (coord, timestamp, candidates)) in observations.iter().enumerate().skip(1) {
+            let great_circle = distance_meters(&prev_coord, coord)?;
+            let mut max_distance = great_circle * MAX_ROUTE_FACTOR + 2.0 * self.search_radius;
+            if let (Some(t0), Some(t1)) = (prev_timestamp, timestamp) {
+                let reachable = self.max_speed * (t1 - t0) + 2.0 * self.search_radius;
+                max_distance = max_distance.min(reachable);
+            }
+            let routes = prev_candidates
+                .iter()
+                .map(|a| self.routes_from(a, candidates, max_distance))
+                .collect::<Result<Vec<_>, PluginError>>()?;
+
+            let mut next_scores = vec![f64::NEG_INFINITY; candidates.len()];
+            let mut back = vec![None; candidates.len()];
+            for (j, b) in candidates.iter().enumerate() {
+                for (i, score) in scores.iter().enumerate() {
+                    if let Some((route_distance, path)) = &routes[i][j] {
+                        let transition = -(route_distance - great_circle).abs() / self.beta;
+                        let next = score + transition + self.emission(b);
+                        if next > next_scores[j] {
+                            next_scores[j] = next;
+                            back[j] = Some((i, path.clone()));
+                        }
+                    }
+                }
+            }
+            if next_scores.iter().all(|s| s.is_infinite()) {
+                return Err(PluginError::InputError(format!(
+                    "unable to find a route between trace observations {:?} and {:?}",
+                    prev_coord, coord
+                )));
+            }
+            log::debug!(
+                "map matching step {} has {} candidates",
+                step,
+                candidates.len()
+            );
+            scores = next_scores;
+            backpointers.push(back);
+            prev_coord = *coord;
+            prev_timestamp = *timestamp;
+            prev_candidates = candidates;
+        }
+
+        // walk back from the most likely final candidate
+        let (mut idx, _) = scores
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .ok_or_else(|| PluginError::InternalError(String::from("empty candidate set")))?;
+        let mut segments: Vec<Vec<EdgeId>> = vec![];
+        for back in backpointers.iter().rev() {
+            let (prev_idx, path) = back[idx].clone().ok_or_else(|| {
+                PluginError::InternalError(String::from("map matching path is broken"))
+            })?;
+            segments.push(path);
+            idx = prev_idx;
+        }
+        let mut edge_ids = vec![first_candidates[idx].edge_id];
+        for edge_id in segments.into_iter().rev().flatten() {
+            if edge_ids.last() != Some(&edge_id) {
+                edge_ids.push(edge_id);
+            }
+        }
+        Ok(MatchedTrace {
+            edge_ids,
+            unmatched,
+        })
+    }
+
+    /// finds the edges near an observation along with the position of the observation
+    /// projected onto each edge, nearest first.
+    fn candidates(&self, coord: &Coord<f32>) -> Result<Vec<Candidate>, PluginError> {
+        let dy = self.search_radius / METERS_PER_DEGREE;
+        let dx = dy / (coord.y as f64).to_radians().cos().max(0.01);
+        let envelope = AABB::from_corners(
+            Point::new(coord.x - dx as f32, coord.y - dy as f32),
+            Point::new(coord.x + dx as f32, coord.y + dy as f32),
+        );
+        let point = Point(*coord);
+        let mut candidates = vec![];
+        for record in self.rtree.locate_in_envelope_intersecting(&envelope) {
+            let closest = match record.project(&point) {
+                Some(p) => p,
+                None => continue,
+            };
+            let distance = distance_meters(coord, &closest.0)?;
+            if distance > self.search_radius {
+                continue;
+            }
+            let fraction = record.locate(&closest).map_err(PluginError::PluginFailed)?;
+            candidates.push(Candidate {
+                edge_id: record.edge_id,
+                fraction,
+                distance,
+            });
+        }
+        candidates.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        candidates.truncate(self.max_candidates);
+        Ok(candidates)
+    }
+
+    /// log probability that an observation was recorded on a candidate edge
+    fn emission(&self, candidate: &Candidate) -> f64 {
+        -0.5 * (candidate.distance / self.gps_sigma).powi(2)
+    }
+
+    /// finds the network distance from a candidate to each of the next candidates
+    /// along with the edges traversed, for routes up to some maximum distance. the path begins
+    /// with the edge of `src` and ends with the edge of the next candidate.
+    fn routes_from(
+        &self,
+        src: &Candidate,
+        next: &[Candidate],
+        max_distance: f64,
+    ) -> Result<Vec<Option<CandidateRoute>>, PluginError> {
+        let src_edge = self.graph.get_edge(src.edge_id).map_err(graph_error)?;
+        let src_remaining = (1.0 - src.fraction) * src_edge.distance.as_f64();
+        let tree = self.shortest_paths(src_edge.dst_vertex_id, max_distance - src_remaining)?;
+        next.iter()
+            .map(|dst| {
+                let dst_edge = self.graph.get_edge(dst.edge_id).map_err(graph_error)?;
+                if dst.edge_id == src.edge_id && dst.fraction >= src.fraction {
+                    let distance = (dst.fraction - src.fraction) * src_edge.distance.as_f64();
+                    let route = Some((distance, vec![src.edge_id]));
+                    return Ok(route.filter(|(total, _)| *total <= max_distance));
+                }
+                let route = tree.get(&dst_edge.src_vertex_id).map(|(distance, _)| {
+                    let mut path = vec![dst.edge_id];
+                    let mut vertex_id = dst_edge.src_vertex_id;
+                    while let Some((_, Some(edge_id))) = tree.get(&vertex_id) {
+                        path.push(*edge_id);
+                        vertex_id = self.graph.edges[edge_id.0].src_vertex_id;
+                    }
+                    path.push(src.edge_id);
+                    path.reverse();
+                    let total =
+                        src_remaining + distance + dst.fraction * dst_edge.distance.as_f64();
+                    (total, path)
+                });
+                Ok(route.filter(|(total, _)| *total <= max_distance))
+            })
+            .collect()
+    }
+
+    /// a bounded Dijkstra search over edge distances from a vertex, returning the
+    /// distance to each reached vertex and the edge used to arrive there.
+    fn shortest_paths(
+        &self,
+        source: VertexId,
+        max_distance: f64,
+    ) -> Result<HashMap<VertexId, (f64, Option<EdgeId>)>, PluginError> {
+        let mut tree: HashMap<VertexId, (f64, Option<EdgeId>)> =
+            HashMap::from([(source, (0.0, None))]);
+        let mut queue = BinaryHeap::from([Reverse((Cost::ZERO, source))]);
+        while let Some(Reverse((distance, vertex_id))) = queue.pop() {
+            let distance = distance.as_f64();
+            if tree.get(&vertex_id).is_some_and(|(d, _)| distance > *d) {
+                continue;
+            }
+            for edge_id in self.graph.out_edges_iter(vertex_id).map_err(graph_error)? {
+                let edge = self.graph.get_edge(*edge_id).map_err(graph_error)?;
+                let next_distance = distance + edge.distance.as_f64();
+                if next_distance > max_distance {
+                    continue;
+                }
+                let improved = tree
+                    .get(&edge.dst_vertex_id)
+                    .is_none_or(|(d, _)| next_distance < *d);
+                if improved {
+                    tree.insert(edge.dst_vertex_id, (next_distance, Some(*edge_id)));
+                    queue.push(Reverse((Cost::new(next_distance), edge.dst_vertex_id)));
+                }
+            }
+        }
+        Ok(tree)
+    }
+}
+
+/// confirms that a trace has timestamps for all or none of its points, and that
+/// they do not go backwards
+fn validate_timestamps(trace: &[TracePoint]) -> Result<(), PluginError> {
+    let timestamps = trace.iter().filter_map(|(_, t)| *t).collect::<Vec<_>>();
+    if !timestamps.is_empty() && timestamps.len() != trace.len() {
+        return Err(PluginError::InputError(String::from(
+            "trace must have a timestamp for either every point or none of them",
+        )));
+    }
+    let unordered = timestamps.windows(2).any(|w| w[1] < w[0]);
+    if unordered || timestamps.iter().any(|t| !t.is_finite()) {
+        return Err(PluginError::InputError(String::from(
+            "trace timestamps must be finite and in increasing order",
+        )));
+    }
+    Ok(())
+}
+
+fn distance_meters(src: &Coord<f32>, dst: &Coord<f32>) -> Result<f64, PluginError> {
+    haversine::coord_distance_meters(src, dst)
+        .map(|d| d.as_f64())
+        .map_err(PluginError::InputError)
+}
+
+fn graph_error(e: impl std::fmt::Display) -> PluginError {
+    PluginError::InternalError(format!("map matching graph error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::LineString;
+    use routee_compass_core::{
+        model::{
+            property::{edge::Edge, vertex::Vertex},
+            road_network::edge_geometry_store::EdgeGeometryStore,
+        },
+        util::compact_ordered_hash_map::CompactOrderedHashMap,
+    };
+
+    /// a road heading east through vertices (0), (1) and (2), with a branch
+    /// heading north from (1) to (3). each edge is roughly 111 meters long.
+    fn build_matcher() -> HmmMatcher {
+        let coords = [(0.0, 0.0), (0.001, 0.0), (0.002, 0.0), (0.001, 0.001)];
+        let vertices = coords
+            .iter()
+            .enumerate()
+            .map(|(i, (x, y))| Vertex::new(i, *x, *y))
+            .collect::<Vec<_>>();
+        let edges = vec![
+            Edge::new(0, 0, 1, 111.3),
+            Edge::new(1, 1, 2, 111.3),
+            Edge::new(2, 1, 3, 111.3),
+        ];
+        let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        for edge in &edges {
+            adj[edge.src_vertex_id.0].insert(edge.edge_id, edge.dst_vertex_id);
+            rev[edge.dst_vertex_id.0].insert(edge.edge_id, edge.src_vertex_id);
+        }
+        let geometries = edges
+            .iter()
+            .map(|e| {
+                let (sx, sy) = coords[e.src_vertex_id.0];
+                let (dx, dy) = coords[e.dst_vertex_id.0];
+                LineString::from(vec![(sx, sy), (dx, dy)])
+            })
+            .collect();
+        let geometries = Arc::new(EdgeGeometryStore::new(geometries, edges.len()).unwrap());
+        let records = EdgeRtreeRecord::from_geometries(&geometries);
+        let graph = Graph {
+            adj: adj.into(),
+            rev: rev.into(),
+            edges: edges.into(),
+            vertices: vertices.into(),
+            geometries: Some(geometries),
+            edge_attributes: None,
+        };
+        HmmMatcher {
+            graph: Arc::new(graph),
+            rtree: RTree::bulk_load(records),
+            search_radius: 50.0,
+            gps_sigma: 10.0,
+            beta: 50.0,
+            max_candidates: 8,
+            max_speed: 30.0,
+            skip_unmatched_points: false,
+        }
+    }
+
+    fn untimed(points: &[(f32, f32)]) -> Vec<TracePoint> {
+        points.iter().map(|p| (Coord::from(*p), None)).collect()
+    }
+
+    #[test]
+    fn test_match_trace_along_road() {
+        let matcher = build_matcher();
+        let trace = untimed(&[
+            (0.0002, 0.00001),
+            (0.0008, 0.00002),
+            (0.0013, 0.00001),
+            (0.0018, -0.00001),
+        ]);
+        let matched = matcher.match_trace(&trace).unwrap();
+        assert_eq!(matched.edge_ids, vec![EdgeId(0), EdgeId(1)]);
+
+        // turning north at the intersection follows the branch instead
+        let trace = untimed(&[(0.0005, 0.00001), (0.00101, 0.0004), (0.00099, 0.0008)]);
+        let matched = matcher.match_trace(&trace).unwrap();
+        assert_eq!(matched.edge_ids, vec![EdgeId(0), EdgeId(2)]);
+    }
+
+    #[test]
+    fn test_match_trace_unmatched_points() {
+        let mut matcher = build_matcher();
+        let trace = untimed(&[(0.0002, 0.00001), (0.0008, 0.01), (0.0018, -0.00001)]);
+        assert!(matcher.match_trace(&trace).is_err());
+
+        matcher.skip_unmatched_points = true;
+        let matched = matcher.match_trace(&trace).unwrap();
+        assert_eq!(matched.edge_ids, vec![EdgeId(0), EdgeId(1)]);
+        assert_eq!(matched.unmatched, vec![1]);
+    }
+
+    #[test]
+    fn test_match_trace_with_timestamps() {
+        let matcher = build_matcher();
+        let points = [(0.0002, 0.00001), (0.0018, -0.00001)];
+        // 180 meters in 10 seconds is within the max speed of 30 meters per second
+        let trace = vec![
+            (Coord::from(points[0]), Some(0.0)),
+            (Coord::from(points[1]), Some(10.0)),
+        ];
+        let matched = matcher.match_trace(&trace).unwrap();
+        assert_eq!(matched.edge_ids, vec![EdgeId(0), EdgeId(1)]);
+
+        // in 1 second, no route is fast enough
+        let trace = vec![
+            (Coord::from(points[0]), Some(0.0)),
+            (Coord::from(points[1]), Some(1.0)),
+        ];
+        assert!(matcher.match_trace(&trace).is_err());
+
+        // timestamps must not go backwards or be partially missing
+        let trace = vec![
+            (Coord::from(points[0]), Some(10.0)),
+            (Coord::from(points[1]), Some(0.0)),
+        ];
+        assert!(matcher.match_trace(&trace).is_err());
+        let trace = vec![
+            (Coord::from(points[0]), Some(0.0)),
+            (Coord::from(points[1]), None),
+        ];
+        assert!(matcher.match_trace(&trace).is_err());
+    }
+}
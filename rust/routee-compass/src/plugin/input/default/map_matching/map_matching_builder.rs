@@ -0,0 +1,90 @@
+use super::{hmm_matcher::HmmMatcher, map_matching_plugin::MapMatchingInputPlugin};
+use crate::{
+    app::compass::config::{
+        builders::InputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::input::{
+        default::edge_rtree::edge_rtree_record::EdgeRtreeRecord, input_plugin::InputPlugin,
+    },
+};
+use routee_compass_core::model::road_network::{
+    edge_geometry_store::EdgeGeometryStore, graph::Graph,
+};
+use rstar::RTree;
+use std::sync::Arc;
+
+pub struct MapMatchingBuilder {}
+
+impl InputPluginBuilder for MapMatchingBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from(
+                "map_matching: the map matching plugin must be built with the graph of the app",
+            ),
+        ))
+    }
+
+    /// matches traces to the graph of the app, so the graph is not loaded twice
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("map_matching");
+        let linestring_file =
+            parameters.get_config_path_optional(&"geometry_input_file", &parent_key)?;
+        let search_radius = parameters
+            .get_config_serde_optional::<f64>(&"search_radius_meters", &parent_key)?
+            .unwrap_or(50.0);
+        let gps_sigma = parameters
+            .get_config_serde_optional::<f64>(&"gps_sigma_meters", &parent_key)?
+            .unwrap_or(10.0);
+        let beta = parameters
+            .get_config_serde_optional::<f64>(&"beta_meters", &parent_key)?
+            .unwrap_or(50.0);
+        let max_candidates = parameters
+            .get_config_serde_optional::<usize>(&"max_candidates", &parent_key)?
+            .unwrap_or(8);
+        let max_speed_kph = parameters
+            .get_config_serde_optional::<f64>(&"max_speed_kph", &parent_key)?
+            .unwrap_or(200.0);
+        let skip_unmatched_points = parameters
+            .get_config_serde_optional::<bool>(&"skip_unmatched_points", &parent_key)?
+            .unwrap_or(false);
+        for (key, value) in [
+            ("search_radius_meters", search_radius),
+            ("gps_sigma_meters", gps_sigma),
+            ("beta_meters", beta),
+            ("max_speed_kph", max_speed_kph),
+        ] {
+            if value <= 0.0 {
+                return Err(CompassConfigurationError::UserConfigurationError(format!(
+                    "map_matching: {} must be positive, found {}",
+                    key, value
+                )));
+            }
+        }
+
+        let geometries = EdgeGeometryStore::from_graph_or_file(&graph, linestring_file.as_ref())
+            .map_err(|e| {
+                CompassConfigurationError::UserConfigurationError(format!("map_matching: {}", e))
+            })?;
+        let records = EdgeRtreeRecord::from_geometries(&geometries);
+
+        let matcher = HmmMatcher {
+            graph,
+            rtree: RTree::bulk_load(records),
+            search_radius,
+            gps_sigma,
+            beta,
+            max_candidates: max_candidates.max(1),
+            max_speed: max_speed_kph / 3.6,
+            skip_unmatched_points,
+        };
+        Ok(Arc::new(MapMatchingInputPlugin { matcher }))
+    }
+}
@@ -0,0 +1,48 @@
+use super::hmm_matcher::HmmMatcher;
+use crate::plugin::{
+    input::{input_json_extensions::InputJsonExtensions, input_plugin::InputPlugin},
+    plugin_error::PluginError,
+};
+use serde_json::json;
+
+pub struct MapMatchingInputPlugin {
+    pub matcher: HmmMatcher,
+}
+
+impl InputPlugin for MapMatchingInputPlugin {
+    /// map-matches a GPS `trace` on the query to the road network. the first and last
+    /// matched edges become the origin and destination edges, and the full matched
+    /// path is stored as the query `route_edges`. the indices of any trace points which
+    /// were skipped because no edge was nearby are stored as `unmatched_trace_points`.
+    /// queries without a trace are unchanged.
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        let mut query = input.clone();
+        let trace = match query.get_trace()? {
+            None => return Ok(vec![query]),
+            Some(trace) => trace,
+        };
+        if trace.len() < 2 {
+            return Err(PluginError::InputError(format!(
+                "map matching requires a trace with at least two points, found {}",
+                trace.len()
+            )));
+        }
+        let matched = self.matcher.match_trace(&trace)?;
+        let (origin_edge, destination_edge) =
+            match (matched.edge_ids.first(), matched.edge_ids.last()) {
+                (Some(o), Some(d)) => (*o, *d),
+                _ => {
+                    return Err(PluginError::InputError(String::from(
+                        "map matching produced an empty path",
+                    )))
+                }
+            };
+        query.add_origin_edge(origin_edge)?;
+        query.add_destination_edge(destination_edge)?;
+        query.add_route_edges(matched.edge_ids)?;
+        if !matched.unmatched.is_empty() {
+            query["unmatched_trace_points"] = json![matched.unmatched];
+        }
+        Ok(vec![query])
+    }
+}
@@ -0,0 +1,3 @@
+pub mod hmm_matcher;
+pub mod map_matching_builder;
+pub mod map_matching_plugin;
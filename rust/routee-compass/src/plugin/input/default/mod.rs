@@ -1,6 +1,9 @@
 pub mod debug;
 pub mod edge_rtree;
+pub mod geocode;
 pub mod grid_search;
 pub mod inject;
 pub mod load_balancer;
+pub mod map_matching;
+pub mod sampling;
 pub mod vertex_rtree;
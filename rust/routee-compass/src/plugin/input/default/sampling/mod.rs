@@ -0,0 +1,3 @@
+pub mod sample_source;
+pub mod sampling_builder;
+pub mod sampling_plugin;
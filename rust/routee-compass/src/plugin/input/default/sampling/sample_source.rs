@@ -0,0 +1,78 @@
+use crate::{
+    app::compass::config::frontier_model::geofence::geofence_ops, plugin::plugin_error::PluginError,
+};
+use geo::{Area, BoundingRect, Contains, Coord, Point, Polygon};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use serde::Deserialize;
+
+/// a row of a weighted points file, with the header `x,y,weight`
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct WeightedPoint {
+    pub x: f32,
+    pub y: f32,
+    pub weight: f64,
+}
+
+/// a set of locations to draw sample coordinates from
+pub enum SampleSource {
+    /// uniformly distributed over the area of the polygons
+    Polygons {
+        polygons: Vec<Polygon<f32>>,
+        areas: WeightedIndex<f64>,
+    },
+    /// one of a list of points, chosen in proportion to its weight, such as
+    /// the population of each census block
+    WeightedPoints {
+        points: Vec<Coord<f32>>,
+        weights: WeightedIndex<f64>,
+    },
+}
+
+impl SampleSource {
+    /// attempts to find a point inside a polygon before giving up
+    const MAX_POLYGON_ATTEMPTS: usize = 1000;
+
+    /// builds a source from a GeoJSON polygon or multipolygon
+    pub fn from_geojson(value: &serde_json::Value) -> Result<SampleSource, PluginError> {
+        let polygons = geofence_ops::polygons_from_geojson(value)
+            .map_err(|e| PluginError::InputError(e.to_string()))?;
+        let areas =
+            WeightedIndex::new(polygons.iter().map(|p| p.unsigned_area() as f64)).map_err(|e| {
+                PluginError::InputError(format!("invalid sampling polygon areas: {}", e))
+            })?;
+        Ok(SampleSource::Polygons { polygons, areas })
+    }
+
+    /// builds a source from a list of weighted points
+    pub fn from_weighted_points(points: &[WeightedPoint]) -> Result<SampleSource, PluginError> {
+        let weights = WeightedIndex::new(points.iter().map(|p| p.weight))
+            .map_err(|e| PluginError::InputError(format!("invalid sampling weights: {}", e)))?;
+        let points = points.iter().map(|p| Coord::from((p.x, p.y))).collect();
+        Ok(SampleSource::WeightedPoints { points, weights })
+    }
+
+    /// draws one coordinate from this source
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Result<Coord<f32>, PluginError> {
+        match self {
+            SampleSource::WeightedPoints { points, weights } => Ok(points[weights.sample(rng)]),
+            SampleSource::Polygons { polygons, areas } => {
+                let polygon = &polygons[areas.sample(rng)];
+                let rect = polygon.bounding_rect().ok_or_else(|| {
+                    PluginError::InputError(String::from("cannot sample from an empty polygon"))
+                })?;
+                // rejection sampling over the bounding box
+                for _ in 0..Self::MAX_POLYGON_ATTEMPTS {
+                    let x = rng.gen_range(rect.min().x..=rect.max().x);
+                    let y = rng.gen_range(rect.min().y..=rect.max().y);
+                    if polygon.contains(&Point::new(x, y)) {
+                        return Ok(Coord::from((x, y)));
+                    }
+                }
+                Err(PluginError::PluginFailed(format!(
+                    "unable to sample a point inside polygon after {} attempts",
+                    Self::MAX_POLYGON_ATTEMPTS
+                )))
+            }
+        }
+    }
+}
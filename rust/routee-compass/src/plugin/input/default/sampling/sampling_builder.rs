@@ -0,0 +1,36 @@
+use super::{
+    sample_source::{SampleSource, WeightedPoint},
+    sampling_plugin::SamplingPlugin,
+};
+use crate::{
+    app::compass::config::{
+        builders::InputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::input::input_plugin::InputPlugin,
+};
+use routee_compass_core::util::fs::read_utils;
+use std::sync::Arc;
+
+pub struct SamplingBuilder {}
+
+impl InputPluginBuilder for SamplingBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn InputPlugin>, CompassConfigurationError> {
+        let weighted_points = match parameters
+            .get_config_path_optional(&"weighted_points_input_file", &"sampling")?
+        {
+            None => None,
+            Some(path) => {
+                let rows: Box<[WeightedPoint]> = read_utils::from_csv(&path, true, None)
+                    .map_err(|e| CompassConfigurationError::PluginError(e.into()))?;
+                let source = SampleSource::from_weighted_points(&rows)
+                    .map_err(CompassConfigurationError::PluginError)?;
+                Some(Arc::new(source))
+            }
+        };
+        Ok(Arc::new(SamplingPlugin { weighted_points }))
+    }
+}
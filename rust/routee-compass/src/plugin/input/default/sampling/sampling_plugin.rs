@@ -0,0 +1,147 @@
+use super::sample_source::SampleSource;
+use crate::plugin::{
+    input::{
+        input_field::InputField, input_json_extensions::InputJsonExtensions,
+        input_plugin::InputPlugin,
+    },
+    plugin_error::PluginError,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::Arc;
+
+/// expands a query with a `sample` section into many queries whose origins and/or
+/// destinations are drawn at random from a polygon or from a weighted set of points.
+pub struct SamplingPlugin {
+    /// points loaded from the `weighted_points_input_file`, if configured
+    pub weighted_points: Option<Arc<SampleSource>>,
+}
+
+impl SamplingPlugin {
+    /// a sample source which refers to the configured weighted points
+    const WEIGHTED_POINTS: &'static str = "weighted_points";
+
+    /// reads the sample source for the origin or destination, which is either the
+    /// string "weighted_points" or a GeoJSON polygon.
+    fn source(
+        &self,
+        sample: &serde_json::Value,
+        key: &str,
+    ) -> Result<Option<Arc<SampleSource>>, PluginError> {
+        match sample.get(key) {
+            None => Ok(None),
+            Some(serde_json::Value::String(s)) if s == Self::WEIGHTED_POINTS => {
+                let points = self.weighted_points.clone().ok_or_else(|| {
+                    PluginError::InputError(String::from(
+                        "sampling from weighted_points requires a weighted_points_input_file in the plugin configuration",
+                    ))
+                })?;
+                Ok(Some(points))
+            }
+            Some(value) => SampleSource::from_geojson(value).map(|s| Some(Arc::new(s))),
+        }
+    }
+}
+
+impl InputPlugin for SamplingPlugin {
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        let sample = match input.get(InputField::Sample.to_str()) {
+            None => return Ok(vec![input.clone()]),
+            Some(sample) => sample.clone(),
+        };
+        let samples = sample
+            .get("samples")
+            .ok_or_else(|| PluginError::MissingField(String::from("sample.samples")))?
+            .as_u64()
+            .ok_or_else(|| {
+                PluginError::ParseError(String::from("sample.samples"), String::from("u64"))
+            })?;
+        let origin = self.source(&sample, "origin")?;
+        let destination = self.source(&sample, "destination")?;
+        if origin.is_none() && destination.is_none() {
+            return Err(PluginError::InputError(String::from(
+                "sample requires an origin and/or destination to sample from",
+            )));
+        }
+        let mut rng = match sample.get("seed") {
+            None => StdRng::from_entropy(),
+            Some(seed) => {
+                let seed = seed.as_u64().ok_or_else(|| {
+                    PluginError::ParseError(String::from("sample.seed"), String::from("u64"))
+                })?;
+                StdRng::seed_from_u64(seed)
+            }
+        };
+
+        let mut initial_map = input
+            .as_object()
+            .ok_or_else(|| PluginError::UnexpectedQueryStructure(format!("{:?}", input)))?
+            .clone();
+        initial_map.remove(InputField::Sample.to_str());
+        let initial = serde_json::json!(initial_map);
+        let mut result = Vec::with_capacity(samples as usize);
+        for sample_id in 0..samples {
+            let mut instance = initial.clone();
+            if let Some(source) = &origin {
+                instance.add_origin_coordinate(source.sample(&mut rng)?)?;
+            }
+            if let Some(source) = &destination {
+                instance.add_destination_coordinate(source.sample(&mut rng)?)?;
+            }
+            instance[InputField::SampleId.to_str()] = serde_json::json!(sample_id);
+            result.push(instance);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::input::default::sampling::sample_source::WeightedPoint;
+    use geo::Coord;
+    use serde_json::json;
+
+    #[test]
+    fn test_sample_origins_and_destinations() {
+        let points = SampleSource::from_weighted_points(&[
+            WeightedPoint {
+                x: 1.0,
+                y: 1.0,
+                weight: 1.0,
+            },
+            WeightedPoint {
+                x: 2.0,
+                y: 2.0,
+                weight: 0.0,
+            },
+        ])
+        .unwrap();
+        let plugin = SamplingPlugin {
+            weighted_points: Some(Arc::new(points)),
+        };
+        let query = json!({
+            "model_name": "2016_TOYOTA_Camry_4cyl_2WD",
+            "sample": {
+                "samples": 3,
+                "seed": 42,
+                "origin": "weighted_points",
+                "destination": {
+                    "type": "Polygon",
+                    "coordinates": [[[0.0, 0.0], [0.5, 0.0], [0.5, 0.5], [0.0, 0.5], [0.0, 0.0]]]
+                }
+            }
+        });
+        let queries = plugin.process(&query).unwrap();
+        assert_eq!(queries.len(), 3);
+        for (idx, q) in queries.iter().enumerate() {
+            assert!(q.get("sample").is_none());
+            assert_eq!(q["model_name"], json!("2016_TOYOTA_Camry_4cyl_2WD"));
+            assert_eq!(q["sample_id"], json!(idx));
+            // the second point has no weight and is never chosen
+            assert_eq!(q.get_origin_coordinate().unwrap(), Coord::from((1.0, 1.0)));
+            let d = q.get_destination_coordinate().unwrap().unwrap();
+            assert!((0.0..=0.5).contains(&d.x) && (0.0..=0.5).contains(&d.y));
+        }
+    }
+}
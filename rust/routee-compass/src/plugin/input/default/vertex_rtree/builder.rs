@@ -10,7 +10,7 @@ use crate::{
     plugin::input::input_plugin::InputPlugin,
 };
 
-use super::plugin::RTreePlugin;
+use super::plugin::{OriginCandidatesConfig, RTreePlugin};
 
 pub struct VertexRTreeBuilder {}
 
@@ -25,8 +25,17 @@ impl InputPluginBuilder for VertexRTreeBuilder {
             parameters.get_config_serde_optional::<Distance>(&"distance_tolerance", &parent_key)?;
         let distance_unit =
             parameters.get_config_serde_optional::<DistanceUnit>(&"distance_unit", &parent_key)?;
-        let rtree = RTreePlugin::new(&vertex_path, tolerance_distance, distance_unit)
-            .map_err(CompassConfigurationError::PluginError)?;
+        let origin_candidates = parameters.get_config_serde_optional::<OriginCandidatesConfig>(
+            &"origin_candidates",
+            &parent_key,
+        )?;
+        let rtree = RTreePlugin::new(
+            &vertex_path,
+            tolerance_distance,
+            distance_unit,
+            origin_candidates,
+        )
+        .map_err(CompassConfigurationError::PluginError)?;
         let m: Arc<dyn InputPlugin> = Arc::new(rtree);
         Ok(m)
     }
@@ -1,15 +1,20 @@
 use std::path::Path;
 
-use crate::plugin::input::input_json_extensions::InputJsonExtensions;
+use crate::app::compass::config::frontier_model::geofence::geofence_ops;
+use crate::plugin::input::input_field::InputField;
+use crate::plugin::input::input_json_extensions::{InputJsonExtensions, OriginCandidate};
 use crate::plugin::input::input_plugin::InputPlugin;
 use crate::plugin::plugin_error::PluginError;
-use geo::{coord, Coord};
+use geo::{coord, BoundingRect, Coord, Intersects, Point, Polygon};
+use itertools::Itertools;
 use routee_compass_core::{
-    model::unit::{Distance, DistanceUnit, BASE_DISTANCE_UNIT},
+    model::traversal::default::time_dependent_speed_service::TimeDependentSpeedService,
+    model::unit::{as_f64::AsF64, Cost, Distance, DistanceUnit, BASE_DISTANCE_UNIT},
     model::{property::vertex::Vertex, road_network::graph::Graph},
     util::{fs::read_utils, geo::haversine},
 };
 use rstar::{PointDistance, RTree, RTreeObject, AABB};
+use serde::{Deserialize, Serialize};
 
 pub struct RTreeVertex {
     vertex: Vertex,
@@ -57,6 +62,24 @@ impl VertexRTree {
             .map(|rtv| &rtv.vertex)
             .collect()
     }
+
+    /// the vertices within or on the boundary of any of the polygons
+    pub fn vertices_within(&self, polygons: &[Polygon<f32>]) -> Vec<&Vertex> {
+        let mut vertices = vec![];
+        for polygon in polygons {
+            let rect = match polygon.bounding_rect() {
+                Some(rect) => rect,
+                None => continue,
+            };
+            let envelope = AABB::from_corners(rect.min(), rect.max());
+            for rtv in self.rtree.locate_in_envelope(&envelope) {
+                if Point::from(*rtv.vertex.coordinate).intersects(polygon) {
+                    vertices.push(&rtv.vertex);
+                }
+            }
+        }
+        vertices
+    }
 }
 
 impl RTreeObject for RTreeVertex {
@@ -78,6 +101,27 @@ impl PointDistance for RTreeVertex {
     }
 }
 
+/// matches the origin coordinate to several of its nearest vertices, so that a single
+/// search can begin from whichever of them leads to the best route. only queries with a
+/// single destination vertex are given candidates, see [`supports_origin_candidates`].
+///
+/// ```toml
+/// origin_candidates = { count = 3, offset_rate = 1.0 }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct OriginCandidatesConfig {
+    /// the number of nearest vertices to begin from
+    pub count: usize,
+    /// the cost of each unit of distance between the origin coordinate and a candidate
+    /// vertex, in the distance unit of the plugin
+    #[serde(default = "default_offset_rate")]
+    pub offset_rate: f64,
+}
+
+fn default_offset_rate() -> f64 {
+    1.0
+}
+
 /// Builds an input plugin that uses an RTree to find the nearest vertex to the origin and destination coordinates.
 ///
 /// # Arguments
@@ -90,6 +134,7 @@ impl PointDistance for RTreeVertex {
 pub struct RTreePlugin {
     vertex_rtree: VertexRTree,
     tolerance: Option<(Distance, DistanceUnit)>,
+    origin_candidates: Option<OriginCandidatesConfig>,
 }
 
 impl RTreePlugin {
@@ -100,6 +145,7 @@ impl RTreePlugin {
     /// * `vertex_file` - file containing vertices
     /// * `tolerance_distance` - optional max distance to nearest vertex (assumed infinity if not included)
     /// * `distance_unit` - distance unit for tolerance, assumed BASE_DISTANCE_UNIT if not provided
+    /// * `origin_candidates` - optionally matches the origin to several of its nearest vertices
     ///
     /// # Returns
     ///
@@ -108,6 +154,7 @@ impl RTreePlugin {
         vertex_file: &Path,
         tolerance_distance: Option<Distance>,
         distance_unit: Option<DistanceUnit>,
+        origin_candidates: Option<OriginCandidatesConfig>,
     ) -> Result<Self, PluginError> {
         let vertices: Box<[Vertex]> =
             read_utils::from_csv(&vertex_file, true, None).map_err(PluginError::CsvReadError)?;
@@ -121,6 +168,7 @@ impl RTreePlugin {
         Ok(RTreePlugin {
             vertex_rtree,
             tolerance,
+            origin_candidates,
         })
     }
 }
@@ -136,7 +184,8 @@ impl InputPlugin for RTreePlugin {
     ///
     /// * either vertex ids for the nearest coordinates to the the origin (and optionally destination),
     ///   or, an error if not found or not within tolerance
-    fn process(&self, query: &mut serde_json::Value) -> Result<(), PluginError> {
+    fn process(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, PluginError> {
+        let mut query = input.clone();
         let src_coord = query.get_origin_coordinate()?;
         let dst_coord_option = query.get_destination_coordinate()?;
 
@@ -164,10 +213,93 @@ impl InputPlugin for RTreePlugin {
             }
         }
 
-        Ok(())
+        if let Some(polygon) = query.get_destination_polygon() {
+            let polygons = geofence_ops::polygons_from_geojson(polygon)
+                .map_err(|e| PluginError::InputError(e.to_string()))?;
+            let destination_vertices = self
+                .vertex_rtree
+                .vertices_within(&polygons)
+                .into_iter()
+                .map(|v| v.vertex_id)
+                .sorted()
+                .dedup()
+                .collect::<Vec<_>>();
+            if destination_vertices.is_empty() {
+                return Err(PluginError::PluginFailed(String::from(
+                    "no vertices found within the destination polygon",
+                )));
+            }
+            query.add_destination_vertices(destination_vertices)?;
+        }
+
+        let waypoint_coords = query.get_waypoint_coordinates()?;
+        if !waypoint_coords.is_empty() {
+            let waypoint_vertices = waypoint_coords
+                .iter()
+                .map(|coord| {
+                    let vertex = self.vertex_rtree.nearest_vertex(*coord).ok_or_else(|| {
+                        PluginError::PluginFailed(format!(
+                            "nearest vertex not found for waypoint coordinate {:?}",
+                            coord
+                        ))
+                    })?;
+                    validate_tolerance(coord, &vertex.coordinate, &self.tolerance)?;
+                    Ok(vertex.vertex_id)
+                })
+                .collect::<Result<Vec<_>, PluginError>>()?;
+            query.add_waypoint_vertices(waypoint_vertices)?;
+        }
+
+        // candidate origins are only searched for a query with a single destination
+        // vertex, which is the only kind of query a search from several origins supports
+        if let Some(config) = self
+            .origin_candidates
+            .as_ref()
+            .filter(|_| supports_origin_candidates(&query))
+        {
+            let distance_unit = self
+                .tolerance
+                .map(|(_, unit)| unit)
+                .unwrap_or(BASE_DISTANCE_UNIT);
+            let candidates = self
+                .vertex_rtree
+                .nearest_vertices(src_coord, config.count)
+                .into_iter()
+                .filter(|v| validate_tolerance(&src_coord, &v.coordinate, &self.tolerance).is_ok())
+                .map(|v| {
+                    let meters = haversine::coord_distance_meters(&src_coord, &v.coordinate)
+                        .map_err(PluginError::PluginFailed)?;
+                    let distance = DistanceUnit::Meters.convert(&meters, &distance_unit);
+                    Ok(OriginCandidate {
+                        vertex_id: v.vertex_id,
+                        offset: Cost::new(distance.as_f64() * config.offset_rate),
+                    })
+                })
+                .collect::<Result<Vec<_>, PluginError>>()?;
+            query.add_origin_vertices(candidates)?;
+        }
+        Ok(vec![query])
     }
 }
 
+/// true if a query may be searched from several candidate origins: it has a single
+/// destination vertex and no set of destinations, arrival time, waypoints, route
+/// edges or origin edge
+fn supports_origin_candidates(query: &serde_json::Value) -> bool {
+    let single_destination = matches!(query.get_destination_vertex(), Ok(Some(_)))
+        && query
+            .get(InputField::DestinationVertices.to_str())
+            .is_none()
+        && query.get_destination_polygon().is_none();
+    let other_options = [
+        TimeDependentSpeedService::ARRIVAL_TIME,
+        InputField::WaypointVertices.to_str(),
+        InputField::RouteEdges.to_str(),
+        InputField::OriginEdge.to_str(),
+    ];
+    single_destination && other_options.iter().all(|key| query.get(key).is_none())
+}
+
 /// confirms that two coordinates are within some stated distance tolerance.
 /// if no tolerance is provided, the dst coordinate is assumed to be a valid distance.
 ///
@@ -175,10 +307,10 @@ impl InputPlugin for RTreePlugin {
 ///
 /// * `src` - source coordinate
 /// * `dst` - destination coordinate that may or may not be within some distance
-///           tolerance of the src coordinate
+///   tolerance of the src coordinate
 /// * `tolerance` - tolerance parameters set by user for the rtree plugin. if this is None,
-///                 all coordinate pairs are assumed to be within distance tolerance, but this
-///                 may lead to unexpected behavior where far away coordinates are considered "matched".
+///   all coordinate pairs are assumed to be within distance tolerance, but this
+///   may lead to unexpected behavior where far away coordinates are considered "matched".
 ///
 /// # Returns
 ///
@@ -222,6 +354,7 @@ mod test {
 
     use super::*;
     use crate::plugin::input::input_field::InputField;
+    use routee_compass_core::model::road_network::vertex_id::VertexId;
     use serde_json::json;
 
     #[test]
@@ -244,11 +377,12 @@ mod test {
             .join("test")
             .join("rtree_query.json");
         let query_str = fs::read_to_string(query_filepath).unwrap();
-        let rtree_plugin = RTreePlugin::new(&vertices_filepath, None, None).unwrap();
-        let mut query: serde_json::Value = serde_json::from_str(&query_str).unwrap();
-        rtree_plugin.process(&mut query).unwrap();
+        let rtree_plugin = RTreePlugin::new(&vertices_filepath, None, None, None).unwrap();
+        let query: serde_json::Value = serde_json::from_str(&query_str).unwrap();
+        let mut result = rtree_plugin.process(&query).unwrap();
+        assert_eq!(result.len(), 1);
 
-        match query {
+        match result.remove(0) {
             serde_json::Value::Object(obj) => {
                 assert_eq!(
                     json![obj],
@@ -267,4 +401,97 @@ mod test {
             other => panic!("expected object result, found {}", other),
         }
     }
+
+    #[test]
+    fn test_rtree_plugin_destination_polygon() {
+        let vertices_filepath = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("plugin")
+            .join("input")
+            .join("default")
+            .join("vertex_rtree")
+            .join("test")
+            .join("rtree_vertices.csv");
+        let rtree_plugin = RTreePlugin::new(&vertices_filepath, None, None, None).unwrap();
+        let polygon = json!({
+            "type": "Polygon",
+            "coordinates": [[[0.5, 0.5], [2.5, 0.5], [2.5, 2.5], [0.5, 2.5], [0.5, 0.5]]]
+        });
+        let query = json!({
+            InputField::OriginX.to_str(): 0.1,
+            InputField::OriginY.to_str(): 0.1,
+            InputField::DestinationPolygon.to_str(): polygon,
+        });
+        let result = rtree_plugin.process(&query).unwrap();
+        assert_eq!(
+            result[0][InputField::DestinationVertices.to_str()],
+            json!([1, 2])
+        );
+
+        let empty = json!({
+            InputField::OriginX.to_str(): 0.1,
+            InputField::OriginY.to_str(): 0.1,
+            InputField::DestinationPolygon.to_str(): {
+                "type": "Polygon",
+                "coordinates": [[[5.0, 5.0], [6.0, 5.0], [6.0, 6.0], [5.0, 5.0]]]
+            },
+        });
+        assert!(rtree_plugin.process(&empty).is_err());
+    }
+
+    #[test]
+    fn test_rtree_plugin_origin_candidates() {
+        let vertices_filepath = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("plugin")
+            .join("input")
+            .join("default")
+            .join("vertex_rtree")
+            .join("test")
+            .join("rtree_vertices.csv");
+        let config = OriginCandidatesConfig {
+            count: 2,
+            offset_rate: 1.0,
+        };
+        let rtree_plugin = RTreePlugin::new(&vertices_filepath, None, None, Some(config)).unwrap();
+        let query = json!({
+            InputField::OriginX.to_str(): 0.1,
+            InputField::OriginY.to_str(): 0.1,
+            InputField::DestinationX.to_str(): 2.0,
+            InputField::DestinationY.to_str(): 2.0,
+        });
+        let result = rtree_plugin.process(&query).unwrap();
+        let candidates = result[0].get_origin_vertices().unwrap();
+        let vertex_ids = candidates.iter().map(|c| c.vertex_id).collect::<Vec<_>>();
+        assert_eq!(vertex_ids, vec![VertexId(0), VertexId(1)]);
+        // offsets are the distance from the origin coordinate in meters
+        assert!(candidates[0].offset > Cost::ZERO);
+        assert!(candidates[1].offset > candidates[0].offset);
+
+        // queries a search from several origins does not support keep a single origin
+        let isochrone = json!({
+            InputField::OriginX.to_str(): 0.1,
+            InputField::OriginY.to_str(): 0.1,
+        });
+        let arrive_by = json!({
+            InputField::OriginX.to_str(): 0.1,
+            InputField::OriginY.to_str(): 0.1,
+            InputField::DestinationX.to_str(): 2.0,
+            InputField::DestinationY.to_str(): 2.0,
+            "arrival_time": "2024-03-01T09:00:00",
+        });
+        let polygon = json!({
+            InputField::OriginX.to_str(): 0.1,
+            InputField::OriginY.to_str(): 0.1,
+            InputField::DestinationPolygon.to_str(): {
+                "type": "Polygon",
+                "coordinates": [[[0.5, 0.5], [2.5, 0.5], [2.5, 2.5], [0.5, 2.5], [0.5, 0.5]]]
+            },
+        });
+        for query in [isochrone, arrive_by, polygon] {
+            let result = rtree_plugin.process(&query).unwrap();
+            assert!(result[0].get(InputField::OriginVertices.to_str()).is_none());
+            assert!(result[0].get(InputField::OriginVertex.to_str()).is_some());
+        }
+    }
 }
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// how a query is handled when an input plugin fails to process it
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InputErrorPolicy {
+    /// stop the run with an error at the first query that fails
+    FailFast,
+    /// drop the failed query, returning an error response in its place. other
+    /// queries expanded from the same input query are still run.
+    #[default]
+    SkipAndRecord,
+    /// run the query as it was before the failed plugin, recording the error in its
+    /// `input_plugin_errors` field
+    PassThrough,
+}
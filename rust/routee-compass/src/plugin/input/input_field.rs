@@ -6,11 +6,30 @@ pub enum InputField {
     DestinationX,
     DestinationY,
     OriginVertex,
+    OriginVertices,
     DestinationVertex,
+    DestinationVertices,
+    DestinationPolygon,
     OriginEdge,
     DestinationEdge,
+    OriginEdgeOffset,
+    DestinationEdgeOffset,
+    DeadEndPolicy,
     GridSearch,
     QueryWeightEstimate,
+    Objectives,
+    Waypoints,
+    WaypointVertices,
+    WaypointEdges,
+    Trace,
+    RouteEdges,
+    OriginAddress,
+    DestinationAddress,
+    Sample,
+    SampleId,
+    Termination,
+    QueryId,
+    ParentQueryId,
 }
 
 impl InputField {
@@ -22,11 +41,30 @@ impl InputField {
             I::DestinationX => "destination_x",
             I::DestinationY => "destination_y",
             I::OriginVertex => "origin_vertex",
+            I::OriginVertices => "origin_vertices",
             I::DestinationVertex => "destination_vertex",
+            I::DestinationVertices => "destination_vertices",
+            I::DestinationPolygon => "destination_polygon",
             I::OriginEdge => "origin_edge",
             I::DestinationEdge => "destination_edge",
+            I::OriginEdgeOffset => "origin_edge_offset",
+            I::DestinationEdgeOffset => "destination_edge_offset",
+            I::DeadEndPolicy => "dead_end_policy",
             I::GridSearch => "grid_search",
             I::QueryWeightEstimate => "query_weight_estimate",
+            I::Objectives => "objectives",
+            I::Waypoints => "waypoints",
+            I::WaypointVertices => "waypoint_vertices",
+            I::WaypointEdges => "waypoint_edges",
+            I::Trace => "trace",
+            I::RouteEdges => "route_edges",
+            I::OriginAddress => "origin_address",
+            I::DestinationAddress => "destination_address",
+            I::Sample => "sample",
+            I::SampleId => "sample_id",
+            I::Termination => "termination",
+            I::QueryId => "query_id",
+            I::ParentQueryId => "parent_query_id",
         }
     }
 }
@@ -1,23 +1,66 @@
+use super::default::map_matching::hmm_matcher::TracePoint;
 use super::input_field::InputField;
 use crate::plugin::plugin_error::PluginError;
 use geo;
-use routee_compass_core::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
+use routee_compass_core::{
+    algorithm::search::dead_end_policy::DeadEndPolicy,
+    model::{
+        road_network::{edge_id::EdgeId, vertex_id::VertexId},
+        unit::Cost,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// a candidate origin vertex of a query with several origins, along with the cost of
+/// reaching it from the origin of the query, such as its distance from a snapped coordinate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OriginCandidate {
+    pub vertex_id: VertexId,
+    #[serde(default = "zero_offset")]
+    pub offset: Cost,
+}
+
+fn zero_offset() -> Cost {
+    Cost::ZERO
+}
 use serde_json::{self, json};
 
 pub trait InputJsonExtensions {
     fn get_origin_coordinate(&self) -> Result<geo::Coord<f32>, PluginError>;
     fn get_destination_coordinate(&self) -> Result<Option<geo::Coord<f32>>, PluginError>;
+    fn add_origin_coordinate(&mut self, coord: geo::Coord<f32>) -> Result<(), PluginError>;
+    fn add_destination_coordinate(&mut self, coord: geo::Coord<f32>) -> Result<(), PluginError>;
     fn add_origin_vertex(&mut self, vertex_id: VertexId) -> Result<(), PluginError>;
     fn add_destination_vertex(&mut self, vertex_id: VertexId) -> Result<(), PluginError>;
     fn add_origin_edge(&mut self, edge_id: EdgeId) -> Result<(), PluginError>;
     fn add_destination_edge(&mut self, edge_id: EdgeId) -> Result<(), PluginError>;
     fn get_origin_vertex(&self) -> Result<VertexId, PluginError>;
+    fn add_origin_vertices(&mut self, candidates: Vec<OriginCandidate>) -> Result<(), PluginError>;
+    fn get_origin_vertices(&self) -> Result<Vec<OriginCandidate>, PluginError>;
     fn get_destination_vertex(&self) -> Result<Option<VertexId>, PluginError>;
+    fn get_destination_polygon(&self) -> Option<&serde_json::Value>;
+    fn add_destination_vertices(&mut self, vertex_ids: Vec<VertexId>) -> Result<(), PluginError>;
+    fn get_destination_vertices(&self) -> Result<Vec<VertexId>, PluginError>;
     fn get_origin_edge(&self) -> Result<EdgeId, PluginError>;
     fn get_destination_edge(&self) -> Result<Option<EdgeId>, PluginError>;
+    fn get_dead_end_policy(&self) -> Result<DeadEndPolicy, PluginError>;
+    fn get_origin_edge_offset(&self) -> Result<Option<f64>, PluginError>;
+    fn get_destination_edge_offset(&self) -> Result<Option<f64>, PluginError>;
+    fn add_origin_edge_offset(&mut self, offset: f64) -> Result<(), PluginError>;
+    fn add_destination_edge_offset(&mut self, offset: f64) -> Result<(), PluginError>;
     fn get_grid_search(&self) -> Option<&serde_json::Value>;
+    fn get_termination(&self) -> Option<&serde_json::Value>;
     fn add_query_weight_estimate(&mut self, weight: f64) -> Result<(), PluginError>;
     fn get_query_weight_estimate(&self) -> Result<Option<f64>, PluginError>;
+    fn get_objectives(&self) -> Result<Option<Vec<String>>, PluginError>;
+    fn get_waypoint_coordinates(&self) -> Result<Vec<geo::Coord<f32>>, PluginError>;
+    fn add_waypoint_vertices(&mut self, vertex_ids: Vec<VertexId>) -> Result<(), PluginError>;
+    fn add_waypoint_edges(&mut self, edge_ids: Vec<EdgeId>) -> Result<(), PluginError>;
+    fn get_waypoint_vertices(&self) -> Result<Vec<VertexId>, PluginError>;
+    fn get_waypoint_edges(&self) -> Result<Vec<EdgeId>, PluginError>;
+    fn get_trace(&self) -> Result<Option<Vec<TracePoint>>, PluginError>;
+    fn add_route_edges(&mut self, edge_ids: Vec<EdgeId>) -> Result<(), PluginError>;
+    fn get_route_edges(&self) -> Result<Option<Vec<EdgeId>>, PluginError>;
 }
 
 impl InputJsonExtensions for serde_json::Value {
@@ -64,6 +107,30 @@ impl InputJsonExtensions for serde_json::Value {
             }
         }
     }
+    fn add_origin_coordinate(&mut self, coord: geo::Coord<f32>) -> Result<(), PluginError> {
+        match self {
+            serde_json::Value::Object(map) => {
+                map.insert(InputField::OriginX.to_string(), json![coord.x]);
+                map.insert(InputField::OriginY.to_string(), json![coord.y]);
+                Ok(())
+            }
+            _ => Err(PluginError::InputError(String::from(
+                "InputQuery is not a JSON object",
+            ))),
+        }
+    }
+    fn add_destination_coordinate(&mut self, coord: geo::Coord<f32>) -> Result<(), PluginError> {
+        match self {
+            serde_json::Value::Object(map) => {
+                map.insert(InputField::DestinationX.to_string(), json![coord.x]);
+                map.insert(InputField::DestinationY.to_string(), json![coord.y]);
+                Ok(())
+            }
+            _ => Err(PluginError::InputError(String::from(
+                "InputQuery is not a JSON object",
+            ))),
+        }
+    }
     fn add_origin_vertex(&mut self, vertex_id: VertexId) -> Result<(), PluginError> {
         match self {
             serde_json::Value::Object(map) => {
@@ -103,6 +170,31 @@ impl InputJsonExtensions for serde_json::Value {
             })
     }
 
+    fn add_origin_vertices(&mut self, candidates: Vec<OriginCandidate>) -> Result<(), PluginError> {
+        match self {
+            serde_json::Value::Object(map) => {
+                map.insert(InputField::OriginVertices.to_string(), json![candidates]);
+                Ok(())
+            }
+            _ => Err(PluginError::InputError(String::from(
+                "InputQuery is not a JSON object",
+            ))),
+        }
+    }
+
+    /// candidate origin vertices with their cost offsets, empty if not provided
+    fn get_origin_vertices(&self) -> Result<Vec<OriginCandidate>, PluginError> {
+        match self.get(InputField::OriginVertices.to_string()) {
+            None => Ok(vec![]),
+            Some(v) => serde_json::from_value::<Vec<OriginCandidate>>(v.clone()).map_err(|_| {
+                PluginError::ParseError(
+                    InputField::OriginVertices.to_string(),
+                    String::from("[{\"vertex_id\": u64, \"offset\": f64}, ...]"),
+                )
+            }),
+        }
+    }
+
     fn get_destination_vertex(&self) -> Result<Option<VertexId>, PluginError> {
         match self.get(InputField::DestinationVertex.to_string()) {
             None => Ok(None),
@@ -118,6 +210,37 @@ impl InputJsonExtensions for serde_json::Value {
         }
     }
 
+    /// a GeoJSON polygon or multipolygon, any vertex within which is a destination
+    fn get_destination_polygon(&self) -> Option<&serde_json::Value> {
+        self.get(InputField::DestinationPolygon.to_string())
+    }
+    fn add_destination_vertices(&mut self, vertex_ids: Vec<VertexId>) -> Result<(), PluginError> {
+        match self {
+            serde_json::Value::Object(map) => {
+                map.insert(
+                    InputField::DestinationVertices.to_string(),
+                    json![vertex_ids],
+                );
+                Ok(())
+            }
+            _ => Err(PluginError::InputError(String::from(
+                "InputQuery is not a JSON object",
+            ))),
+        }
+    }
+    /// a set of destination vertices, any one of which ends the search, empty if not provided
+    fn get_destination_vertices(&self) -> Result<Vec<VertexId>, PluginError> {
+        match self.get(InputField::DestinationVertices.to_string()) {
+            None => Ok(vec![]),
+            Some(v) => serde_json::from_value::<Vec<VertexId>>(v.clone()).map_err(|_| {
+                PluginError::ParseError(
+                    InputField::DestinationVertices.to_string(),
+                    String::from("Vec<u64>"),
+                )
+            }),
+        }
+    }
+
     fn get_origin_edge(&self) -> Result<EdgeId, PluginError> {
         self.get(InputField::OriginEdge.to_string())
             .ok_or_else(|| PluginError::MissingField(InputField::OriginEdge.to_string()))?
@@ -136,9 +259,158 @@ impl InputJsonExtensions for serde_json::Value {
             }),
         }
     }
+    /// how an edge-oriented query treats the endpoints of its origin and destination
+    /// edges, strict if not provided
+    fn get_dead_end_policy(&self) -> Result<DeadEndPolicy, PluginError> {
+        match self.get(InputField::DeadEndPolicy.to_string()) {
+            None => Ok(DeadEndPolicy::default()),
+            Some(v) => serde_json::from_value::<DeadEndPolicy>(v.clone()).map_err(|_| {
+                PluginError::ParseError(
+                    InputField::DeadEndPolicy.to_string(),
+                    String::from("{\"type\": \"strict\" | \"both_endpoints\" | \"u_turn\"}"),
+                )
+            }),
+        }
+    }
+    /// the fraction of the length of the origin edge from its start at which the trip
+    /// begins, if provided
+    fn get_origin_edge_offset(&self) -> Result<Option<f64>, PluginError> {
+        get_edge_offset(self, InputField::OriginEdgeOffset)
+    }
+    /// the fraction of the length of the destination edge from its start at which the
+    /// trip ends, if provided
+    fn get_destination_edge_offset(&self) -> Result<Option<f64>, PluginError> {
+        get_edge_offset(self, InputField::DestinationEdgeOffset)
+    }
+    fn add_origin_edge_offset(&mut self, offset: f64) -> Result<(), PluginError> {
+        add_edge_offset(self, InputField::OriginEdgeOffset, offset)
+    }
+    fn add_destination_edge_offset(&mut self, offset: f64) -> Result<(), PluginError> {
+        add_edge_offset(self, InputField::DestinationEdgeOffset, offset)
+    }
     fn get_grid_search(&self) -> Option<&serde_json::Value> {
         self.get(InputField::GridSearch.to_string())
     }
+    fn get_termination(&self) -> Option<&serde_json::Value> {
+        self.get(InputField::Termination.to_string())
+    }
+    /// waypoints are an ordered list of `[x, y]` coordinate pairs, empty if not provided
+    fn get_waypoint_coordinates(&self) -> Result<Vec<geo::Coord<f32>>, PluginError> {
+        match self.get(InputField::Waypoints.to_string()) {
+            None => Ok(vec![]),
+            Some(v) => {
+                let pairs = serde_json::from_value::<Vec<(f32, f32)>>(v.clone()).map_err(|_| {
+                    PluginError::ParseError(
+                        InputField::Waypoints.to_string(),
+                        String::from("[[x, y], ...]"),
+                    )
+                })?;
+                Ok(pairs.into_iter().map(geo::Coord::from).collect())
+            }
+        }
+    }
+    fn add_waypoint_vertices(&mut self, vertex_ids: Vec<VertexId>) -> Result<(), PluginError> {
+        match self {
+            serde_json::Value::Object(map) => {
+                map.insert(InputField::WaypointVertices.to_string(), json![vertex_ids]);
+                Ok(())
+            }
+            _ => Err(PluginError::InputError(String::from(
+                "InputQuery is not a JSON object",
+            ))),
+        }
+    }
+    fn add_waypoint_edges(&mut self, edge_ids: Vec<EdgeId>) -> Result<(), PluginError> {
+        match self {
+            serde_json::Value::Object(map) => {
+                map.insert(InputField::WaypointEdges.to_string(), json![edge_ids]);
+                Ok(())
+            }
+            _ => Err(PluginError::InputError(String::from(
+                "InputQuery is not a JSON object",
+            ))),
+        }
+    }
+    fn get_waypoint_vertices(&self) -> Result<Vec<VertexId>, PluginError> {
+        match self.get(InputField::WaypointVertices.to_string()) {
+            None => Ok(vec![]),
+            Some(v) => serde_json::from_value::<Vec<VertexId>>(v.clone()).map_err(|_| {
+                PluginError::ParseError(
+                    InputField::WaypointVertices.to_string(),
+                    String::from("Vec<u64>"),
+                )
+            }),
+        }
+    }
+    fn get_waypoint_edges(&self) -> Result<Vec<EdgeId>, PluginError> {
+        match self.get(InputField::WaypointEdges.to_string()) {
+            None => Ok(vec![]),
+            Some(v) => serde_json::from_value::<Vec<EdgeId>>(v.clone()).map_err(|_| {
+                PluginError::ParseError(
+                    InputField::WaypointEdges.to_string(),
+                    String::from("Vec<u64>"),
+                )
+            }),
+        }
+    }
+    fn get_trace(&self) -> Result<Option<Vec<TracePoint>>, PluginError> {
+        let field = InputField::Trace.to_string();
+        let points = match self.get(&field) {
+            None => return Ok(None),
+            Some(v) => serde_json::from_value::<Vec<Vec<f64>>>(v.clone()).map_err(|_| {
+                PluginError::ParseError(field.clone(), String::from("[[lon, lat, ts], ...]"))
+            })?,
+        };
+        let trace = points
+            .iter()
+            .map(|point| match point.as_slice() {
+                [x, y] => Ok((geo::Coord::from((*x as f32, *y as f32)), None)),
+                [x, y, t] => Ok((geo::Coord::from((*x as f32, *y as f32)), Some(*t))),
+                _ => Err(PluginError::ParseError(
+                    field.clone(),
+                    String::from("[[lon, lat, ts], ...]"),
+                )),
+            })
+            .collect::<Result<Vec<_>, PluginError>>()?;
+        Ok(Some(trace))
+    }
+    fn add_route_edges(&mut self, edge_ids: Vec<EdgeId>) -> Result<(), PluginError> {
+        match self {
+            serde_json::Value::Object(map) => {
+                map.insert(InputField::RouteEdges.to_string(), json![edge_ids]);
+                Ok(())
+            }
+            _ => Err(PluginError::InputError(String::from(
+                "InputQuery is not a JSON object",
+            ))),
+        }
+    }
+    fn get_route_edges(&self) -> Result<Option<Vec<EdgeId>>, PluginError> {
+        match self.get(InputField::RouteEdges.to_string()) {
+            None => Ok(None),
+            Some(v) => serde_json::from_value::<Vec<EdgeId>>(v.clone())
+                .map(Some)
+                .map_err(|_| {
+                    PluginError::ParseError(
+                        InputField::RouteEdges.to_string(),
+                        String::from("Vec<u64>"),
+                    )
+                }),
+        }
+    }
+    fn get_objectives(&self) -> Result<Option<Vec<String>>, PluginError> {
+        match self.get(InputField::Objectives.to_string()) {
+            None => Ok(None),
+            Some(v) => serde_json::from_value::<Vec<String>>(v.clone())
+                .map(Some)
+                .map_err(|_| {
+                    PluginError::ParseError(
+                        InputField::Objectives.to_string(),
+                        String::from("Vec<String>"),
+                    )
+                }),
+        }
+    }
 
     fn add_origin_edge(&mut self, edge_id: EdgeId) -> Result<(), PluginError> {
         match self {
@@ -195,6 +467,38 @@ impl InputJsonExtensions for serde_json::Value {
     }
 }
 
+fn get_edge_offset(
+    value: &serde_json::Value,
+    field: InputField,
+) -> Result<Option<f64>, PluginError> {
+    match value.get(field.to_string()) {
+        None => Ok(None),
+        Some(v) => match v.as_f64() {
+            Some(offset) if (0.0..=1.0).contains(&offset) => Ok(Some(offset)),
+            _ => Err(PluginError::ParseError(
+                field.to_string(),
+                String::from("f64 in the range [0, 1]"),
+            )),
+        },
+    }
+}
+
+fn add_edge_offset(
+    value: &mut serde_json::Value,
+    field: InputField,
+    offset: f64,
+) -> Result<(), PluginError> {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.insert(field.to_string(), json!(offset));
+            Ok(())
+        }
+        _ => Err(PluginError::InputError(String::from(
+            "InputQuery is not a JSON object",
+        ))),
+    }
+}
+
 // pub type DecodeOp<T> = Box<dyn Fn(&serde_json::Value) -> Option<T>>;
 
 // fn get_from_json<T>(
@@ -1,15 +1,23 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
+use super::input_error_policy::InputErrorPolicy;
+use super::input_field::InputField;
+use super::input_plugin::InputPlugin;
+use crate::app::compass::error_taxonomy::{ErrorCode, ErrorComponent, ErrorDetail, ErrorTaxonomy};
+use crate::app::compass::query_id::expanded_query_id;
 use crate::plugin::plugin_error::PluginError;
 use indoc::indoc;
 use serde_json::{json, Value};
 
 /// helper to return errors as JSON response objects which include the
-/// original request along with the error message
-pub fn package_error<E: ToString>(query: &mut Value, error: E) -> Value {
+/// original request along with the code, message and component of the error
+pub fn package_error<E: ErrorTaxonomy>(query: &mut Value, error: E) -> Value {
+    let detail = error
+        .error_detail()
+        .attributed_to(ErrorComponent::InputPlugin);
     json!({
         "request": query,
-        "error": error.to_string()
+        "error": detail.to_json()
     })
 }
 
@@ -56,97 +64,167 @@ pub fn package_invariant_error(
         }
     };
 
+    let error = ErrorDetail::new(ErrorCode::InternalError, ErrorComponent::InputPlugin, msg);
     match query {
-        Some(q) => package_error(q, msg),
-        None => package_error(&mut json![{"error": "unable to display query"}], msg),
+        Some(q) => package_error(q, error),
+        None => package_error(&mut json![{"error": "unable to display query"}], error),
     }
 }
 
-pub type ArrayOp<'a> = Rc<dyn Fn(&mut Value) -> Result<(), PluginError> + 'a>;
+/// the field of a query which lists the errors of input plugins that failed under the
+/// [`InputErrorPolicy::PassThrough`] policy
+pub const INPUT_PLUGIN_ERRORS_FIELD: &str = "input_plugin_errors";
 
-/// executes an operation on an input query. maintains the invariant that
-/// input queries should always remain wrapped in a top-level JSON Array
-/// so that we can perform operations like grid search, which transform a
-/// single query into multiple child queries.
-pub fn json_array_op<'a>(query: &'a mut Value, op: ArrayOp<'a>) -> Result<(), Value> {
-    match query {
-        Value::Array(queries) => {
-            for q in queries.iter_mut() {
-                op(q).map_err(|e| package_error(q, e))?;
+/// applies each input plugin in order to a query. a plugin may expand a query into
+/// many, each of which is processed by the plugins that follow. when the query has a
+/// `query_id`, each query expanded from it is given a `query_id` of its own and the
+/// original id as its `parent_query_id`.
+/// failures are handled according to the error policy.
+///
+/// # Arguments
+///
+/// * `query` - the user query
+/// * `plugins` - the input plugins to apply
+/// * `policy` - how to handle a query which a plugin fails to process
+///
+/// # Returns
+///
+/// the processed queries, along with an error response for each query that was
+/// dropped. under [`InputErrorPolicy::FailFast`], processing stops at the first error.
+pub fn apply_input_plugins(
+    query: &Value,
+    plugins: &[Arc<dyn InputPlugin>],
+    policy: InputErrorPolicy,
+) -> (Vec<Value>, Vec<Value>) {
+    let mut queries = vec![query.clone()];
+    let mut errors = vec![];
+    for plugin in plugins.iter() {
+        let mut processed = Vec::with_capacity(queries.len());
+        for mut q in queries.into_iter() {
+            match plugin.process(&q) {
+                Ok(mut expanded) => {
+                    if expanded.len() > 1 {
+                        add_expanded_query_ids(&q, &mut expanded);
+                    }
+                    processed.extend(expanded);
+                }
+                Err(e) if policy == InputErrorPolicy::PassThrough => {
+                    add_input_plugin_error(&mut q, e);
+                    processed.push(q);
+                }
+                Err(e) => {
+                    errors.push(package_error(&mut q, e));
+                    if policy == InputErrorPolicy::FailFast {
+                        return (vec![], errors);
+                    }
+                }
             }
-            json_array_flatten_in_place(query)
-        }
-        other => {
-            let error = package_invariant_error(None, Some(other));
-            Err(error)
         }
+        queries = processed;
     }
+
+    // every query must be a JSON object once all plugins have run
+    let (queries, mut invalid): (Vec<_>, Vec<_>) = queries.into_iter().partition(|q| q.is_object());
+    errors.extend(
+        invalid
+            .iter_mut()
+            .map(|q| package_invariant_error(Some(q), None)),
+    );
+    (queries, errors)
 }
 
-/// flattens the result of input processing into a response vector, ensuring
-/// that the nesting and types are correct. the flatten operation effect occurs
-/// in-place on the function argument via a memory swap.
-pub fn json_array_flatten_in_place(result: &mut Value) -> Result<(), Value> {
-    if let Value::Array(top_array) = result {
-        if top_array.iter().all(|v| !v.is_array()) {
-            // short circuit if there are no nested arrays
-            return Ok(());
+/// gives each query expanded from a parent query its own `query_id`, along with the
+/// `parent_query_id` of the user query it came from. a query which was itself expanded
+/// from another keeps its original parent.
+fn add_expanded_query_ids(parent: &Value, expanded: &mut [Value]) {
+    let parent_id = match parent.get(InputField::QueryId.to_str()) {
+        Some(id) => id,
+        None => return,
+    };
+    let root_id = parent
+        .get(InputField::ParentQueryId.to_str())
+        .unwrap_or(parent_id);
+    for (idx, child) in expanded.iter_mut().enumerate() {
+        if let Value::Object(obj) = child {
+            obj.insert(
+                InputField::QueryId.to_str().to_string(),
+                expanded_query_id(parent_id, idx),
+            );
+            obj.insert(
+                InputField::ParentQueryId.to_str().to_string(),
+                root_id.clone(),
+            );
         }
+    }
+}
 
-        // de-nest sub-arrays into new vector
-        let mut flattened: Vec<&mut Value> = vec![];
-        for v1 in top_array.iter_mut() {
-            match v1 {
-                Value::Array(sub_array) => {
-                    for v2 in sub_array.iter_mut() {
-                        flattened.push(v2)
-                    }
-                }
-                other => flattened.push(other),
-            }
+/// appends the detail of an input plugin error to the errors listed on a query
+fn add_input_plugin_error(query: &mut Value, error: PluginError) {
+    let detail = error
+        .error_detail()
+        .attributed_to(ErrorComponent::InputPlugin)
+        .to_json();
+    if let Value::Object(obj) = query {
+        match obj
+            .entry(INPUT_PLUGIN_ERRORS_FIELD)
+            .or_insert_with(|| json![[]])
+        {
+            Value::Array(errors) => errors.push(detail),
+            other => *other = json![[detail]],
         }
-        let mut flat_result = json![flattened];
-        std::mem::swap(result, &mut flat_result);
-        Ok(())
-    } else {
-        let error_response = package_invariant_error(Some(result), None);
-        Err(error_response)
     }
 }
 
-/// flattens the result of input processing into a response vector, ensuring
-/// that the nesting and types are correct. returns a new vector of values,
-/// moving the valid input processing results into the new flattened vector.
-pub fn json_array_flatten(result: &mut Value) -> Result<Vec<Value>, Value> {
-    let mut flattened: Vec<Value> = vec![];
-    if !result.is_array() {
-        let error_response = package_invariant_error(Some(result), None);
-        return Err(error_response);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::input::input_plugin::InputPlugin;
+
+    /// expands a query into two copies, one of which is marked as invalid
+    struct ExpandPlugin {}
+    impl InputPlugin for ExpandPlugin {
+        fn process(&self, input: &Value) -> Result<Vec<Value>, PluginError> {
+            let mut invalid = input.clone();
+            invalid["invalid"] = json!(true);
+            Ok(vec![input.clone(), invalid])
+        }
     }
-    let mut error: Option<&mut Value> = None;
-    match result {
-        Value::Array(sub_array) => {
-            for sub_obj in sub_array.iter_mut() {
-                match sub_obj {
-                    Value::Object(obj) => {
-                        flattened.push(json![obj]);
-                    }
-                    other => {
-                        error = Some(other);
-                    }
-                }
+
+    /// fails on queries marked as invalid
+    struct CheckPlugin {}
+    impl InputPlugin for CheckPlugin {
+        fn process(&self, input: &Value) -> Result<Vec<Value>, PluginError> {
+            match input.get("invalid") {
+                Some(_) => Err(PluginError::MissingField(String::from("origin_x"))),
+                None => Ok(vec![input.clone()]),
             }
         }
-        other => {
-            error = Some(other);
-        }
     }
 
-    match error {
-        Some(_) => {
-            let error_response = package_invariant_error(None, error);
-            Err(error_response)?
-        }
-        None => Ok(flattened),
+    #[test]
+    fn test_apply_input_plugins_error_policy() {
+        let plugins: Vec<Arc<dyn InputPlugin>> =
+            vec![Arc::new(ExpandPlugin {}), Arc::new(CheckPlugin {})];
+        let query = json!({ "origin_x": 0.0, "query_id": 7 });
+        let child = json!({ "origin_x": 0.0, "query_id": "7-0", "parent_query_id": 7 });
+
+        let (queries, errors) = apply_input_plugins(&query, &plugins, InputErrorPolicy::FailFast);
+        assert_eq!((queries.len(), errors.len()), (0, 1));
+
+        let (queries, errors) =
+            apply_input_plugins(&query, &plugins, InputErrorPolicy::SkipAndRecord);
+        assert_eq!(queries, vec![child]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["request"]["invalid"], json!(true));
+
+        let (queries, errors) =
+            apply_input_plugins(&query, &plugins, InputErrorPolicy::PassThrough);
+        assert_eq!(queries.len(), 2);
+        assert!(errors.is_empty());
+        assert_eq!(queries[1]["parent_query_id"], json!(7));
+        assert_eq!(
+            queries[1][INPUT_PLUGIN_ERRORS_FIELD][0]["code"],
+            "MISSING_FIELD"
+        );
     }
 }
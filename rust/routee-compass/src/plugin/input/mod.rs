@@ -1,4 +1,5 @@
 pub mod default;
+pub mod input_error_policy;
 pub mod input_field;
 pub mod input_json_extensions;
 pub mod input_plugin;
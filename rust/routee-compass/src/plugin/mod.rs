@@ -1,3 +1,4 @@
+pub mod dynamic;
 pub mod input;
 pub mod output;
 pub mod plugin_error;
@@ -0,0 +1,60 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use super::plugin::BatchSummaryPlugin;
+use crate::{
+    app::compass::config::{
+        builders::OutputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::output::output_plugin::OutputPlugin,
+};
+
+/// Builds a plugin that aggregates the route totals of every query in a run.
+///
+/// # Configuration
+///
+/// This plugin expects the following keys:
+/// * `output_file` - the JSON file to write the summary to at the end of each run
+/// * `group_by` (optional) - query field to group the summary by, `model_name` by default
+/// * `histogram_bins` (optional) - number of bins in each histogram, 10 by default
+/// * `units` (optional) - unit to summarize each state feature in, by feature name.
+///   features without a unit are summarized in their unit in the first query of each group
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "batch_summary"
+/// output_file = "summary.json"
+/// group_by = "model_name"
+/// units = { distance = "miles", time = "hours" }
+/// ```
+///
+pub struct BatchSummaryPluginBuilder {}
+
+impl OutputPluginBuilder for BatchSummaryPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("batch_summary");
+        let output_file = parameters.get_config_string(&"output_file", &parent_key)?;
+        let group_by = parameters
+            .get_config_serde_optional::<String>(&"group_by", &parent_key)?
+            .unwrap_or_else(|| String::from(BatchSummaryPlugin::DEFAULT_GROUP_BY));
+        let histogram_bins = parameters
+            .get_config_serde_optional::<usize>(&"histogram_bins", &parent_key)?
+            .unwrap_or(BatchSummaryPlugin::DEFAULT_HISTOGRAM_BINS);
+        if histogram_bins == 0 {
+            return Err(CompassConfigurationError::UserConfigurationError(
+                String::from("batch_summary.histogram_bins must be positive"),
+            ));
+        }
+        let units = parameters
+            .get_config_serde_optional::<HashMap<String, String>>(&"units", &parent_key)?
+            .unwrap_or_default();
+        let plugin =
+            BatchSummaryPlugin::new(PathBuf::from(output_file), group_by, histogram_bins, units);
+        Ok(Arc::new(plugin))
+    }
+}
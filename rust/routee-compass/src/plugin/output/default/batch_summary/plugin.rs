@@ -0,0 +1,278 @@
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// aggregates the state at the end of each route across every query of a run, such as
+/// the total and mean distance, time and energy, grouped by a query field such as the
+/// vehicle model. the summary is written as JSON when the app shuts down.
+pub struct BatchSummaryPlugin {
+    output_file: PathBuf,
+    group_by: String,
+    histogram_bins: usize,
+    /// the unit to summarize each feature in, by feature name
+    units: HashMap<String, String>,
+    groups: Mutex<BTreeMap<String, GroupAccumulator>>,
+}
+
+/// the route totals collected for one group of queries
+#[derive(Default)]
+struct GroupAccumulator {
+    queries: usize,
+    failed: usize,
+    /// each state feature with its unit and its value at the end of each route
+    features: BTreeMap<String, (String, Vec<f64>)>,
+}
+
+/// the summary of one group of queries
+#[derive(Serialize, Debug)]
+pub struct GroupSummary {
+    pub queries: usize,
+    pub failed: usize,
+    pub features: BTreeMap<String, FeatureSummary>,
+}
+
+/// the distribution of one state feature at the end of each route
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FeatureSummary {
+    pub unit: String,
+    pub count: usize,
+    pub total: f64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub histogram: Histogram,
+}
+
+/// equal-width bins between the min and max of a feature, where `bin_edges` has one
+/// more entry than `counts`
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Histogram {
+    pub bin_edges: Vec<f64>,
+    pub counts: Vec<usize>,
+}
+
+impl BatchSummaryPlugin {
+    pub const DEFAULT_GROUP_BY: &'static str = "model_name";
+    pub const DEFAULT_HISTOGRAM_BINS: usize = 10;
+    /// the group of queries without the `group_by` field
+    pub const DEFAULT_GROUP: &'static str = "all";
+    /// the response field holding the value of each state feature counted for the query
+    pub const RESPONSE_FIELD: &'static str = "batch_summary";
+
+    pub fn new(
+        output_file: PathBuf,
+        group_by: String,
+        histogram_bins: usize,
+        units: HashMap<String, String>,
+    ) -> Self {
+        BatchSummaryPlugin {
+            output_file,
+            group_by,
+            histogram_bins,
+            units,
+            groups: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// summarizes the queries recorded since the last summary
+    pub fn summarize(&self) -> Result<BTreeMap<String, GroupSummary>, PluginError> {
+        let groups = std::mem::take(&mut *self.lock()?);
+        let summary = groups
+            .into_iter()
+            .map(|(group, acc)| {
+                let features = acc
+                    .features
+                    .into_iter()
+                    .map(|(name, (unit, values))| {
+                        (name, summarize_feature(unit, &values, self.histogram_bins))
+                    })
+                    .collect();
+                let group_summary = GroupSummary {
+                    queries: acc.queries,
+                    failed: acc.failed,
+                    features,
+                };
+                (group, group_summary)
+            })
+            .collect();
+        Ok(summary)
+    }
+
+    fn group_name(&self, output: &Value) -> String {
+        match output.get("request").and_then(|r| r.get(&self.group_by)) {
+            None | Some(Value::Null) => String::from(BatchSummaryPlugin::DEFAULT_GROUP),
+            Some(Value::String(name)) => name.clone(),
+            Some(other) => other.to_string(),
+        }
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, BTreeMap<String, GroupAccumulator>>, PluginError> {
+        self.groups
+            .lock()
+            .map_err(|e| PluginError::InternalError(format!("batch summary poisoned: {}", e)))
+    }
+}
+
+impl OutputPlugin for BatchSummaryPlugin {
+    /// counts the query in its group, along with the value of each state feature at the
+    /// end of its route. the values are also added to the response, so that a response
+    /// served from the result cache is counted in the same way.
+    fn process(
+        &self,
+        output: &mut Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        let group = self.group_name(output);
+        let mut groups = self.lock()?;
+        let acc = groups.entry(group).or_default();
+        acc.queries += 1;
+        let (result, si) = match search_result {
+            Err(_) => {
+                acc.failed += 1;
+                return Ok(());
+            }
+            Ok(result) => result,
+        };
+        // only the chosen route is counted, so that alternative routes do not count
+        // as extra queries
+        let last_state = match result.routes.first().and_then(|route| route.last()) {
+            Some(et) => &et.result_state,
+            None => return Ok(()),
+        };
+        let mut recorded = serde_json::Map::new();
+        // each feature is summarized in its configured unit, or else in its unit in the
+        // first query of the group, so that queries whose state models use different
+        // units are not summed together
+        for (name, feature) in si.state_model.iter() {
+            let (unit, values) = acc.features.entry(name.clone()).or_insert_with(|| {
+                let unit = self
+                    .units
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| feature.get_feature_unit_name());
+                (unit, vec![])
+            });
+            let value = si
+                .state_model
+                .get_value(last_state, name, unit)
+                .map_err(|e| PluginError::PluginFailed(e.to_string()))?;
+            values.push(value);
+            recorded.insert(name.clone(), json!({ "unit": unit, "value": value }));
+        }
+        output[BatchSummaryPlugin::RESPONSE_FIELD] = Value::Object(recorded);
+        Ok(())
+    }
+
+    fn process_cached(&self, output: &Value) -> Result<(), PluginError> {
+        let group = self.group_name(output);
+        let mut groups = self.lock()?;
+        let acc = groups.entry(group).or_default();
+        acc.queries += 1;
+        let recorded = match output
+            .get(BatchSummaryPlugin::RESPONSE_FIELD)
+            .and_then(Value::as_object)
+        {
+            Some(recorded) => recorded,
+            None => return Ok(()),
+        };
+        for (name, recorded_value) in recorded.iter() {
+            let (unit, value) = match (
+                recorded_value.get("unit").and_then(Value::as_str),
+                recorded_value.get("value").and_then(Value::as_f64),
+            ) {
+                (Some(unit), Some(value)) => (unit, value),
+                _ => continue,
+            };
+            let (group_unit, values) = acc
+                .features
+                .entry(name.clone())
+                .or_insert_with(|| (unit.to_string(), vec![]));
+            if group_unit == unit {
+                values.push(value);
+            } else {
+                log::warn!(
+                    "batch summary skipping cached {} in {}, expected {}",
+                    name,
+                    unit,
+                    group_unit
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<(), PluginError> {
+        let summary = self.summarize()?;
+        let contents = serde_json::to_string_pretty(&summary)?;
+        std::fs::write(&self.output_file, contents).map_err(|e| {
+            PluginError::PluginFailed(format!(
+                "failed writing batch summary {:?}: {}",
+                self.output_file, e
+            ))
+        })?;
+        log::info!("batch summary written to {:?}", self.output_file);
+        Ok(())
+    }
+}
+
+fn summarize_feature(unit: String, values: &[f64], bins: usize) -> FeatureSummary {
+    let count = values.len();
+    let total = values.iter().sum::<f64>();
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let width = (max - min) / bins as f64;
+    let bin_edges = (0..=bins).map(|i| min + width * i as f64).collect();
+    let mut counts = vec![0; bins];
+    for value in values.iter() {
+        let bin = if width > 0.0 {
+            (((value - min) / width) as usize).min(bins - 1)
+        } else {
+            0
+        };
+        counts[bin] += 1;
+    }
+    FeatureSummary {
+        unit,
+        count,
+        total,
+        mean: total / count as f64,
+        min,
+        max,
+        histogram: Histogram { bin_edges, counts },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_feature() {
+        let values = [1.0, 2.0, 2.5, 5.0];
+        let summary = summarize_feature(String::from("kilometers"), &values, 2);
+        assert_eq!(
+            summary,
+            FeatureSummary {
+                unit: String::from("kilometers"),
+                count: 4,
+                total: 10.5,
+                mean: 2.625,
+                min: 1.0,
+                max: 5.0,
+                histogram: Histogram {
+                    bin_edges: vec![1.0, 3.0, 5.0],
+                    counts: vec![3, 1],
+                },
+            }
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use super::plugin::{AttributeColumn, AttributeType, EdgeAttributesPlugin};
+use crate::{
+    app::compass::config::{
+        builders::OutputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::{output::output_plugin::OutputPlugin, plugin_error::PluginError},
+};
+use routee_compass_core::{
+    model::road_network::graph::Graph,
+    util::fs::{read_decoders, read_utils::read_raw_file},
+};
+
+/// Builds a plugin that joins per-edge metadata files to the edges of each route.
+///
+/// # Configuration
+///
+/// This plugin expects the following keys:
+/// * `attribute_input_files` - a table from the name of each attribute to a file
+///   with its value for each edge, one per line in edge id order
+/// * `attribute_types` (optional) - a table from the name of an attribute to the
+///   type of its values, one of `string`, `f64`, `i64` or `bool`. attributes are
+///   written as text unless a type is declared.
+///
+/// every file must have one line for each edge of the graph.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "edge_attributes"
+/// [plugin.output_plugins.attribute_input_files]
+/// street_name = "edges-names.txt.gz"
+/// speed_limit = "edges-posted-speed-enumerated.txt.gz"
+/// functional_class = "edges-road-class.txt.gz"
+/// [plugin.output_plugins.attribute_types]
+/// speed_limit = "f64"
+/// ```
+///
+pub struct EdgeAttributesPluginBuilder {}
+
+impl OutputPluginBuilder for EdgeAttributesPluginBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from(
+                "edge_attributes: the edge attributes plugin must be built with the graph of the app",
+            ),
+        ))
+    }
+
+    /// builds the plugin, checking that each attribute file has a line for every edge
+    /// of the graph
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("edge_attributes");
+        let files: BTreeMap<String, PathBuf> =
+            parameters.get_config_serde(&"attribute_input_files", &parent_key)?;
+        if files.is_empty() {
+            return Err(CompassConfigurationError::UserConfigurationError(
+                String::from(
+                    "edge_attributes plugin requires at least one entry in attribute_input_files",
+                ),
+            ));
+        }
+        let mut types: BTreeMap<String, AttributeType> = parameters
+            .get_config_serde_optional(&"attribute_types", &parent_key)?
+            .unwrap_or_default();
+        let attributes = files
+            .into_iter()
+            .map(|(name, file)| {
+                let values = read_raw_file(&file, read_decoders::string, None)
+                    .map_err(|e| PluginError::FileReadError(file.clone(), e.to_string()))?;
+                if values.len() != graph.n_edges() {
+                    return Err(CompassConfigurationError::UserConfigurationError(format!(
+                        "edge_attributes: file {} for attribute '{}' has {} lines but the graph has {} edges",
+                        file.display(),
+                        name,
+                        values.len(),
+                        graph.n_edges()
+                    )));
+                }
+                let attribute_type = types.remove(&name).unwrap_or_default();
+                let column = AttributeColumn::parse(&name, &values, attribute_type)?;
+                Ok((name, column))
+            })
+            .collect::<Result<Vec<_>, CompassConfigurationError>>()?;
+        if let Some(name) = types.keys().next() {
+            return Err(CompassConfigurationError::UserConfigurationError(format!(
+                "edge_attributes: attribute_types has '{}', which is not in attribute_input_files",
+                name
+            )));
+        }
+        let plugin = EdgeAttributesPlugin::new(attributes)?;
+        Ok(Arc::new(plugin))
+    }
+}
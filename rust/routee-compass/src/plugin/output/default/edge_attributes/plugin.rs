@@ -0,0 +1,237 @@
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use routee_compass_core::model::road_network::edge_id::EdgeId;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt::Display;
+
+/// the type of the values of an attribute, declared for the whole attribute so that
+/// every edge has a value of the same type
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeType {
+    /// text, written as it appears in the file, such as a street name or a ZIP code
+    #[default]
+    String,
+    F64,
+    I64,
+    Bool,
+}
+
+impl Display for AttributeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AttributeType::String => "string",
+            AttributeType::F64 => "f64",
+            AttributeType::I64 => "i64",
+            AttributeType::Bool => "bool",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// the values of one attribute for every edge, where an empty value is missing
+pub enum AttributeColumn {
+    String(Box<[Option<String>]>),
+    F64(Box<[Option<f64>]>),
+    I64(Box<[Option<i64>]>),
+    Bool(Box<[Option<bool>]>),
+}
+
+impl AttributeColumn {
+    /// parses the text of each edge as the declared type of the attribute, failing on
+    /// the first value which is not of that type
+    pub fn parse(
+        name: &str,
+        values: &[String],
+        attribute_type: AttributeType,
+    ) -> Result<AttributeColumn, PluginError> {
+        fn parse_all<T>(
+            name: &str,
+            values: &[String],
+            attribute_type: AttributeType,
+            op: impl Fn(&str) -> Option<T>,
+        ) -> Result<Box<[Option<T>]>, PluginError> {
+            values
+                .iter()
+                .enumerate()
+                .map(|(edge_id, text)| match text.trim() {
+                    "" => Ok(None),
+                    text => op(text).map(Some).ok_or_else(|| {
+                        PluginError::PluginFailed(format!(
+                            "edge attribute '{}' has value '{}' for edge {}, which is not a {}",
+                            name, text, edge_id, attribute_type
+                        ))
+                    }),
+                })
+                .collect()
+        }
+        let column = match attribute_type {
+            AttributeType::String => {
+                AttributeColumn::String(parse_all(name, values, attribute_type, |t| {
+                    Some(t.to_string())
+                })?)
+            }
+            AttributeType::F64 => {
+                AttributeColumn::F64(parse_all(name, values, attribute_type, |t| {
+                    t.parse::<f64>().ok().filter(|f| f.is_finite())
+                })?)
+            }
+            AttributeType::I64 => {
+                AttributeColumn::I64(parse_all(name, values, attribute_type, |t| {
+                    t.parse::<i64>().ok()
+                })?)
+            }
+            AttributeType::Bool => {
+                AttributeColumn::Bool(parse_all(name, values, attribute_type, |t| {
+                    t.to_lowercase().parse::<bool>().ok()
+                })?)
+            }
+        };
+        Ok(column)
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            AttributeColumn::String(values) => values.len(),
+            AttributeColumn::F64(values) => values.len(),
+            AttributeColumn::I64(values) => values.len(),
+            AttributeColumn::Bool(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the JSON value of an edge, or null when it is missing
+    fn get(&self, edge_id: EdgeId) -> serde_json::Value {
+        let idx = edge_id.0;
+        let value = match self {
+            AttributeColumn::String(values) => values.get(idx).cloned().flatten().map(|v| json![v]),
+            AttributeColumn::F64(values) => values.get(idx).copied().flatten().map(|v| json![v]),
+            AttributeColumn::I64(values) => values.get(idx).copied().flatten().map(|v| json![v]),
+            AttributeColumn::Bool(values) => values.get(idx).copied().flatten().map(|v| json![v]),
+        };
+        value.unwrap_or(serde_json::Value::Null)
+    }
+}
+
+/// joins per-edge metadata, such as street names, speed limits or functional
+/// classes, to the edges of each route. the metadata is read from files with one
+/// value per edge, so it does not need to be part of the graph or the search.
+pub struct EdgeAttributesPlugin {
+    /// the name of each attribute along with its value for each edge
+    attributes: Vec<(String, AttributeColumn)>,
+}
+
+impl EdgeAttributesPlugin {
+    pub const OUTPUT_KEY: &'static str = "edge_attributes";
+    const EDGE_ID_KEY: &'static str = "edge_id";
+
+    pub fn new(attributes: Vec<(String, AttributeColumn)>) -> Result<Self, PluginError> {
+        if attributes.iter().any(|(name, _)| name == Self::EDGE_ID_KEY) {
+            return Err(PluginError::PluginFailed(format!(
+                "edge attribute name '{}' is reserved",
+                Self::EDGE_ID_KEY
+            )));
+        }
+        Ok(EdgeAttributesPlugin { attributes })
+    }
+
+    /// one object per edge with its id and the value of each attribute, or null for
+    /// an edge with an empty value
+    pub fn route_attributes(&self, edge_ids: &[EdgeId]) -> Vec<serde_json::Value> {
+        edge_ids
+            .iter()
+            .map(|edge_id| {
+                let mut row = serde_json::Map::new();
+                row.insert(String::from(Self::EDGE_ID_KEY), json![edge_id]);
+                for (name, column) in self.attributes.iter() {
+                    row.insert(name.clone(), column.get(*edge_id));
+                }
+                serde_json::Value::Object(row)
+            })
+            .collect()
+    }
+}
+
+impl OutputPlugin for EdgeAttributesPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        let result = match search_result {
+            Err(_) => return Ok(()),
+            Ok((result, _)) => result,
+        };
+        let routes = result
+            .routes
+            .iter()
+            .map(|route| {
+                let edge_ids = route.iter().map(|et| et.edge_id).collect::<Vec<_>>();
+                json![self.route_attributes(&edge_ids)]
+            })
+            .collect::<Vec<_>>();
+        // as with traversal output, store null for no route, an array of edges for
+        // one route, and an array of arrays for many routes.
+        output[Self::OUTPUT_KEY] = match routes.as_slice() {
+            [] => serde_json::Value::Null,
+            [route] => route.to_owned(),
+            _ => json![routes],
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_attributes() {
+        let column = |values: &[&str], attribute_type: AttributeType| {
+            let values = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+            AttributeColumn::parse("test", &values, attribute_type).unwrap()
+        };
+        let plugin = EdgeAttributesPlugin::new(vec![
+            (
+                String::from("street_name"),
+                column(&["Main St", "", "101"], AttributeType::String),
+            ),
+            (
+                String::from("zip_code"),
+                column(&["02134", "02135", "02134"], AttributeType::String),
+            ),
+            (
+                String::from("speed_limit"),
+                column(&["35", "55.5", "25"], AttributeType::F64),
+            ),
+            (
+                String::from("toll"),
+                column(&["false", "true", ""], AttributeType::Bool),
+            ),
+        ])
+        .unwrap();
+        let rows = plugin.route_attributes(&[EdgeId(2), EdgeId(1)]);
+        assert_eq!(
+            rows,
+            vec![
+                json!({"edge_id": 2, "street_name": "101", "zip_code": "02134", "speed_limit": 25.0, "toll": null}),
+                json!({"edge_id": 1, "street_name": null, "zip_code": "02135", "speed_limit": 55.5, "toll": true}),
+            ]
+        );
+
+        let values = vec![String::from("3"), String::from("three")];
+        assert!(AttributeColumn::parse("lanes", &values, AttributeType::I64).is_err());
+        let reserved = EdgeAttributesPlugin::new(vec![(
+            String::from("edge_id"),
+            column(&[], AttributeType::String),
+        )]);
+        assert!(reserved.is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use std::{path::PathBuf, sync::Arc};
+
+use super::{edge_detail_format::EdgeDetailFormat, plugin::EdgeDetailPlugin};
+use crate::{
+    app::compass::config::{
+        builders::OutputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::output::output_plugin::OutputPlugin,
+};
+
+/// Builds a plugin that reports the traversal of each edge of a route.
+///
+/// # Configuration
+///
+/// This plugin expects the following keys:
+/// * `format` (optional) - either `json` (default) or `csv`
+/// * `output_file` - the CSV file to write, required when `format` is `csv`
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "edge_detail"
+/// format = "csv"
+/// output_file = "edge_detail.csv"
+/// ```
+///
+pub struct EdgeDetailPluginBuilder {}
+
+impl OutputPluginBuilder for EdgeDetailPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("edge_detail");
+        let format: EdgeDetailFormat = parameters
+            .get_config_serde_optional(&"format", &parent_key)?
+            .unwrap_or(EdgeDetailFormat::Json);
+        let plugin = match format {
+            EdgeDetailFormat::Json => EdgeDetailPlugin::json(),
+            EdgeDetailFormat::Csv => {
+                let output_file = parameters.get_config_string(&"output_file", &parent_key)?;
+                EdgeDetailPlugin::csv(PathBuf::from(output_file))?
+            }
+        };
+        Ok(Arc::new(plugin))
+    }
+}
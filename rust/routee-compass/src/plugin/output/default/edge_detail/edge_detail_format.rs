@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// where the detail of each edge of a route is written
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeDetailFormat {
+    /// a nested array of edge objects in the query output
+    Json,
+    /// rows of a sidecar CSV file, keyed by query index
+    Csv,
+}
@@ -0,0 +1,215 @@
+use crate::app::search::state_timeline::StateTimeline;
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::{
+    algorithm::search::{edge_traversal::EdgeTraversal, search_instance::SearchInstance},
+    model::{
+        road_network::edge_id::EdgeId,
+        state::state_error::StateError,
+        traversal::state::state_variable::StateVar,
+        unit::{as_f64::AsF64, Cost, Distance, DistanceUnit, TimeUnit, BASE_DISTANCE_UNIT},
+    },
+};
+use serde_json::json;
+
+const DISTANCE_FEATURE: &str = "distance";
+const TIME_FEATURE: &str = "time";
+
+/// the traversal of a single edge of a route, with the change in each state
+/// feature over the edge and the state accumulated up to the end of the edge.
+#[derive(Debug, Clone)]
+pub struct EdgeDetail {
+    pub edge_id: EdgeId,
+    /// length of the edge in the base distance unit
+    pub distance: Distance,
+    /// average speed over the edge in kilometers per hour, when the state model
+    /// has both `distance` and `time` features
+    pub speed_kph: Option<f64>,
+    pub access_cost: Cost,
+    pub traversal_cost: Cost,
+    /// change in each state feature over the edge, in state model order
+    pub deltas: Vec<(String, f64)>,
+    /// each state feature at the end of the edge, in state model order
+    pub state: Vec<(String, f64)>,
+}
+
+impl EdgeDetail {
+    /// builds the detail of each edge of a route by comparing the state at the end
+    /// of each edge with the state at its start.
+    pub fn from_route(
+        route: &[EdgeTraversal],
+        si: &SearchInstance,
+    ) -> Result<Vec<EdgeDetail>, PluginError> {
+        let state_error = |e: StateError| PluginError::PluginFailed(format!("edge detail: {}", e));
+        let names = si
+            .state_model
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        let has_speed = si.state_model.contains_key(&String::from(DISTANCE_FEATURE))
+            && si.state_model.contains_key(&String::from(TIME_FEATURE));
+
+        let timeline = StateTimeline::new(route, si).map_err(state_error)?;
+        let mut details = Vec::with_capacity(route.len());
+        for (et, prev_state, next_state) in timeline.edges() {
+            let edge = si
+                .directed_graph
+                .get_edge(et.edge_id)
+                .map_err(|e| PluginError::PluginFailed(format!("edge detail: {}", e)))?;
+            let deltas = names
+                .iter()
+                .map(|name| {
+                    let delta = si.state_model.get_delta(prev_state, next_state, name)?;
+                    Ok((name.clone(), delta.as_f64()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(state_error)?;
+            let state = names
+                .iter()
+                .zip(next_state.iter())
+                .map(|(name, value)| (name.clone(), value.as_f64()))
+                .collect::<Vec<_>>();
+            let speed_kph = if has_speed {
+                average_speed_kph(si, prev_state, next_state).map_err(state_error)?
+            } else {
+                None
+            };
+            details.push(EdgeDetail {
+                edge_id: et.edge_id,
+                distance: edge.distance,
+                speed_kph,
+                access_cost: et.access_cost,
+                traversal_cost: et.traversal_cost,
+                deltas,
+                state,
+            });
+        }
+        Ok(details)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let deltas = self
+            .deltas
+            .iter()
+            .map(|(name, value)| (name.clone(), json![value]))
+            .collect::<serde_json::Map<_, _>>();
+        let state = self
+            .state
+            .iter()
+            .map(|(name, value)| (name.clone(), json![value]))
+            .collect::<serde_json::Map<_, _>>();
+        json!({
+            "edge_id": self.edge_id,
+            "distance": self.distance,
+            "distance_unit": BASE_DISTANCE_UNIT,
+            "speed_kph": self.speed_kph,
+            "access_cost": self.access_cost,
+            "traversal_cost": self.traversal_cost,
+            "delta": deltas,
+            "state": state,
+        })
+    }
+
+    /// column names of a CSV row for this edge
+    pub fn csv_header(&self) -> Vec<String> {
+        let mut header = [
+            "query",
+            "route_index",
+            "edge_index",
+            "edge_id",
+            "distance",
+            "speed_kph",
+            "access_cost",
+            "traversal_cost",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+        header.extend(
+            self.deltas
+                .iter()
+                .map(|(name, _)| format!("{}_delta", name)),
+        );
+        header.extend(self.state.iter().map(|(name, _)| name.clone()));
+        header
+    }
+
+    /// values of a CSV row for this edge, matching [`EdgeDetail::csv_header`]
+    pub fn csv_record(&self, query: usize, route_index: usize, edge_index: usize) -> Vec<String> {
+        let mut record = vec![
+            query.to_string(),
+            route_index.to_string(),
+            edge_index.to_string(),
+            self.edge_id.to_string(),
+            self.distance.to_string(),
+            self.speed_kph.map(|s| s.to_string()).unwrap_or_default(),
+            self.access_cost.to_string(),
+            self.traversal_cost.to_string(),
+        ];
+        record.extend(self.deltas.iter().map(|(_, v)| v.to_string()));
+        record.extend(self.state.iter().map(|(_, v)| v.to_string()));
+        record
+    }
+}
+
+/// the distance traveled over the time taken between two states, or None if no time passed
+fn average_speed_kph(
+    si: &SearchInstance,
+    prev: &[StateVar],
+    next: &[StateVar],
+) -> Result<Option<f64>, StateError> {
+    let distance_name = String::from(DISTANCE_FEATURE);
+    let time_name = String::from(TIME_FEATURE);
+    let km = |s| {
+        si.state_model
+            .get_distance(s, &distance_name, &DistanceUnit::Kilometers)
+            .map(|d| d.as_f64())
+    };
+    let hours = |s| {
+        si.state_model
+            .get_time(s, &time_name, &TimeUnit::Hours)
+            .map(|t| t.as_f64())
+    };
+    let distance_km = km(next)? - km(prev)?;
+    let time_hours = hours(next)? - hours(prev)?;
+    if time_hours > 0.0 {
+        Ok(Some(distance_km / time_hours))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_record_matches_header() {
+        let detail = EdgeDetail {
+            edge_id: EdgeId(7),
+            distance: Distance::new(100.0),
+            speed_kph: Some(36.0),
+            access_cost: Cost::ZERO,
+            traversal_cost: Cost::new(10.0),
+            deltas: vec![
+                (String::from("distance"), 0.1),
+                (String::from("time"), 10.0),
+            ],
+            state: vec![
+                (String::from("distance"), 0.5),
+                (String::from("time"), 50.0),
+            ],
+        };
+        let header = detail.csv_header();
+        let record = detail.csv_record(3, 0, 4);
+        assert_eq!(header.len(), record.len());
+        assert_eq!(header[8], "distance_delta");
+        assert_eq!(header[11], "time");
+        assert_eq!(record[..4], ["3", "0", "4", "7"]);
+        assert_eq!(record[5], "36");
+        assert_eq!(record[11], "50");
+
+        let json = detail.to_json();
+        assert_eq!(json["delta"]["time"], json![10.0]);
+        assert_eq!(json["state"]["distance"], json![0.5]);
+    }
+}
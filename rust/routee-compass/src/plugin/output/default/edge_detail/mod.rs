@@ -0,0 +1,4 @@
+pub mod builder;
+pub mod edge_detail_format;
+pub mod edge_detail_record;
+pub mod plugin;
@@ -0,0 +1,146 @@
+use super::edge_detail_record::EdgeDetail;
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use serde_json::json;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// a sidecar CSV file along with the header written to it, if any rows have been written
+pub struct EdgeDetailCsv {
+    path: PathBuf,
+    writer: csv::Writer<File>,
+    header: Option<Vec<String>>,
+}
+
+/// reports the traversal of each edge of each route, either as a nested
+/// array in the query output or as rows of a sidecar CSV file.
+pub struct EdgeDetailPlugin {
+    csv: Option<Mutex<EdgeDetailCsv>>,
+    counter: AtomicUsize,
+}
+
+impl EdgeDetailPlugin {
+    pub const OUTPUT_KEY: &'static str = "edge_detail";
+    pub const QUERY_KEY: &'static str = "edge_detail_query";
+
+    /// a plugin that writes edge detail into the query output
+    pub fn json() -> EdgeDetailPlugin {
+        EdgeDetailPlugin {
+            csv: None,
+            counter: AtomicUsize::new(0),
+        }
+    }
+
+    /// a plugin that writes edge detail to a CSV file
+    pub fn csv(path: PathBuf) -> Result<EdgeDetailPlugin, PluginError> {
+        let writer = csv::Writer::from_path(&path).map_err(|e| {
+            PluginError::PluginFailed(format!(
+                "failed creating edge detail file {:?}: {}",
+                path, e
+            ))
+        })?;
+        let csv = EdgeDetailCsv {
+            path,
+            writer,
+            header: None,
+        };
+        Ok(EdgeDetailPlugin {
+            csv: Some(Mutex::new(csv)),
+            counter: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl OutputPlugin for EdgeDetailPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        let (result, si) = match search_result {
+            Err(_) => return Ok(()),
+            Ok((result, si)) => (result, si),
+        };
+        let routes = result
+            .routes
+            .iter()
+            .map(|route| EdgeDetail::from_route(route, si))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match &self.csv {
+            None => {
+                let routes_json = routes
+                    .iter()
+                    .map(|details| json![details.iter().map(|d| d.to_json()).collect::<Vec<_>>()])
+                    .collect::<Vec<_>>();
+                // as with traversal output, store null for no route, an array of edges
+                // for one route, and an array of arrays for many routes.
+                output[Self::OUTPUT_KEY] = match routes_json.as_slice() {
+                    [] => serde_json::Value::Null,
+                    [route] => route.to_owned(),
+                    _ => json![routes_json],
+                };
+            }
+            Some(csv) => {
+                let query = self.counter.fetch_add(1, Ordering::SeqCst);
+                let mut csv = csv.lock().map_err(|e| {
+                    PluginError::InternalError(format!("edge detail file lock poisoned: {}", e))
+                })?;
+                csv.write_routes(query, &routes)?;
+                output[Self::QUERY_KEY] = json![query];
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EdgeDetailCsv {
+    /// writes one row per edge, writing the header with the first row. all routes
+    /// must share the same state model so that the rows line up with the header.
+    fn write_routes(
+        &mut self,
+        query: usize,
+        routes: &[Vec<EdgeDetail>],
+    ) -> Result<(), PluginError> {
+        for (route_index, details) in routes.iter().enumerate() {
+            for (edge_index, detail) in details.iter().enumerate() {
+                let header = detail.csv_header();
+                match &self.header {
+                    None => {
+                        self.write_record(&header)?;
+                        self.header = Some(header);
+                    }
+                    Some(existing) if existing != &header => {
+                        return Err(PluginError::PluginFailed(format!(
+                            "edge detail columns [{}] do not match the columns [{}] already written to {:?}",
+                            header.join(","),
+                            existing.join(","),
+                            self.path
+                        )));
+                    }
+                    Some(_) => {}
+                }
+                self.write_record(&detail.csv_record(query, route_index, edge_index))?;
+            }
+        }
+        self.writer.flush().map_err(|e| self.write_error(e))
+    }
+
+    fn write_record(&mut self, record: &[String]) -> Result<(), PluginError> {
+        self.writer
+            .write_record(record)
+            .map_err(|e| self.write_error(e))
+    }
+
+    fn write_error<E: std::fmt::Display>(&self, e: E) -> PluginError {
+        PluginError::PluginFailed(format!(
+            "failed writing edge detail file {:?}: {}",
+            self.path, e
+        ))
+    }
+}
@@ -1,3 +1,10 @@
+pub mod batch_summary;
+pub mod edge_attributes;
+pub mod edge_detail;
+pub mod route_export;
+pub mod router_response;
+pub mod search_tree;
 pub mod summary;
 pub mod traversal;
+pub mod turn_by_turn;
 pub mod uuid;
@@ -0,0 +1,76 @@
+use std::{path::PathBuf, sync::Arc};
+
+use super::{plugin::RouteExportPlugin, route_export_format::RouteExportFormat};
+use crate::{
+    app::compass::config::{
+        builders::OutputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::output::output_plugin::OutputPlugin,
+};
+use routee_compass_core::model::road_network::{
+    edge_geometry_store::EdgeGeometryStore, graph::Graph,
+};
+
+/// Builds a plugin that writes each route to a GPX or KML file.
+///
+/// # Configuration
+///
+/// This plugin expects the following keys:
+/// * `geometry_input_file` (optional) - the filename providing edge geometries, required
+///   unless edge geometries are loaded with the `[graph]`
+/// * `format` - either `gpx` or `kml`
+/// * `output_directory` or `combined_file` - write one file per query to a directory,
+///   or append all routes to a single document
+/// * `name_field` (optional) - field of the query used to name each route
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "route_export"
+/// format = "gpx"
+/// geometry_input_file = "edges-geometries-enumerated.txt.gz"
+/// output_directory = "routes"
+/// ```
+///
+pub struct RouteExportPluginBuilder {}
+
+impl OutputPluginBuilder for RouteExportPluginBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from(
+                "route_export: the route export plugin must be built with the graph of the app",
+            ),
+        ))
+    }
+
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("route_export");
+        let geometry_filename =
+            parameters.get_config_path_optional(&"geometry_input_file", &parent_key)?;
+        let format: RouteExportFormat = parameters.get_config_serde(&"format", &parent_key)?;
+        let output_directory = parameters
+            .get_config_string_optional(&"output_directory")?
+            .map(PathBuf::from);
+        let combined_file = parameters
+            .get_config_string_optional(&"combined_file")?
+            .map(PathBuf::from);
+        let name_field = parameters.get_config_string_optional(&"name_field")?;
+
+        let geoms = EdgeGeometryStore::from_graph_or_file(&graph, geometry_filename.as_ref())
+            .map_err(|e| {
+                CompassConfigurationError::UserConfigurationError(format!("route_export: {}", e))
+            })?;
+        let plugin =
+            RouteExportPlugin::new(geoms, format, output_directory, combined_file, name_field)?;
+        Ok(Arc::new(plugin))
+    }
+}
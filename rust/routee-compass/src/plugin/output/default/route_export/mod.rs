@@ -0,0 +1,3 @@
+pub mod builder;
+pub mod plugin;
+pub mod route_export_format;
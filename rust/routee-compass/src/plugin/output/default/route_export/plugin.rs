@@ -0,0 +1,203 @@
+use super::route_export_format::RouteExportFormat;
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::output::default::traversal::traversal_ops;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use routee_compass_core::model::road_network::edge_geometry_store::EdgeGeometryStore;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// where exported routes are written
+pub enum RouteExportDestination {
+    /// one file per query, written to this directory
+    Directory(PathBuf),
+    /// all routes appended to a single document, which is closed by [`OutputPlugin::finish`]
+    Combined {
+        path: PathBuf,
+        file: Mutex<Option<File>>,
+    },
+}
+
+/// writes the routes of each query to GPX or KML files.
+pub struct RouteExportPlugin {
+    geoms: Arc<EdgeGeometryStore>,
+    format: RouteExportFormat,
+    destination: RouteExportDestination,
+    /// optional field of the request used to name each route
+    name_field: Option<String>,
+    counter: AtomicUsize,
+    /// names of the routes exported so far, so that no two queries write the same file
+    names: Mutex<HashSet<String>>,
+}
+
+impl RouteExportPlugin {
+    pub fn new(
+        geoms: Arc<EdgeGeometryStore>,
+        format: RouteExportFormat,
+        output_directory: Option<PathBuf>,
+        combined_file: Option<PathBuf>,
+        name_field: Option<String>,
+    ) -> Result<RouteExportPlugin, PluginError> {
+        let destination = match (output_directory, combined_file) {
+            (Some(directory), None) => {
+                std::fs::create_dir_all(&directory).map_err(|e| write_error(&directory, e))?;
+                RouteExportDestination::Directory(directory)
+            }
+            (None, Some(path)) => {
+                let mut file = File::create(&path).map_err(|e| write_error(&path, e))?;
+                file.write_all(format.header().as_bytes())
+                    .map_err(|e| write_error(&path, e))?;
+                RouteExportDestination::Combined {
+                    path,
+                    file: Mutex::new(Some(file)),
+                }
+            }
+            _ => {
+                return Err(PluginError::InputError(String::from(
+                    "route export requires exactly one of output_directory or combined_file",
+                )))
+            }
+        };
+        Ok(RouteExportPlugin {
+            geoms,
+            format,
+            destination,
+            name_field,
+            counter: AtomicUsize::new(0),
+            names: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// records the name of the routes of a query, failing if another query already
+    /// used it, as its routes would overwrite or be confused with the earlier ones
+    fn claim_name(&self, name: &str) -> Result<(), PluginError> {
+        let mut names = self.names.lock().map_err(|e| {
+            PluginError::InternalError(format!("route export names lock poisoned: {}", e))
+        })?;
+        let filename = sanitize_filename(name);
+        if !names.insert(filename.clone()) {
+            return Err(PluginError::PluginFailed(format!(
+                "route export name '{}' is used by more than one query",
+                filename
+            )));
+        }
+        Ok(())
+    }
+
+    /// names the routes of a query from the request, or from a running count of queries
+    fn route_name(&self, output: &serde_json::Value) -> String {
+        let from_request = self
+            .name_field
+            .as_ref()
+            .and_then(|field| output.get("request").and_then(|r| r.get(field)))
+            .map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+        from_request.unwrap_or_else(|| {
+            let idx = self.counter.fetch_add(1, Ordering::SeqCst);
+            format!("route_{}", idx)
+        })
+    }
+}
+
+impl OutputPlugin for RouteExportPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        let result = match search_result {
+            Err(_) => return Ok(()),
+            Ok((result, _)) => result,
+        };
+        if result.routes.is_empty() {
+            return Ok(());
+        }
+        let name = self.route_name(output);
+        self.claim_name(&name)?;
+        let entries = result
+            .routes
+            .iter()
+            .enumerate()
+            .map(|(idx, route)| {
+                let geometry =
+                    traversal_ops::create_route_linestring(route, self.geoms.as_slice())?;
+                let route_name = if result.routes.len() == 1 {
+                    name.clone()
+                } else {
+                    format!("{}_{}", name, idx)
+                };
+                Ok(self.format.route_entry(&route_name, &geometry))
+            })
+            .collect::<Result<Vec<_>, PluginError>>()?;
+
+        let path = match &self.destination {
+            RouteExportDestination::Directory(directory) => {
+                let filename = format!("{}.{}", sanitize_filename(&name), self.format.extension());
+                let path = directory.join(filename);
+                std::fs::write(&path, self.format.document(&entries))
+                    .map_err(|e| write_error(&path, e))?;
+                path
+            }
+            RouteExportDestination::Combined { path, file } => {
+                let mut file = file.lock().map_err(|e| {
+                    PluginError::InternalError(format!("route export file lock poisoned: {}", e))
+                })?;
+                let file = file.as_mut().ok_or_else(|| {
+                    PluginError::PluginFailed(format!(
+                        "route export file {:?} was already closed",
+                        path
+                    ))
+                })?;
+                file.write_all(entries.concat().as_bytes())
+                    .map_err(|e| write_error(path, e))?;
+                path.clone()
+            }
+        };
+        output["route_file"] = json![path.to_string_lossy()];
+        Ok(())
+    }
+
+    /// closes the combined document. later routes cannot be added to it.
+    fn finish(&self) -> Result<(), PluginError> {
+        if let RouteExportDestination::Combined { path, file } = &self.destination {
+            let mut file = file.lock().map_err(|e| {
+                PluginError::InternalError(format!("route export file lock poisoned: {}", e))
+            })?;
+            if let Some(mut file) = file.take() {
+                file.write_all(self.format.footer().as_bytes())
+                    .map_err(|e| write_error(path, e))?;
+                log::info!("route export written to {:?}", path);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// replaces characters which are unsafe in file names
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn write_error(path: &Path, e: std::io::Error) -> PluginError {
+    PluginError::PluginFailed(format!(
+        "failed writing route export file {:?}: {}",
+        path, e
+    ))
+}
@@ -0,0 +1,111 @@
+use geo::LineString;
+use serde::{Deserialize, Serialize};
+
+/// file formats for exporting routes to GPS units and mapping tools
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteExportFormat {
+    /// GPS Exchange Format, where each route is a track
+    Gpx,
+    /// Keyhole Markup Language, where each route is a placemark
+    Kml,
+}
+
+impl RouteExportFormat {
+    /// file extension for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RouteExportFormat::Gpx => "gpx",
+            RouteExportFormat::Kml => "kml",
+        }
+    }
+
+    /// the start of a document, up to the first route
+    pub fn header(&self) -> &'static str {
+        match self {
+            RouteExportFormat::Gpx => concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<gpx version=\"1.1\" creator=\"routee-compass\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n"
+            ),
+            RouteExportFormat::Kml => concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n",
+                "<Document>\n"
+            ),
+        }
+    }
+
+    /// the end of a document, after the last route
+    pub fn footer(&self) -> &'static str {
+        match self {
+            RouteExportFormat::Gpx => "</gpx>\n",
+            RouteExportFormat::Kml => "</Document>\n</kml>\n",
+        }
+    }
+
+    /// a single named route, written as a GPX track or a KML placemark
+    pub fn route_entry(&self, name: &str, geometry: &LineString<f32>) -> String {
+        let name = escape_xml(name);
+        match self {
+            RouteExportFormat::Gpx => {
+                let points = geometry
+                    .coords()
+                    .map(|c| format!("      <trkpt lat=\"{}\" lon=\"{}\"/>\n", c.y, c.x))
+                    .collect::<String>();
+                format!(
+                    "  <trk>\n    <name>{}</name>\n    <trkseg>\n{}    </trkseg>\n  </trk>\n",
+                    name, points
+                )
+            }
+            RouteExportFormat::Kml => {
+                let coordinates = geometry
+                    .coords()
+                    .map(|c| format!("{},{}", c.x, c.y))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                format!(
+                    "<Placemark>\n  <name>{}</name>\n  <LineString>\n    <coordinates>{}</coordinates>\n  </LineString>\n</Placemark>\n",
+                    name, coordinates
+                )
+            }
+        }
+    }
+
+    /// a complete document holding the given routes
+    pub fn document(&self, entries: &[String]) -> String {
+        format!("{}{}{}", self.header(), entries.concat(), self.footer())
+    }
+}
+
+/// escapes the characters which are not allowed in XML text
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::coord;
+
+    #[test]
+    fn test_route_entries() {
+        let geometry = LineString(vec![
+            coord! { x: -105.1, y: 39.7 },
+            coord! { x: -105.2, y: 39.8 },
+        ]);
+        let gpx = RouteExportFormat::Gpx.route_entry("a & b", &geometry);
+        assert!(gpx.contains("<name>a &amp; b</name>"));
+        assert!(gpx.contains("<trkpt lat=\"39.7\" lon=\"-105.1\"/>"));
+        assert!(gpx.contains("<trkpt lat=\"39.8\" lon=\"-105.2\"/>"));
+
+        let kml = RouteExportFormat::Kml.route_entry("route", &geometry);
+        assert!(kml.contains("<coordinates>-105.1,39.7 -105.2,39.8</coordinates>"));
+        let document = RouteExportFormat::Kml.document(&[kml]);
+        assert!(document.starts_with("<?xml"));
+        assert!(document.ends_with("</kml>\n"));
+    }
+}
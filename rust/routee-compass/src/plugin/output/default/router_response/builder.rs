@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use super::{plugin::RouterResponsePlugin, router_response_format::RouterResponseFormat};
+use crate::{
+    app::compass::config::{
+        builders::OutputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::output::output_plugin::OutputPlugin,
+};
+use routee_compass_core::model::road_network::{
+    edge_geometry_store::EdgeGeometryStore, graph::Graph,
+};
+
+/// Builds a plugin that reshapes results into the response schema of another router.
+///
+/// # Configuration
+///
+/// This plugin expects the following keys:
+/// * `geometry_input_file` (optional) - the filename providing edge geometries, required
+///   unless edge geometries are loaded with the `[graph]`
+/// * `format` - either `osrm` or `valhalla`
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "router_response"
+/// format = "osrm"
+/// geometry_input_file = "edges-geometries-enumerated.txt.gz"
+/// ```
+///
+pub struct RouterResponsePluginBuilder {}
+
+impl OutputPluginBuilder for RouterResponsePluginBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from(
+                "router_response: the router response plugin must be built with the graph of the app",
+            ),
+        ))
+    }
+
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("router_response");
+        let geometry_filename =
+            parameters.get_config_path_optional(&"geometry_input_file", &parent_key)?;
+        let format: RouterResponseFormat = parameters.get_config_serde(&"format", &parent_key)?;
+        let geoms = EdgeGeometryStore::from_graph_or_file(&graph, geometry_filename.as_ref())
+            .map_err(|e| {
+                CompassConfigurationError::UserConfigurationError(format!("router_response: {}", e))
+            })?;
+        Ok(Arc::new(RouterResponsePlugin::new(geoms, format)))
+    }
+}
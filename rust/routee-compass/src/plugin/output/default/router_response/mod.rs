@@ -0,0 +1,4 @@
+pub mod builder;
+pub mod plugin;
+pub mod polyline;
+pub mod router_response_format;
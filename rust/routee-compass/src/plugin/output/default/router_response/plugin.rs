@@ -0,0 +1,134 @@
+use super::router_response_format::{RouteTotals, RouterResponseFormat};
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::compass::error_taxonomy::ErrorTaxonomy;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::input::input_json_extensions::InputJsonExtensions;
+use crate::plugin::output::default::traversal::traversal_ops;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::plugin_error::PluginError;
+use geo::Coord;
+use routee_compass_core::algorithm::search::edge_traversal::EdgeTraversal;
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use routee_compass_core::model::road_network::edge_geometry_store::EdgeGeometryStore;
+use routee_compass_core::model::unit::{as_f64::AsF64, Cost, DistanceUnit, TimeUnit};
+use std::sync::Arc;
+
+/// adds the routes of a search to the output in the response schema of OSRM or Valhalla.
+pub struct RouterResponsePlugin {
+    geoms: Arc<EdgeGeometryStore>,
+    format: RouterResponseFormat,
+}
+
+impl RouterResponsePlugin {
+    const DISTANCE_FEATURE: &'static str = "distance";
+    const TIME_FEATURE: &'static str = "time";
+
+    pub fn new(
+        geoms: Arc<EdgeGeometryStore>,
+        format: RouterResponseFormat,
+    ) -> RouterResponsePlugin {
+        RouterResponsePlugin { geoms, format }
+    }
+
+    /// totals a route using the state model where possible, falling back to the
+    /// lengths of the edges of the route for distance
+    fn route_totals(
+        &self,
+        route: &[EdgeTraversal],
+        si: &SearchInstance,
+    ) -> Result<RouteTotals, PluginError> {
+        let geometry = traversal_ops::create_route_linestring(route, self.geoms.as_slice())?;
+        let cost = route
+            .iter()
+            .map(|et| et.access_cost + et.traversal_cost)
+            .sum::<Cost>()
+            .as_f64();
+        let final_state = match route.last() {
+            Some(et) => et.result_state.clone(),
+            None => si
+                .state_model
+                .initial_state()
+                .map_err(|e| PluginError::PluginFailed(e.to_string()))?,
+        };
+        let distance_name = String::from(Self::DISTANCE_FEATURE);
+        let distance_meters = if si.state_model.contains_key(&distance_name) {
+            si.state_model
+                .get_distance(&final_state, &distance_name, &DistanceUnit::Meters)
+                .map_err(|e| PluginError::PluginFailed(e.to_string()))?
+                .as_f64()
+        } else {
+            route
+                .iter()
+                .map(|et| {
+                    si.directed_graph
+                        .get_edge(et.edge_id)
+                        .map(|e| e.distance.as_f64())
+                        .map_err(|e| PluginError::PluginFailed(e.to_string()))
+                })
+                .sum::<Result<f64, _>>()?
+        };
+        let time_name = String::from(Self::TIME_FEATURE);
+        let duration_seconds = if si.state_model.contains_key(&time_name) {
+            let time = si
+                .state_model
+                .get_time(&final_state, &time_name, &TimeUnit::Seconds)
+                .map_err(|e| PluginError::PluginFailed(e.to_string()))?;
+            Some(time.as_f64())
+        } else {
+            None
+        };
+        Ok(RouteTotals {
+            geometry,
+            distance_meters,
+            duration_seconds,
+            cost,
+        })
+    }
+}
+
+impl OutputPlugin for RouterResponsePlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        let (result, si) = match search_result {
+            Err(e) => {
+                output[self.format.output_key()] = self.format.error_response(&e.error_detail());
+                return Ok(());
+            }
+            Ok((result, si)) => (result, si),
+        };
+        let routes = result
+            .routes
+            .iter()
+            .map(|route| self.route_totals(route, si))
+            .collect::<Result<Vec<_>, _>>()?;
+        let locations = route_locations(output, &routes);
+        output[self.format.output_key()] = self.format.response(&routes, &locations);
+        Ok(())
+    }
+}
+
+/// the origin and destination from the request, or else the ends of the best route
+fn route_locations(output: &serde_json::Value, routes: &[RouteTotals]) -> Vec<Coord<f32>> {
+    let request = output.get("request");
+    let from_request = request.and_then(|r| {
+        let origin = r.get_origin_coordinate().ok()?;
+        let destination = r.get_destination_coordinate().ok()??;
+        Some(vec![origin, destination])
+    });
+    match (from_request, routes.first()) {
+        (Some(locations), _) => locations,
+        (None, Some(route)) => {
+            let coords = &route.geometry.0;
+            coords
+                .first()
+                .iter()
+                .chain(coords.last().iter())
+                .map(|c| **c)
+                .collect()
+        }
+        (None, None) => vec![],
+    }
+}
@@ -0,0 +1,47 @@
+use geo::LineString;
+
+/// encodes a linestring with the Encoded Polyline Algorithm used by Google, OSRM
+/// and Valhalla. coordinates are rounded to `precision` decimal places, which is
+/// 5 for OSRM and 6 for Valhalla.
+pub fn encode(geometry: &LineString<f32>, precision: u32) -> String {
+    let factor = 10_f64.powi(precision as i32);
+    let mut encoded = String::new();
+    let mut prev_lat = 0_i64;
+    let mut prev_lon = 0_i64;
+    for coord in geometry.coords() {
+        let lat = (coord.y as f64 * factor).round() as i64;
+        let lon = (coord.x as f64 * factor).round() as i64;
+        encode_value(lat - prev_lat, &mut encoded);
+        encode_value(lon - prev_lon, &mut encoded);
+        prev_lat = lat;
+        prev_lon = lon;
+    }
+    encoded
+}
+
+fn encode_value(value: i64, encoded: &mut String) {
+    let mut shifted = if value < 0 { !(value << 1) } else { value << 1 };
+    while shifted >= 0x20 {
+        let chunk = ((shifted & 0x1f) | 0x20) as u8 + 63;
+        encoded.push(chunk as char);
+        shifted >>= 5;
+    }
+    encoded.push((shifted as u8 + 63) as char);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::coord;
+
+    #[test]
+    fn test_encode() {
+        // example from the Encoded Polyline Algorithm Format documentation
+        let geometry = LineString(vec![
+            coord! { x: -120.2, y: 38.5 },
+            coord! { x: -120.95, y: 40.7 },
+            coord! { x: -126.453, y: 43.252 },
+        ]);
+        assert_eq!(encode(&geometry, 5), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    }
+}
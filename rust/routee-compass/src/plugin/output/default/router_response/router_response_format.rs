@@ -0,0 +1,220 @@
+use super::polyline;
+use crate::app::compass::error_taxonomy::{ErrorCode, ErrorDetail};
+use geo::{Coord, LineString};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// response schemas of other routing engines, so that front-ends built for
+/// those engines can display Compass routes
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RouterResponseFormat {
+    /// the response of the OSRM `route` service
+    Osrm,
+    /// the response of the Valhalla `route` action
+    Valhalla,
+}
+
+/// the totals of one route of a search result
+pub struct RouteTotals {
+    pub geometry: LineString<f32>,
+    pub distance_meters: f64,
+    /// not every traversal model tracks time, in which case this is None
+    pub duration_seconds: Option<f64>,
+    pub cost: f64,
+}
+
+impl RouterResponseFormat {
+    /// the key of the response in the Compass output
+    pub fn output_key(&self) -> &'static str {
+        match self {
+            RouterResponseFormat::Osrm => "osrm",
+            RouterResponseFormat::Valhalla => "valhalla",
+        }
+    }
+
+    /// builds a response from the routes of a search, the first being the best
+    /// route, and the locations the routes travel between
+    pub fn response(&self, routes: &[RouteTotals], locations: &[Coord<f32>]) -> serde_json::Value {
+        match self {
+            RouterResponseFormat::Osrm => osrm_response(routes, locations),
+            RouterResponseFormat::Valhalla => valhalla_response(routes, locations),
+        }
+    }
+
+    /// builds the error response of the router for a search that failed. the
+    /// router's error code is chosen from the code of the Compass error, which is
+    /// also included as a structured `compass_error`.
+    pub fn error_response(&self, error: &ErrorDetail) -> serde_json::Value {
+        let mut response = match self {
+            RouterResponseFormat::Osrm => osrm_error(osrm_code(error.code), &error.message),
+            RouterResponseFormat::Valhalla => {
+                let (error_code, status_code) = valhalla_codes(error.code);
+                valhalla_error(error_code, status_code, &error.message)
+            }
+        };
+        response["compass_error"] = error.to_json();
+        response
+    }
+}
+
+/// the OSRM response code closest to a Compass error code
+fn osrm_code(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::NoPathFound => "NoRoute",
+        ErrorCode::QueryTerminated => "TooBig",
+        ErrorCode::InvalidInput | ErrorCode::MissingField | ErrorCode::ParseError => "InvalidQuery",
+        ErrorCode::GraphError => "NoSegment",
+        _ => "InternalError",
+    }
+}
+
+/// the Valhalla error code and HTTP status code closest to a Compass error code
+fn valhalla_codes(code: ErrorCode) -> (u16, u16) {
+    match code {
+        ErrorCode::NoPathFound | ErrorCode::QueryTerminated => (442, 400),
+        ErrorCode::InvalidInput | ErrorCode::MissingField => (154, 400),
+        ErrorCode::ParseError => (100, 400),
+        ErrorCode::GraphError => (171, 400),
+        _ => (199, 500),
+    }
+}
+
+fn osrm_error(code: &str, message: &str) -> serde_json::Value {
+    json!({
+        "code": code,
+        "message": message,
+        "routes": [],
+        "waypoints": [],
+    })
+}
+
+fn valhalla_error(error_code: u16, status_code: u16, message: &str) -> serde_json::Value {
+    let status = if status_code == 400 {
+        "Bad Request"
+    } else {
+        "Internal Server Error"
+    };
+    json!({
+        "error_code": error_code,
+        "error": message,
+        "status_code": status_code,
+        "status": status,
+    })
+}
+
+fn osrm_response(routes: &[RouteTotals], locations: &[Coord<f32>]) -> serde_json::Value {
+    let waypoints = locations
+        .iter()
+        .map(|c| json!({ "name": "", "location": [c.x, c.y], "distance": 0.0 }))
+        .collect::<Vec<_>>();
+    if routes.is_empty() {
+        let mut response = osrm_error("NoRoute", "No route found between points");
+        response["waypoints"] = json![waypoints];
+        return response;
+    }
+    let routes_json = routes
+        .iter()
+        .map(|route| {
+            // without a time feature the duration is unknown, which is reported as null
+            let duration = route.duration_seconds;
+            json!({
+                "geometry": polyline::encode(&route.geometry, 5),
+                "distance": route.distance_meters,
+                "duration": duration,
+                "weight": route.cost,
+                "weight_name": "compass_cost",
+                "legs": [{
+                    "distance": route.distance_meters,
+                    "duration": duration,
+                    "weight": route.cost,
+                    "summary": "",
+                    "steps": [],
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "code": "Ok",
+        "routes": routes_json,
+        "waypoints": waypoints,
+    })
+}
+
+fn valhalla_response(routes: &[RouteTotals], locations: &[Coord<f32>]) -> serde_json::Value {
+    let locations_json = locations
+        .iter()
+        .map(|c| json!({ "type": "break", "lat": c.y, "lon": c.x }))
+        .collect::<Vec<_>>();
+    let trip = |route: &RouteTotals| {
+        let summary = json!({
+            "length": route.distance_meters / 1000.0,
+            "time": route.duration_seconds,
+            "cost": route.cost,
+        });
+        json!({
+            "status": 0,
+            "status_message": "Found route between points",
+            "units": "kilometers",
+            "language": "en-US",
+            "locations": locations_json,
+            "legs": [{
+                "shape": polyline::encode(&route.geometry, 6),
+                "summary": summary,
+                "maneuvers": [],
+            }],
+            "summary": summary,
+        })
+    };
+    match routes.split_first() {
+        None => valhalla_error(442, 400, "No path could be found for input"),
+        Some((best, alternates)) => {
+            let mut response = json!({ "trip": trip(best) });
+            if !alternates.is_empty() {
+                let alternates_json = alternates
+                    .iter()
+                    .map(|r| json!({ "trip": trip(r) }))
+                    .collect::<Vec<_>>();
+                response["alternates"] = json![alternates_json];
+            }
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::compass::error_taxonomy::ErrorComponent;
+
+    #[test]
+    fn test_unknown_duration_is_null() {
+        let route = RouteTotals {
+            geometry: LineString::from(vec![(0.0, 0.0), (0.0, 1.0)]),
+            distance_meters: 100.0,
+            duration_seconds: None,
+            cost: 1.0,
+        };
+        let locations = vec![Coord { x: 0.0, y: 0.0 }, Coord { x: 0.0, y: 1.0 }];
+        let osrm = RouterResponseFormat::Osrm.response(&[route], &locations);
+        assert!(osrm["routes"][0]["duration"].is_null());
+        assert!(osrm["routes"][0]["legs"][0]["duration"].is_null());
+    }
+
+    #[test]
+    fn test_error_response() {
+        let error = ErrorDetail::new(ErrorCode::NoPathFound, ErrorComponent::Search, "no path");
+        let osrm = RouterResponseFormat::Osrm.error_response(&error);
+        assert_eq!(osrm["code"], json!("NoRoute"));
+        assert_eq!(osrm["message"], json!("no path"));
+        assert_eq!(osrm["compass_error"]["code"], json!("NO_PATH_FOUND"));
+        let valhalla = RouterResponseFormat::Valhalla.error_response(&error);
+        assert_eq!(valhalla["error_code"], json!(442));
+        assert_eq!(valhalla["error"], json!("no path"));
+        assert_eq!(valhalla["compass_error"]["component"], json!("search"));
+
+        let error = ErrorDetail::new(ErrorCode::MissingField, ErrorComponent::Query, "no origin");
+        let osrm = RouterResponseFormat::Osrm.error_response(&error);
+        assert_eq!(osrm["code"], json!("InvalidQuery"));
+    }
+}
@@ -0,0 +1,76 @@
+use std::{path::PathBuf, sync::Arc};
+
+use super::{plugin::SearchTreePlugin, search_tree_format::SearchTreeFormat};
+use crate::{
+    app::compass::config::{
+        builders::OutputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::output::output_plugin::OutputPlugin,
+};
+use routee_compass_core::model::road_network::{
+    edge_geometry_store::EdgeGeometryStore, graph::Graph,
+};
+
+/// Builds a plugin that dumps the search tree of queries which set `"debug_tree": true`.
+///
+/// # Configuration
+///
+/// This plugin expects the following keys:
+/// * `format` - either `geo_json` or `csv`
+/// * `geometry_input_file` (optional) - the filename providing edge geometries for
+///   `geo_json`, required unless edge geometries are loaded with the `[graph]`
+/// * `output_file` - the CSV file to write, required for `csv`
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "search_tree"
+/// format = "geo_json"
+/// geometry_input_file = "edges-geometries-enumerated.txt.gz"
+/// ```
+///
+pub struct SearchTreePluginBuilder {}
+
+impl OutputPluginBuilder for SearchTreePluginBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from(
+                "search_tree: the search tree plugin must be built with the graph of the app",
+            ),
+        ))
+    }
+
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("search_tree");
+        let format: SearchTreeFormat = parameters.get_config_serde(&"format", &parent_key)?;
+        let plugin = match format {
+            SearchTreeFormat::GeoJson => {
+                let geometry_filename =
+                    parameters.get_config_path_optional(&"geometry_input_file", &parent_key)?;
+                let geoms =
+                    EdgeGeometryStore::from_graph_or_file(&graph, geometry_filename.as_ref())
+                        .map_err(|e| {
+                            CompassConfigurationError::UserConfigurationError(format!(
+                                "search_tree: {}",
+                                e
+                            ))
+                        })?;
+                SearchTreePlugin::geojson(geoms)
+            }
+            SearchTreeFormat::Csv => {
+                let output_file = parameters.get_config_string(&"output_file", &parent_key)?;
+                SearchTreePlugin::csv(PathBuf::from(output_file))?
+            }
+        };
+        Ok(Arc::new(plugin))
+    }
+}
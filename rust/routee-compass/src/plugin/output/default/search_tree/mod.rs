@@ -0,0 +1,4 @@
+pub mod builder;
+pub mod plugin;
+pub mod search_tree_format;
+pub mod search_tree_ops;
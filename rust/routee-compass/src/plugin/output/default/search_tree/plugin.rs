@@ -0,0 +1,180 @@
+use super::search_tree_ops;
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::plugin_error::PluginError;
+use geo::LineString;
+use geojson::{feature::Id, Feature, FeatureCollection};
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use routee_compass_core::algorithm::search::search_tree_branch::SearchTreeBranch;
+use routee_compass_core::model::road_network::{
+    edge_geometry_store::EdgeGeometryStore, vertex_id::VertexId,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// where dumped search trees are written
+pub enum SearchTreeDestination {
+    /// a GeoJSON FeatureCollection stored in the query output
+    GeoJson { geoms: Arc<EdgeGeometryStore> },
+    /// rows appended to a CSV file
+    Csv {
+        path: PathBuf,
+        writer: Mutex<Box<csv::Writer<File>>>,
+    },
+}
+
+/// dumps the full search tree of a query, with the best-known cost of each visited
+/// vertex, for visualizing search effort. as trees can be very large, only queries
+/// which set `"debug_tree": true` are dumped.
+pub struct SearchTreePlugin {
+    destination: SearchTreeDestination,
+    counter: AtomicUsize,
+}
+
+impl SearchTreePlugin {
+    pub const QUERY_FLAG: &'static str = "debug_tree";
+    pub const OUTPUT_KEY: &'static str = "search_tree";
+    pub const QUERY_KEY: &'static str = "search_tree_query";
+    const CSV_HEADER: [&'static str; 7] = [
+        "query",
+        "tree_index",
+        "vertex_id",
+        "parent_vertex_id",
+        "edge_id",
+        "edge_cost",
+        "cost",
+    ];
+
+    /// a plugin that stores each tree as GeoJSON in the query output
+    pub fn geojson(geoms: Arc<EdgeGeometryStore>) -> SearchTreePlugin {
+        SearchTreePlugin {
+            destination: SearchTreeDestination::GeoJson { geoms },
+            counter: AtomicUsize::new(0),
+        }
+    }
+
+    /// a plugin that writes each tree to a CSV file
+    pub fn csv(path: PathBuf) -> Result<SearchTreePlugin, PluginError> {
+        let mut writer = csv::Writer::from_path(&path).map_err(|e| write_error(&path, e))?;
+        writer
+            .write_record(Self::CSV_HEADER)
+            .map_err(|e| write_error(&path, e))?;
+        Ok(SearchTreePlugin {
+            destination: SearchTreeDestination::Csv {
+                path,
+                writer: Mutex::new(Box::new(writer)),
+            },
+            counter: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl OutputPlugin for SearchTreePlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        let result = match search_result {
+            Err(_) => return Ok(()),
+            Ok((result, _)) => result,
+        };
+        let requested = output
+            .get("request")
+            .and_then(|r| r.get(Self::QUERY_FLAG))
+            .and_then(|f| f.as_bool())
+            .unwrap_or(false);
+        if !requested {
+            return Ok(());
+        }
+
+        match &self.destination {
+            SearchTreeDestination::GeoJson { geoms } => {
+                let trees_json = result
+                    .trees
+                    .iter()
+                    .map(|tree| create_tree_geojson(tree, geoms.as_slice()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                output[Self::OUTPUT_KEY] = match trees_json.as_slice() {
+                    [] => serde_json::Value::Null,
+                    [tree] => tree.to_owned(),
+                    _ => json![trees_json],
+                };
+            }
+            SearchTreeDestination::Csv { path, writer } => {
+                let query = self.counter.fetch_add(1, Ordering::SeqCst);
+                let mut writer = writer.lock().map_err(|e| {
+                    PluginError::InternalError(format!("search tree file lock poisoned: {}", e))
+                })?;
+                for (tree_index, tree) in result.trees.iter().enumerate() {
+                    let costs = search_tree_ops::cost_to_reach(tree)?;
+                    for (vertex_id, branch) in tree.iter() {
+                        let et = &branch.edge_traversal;
+                        let record = [
+                            query.to_string(),
+                            tree_index.to_string(),
+                            vertex_id.to_string(),
+                            branch.terminal_vertex.to_string(),
+                            et.edge_id.to_string(),
+                            (et.access_cost + et.traversal_cost).to_string(),
+                            costs[vertex_id].to_string(),
+                        ];
+                        writer
+                            .write_record(&record)
+                            .map_err(|e| write_error(path, e))?;
+                    }
+                }
+                writer.flush().map_err(|e| write_error(path, e))?;
+                output[Self::QUERY_KEY] = json![query];
+            }
+        }
+        Ok(())
+    }
+}
+
+/// a FeatureCollection with one feature per visited edge, annotated with the
+/// vertices it connects and the best-known cost to reach the end of the edge
+fn create_tree_geojson(
+    tree: &HashMap<VertexId, SearchTreeBranch>,
+    geoms: &[LineString<f32>],
+) -> Result<serde_json::Value, PluginError> {
+    let costs = search_tree_ops::cost_to_reach(tree)?;
+    let features = tree
+        .iter()
+        .map(|(vertex_id, branch)| {
+            let et = &branch.edge_traversal;
+            let geometry = geoms
+                .get(et.edge_id.0)
+                .ok_or(PluginError::EdgeGeometryMissing(et.edge_id))?;
+            let properties = json!({
+                "edge_id": et.edge_id,
+                "vertex_id": vertex_id,
+                "parent_vertex_id": branch.terminal_vertex,
+                "edge_cost": et.access_cost + et.traversal_cost,
+                "cost": costs[vertex_id],
+            });
+            Ok(Feature {
+                bbox: None,
+                geometry: Some(geojson::Geometry::from(geometry)),
+                id: Some(Id::Number(serde_json::Number::from(et.edge_id.0))),
+                properties: properties.as_object().cloned(),
+                foreign_members: None,
+            })
+        })
+        .collect::<Result<Vec<_>, PluginError>>()?;
+    let feature_collection = FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    };
+    Ok(serde_json::to_value(feature_collection)?)
+}
+
+fn write_error<E: std::fmt::Display>(path: &Path, e: E) -> PluginError {
+    PluginError::PluginFailed(format!("failed writing search tree file {:?}: {}", path, e))
+}
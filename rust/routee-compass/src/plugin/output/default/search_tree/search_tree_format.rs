@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// how a dumped search tree is written
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchTreeFormat {
+    /// a FeatureCollection of the visited edges in the query output
+    GeoJson,
+    /// rows of a sidecar CSV file, keyed by query index
+    Csv,
+}
@@ -0,0 +1,76 @@
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::{
+    algorithm::search::search_tree_branch::SearchTreeBranch,
+    model::{road_network::vertex_id::VertexId, unit::Cost},
+};
+use std::collections::HashMap;
+
+/// finds the best-known cost to reach each vertex of a search tree by summing the
+/// access and traversal costs of each branch on the way back to the root of the tree.
+pub fn cost_to_reach(
+    tree: &HashMap<VertexId, SearchTreeBranch>,
+) -> Result<HashMap<VertexId, Cost>, PluginError> {
+    let mut costs: HashMap<VertexId, Cost> = HashMap::with_capacity(tree.len());
+    for vertex_id in tree.keys() {
+        // walk toward the root until reaching a vertex with a known cost or the root itself
+        let mut path: Vec<VertexId> = vec![];
+        let mut this_vertex = *vertex_id;
+        let mut base = Cost::ZERO;
+        while let Some(branch) = tree.get(&this_vertex) {
+            if let Some(known) = costs.get(&this_vertex) {
+                base = *known;
+                break;
+            }
+            if path.len() > tree.len() {
+                return Err(PluginError::InternalError(format!(
+                    "search tree has a loop through vertex {}",
+                    this_vertex
+                )));
+            }
+            path.push(this_vertex);
+            this_vertex = branch.terminal_vertex;
+        }
+        // unwind the path, accumulating cost away from the root
+        for v in path.into_iter().rev() {
+            let et = &tree[&v].edge_traversal;
+            base = base + et.access_cost + et.traversal_cost;
+            costs.insert(v, base);
+        }
+    }
+    Ok(costs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::{
+        algorithm::search::edge_traversal::EdgeTraversal, model::road_network::edge_id::EdgeId,
+    };
+
+    fn branch(parent: usize, edge_id: usize, cost: f64) -> SearchTreeBranch {
+        SearchTreeBranch {
+            terminal_vertex: VertexId(parent),
+            edge_traversal: EdgeTraversal {
+                edge_id: EdgeId(edge_id),
+                access_cost: Cost::ZERO,
+                traversal_cost: Cost::from(cost),
+                result_state: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_cost_to_reach() {
+        // 0 -> 1 -> 2 and 0 -> 3
+        let tree = HashMap::from([
+            (VertexId(1), branch(0, 0, 2.0)),
+            (VertexId(2), branch(1, 1, 3.0)),
+            (VertexId(3), branch(0, 2, 4.0)),
+        ]);
+        let costs = cost_to_reach(&tree).unwrap();
+        assert_eq!(costs[&VertexId(1)], Cost::from(2.0));
+        assert_eq!(costs[&VertexId(2)], Cost::from(5.0));
+        assert_eq!(costs[&VertexId(3)], Cost::from(4.0));
+        assert!(!costs.contains_key(&VertexId(0)));
+    }
+}
@@ -8,13 +8,17 @@ use crate::{
     },
     plugin::output::output_plugin::OutputPlugin,
 };
+use routee_compass_core::model::road_network::{
+    edge_geometry_store::EdgeGeometryStore, graph::Graph,
+};
 
 /// Builds a plugin that can generate traversal outputs.
 ///
 /// # Configuration
 ///
 /// This plugin expects the following keys:
-/// * `geometry_file` - the filename providing edge geometries
+/// * `geometry_input_file` (optional) - the filename providing edge geometries, required
+///   unless edge geometries are loaded with the `[graph]`
 /// * `route` (optional) - traversal output format for the route result
 /// * `tree` (optional) - traversal output format for the search tree result
 ///
@@ -38,18 +42,34 @@ impl OutputPluginBuilder for TraversalPluginBuilder {
     /// builds the traversal output plugin, which allows users to configure how they want to
     /// output datasets related to the route plan and tree.
     fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from("traversal: the traversal plugin must be built with the graph of the app"),
+        ))
+    }
+
+    /// builds the plugin with the edge geometries of the graph, or else those of its
+    /// `geometry_input_file`
+    fn build_with_graph(
         &self,
         parameters: &serde_json::Value,
+        graph: Arc<Graph>,
     ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
         let parent_key = String::from("traversal");
 
-        let geometry_filename = parameters.get_config_path(&"geometry_input_file", &parent_key)?;
+        let geometry_filename =
+            parameters.get_config_path_optional(&"geometry_input_file", &parent_key)?;
+        let geometries = EdgeGeometryStore::from_graph_or_file(&graph, geometry_filename.as_ref())
+            .map_err(|e| {
+                CompassConfigurationError::UserConfigurationError(format!("traversal: {}", e))
+            })?;
         let route: Option<TraversalOutputFormat> =
             parameters.get_config_serde_optional(&"route", &parent_key)?;
         let tree: Option<TraversalOutputFormat> =
             parameters.get_config_serde_optional(&"tree", &parent_key)?;
 
-        let geom_plugin = TraversalPlugin::from_file(&geometry_filename, route, tree)?;
-        Ok(Arc::new(geom_plugin))
+        Ok(Arc::new(TraversalPlugin::new(geometries, route, tree)))
     }
 }
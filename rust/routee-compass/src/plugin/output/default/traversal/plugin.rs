@@ -2,21 +2,19 @@ use super::json_extensions::TraversalJsonField;
 use super::traversal_output_format::TraversalOutputFormat;
 use crate::app::compass::compass_app_error::CompassAppError;
 use crate::app::search::search_app_result::SearchAppResult;
+use crate::app::search::state_timeline::StateTimeline;
 use crate::plugin::output::output_plugin::OutputPlugin;
 use crate::plugin::plugin_error::PluginError;
 use geo::LineString;
-use kdam::Bar;
-use kdam::BarExt;
 use routee_compass_core::algorithm::search::edge_traversal::EdgeTraversal;
 use routee_compass_core::algorithm::search::search_instance::SearchInstance;
-use routee_compass_core::util::fs::fs_utils;
-use routee_compass_core::util::fs::read_utils::read_raw_file;
-use routee_compass_core::util::geo::geo_io_utils;
+use routee_compass_core::model::road_network::edge_geometry_store::EdgeGeometryStore;
+use routee_compass_core::model::unit::Currency;
 use serde_json::json;
-use std::path::Path;
+use std::sync::Arc;
 
 pub struct TraversalPlugin {
-    geoms: Box<[LineString<f32>]>,
+    geoms: Arc<EdgeGeometryStore>,
     route: Option<TraversalOutputFormat>,
     tree: Option<TraversalOutputFormat>,
     route_key: String,
@@ -24,40 +22,22 @@ pub struct TraversalPlugin {
 }
 
 impl TraversalPlugin {
-    pub fn from_file<P: AsRef<Path>>(
-        filename: &P,
+    /// creates a plugin which writes the given edge geometries, such as those loaded
+    /// with the graph
+    pub fn new(
+        geoms: Arc<EdgeGeometryStore>,
         route: Option<TraversalOutputFormat>,
         tree: Option<TraversalOutputFormat>,
-    ) -> Result<TraversalPlugin, PluginError> {
-        let count = fs_utils::line_count(filename, fs_utils::is_gzip(filename)).map_err(|e| {
-            PluginError::FileReadError(filename.as_ref().to_path_buf(), e.to_string())
-        })?;
-
-        let mut pb = Bar::builder()
-            .total(count)
-            .animation("fillup")
-            .desc("geometry file")
-            .build()
-            .map_err(PluginError::InternalError)?;
-
-        let cb = Box::new(|| {
-            let _ = pb.update(1);
-        });
-        let geoms =
-            read_raw_file(filename, geo_io_utils::parse_linestring, Some(cb)).map_err(|e| {
-                PluginError::FileReadError(filename.as_ref().to_path_buf(), e.to_string())
-            })?;
-        println!();
-
+    ) -> TraversalPlugin {
         let route_key = TraversalJsonField::RouteOutput.to_string();
         let tree_key = TraversalJsonField::TreeOutput.to_string();
-        Ok(TraversalPlugin {
+        TraversalPlugin {
             geoms,
             route,
             tree,
             route_key,
             tree_key,
-        })
+        }
     }
 }
 
@@ -77,7 +57,12 @@ impl OutputPlugin for TraversalPlugin {
                             .routes
                             .iter()
                             .map(|route| {
-                                construct_route_output(route, si, &route_args, &self.geoms)
+                                construct_route_output(
+                                    route,
+                                    si,
+                                    &route_args,
+                                    self.geoms.as_slice(),
+                                )
                             })
                             .collect::<Result<Vec<_>, _>>()
                             .map_err(PluginError::PluginFailed)?;
@@ -100,7 +85,7 @@ impl OutputPlugin for TraversalPlugin {
                         let trees_serialized = result
                             .trees
                             .iter()
-                            .map(|tree| tree_args.generate_tree_output(tree, &self.geoms))
+                            .map(|tree| tree_args.generate_tree_output(tree, self.geoms.as_slice()))
                             .collect::<Result<Vec<_>, _>>()?;
                         let trees_json = match trees_serialized.as_slice() {
                             [] => serde_json::Value::Null,
@@ -115,6 +100,10 @@ impl OutputPlugin for TraversalPlugin {
             }
         }
     }
+
+    fn edge_geometries(&self) -> Option<&[LineString<f32>]> {
+        Some(self.geoms.as_slice())
+    }
 }
 
 /// creates the JSON output for a route.
@@ -140,16 +129,37 @@ fn construct_route_output(
         .cost_model
         .serialize_cost_info()
         .map_err(|e| e.to_string())?;
-    let result = serde_json::json![{
+    let mut result = serde_json::json![{
         "traversal_summary": traversal_summary,
         "state_model": state_model,
         "cost_model": cost_model,
         "cost": cost,
         "path": path_json
     }];
+    if si.cost_model.has_tolls() {
+        result["toll"] = json![route_tolls(route, si)?];
+    }
     Ok(result)
 }
 
+/// sums the tolls paid on each edge of a route
+fn route_tolls(route: &[EdgeTraversal], si: &SearchInstance) -> Result<Currency, String> {
+    let timeline = StateTimeline::new(route, si).map_err(|e| e.to_string())?;
+    let mut total = Currency::ZERO;
+    for (et, prev_state, _) in timeline.edges() {
+        let edge = si
+            .directed_graph
+            .get_edge(et.edge_id)
+            .map_err(|e| e.to_string())?;
+        let toll = si
+            .cost_model
+            .toll_cost(edge, prev_state)
+            .map_err(|e| e.to_string())?;
+        total = total + toll;
+    }
+    Ok(total)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -9,17 +9,25 @@ use routee_compass_core::model::road_network::vertex_id::VertexId;
 use routee_compass_core::util::geo::geo_io_utils;
 use std::collections::HashMap;
 
+/// the branches of a search tree ordered by the vertex they reach, so that the tree
+/// is written the same way on every run rather than in the order of the hash map
+pub fn sorted_tree_branches(tree: &HashMap<VertexId, SearchTreeBranch>) -> Vec<&SearchTreeBranch> {
+    let mut branches = tree.iter().collect::<Vec<_>>();
+    branches.sort_by_key(|(vertex_id, _)| vertex_id.0);
+    branches.into_iter().map(|(_, branch)| branch).collect()
+}
+
 pub fn create_tree_geojson(
     tree: &HashMap<VertexId, SearchTreeBranch>,
     geoms: &[LineString<f32>],
 ) -> Result<serde_json::Value, PluginError> {
-    let features = tree
-        .values()
+    let features = sorted_tree_branches(tree)
+        .into_iter()
         .map(|t| {
             let row_result = geoms
                 .get(t.edge_traversal.edge_id.0)
                 .cloned()
-                .ok_or_else(|| PluginError::EdgeGeometryMissing(t.edge_traversal.edge_id))
+                .ok_or(PluginError::EdgeGeometryMissing(t.edge_traversal.edge_id))
                 .and_then(|g| create_geojson_feature(&t.edge_traversal, g));
 
             row_result
@@ -45,7 +53,7 @@ pub fn create_route_geojson(
             let row_result = geoms
                 .get(t.edge_id.0)
                 .cloned()
-                .ok_or_else(|| PluginError::EdgeGeometryMissing(t.edge_id))
+                .ok_or(PluginError::EdgeGeometryMissing(t.edge_id))
                 .and_then(|g| create_geojson_feature(t, g));
 
             row_result
@@ -93,7 +101,7 @@ pub fn create_edge_geometry(
     geoms
         .get(edge.edge_id.0)
         .cloned()
-        .ok_or_else(|| PluginError::EdgeGeometryMissing(edge.edge_id))
+        .ok_or(PluginError::EdgeGeometryMissing(edge.edge_id))
 }
 
 pub fn create_branch_geometry(
@@ -117,7 +125,7 @@ pub fn create_route_linestring(
         .map(|eid| {
             let geom = geoms
                 .get(eid.0)
-                .ok_or_else(|| PluginError::EdgeGeometryMissing(*eid));
+                .ok_or(PluginError::EdgeGeometryMissing(*eid));
             geom
         })
         .collect::<Result<Vec<&LineString<f32>>, PluginError>>()?;
@@ -129,8 +137,8 @@ pub fn create_tree_multilinestring(
     tree: &HashMap<VertexId, SearchTreeBranch>,
     geoms: &[LineString<f32>],
 ) -> Result<MultiLineString<f32>, PluginError> {
-    let edge_ids = tree
-        .values()
+    let edge_ids = sorted_tree_branches(tree)
+        .into_iter()
         .map(|traversal| traversal.edge_traversal.edge_id)
         .collect::<Vec<_>>();
 
@@ -139,7 +147,7 @@ pub fn create_tree_multilinestring(
         .map(|eid| {
             let geom = geoms
                 .get(eid.0)
-                .ok_or_else(|| PluginError::EdgeGeometryMissing(*eid));
+                .ok_or(PluginError::EdgeGeometryMissing(*eid));
             geom.cloned()
         })
         .collect::<Result<Vec<LineString<f32>>, PluginError>>()?;
@@ -151,8 +159,8 @@ pub fn create_tree_multipoint(
     tree: &HashMap<VertexId, SearchTreeBranch>,
     geoms: &[LineString<f64>],
 ) -> Result<MultiPoint, PluginError> {
-    let edge_ids = tree
-        .values()
+    let edge_ids = sorted_tree_branches(tree)
+        .into_iter()
         .map(|traversal| traversal.edge_traversal.edge_id)
         .collect::<Vec<_>>();
 
@@ -161,9 +169,9 @@ pub fn create_tree_multipoint(
         .map(|eid| {
             let geom = geoms
                 .get(eid.0)
-                .ok_or_else(|| PluginError::EdgeGeometryMissing(*eid))
+                .ok_or(PluginError::EdgeGeometryMissing(*eid))
                 .map(|l| {
-                    l.points().last().ok_or_else(|| {
+                    l.points().next_back().ok_or_else(|| {
                         PluginError::InputError(format!(
                             "linestring is invalid for edge_id {}",
                             eid
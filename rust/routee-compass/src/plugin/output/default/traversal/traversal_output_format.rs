@@ -64,7 +64,7 @@ impl TraversalOutputFormat {
                 Ok(serde_json::Value::String(route_wkt))
             }
             TraversalOutputFormat::Json => {
-                let result = serde_json::to_value(tree.values().collect::<Vec<_>>())?;
+                let result = serde_json::to_value(ops::sorted_tree_branches(tree))?;
                 Ok(result)
             }
             TraversalOutputFormat::GeoJson => {
@@ -72,8 +72,8 @@ impl TraversalOutputFormat {
                 Ok(result)
             }
             TraversalOutputFormat::EdgeId => {
-                let tree_ids = tree
-                    .values()
+                let tree_ids = ops::sorted_tree_branches(tree)
+                    .into_iter()
                     .map(|b| b.edge_traversal.edge_id)
                     .collect::<Vec<_>>();
                 let json = serde_json::json![tree_ids];
@@ -90,7 +90,7 @@ mod test {
     use chrono::Local;
     use geo::{coord, LineString};
     use routee_compass_core::{
-        algorithm::search::edge_traversal::EdgeTraversal,
+        algorithm::search::{edge_traversal::EdgeTraversal, search_metrics::SearchMetrics},
         model::{
             road_network::edge_id::EdgeId, traversal::state::state_variable::StateVar, unit::Cost,
         },
@@ -125,6 +125,11 @@ mod test {
             search_executed_time: Local::now().to_rfc3339(),
             search_runtime: Duration::ZERO,
             iterations: 0,
+            metrics: SearchMetrics::default(),
+            charging_stops: vec![],
+            arrive_by: None,
+            chosen_destination: None,
+            chosen_origin: None,
         };
 
         let geoms = vec![
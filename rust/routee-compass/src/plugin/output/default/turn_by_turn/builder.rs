@@ -0,0 +1,141 @@
+use std::{collections::HashSet, sync::Arc};
+
+use super::plugin::{EdgeLabels, TurnByTurnPlugin};
+use crate::{
+    app::compass::config::{
+        builders::OutputPluginBuilder, compass_configuration_error::CompassConfigurationError,
+        config_json_extension::ConfigJsonExtensions,
+    },
+    plugin::{output::output_plugin::OutputPlugin, plugin_error::PluginError},
+};
+use routee_compass_core::{
+    model::{
+        property::edge_attribute_store::EdgeAttributeColumnType,
+        road_network::{edge_geometry_store::EdgeGeometryStore, graph::Graph},
+    },
+    util::fs::{read_decoders, read_utils::read_raw_file},
+};
+
+/// Builds a plugin that splits routes into legs at waypoints and writes
+/// turn-by-turn maneuver instructions for each leg.
+///
+/// # Configuration
+///
+/// This plugin expects the following keys:
+/// * `geometry_input_file` (optional) - the filename providing edge geometries,
+///   required unless edge geometries are loaded with the `[graph]`
+/// * `name_input_file` (optional) - the filename providing the road name of each
+///   edge, one per line, where an empty line is an unnamed road
+/// * `name_column` (optional) - in place of a `name_input_file`, a categorical
+///   column of the edge attributes loaded with the `[graph]`
+/// * `road_class_input_file` (optional) - the filename providing the road class of
+///   each edge, one per line
+/// * `road_class_column` (optional) - in place of a `road_class_input_file`, a
+///   categorical column of the edge attributes loaded with the `[graph]`
+/// * `merge_road_classes` (optional) - road classes which are merged onto from other
+///   road classes, `["motorway", "trunk"]` by default
+///
+/// every file must have one line for each edge of the graph.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "turn_by_turn"
+/// geometry_input_file = "edges-geometries-enumerated.txt.gz"
+/// name_input_file = "edges-names.txt.gz"
+/// road_class_input_file = "edges-road-class.txt.gz"
+/// ```
+///
+pub struct TurnByTurnPluginBuilder {}
+
+impl OutputPluginBuilder for TurnByTurnPluginBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        Err(CompassConfigurationError::UserConfigurationError(
+            String::from(
+                "turn_by_turn: the turn by turn plugin must be built with the graph of the app",
+            ),
+        ))
+    }
+
+    /// builds the plugin with the edge geometries of the graph, or else those of its
+    /// `geometry_input_file`, checking that each per-edge file matches the graph
+    fn build_with_graph(
+        &self,
+        parameters: &serde_json::Value,
+        graph: Arc<Graph>,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("turn_by_turn");
+        let config_error = |msg: String| {
+            CompassConfigurationError::UserConfigurationError(format!("turn_by_turn: {}", msg))
+        };
+        let geometry_filename =
+            parameters.get_config_path_optional(&"geometry_input_file", &parent_key)?;
+        let geometries = EdgeGeometryStore::from_graph_or_file(&graph, geometry_filename.as_ref())
+            .map_err(|e| config_error(e.to_string()))?;
+        let read_labels = |file_key: &str,
+                           column_key: &str|
+         -> Result<Option<EdgeLabels>, CompassConfigurationError> {
+            let file = parameters.get_config_path_optional(&file_key, &parent_key)?;
+            let column: Option<String> =
+                parameters.get_config_serde_optional(&column_key, &parent_key)?;
+            match (file, column) {
+                (None, None) => Ok(None),
+                (Some(_), Some(_)) => Err(config_error(format!(
+                    "{} and {} cannot both be set",
+                    file_key, column_key
+                ))),
+                (Some(file), None) => {
+                    let values = read_raw_file(&file, read_decoders::string, None)
+                        .map_err(|e| PluginError::FileReadError(file.clone(), e.to_string()))?;
+                    if values.len() != graph.n_edges() {
+                        return Err(config_error(format!(
+                            "{} {} has {} lines but the graph has {} edges",
+                            file_key,
+                            file.display(),
+                            values.len(),
+                            graph.n_edges()
+                        )));
+                    }
+                    Ok(Some(EdgeLabels::File(values)))
+                }
+                (None, Some(column)) => {
+                    let store = graph
+                        .edge_attributes()
+                        .map_err(|e| config_error(format!("{} requires that {}", column_key, e)))?;
+                    let column_type = store
+                        .column(&column)
+                        .map_err(|e| config_error(e.to_string()))?
+                        .column_type();
+                    if column_type != EdgeAttributeColumnType::Categorical {
+                        return Err(config_error(format!(
+                            "{} '{}' is a {} column, not a {} column",
+                            column_key,
+                            column,
+                            column_type,
+                            EdgeAttributeColumnType::Categorical
+                        )));
+                    }
+                    Ok(Some(EdgeLabels::Attribute {
+                        store: store.clone(),
+                        column,
+                    }))
+                }
+            }
+        };
+        let names = read_labels("name_input_file", "name_column")?;
+        let road_classes = read_labels("road_class_input_file", "road_class_column")?;
+        let merge_road_classes: HashSet<String> = parameters
+            .get_config_serde_optional(&"merge_road_classes", &parent_key)?
+            .unwrap_or_else(|| HashSet::from([String::from("motorway"), String::from("trunk")]));
+        Ok(Arc::new(TurnByTurnPlugin::new(
+            geometries,
+            names,
+            road_classes,
+            merge_road_classes,
+        )))
+    }
+}
@@ -0,0 +1,356 @@
+use geo::{Coord, LineString};
+use routee_compass_core::model::road_network::{edge_id::EdgeId, vertex_id::VertexId};
+use serde::Serialize;
+use std::{collections::HashSet, ops::Range};
+
+/// the kind of a maneuver, with turns classified by the change of bearing between
+/// the end of one edge and the start of the next
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ManeuverType {
+    Depart,
+    Continue,
+    SlightLeft,
+    SlightRight,
+    Left,
+    Right,
+    SharpLeft,
+    SharpRight,
+    UTurn,
+    Merge,
+    Arrive,
+}
+
+impl ManeuverType {
+    /// classifies a change of bearing in degrees, positive to the right
+    pub fn from_turn_angle(angle: f64) -> ManeuverType {
+        let magnitude = angle.abs();
+        let right = angle > 0.0;
+        match magnitude {
+            m if m <= 20.0 => ManeuverType::Continue,
+            m if m <= 45.0 && right => ManeuverType::SlightRight,
+            m if m <= 45.0 => ManeuverType::SlightLeft,
+            m if m <= 135.0 && right => ManeuverType::Right,
+            m if m <= 135.0 => ManeuverType::Left,
+            m if m <= 170.0 && right => ManeuverType::SharpRight,
+            m if m <= 170.0 => ManeuverType::SharpLeft,
+            _ => ManeuverType::UTurn,
+        }
+    }
+
+    fn verb(&self) -> &'static str {
+        match self {
+            ManeuverType::Depart => "Head",
+            ManeuverType::Continue => "Continue",
+            ManeuverType::SlightLeft => "Bear left",
+            ManeuverType::SlightRight => "Bear right",
+            ManeuverType::Left => "Turn left",
+            ManeuverType::Right => "Turn right",
+            ManeuverType::SharpLeft => "Make a sharp left",
+            ManeuverType::SharpRight => "Make a sharp right",
+            ManeuverType::UTurn => "Make a U-turn",
+            ManeuverType::Merge => "Merge",
+            ManeuverType::Arrive => "Arrive",
+        }
+    }
+}
+
+/// one instruction of a leg of a route
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Maneuver {
+    #[serde(rename = "type")]
+    pub maneuver_type: ManeuverType,
+    pub instruction: String,
+    pub road_name: Option<String>,
+    /// index into the edges of the route of the edge this maneuver leads onto, or of
+    /// the last edge of the leg for an arrival
+    pub edge_index: usize,
+    pub location: [f32; 2],
+    pub bearing_before: Option<f64>,
+    pub bearing_after: Option<f64>,
+    /// the distance traveled from this maneuver to the next, in meters
+    pub distance_meters: f64,
+}
+
+/// one leg of a route, between two consecutive stops
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Leg {
+    pub start_edge_index: usize,
+    pub end_edge_index: usize,
+    pub distance_meters: f64,
+    pub maneuvers: Vec<Maneuver>,
+}
+
+/// what is known about one edge of a route when writing its instructions
+pub struct RouteEdge<'a> {
+    pub geometry: &'a LineString<f32>,
+    pub name: Option<&'a str>,
+    pub road_class: Option<&'a str>,
+    pub distance_meters: f64,
+}
+
+/// splits a route into legs which each end at the next waypoint in order: at a
+/// waypoint edge, or at the edge which arrives at a waypoint vertex. a waypoint which
+/// is not found ends no leg, and the last leg ends with the route.
+///
+/// # Arguments
+///
+/// * `edge_ids` - the edges of the route
+/// * `dst_vertex_ids` - for each edge of the route, the vertex at its end
+/// * `waypoint_edges` - the waypoint edges of the query, in order
+/// * `waypoint_vertices` - the waypoint vertices of the query, in order
+pub fn split_legs(
+    edge_ids: &[EdgeId],
+    dst_vertex_ids: &[VertexId],
+    waypoint_edges: &[EdgeId],
+    waypoint_vertices: &[VertexId],
+) -> Vec<Range<usize>> {
+    let mut legs = vec![];
+    let mut start = 0;
+    let mut next_edge = waypoint_edges.iter().peekable();
+    let mut next_vertex = waypoint_vertices.iter().peekable();
+    for (idx, (edge_id, dst)) in edge_ids.iter().zip(dst_vertex_ids).enumerate() {
+        let at_edge = next_edge.next_if(|w| *w == edge_id).is_some();
+        let at_vertex = next_vertex.next_if(|w| *w == dst).is_some();
+        if (at_edge || at_vertex) && idx + 1 < edge_ids.len() {
+            legs.push(start..idx + 1);
+            start = idx + 1;
+        }
+    }
+    if start < edge_ids.len() {
+        legs.push(start..edge_ids.len());
+    }
+    legs
+}
+
+/// writes the maneuvers of one leg. a maneuver is written for each turn and for each
+/// change of road name, while travel straight on along the same road is folded into
+/// the maneuver before it.
+///
+/// # Arguments
+///
+/// * `edges` - the edges of the leg
+/// * `first_edge_index` - the index of the first edge of the leg within the route
+/// * `arrival` - a description of the stop at the end of the leg
+/// * `merge_road_classes` - road classes which are merged onto from other classes
+pub fn leg_maneuvers(
+    edges: &[RouteEdge],
+    first_edge_index: usize,
+    arrival: &str,
+    merge_road_classes: &HashSet<String>,
+) -> Leg {
+    let mut maneuvers: Vec<Maneuver> = vec![];
+    let mut prev: Option<&RouteEdge> = None;
+    for (idx, edge) in edges.iter().enumerate() {
+        let bearing_after = start_bearing(edge.geometry);
+        let (maneuver_type, bearing_before) = match prev {
+            None => (ManeuverType::Depart, None),
+            Some(prev) => {
+                let before = end_bearing(prev.geometry);
+                let turn = match (before, bearing_after) {
+                    (Some(b), Some(a)) => ManeuverType::from_turn_angle(turn_angle(b, a)),
+                    _ => ManeuverType::Continue,
+                };
+                let is_merge_class = |e: &RouteEdge| {
+                    e.road_class
+                        .map(|c| merge_road_classes.contains(c))
+                        .unwrap_or_default()
+                };
+                let gentle = matches!(
+                    turn,
+                    ManeuverType::Continue | ManeuverType::SlightLeft | ManeuverType::SlightRight
+                );
+                let turn = if gentle && is_merge_class(edge) && !is_merge_class(prev) {
+                    ManeuverType::Merge
+                } else {
+                    turn
+                };
+                (turn, before)
+            }
+        };
+        let same_road = prev.map(|p| p.name == edge.name).unwrap_or_default();
+        match maneuvers.last_mut() {
+            Some(last) if maneuver_type == ManeuverType::Continue && same_road => {
+                last.distance_meters += edge.distance_meters;
+            }
+            _ => {
+                let location = edge.geometry.0.first().copied().unwrap_or(Coord::zero());
+                maneuvers.push(Maneuver {
+                    maneuver_type,
+                    instruction: instruction(maneuver_type, edge.name, bearing_after),
+                    road_name: edge.name.map(String::from),
+                    edge_index: first_edge_index + idx,
+                    location: [location.x, location.y],
+                    bearing_before,
+                    bearing_after,
+                    distance_meters: edge.distance_meters,
+                });
+            }
+        }
+        prev = Some(edge);
+    }
+    if let Some(last) = edges.last() {
+        let location = last.geometry.0.last().copied().unwrap_or(Coord::zero());
+        maneuvers.push(Maneuver {
+            maneuver_type: ManeuverType::Arrive,
+            instruction: format!("Arrive at {}", arrival),
+            road_name: last.name.map(String::from),
+            edge_index: first_edge_index + edges.len() - 1,
+            location: [location.x, location.y],
+            bearing_before: end_bearing(last.geometry),
+            bearing_after: None,
+            distance_meters: 0.0,
+        });
+    }
+    Leg {
+        start_edge_index: first_edge_index,
+        end_edge_index: first_edge_index + edges.len().saturating_sub(1),
+        distance_meters: edges.iter().map(|e| e.distance_meters).sum(),
+        maneuvers,
+    }
+}
+
+fn instruction(maneuver_type: ManeuverType, name: Option<&str>, bearing: Option<f64>) -> String {
+    let verb = maneuver_type.verb();
+    match (maneuver_type, name) {
+        (ManeuverType::Depart, name) => {
+            let heading = bearing
+                .map(|b| format!("{} {}", verb, cardinal(b)))
+                .unwrap_or_else(|| String::from("Depart"));
+            match name {
+                Some(name) => format!("{} on {}", heading, name),
+                None => heading,
+            }
+        }
+        (_, Some(name)) => format!("{} onto {}", verb, name),
+        (_, None) => String::from(verb),
+    }
+}
+
+/// the compass direction of a bearing
+fn cardinal(bearing: f64) -> &'static str {
+    const DIRECTIONS: [&str; 8] = [
+        "north",
+        "northeast",
+        "east",
+        "southeast",
+        "south",
+        "southwest",
+        "west",
+        "northwest",
+    ];
+    let idx = ((bearing.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8;
+    DIRECTIONS[idx]
+}
+
+/// the change from one bearing to another in degrees, in (-180, 180], positive to
+/// the right
+fn turn_angle(before: f64, after: f64) -> f64 {
+    let angle = (after - before).rem_euclid(360.0);
+    if angle > 180.0 {
+        angle - 360.0
+    } else {
+        angle
+    }
+}
+
+/// the bearing of the first segment of a geometry
+fn start_bearing(geometry: &LineString<f32>) -> Option<f64> {
+    geometry
+        .lines()
+        .find(|l| l.start != l.end)
+        .map(|l| bearing(l.start, l.end))
+}
+
+/// the bearing of the last segment of a geometry
+fn end_bearing(geometry: &LineString<f32>) -> Option<f64> {
+    geometry
+        .0
+        .windows(2)
+        .rev()
+        .find(|w| w[0] != w[1])
+        .map(|w| bearing(w[0], w[1]))
+}
+
+/// the initial great circle bearing from one WGS84 coordinate to another, in degrees
+/// clockwise from north
+fn bearing(src: Coord<f32>, dst: Coord<f32>) -> f64 {
+    let (lat1, lat2) = ((src.y as f64).to_radians(), (dst.y as f64).to_radians());
+    let d_lon = (dst.x as f64 - src.x as f64).to_radians();
+    let y = d_lon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::line_string;
+
+    #[test]
+    fn test_leg_maneuvers() {
+        // east along Main St over two edges, left (north) onto Oak Ave, then a
+        // gentle merge onto the motorway
+        let geometries = [
+            line_string![(x: 0.0, y: 0.0), (x: 0.001, y: 0.0)],
+            line_string![(x: 0.001, y: 0.0), (x: 0.002, y: 0.0)],
+            line_string![(x: 0.002, y: 0.0), (x: 0.002, y: 0.001)],
+            line_string![(x: 0.002, y: 0.001), (x: 0.0022, y: 0.002)],
+        ];
+        let names = [
+            Some("Main St"),
+            Some("Main St"),
+            Some("Oak Ave"),
+            Some("I-70"),
+        ];
+        let classes = [None, None, None, Some("motorway")];
+        let edges = (0..4)
+            .map(|i| RouteEdge {
+                geometry: &geometries[i],
+                name: names[i],
+                road_class: classes[i],
+                distance_meters: 100.0,
+            })
+            .collect::<Vec<_>>();
+        let merge = HashSet::from([String::from("motorway")]);
+        let leg = leg_maneuvers(&edges, 0, "your destination", &merge);
+
+        let types = leg
+            .maneuvers
+            .iter()
+            .map(|m| m.maneuver_type)
+            .collect::<Vec<_>>();
+        use ManeuverType as M;
+        assert_eq!(types, vec![M::Depart, M::Left, M::Merge, M::Arrive]);
+        let instructions = leg
+            .maneuvers
+            .iter()
+            .map(|m| m.instruction.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            instructions,
+            vec![
+                "Head east on Main St",
+                "Turn left onto Oak Ave",
+                "Merge onto I-70",
+                "Arrive at your destination"
+            ]
+        );
+        assert_eq!(leg.maneuvers[0].distance_meters, 200.0);
+        assert_eq!(leg.maneuvers[1].edge_index, 2);
+        assert_eq!(leg.distance_meters, 400.0);
+
+        // the route is split into legs after the waypoint edge
+        let edge_ids = (0..4).map(EdgeId).collect::<Vec<_>>();
+        let dst_ids = (1..5).map(VertexId).collect::<Vec<_>>();
+        assert_eq!(
+            split_legs(&edge_ids, &dst_ids, &[EdgeId(1)], &[]),
+            vec![0..2, 2..4]
+        );
+        assert_eq!(
+            split_legs(&edge_ids, &dst_ids, &[], &[VertexId(3)]),
+            vec![0..3, 3..4]
+        );
+        assert_eq!(split_legs(&edge_ids, &dst_ids, &[], &[]), vec![0..4]);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod builder;
+pub mod maneuver;
+pub mod plugin;
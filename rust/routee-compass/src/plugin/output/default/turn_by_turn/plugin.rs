@@ -0,0 +1,179 @@
+use super::maneuver::{self, Leg, RouteEdge};
+use crate::app::compass::compass_app_error::CompassAppError;
+use crate::app::search::search_app_result::SearchAppResult;
+use crate::plugin::input::input_json_extensions::InputJsonExtensions;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::plugin_error::PluginError;
+use routee_compass_core::algorithm::search::edge_offsets::EdgeOffsets;
+use routee_compass_core::algorithm::search::edge_traversal::EdgeTraversal;
+use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use routee_compass_core::model::property::edge_attribute_store::EdgeAttributeStore;
+use routee_compass_core::model::road_network::edge_geometry_store::EdgeGeometryStore;
+use routee_compass_core::model::road_network::edge_id::EdgeId;
+use routee_compass_core::model::unit::as_f64::AsF64;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// the road name or road class of each edge, read from a file with a value per edge
+/// or from a categorical column of the edge attributes of the graph
+pub enum EdgeLabels {
+    File(Box<[String]>),
+    Attribute {
+        store: Arc<EdgeAttributeStore>,
+        column: String,
+    },
+}
+
+impl EdgeLabels {
+    /// the label of an edge, where an empty value is missing
+    fn get(&self, edge_id: EdgeId) -> Option<&str> {
+        let label = match self {
+            EdgeLabels::File(values) => values.get(edge_id.0).map(|s| s.as_str()),
+            EdgeLabels::Attribute { store, column } => store.get_categorical(column, edge_id).ok(),
+        };
+        label.filter(|s| !s.is_empty())
+    }
+}
+
+/// splits each route into legs at the waypoints of the query and writes the
+/// maneuvers of each leg, such as "Turn left onto Main St", from the bearings of
+/// the edge geometries and the name and road class of each edge.
+pub struct TurnByTurnPlugin {
+    geometries: Arc<EdgeGeometryStore>,
+    names: Option<EdgeLabels>,
+    road_classes: Option<EdgeLabels>,
+    merge_road_classes: HashSet<String>,
+}
+
+impl TurnByTurnPlugin {
+    pub const OUTPUT_KEY: &'static str = "legs";
+
+    pub fn new(
+        geometries: Arc<EdgeGeometryStore>,
+        names: Option<EdgeLabels>,
+        road_classes: Option<EdgeLabels>,
+        merge_road_classes: HashSet<String>,
+    ) -> TurnByTurnPlugin {
+        TurnByTurnPlugin {
+            geometries,
+            names,
+            road_classes,
+            merge_road_classes,
+        }
+    }
+
+    /// the legs of a route, split at the waypoints of the request. when the request
+    /// has edge offsets, the first and last edges of the route only count the part of
+    /// their length the route traverses.
+    fn route_legs(
+        &self,
+        route: &[EdgeTraversal],
+        request: Option<&serde_json::Value>,
+        si: &SearchInstance,
+    ) -> Result<Vec<Leg>, PluginError> {
+        let edge_ids = route.iter().map(|et| et.edge_id).collect::<Vec<_>>();
+        let dst_vertex_ids = edge_ids
+            .iter()
+            .map(|e| si.directed_graph.dst_vertex_id(*e))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PluginError::PluginFailed(e.to_string()))?;
+        let (waypoint_edges, waypoint_vertices, offsets) = match request {
+            Some(request) => (
+                request.get_waypoint_edges()?,
+                request.get_waypoint_vertices()?,
+                request_offsets(request)?,
+            ),
+            None => (vec![], vec![], EdgeOffsets::default()),
+        };
+        let edges = edge_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, edge_id)| {
+                let edge = si
+                    .directed_graph
+                    .get_edge(*edge_id)
+                    .map_err(|e| PluginError::PluginFailed(e.to_string()))?;
+                let fraction = if offsets.is_empty() {
+                    1.0
+                } else {
+                    offsets.fraction(idx, edge_ids.len())
+                };
+                Ok(RouteEdge {
+                    geometry: self
+                        .geometries
+                        .get(*edge_id)
+                        .map_err(|_| PluginError::EdgeGeometryMissing(*edge_id))?,
+                    name: self.names.as_ref().and_then(|n| n.get(*edge_id)),
+                    road_class: self.road_classes.as_ref().and_then(|r| r.get(*edge_id)),
+                    distance_meters: edge.distance.as_f64() * fraction,
+                })
+            })
+            .collect::<Result<Vec<_>, PluginError>>()?;
+
+        let ranges = maneuver::split_legs(
+            &edge_ids,
+            &dst_vertex_ids,
+            &waypoint_edges,
+            &waypoint_vertices,
+        );
+        let n_legs = ranges.len();
+        let legs = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(idx, range)| {
+                let arrival = if idx + 1 == n_legs {
+                    String::from("your destination")
+                } else {
+                    format!("waypoint {}", idx + 1)
+                };
+                let start = range.start;
+                maneuver::leg_maneuvers(&edges[range], start, &arrival, &self.merge_road_classes)
+            })
+            .collect();
+        Ok(legs)
+    }
+}
+
+impl OutputPlugin for TurnByTurnPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), PluginError> {
+        let (result, si) = match search_result {
+            Err(_) => return Ok(()),
+            Ok((result, si)) => (result, si),
+        };
+        let request = output.get("request");
+        let routes = result
+            .routes
+            .iter()
+            .map(|route| {
+                let legs = self.route_legs(route, request, si)?;
+                Ok(serde_json::to_value(legs)?)
+            })
+            .collect::<Result<Vec<_>, PluginError>>()?;
+        // as with traversal output, store null for no route, an array of legs for
+        // one route, and an array of arrays for many routes.
+        output[Self::OUTPUT_KEY] = match routes.as_slice() {
+            [] => serde_json::Value::Null,
+            [route] => route.to_owned(),
+            _ => json![routes],
+        };
+        Ok(())
+    }
+}
+
+/// the edge offsets of an edge-oriented request, which the search applied to its
+/// routes, or none for other requests
+fn request_offsets(request: &serde_json::Value) -> Result<EdgeOffsets, PluginError> {
+    if request.get_origin_edge().is_err() {
+        return Ok(EdgeOffsets::default());
+    }
+    EdgeOffsets::new(
+        request.get_origin_edge_offset()?,
+        request.get_destination_edge_offset()?,
+    )
+    .map_err(|e| PluginError::InputError(e.to_string()))
+}
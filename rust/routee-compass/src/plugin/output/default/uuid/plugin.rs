@@ -18,7 +18,7 @@ pub struct UUIDOutputPlugin {
 
 impl UUIDOutputPlugin {
     pub fn from_file<P: AsRef<Path>>(filename: &P) -> Result<UUIDOutputPlugin, PluginError> {
-        let count = fs_utils::line_count(filename, fs_utils::is_gzip(filename)).map_err(|e| {
+        let count = fs_utils::line_count(filename).map_err(|e| {
             PluginError::FileReadError(filename.as_ref().to_path_buf(), e.to_string())
         })?;
 
@@ -62,12 +62,12 @@ impl OutputPlugin for UUIDOutputPlugin {
                     .uuids
                     .get(origin_vertex_id.0)
                     .cloned()
-                    .ok_or_else(|| PluginError::UUIDMissing(origin_vertex_id.0))?;
+                    .ok_or(PluginError::UUIDMissing(origin_vertex_id.0))?;
                 let destination_uuid = self
                     .uuids
                     .get(destination_vertex_id.0)
                     .cloned()
-                    .ok_or_else(|| PluginError::UUIDMissing(destination_vertex_id.0))?;
+                    .ok_or(PluginError::UUIDMissing(destination_vertex_id.0))?;
 
                 output[&self.o_key] = serde_json::Value::String(origin_uuid);
                 output[&self.d_key] = serde_json::Value::String(destination_uuid);
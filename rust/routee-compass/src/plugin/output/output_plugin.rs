@@ -1,3 +1,4 @@
+use geo::LineString;
 use routee_compass_core::algorithm::search::search_instance::SearchInstance;
 
 use crate::app::compass::compass_app_error::CompassAppError;
@@ -29,7 +30,8 @@ pub trait OutputPlugin: Send + Sync {
     ///
     /// * `output` - the search result passed to this plugin
     /// * `result` - the result of the search via the [internal representation].
-    ///              this is passed as a `Result` as the search may have failed.
+    ///   this is passed as a `Result` as the search may have failed. the state at
+    ///   each step of a route is found with [`SearchAppResult::state_timelines`].
     ///
     /// # Returns
     ///
@@ -41,4 +43,23 @@ pub trait OutputPlugin: Send + Sync {
         output: &mut serde_json::Value,
         result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
     ) -> Result<(), PluginError>;
+
+    /// Called with a response served from the result cache in place of `process`,
+    /// which has already been applied to it, so that a plugin which aggregates over
+    /// every query can count it.
+    fn process_cached(&self, _output: &serde_json::Value) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    /// Called once when the app shuts down, after every query it has run, so that a
+    /// plugin which aggregates over those queries can write its results.
+    fn finish(&self) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    /// The geometry of each edge in edge id order, for plugins which load them, so
+    /// that the application can share them such as with the language bindings.
+    fn edge_geometries(&self) -> Option<&[LineString<f32>]> {
+        None
+    }
 }
@@ -1,8 +1,11 @@
 use crate::app::{
-    compass::compass_app_error::CompassAppError,
+    compass::{compass_app_error::CompassAppError, error_taxonomy::ErrorTaxonomy},
     search::{search_app::SearchApp, search_app_result::SearchAppResult},
 };
-use routee_compass_core::algorithm::search::search_instance::SearchInstance;
+use routee_compass_core::algorithm::search::{
+    search_error::SearchError, search_instance::SearchInstance,
+};
+use routee_compass_core::util::duration_extension::DurationExtension;
 use serde_json::{json, Value};
 
 /// creates the initial output with summary information from the search app,
@@ -13,12 +16,28 @@ pub fn create_initial_output(
     _app: &SearchApp,
 ) -> Result<Value, Value> {
     match &res {
-        Err(e) => Err(package_error(req, e)),
-        Ok((_, _)) => {
+        Err(e) => Err(package_failed_search(req, e)),
+        Ok((result, _)) => {
             let mut init_output = serde_json::json!({
                 "request": req,
             });
 
+            if !result.charging_stops.is_empty() {
+                init_output["charging_stops"] = serde_json::json!(result.charging_stops);
+            }
+            if let Some(arrive_by) = &result.arrive_by {
+                init_output["departure_time"] = serde_json::json!(arrive_by.departure_time);
+                init_output["estimated_arrival_time"] =
+                    serde_json::json!(arrive_by.estimated_arrival_time);
+                init_output["late_seconds"] = serde_json::json!(arrive_by.late_seconds);
+            }
+            if let Some(chosen_destination) = &result.chosen_destination {
+                init_output["chosen_destination_vertex"] = serde_json::json!(chosen_destination);
+            }
+            if let Some(chosen_origin) = &result.chosen_origin {
+                init_output["chosen_origin_vertex"] = serde_json::json!(chosen_origin);
+            }
+
             let output_plugin_executed_time = chrono::Local::now();
             init_output["output_plugin_executed_time"] =
                 serde_json::json!(output_plugin_executed_time.to_rfc3339());
@@ -29,10 +48,37 @@ pub fn create_initial_output(
 }
 
 /// helper to return errors as JSON response objects which include the
-/// original request along with the error message
-pub fn package_error<E: ToString>(req: &Value, error: E) -> Value {
+/// original request along with the code, message and component of the error
+pub fn package_error<E: ErrorTaxonomy>(req: &Value, error: E) -> Value {
     json!({
         "request": req,
-        "error": error.to_string()
+        "error": error.error_detail().to_json()
     })
 }
+
+/// packages a failed search as a JSON response. a search stopped by the termination
+/// model is tagged with a `"terminated"` status along with how far it had progressed,
+/// so that it can be told apart from a query with no path, and a search aborted by
+/// the caller is tagged with a `"cancelled"` status.
+pub fn package_failed_search(req: &Value, error: &CompassAppError) -> Value {
+    match error {
+        CompassAppError::SearchError(SearchError::Terminated(terminated)) => json!({
+            "request": req,
+            "status": "terminated",
+            "error": error.error_detail().to_json(),
+            "termination": {
+                "reason": terminated.reason,
+                "best_vertex": terminated.best_vertex,
+                "tree_size": terminated.tree_size,
+                "iterations": terminated.iterations,
+                "runtime": terminated.runtime.hhmmss(),
+            }
+        }),
+        CompassAppError::SearchError(SearchError::Cancelled) => json!({
+            "request": req,
+            "status": "cancelled",
+            "error": error.error_detail().to_json(),
+        }),
+        _ => package_error(req, error),
+    }
+}